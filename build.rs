@@ -41,7 +41,7 @@ fn png_to_ico(png_path: &str, ico_path: &str) -> Result<(), Box<dyn std::error::
         let resized = img.resize_exact(size, size, FilterType::Lanczos3);
         let rgba = resized.to_rgba8();
 
-        let and_row_stride = ((size + 31) / 32 * 4) as usize;
+        let and_row_stride = (size.div_ceil(32) * 4) as usize;
         let and_mask = vec![0u8; and_row_stride * size as usize];
 
         let mut bmp = Vec::new();
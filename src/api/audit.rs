@@ -0,0 +1,166 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// One line of the audit file. Every order request writes a `request` line before it is
+/// sent and a `response`, `timeout` or `error` line once it completes, both keyed by the
+/// client order ID.
+#[derive(Debug, Serialize)]
+struct AuditRecord<'a> {
+    at: DateTime<Utc>,
+    phase: &'a str,
+    client_order_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    endpoint: Option<&'a str>,
+    /// Request parameters, without the signature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    http_status: Option<u16>,
+    /// Raw response body (JSON as sent by Binance)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Append-only JSONL audit of outgoing orders, separate from the human-readable log.
+/// Records are serialized on the caller and appended by a dedicated writer thread, so an
+/// order request never waits on the disk. Write failures are only traced: auditing never
+/// blocks trading.
+#[derive(Default)]
+pub struct AuditLog {
+    path: Option<PathBuf>,
+    /// Queue of serialized lines for the writer thread (None = auditing disabled)
+    lines: Option<mpsc::Sender<String>>,
+    /// Held by the writer while it appends and by `prune` while it rewrites the file
+    lock: Arc<Mutex<()>>,
+}
+
+impl AuditLog {
+    pub fn open(path: PathBuf) -> Self {
+        let lock = Arc::new(Mutex::new(()));
+        let (tx, rx) = mpsc::channel::<String>();
+        let writer_path = path.clone();
+        let writer_lock = Arc::clone(&lock);
+        let spawned = std::thread::Builder::new()
+            .name("order-audit".into())
+            .spawn(move || {
+                // Ends once the log (and with it the sender) is dropped
+                while let Ok(first) = rx.recv() {
+                    // Everything queued meanwhile goes out in the same write, in order
+                    let mut out = first;
+                    out.push('\n');
+                    for line in rx.try_iter() {
+                        out.push_str(&line);
+                        out.push('\n');
+                    }
+                    let _guard = writer_lock.lock().unwrap_or_else(|e| e.into_inner());
+                    let result = std::fs::OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(&writer_path)
+                        .and_then(|mut file| file.write_all(out.as_bytes()));
+                    if let Err(e) = result {
+                        tracing::warn!("Could not append to order audit {:?}: {}", writer_path, e);
+                    }
+                }
+            });
+        let lines = match spawned {
+            Ok(_) => Some(tx),
+            Err(e) => {
+                tracing::warn!("Could not start the order audit writer: {}", e);
+                None
+            }
+        };
+        Self { path: Some(path), lines, lock }
+    }
+
+    pub fn request(&self, client_order_id: &str, endpoint: &str, params: &str) {
+        self.append(AuditRecord {
+            endpoint: Some(endpoint),
+            params: Some(params),
+            ..AuditRecord::new("request", client_order_id)
+        });
+    }
+
+    pub fn response(&self, client_order_id: &str, http_status: u16, body: &str) {
+        self.append(AuditRecord {
+            http_status: Some(http_status),
+            body: Some(body),
+            ..AuditRecord::new("response", client_order_id)
+        });
+    }
+
+    /// The request got no answer: with a timeout the order may or may not have been placed
+    pub fn failed(&self, client_order_id: &str, err: &reqwest::Error) {
+        let phase = if err.is_timeout() { "timeout" } else { "error" };
+        self.append(AuditRecord {
+            error: Some(err.to_string()),
+            ..AuditRecord::new(phase, client_order_id)
+        });
+    }
+
+    /// Drops the records written before `before`, rewriting the file. Lines that do not
+    /// parse are kept. Returns how many records were dropped.
+    pub fn prune(&self, before: DateTime<Utc>) -> std::io::Result<usize> {
+        let Some(path) = &self.path else { return Ok(0) };
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+        let old = |line: &str| {
+            serde_json::from_str::<serde_json::Value>(line)
+                .ok()
+                .and_then(|v| v["at"].as_str()?.parse::<DateTime<Utc>>().ok())
+                .is_some_and(|at| at < before)
+        };
+        let (dropped, kept): (Vec<&str>, Vec<&str>) = content.lines().partition(|line| old(line));
+        if dropped.is_empty() {
+            return Ok(0);
+        }
+        let mut out = kept.join("\n");
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let tmp = path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, out)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(dropped.len())
+    }
+
+    /// Serializes the record and queues it for the writer thread
+    fn append(&self, record: AuditRecord) {
+        let Some(lines) = &self.lines else { return };
+        let line = match serde_json::to_string(&record) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Could not serialize audit record: {}", e);
+                return;
+            }
+        };
+        if lines.send(line).is_err() {
+            tracing::warn!("Order audit writer is gone; record dropped");
+        }
+    }
+}
+
+impl<'a> AuditRecord<'a> {
+    fn new(phase: &'a str, client_order_id: &'a str) -> Self {
+        Self {
+            at: Utc::now(),
+            phase,
+            client_order_id,
+            endpoint: None,
+            params: None,
+            http_status: None,
+            body: None,
+            error: None,
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+struct Entry<V> {
+    value: V,
+    expires_at: Instant,
+    last_used: Instant,
+}
+
+/// Small in-memory LRU cache with per-entry TTL.
+/// Concurrent fetches of the same key are deduplicated: only the first caller hits the
+/// network, the rest wait for it and read the cached value.
+pub struct TtlCache<K, V> {
+    capacity: usize,
+    entries: Mutex<HashMap<K, Entry<V>>>,
+    inflight: Mutex<HashMap<K, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::new(HashMap::new()),
+            inflight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the cached value if present and not expired
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+        match entries.get_mut(key) {
+            Some(entry) if entry.expires_at > now => {
+                entry.last_used = now;
+                Some(entry.value.clone())
+            }
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Stores a value, evicting expired entries first and then the least recently used one
+    pub fn insert(&self, key: K, value: V, ttl: Duration) {
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            entries.retain(|_, e| e.expires_at > now);
+            if entries.len() >= self.capacity {
+                if let Some(oldest) = entries
+                    .iter()
+                    .min_by_key(|(_, e)| e.last_used)
+                    .map(|(k, _)| k.clone())
+                {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(key, Entry { value, expires_at: now + ttl, last_used: now });
+    }
+
+    /// Returns the cached value or runs `fetch` once for all concurrent callers of `key`
+    pub async fn get_or_fetch<F, Fut>(&self, key: K, ttl: Duration, fetch: F) -> Result<V>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V>>,
+    {
+        if let Some(v) = self.get(&key) {
+            return Ok(v);
+        }
+
+        let gate = self
+            .inflight
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_insert_with(|| Arc::new(tokio::sync::Mutex::new(())))
+            .clone();
+        let _guard = gate.lock().await;
+
+        // Another caller may have filled it while we were waiting
+        if let Some(v) = self.get(&key) {
+            return Ok(v);
+        }
+
+        let result = fetch().await;
+        if let Ok(v) = &result {
+            self.insert(key.clone(), v.clone(), ttl);
+        }
+        self.inflight.lock().unwrap().remove(&key);
+        result
+    }
+}
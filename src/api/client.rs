@@ -1,31 +1,86 @@
-use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use chrono::Utc;
 use hmac::{Hmac, Mac};
-use reqwest::{header, Client};
+use reqwest::{header, Client, Method, StatusCode};
 use serde_json::Value;
 use sha2::Sha256;
 
+use crate::api::audit::AuditLog;
+use crate::api::cache::TtlCache;
 use crate::config::BinanceConfig;
+use crate::session_stats::ApiCounters;
 use crate::models::{
     account::AccountInfo,
-    order::Order,
-    ticker::{Kline, TickerPrice},
+    order::{AccountTrade, Order},
+    symbol::SymbolFilters,
+    ticker::{BookTicker, Kline, Ticker24h, TickerPrice},
 };
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Order request that may have reached Binance without an answer coming back (timeout,
+/// connection dropped mid-request, 5xx). Look it up by `client_order_id` before sending it
+/// again.
+#[derive(Debug)]
+pub struct UncertainOrder {
+    pub client_order_id: String,
+    pub source: anyhow::Error,
+}
+
+impl std::fmt::Display for UncertainOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (order {} status unknown)", self.source, self.client_order_id)
+    }
+}
+
+impl std::error::Error for UncertainOrder {}
+
+/// HMAC-SHA256 of `payload` with `secret`, hex encoded (the `signature` parameter)
+pub fn signature(secret: &str, payload: &str) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(payload.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
 /// Binance base URLs
 const MAINNET_URL: &str = "https://api.binance.com";
 const TESTNET_URL: &str = "https://testnet.binance.vision";
 
+/// Cache TTLs: exchangeInfo barely changes, tickers go stale within seconds
+const EXCHANGE_INFO_TTL: Duration = Duration::from_secs(6 * 3600);
+const TICKER_TTL: Duration = Duration::from_secs(2);
+/// Top of book reused by the testnet checks of consecutive ticks
+const BOOK_TTL: Duration = Duration::from_secs(2);
+
 pub struct BinanceClient {
     http: Client,
     secret: String,
     base_url: String,
+    testnet: bool,
+    /// Testnet price reconciliation: threshold in % (0 = off)
+    testnet_max_divergence_pct: f64,
     /// Offset in ms between local clock and Binance server
     time_offset_ms: AtomicI64,
+    /// Raw exchangeInfo response (single key)
+    exchange_info_cache: TtlCache<(), Arc<Value>>,
+    /// Klines keyed by (symbol, interval, limit)
+    klines_cache: TtlCache<(String, String, u32), Arc<Vec<Kline>>>,
+    /// Last price keyed by symbol
+    ticker_cache: TtlCache<String, f64>,
+    /// Parsed trading filters keyed by symbol (same TTL as exchangeInfo)
+    filters_cache: TtlCache<String, SymbolFilters>,
+    /// Best bid/ask keyed by symbol, for the testnet divergence and spread checks
+    book_cache: TtlCache<String, BookTicker>,
+    /// Two-phase record of every order request (request, then response or timeout)
+    audit: AuditLog,
+    /// Sequence for client order IDs generated in this session
+    order_seq: AtomicU64,
+    /// Orders placed / failed and error responses, for the session stats
+    counters: Arc<ApiCounters>,
 }
 
 impl BinanceClient {
@@ -57,19 +112,47 @@ impl BinanceClient {
             http,
             secret: config.api_secret,
             base_url,
+            testnet: config.testnet,
+            testnet_max_divergence_pct: if config.testnet_price_check {
+                config.testnet_max_divergence_pct
+            } else {
+                0.0
+            },
             time_offset_ms: AtomicI64::new(0),
+            exchange_info_cache: TtlCache::new(1),
+            klines_cache: TtlCache::new(64),
+            ticker_cache: TtlCache::new(128),
+            filters_cache: TtlCache::new(128),
+            book_cache: TtlCache::new(128),
+            audit: AuditLog::default(),
+            order_seq: AtomicU64::new(0),
+            counters: Arc::default(),
         })
     }
 
+    /// Records every order request and its outcome in `path` (JSONL, append-only)
+    pub fn with_audit_log(mut self, path: std::path::PathBuf) -> Self {
+        self.audit = AuditLog::open(path);
+        self
+    }
+
+    /// Counts orders and error responses into `counters` (shared with the session stats)
+    pub fn with_counters(mut self, counters: Arc<ApiCounters>) -> Self {
+        self.counters = counters;
+        self
+    }
+
+    /// Drops the order audit records older than `before`
+    pub fn prune_audit_log(&self, before: chrono::DateTime<chrono::Utc>) -> std::io::Result<usize> {
+        self.audit.prune(before)
+    }
+
     // -------------------------------------------------------
     // Internal helpers
     // -------------------------------------------------------
 
     fn sign(&self, payload: &str) -> String {
-        let mut mac = HmacSha256::new_from_slice(self.secret.as_bytes())
-            .expect("HMAC accepts keys of any size");
-        mac.update(payload.as_bytes());
-        hex::encode(mac.finalize().into_bytes())
+        signature(&self.secret, payload)
     }
 
     fn timestamp_ms(&self) -> u64 {
@@ -81,16 +164,78 @@ impl BinanceClient {
         if resp.status().is_success() {
             return Ok(resp);
         }
+        self.counters.api_errors.fetch_add(1, Ordering::Relaxed);
         let status = resp.status();
         let text = resp.text().await.unwrap_or_default();
-        // Try to parse Binance error message
-        if let Ok(val) = serde_json::from_str::<Value>(&text) {
-            let code = val["code"].as_i64().unwrap_or(0);
-            let msg = val["msg"].as_str().unwrap_or(&text);
-            Err(anyhow!("Binance error {}: {} (HTTP {})", code, msg, status))
-        } else {
-            Err(anyhow!("HTTP {}: {}", status, text))
+        Err(api_error(status, &text))
+    }
+
+    /// Signed request to /api/v3/order (place or cancel) under a fresh client order ID.
+    /// It is audited before it is sent and again with the response or the failure.
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    async fn send_order(&self, method: Method, params: &str) -> Result<String> {
+        let client_order_id = format!(
+            "tv-{}-{}",
+            Utc::now().timestamp_millis(),
+            self.order_seq.fetch_add(1, Ordering::Relaxed)
+        );
+        let body = format!("{}&newClientOrderId={}&timestamp={}", params, client_order_id, self.timestamp_ms());
+        self.audit.request(&client_order_id, &format!("{} /api/v3/order", method), &body);
+        let sig = self.sign(&body);
+        let full_body = format!("{}&signature={}", body, sig);
+
+        let url = format!("{}/api/v3/order", self.base_url);
+        // Cancels only count as API errors when rejected; placements count either way
+        let placing = method == Method::POST;
+        let sent = self
+            .http
+            .request(method, &url)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(full_body)
+            .send()
+            .await;
+        let resp = match sent {
+            Ok(resp) => resp,
+            Err(e) => {
+                self.audit.failed(&client_order_id, &e);
+                if placing {
+                    self.counters.orders_failed.fetch_add(1, Ordering::Relaxed);
+                }
+                // Only a failed connection proves the order never left
+                if placing && !e.is_connect() {
+                    return Err(UncertainOrder { client_order_id, source: e.into() }.into());
+                }
+                return Err(e.into());
+            }
+        };
+        let status = resp.status();
+        let text = match resp.text().await {
+            Ok(text) => text,
+            Err(e) => {
+                self.audit.failed(&client_order_id, &e);
+                if placing {
+                    self.counters.orders_failed.fetch_add(1, Ordering::Relaxed);
+                    return Err(UncertainOrder { client_order_id, source: e.into() }.into());
+                }
+                return Err(e.into());
+            }
+        };
+        self.audit.response(&client_order_id, status.as_u16(), &text);
+        if !status.is_success() {
+            self.counters.api_errors.fetch_add(1, Ordering::Relaxed);
+            if placing {
+                self.counters.orders_failed.fetch_add(1, Ordering::Relaxed);
+                // Binance: a 5xx means the order may or may not have been executed
+                if status.is_server_error() {
+                    return Err(UncertainOrder { client_order_id, source: api_error(status, &text) }.into());
+                }
+            }
+            return Err(api_error(status, &text));
+        }
+        if placing {
+            self.counters.orders_placed.fetch_add(1, Ordering::Relaxed);
         }
+        Ok(text)
     }
 
     // -------------------------------------------------------
@@ -98,6 +243,7 @@ impl BinanceClient {
     // -------------------------------------------------------
 
     /// Connectivity test
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
     pub async fn ping(&self) -> Result<()> {
         let url = format!("{}/api/v3/ping", self.base_url);
         self.http.get(&url).send().await?;
@@ -106,6 +252,7 @@ impl BinanceClient {
 
     /// Local clock synchronization with Binance server to avoid error -1021.
     /// Calculates the offset and stores it to apply it on each signed timestamp.
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
     pub async fn sync_time(&self) -> Result<()> {
         let local_before = Utc::now().timestamp_millis();
         let url = format!("{}/api/v3/time", self.base_url);
@@ -125,13 +272,85 @@ impl BinanceClient {
         Ok(())
     }
 
-    /// Gets all active USDT pairs in Spot — public endpoint, no signature.
-    /// Returns the list sorted alphabetically.
-    pub async fn get_usdt_symbols(&self) -> Result<Vec<String>> {
+    /// Offset applied to signed timestamps (server time - local time), in ms
+    pub fn time_offset_ms(&self) -> i64 {
+        self.time_offset_ms.load(Ordering::Relaxed)
+    }
+
+    /// Full exchangeInfo (symbols, status, filters) — cached for hours. Only a successful
+    /// response with a symbol list is cached; an error body would otherwise stick for the TTL
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn exchange_info(&self) -> Result<Arc<Value>> {
+        self.exchange_info_cache
+            .get_or_fetch((), EXCHANGE_INFO_TTL, || async {
+                let url = format!("{}/api/v3/exchangeInfo", self.base_url);
+                let resp = self.http.get(&url).send().await?;
+                let info: Value = self.check_response(resp).await?.json().await?;
+                if !info["symbols"].is_array() {
+                    return Err(anyhow!("exchangeInfo: 'symbols' field not found"));
+                }
+                Ok(Arc::new(info))
+            })
+            .await
+    }
+
+    /// Live trading status of a symbol ("TRADING", "HALT", "BREAK", ...), bypassing the
+    /// exchangeInfo cache. None when the symbol is no longer listed (-1121).
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn symbol_status(&self, symbol: &str) -> Result<Option<String>> {
         let url = format!("{}/api/v3/exchangeInfo", self.base_url);
-        let resp: serde_json::Value = self.http.get(&url).send().await?.json().await?;
+        let resp = self.http.get(&url).query(&[("symbol", symbol)]).send().await?;
+        let info: Value = match self.check_response(resp).await {
+            Ok(resp) => resp.json().await?,
+            Err(e) if e.to_string().contains("-1121") => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        Ok(info["symbols"][0]["status"].as_str().map(str::to_string))
+    }
+
+    /// Trading filters of one symbol (from the cached exchangeInfo)
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn symbol_filters(&self, symbol: &str) -> Result<SymbolFilters> {
+        self.filters_cache
+            .get_or_fetch(symbol.to_string(), EXCHANGE_INFO_TTL, || async {
+                let resp = self.exchange_info().await?;
+                let entry = resp["symbols"]
+                    .as_array()
+                    .ok_or_else(|| anyhow!("exchangeInfo: 'symbols' field not found"))?
+                    .iter()
+                    .find(|s| s["symbol"].as_str() == Some(symbol))
+                    .ok_or_else(|| anyhow!("exchangeInfo: symbol {} not found", symbol))?;
+                Ok(SymbolFilters::from_exchange_info(entry))
+            })
+            .await
+    }
+
+    /// Order quantity rounded to the symbol's LOT_SIZE step and checked against minQty /
+    /// minNotional. Market orders use the last price for the notional check.
+    /// If the filters cannot be loaded the quantity is sent as is.
+    async fn compliant_qty(&self, symbol: &str, quantity: f64, price: Option<f64>) -> Result<f64> {
+        let filters = match self.symbol_filters(symbol).await {
+            Ok(f) => f,
+            Err(e) => {
+                tracing::warn!("No filters for {} ({}), sending unrounded quantity", symbol, e);
+                return Ok(quantity);
+            }
+        };
+        let price = match price {
+            Some(p) => Some(p),
+            None => self.get_price(symbol).await.ok(),
+        };
+        Ok(filters.compliant_qty(quantity, price)?)
+    }
 
-        let mut symbols: Vec<String> = resp["symbols"]
+    /// Gets all active Spot pairs quoted in one of `quote_assets` — public endpoint, no
+    /// signature. Returns the list grouped by quote asset (in the given order), each group
+    /// sorted alphabetically.
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn get_symbols(&self, quote_assets: &[String]) -> Result<Vec<String>> {
+        let resp = self.exchange_info().await?;
+
+        let mut symbols: Vec<(usize, String)> = resp["symbols"]
             .as_array()
             .ok_or_else(|| anyhow!("exchangeInfo: 'symbols' field not found"))?
             .iter()
@@ -143,8 +362,9 @@ impl BinanceClient {
                 let permissions = s["permissions"].as_array()?;
                 let is_margin = permissions.iter().any(|p| p == "MARGIN" || p == "ISOLATED_MARGIN");
 
-                if status == "TRADING" && quote == "USDT" && is_spot && is_margin {
-                    Some(symbol.to_string())
+                let rank = quote_assets.iter().position(|q| q == quote)?;
+                if status == "TRADING" && is_spot && is_margin {
+                    Some((rank, symbol.to_string()))
                 } else {
                     None
                 }
@@ -152,37 +372,134 @@ impl BinanceClient {
             .collect();
 
         symbols.sort();
-        Ok(symbols)
+        Ok(symbols.into_iter().map(|(_, symbol)| symbol).collect())
     }
 
     /// Gets historical OHLC candles (klines) — public endpoint, no signature
     /// Returns up to `limit` candles of the indicated `interval` (e.g.: "1h", "4h", "1d")
+    /// Cached for a fraction of the candle interval, so callers sharing a symbol reuse it
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
     pub async fn get_klines(&self, symbol: &str, interval: &str, limit: u32) -> Result<Vec<Kline>> {
-        let url = format!(
-            "{}/api/v3/klines?symbol={}&interval={}&limit={}",
-            self.base_url, symbol, interval, limit
-        );
-        // API returns Vec<Vec<Value>>; each candle is an array of 12+ elements:
-        // [open_time, open, high, low, close, volume, close_time, ...]
-        let resp: Vec<serde_json::Value> = self.http.get(&url).send().await?.json().await?;
-        let klines = resp
-            .into_iter()
-            .filter_map(|k| {
-                let high: f64 = k.get(2)?.as_str()?.parse().ok()?;
-                let low:  f64 = k.get(3)?.as_str()?.parse().ok()?;
-                Some(Kline { high, low })
+        let key = (symbol.to_string(), interval.to_string(), limit);
+        let klines = self
+            .klines_cache
+            .get_or_fetch(key, kline_ttl(interval), || async {
+                let url = format!(
+                    "{}/api/v3/klines?symbol={}&interval={}&limit={}",
+                    self.base_url, symbol, interval, limit
+                );
+                // API returns Vec<Vec<Value>>; each candle is an array of 12+ elements:
+                // [open_time, open, high, low, close, volume, close_time, ...]
+                let resp = self.http.get(&url).send().await?;
+                let resp: Vec<Value> = self.check_response(resp).await?.json().await?;
+                let klines = resp
+                    .into_iter()
+                    .filter_map(|k| {
+                        let open_time = k.get(0)?.as_i64()?;
+                        let high: f64 = k.get(2)?.as_str()?.parse().ok()?;
+                        let low:  f64 = k.get(3)?.as_str()?.parse().ok()?;
+                        let close: f64 = k.get(4)?.as_str()?.parse().ok()?;
+                        Some(Kline { open_time, high, low, close })
+                    })
+                    .collect();
+                Ok(Arc::new(klines))
             })
-            .collect();
-        Ok(klines)
+            .await?;
+        Ok(klines.as_ref().clone())
     }
 
-    /// Current price of a symbol
+    /// Current price of a symbol (cached for a couple of seconds)
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
     pub async fn get_price(&self, symbol: &str) -> Result<f64> {
-        let url = format!("{}/api/v3/ticker/price?symbol={}", self.base_url, symbol);
-        let resp: TickerPrice = self.http.get(&url).send().await?.json().await?;
-        resp.price
-            .parse::<f64>()
-            .map_err(|_| anyhow!("Invalid price: {}", resp.price))
+        self.ticker_cache
+            .get_or_fetch(symbol.to_string(), TICKER_TTL, || async {
+                let url = format!("{}/api/v3/ticker/price?symbol={}", self.base_url, symbol);
+                let resp: TickerPrice = self.http.get(&url).send().await?.json().await?;
+                resp.price
+                    .parse::<f64>()
+                    .map_err(|_| anyhow!("Invalid price: {}", resp.price))
+            })
+            .await
+    }
+
+    /// Last price of every symbol (`ticker/price` without filter, weight 4)
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn all_prices(&self) -> Result<std::collections::HashMap<String, f64>> {
+        let url = format!("{}/api/v3/ticker/price", self.base_url);
+        let resp = self.http.get(&url).send().await?;
+        let tickers: Vec<TickerPrice> = self.check_response(resp).await?.json().await?;
+        Ok(tickers
+            .into_iter()
+            .filter_map(|t| Some((t.symbol, t.price.parse::<f64>().ok()?)))
+            .collect())
+    }
+
+    /// 24h statistics of several symbols in a single request (`ticker/24hr?symbols=[...]`)
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn get_tickers_24h(&self, symbols: &[String]) -> Result<Vec<Ticker24h>> {
+        if symbols.is_empty() {
+            return Ok(vec![]);
+        }
+        let url = format!("{}/api/v3/ticker/24hr", self.base_url);
+        let resp = self
+            .http
+            .get(&url)
+            .query(&[("symbols", serde_json::to_string(symbols)?)])
+            .send()
+            .await?;
+        let tickers: Vec<Ticker24h> = self.check_response(resp).await?.json().await?;
+        for t in &tickers {
+            self.ticker_cache.insert(t.symbol.clone(), t.last_f64(), TICKER_TTL);
+        }
+        Ok(tickers)
+    }
+
+    /// Best bid/ask of a symbol on the configured environment (testnet or mainnet)
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn get_book_ticker(&self, symbol: &str) -> Result<BookTicker> {
+        let url = format!("{}/api/v3/ticker/bookTicker?symbol={}", self.base_url, symbol);
+        let resp = self.http.get(&url).send().await?;
+        Ok(self.check_response(resp).await?.json().await?)
+    }
+
+    /// Best bid/ask like `get_book_ticker`, reused for a couple of seconds: the testnet
+    /// checks run on every tick that is about to trade
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn cached_book_ticker(&self, symbol: &str) -> Result<BookTicker> {
+        self.book_cache
+            .get_or_fetch(symbol.to_string(), BOOK_TTL, || self.get_book_ticker(symbol))
+            .await
+    }
+
+    /// True when orders go to the testnet, whose book differs from the streamed mainnet one
+    pub fn is_testnet(&self) -> bool {
+        self.testnet
+    }
+
+    /// Testnet only: compares the testnet book price for the side about to be traded
+    /// (ask for buys, bid for sells) with the displayed mainnet price.
+    /// Returns (testnet_price, divergence %) when it exceeds the configured threshold.
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn testnet_divergence(
+        &self,
+        symbol: &str,
+        displayed_price: f64,
+        is_buy: bool,
+    ) -> Result<Option<(f64, f64)>> {
+        if !self.testnet || self.testnet_max_divergence_pct <= 0.0 || displayed_price <= 0.0 {
+            return Ok(None);
+        }
+        let book = self.cached_book_ticker(symbol).await?;
+        let book_price = if is_buy { book.ask_f64() } else { book.bid_f64() };
+        if book_price <= 0.0 {
+            return Ok(None);
+        }
+        let diff_pct = (book_price - displayed_price) / displayed_price * 100.0;
+        if diff_pct.abs() > self.testnet_max_divergence_pct {
+            Ok(Some((book_price, diff_pct)))
+        } else {
+            Ok(None)
+        }
     }
 
     // -------------------------------------------------------
@@ -190,6 +507,7 @@ impl BinanceClient {
     // -------------------------------------------------------
 
     /// Account info (balances, permissions)
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
     pub async fn get_account(&self) -> Result<AccountInfo> {
         let ts = self.timestamp_ms();
         let query = format!("timestamp={}", ts);
@@ -202,95 +520,193 @@ impl BinanceClient {
     }
 
     /// Market buy order using quoteOrderQty (monto en USDT)
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
     pub async fn market_buy_quote(&self, symbol: &str, quote_qty: f64) -> Result<Order> {
-        let ts = self.timestamp_ms();
-        let body = format!(
-            "symbol={}&side=BUY&type=MARKET&quoteOrderQty={:.8}&timestamp={}",
-            symbol, quote_qty, ts
-        );
-        let sig = self.sign(&body);
-        let full_body = format!("{}&signature={}", body, sig);
-
-        let url = format!("{}/api/v3/order", self.base_url);
-        let resp = self
-            .http
-            .post(&url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .body(full_body)
-            .send()
-            .await?;
-
-        let resp = self.check_response(resp).await?;
-        Ok(resp.json::<Order>().await?)
+        let params = format!("symbol={}&side=BUY&type=MARKET&quoteOrderQty={:.8}", symbol, quote_qty);
+        let text = self.send_order(Method::POST, &params).await?;
+        Ok(serde_json::from_str::<Order>(&text)?)
     }
 
     /// Market buy order using quantity (exact base quantity, e.g.: BTC)
     /// Used to close SHORT positions: rebuy the exact quantity sold
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
     pub async fn market_buy_qty(&self, symbol: &str, quantity: f64) -> Result<Order> {
-        let ts = self.timestamp_ms();
-        let body = format!(
-            "symbol={}&side=BUY&type=MARKET&quantity={:.8}&timestamp={}",
-            symbol, quantity, ts
+        let quantity = self.compliant_qty(symbol, quantity, None).await?;
+        let params = format!("symbol={}&side=BUY&type=MARKET&quantity={:.8}", symbol, quantity);
+        let text = self.send_order(Method::POST, &params).await?;
+        Ok(serde_json::from_str::<Order>(&text)?)
+    }
+
+    /// Market sell order using quantity (base quantity, e.g.: BTC)
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn market_sell_qty(&self, symbol: &str, quantity: f64) -> Result<Order> {
+        let quantity = self.compliant_qty(symbol, quantity, None).await?;
+        let params = format!("symbol={}&side=SELL&type=MARKET&quantity={:.8}", symbol, quantity);
+        let text = self.send_order(Method::POST, &params).await?;
+        Ok(serde_json::from_str::<Order>(&text)?)
+    }
+
+    /// Limit buy order (GTC) for an exact base quantity.
+    /// `iceberg_qty` shows only that much of the order on the book.
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn limit_buy(&self, symbol: &str, quantity: f64, price: f64, iceberg_qty: Option<f64>) -> Result<Order> {
+        self.limit_order(symbol, "BUY", "LIMIT", quantity, price, iceberg_qty).await
+    }
+
+    /// Limit sell order (GTC) for an exact base quantity
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn limit_sell(&self, symbol: &str, quantity: f64, price: f64, iceberg_qty: Option<f64>) -> Result<Order> {
+        self.limit_order(symbol, "SELL", "LIMIT", quantity, price, iceberg_qty).await
+    }
+
+    /// Post-only buy (LIMIT_MAKER): rejected instead of filled if it would take liquidity
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn limit_maker_buy(&self, symbol: &str, quantity: f64, price: f64, iceberg_qty: Option<f64>) -> Result<Order> {
+        self.limit_order(symbol, "BUY", "LIMIT_MAKER", quantity, price, iceberg_qty).await
+    }
+
+    /// Post-only sell (LIMIT_MAKER): rejected instead of filled if it would take liquidity
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn limit_maker_sell(&self, symbol: &str, quantity: f64, price: f64, iceberg_qty: Option<f64>) -> Result<Order> {
+        self.limit_order(symbol, "SELL", "LIMIT_MAKER", quantity, price, iceberg_qty).await
+    }
+
+    async fn limit_order(
+        &self,
+        symbol: &str,
+        side: &str,
+        order_type: &str,
+        quantity: f64,
+        price: f64,
+        iceberg_qty: Option<f64>,
+    ) -> Result<Order> {
+        let price = match self.symbol_filters(symbol).await {
+            Ok(f) => f.round_price(price),
+            Err(_) => price,
+        };
+        let quantity = self.compliant_qty(symbol, quantity, Some(price)).await?;
+        // LIMIT_MAKER does not accept timeInForce
+        let time_in_force = if order_type == "LIMIT" { "&timeInForce=GTC" } else { "" };
+        let iceberg = iceberg_qty
+            .filter(|q| *q > 0.0 && *q < quantity)
+            .map(|q| format!("&icebergQty={:.8}", q))
+            .unwrap_or_default();
+        let params = format!(
+            "symbol={}&side={}&type={}{}&quantity={:.8}&price={:.8}{}",
+            symbol, side, order_type, time_in_force, quantity, price, iceberg
         );
-        let sig = self.sign(&body);
-        let full_body = format!("{}&signature={}", body, sig);
+        let text = self.send_order(Method::POST, &params).await?;
+        Ok(serde_json::from_str::<Order>(&text)?)
+    }
 
-        let url = format!("{}/api/v3/order", self.base_url);
-        let resp = self
-            .http
-            .post(&url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .body(full_body)
-            .send()
-            .await?;
+    /// True when a LIMIT_MAKER order was rejected because it would cross the book
+    pub fn would_take(err: &anyhow::Error) -> bool {
+        err.to_string().contains("immediately match")
+    }
 
-        let resp = self.check_response(resp).await?;
-        Ok(resp.json::<Order>().await?)
+    /// True when the request never got a usable answer (as opposed to an API rejection):
+    /// either the connection failed, or the order is an `UncertainOrder`
+    pub fn is_unreachable(err: &anyhow::Error) -> bool {
+        if Self::uncertain_order(err).is_some() {
+            return true;
+        }
+        err.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_connect())
     }
 
-    /// Market sell order using quantity (base quantity, e.g.: BTC)
-    pub async fn market_sell_qty(&self, symbol: &str, quantity: f64) -> Result<Order> {
+    /// Client order ID of an order whose outcome is unknown (see `UncertainOrder`)
+    pub fn uncertain_order(err: &anyhow::Error) -> Option<&str> {
+        err.downcast_ref::<UncertainOrder>().map(|u| u.client_order_id.as_str())
+    }
+
+    /// Status of an order by the client order ID it was sent with; None when Binance has
+    /// no such order (code -2013)
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn get_order_by_client_id(&self, symbol: &str, client_order_id: &str) -> Result<Option<Order>> {
         let ts = self.timestamp_ms();
-        let body = format!(
-            "symbol={}&side=SELL&type=MARKET&quantity={:.8}&timestamp={}",
-            symbol, quantity, ts
-        );
-        let sig = self.sign(&body);
-        let full_body = format!("{}&signature={}", body, sig);
+        let query = format!("symbol={}&origClientOrderId={}&timestamp={}", symbol, client_order_id, ts);
+        let sig = self.sign(&query);
+        let url = format!("{}/api/v3/order?{}&signature={}", self.base_url, query, sig);
 
-        let url = format!("{}/api/v3/order", self.base_url);
-        let resp = self
-            .http
-            .post(&url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .body(full_body)
-            .send()
-            .await?;
+        let resp = self.http.get(&url).send().await?;
+        match self.check_response(resp).await {
+            Ok(resp) => Ok(Some(resp.json::<Order>().await?)),
+            Err(e) if e.to_string().contains("Binance error -2013") => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
 
+    /// Current status of an order by ID
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn get_order(&self, symbol: &str, order_id: u64) -> Result<Order> {
+        let ts = self.timestamp_ms();
+        let query = format!("symbol={}&orderId={}&timestamp={}", symbol, order_id, ts);
+        let sig = self.sign(&query);
+        let url = format!("{}/api/v3/order?{}&signature={}", self.base_url, query, sig);
+
+        let resp = self.http.get(&url).send().await?;
         let resp = self.check_response(resp).await?;
         Ok(resp.json::<Order>().await?)
     }
 
-    /// Cancels an order by ID
-    pub async fn cancel_order(&self, symbol: &str, order_id: u64) -> Result<Value> {
+    /// Most recent fills of the account for a symbol (up to `limit`, oldest first)
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn my_trades(&self, symbol: &str, limit: u32) -> Result<Vec<AccountTrade>> {
         let ts = self.timestamp_ms();
-        let body = format!(
-            "symbol={}&orderId={}&timestamp={}",
-            symbol, order_id, ts
-        );
-        let sig = self.sign(&body);
-        let full_body = format!("{}&signature={}", body, sig);
+        let query = format!("symbol={}&limit={}&timestamp={}", symbol, limit, ts);
+        let sig = self.sign(&query);
+        let url = format!("{}/api/v3/myTrades?{}&signature={}", self.base_url, query, sig);
 
-        let url = format!("{}/api/v3/order", self.base_url);
-        let resp = self
-            .http
-            .delete(&url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .body(full_body)
-            .send()
-            .await?;
+        let resp = self.http.get(&url).send().await?;
+        let resp = self.check_response(resp).await?;
+        Ok(resp.json::<Vec<AccountTrade>>().await?)
+    }
 
+    /// Orders still open on the book for a symbol
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn open_orders(&self, symbol: &str) -> Result<Vec<Order>> {
+        let ts = self.timestamp_ms();
+        let query = format!("symbol={}&timestamp={}", symbol, ts);
+        let sig = self.sign(&query);
+        let url = format!("{}/api/v3/openOrders?{}&signature={}", self.base_url, query, sig);
+
+        let resp = self.http.get(&url).send().await?;
         let resp = self.check_response(resp).await?;
-        Ok(resp.json().await?)
+        Ok(resp.json::<Vec<Order>>().await?)
     }
+
+    /// Cancels an order by ID
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    pub async fn cancel_order(&self, symbol: &str, order_id: u64) -> Result<Value> {
+        let params = format!("symbol={}&orderId={}", symbol, order_id);
+        let text = self.send_order(Method::DELETE, &params).await?;
+        Ok(serde_json::from_str(&text)?)
+    }
+}
+
+/// Error for a non-2xx response, with Binance's code and message when the body has them
+fn api_error(status: StatusCode, text: &str) -> anyhow::Error {
+    match serde_json::from_str::<Value>(text) {
+        Ok(val) => {
+            let code = val["code"].as_i64().unwrap_or(0);
+            let msg = val["msg"].as_str().unwrap_or(text);
+            anyhow!("Binance error {}: {} (HTTP {})", code, msg, status)
+        }
+        Err(_) => anyhow!("HTTP {}: {}", status, text),
+    }
+}
+
+/// Kline cache TTL: a fifth of the candle interval, between 10 seconds and 15 minutes
+fn kline_ttl(interval: &str) -> Duration {
+    let (num, unit) = interval.split_at(interval.len().saturating_sub(1));
+    let n: u64 = num.parse().unwrap_or(1);
+    let secs = match unit {
+        "s" => n,
+        "m" => n * 60,
+        "h" => n * 3600,
+        "d" => n * 86_400,
+        "w" => n * 604_800,
+        "M" => n * 2_592_000,
+        _ => 3600,
+    };
+    Duration::from_secs((secs / 5).clamp(10, 900))
 }
@@ -1,2 +1,5 @@
+pub mod audit;
+pub mod cache;
 pub mod client;
+pub mod recording;
 pub mod websocket;
@@ -0,0 +1,138 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::websocket::{parse_frame, Frame};
+
+/// Seconds between two appends to the capture file
+const FLUSH_SECS: i64 = 5;
+
+/// One line of the capture file: a text frame exactly as the server sent it
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedFrame {
+    at: DateTime<Utc>,
+    frame: String,
+}
+
+/// Raw WebSocket frames of the last `minutes`, kept in memory and appended to a JSONL file
+/// every few seconds. Once per window the file is rewritten with just the frames in memory,
+/// so it holds between one and two windows and can be attached to bug reports about wrong
+/// prices or parsing. Write failures are only traced.
+#[derive(Debug)]
+pub struct FrameRecorder {
+    path: PathBuf,
+    window: Duration,
+    frames: VecDeque<RecordedFrame>,
+    /// Frames at the back of `frames` not in the file yet
+    unwritten: usize,
+    last_flush: DateTime<Utc>,
+    /// Last rewrite; None until the first one replaces the previous session's file
+    last_trim: Option<DateTime<Utc>>,
+}
+
+impl FrameRecorder {
+    /// Recorder writing to `path`, or None when `minutes` is 0 (recording off)
+    pub fn new(path: PathBuf, minutes: u64) -> Option<Self> {
+        (minutes > 0).then(|| Self {
+            path,
+            window: Duration::minutes(minutes as i64),
+            frames: VecDeque::new(),
+            unwritten: 0,
+            last_flush: Utc::now(),
+            last_trim: None,
+        })
+    }
+
+    pub fn record(&mut self, text: &str) {
+        let now = Utc::now();
+        self.frames.push_back(RecordedFrame { at: now, frame: text.to_string() });
+        self.unwritten += 1;
+        while self.frames.front().is_some_and(|f| now - f.at > self.window) {
+            self.frames.pop_front();
+        }
+        self.unwritten = self.unwritten.min(self.frames.len());
+        if (now - self.last_flush).num_seconds() >= FLUSH_SECS {
+            self.flush();
+        }
+    }
+
+    /// Appends the frames recorded since the last flush. Once a window has passed since
+    /// the last rewrite, rewrites the file with the frames in the window instead (through a
+    /// temporary file, so a reader never sees it half written)
+    pub fn flush(&mut self) {
+        let now = Utc::now();
+        self.last_flush = now;
+        let trim = self.last_trim.is_none_or(|t| now - t >= self.window);
+        let skip = if trim { 0 } else { self.frames.len() - self.unwritten };
+        let mut content = String::new();
+        for frame in self.frames.iter().skip(skip) {
+            match serde_json::to_string(frame) {
+                Ok(line) => {
+                    content.push_str(&line);
+                    content.push('\n');
+                }
+                Err(e) => tracing::warn!("Could not serialize recorded frame: {}", e),
+            }
+        }
+        let written = if trim {
+            let tmp = self.path.with_extension("jsonl.tmp");
+            std::fs::write(&tmp, content).and_then(|_| std::fs::rename(&tmp, &self.path))
+        } else if content.is_empty() {
+            Ok(())
+        } else {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .and_then(|mut file| file.write_all(content.as_bytes()))
+        };
+        match written {
+            Ok(()) => {
+                self.unwritten = 0;
+                if trim {
+                    self.last_trim = Some(now);
+                }
+            }
+            Err(e) => tracing::warn!("Could not write WebSocket capture {:?}: {}", self.path, e),
+        }
+    }
+}
+
+/// Runs every frame of a capture through the stream parser, printing what each one decoded
+/// to, and returns a summary with the count per kind
+pub fn replay(path: &Path) -> Result<String> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("Could not read {:?}", path))?;
+    let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    for (n, line) in content.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        let recorded: RecordedFrame = serde_json::from_str(line)
+            .with_context(|| format!("Line {} is not a recorded frame", n + 1))?;
+        let (kind, detail) = match parse_frame(&recorded.frame) {
+            Frame::Ticker(t) => ("ticker", format!("{} close {}", t.symbol, t.close_price)),
+            Frame::Kline(k) => (
+                "kline",
+                format!(
+                    "{} {} open_time {} close {}{}",
+                    k.symbol, k.kline.interval, k.kline.open_time, k.kline.close,
+                    if k.kline.closed { " (closed)" } else { "" }
+                ),
+            ),
+            Frame::AggTrade(t) => ("trade", format!("{} {} @ {}", t.symbol, t.quantity, t.price)),
+            Frame::Depth(d) => ("depth", format!("{} bid {} ask {}", d.symbol, d.best_bid(), d.best_ask())),
+            Frame::Response { id, error: None } => ("response", format!("request {} ok", id)),
+            Frame::Response { id, error: Some(error) } => ("error", format!("request {} failed: {}", id, error)),
+            Frame::Invalid { kind, stream, error } => {
+                ("error", format!("{} event not recognized ({}): {}", kind, stream, error))
+            }
+            Frame::Unrecognized => ("error", format!("JSON not recognized: {}", recorded.frame)),
+        };
+        *counts.entry(kind).or_default() += 1;
+        println!("{} {:<8} {}", recorded.at.format("%H:%M:%S%.3f"), kind, detail);
+    }
+    let total: usize = counts.values().sum();
+    let summary = counts.iter().map(|(k, n)| format!("{} {}", n, k)).collect::<Vec<_>>().join(", ");
+    Ok(format!("{} frame(s) replayed: {}", total, if total == 0 { "none" } else { summary.as_str() }))
+}
@@ -1,20 +1,134 @@
-use anyhow::Result;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
 use futures_util::{SinkExt, StreamExt};
 use tokio::sync::{mpsc, watch};
+use tokio::time::Instant;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
-use crate::models::ticker::{CombinedStreamWrapper, MiniTickerEvent};
+use crate::api::recording::FrameRecorder;
+use crate::config::WebSocketConfig;
+use crate::market_data::TickerBoard;
+use crate::models::ticker::{AggTradeEvent, CombinedStreamWrapper, KlineEvent, MiniTickerEvent, PartialDepth};
 
 // Los precios son datos públicos: siempre se usa mainnet para el WebSocket.
-const MAINNET_WS: &str = "wss://stream.binance.com:9443";
+// Si un endpoint no responde se prueba el siguiente (data-stream.binance.vision solo
+// sirve datos de mercado, que es todo lo que se usa acá).
+pub const MAINNET_WS: [&str; 3] = [
+    "wss://stream.binance.com:9443",
+    "wss://stream.binance.com:443",
+    "wss://data-stream.binance.vision",
+];
+
+/// Binance corta cada conexión a las 24 h: se reconecta antes, con margen
+const MAX_SESSION: Duration = Duration::from_secs(23 * 3600 + 30 * 60);
+/// Cada cuánto se envía un ping propio al servidor
+const PING_EVERY: Duration = Duration::from_secs(60);
+/// Sin ningún mensaje (datos, ping o pong) en este tiempo la conexión se da por muerta
+const SILENCE_TIMEOUT: Duration = Duration::from_secs(90);
+/// Streams pedidos en la URL de conexión: el resto se suscribe después por frames, así la
+/// URL no crece sin límite con muchos slots
+const URL_STREAMS: usize = 100;
+/// Streams por frame SUBSCRIBE/UNSUBSCRIBE
+const SUBSCRIBE_BATCH: usize = 100;
+/// Pausa entre frames de suscripción: Binance acepta hasta 5 mensajes por segundo
+const SUBSCRIBE_GAP: Duration = Duration::from_millis(250);
+/// Streams que Binance permite en una sola conexión
+const MAX_STREAMS: usize = 1024;
+
+/// Cómo terminó una sesión del WebSocket
+enum SessionEnd {
+    /// No se pudo conectar: se prueba el próximo endpoint
+    Unreachable(anyhow::Error),
+    /// El servidor cerró la conexión o falló en medio de la sesión
+    Dropped(Option<anyhow::Error>),
+    /// Se alcanzó `MAX_SESSION`: reconexión preventiva
+    Expired,
+}
+
+/// Estado de la conexión del stream de precios
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StreamStatus {
+    /// Todavía no hubo una primera conexión
+    #[default]
+    Connecting,
+    Connected,
+    /// Conexión perdida, esperando reconectar
+    Reconnecting,
+    /// Conectado pero algún símbolo operado no recibe precios dentro del umbral
+    /// (lo determina el motor, no el stream)
+    Stale,
+    /// Se agotaron los reintentos (`websocket.max_retries`): espera un cambio de símbolos
+    Down,
+}
+
+impl StreamStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StreamStatus::Connecting => "connecting",
+            StreamStatus::Connected => "connected",
+            StreamStatus::Reconnecting => "reconnecting",
+            StreamStatus::Stale => "stale",
+            StreamStatus::Down => "down",
+        }
+    }
+}
+
+/// Contadores de conexión del stream, visibles en el panel de diagnóstico
+#[derive(Debug, Clone, Default)]
+pub struct ReconnectStats {
+    /// Sesiones establecidas desde el arranque
+    pub sessions: u64,
+    /// Intentos de conexión fallidos (endpoint inalcanzable)
+    pub failed_attempts: u64,
+    /// Sesiones cortadas por el servidor, por error o por silencio
+    pub drops: u64,
+    /// Reconexiones preventivas antes del límite de 24 h
+    pub expirations: u64,
+    /// Cambios en la lista de símbolos, aplicados con SUBSCRIBE/UNSUBSCRIBE
+    pub symbol_changes: u64,
+    /// Trades de @aggTrade descartados porque el canal hacia el motor estaba lleno
+    pub trades_dropped: u64,
+    /// Fallos seguidos sin lograr una sesión (define la espera del backoff)
+    pub consecutive_failures: u32,
+    /// Espera aplicada antes del último reintento
+    pub last_delay: Option<Duration>,
+    pub last_error: Option<String>,
+    pub endpoint: Option<&'static str>,
+    pub connected_since: Option<DateTime<Utc>>,
+}
 
-/// Inicia el stream de precios vía WebSocket (@miniTicker).
-/// Soporta múltiples símbolos usando el combined stream de Binance.
-/// Se reconecta automáticamente en caso de error o cambio en la lista de símbolos.
+/// Estado de conexión más contadores, publicado en un canal watch
+#[derive(Debug, Clone, Default)]
+pub struct StreamHealth {
+    pub status: StreamStatus,
+    pub stats: ReconnectStats,
+}
+
+/// Inicia el stream de precios vía WebSocket (@miniTicker, publicado en `tickers`), el tope del libro
+/// (@depth5@1000ms) y las velas (@kline_<intervalo>) de cada símbolo para `intervals`.
+/// Con `trade_tx` también se suscribe a @aggTrade (volumen comprador/vendedor).
+/// Soporta múltiples símbolos usando el combined stream de Binance: los cambios en la lista de
+/// símbolos se aplican con frames SUBSCRIBE/UNSUBSCRIBE sobre la misma conexión, sin cortar
+/// los precios de los demás. Se reconecta automáticamente en caso de error (con el backoff de
+/// `policy`) y de forma preventiva antes del límite de 24 h de Binance, publicando cada cambio
+/// de conexión y los contadores en `health_tx`. Con `recorder` guarda los frames crudos de
+/// los últimos minutos para adjuntarlos a reportes de errores.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_price_stream(
     mut symbol_rx: watch::Receiver<Vec<String>>,
-    price_tx: mpsc::Sender<MiniTickerEvent>,
+    policy: WebSocketConfig,
+    health_tx: watch::Sender<StreamHealth>,
+    intervals: Vec<String>,
+    tickers: Arc<TickerBoard>,
+    book_tx: mpsc::Sender<PartialDepth>,
+    kline_tx: mpsc::Sender<KlineEvent>,
+    trade_tx: Option<mpsc::Sender<AggTradeEvent>>,
+    mut recorder: Option<FrameRecorder>,
 ) {
+    let mut endpoint = 0usize;
     loop {
         let symbols = symbol_rx.borrow_and_update().clone();
 
@@ -23,71 +137,343 @@ pub async fn run_price_stream(
             continue;
         }
 
-        // Combined stream URL:
-        // wss://stream.binance.com:9443/stream?streams=btcusdt@miniTicker/ethusdt@miniTicker
-        let streams: String = symbols
-            .iter()
-            .map(|s| format!("{}@miniTicker", s.to_lowercase()))
-            .collect::<Vec<_>>()
-            .join("/");
-        let ws_url = format!("{}/stream?streams={}", MAINNET_WS, streams);
+        let streams = stream_names(&symbols, &intervals, trade_tx.is_some());
+        let base = MAINNET_WS[endpoint % MAINNET_WS.len()];
 
-        tracing::info!("Connecting WebSocket ({} symbol(s))", symbols.len());
+        tracing::info!("Connecting WebSocket to {} ({} symbol(s), {} streams)", base, symbols.len(), streams.len());
+        if streams.len() > MAX_STREAMS {
+            tracing::warn!(
+                "{} streams exceed the {} Binance allows per connection: some symbols will get no data",
+                streams.len(), MAX_STREAMS
+            );
+        }
 
-        tokio::select! {
-            result = connect_and_stream(&ws_url, price_tx.clone()) => {
-                match result {
-                    Ok(_) => tracing::warn!("WebSocket closed, reconnecting..."),
-                    Err(e) => tracing::error!("WebSocket error: {}, reconnecting in 5s...", e),
+        let end = connect_and_stream(
+            base,
+            streams,
+            &intervals,
+            &mut symbol_rx,
+            &health_tx,
+            &tickers,
+            book_tx.clone(),
+            kline_tx.clone(),
+            trade_tx.clone(),
+            &mut recorder,
+        )
+        .await;
+        // La captura queda completa hasta el corte, útil justo cuando algo falló
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.flush();
+        }
+
+        let mut error = None;
+        health_tx.send_modify(|h| {
+            h.status = StreamStatus::Reconnecting;
+            h.stats.connected_since = None;
+            match &end {
+                SessionEnd::Expired => h.stats.expirations += 1,
+                SessionEnd::Unreachable(e) => {
+                    h.stats.failed_attempts += 1;
+                    h.stats.consecutive_failures += 1;
+                    error = Some(e.to_string());
                 }
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                SessionEnd::Dropped(e) => {
+                    h.stats.drops += 1;
+                    h.stats.consecutive_failures += 1;
+                    error = Some(e.as_ref().map_or_else(|| "closed by server".to_string(), |e| e.to_string()));
+                }
+            }
+            if error.is_some() {
+                h.stats.last_error = error.clone();
+            }
+        });
+        // Reconexión inmediata: la sesión vieja ya no recibe datos
+        if let SessionEnd::Expired = end {
+            tracing::info!("WebSocket session near the 24h limit, reconnecting");
+            continue;
+        }
+
+        let failures = health_tx.borrow().stats.consecutive_failures;
+        if policy.gave_up(failures) {
+            health_tx.send_modify(|h| h.status = StreamStatus::Down);
+            tracing::error!(
+                "WebSocket gave up after {} failed attempts in a row ({}), waiting for a symbol change",
+                failures, error.unwrap_or_default()
+            );
+            if symbol_rx.changed().await.is_err() {
+                return;
             }
+            health_tx.send_modify(|h| {
+                h.status = StreamStatus::Reconnecting;
+                h.stats.consecutive_failures = 0;
+                h.stats.symbol_changes += 1;
+            });
+            continue;
+        }
+
+        let delay = policy.backoff(failures);
+        health_tx.send_modify(|h| h.stats.last_delay = Some(delay));
+        match end {
+            SessionEnd::Unreachable(e) => {
+                endpoint += 1;
+                tracing::error!(
+                    "WebSocket {} unreachable: {}, trying {} in {:.1}s (attempt {})...",
+                    base, e, MAINNET_WS[endpoint % MAINNET_WS.len()], delay.as_secs_f64(), failures
+                );
+            }
+            SessionEnd::Dropped(None) => {
+                tracing::warn!("WebSocket closed, reconnecting in {:.1}s...", delay.as_secs_f64())
+            }
+            SessionEnd::Dropped(Some(e)) => {
+                tracing::error!("WebSocket error: {}, reconnecting in {:.1}s...", e, delay.as_secs_f64())
+            }
+            SessionEnd::Expired => {}
+        }
+        // Un cambio de símbolos durante la espera reconecta enseguida
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
             _ = symbol_rx.changed() => {
-                tracing::info!("Symbols changed, reconnecting WebSocket...");
+                health_tx.send_modify(|h| h.stats.symbol_changes += 1);
             }
         }
     }
 }
 
+/// Nombres de los streams de `symbols`, ej. btcusdt@miniTicker, btcusdt@kline_1h
+fn stream_names(symbols: &[String], intervals: &[String], trades: bool) -> BTreeSet<String> {
+    symbols
+        .iter()
+        .flat_map(|s| {
+            let s = s.to_lowercase();
+            [format!("{}@miniTicker", s), format!("{}@depth5@1000ms", s)]
+                .into_iter()
+                .chain(trades.then(|| format!("{}@aggTrade", s)))
+                .chain(intervals.iter().map(move |i| format!("{}@kline_{}", s, i)))
+        })
+        .collect()
+}
+
+/// Frame de texto del stream ya interpretado
+pub enum Frame {
+    Ticker(MiniTickerEvent),
+    Kline(KlineEvent),
+    AggTrade(AggTradeEvent),
+    /// Con el símbolo tomado del nombre del stream
+    Depth(PartialDepth),
+    /// Respuesta a un SUBSCRIBE/UNSUBSCRIBE: {"result": null, "id": 1}
+    Response { id: serde_json::Value, error: Option<serde_json::Value> },
+    /// JSON de un stream conocido que no se pudo leer como `kind`
+    Invalid { kind: &'static str, stream: String, error: serde_json::Error },
+    /// No es JSON
+    Unrecognized,
+}
+
+/// Interpreta un frame de texto (también lo usa la reproducción de capturas, `--replay-ws`)
+pub fn parse_frame(text: &str) -> Frame {
+    // Intentar parsear como combined stream wrapper primero
+    let (stream, data) = match serde_json::from_str::<CombinedStreamWrapper>(text) {
+        Ok(wrapper) => (wrapper.stream, wrapper.data),
+        Err(_) => match serde_json::from_str::<serde_json::Value>(text) {
+            Ok(data) if data.get("id").is_some() => {
+                return Frame::Response { id: data["id"].clone(), error: data.get("error").cloned() };
+            }
+            Ok(data) => (String::new(), data),
+            Err(_) => return Frame::Unrecognized,
+        },
+    };
+
+    let invalid = |kind, stream: String, error| Frame::Invalid { kind, stream, error };
+    if stream.contains("@kline_") || data["e"] == "kline" {
+        serde_json::from_value(data).map_or_else(|e| invalid("Kline", stream, e), Frame::Kline)
+    } else if stream.contains("@aggTrade") || data["e"] == "aggTrade" {
+        serde_json::from_value(data).map_or_else(|e| invalid("AggTrade", stream, e), Frame::AggTrade)
+    } else if stream.contains("@depth") {
+        // El payload de @depth5 no trae el símbolo: se toma del nombre del stream
+        match serde_json::from_value::<PartialDepth>(data) {
+            Ok(mut depth) => {
+                depth.symbol = stream.split('@').next().unwrap_or_default().to_uppercase();
+                Frame::Depth(depth)
+            }
+            Err(e) => invalid("Depth", stream, e),
+        }
+    } else {
+        serde_json::from_value(data).map_or_else(|e| invalid("Ticker", stream, e), Frame::Ticker)
+    }
+}
+
+/// Frame SUBSCRIBE/UNSUBSCRIBE del combined stream (`id` identifica la respuesta)
+fn subscription_frame(method: &str, streams: &[&String], id: u64) -> Message {
+    Message::Text(serde_json::json!({ "method": method, "params": streams, "id": id }).to_string())
+}
+
+/// Envía `streams` en frames de hasta `SUBSCRIBE_BATCH`, espaciados por `SUBSCRIBE_GAP` para
+/// no pasar el límite de mensajes por segundo (pasarlo cierra la conexión)
+async fn send_subscriptions<S>(write: &mut S, method: &str, streams: &[&String], request_id: &mut u64) -> anyhow::Result<()>
+where
+    S: futures_util::Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin,
+{
+    for (i, batch) in streams.chunks(SUBSCRIBE_BATCH).enumerate() {
+        if i > 0 {
+            tokio::time::sleep(SUBSCRIBE_GAP).await;
+        }
+        *request_id += 1;
+        write.send(subscription_frame(method, batch, *request_id)).await?;
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn connect_and_stream(
-    ws_url: &str,
-    price_tx: mpsc::Sender<MiniTickerEvent>,
-) -> Result<()> {
-    let (ws_stream, _response) = connect_async(ws_url).await?;
+    base: &'static str,
+    mut streams: BTreeSet<String>,
+    intervals: &[String],
+    symbol_rx: &mut watch::Receiver<Vec<String>>,
+    health_tx: &watch::Sender<StreamHealth>,
+    tickers: &TickerBoard,
+    book_tx: mpsc::Sender<PartialDepth>,
+    kline_tx: mpsc::Sender<KlineEvent>,
+    trade_tx: Option<mpsc::Sender<AggTradeEvent>>,
+    recorder: &mut Option<FrameRecorder>,
+) -> SessionEnd {
+    // Combined stream URL:
+    // wss://stream.binance.com:9443/stream?streams=btcusdt@miniTicker/btcusdt@depth5@1000ms/...
+    let ws_url = format!(
+        "{}/stream?streams={}",
+        base,
+        streams.iter().take(URL_STREAMS).map(String::as_str).collect::<Vec<_>>().join("/")
+    );
+    let ws_stream = match connect_async(&ws_url).await {
+        Ok((ws_stream, _response)) => ws_stream,
+        Err(e) => return SessionEnd::Unreachable(e.into()),
+    };
     let (mut write, mut read) = ws_stream.split();
 
-    tracing::info!("WebSocket connected");
+    health_tx.send_modify(|h| {
+        h.status = StreamStatus::Connected;
+        h.stats.sessions += 1;
+        h.stats.consecutive_failures = 0;
+        h.stats.endpoint = Some(base);
+        h.stats.connected_since = Some(Utc::now());
+    });
+    let stats = health_tx.borrow().stats.clone();
+    tracing::info!(
+        "WebSocket connected (session #{}, {} drops, {} failed attempts, {} 24h rotations)",
+        stats.sessions, stats.drops, stats.failed_attempts, stats.expirations
+    );
+
+    let mut request_id = 0u64;
+    // Los streams que no entraron en la URL
+    let rest: Vec<&String> = streams.iter().skip(URL_STREAMS).collect();
+    if !rest.is_empty() {
+        tokio::time::sleep(SUBSCRIBE_GAP).await;
+        if let Err(e) = send_subscriptions(&mut write, "SUBSCRIBE", &rest, &mut request_id).await {
+            return SessionEnd::Dropped(Some(e));
+        }
+    }
+
+    let expires = Instant::now() + MAX_SESSION;
+    let mut ping = tokio::time::interval(PING_EVERY);
+    ping.tick().await;
+    let mut last_message = Instant::now();
 
-    while let Some(msg) = read.next().await {
+    loop {
+        let msg = tokio::select! {
+            msg = read.next() => match msg {
+                Some(msg) => msg,
+                None => return SessionEnd::Dropped(None),
+            },
+            _ = ping.tick() => {
+                // Heartbeat propio: detecta conexiones colgadas que no cierran el socket
+                if last_message.elapsed() > SILENCE_TIMEOUT {
+                    return SessionEnd::Dropped(Some(anyhow::anyhow!(
+                        "no messages for {}s",
+                        last_message.elapsed().as_secs()
+                    )));
+                }
+                if let Err(e) = write.send(Message::Ping(Vec::new())).await {
+                    return SessionEnd::Dropped(Some(e.into()));
+                }
+                continue;
+            }
+            _ = tokio::time::sleep_until(expires) => return SessionEnd::Expired,
+            Ok(()) = symbol_rx.changed() => {
+                // Solo se piden los streams que cambiaron: los demás siguen recibiendo datos
+                let symbols = symbol_rx.borrow_and_update().clone();
+                let wanted = stream_names(&symbols, intervals, trade_tx.is_some());
+                let removed: Vec<&String> = streams.difference(&wanted).collect();
+                let added: Vec<&String> = wanted.difference(&streams).collect();
+                tracing::info!(
+                    "Symbols changed: subscribing {} and unsubscribing {} stream(s)",
+                    added.len(), removed.len()
+                );
+                for (method, changed) in [("UNSUBSCRIBE", removed), ("SUBSCRIBE", added)] {
+                    if changed.is_empty() {
+                        continue;
+                    }
+                    if let Err(e) = send_subscriptions(&mut write, method, &changed, &mut request_id).await {
+                        return SessionEnd::Dropped(Some(e));
+                    }
+                }
+                health_tx.send_modify(|h| h.stats.symbol_changes += 1);
+                streams = wanted;
+                continue;
+            }
+        };
+        last_message = Instant::now();
         match msg {
             Ok(Message::Text(text)) => {
-                // Intentar parsear como combined stream wrapper primero
-                let event = if let Ok(wrapper) = serde_json::from_str::<CombinedStreamWrapper>(&text) {
-                    Some(wrapper.data)
-                } else if let Ok(event) = serde_json::from_str::<MiniTickerEvent>(&text) {
-                    Some(event)
-                } else {
-                    tracing::warn!("JSON not recognized: {}", &text[..text.len().min(120)]);
-                    None
-                };
-
-                if let Some(event) = event {
-                    let _ = price_tx.try_send(event);
+                if let Some(recorder) = recorder.as_mut() {
+                    recorder.record(&text);
+                }
+                match parse_frame(&text) {
+                    Frame::Ticker(event) => tickers.publish(event),
+                    Frame::Kline(event) => {
+                        let _ = kline_tx.try_send(event);
+                    }
+                    Frame::AggTrade(event) => {
+                        // Canal lleno: el trade se descarta y se cuenta, sin despertar a
+                        // los que miran la salud del stream por cada uno
+                        if let Some(trade_tx) = &trade_tx {
+                            if trade_tx.try_send(event).is_err() {
+                                health_tx.send_if_modified(|h| {
+                                    h.stats.trades_dropped += 1;
+                                    if h.stats.trades_dropped == 1 {
+                                        tracing::warn!("aggTrade channel full, dropping trades (volume will read low)");
+                                    }
+                                    false
+                                });
+                            }
+                        }
+                    }
+                    Frame::Depth(depth) => {
+                        let _ = book_tx.try_send(depth);
+                    }
+                    Frame::Response { error: None, .. } => {}
+                    Frame::Response { id, error: Some(error) } => {
+                        tracing::error!("WebSocket subscription request {} failed: {}", id, error);
+                        health_tx.send_modify(|h| h.stats.last_error = Some(format!("subscription: {}", error)));
+                    }
+                    Frame::Invalid { kind, stream, error } => {
+                        tracing::warn!("{} event not recognized ({}): {}", kind, stream, error)
+                    }
+                    Frame::Unrecognized => {
+                        tracing::warn!("JSON not recognized: {}", &text[..text.len().min(120)])
+                    }
                 }
             }
             Ok(Message::Ping(data)) => {
-                write.send(Message::Pong(data)).await?;
+                if let Err(e) = write.send(Message::Pong(data)).await {
+                    return SessionEnd::Dropped(Some(e.into()));
+                }
             }
             Ok(Message::Close(_)) => {
                 tracing::warn!("WebSocket: server closed the connection");
-                break;
+                return SessionEnd::Dropped(None);
             }
             Err(e) => {
-                return Err(e.into());
+                return SessionEnd::Dropped(Some(e.into()));
             }
             _ => {}
         }
     }
-
-    Ok(())
 }
@@ -1,10 +1,27 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
 
-use crate::config::Direction;
+use crate::api::websocket::{ReconnectStats, StreamStatus};
+use crate::config::{DcaConfig, Direction, DisplayTimeZone, EquityFloorAction, ExposureLimit, GroupConfig, NotificationsConfig, NotifyEvent, QuoteAmount, Setting, SlotSection, StrategyKind, FeesConfig, UiConfig, WebSocketConfig};
+use crate::ladder::{LadderBook, LadderSizing};
+use crate::market_data::{CandleStore, PriceHistory, VolumeTracker};
+use crate::models::order::{OrderSide, PendingExit};
+use crate::models::symbol::SymbolFilters;
+use crate::orders::OrderManager;
+use crate::position_import::PositionImport;
+use crate::presets::PresetBook;
+use crate::equity_history::EquityHistory;
+use crate::session_stats::SessionStats;
+use crate::state_file::StateWriter;
+use crate::strategy::cycles::CycleHistory;
 use crate::strategy::dca::DcaStrategy;
-
-/// Máximo de estrategias simultáneas
-pub const MAX_SLOTS: usize = 4;
+use crate::strategy::events::{EventLog, SlotEventKind};
+use crate::strategy::fees::CommissionRates;
+use crate::strategy::performance::SlotPerformance;
+use crate::triggers::TriggerBook;
+use crate::ui::theme::Theme;
+use crate::valuation::Valuation;
 
 /// Lista de respaldo cuando la API de Binance no está disponible
 pub const DEFAULT_SYMBOLS: &[&str] = &[
@@ -19,6 +36,193 @@ pub struct MarketData {
     pub change_24h_pct: f64,
     pub high_24h: f64,
     pub low_24h: f64,
+    /// Mejor bid/ask del stream @depth5 (0 hasta el primer evento)
+    pub bid: f64,
+    pub ask: f64,
+    /// Momento del último evento del libro
+    pub book_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Momento de la última actualización del precio (ticker del WebSocket o fallback REST)
+    pub price_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Último mensaje del WebSocket para el símbolo (ticker, libro o vela). @miniTicker solo
+    /// llega cuando hay operaciones; el libro y las velas siguen llegando en un par quieto
+    pub message_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Segundos sin eventos del libro tras los cuales bid/ask se consideran viejos
+pub const BOOK_STALE_SECS: i64 = 10;
+
+impl MarketData {
+    /// Spread entre el mejor bid y ask en % del punto medio. None sin libro reciente.
+    pub fn spread_pct(&self) -> Option<f64> {
+        let fresh = self
+            .book_at
+            .is_some_and(|t| (chrono::Utc::now() - t).num_seconds() < BOOK_STALE_SECS);
+        if !fresh || self.bid <= 0.0 || self.ask < self.bid {
+            return None;
+        }
+        let mid = (self.bid + self.ask) / 2.0;
+        Some((self.ask - self.bid) / mid * 100.0)
+    }
+
+    /// Segundos desde la última señal de vida del símbolo: la actualización del precio o
+    /// cualquier mensaje del stream, lo más nuevo (None si el precio nunca llegó). Un par sin
+    /// operaciones conserva su último precio mientras el libro y las velas sigan llegando
+    pub fn price_age_secs(&self, now: chrono::DateTime<chrono::Utc>) -> Option<i64> {
+        let price_at = self.price_at?;
+        let latest = self.message_at.map_or(price_at, |t| t.max(price_at));
+        Some((now - latest).num_seconds().max(0))
+    }
+
+    /// Precio sin señal de vida hace más de `max_age_secs` (0 = sin control)
+    pub fn is_stale(&self, max_age_secs: u64, now: chrono::DateTime<chrono::Utc>) -> bool {
+        max_age_secs > 0 && self.price_age_secs(now).is_some_and(|age| age > max_age_secs as i64)
+    }
+}
+
+/// Líneas de log guardadas en memoria (el panel muestra las últimas, la vista L todas)
+pub const LOG_CAPACITY: usize = 1000;
+
+/// Severidad de una línea del log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Info,
+    Alert,
+    Error,
+}
+
+impl LogLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogLevel::Info => "info",
+            LogLevel::Alert => "alert",
+            LogLevel::Error => "error",
+        }
+    }
+}
+
+/// Línea del log del TUI
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub at: chrono::DateTime<chrono::Utc>,
+    pub level: LogLevel,
+    /// Símbolo al que se refiere el mensaje (None = general), lo pasa quien loguea
+    pub symbol: Option<String>,
+    pub message: String,
+}
+
+impl LogEntry {
+    /// Texto como se muestra: "[12:00:00] ALERT ..." / "[12:00:00] ⚠ ..."
+    pub fn text(&self) -> String {
+        let prefix = match self.level {
+            LogLevel::Info => "",
+            LogLevel::Alert => "ALERT ",
+            LogLevel::Error => "⚠ ",
+        };
+        format!("[{}] {}{}", fmt_time(self.at, "%H:%M:%S"), prefix, self.message)
+    }
+}
+
+/// Config de partida del modal de nueva estrategia cuando no es `[dca]`: un slot duplicado
+/// (Y) o un preset (P)
+#[derive(Debug, Clone)]
+pub struct StrategyTemplate {
+    /// Título del modal, ej. "Duplicate BTCUSDT" o "Preset conservative"
+    pub label: String,
+    pub config: DcaConfig,
+    /// Campos que difieren de `[dca]`: se guardan como overrides del slot nuevo
+    pub overrides: Vec<(String, serde_json::Value)>,
+}
+
+/// Texto que se está escribiendo en la pantalla de presets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresetInput {
+    /// Nombre para guardar la config del slot seleccionado (N)
+    SaveAs,
+    /// Nuevo nombre del preset seleccionado (R)
+    Rename,
+    /// Valor del campo seleccionado (Enter)
+    Value,
+}
+
+/// Selección y edición de la pantalla de presets (M)
+#[derive(Debug, Clone, Default)]
+pub struct PresetView {
+    pub selected: usize,
+    /// Campo seleccionado del preset (en el orden del archivo)
+    pub field: usize,
+    pub input: Option<PresetInput>,
+    pub buf: String,
+    /// Error de lo último que se escribió (se muestra en la pantalla)
+    pub error: Option<String>,
+}
+
+/// Filtros y scroll de la vista de log (L)
+#[derive(Debug, Clone, Default)]
+pub struct LogView {
+    /// Líneas desde la más nueva (0 = sigue el final)
+    pub scroll: usize,
+    /// Solo esta severidad (None = todas)
+    pub level: Option<LogLevel>,
+    /// Solo las líneas del símbolo del slot seleccionado
+    pub selected_slot_only: bool,
+    /// Texto a buscar (sin distinguir mayúsculas)
+    pub search: String,
+    /// Escribiendo en la búsqueda: las teclas van al texto
+    pub searching: bool,
+}
+
+impl LogView {
+    /// Siguiente filtro de severidad: todas → info → alert → error → todas
+    pub fn cycle_level(&mut self) {
+        self.level = match self.level {
+            None => Some(LogLevel::Info),
+            Some(LogLevel::Info) => Some(LogLevel::Alert),
+            Some(LogLevel::Alert) => Some(LogLevel::Error),
+            Some(LogLevel::Error) => None,
+        };
+        self.scroll = 0;
+    }
+
+    /// La línea pasa los filtros (`symbol` es el del slot seleccionado)
+    pub fn matches(&self, entry: &LogEntry, symbol: Option<&str>) -> bool {
+        self.level.is_none_or(|level| entry.level == level)
+            && (!self.selected_slot_only || entry.symbol.is_some() && entry.symbol.as_deref() == symbol)
+            && (self.search.is_empty() || entry.message.to_lowercase().contains(&self.search.to_lowercase()))
+    }
+}
+
+/// Alerta S/R disparada, con su estado de reconocimiento
+#[derive(Debug, Clone)]
+pub struct AlertRecord {
+    pub at: chrono::DateTime<chrono::Utc>,
+    pub symbol: String,
+    /// "SUPPORT", "RESISTANCE", "VOL BUY" o "VOL SELL"
+    pub kind: &'static str,
+    /// Nivel cruzado (en las de volumen: múltiplo sobre el promedio)
+    pub level: f64,
+    /// Precio al dispararse
+    pub price: f64,
+    pub acknowledged: bool,
+}
+
+/// Símbolo de un slot sin velas en Binance (recién listado, o mal escrito en un estado
+/// restaurado): el motor de alertas y el relleno de gráficos lo saltean con backoff
+#[derive(Debug, Clone)]
+pub struct MissingKlines {
+    /// Binance no conoce el símbolo (-1121); si no, respondió sin velas
+    pub unknown: bool,
+    /// Símbolo disponible más parecido, como sugerencia
+    pub suggestion: Option<String>,
+    /// Pedidos fallidos seguidos (cada uno duplica la espera)
+    pub failures: u32,
+    /// No se vuelven a pedir velas antes de este instante
+    pub retry_at: std::time::Instant,
+}
+
+impl MissingKlines {
+    pub fn reason(&self) -> &'static str {
+        if self.unknown { "symbol not found" } else { "no klines yet" }
+    }
 }
 
 /// Niveles de soporte/resistencia calculados por el motor de alertas
@@ -44,6 +248,186 @@ pub struct StrategySlot {
     pub quote_asset: String,
     pub base_balance: f64,
     pub quote_balance: f64,
+    /// Órdenes enviadas y fallidas en esta sesión (para el health score)
+    pub api_calls: u32,
+    pub api_errors: u32,
+    /// Entrada bloqueada por el presupuesto del grupo (para loguear solo el cambio)
+    pub group_blocked: bool,
+    /// Entrada bloqueada por falta de efectivo en la billetera virtual (ídem)
+    pub wallet_blocked: bool,
+    /// Entrada bloqueada por la exposición máxima del asset (ídem)
+    pub exposure_blocked: bool,
+    /// Estrategia recién creada: no entra hasta tener un tick en vivo y los filtros del símbolo
+    pub warming_up: bool,
+    /// Filtros del símbolo (exchangeInfo), None hasta que se obtienen
+    pub filters: Option<SymbolFilters>,
+    /// Historial del event log: P&L realizado (sparklines) y duración de ciclos
+    pub performance: SlotPerformance,
+    /// Estado del símbolo cuando salió de TRADING (HALT, BREAK, DELISTED...): slot pausado
+    pub halted: Option<String>,
+    /// Salida automática que no llegó a Binance (EXIT PENDING): se reintenta en cada tick
+    pub exit_pending: Option<PendingExit>,
+    /// LIMIT_MAKER de entrada rechazada por cruzar el libro: rechazos seguidos y desde
+    /// cuándo se puede volver a colocar (backoff de `orders::maker_retry_delay`)
+    pub maker_retry: Option<(u32, chrono::DateTime<chrono::Utc>)>,
+    /// Intervalo de velas del gráfico y de los niveles S/R del panel de precio (T)
+    pub analysis_interval: String,
+}
+
+impl StrategySlot {
+    pub fn new(id: usize, symbol: String, strategy: DcaStrategy) -> Self {
+        let (base_asset, quote_asset) = parse_symbol(&symbol);
+        Self {
+            id,
+            strategy,
+            symbol,
+            base_asset,
+            quote_asset,
+            base_balance: 0.0,
+            quote_balance: 0.0,
+            api_calls: 0,
+            api_errors: 0,
+            group_blocked: false,
+            wallet_blocked: false,
+            exposure_blocked: false,
+            warming_up: false,
+            filters: None,
+            performance: SlotPerformance::default(),
+            halted: None,
+            exit_pending: None,
+            maker_retry: None,
+            analysis_interval: crate::market_data::DEFAULT_ANALYSIS_INTERVAL.to_string(),
+        }
+    }
+
+    /// Efectivo libre de la billetera virtual: capital asignado + P&L realizado − lo
+    /// comprometido en la posición abierta. None si el slot no tiene capital asignado
+    /// (usa el balance de la cuenta como antes).
+    pub fn wallet_cash(&self) -> Option<f64> {
+        let capital = self.strategy.config.capital_usdt;
+        if capital <= 0.0 {
+            return None;
+        }
+        Some(capital + self.performance.realized_total() - self.strategy.total_invested())
+    }
+
+    /// Monto de la próxima entrada. Con billetera virtual, los montos en % y el balance
+    /// disponible se limitan a su efectivo aunque la cuenta tenga más.
+    pub fn entry_amount(&self, price: f64) -> f64 {
+        let (mut quote, mut base) = (self.quote_balance, self.base_balance);
+        if let Some(cash) = self.wallet_cash() {
+            let cash = cash.max(0.0);
+            quote = quote.min(cash);
+            if price > 0.0 {
+                base = base.min(cash / price);
+            }
+        }
+        self.strategy.entry_amount(quote, base, price)
+    }
+
+    /// Registra el resultado de una orden enviada al exchange
+    pub fn record_api_result(&mut self, ok: bool) {
+        self.api_calls += 1;
+        if !ok {
+            self.api_errors += 1;
+        }
+    }
+}
+
+/// Cambios de config que se pueden deshacer (U)
+pub const CONFIG_HISTORY_LEN: usize = 20;
+
+/// Un monto nuevo N veces mayor al actual pide segunda confirmación (ej: 1000 en vez de 100)
+pub const AMOUNT_JUMP_FACTOR: f64 = 5.0;
+
+/// Campos del editor por slot (E): (etiqueta, parámetro de la paleta `set <param>`)
+pub const EDIT_SLOT_FIELDS: [(&str, &str); 9] = [
+    ("Take profit %", "tp"),
+    ("Stop loss %", "sl"),
+    ("Trailing TP %", "trail"),
+    ("Break-even at %", "breakeven"),
+    ("B/E offset %", "beoffset"),
+    ("Interval (min)", "interval"),
+    ("Max orders", "maxorders"),
+    ("Drop trigger %", "drop"),
+    ("Daily limit", "daily"),
+];
+
+/// Ajustes globales del modal de settings (G): (etiqueta, clave `sección.clave` de config.toml)
+pub const SETTINGS_FIELDS: [(&str, &str); 13] = [
+    ("Max daily spend", "risk.max_daily_spend"),
+    ("Daily cap, total", "risk.max_daily_spend_total"),
+    ("Amount confirm %", "risk.amount_confirm_pct"),
+    ("Equity floor", "risk.equity_floor_usdt"),
+    ("Max price age s", "risk.max_price_age_secs"),
+    ("S/R window", "alerts.rolling_window"),
+    ("Alert cooldown m", "alerts.cooldown_minutes"),
+    ("Volume spike x", "alerts.volume_spike_factor"),
+    ("Max slots", "app.max_slots"),
+    ("Theme", "ui.theme"),
+    ("Display currency", "ui.display_currency"),
+    ("Timezone", "ui.timezone"),
+    ("Language", "ui.language"),
+];
+
+/// Campos editables del modal de nueva estrategia: (etiqueta, parámetro de `set`)
+pub const NEW_STRAT_FIELDS: [(&str, &str); 4] = [
+    ("Amount", "amount"),
+    ("Take profit %", "tp"),
+    ("Stop loss %", "sl"),
+    ("Interval (min)", "interval"),
+];
+
+/// Un cambio de config reversible
+#[derive(Debug, Clone)]
+pub struct ConfigChange {
+    pub description: String,
+    /// (slot_id, campo, valor anterior)
+    pub previous: Vec<(usize, String, serde_json::Value)>,
+    /// Valores que tenía config.toml, si el cambio lo reescribió
+    pub saved_settings: Vec<Setting>,
+}
+
+impl ConfigChange {
+    pub fn new(description: String) -> Self {
+        Self { description, previous: Vec::new(), saved_settings: Vec::new() }
+    }
+
+    /// Cambia un campo del slot recordando su valor anterior
+    pub fn set<T: serde::Serialize>(&mut self, slot: &mut StrategySlot, field: &str, value: T) {
+        if let Some(old) = slot.strategy.config_value(field) {
+            self.previous.push((slot.id, field.to_string(), old));
+        }
+        slot.strategy.set_config_field(field, value);
+    }
+}
+
+/// Inconsistencia encontrada al restaurar la sesión (se muestra en el modal de restauración)
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestoreIssue {
+    /// Trades registrados más de una vez para la misma orden (ya fusionados)
+    DuplicateTrades { symbol: String, order_ids: Vec<u64> },
+    /// Las posiciones LONG registradas superan lo que hay en la wallet (más que polvo)
+    ExceedsHoldings { asset: String, slot_ids: Vec<usize>, recorded: f64, held: f64 },
+}
+
+/// Archivo de estado ilegible al arrancar: nada se restaura ni se guarda hasta que el
+/// usuario elige cómo seguir (modal de recuperación)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateRecovery {
+    /// Error de lectura / parseo del archivo
+    pub error: String,
+    /// Copia del archivo dañado (None si no se pudo copiar)
+    pub preserved: Option<PathBuf>,
+    /// Backup rotado más reciente que se puede leer
+    pub backup: Option<StateBackup>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateBackup {
+    pub path: PathBuf,
+    pub saved_at: chrono::DateTime<chrono::Utc>,
+    pub slots: usize,
 }
 
 /// Resultado de una venta (para mostrar en el overlay post-venta)
@@ -55,6 +439,33 @@ pub struct SaleResult {
     pub pnl_pct: f64,    // ganancia/pérdida en %
 }
 
+/// Pestaña de la vista principal (Tab o 1-4)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewTab {
+    /// Slot seleccionado: precio, stats y operaciones
+    #[default]
+    Detail,
+    /// Resumen de todos los slots y equity total
+    Portfolio,
+}
+
+impl ViewTab {
+    /// Pestañas en orden; la tecla N elige la N-ésima
+    pub const ALL: [ViewTab; 2] = [ViewTab::Detail, ViewTab::Portfolio];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ViewTab::Detail => "Detail",
+            ViewTab::Portfolio => "Portfolio",
+        }
+    }
+
+    pub fn next(&self) -> ViewTab {
+        let i = Self::ALL.iter().position(|t| t == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
 /// Modo de la interfaz de usuario
 #[derive(Debug, Clone, PartialEq)]
 pub enum UiMode {
@@ -64,14 +475,38 @@ pub enum UiMode {
     /// Overlay al inicio: sesiones anteriores encontradas
     /// Vec<(symbol, direction, trade_count)>
     RestoreSession(Vec<(String, Direction, usize, bool)>),
+    /// Overlay al inicio: strategy_state.json dañado (backup, reconstruir o empezar de cero)
+    Recovery(StateRecovery),
     /// Modal para lanzar una nueva estrategia (S)
     NewStrategy,
+    /// Resumen de todos los parámetros antes de crear la estrategia
+    NewStrategySummary,
     /// Overlay post-venta: muestra resultado de un slot específico
     PostSale(usize, SaleResult),
     /// Confirmación de cierre manual de posición (V)
     ConfirmClose,
     /// Confirmación de borrado de slot (D)
     ConfirmDelete,
+    /// Paleta de comandos (:)
+    Palette,
+    /// Historial de alertas S/R (A)
+    Alerts,
+    /// Editor de TP/SL/trailing/intervalo/órdenes/caída del slot seleccionado (E)
+    EditSlot,
+    /// Límites de riesgo, alertas y UI de config.toml (G)
+    Settings,
+    /// Órdenes condicionales pendientes (O)
+    Triggers,
+    /// Presets de estrategia de presets.toml (M)
+    Presets,
+    /// Diagnóstico de la conexión de precios (I)
+    Diagnostics,
+    /// Log completo con scroll, filtros y búsqueda (L)
+    LogView,
+    /// Detalle de la entrada seleccionada en Trade History (Enter)
+    TradeDetail,
+    /// Todas las teclas de cada modo (?)
+    Help,
 }
 
 /// Mensajes que el UI puede enviar al motor de estrategia
@@ -82,18 +517,39 @@ pub enum AppCommand {
     // --- Navegación de slots ---
     SlotSelectUp,
     SlotSelectDown,
+    /// Slot N de la lista, desde 0 (click con el mouse)
+    SelectSlot(usize),
+    /// Mueve el slot seleccionado un lugar arriba / abajo en la lista (K / J)
+    MoveSlotUp,
+    MoveSlotDown,
+    /// Trade History: entrada más reciente / más antigua ([ / ])
+    TradeSelectUp,
+    TradeSelectDown,
+    /// Detalle de la entrada seleccionada (Enter)
+    OpenTradeDetail,
     ToggleStartStopSelected,
     ToggleAutoFlip,
+    ToggleReduceOnly,
 
     // --- Modal nueva estrategia (S) ---
     OpenNewStrategy,
+    /// Modal de nueva estrategia con la config del slot seleccionado (Y)
+    DuplicateSlot,
     NewStratSymbolUp,
     NewStratSymbolDown,
     NewStratToggleDirection,      // Tab: alterna LONG/SHORT
+    NewStratToggleKind,           // T: alterna DCA trading / acumulación
     NewStratToggleAutoRestart,    // ←/→: alterna manual/auto
     NewStratToggleAutoFlip,       // F: alterna auto-flip
     NewStratToggleBnb,            // B: alterna uso de BNB para fees
-    NewStratConfirm,              // Enter: crear y lanzar
+    NewStratCycleGroup,           // G: recorre los grupos configurados
+    NewStratCyclePreset,          // P: recorre los presets (y vuelve a `[dca]`)
+    NewStratNextField,            // N: siguiente campo (monto, TP, SL, intervalo)
+    NewStratInput(char),          // dígitos, '.' y '%' en el campo activo
+    NewStratBackspace,
+    NewStratReview,               // Enter: ver resumen antes de crear
+    NewStratBack,                 // Esc en el resumen: volver al modal
+    NewStratConfirm,              // Enter en el resumen: crear y lanzar
     NewStratCancel,               // Esc: cancelar
 
     // --- Post-venta por slot ---
@@ -103,6 +559,16 @@ pub enum AppCommand {
     // --- Restauración de sesión ---
     RestoreSessionContinue,
     RestoreSessionDiscard,
+    /// Continuar ajustando las posiciones a lo que hay en la wallet
+    RestoreSessionReconcile,
+
+    // --- Recuperación de un archivo de estado dañado ---
+    /// Cargar el backup rotado más reciente (+ eventos posteriores del log)
+    RecoveryLoadBackup,
+    /// Reconstruir los slots desde el event log y los fills de Binance
+    RecoveryRebuild,
+    /// Empezar de cero (el archivo dañado queda copiado aparte)
+    RecoveryStartClean,
 
     // --- Panel de configuración (solo monto) ---
     OpenConfig,
@@ -119,69 +585,396 @@ pub enum AppCommand {
     // --- Borrado de slot (D) ---
     OpenConfirmDelete,
     ConfirmDeleteNow,
+
+    // --- Deshacer cambios de config (U) ---
+    UndoConfigChange,
+
+    // --- Paleta de comandos (:) ---
+    OpenPalette,
+    PaletteInput(char),
+    PaletteBackspace,
+    PaletteUp,
+    PaletteDown,
+    PaletteConfirm,
+    PaletteCancel,
+
+    // --- Comandos parametrizados (desde la paleta) ---
+    /// Cierra un % de la posición del slot seleccionado a mercado
+    ClosePositionPct(f64),
+    /// Cambia un campo de config del slot seleccionado
+    SetSelectedParam { field: String, value: serde_json::Value },
+    /// Activa/desactiva un evento de notificación en el slot seleccionado (None = por defecto)
+    SetSlotNotify { event: NotifyEvent, enabled: Option<bool> },
+
+    /// Guarda el dashboard actual como texto plano y ANSI (P)
+    DumpScreen,
+    /// Exporta los trades del slot seleccionado y el historial de ciclos a CSV / JSON (W)
+    ExportTrades,
+
+    // --- Historial de alertas (A) ---
+    OpenAlerts,
+    AckAlerts,
+    ClearAlerts,
+
+    /// Panel de diagnóstico del WebSocket (I)
+    OpenDiagnostics,
+    /// Mueve el scroll del panel de diagnóstico `n` líneas (negativo: hacia arriba)
+    DiagnosticsScroll(isize),
+    /// Pasa al siguiente intervalo de análisis del slot seleccionado (T)
+    CycleAnalysisInterval,
+    // --- Vista de log (L) ---
+    OpenLogView,
+    /// Mueve el scroll `n` líneas hacia las más viejas (negativo: hacia las nuevas)
+    LogScroll(isize),
+    LogCycleLevel,
+    LogToggleSlot,
+    LogSearchStart,
+    LogSearchInput(char),
+    LogSearchBackspace,
+    /// Termina de escribir la búsqueda (el filtro queda aplicado)
+    LogSearchDone,
+    // --- Ayuda (?) ---
+    OpenHelp,
+    /// Mueve el scroll de la ayuda `n` líneas (negativo: hacia arriba)
+    HelpScroll(isize),
+
+    /// Siguiente pestaña de la vista principal (Tab)
+    NextTab,
+    /// Pestaña N de la vista principal, desde 0 (teclas 1-4; las que no existen se ignoran)
+    SelectTab(usize),
+
+    // --- Presets de estrategia (M) ---
+    OpenPresets,
+    /// Preset anterior / siguiente de la lista
+    PresetSelect(isize),
+    /// Campo anterior / siguiente del preset
+    PresetField(isize),
+    /// Empieza a escribir: nombre nuevo (N), renombrar (R) o valor del campo (Enter)
+    PresetStartInput(PresetInput),
+    PresetInputChar(char),
+    PresetInputBackspace,
+    PresetInputDone,
+    PresetInputCancel,
+    /// Quita el campo seleccionado: vuelve al valor de `[dca]` (X)
+    PresetRemoveField,
+    PresetDelete,
+    /// Guarda la config del slot seleccionado como preset (`preset save <nombre>`)
+    SavePreset(String),
+
+    // --- Órdenes condicionales (O) ---
+    OpenTriggers,
+    TriggerSelectUp,
+    TriggerSelectDown,
+    /// Cancela la orden condicional seleccionada en el panel
+    CancelTrigger,
+    /// Compra `amount` USDT (BUY) o vende `amount` de base (SELL) cuando el precio llegue a `price`
+    AddTrigger { side: OrderSide, symbol: String, amount: f64, price: f64 },
+
+    // --- Escaleras de órdenes LIMIT (desde la paleta) ---
+    /// Reparte `total` USDT en `count` órdenes LIMIT entre `low` y `high`
+    PlaceLadder { side: OrderSide, symbol: String, total: f64, low: f64, high: f64, count: usize, sizing: LadderSizing },
+    /// Cancela las órdenes que siguen en el libro
+    CancelLadder(u64),
+    /// Cancela lo pendiente y pasa lo ejecutado a un slot DCA nuevo, que gestiona la salida
+    AdoptLadder(u64),
+
+    /// Slot watch-only para una tenencia comprada fuera del bot: solo gestiona la salida.
+    /// `position` = (cantidad, costo promedio); None = derivarlo del historial de trades
+    WatchPosition { symbol: String, position: Option<(f64, f64)> },
+    /// Slot nuevo con entradas hechas fuera del bot (archivo JSON o `import` en la paleta)
+    ImportPosition(PositionImport),
+
+    // --- Editor de parámetros del slot (E) ---
+    OpenEditSlot,
+    EditSlotUp,
+    EditSlotDown,
+    EditSlotInput(char),
+    EditSlotBackspace,
+    EditSlotConfirm,
+
+    // --- Ajustes globales de config.toml (G) ---
+    OpenSettings,
+    SettingsUp,
+    SettingsDown,
+    SettingsInput(char),
+    SettingsBackspace,
+    SettingsConfirm,
 }
 
 /// Estado compartido entre el UI y el motor de estrategia
 pub struct AppState {
-    /// Slots de estrategia activos (hasta MAX_SLOTS)
+    /// Slots de estrategia activos (hasta `max_slots`)
     pub slots: Vec<StrategySlot>,
+    /// Máximo de estrategias simultáneas (`[app] max_slots`)
+    pub max_slots: usize,
     /// Índice del slot seleccionado en el panel izquierdo
     pub selected_slot: usize,
+    /// Fila seleccionada en Trade History (0 = la entrada más reciente)
+    pub trade_selected: usize,
     /// Datos de precio por símbolo
     pub prices: HashMap<String, MarketData>,
+    /// Símbolos que ya recibieron al menos un tick del WebSocket en esta sesión
+    pub live_symbols: HashSet<String>,
+    /// Conexión del WebSocket de precios
+    pub stream_status: StreamStatus,
+    /// Contadores de conexión del WebSocket (panel de diagnóstico)
+    pub stream_stats: ReconnectStats,
+    /// Política de reconexión (`[websocket]`), para el panel de diagnóstico
+    pub ws_policy: WebSocketConfig,
+    /// Antigüedad máxima del precio para operar un símbolo (0 = sin control)
+    pub max_price_age_secs: u64,
+    /// Símbolos operados con precio viejo: el motor no opera con ellos hasta que se actualice
+    pub stale_symbols: HashSet<String>,
     /// Niveles S/R calculados por el motor de alertas (por símbolo)
     pub alert_levels: HashMap<String, AlertLevel>,
+    /// Símbolos sin velas (por símbolo), con badge en la lista de slots
+    pub missing_klines: HashMap<String, MissingKlines>,
+    /// Velas cerradas usadas para los niveles S/R del panel de precio (`alerts.rolling_window`)
+    pub sr_window: usize,
+    /// Minutos entre dos alertas S/R del mismo tipo y símbolo (`[alerts] cooldown_minutes`)
+    pub alert_cooldown_minutes: u64,
     /// Lista de pares disponibles obtenida de Binance al arrancar
     pub symbols: Vec<String>,
-    /// Ring buffer para mensajes de log (últimos 100)
-    pub log: VecDeque<String>,
+    /// Ring buffer para mensajes de log (últimos `LOG_CAPACITY`)
+    pub log: VecDeque<LogEntry>,
+    pub log_view: LogView,
+    /// Primera línea visible de la ayuda (?)
+    pub help_scroll: usize,
+    /// Primera línea visible del panel de diagnóstico (I)
+    pub diagnostics_scroll: usize,
+    /// Colores del dashboard ([ui] theme)
+    pub theme: Theme,
     pub should_quit: bool,
     pub ui_mode: UiMode,
+    pub view_tab: ViewTab,
 
     // --- Modal nueva estrategia ---
     pub new_strat_symbol_idx: usize,
     pub new_strat_direction: Direction,
+    pub new_strat_kind: StrategyKind,
     pub new_strat_auto_restart: bool,
     pub new_strat_auto_flip: bool,
     pub new_strat_has_bnb: bool,
+    pub new_strat_group: Option<String>,
+    /// Slot duplicado o preset del que parte el modal (None = `[dca]`)
+    pub new_strat_template: Option<StrategyTemplate>,
+    /// Índice del preset elegido con P (None = ninguno)
+    pub new_strat_preset: Option<usize>,
+    /// Un buffer por campo de NEW_STRAT_FIELDS (vacío = valor de `[dca]`)
+    pub new_strat_bufs: Vec<String>,
+    pub new_strat_field: usize,
+    /// Error de validación del campo `new_strat_field` (se muestra en el modal)
+    pub new_strat_error: Option<String>,
+    /// Config completa mostrada en el resumen previo a crear
+    pub new_strat_preview: Option<DcaConfig>,
+
+    /// Grupos de slots configurados (presupuesto compartido)
+    pub groups: Vec<GroupConfig>,
+
+    /// `[[slots]]` de config.toml: se crean al empezar una sesión nueva
+    pub config_slots: Vec<SlotSection>,
 
     // --- Panel de configuración ---
     pub cfg_amount_buf: String,
     pub cfg_has_bnb: bool,
+    /// Aviso de monto sospechoso: el siguiente Enter confirma igual
+    pub cfg_amount_warning: Option<String>,
+    /// % del balance libre a partir del cual se pide segunda confirmación (0 = off)
+    pub amount_confirm_pct: f64,
+
+    // --- Paleta de comandos ---
+    pub palette_input: String,
+    pub palette_selected: usize,
+
+    // --- Editor del slot (un buffer por campo de EDIT_SLOT_FIELDS) ---
+    pub edit_slot_bufs: Vec<String>,
+    /// Valores al abrir el editor: solo se aplican los campos modificados
+    pub edit_slot_initial: Vec<String>,
+    pub edit_slot_field: usize,
+    pub edit_slot_error: Option<String>,
+
+    // --- Settings (un buffer por campo de SETTINGS_FIELDS; vacío = valor por defecto) ---
+    pub settings_bufs: Vec<String>,
+    pub settings_initial: Vec<String>,
+    pub settings_field: usize,
+    pub settings_error: Option<String>,
 
     /// Próximo ID de slot (auto-incremental)
     pub next_slot_id: usize,
+
+    /// Event log append-only de todos los slots
+    pub event_log: EventLog,
+    /// Ciclos completados de todos los slots (también de los borrados), sin poda
+    pub cycles: CycleHistory,
+    /// Guarda strategy_state.json en segundo plano (el motor no espera al disco)
+    pub state_writer: Arc<StateWriter>,
+    /// Curva de equity de la cuenta (equity_history.jsonl)
+    pub equity_history: Arc<EquityHistory>,
+    /// Contadores de la sesión y de todas las anteriores (stats.json, panel I)
+    pub stats: SessionStats,
+
+    /// Comisiones de la cuenta (se actualizan con el balance)
+    pub commission: CommissionRates,
+    /// Comisiones configuradas en `[fees]`, con prioridad sobre las de la cuenta
+    pub fees: FeesConfig,
+
+    /// Historial de cambios de config para deshacer (el último al final)
+    pub config_history: VecDeque<ConfigChange>,
+
+    /// Contador de cambios: el UI solo redibuja cuando cambia
+    pub revision: u64,
+    /// El próximo frame dibujado se guarda a archivo (P / `snapshot`)
+    pub dump_screen_requested: bool,
+
+    /// Historial de alertas S/R (la más reciente al final)
+    pub alerts: VecDeque<AlertRecord>,
+    /// Alertas que se conservan en el historial ([retention] alert_history)
+    pub alert_history_len: usize,
+
+    /// Órdenes condicionales (fuera de las estrategias) y la seleccionada en su panel
+    pub triggers: TriggerBook,
+    pub trigger_selected: usize,
+    /// Escaleras de órdenes LIMIT colocadas desde la paleta
+    pub ladders: LadderBook,
+    /// Presets de estrategia (presets.toml) y su pantalla
+    pub presets: PresetBook,
+    pub preset_view: PresetView,
+
+    /// Beeps y horario silencioso
+    pub notifications: NotificationsConfig,
+
+    /// Inconsistencias de la sesión restaurada (vacío si no hay)
+    pub restore_issues: Vec<RestoreIssue>,
+
+    /// Velas en memoria de los símbolos activos (streams @kline)
+    pub candles: CandleStore,
+    /// Volumen comprador/vendedor por minuto (@aggTrade) y detección de picos
+    pub volume: VolumeTracker,
+    /// Precios en vivo desde el arranque, para el sparkline del panel de precio
+    pub price_history: PriceHistory,
+
+    /// Balances valuados en `display_currency` (equity, portfolio y resumen diario;
+    /// None hasta el primer cálculo)
+    pub valuation: Option<Valuation>,
+    pub display_currency: String,
+    /// Precio en USDT de la quote de cada slot, para el límite de gasto diario (se
+    /// actualiza junto con la valuación)
+    pub quote_rates: HashMap<String, f64>,
+    /// Día UTC y equity del último resumen diario (el primero de la sesión no se anuncia)
+    pub daily_summary: Option<(chrono::NaiveDate, f64)>,
+    /// Balances de la cuenta (libre + bloqueado) por asset, actualizados con el balance
+    pub holdings: HashMap<String, f64>,
+    /// `[risk] max_daily_spend`: límite diario (USDT) de los slots sin uno propio
+    pub max_daily_spend: f64,
+    /// `[risk] max_daily_spend_total`: tope diario de todos los slots juntos (USDT, 0 = sin tope)
+    pub max_daily_spend_total: f64,
+    /// Exposición máxima por asset (`[risk.max_exposure]`)
+    pub max_exposure: std::collections::BTreeMap<String, ExposureLimit>,
+    /// Stop de portafolio: equity mínima (0 = desactivado) y qué hacer al perforarla
+    pub equity_floor_usdt: f64,
+    pub equity_floor_action: EquityFloorAction,
+    /// Equity bajo el piso: ya se actuó y se avisó (se rearma al recuperarse)
+    pub equity_floor_tripped: bool,
+
+    /// Órdenes LIMIT / LIMIT_MAKER de entrada pendientes, una por slot
+    pub orders: OrderManager,
 }
 
 impl AppState {
+    /// Marca el estado como modificado para que el UI redibuje
+    pub fn touch(&mut self) {
+        self.revision = self.revision.wrapping_add(1);
+    }
+
     pub fn log(&mut self, msg: &str) {
-        let ts = chrono::Utc::now().format("%H:%M:%S");
-        let entry = format!("[{}] {}", ts, msg);
         tracing::info!("{}", msg);
-        if self.log.len() >= 100 {
-            self.log.pop_front();
-        }
-        self.log.push_back(entry);
+        self.push_log(LogLevel::Info, None, msg);
     }
 
     pub fn log_alert(&mut self, msg: &str) {
-        let ts = chrono::Utc::now().format("%H:%M:%S");
-        let entry = format!("[{}] ALERT {}", ts, msg);
         tracing::warn!("ALERT: {}", msg);
-        if self.log.len() >= 100 {
+        self.push_log(LogLevel::Alert, None, msg);
+    }
+
+    /// Línea sobre un símbolo: el filtro "solo el slot seleccionado" de la vista L la muestra
+    /// con el slot de ese símbolo
+    pub fn log_for(&mut self, symbol: &str, msg: &str) {
+        tracing::info!("{}", msg);
+        self.push_log(LogLevel::Info, Some(symbol), msg);
+    }
+
+    pub fn log_alert_for(&mut self, symbol: &str, msg: &str) {
+        tracing::warn!("ALERT: {}", msg);
+        self.push_log(LogLevel::Alert, Some(symbol), msg);
+    }
+
+    pub fn log_error_for(&mut self, symbol: &str, msg: &str) {
+        tracing::error!("{}", msg);
+        self.push_log(LogLevel::Error, Some(symbol), msg);
+    }
+
+    /// Línea sobre el slot seleccionado (toggles y ediciones de la UI)
+    pub fn log_selected(&mut self, msg: &str) {
+        let symbol = self.selected().map(|s| s.symbol.clone());
+        tracing::info!("{}", msg);
+        self.push_log(LogLevel::Info, symbol.as_deref(), msg);
+    }
+
+    /// Agrega una línea al ring buffer, etiquetada con el símbolo del que habla (None =
+    /// general)
+    fn push_log(&mut self, level: LogLevel, symbol: Option<&str>, msg: &str) {
+        self.touch();
+        let symbol = symbol.map(str::to_string);
+        let entry = LogEntry { at: chrono::Utc::now(), level, symbol, message: msg.to_string() };
+        // Con scroll, la vista L queda fija en las mismas líneas mientras llegan nuevas
+        if self.log_view.scroll > 0 && self.log_view.matches(&entry, self.selected().map(|s| s.symbol.as_str())) {
+            self.log_view.scroll += 1;
+        }
+        if self.log.len() >= LOG_CAPACITY {
             self.log.pop_front();
         }
         self.log.push_back(entry);
     }
 
+    /// Registra una alerta (S/R o volumen): va al log y al historial como no reconocida
+    pub fn push_alert(&mut self, symbol: &str, kind: &'static str, level: f64, price: f64, msg: &str) {
+        self.log_alert_for(symbol, msg);
+        self.stats.alerts_fired += 1;
+        while self.alerts.len() >= self.alert_history_len {
+            self.alerts.pop_front();
+        }
+        self.alerts.push_back(AlertRecord {
+            at: chrono::Utc::now(),
+            symbol: symbol.to_string(),
+            kind,
+            level,
+            price,
+            acknowledged: false,
+        });
+    }
+
+    /// Estado del feed de precios para el header: conectado pero con algún símbolo
+    /// operado sin precio reciente se muestra como viejo
+    pub fn feed_status(&self) -> StreamStatus {
+        match self.stream_status {
+            StreamStatus::Connected if !self.stale_symbols.is_empty() => StreamStatus::Stale,
+            status => status,
+        }
+    }
+
+    /// Comisiones que se aplican a `symbol`: `[fees]` si está configurado, si no las de la cuenta
+    pub fn fee_rates(&self, symbol: &str) -> CommissionRates {
+        self.commission.for_symbol(&self.fees, symbol)
+    }
+
+    /// Alertas que todavía no se reconocieron (contador del header)
+    pub fn unacked_alerts(&self) -> usize {
+        self.alerts.iter().filter(|a| !a.acknowledged).count()
+    }
+
     pub fn log_error(&mut self, msg: &str) {
-        let ts = chrono::Utc::now().format("%H:%M:%S");
-        let entry = format!("[{}] ⚠ {}", ts, msg);
         tracing::error!("{}", msg);
-        if self.log.len() >= 100 {
-            self.log.pop_front();
-        }
-        self.log.push_back(entry);
+        self.push_log(LogLevel::Error, None, msg);
     }
 
     /// Precio actual del slot seleccionado
@@ -225,7 +1018,11 @@ impl AppState {
     /// Elimina un slot por ID
     pub fn remove_slot(&mut self, id: usize) {
         if let Some(pos) = self.slots.iter().position(|s| s.id == id) {
-            self.slots.remove(pos);
+            let mut slot = self.slots.remove(pos);
+            for event in slot.strategy.drain_events() {
+                self.event_log.push(id, &slot.symbol, event);
+            }
+            self.event_log.push(id, &slot.symbol, SlotEventKind::Removed);
             if self.selected_slot >= self.slots.len() && !self.slots.is_empty() {
                 self.selected_slot = self.slots.len() - 1;
             }
@@ -239,9 +1036,229 @@ impl AppState {
         id
     }
 
+    /// Guarda un cambio de config en el historial de deshacer
+    pub fn push_config_change(&mut self, change: ConfigChange) {
+        if change.previous.is_empty() {
+            return;
+        }
+        if self.config_history.len() >= CONFIG_HISTORY_LEN {
+            self.config_history.pop_front();
+        }
+        self.config_history.push_back(change);
+    }
+
+    /// Config del grupo de un slot (si pertenece a uno que existe en config.toml)
+    pub fn group_of(&self, slot: &StrategySlot) -> Option<&GroupConfig> {
+        let name = slot.strategy.config.group.as_ref()?;
+        self.groups.iter().find(|g| &g.name == name)
+    }
+
+    /// Primer slot donde el monto queda bajo el minNotional del símbolo (el monto aplica a todos).
+    /// Un porcentaje se toma del balance que financia la entrada, como en `entry_amount`: la
+    /// quote libre en LONG, la base valuada al precio actual en SHORT (sin precio no se controla)
+    pub fn min_notional_violation(&self, amount: QuoteAmount) -> Option<String> {
+        self.slots.iter().find_map(|slot| {
+            let min = slot.filters.as_ref()?.min_notional;
+            let funding = match slot.strategy.config.direction {
+                Direction::Long => slot.quote_balance,
+                Direction::Short if !amount.is_percent() => 0.0,
+                Direction::Short => {
+                    let price = self.prices.get(&slot.symbol).map(|m| m.price).filter(|p| *p > 0.0)?;
+                    slot.base_balance * price
+                }
+            };
+            let value = amount.resolve(funding);
+            (value < min).then(|| {
+                format!("${:.2} is below the {} minimum order of ${:.2}", value, slot.symbol, min)
+            })
+        })
+    }
+
+    /// Verifica un monto del panel de config contra el balance libre y el monto actual.
+    /// Devuelve el motivo si hace falta una segunda confirmación.
+    pub fn amount_sanity_warning(&self, amount: QuoteAmount) -> Option<String> {
+        let slot = self.selected()?;
+        let free = slot.quote_balance;
+        let value = amount.resolve(free);
+        let current = slot.strategy.config.quote_amount.resolve(free);
+
+        if free > 0.0 && value > free {
+            return Some(format!("${:.2} exceeds the free balance (${:.2} {})", value, free, slot.quote_asset));
+        }
+        if self.amount_confirm_pct > 0.0 && free > 0.0 && value > free * self.amount_confirm_pct / 100.0 {
+            return Some(format!("${:.2} is {:.0}% of the free balance", value, value / free * 100.0));
+        }
+        if current > 0.0 && value >= current * AMOUNT_JUMP_FACTOR {
+            return Some(format!("${:.2} is {:.0}x the current amount", value, value / current));
+        }
+        None
+    }
+
+    /// Queda lugar para otro slot (`[app] max_slots`)
+    pub fn can_add_slot(&self) -> bool {
+        self.slots.len() < self.max_slots
+    }
+
+    /// Precio en USDT de una unidad de `quote`. Sin cotización todavía las stablecoins en
+    /// dólares van 1:1; el resto es None hasta la primera valuación
+    pub fn quote_rate(&self, quote: &str) -> Option<f64> {
+        if quote == "USDT" {
+            return Some(1.0);
+        }
+        match self.quote_rates.get(quote) {
+            Some(rate) if *rate > 0.0 => Some(*rate),
+            _ => is_usd_quote(quote).then_some(1.0),
+        }
+    }
+
+    /// Ciclos completados de todos los slots (también los borrados) y su P&L en USDT; los de
+    /// una quote sin cotización cuentan pero no suman
+    pub fn realized_cycles_usdt(&self) -> (usize, f64) {
+        let records = self.cycles.records();
+        let total = records
+            .iter()
+            .filter_map(|c| {
+                let quote = match self.slots.iter().find(|s| s.id == c.slot_id && s.symbol == c.symbol) {
+                    Some(slot) => slot.quote_asset.clone(),
+                    None => parse_symbol(&c.symbol).1,
+                };
+                self.quote_rate(&quote).map(|rate| c.pnl * rate)
+            })
+            .sum();
+        (records.len(), total)
+    }
+
+    /// Un monto en USDT expresado en `quote`; 0 (sin entradas) mientras no se conozca su
+    /// cotización
+    pub fn daily_limit_in(&self, quote: &str, max_daily_usdt: f64) -> f64 {
+        self.quote_rate(quote).map_or(0.0, |rate| max_daily_usdt / rate)
+    }
+
+    /// Gastado hoy por todos los slots, en USDT (None si falta la cotización de alguna quote)
+    pub fn daily_spent_usdt(&self) -> Option<f64> {
+        self.slots
+            .iter()
+            .filter(|sl| sl.strategy.daily_spent > 0.0)
+            .map(|sl| self.quote_rate(&sl.quote_asset).map(|rate| sl.strategy.daily_spent * rate))
+            .sum()
+    }
+
+    /// Límite diario de entradas del slot en su quote: el propio (`max_daily_spend` del
+    /// slot) o el de `[risk]`, recortado a lo que le deja el tope global de todos los slots
+    pub fn daily_limit(&self, slot: &StrategySlot) -> f64 {
+        let own = match slot.strategy.config.max_daily_spend {
+            limit if limit > 0.0 => limit,
+            _ => self.daily_limit_in(&slot.quote_asset, self.max_daily_spend),
+        };
+        if self.max_daily_spend_total <= 0.0 {
+            return own;
+        }
+        match (self.quote_rate(&slot.quote_asset), self.daily_spent_usdt()) {
+            (Some(rate), Some(spent)) => {
+                let left = (self.max_daily_spend_total - spent).max(0.0) / rate;
+                own.min(slot.strategy.daily_spent + left)
+            }
+            _ => 0.0,
+        }
+    }
+
     pub fn selected_slot_is_active(&self) -> bool {
         self.selected()
             .map(|s| s.strategy.state.is_active())
             .unwrap_or(false)
     }
+
+    /// Hay posiciones restauradas mayores que la wallet: el modal ofrece ajustarlas (R)
+    pub fn can_reconcile_restore(&self) -> bool {
+        self.restore_issues.iter().any(|i| matches!(i, RestoreIssue::ExceedsHoldings { .. }))
+    }
+}
+
+/// Stablecoins en dólares: sus montos se muestran como "$12.34"
+const USD_QUOTES: &[&str] = &["USDT", "USDC", "FDUSD", "BUSD", "TUSD", "DAI", "USDP"];
+
+pub fn is_usd_quote(quote: &str) -> bool {
+    USD_QUOTES.contains(&quote)
+}
+
+/// Zona horaria y decimales de `[ui]`. Global porque los usan las funciones `fmt_*` y las
+/// entradas del log; se actualiza al recargar config.toml
+struct DisplayFormat {
+    timezone: DisplayTimeZone,
+    decimals: BTreeMap<String, usize>,
+}
+
+static DISPLAY: RwLock<DisplayFormat> =
+    RwLock::new(DisplayFormat { timezone: DisplayTimeZone::Local, decimals: BTreeMap::new() });
+
+/// Aplica `[ui] timezone` y `[ui] decimals` a todo lo que se muestra
+pub fn set_display_format(ui: &UiConfig) {
+    if let Ok(mut display) = DISPLAY.write() {
+        display.timezone = ui.timezone;
+        display.decimals = ui.decimals.iter().map(|(asset, d)| (asset.to_uppercase(), *d)).collect();
+    }
+}
+
+/// Fecha / hora en la zona de `[ui] timezone`
+pub fn fmt_time(at: chrono::DateTime<chrono::Utc>, fmt: &str) -> String {
+    let timezone = DISPLAY.read().map(|d| d.timezone).unwrap_or_default();
+    timezone.format(at, fmt)
+}
+
+/// Decimales de los montos en `asset`: los de `[ui] decimals`, o 2 en stablecoins en
+/// dólares y 8 en el resto
+fn decimals(asset: &str) -> usize {
+    let configured = DISPLAY.read().ok().and_then(|d| d.decimals.get(asset).copied());
+    configured.unwrap_or(if is_usd_quote(asset) { 2 } else { 8 })
+}
+
+/// Monto en `quote`: "$12.34" en stablecoins en dólares, "0.00123400 BTC" en el resto
+pub fn fmt_quote(value: f64, quote: &str) -> String {
+    if is_usd_quote(quote) {
+        format!("${:.*}", decimals(quote), value)
+    } else {
+        format!("{:.*} {}", decimals(quote), value, quote)
+    }
+}
+
+/// Como `fmt_quote` pero nombrando también la stablecoin: "$12.34 USDC"
+pub fn fmt_quote_named(value: f64, quote: &str) -> String {
+    if is_usd_quote(quote) {
+        format!("${:.*} {}", decimals(quote), value, quote)
+    } else {
+        fmt_quote(value, quote)
+    }
+}
+
+/// Ganancia / pérdida en `quote` con signo: "+1.23$" o "-0.00001200 BTC"
+pub fn fmt_pnl(value: f64, quote: &str) -> String {
+    if is_usd_quote(quote) {
+        format!("{:+.*}$", decimals(quote), value)
+    } else {
+        format!("{:+.*} {}", decimals(quote), value, quote)
+    }
+}
+
+/// Precio en `quote`: con `usd_decimals` y "$" en stablecoins, 8 decimales en el resto
+/// (ETHBTC cotiza en 0.05)
+pub fn fmt_price(price: f64, quote: &str, usd_decimals: usize) -> String {
+    if is_usd_quote(quote) {
+        format!("${:.*}", usd_decimals, price)
+    } else {
+        format!("{:.8}", price)
+    }
+}
+
+/// Extrae base y quote asset de un símbolo de Binance
+/// Ej: "BTCUSDT" → ("BTC", "USDT")
+pub fn parse_symbol(symbol: &str) -> (String, String) {
+    const QUOTE_ASSETS: &[&str] = &["FDUSD", "USDT", "BUSD", "USDC", "TUSD", "BTC", "ETH", "BNB", "DAI", "EUR", "TRY"];
+    for qa in QUOTE_ASSETS {
+        if symbol.ends_with(qa) && symbol.len() > qa.len() {
+            let base = &symbol[..symbol.len() - qa.len()];
+            return (base.to_string(), qa.to_string());
+        }
+    }
+    let mid = symbol.len() / 2;
+    (symbol[..mid].to_string(), symbol[mid..].to_string())
 }
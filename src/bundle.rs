@@ -0,0 +1,165 @@
+use std::io::{Read, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Bumped when the bundle layout changes in an incompatible way
+const BUNDLE_FORMAT: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const CONFIG: &str = "config.toml";
+const STATE: &str = "strategy_state.json";
+const EVENTS: &str = "slot_events.jsonl";
+const CYCLES: &str = "cycle_history.jsonl";
+const EQUITY: &str = "equity_history.jsonl";
+
+/// Bot files that make up a bundle
+pub struct BotFiles<'a> {
+    pub config: &'a Path,
+    pub state: &'a Path,
+    /// Append-only event log: every entry, exit and config change (the trade ledger)
+    pub events: &'a Path,
+    /// Completed cycles, kept after the event log is pruned
+    pub cycles: &'a Path,
+    /// Account equity samples
+    pub equity: &'a Path,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    format: u32,
+    app_version: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+    files: Vec<String>,
+}
+
+/// Writes config (without API keys), strategy state, the event log and the cycle and equity
+/// histories into one zip
+pub fn export(files: &BotFiles, out: &Path) -> Result<()> {
+    let config = std::fs::read_to_string(files.config)
+        .with_context(|| format!("Could not read {:?}", files.config))?;
+
+    let mut entries: Vec<(&str, Vec<u8>)> = vec![(CONFIG, redact_config(&config)?.into_bytes())];
+    for (name, path) in [
+        (STATE, files.state),
+        (EVENTS, files.events),
+        (CYCLES, files.cycles),
+        (EQUITY, files.equity),
+    ] {
+        if path.exists() {
+            let data = std::fs::read(path).with_context(|| format!("Could not read {:?}", path))?;
+            entries.push((name, data));
+        }
+    }
+
+    let manifest = Manifest {
+        format: BUNDLE_FORMAT,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now(),
+        files: entries.iter().map(|(name, _)| name.to_string()).collect(),
+    };
+
+    let file = std::fs::File::create(out).with_context(|| format!("Could not create {:?}", out))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+    zip.start_file(MANIFEST, options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+    for (name, data) in entries {
+        zip.start_file(name, options)?;
+        zip.write_all(&data)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Restores a bundle over the local files. Existing files are kept as `*.bak` and the
+/// local API keys are carried over into the imported config.
+pub fn import(bundle: &Path, files: &BotFiles) -> Result<()> {
+    let file = std::fs::File::open(bundle).with_context(|| format!("Could not open {:?}", bundle))?;
+    let mut zip = zip::ZipArchive::new(file).context("Not a valid bundle (zip)")?;
+
+    let manifest: Manifest = serde_json::from_str(&read_entry(&mut zip, MANIFEST)?)
+        .context("Invalid bundle manifest")?;
+    if manifest.format != BUNDLE_FORMAT {
+        anyhow::bail!(
+            "Unsupported bundle format {} (this version reads format {})",
+            manifest.format,
+            BUNDLE_FORMAT
+        );
+    }
+
+    let mut config = read_entry(&mut zip, CONFIG)?;
+    if let Ok(local) = std::fs::read_to_string(files.config) {
+        config = keep_local_keys(&config, &local)?;
+    }
+    replace_file(files.config, config.as_bytes())?;
+
+    for (name, path) in [
+        (STATE, files.state),
+        (EVENTS, files.events),
+        (CYCLES, files.cycles),
+        (EQUITY, files.equity),
+    ] {
+        if manifest.files.iter().any(|f| f == name) {
+            replace_file(path, read_entry(&mut zip, name)?.as_bytes())?;
+        } else if path.exists() {
+            // The bundle had no state/events: start clean instead of mixing sessions
+            backup(path)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_entry(zip: &mut zip::ZipArchive<std::fs::File>, name: &str) -> Result<String> {
+    let mut entry = zip
+        .by_name(name)
+        .with_context(|| format!("Bundle is missing {}", name))?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// Blanks the Binance API key and secret, preserving comments and layout
+pub fn redact_config(content: &str) -> Result<String> {
+    let mut doc = content
+        .parse::<toml_edit::DocumentMut>()
+        .context("Error parsing config.toml")?;
+    if let Some(binance) = doc.get_mut("binance").and_then(|b| b.as_table_like_mut()) {
+        for key in ["api_key", "api_secret"] {
+            if let Some(item) = binance.get_mut(key) {
+                *item = toml_edit::value("");
+            }
+        }
+    }
+    Ok(doc.to_string())
+}
+
+/// Copies the API key and secret of the local config into the imported one
+fn keep_local_keys(imported: &str, local: &str) -> Result<String> {
+    let local = local
+        .parse::<toml_edit::DocumentMut>()
+        .context("Error parsing local config.toml")?;
+    let mut doc = imported
+        .parse::<toml_edit::DocumentMut>()
+        .context("Error parsing bundled config.toml")?;
+    for key in ["api_key", "api_secret"] {
+        if let Some(value) = local.get("binance").and_then(|b| b.get(key)).and_then(|v| v.as_str()) {
+            doc["binance"][key] = toml_edit::value(value);
+        }
+    }
+    Ok(doc.to_string())
+}
+
+fn backup(path: &Path) -> Result<()> {
+    let mut bak = path.as_os_str().to_owned();
+    bak.push(".bak");
+    std::fs::rename(path, &bak).with_context(|| format!("Could not back up {:?}", path))
+}
+
+fn replace_file(path: &Path, data: &[u8]) -> Result<()> {
+    if path.exists() {
+        backup(path)?;
+    }
+    std::fs::write(path, data).with_context(|| format!("Could not write {:?}", path))
+}
@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+/// Automated DCA trading bot for Binance Spot with a terminal interface.
+///
+/// The API credentials can come from BINANCE_API_KEY / BINANCE_API_SECRET instead of
+/// config.toml.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// config.toml to use (default: working directory, then next to the executable)
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Trade on the Binance testnet, whatever binance.testnet says
+    #[arg(long)]
+    pub testnet: bool,
+
+    /// Named configuration: loads config.<NAME>.toml and keeps its own state files
+    /// (strategy_state.<NAME>.json, slot_events.<NAME>.jsonl, ...)
+    #[arg(long, value_name = "NAME", value_parser = profile_name)]
+    pub profile: Option<String>,
+
+    /// Export config, state and events to a bundle (API keys removed)
+    #[arg(long, value_name = "BUNDLE.ZIP", group = "command")]
+    pub export: Option<PathBuf>,
+
+    /// Import a bundle (previous files kept as *.bak)
+    #[arg(long, value_name = "BUNDLE.ZIP", group = "command")]
+    pub import: Option<PathBuf>,
+
+    /// Export every slot's trades and the cycle history to CSV and JSON (default: next to
+    /// the executable)
+    #[arg(long, value_name = "DIR", num_args = 0..=1, group = "command")]
+    pub export_trades: Option<Option<PathBuf>>,
+
+    /// Write a diagnostics zip with keys and balances redacted
+    #[arg(long, value_name = "OUT.ZIP", num_args = 0..=1, group = "command")]
+    pub diagnostics: Option<Option<PathBuf>>,
+
+    /// Development tool: run a WebSocket capture through the parser
+    #[arg(long, value_name = "FILE", num_args = 0..=1, group = "command")]
+    pub replay_ws: Option<Option<PathBuf>>,
+
+    /// Check config, API key, endpoints and state before trading
+    #[arg(long, group = "command")]
+    pub selftest: bool,
+}
+
+/// Profile names end up in file names: letters, digits, `-` and `_` only
+fn profile_name(name: &str) -> Result<String, String> {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        Ok(name.to_string())
+    } else {
+        Err("use only letters, digits, '-' and '_'".to_string())
+    }
+}
+
+/// One-shot command run instead of the TUI
+pub enum Command {
+    Export(PathBuf),
+    Import(PathBuf),
+    ExportTrades(Option<PathBuf>),
+    Diagnostics(Option<PathBuf>),
+    ReplayWs(Option<PathBuf>),
+    SelfTest,
+}
+
+impl Cli {
+    /// The one-shot command requested, if any (clap allows at most one)
+    pub fn command(&self) -> Option<Command> {
+        if let Some(out) = &self.export {
+            Some(Command::Export(out.clone()))
+        } else if let Some(file) = &self.import {
+            Some(Command::Import(file.clone()))
+        } else if let Some(dir) = &self.export_trades {
+            Some(Command::ExportTrades(dir.clone()))
+        } else if let Some(out) = &self.diagnostics {
+            Some(Command::Diagnostics(out.clone()))
+        } else if let Some(file) = &self.replay_ws {
+            Some(Command::ReplayWs(file.clone()))
+        } else if self.selftest {
+            Some(Command::SelfTest)
+        } else {
+            None
+        }
+    }
+}
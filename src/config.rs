@@ -1,11 +1,16 @@
+use std::collections::BTreeMap;
+
 use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Utc};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 /// DCA strategy direction
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Direction {
     /// LONG: buy and sell when it goes up (original behavior)
+    #[default]
     Long,
     /// SHORT: sell base asset and rebuy when it goes down
     Short,
@@ -20,19 +25,629 @@ impl Direction {
     }
 }
 
-impl Default for Direction {
-    fn default() -> Self {
-        Direction::Long
+/// What a slot does with its entries
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StrategyKind {
+    /// DCA cycles closed by TP / trailing TP / SL (original behavior)
+    #[default]
+    Trading,
+    /// Recurring buy of `quote_amount` on `buy_schedule`, never sold: no TP/SL and the
+    /// position keeps growing (classic DCA investing)
+    Accumulate,
+    /// Gradual sale of a held inventory (`distribute_qty`): `quote_amount` per sale on
+    /// `buy_schedule`, or one sale per price in `sell_levels`
+    Distribute,
+    /// Exit-only shadow of a position bought outside the bot: TP / SL / trailing on the
+    /// imported holding, never an entry. Created with the `watch` palette command
+    Watch,
+}
+
+impl StrategyKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StrategyKind::Trading => "DCA Trading",
+            StrategyKind::Accumulate => "Accumulate",
+            StrategyKind::Distribute => "Distribute",
+            StrategyKind::Watch => "Watch-only",
+        }
+    }
+}
+
+/// Order type used for DCA entries
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryOrderType {
+    /// Market order at the current price (original behavior)
+    #[default]
+    Market,
+    /// LIMIT order placed `limit_offset_pct` away from the current price
+    Limit,
+    /// Post-only LIMIT_MAKER order `limit_offset_pct` behind the best bid (LONG) / ask (SHORT).
+    /// Always pays the maker commission; repositioned if it would cross the book
+    Maker,
+}
+
+/// Amount per entry: fixed in quote currency (`10`) or a percentage of the free balance (`"2%"`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuoteAmount {
+    Fixed(f64),
+    Percent(f64),
+}
+
+impl QuoteAmount {
+    /// Absolute amount in quote currency for an entry funded by `free_balance`
+    pub fn resolve(&self, free_balance: f64) -> f64 {
+        match self {
+            QuoteAmount::Fixed(v) => *v,
+            QuoteAmount::Percent(p) => free_balance.max(0.0) * p / 100.0,
+        }
+    }
+
+    pub fn is_percent(&self) -> bool {
+        matches!(self, QuoteAmount::Percent(_))
+    }
+
+    /// Fixed amounts must be positive; percentages in (0, 100]
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            QuoteAmount::Fixed(v) if *v <= 0.0 => anyhow::bail!("must be greater than 0"),
+            QuoteAmount::Percent(p) if *p <= 0.0 || *p > 100.0 => {
+                anyhow::bail!("percentage must be between 0 and 100")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Value as written in config.toml
+    pub fn to_toml(self) -> toml_edit::Value {
+        match self {
+            QuoteAmount::Fixed(v) => v.into(),
+            QuoteAmount::Percent(_) => self.to_string().into(),
+        }
+    }
+}
+
+impl std::fmt::Display for QuoteAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuoteAmount::Fixed(v) => write!(f, "{}", v),
+            QuoteAmount::Percent(p) => write!(f, "{}%", p),
+        }
+    }
+}
+
+impl std::str::FromStr for QuoteAmount {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        match s.strip_suffix('%') {
+            Some(p) => Ok(QuoteAmount::Percent(p.trim().parse().context("invalid percentage")?)),
+            None => Ok(QuoteAmount::Fixed(s.parse().context("invalid amount")?)),
+        }
+    }
+}
+
+impl Serialize for QuoteAmount {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            QuoteAmount::Fixed(v) => serializer.serialize_f64(*v),
+            QuoteAmount::Percent(_) => serializer.serialize_str(&self.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for QuoteAmount {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Number(f64),
+            Text(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Number(v) => Ok(QuoteAmount::Fixed(v)),
+            Raw::Text(s) => s.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Take profit: a single % from the average entry (`1.5`, 0 = off) or a ladder of
+/// `[pct, fraction]` pairs closing part of the position at each level (`[[1.0, 0.5], [2.0, 1.0]]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TakeProfit {
+    Single(f64),
+    Ladder(Vec<TakeProfitStep>),
+}
+
+/// One take profit level: close `fraction` of the original position at +`pct`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TakeProfitStep {
+    pub pct: f64,
+    pub fraction: f64,
+}
+
+impl TakeProfit {
+    /// Levels in order; a single TP is one level closing the whole position
+    pub fn steps(&self) -> Vec<TakeProfitStep> {
+        match self {
+            TakeProfit::Single(p) if *p > 0.0 => vec![TakeProfitStep { pct: *p, fraction: 1.0 }],
+            TakeProfit::Single(_) => Vec::new(),
+            TakeProfit::Ladder(steps) => steps.clone(),
+        }
+    }
+
+    /// First (lowest) level in %, 0 if TP is off
+    pub fn first_pct(&self) -> f64 {
+        self.steps().first().map(|s| s.pct).unwrap_or(0.0)
+    }
+
+    /// Levels must be increasing and fractions in (0, 1]; the last level always closes the rest
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            TakeProfit::Single(p) if *p < 0.0 => anyhow::bail!("must be 0 or greater"),
+            TakeProfit::Single(_) => Ok(()),
+            TakeProfit::Ladder(steps) => {
+                let mut prev = 0.0;
+                for (i, step) in steps.iter().enumerate() {
+                    if step.pct <= prev {
+                        anyhow::bail!("level {} must be greater than the previous one", i);
+                    }
+                    if step.fraction <= 0.0 || step.fraction > 1.0 {
+                        anyhow::bail!("level {} fraction must be between 0 and 1", i);
+                    }
+                    prev = step.pct;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for TakeProfit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TakeProfit::Single(p) => write!(f, "{:.2}%", p),
+            TakeProfit::Ladder(steps) => {
+                let last = steps.len().saturating_sub(1);
+                for (i, step) in steps.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    if i == last {
+                        write!(f, "{:.2}% (rest)", step.pct)?;
+                    } else {
+                        write!(f, "{:.2}% ({:.0}%)", step.pct, step.fraction * 100.0)?;
+                    }
+                }
+                Ok(())
+            }
+        }
     }
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
+    #[serde(default)]
+    pub app: AppConfig,
     pub binance: BinanceConfig,
     pub dca: DcaConfig,
     pub risk: RiskConfig,
     #[serde(default)]
     pub alerts: AlertsConfig,
+    /// Slot groups with a shared budget (`[[groups]]`)
+    #[serde(default)]
+    pub groups: Vec<GroupConfig>,
+    /// Strategies created on a fresh start instead of the single `[dca]` one (`[[slots]]`)
+    #[serde(default)]
+    pub slots: Vec<SlotSection>,
+    #[serde(default)]
+    pub ui: UiConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub websocket: WebSocketConfig,
+    #[serde(default)]
+    pub fees: FeesConfig,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+}
+
+/// Upper bound of `app.max_slots`: every slot adds its streams to the single WebSocket
+/// connection and its symbol to each alert pass
+pub const MAX_SLOTS_LIMIT: usize = 20;
+
+/// General settings of the bot (`[app]`)
+#[derive(Debug, Deserialize, Clone)]
+pub struct AppConfig {
+    /// Strategies that can run at the same time (1 to `MAX_SLOTS_LIMIT`). Lowering it
+    /// keeps the slots already saved, it only blocks creating new ones
+    #[serde(default = "default_max_slots")]
+    pub max_slots: usize,
+    /// Quote assets whose spot pairs are listed in the new strategy modal, in that order
+    /// (`["USDT", "USDC", "FDUSD", "BTC"]`)
+    #[serde(default = "default_quote_assets")]
+    pub quote_assets: Vec<String>,
+}
+
+fn default_max_slots() -> usize { 4 }
+fn default_quote_assets() -> Vec<String> { vec!["USDT".to_string()] }
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self { max_slots: default_max_slots(), quote_assets: default_quote_assets() }
+    }
+}
+
+/// How long history files are kept before the maintenance task prunes them (`[retention]`)
+#[derive(Debug, Deserialize, Clone)]
+pub struct RetentionConfig {
+    /// Days of slot events kept in the event log, the trade ledger behind the lifetime
+    /// stats (0 = forever). Only events already covered by the saved state are dropped
+    #[serde(default)]
+    pub events_days: u64,
+    /// Days of order requests and responses kept in the order audit log (0 = forever)
+    #[serde(default)]
+    pub audit_days: u64,
+    /// Days of equity samples kept at full resolution; older ones are thinned to the last
+    /// sample of each UTC day (0 = keep all)
+    #[serde(default)]
+    pub equity_days: u64,
+    /// Alerts kept in the alert history panel
+    #[serde(default = "default_alert_history")]
+    pub alert_history: usize,
+    /// Hours between two maintenance runs (the first one runs at startup)
+    #[serde(default = "default_maintenance_hours")]
+    pub maintenance_hours: u64,
+}
+
+fn default_alert_history() -> usize { 50 }
+fn default_maintenance_hours() -> u64 { 6 }
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            events_days: 0,
+            audit_days: 0,
+            equity_days: 0,
+            alert_history: default_alert_history(),
+            maintenance_hours: default_maintenance_hours(),
+        }
+    }
+}
+
+/// Commission rates used in P&L, break-even and minimum-TP math (`[fees]`).
+/// Unset rates come from the Binance account (VIP tier included).
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct FeesConfig {
+    /// Maker commission per side in %, replacing the account rate
+    #[serde(default)]
+    pub maker_pct: Option<f64>,
+    /// Taker commission per side in %, replacing the account rate
+    #[serde(default)]
+    pub taker_pct: Option<f64>,
+    /// Per-symbol rates for fee promotions (`[fees.symbols.BTCUSDT]`)
+    #[serde(default)]
+    pub symbols: BTreeMap<String, FeeRates>,
+}
+
+/// Maker/taker override; an unset side falls back to the `[fees]` or account rate
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+pub struct FeeRates {
+    #[serde(default)]
+    pub maker_pct: Option<f64>,
+    #[serde(default)]
+    pub taker_pct: Option<f64>,
+}
+
+impl FeesConfig {
+    /// Every configured rate with its `[fees]` path, for validation
+    fn rates(&self) -> impl Iterator<Item = (String, f64)> + '_ {
+        let global = [("maker_pct", self.maker_pct), ("taker_pct", self.taker_pct)]
+            .into_iter()
+            .filter_map(|(name, v)| Some((format!("fees.{}", name), v?)));
+        let symbols = self.symbols.iter().flat_map(|(symbol, r)| {
+            [("maker_pct", r.maker_pct), ("taker_pct", r.taker_pct)]
+                .into_iter()
+                .filter_map(move |(name, v)| Some((format!("fees.symbols.{}.{}", symbol, name), v?)))
+        });
+        global.chain(symbols)
+    }
+}
+
+/// Price stream reconnection policy (`[websocket]`)
+#[derive(Debug, Deserialize, Clone)]
+pub struct WebSocketConfig {
+    /// Wait before the first reconnection attempt, in seconds
+    #[serde(default = "default_reconnect_delay_secs")]
+    pub reconnect_delay_secs: f64,
+    /// Ceiling for the delay, which doubles after every failed attempt in a row
+    #[serde(default = "default_max_reconnect_delay_secs")]
+    pub max_reconnect_delay_secs: f64,
+    /// Failed attempts in a row before giving up until the symbol list changes (0 = never)
+    #[serde(default)]
+    pub max_retries: u32,
+    /// Random spread applied to every delay, in % either way (0 = exact delays)
+    #[serde(default = "default_jitter_pct")]
+    pub jitter_pct: f64,
+    /// Minutes of raw frames kept in the capture file for bug reports (0 = no recording)
+    #[serde(default)]
+    pub record_minutes: u64,
+}
+
+fn default_reconnect_delay_secs() -> f64 { 5.0 }
+fn default_max_reconnect_delay_secs() -> f64 { 60.0 }
+fn default_jitter_pct() -> f64 { 20.0 }
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            reconnect_delay_secs: default_reconnect_delay_secs(),
+            max_reconnect_delay_secs: default_max_reconnect_delay_secs(),
+            max_retries: 0,
+            jitter_pct: default_jitter_pct(),
+            record_minutes: 0,
+        }
+    }
+}
+
+impl WebSocketConfig {
+    /// Wait before the next attempt after `failures` failed attempts in a row (at least 1)
+    pub fn backoff(&self, failures: u32) -> std::time::Duration {
+        let doublings = failures.saturating_sub(1).min(16) as i32;
+        let delay = (self.reconnect_delay_secs * 2f64.powi(doublings)).min(self.max_reconnect_delay_secs);
+        let jitter = delay * self.jitter_pct / 100.0 * rand::thread_rng().gen_range(-1.0..=1.0);
+        std::time::Duration::from_secs_f64((delay + jitter).max(0.0))
+    }
+
+    /// True once `failures` attempts in a row exhausted `max_retries`
+    pub fn gave_up(&self, failures: u32) -> bool {
+        self.max_retries > 0 && failures >= self.max_retries
+    }
+}
+
+/// Terminal UI settings
+#[derive(Debug, Deserialize, Clone)]
+pub struct UiConfig {
+    /// How often the UI checks for state changes, in milliseconds.
+    /// The screen is only redrawn when something changed (or once per second for clocks).
+    #[serde(default = "default_refresh_ms")]
+    pub refresh_ms: u64,
+    /// Redraw interval while the terminal window is unfocused, in milliseconds
+    #[serde(default = "default_unfocused_refresh_ms")]
+    pub unfocused_refresh_ms: u64,
+    /// Asset the account equity and holdings are valued in (e.g. "USDT", "EUR", "BTC")
+    #[serde(default = "default_display_currency")]
+    pub display_currency: String,
+    /// Color theme of the dashboard
+    #[serde(default)]
+    pub theme: ThemeName,
+    /// Time zone of every timestamp on screen and in the log
+    #[serde(default)]
+    pub timezone: DisplayTimeZone,
+    /// Decimal places of amounts per asset, e.g. `{ USDT = 2, BTC = 6 }`. Assets not listed
+    /// use 2 for dollar stablecoins and 8 for the rest
+    #[serde(default)]
+    pub decimals: BTreeMap<String, usize>,
+    /// Language of the dashboard texts
+    #[serde(default)]
+    pub language: Language,
+}
+
+/// Languages the dashboard can be shown in
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    Es,
+}
+
+/// Time zone timestamps are shown in: "local", "utc" or a fixed offset from UTC such as
+/// "+02:00" / "-03:00"
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DisplayTimeZone {
+    #[default]
+    Local,
+    Utc,
+    Fixed(chrono::FixedOffset),
+}
+
+impl DisplayTimeZone {
+    /// `at` formatted with `fmt` (strftime) in this zone
+    pub fn format(&self, at: DateTime<Utc>, fmt: &str) -> String {
+        match self {
+            DisplayTimeZone::Local => at.with_timezone(&chrono::Local).format(fmt).to_string(),
+            DisplayTimeZone::Utc => at.format(fmt).to_string(),
+            DisplayTimeZone::Fixed(offset) => at.with_timezone(offset).format(fmt).to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for DisplayTimeZone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisplayTimeZone::Local => write!(f, "local"),
+            DisplayTimeZone::Utc => write!(f, "utc"),
+            DisplayTimeZone::Fixed(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+impl std::str::FromStr for DisplayTimeZone {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("local") {
+            return Ok(DisplayTimeZone::Local);
+        }
+        if s.eq_ignore_ascii_case("utc") {
+            return Ok(DisplayTimeZone::Utc);
+        }
+        let invalid = || anyhow::anyhow!("expected \"local\", \"utc\" or an offset like \"+02:00\"");
+        let (sign, rest) = match s.split_at_checked(1) {
+            Some(("+", rest)) => (1, rest),
+            Some(("-", rest)) => (-1, rest),
+            _ => return Err(invalid()),
+        };
+        let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+        let hours: i32 = hours.parse().map_err(|_| invalid())?;
+        let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+        if !(0..60).contains(&minutes) {
+            return Err(invalid());
+        }
+        chrono::FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+            .map(DisplayTimeZone::Fixed)
+            .ok_or_else(invalid)
+    }
+}
+
+impl Serialize for DisplayTimeZone {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for DisplayTimeZone {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Named color themes of the dashboard
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeName {
+    /// Bright colors for dark terminal backgrounds
+    #[default]
+    Default,
+    /// Darker colors for light terminal backgrounds
+    Light,
+    /// No hues: only grays, for terminals without color or plain logs
+    Monochrome,
+    /// Okabe-Ito colors, where gains and losses are blue / orange instead of green / red
+    Colorblind,
+}
+
+fn default_refresh_ms() -> u64 { 150 }
+fn default_unfocused_refresh_ms() -> u64 { 1000 }
+fn default_display_currency() -> String { "USDT".to_string() }
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            refresh_ms: default_refresh_ms(),
+            unfocused_refresh_ms: default_unfocused_refresh_ms(),
+            display_currency: default_display_currency(),
+            theme: ThemeName::default(),
+            timezone: DisplayTimeZone::default(),
+            decimals: BTreeMap::new(),
+            language: Language::default(),
+        }
+    }
+}
+
+/// Audible notifications (`[notifications]`)
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct NotificationsConfig {
+    /// Hours in which beeps are muted and only logged (None = always notify)
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl NotificationsConfig {
+    /// False when `now` falls in the quiet hours and `event` is not whitelisted
+    pub fn allows(&self, event: NotifyEvent, now: chrono::DateTime<chrono::Utc>) -> bool {
+        match &self.quiet_hours {
+            Some(quiet) if quiet.window.contains(now.time()) => quiet.always_notify.contains(&event),
+            _ => true,
+        }
+    }
+}
+
+/// `[notifications.quiet_hours]`
+#[derive(Debug, Deserialize, Clone)]
+pub struct QuietHours {
+    /// UTC window, e.g. "23:00-07:00"
+    pub window: TimeWindow,
+    /// Events that still notify during quiet hours
+    #[serde(default = "default_always_notify")]
+    pub always_notify: Vec<NotifyEvent>,
+}
+
+fn default_always_notify() -> Vec<NotifyEvent> {
+    vec![NotifyEvent::StopLoss, NotifyEvent::Error, NotifyEvent::EquityFloor]
+}
+
+/// Events that produce a notification
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyEvent {
+    /// Support/resistance alert
+    Alert,
+    /// Stop loss triggered
+    StopLoss,
+    /// Strategy stopped by an error
+    Error,
+    /// Position closed by take profit, trailing TP or by hand
+    Exit,
+    /// Account equity summary at the start of each UTC day (account-wide, not per slot)
+    DailySummary,
+    /// Account equity fell under `equity_floor_usdt` (account-wide, not per slot)
+    EquityFloor,
+}
+
+impl NotifyEvent {
+    /// Events a slot can turn on or off
+    pub const ALL: [NotifyEvent; 4] = [Self::Alert, Self::StopLoss, Self::Error, Self::Exit];
+
+    /// Whether the event notifies when the slot has no override (exits are opt-in)
+    pub fn enabled_by_default(self) -> bool {
+        self != Self::Exit
+    }
+
+    /// Whether a slot with `overrides` notifies this event
+    pub fn enabled_for(self, overrides: &BTreeMap<NotifyEvent, bool>) -> bool {
+        overrides.get(&self).copied().unwrap_or(self.enabled_by_default())
+    }
+
+    /// Name used in config.toml and the command palette
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Alert => "alert",
+            Self::StopLoss => "stop_loss",
+            Self::Error => "error",
+            Self::Exit => "exit",
+            Self::DailySummary => "daily_summary",
+            Self::EquityFloor => "equity_floor",
+        }
+    }
+}
+
+impl std::str::FromStr for NotifyEvent {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|e| e.name() == s)
+            .with_context(|| format!("unknown event '{}' (alert, stop_loss, error, exit)", s))
+    }
+}
+
+/// A group of slots drawing from the same pool
+#[derive(Debug, Deserialize, Clone)]
+pub struct GroupConfig {
+    pub name: String,
+    /// Maximum quote deployed in open positions across the group (0 = unlimited)
+    #[serde(default)]
+    pub budget: f64,
+    /// Maximum quote spent per day across the group (0 = unlimited)
+    #[serde(default)]
+    pub max_daily_spend: f64,
 }
 
 /// Support/Resistance alert engine configuration
@@ -47,11 +662,19 @@ pub struct AlertsConfig {
     /// Minimum minutes between two alerts of the same type for the same symbol
     #[serde(default = "default_cooldown_minutes")]
     pub cooldown_minutes: u64,
+    /// Alert when a minute trades more than this many times the rolling average volume
+    /// (@aggTrade stream; 0 = off)
+    #[serde(default)]
+    pub volume_spike_factor: f64,
+    /// Minutes in the rolling volume average
+    #[serde(default = "default_volume_window_minutes")]
+    pub volume_window_minutes: usize,
 }
 
 fn default_rolling_window() -> usize { 20 }
 fn default_candle_interval() -> String { "1h".to_string() }
 fn default_cooldown_minutes() -> u64 { 30 }
+fn default_volume_window_minutes() -> usize { 20 }
 
 impl Default for AlertsConfig {
     fn default() -> Self {
@@ -59,26 +682,57 @@ impl Default for AlertsConfig {
             rolling_window: default_rolling_window(),
             candle_interval: default_candle_interval(),
             cooldown_minutes: default_cooldown_minutes(),
+            volume_spike_factor: 0.0,
+            volume_window_minutes: default_volume_window_minutes(),
         }
     }
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct BinanceConfig {
+    /// Overridden by the BINANCE_API_KEY environment variable
+    #[serde(default)]
     pub api_key: String,
+    /// Overridden by the BINANCE_API_SECRET environment variable
+    #[serde(default)]
     pub api_secret: String,
+    /// Forced to true by `--testnet`
     pub testnet: bool,
+    /// Testnet only: compare the testnet order book with the (mainnet) displayed price before each order
+    #[serde(default = "default_true")]
+    pub testnet_price_check: bool,
+    /// Testnet only: warn when testnet and mainnet prices diverge more than this % (0 = off)
+    #[serde(default = "default_testnet_max_divergence_pct")]
+    pub testnet_max_divergence_pct: f64,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+fn default_true() -> bool { true }
+fn default_testnet_max_divergence_pct() -> f64 { 1.0 }
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DcaConfig {
     /// Binance symbol (e.g.: BTCUSDT)
     pub symbol: String,
     /// Direction: "long" (buy and sell when it goes up) or "short" (sell and rebuy when it goes down)
     #[serde(default)]
     pub direction: Direction,
-    /// Amount in quote currency per trade (e.g.: 10 USDT)
-    pub quote_amount: f64,
+    /// Strategy type: "trading" (DCA cycles), "accumulate" (recurring buys, never sold)
+    /// or "distribute" (gradual sale of a held inventory)
+    #[serde(default)]
+    pub kind: StrategyKind,
+    /// Accumulate / distribute: when the recurring buy or sale happens (UTC)
+    #[serde(default)]
+    pub buy_schedule: RecurringSchedule,
+    /// Distribute: base quantity to sell in total (0 = the free balance when the slot starts)
+    #[serde(default)]
+    pub distribute_qty: f64,
+    /// Distribute: ascending prices, one sale each time the next one is reached; the last
+    /// level sells whatever is left. Empty = sell on `buy_schedule`
+    #[serde(default)]
+    pub sell_levels: Vec<f64>,
+    /// Amount per trade: quote currency (e.g.: 10 USDT) or "2%" of the free balance,
+    /// recomputed at each entry
+    pub quote_amount: QuoteAmount,
     /// Interval between entries in minutes
     pub interval_minutes: u64,
     /// LONG: additional entry if price drops X% from last buy (0 = off)
@@ -86,12 +740,23 @@ pub struct DcaConfig {
     pub price_drop_trigger: f64,
     /// Maximum number of DCA orders
     pub max_orders: u32,
-    /// Take profit in % from average entry price (0 = off)
-    pub take_profit_pct: f64,
+    /// Multiplier applied to each subsequent safety order (1.0 = same size; 1.5 = 10, 15, 22.5...)
+    #[serde(default = "default_volume_scale")]
+    pub volume_scale: f64,
+    /// Take profit in % from average entry price (0 = off), or a ladder of [pct, fraction] levels
+    pub take_profit_pct: TakeProfit,
     /// Stop loss in % from average entry price (0 = off)
     pub stop_loss_pct: f64,
     /// Trailing take profit: closes if price retreats X% from the peak/trough (0 = off)
     pub trailing_tp_pct: f64,
+    /// Once P&L reaches this % the position gets a stop at break-even (fees included)
+    /// for the rest of the cycle (0 = off)
+    #[serde(default)]
+    pub breakeven_trigger_pct: f64,
+    /// Where the break-even stop sits past the break-even price, in % of it, to lock in
+    /// some profit (0 = exact break-even). Keep it below `breakeven_trigger_pct`.
+    #[serde(default)]
+    pub breakeven_offset_pct: f64,
     /// Restart DCA cycle automatically after a TP/Trailing TP (true/false)
     /// If false, the bot shows an overlay and waits for user decision
     pub auto_restart: bool,
@@ -104,14 +769,467 @@ pub struct DcaConfig {
     /// Minutes to wait before re-entering after a TP/Trailing TP (0 = immediate)
     #[serde(default)]
     pub restart_cooldown_minutes: u64,
+    /// Warn when round-trip fees exceed this fraction of the expected TP (0 = off)
+    #[serde(default = "default_max_fee_tp_ratio")]
+    pub max_fee_tp_ratio: f64,
+    /// If true, (re)starting a strategy that trips the fee guard is refused instead of only warned
+    #[serde(default)]
+    pub block_restart_on_fees: bool,
+    /// Net profit in % required after commissions; take_profit_pct (already net of the exit
+    /// commission) is raised to the entry commission plus this
+    #[serde(default = "default_min_net_profit_pct")]
+    pub min_net_profit_pct: f64,
+    /// Slot group this strategy belongs to (set at slot creation, None = no group)
+    #[serde(default)]
+    pub group: Option<String>,
+    /// Safety order ladder: one step per safety order after the base order.
+    /// When set it replaces `price_drop_trigger`, `volume_scale` and `max_orders`.
+    #[serde(default)]
+    pub safety_orders: Vec<SafetyOrder>,
+    /// UTC hours in which new entries may open, e.g. "08:00-22:00" (None = all day).
+    /// Open positions are still managed (TP/SL) outside the window.
+    #[serde(default)]
+    pub active_hours: Option<TimeWindow>,
+    /// Weekdays in which new entries may open, e.g. ["mon", "tue"] (empty = every day)
+    #[serde(default)]
+    pub active_days: Vec<chrono::Weekday>,
+    /// Entry order type: "market" or "limit"
+    #[serde(default)]
+    pub entry_order_type: EntryOrderType,
+    /// Limit/maker entries: price offset in % below (LONG) / above (SHORT) the reference price
+    #[serde(default = "default_limit_offset_pct")]
+    pub limit_offset_pct: f64,
+    /// Limit/maker entries: seconds without a fill before the order is cancelled and re-placed
+    #[serde(default = "default_limit_timeout_secs")]
+    pub limit_timeout_secs: u64,
+    /// Limit/maker SHORT entries: % of the order shown on the book as an iceberg (0 = off)
+    #[serde(default)]
+    pub iceberg_visible_pct: f64,
+    /// Market entries: maximum bid/ask spread in %; above it the entry is placed as a
+    /// LIMIT_MAKER at the best bid/ask instead (0 = off)
+    #[serde(default)]
+    pub max_market_spread_pct: f64,
+    /// Hold entries while a volume spike led by the other side is in progress (sellers for
+    /// LONG, buyers for SHORT). Needs `alerts.volume_spike_factor` > 0
+    #[serde(default)]
+    pub pause_on_volume_spike: bool,
+    /// Reduce-only: no new entries, the open position is still managed (TP/SL/trailing)
+    /// and the slot stops once it closes. Toggled per slot to wind it down
+    #[serde(default)]
+    pub reduce_only: bool,
+    /// Capital assigned to the slot as a virtual wallet: entries spend only from it and
+    /// exits return cost plus realized P&L to it (0 = no wallet, uses the account balance)
+    #[serde(default)]
+    pub capital_usdt: f64,
+    /// Maximum spend per day of this slot, in its quote (0 = `[risk] max_daily_spend`)
+    #[serde(default)]
+    pub max_daily_spend: f64,
+    /// Per-slot notification overrides by event, e.g. `{ exit = true, alert = false }`.
+    /// Events not listed follow `NotifyEvent::enabled_by_default`
+    #[serde(default)]
+    pub notify: BTreeMap<NotifyEvent, bool>,
+}
+
+impl DcaConfig {
+    /// Orders in a full cycle: the base order plus the safety orders
+    pub fn order_count(&self) -> u32 {
+        if self.safety_orders.is_empty() {
+            self.max_orders
+        } else {
+            self.safety_orders.len() as u32 + 1
+        }
+    }
+
+    /// Quote needed to fill every order of a full cycle, starting from `first_amount`
+    /// and applying `volume_scale` (or the ladder multipliers) to each safety order
+    pub fn cycle_budget(&self, first_amount: f64) -> f64 {
+        if !self.safety_orders.is_empty() {
+            return first_amount * (1.0 + self.safety_orders.iter().map(|so| so.size_multiplier).sum::<f64>());
+        }
+        (0..self.max_orders).map(|i| first_amount * self.volume_scale.powi(i as i32)).sum()
+    }
+
+    /// This config with `fields` (named as in `[dca]`) replaced, plus those fields as JSON
+    /// values: they are recorded as the config overrides of a slot created from it
+    pub fn with_fields<'a>(
+        &self,
+        fields: impl IntoIterator<Item = (&'a String, &'a toml::Value)>,
+    ) -> Result<(DcaConfig, Vec<(String, serde_json::Value)>)> {
+        let overrides: Vec<(String, serde_json::Value)> = fields
+            .into_iter()
+            .map(|(field, value)| Ok((field.clone(), serde_json::to_value(value)?)))
+            .collect::<Result<_>>()?;
+        let mut json = serde_json::to_value(self)?;
+        if let Some(obj) = json.as_object_mut() {
+            for (field, value) in &overrides {
+                anyhow::ensure!(obj.contains_key(field), "unknown field {}", field);
+                obj.insert(field.clone(), value.clone());
+            }
+        }
+        Ok((serde_json::from_value(json)?, overrides))
+    }
+
+    /// Problems of this strategy config, named by field without the section (the caller
+    /// prefixes `dca.` or `slots[i].`). `max_daily_spend` is the `[risk]` limit in USDT
+    pub fn problems(&self, max_daily_spend: f64) -> Vec<String> {
+        let mut problems = Vec::new();
+        if let Err(e) = self.quote_amount.validate() {
+            problems.push(format!("quote_amount: {}", e));
+        }
+        if self.interval_minutes == 0 {
+            problems.push("interval_minutes must be greater than 0".to_string());
+        }
+        if self.volume_scale <= 0.0 {
+            problems.push("volume_scale must be greater than 0".to_string());
+        }
+        if let Err(e) = self.take_profit_pct.validate() {
+            problems.push(format!("take_profit_pct: {}", e));
+        }
+        let mut prev_deviation = 0.0;
+        for (i, so) in self.safety_orders.iter().enumerate() {
+            if so.deviation_pct <= prev_deviation {
+                problems.push(format!("safety_orders[{}]: deviation_pct must be greater than the previous step", i));
+            }
+            if so.size_multiplier <= 0.0 {
+                problems.push(format!("safety_orders[{}]: size_multiplier must be greater than 0", i));
+            }
+            prev_deviation = so.deviation_pct;
+        }
+        if self.limit_offset_pct < 0.0 {
+            problems.push("limit_offset_pct must be 0 or greater".to_string());
+        }
+        if self.limit_timeout_secs == 0 {
+            problems.push("limit_timeout_secs must be greater than 0".to_string());
+        }
+        if !(0.0..100.0).contains(&self.iceberg_visible_pct) {
+            problems.push("iceberg_visible_pct must be between 0 and 100 (0 = off)".to_string());
+        }
+        if self.iceberg_visible_pct > 0.0 && self.entry_order_type == EntryOrderType::Market {
+            problems.push("iceberg_visible_pct needs entry_order_type = \"limit\" or \"maker\"".to_string());
+        }
+        if self.kind == StrategyKind::Accumulate && self.direction != Direction::Long {
+            problems.push("kind = \"accumulate\" only buys: direction must be \"long\"".to_string());
+        }
+        if self.kind == StrategyKind::Watch {
+            problems.push("kind = \"watch\" needs a position to import: use the watch command of the palette".to_string());
+        }
+        if self.kind == StrategyKind::Distribute && self.direction != Direction::Short {
+            problems.push("kind = \"distribute\" only sells: direction must be \"short\"".to_string());
+        }
+        if self.distribute_qty < 0.0 {
+            problems.push("distribute_qty must be 0 or greater".to_string());
+        }
+        if self.sell_levels.iter().any(|p| *p <= 0.0)
+            || self.sell_levels.windows(2).any(|w| w[1] <= w[0])
+        {
+            problems.push("sell_levels must be positive prices in ascending order".to_string());
+        }
+        if self.capital_usdt < 0.0 {
+            problems.push("capital_usdt must be 0 or greater".to_string());
+        }
+        if self.max_daily_spend < 0.0 {
+            problems.push("max_daily_spend must be 0 or greater (0 = risk.max_daily_spend)".to_string());
+        }
+        if self.max_market_spread_pct < 0.0 {
+            problems.push("max_market_spread_pct must be 0 or greater".to_string());
+        }
+        // Settings that are valid alone but contradict each other
+        let tp = self.take_profit_pct.first_pct();
+        if self.trailing_tp_pct > 0.0 && tp > 0.0 && self.trailing_tp_pct >= tp {
+            problems.push(format!(
+                "trailing_tp_pct ({}%) must be lower than take_profit_pct ({}%)",
+                self.trailing_tp_pct, tp
+            ));
+        }
+        if self.breakeven_trigger_pct > 0.0 && self.breakeven_offset_pct >= self.breakeven_trigger_pct {
+            problems.push(format!(
+                "breakeven_offset_pct ({}%) must be lower than breakeven_trigger_pct ({}%)",
+                self.breakeven_offset_pct, self.breakeven_trigger_pct
+            ));
+        }
+        // The slot's own limit is in its quote; the [risk] one in USDT, only comparable for
+        // USDT pairs
+        let limit = if self.max_daily_spend > 0.0 {
+            Some(("max_daily_spend", self.max_daily_spend))
+        } else {
+            self.symbol.ends_with("USDT").then_some(("risk.max_daily_spend", max_daily_spend))
+        };
+        if let (QuoteAmount::Fixed(amount), Some((name, limit))) = (self.quote_amount, limit) {
+            let budget = self.cycle_budget(amount);
+            if self.kind == StrategyKind::Trading && budget > limit + 1e-9 {
+                problems.push(format!(
+                    "quote_amount {} × {} orders needs {:.2} for a full cycle, more than {} ({})",
+                    amount,
+                    self.order_count(),
+                    budget,
+                    name,
+                    limit
+                ));
+            }
+        }
+
+        problems
+    }
+}
+
+/// A strategy created when the bot starts without a saved session (`[[slots]]`): its
+/// symbol plus the `[dca]` fields that differ; every other field comes from `[dca]`
+#[derive(Debug, Deserialize, Clone)]
+pub struct SlotSection {
+    pub symbol: String,
+    #[serde(flatten)]
+    pub fields: BTreeMap<String, toml::Value>,
+}
+
+impl SlotSection {
+    /// `base` with this section applied, plus its fields as JSON values (recorded as
+    /// config overrides of the slot)
+    pub fn apply(&self, base: &DcaConfig) -> Result<(DcaConfig, Vec<(String, serde_json::Value)>)> {
+        let (mut config, overrides) = base.with_fields(&self.fields)?;
+        config.symbol = self.symbol.trim().to_uppercase();
+        Ok((config, overrides))
+    }
 }
 
+/// Daily time window in UTC (`"08:00-22:00"`); may wrap past midnight (`"22:00-06:00"`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeWindow {
+    pub start: chrono::NaiveTime,
+    pub end: chrono::NaiveTime,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, time: chrono::NaiveTime) -> bool {
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl std::fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start.format("%H:%M"), self.end.format("%H:%M"))
+    }
+}
+
+impl std::str::FromStr for TimeWindow {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (start, end) = s.split_once('-').context("expected \"HH:MM-HH:MM\"")?;
+        let parse = |t: &str| chrono::NaiveTime::parse_from_str(t.trim(), "%H:%M").context("invalid time, expected HH:MM");
+        let window = TimeWindow { start: parse(start)?, end: parse(end)? };
+        if window.start == window.end {
+            anyhow::bail!("start and end must differ");
+        }
+        Ok(window)
+    }
+}
+
+impl Serialize for TimeWindow {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeWindow {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Recurring UTC time of accumulation buys: `"daily 09:00"` or `"weekly mon 09:00"`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecurringSchedule {
+    /// Day of the week for weekly buys (None = every day)
+    pub weekday: Option<chrono::Weekday>,
+    pub at: chrono::NaiveTime,
+}
+
+impl Default for RecurringSchedule {
+    fn default() -> Self {
+        Self { weekday: None, at: chrono::NaiveTime::MIN }
+    }
+}
+
+impl RecurringSchedule {
+    /// Most recent scheduled time at or before `now`
+    pub fn last_due(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let mut due = now.date_naive().and_time(self.at).and_utc();
+        if due > now {
+            due -= chrono::Duration::days(1);
+        }
+        if let Some(day) = self.weekday {
+            let back = (due.weekday().num_days_from_monday() + 7 - day.num_days_from_monday()) % 7;
+            due -= chrono::Duration::days(back as i64);
+        }
+        due
+    }
+
+    /// First scheduled time after `now`
+    pub fn next_due(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        self.last_due(now) + self.period()
+    }
+
+    pub fn period(&self) -> chrono::Duration {
+        chrono::Duration::days(if self.weekday.is_some() { 7 } else { 1 })
+    }
+}
+
+impl std::fmt::Display for RecurringSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.weekday {
+            Some(day) => write!(f, "weekly {} {}", day.to_string().to_lowercase(), self.at.format("%H:%M")),
+            None => write!(f, "daily {}", self.at.format("%H:%M")),
+        }
+    }
+}
+
+impl std::str::FromStr for RecurringSchedule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        let parse_time = |t: &str| chrono::NaiveTime::parse_from_str(t, "%H:%M").context("invalid time, expected HH:MM");
+        match parts.as_slice() {
+            ["daily", at] => Ok(Self { weekday: None, at: parse_time(at)? }),
+            ["weekly", day, at] => {
+                let day = day.parse::<chrono::Weekday>().map_err(|_| anyhow::anyhow!("invalid weekday '{}'", day))?;
+                Ok(Self { weekday: Some(day), at: parse_time(at)? })
+            }
+            _ => anyhow::bail!("expected \"daily HH:MM\" or \"weekly <day> HH:MM\""),
+        }
+    }
+}
+
+impl Serialize for RecurringSchedule {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for RecurringSchedule {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// One step of the safety order ladder
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct SafetyOrder {
+    /// Price move against the position, in % from the base order price
+    pub deviation_pct: f64,
+    /// Order size as a multiple of the base order amount
+    pub size_multiplier: f64,
+}
+
+fn default_volume_scale() -> f64 { 1.0 }
+fn default_max_fee_tp_ratio() -> f64 { 0.5 }
+fn default_min_net_profit_pct() -> f64 { 0.05 }
+fn default_limit_offset_pct() -> f64 { 0.1 }
+fn default_limit_timeout_secs() -> u64 { 120 }
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct RiskConfig {
-    /// Maximum USDT spend per day
+    /// Maximum spend per day of each slot without its own `dca.max_daily_spend`, in USDT.
+    /// Slots quoted in another asset convert it at the current rate (stablecoins 1:1 until
+    /// the first rate is known)
     pub max_daily_spend: f64,
+    /// Maximum spend per day across all slots, in USDT (0 = no global cap)
+    #[serde(default)]
+    pub max_daily_spend_total: f64,
+    /// Size each slot's entries inversely to its volatility (daily ATR) so all slots carry similar risk
+    #[serde(default)]
+    pub risk_parity: bool,
+    /// Number of daily candles used for the ATR in risk-parity sizing
+    #[serde(default = "default_atr_period")]
+    pub atr_period: usize,
+    /// Amounts entered in the config panel above this % of the free balance need a second confirmation (0 = off)
+    #[serde(default = "default_amount_confirm_pct")]
+    pub amount_confirm_pct: f64,
+    /// Total account equity (USDT) below which every strategy is closed or paused (0 = off)
+    #[serde(default)]
+    pub equity_floor_usdt: f64,
+    /// What to do when equity drops below `equity_floor_usdt`
+    #[serde(default)]
+    pub equity_floor_action: EquityFloorAction,
+    /// Seconds without a price update after which a symbol is not traded (0 = off)
+    #[serde(default = "default_max_price_age_secs")]
+    pub max_price_age_secs: u64,
+    /// Cap per base asset on the combined position (all slots, pending entries and
+    /// existing holdings), checked before every LONG entry (`[risk.max_exposure]`)
+    #[serde(default)]
+    pub max_exposure: BTreeMap<String, ExposureLimit>,
+}
+
+/// Maximum position in an asset: in base units (`0.5`) or in quote value (`"$5000"`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExposureLimit {
+    Base(f64),
+    Quote(f64),
+}
+
+impl ExposureLimit {
+    /// Whether a position of `quantity` base units worth `price` each fits the limit
+    pub fn allows(&self, quantity: f64, price: f64) -> bool {
+        match self {
+            ExposureLimit::Base(max) => quantity <= max + 1e-12,
+            ExposureLimit::Quote(max) => quantity * price <= max + 1e-9,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            ExposureLimit::Base(v) | ExposureLimit::Quote(v) if *v <= 0.0 => anyhow::bail!("must be greater than 0"),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl std::str::FromStr for ExposureLimit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        match s.strip_prefix('$') {
+            Some(v) => Ok(ExposureLimit::Quote(v.trim().parse().context("invalid quote value")?)),
+            None => Ok(ExposureLimit::Base(s.parse().context("invalid quantity")?)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ExposureLimit {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Number(f64),
+            Text(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Number(v) => Ok(ExposureLimit::Base(v)),
+            Raw::Text(s) => s.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Action of the portfolio-level stop
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EquityFloorAction {
+    /// Market-close every open position and stop all strategies
+    #[default]
+    CloseAll,
+    /// Stop all strategies and cancel pending entries, keeping positions open
+    PauseAll,
 }
 
+fn default_atr_period() -> usize { 14 }
+fn default_amount_confirm_pct() -> f64 { 25.0 }
+fn default_max_price_age_secs() -> u64 { 30 }
+
 /// Returns the directory where the executable lives (or current directory as fallback)
 pub fn exe_dir() -> std::path::PathBuf {
     std::env::current_exe()
@@ -120,45 +1238,398 @@ pub fn exe_dir() -> std::path::PathBuf {
         .unwrap_or_else(|| std::path::PathBuf::from("."))
 }
 
+/// Most decimal places accepted in `[ui] decimals` (Binance amounts have at most 8)
+const MAX_DECIMALS: usize = 8;
+
+/// Kline intervals accepted by Binance
+pub const KLINE_INTERVALS: [&str; 15] =
+    ["1m", "3m", "5m", "15m", "30m", "1h", "2h", "4h", "6h", "8h", "12h", "1d", "3d", "1w", "1M"];
+
+/// Environment variables that replace the API credentials of config.toml
+pub const API_KEY_ENV: &str = "BINANCE_API_KEY";
+pub const API_SECRET_ENV: &str = "BINANCE_API_SECRET";
+
+/// Command line overrides, applied on top of config.toml every time it is read
+/// (hot reloads and the TUI editors included)
+#[derive(Debug, Default)]
+pub struct Overrides {
+    /// `--config <path>`: config.toml to use instead of the default lookup
+    pub config_path: Option<std::path::PathBuf>,
+    /// `--testnet`: use the testnet whatever `binance.testnet` says
+    pub testnet: bool,
+    /// `--profile <name>`: `config.<name>.toml` and state files of its own
+    pub profile: Option<String>,
+}
+
+static OVERRIDES: std::sync::OnceLock<Overrides> = std::sync::OnceLock::new();
+
+/// Sets the command line overrides. Call once at startup, before the config is loaded
+pub fn set_overrides(overrides: Overrides) {
+    let _ = OVERRIDES.set(overrides);
+}
+
+/// Active `--profile`, if any
+pub fn profile() -> Option<&'static str> {
+    OVERRIDES.get().and_then(|o| o.profile.as_deref())
+}
+
+/// `file_name` for the active profile: `strategy_state.json` becomes
+/// `strategy_state.<profile>.json` (unchanged without a profile)
+fn profile_file(file_name: &str) -> String {
+    match (profile(), file_name.rsplit_once('.')) {
+        (Some(profile), Some((stem, ext))) => format!("{}.{}.{}", stem, profile, ext),
+        (Some(profile), None) => format!("{}.{}", file_name, profile),
+        (None, _) => file_name.to_string(),
+    }
+}
+
+/// Data file next to the executable, kept apart per profile so two profiles never write
+/// the same state, event log or audit trail
+pub fn data_path(file_name: &str) -> std::path::PathBuf {
+    exe_dir().join(profile_file(file_name))
+}
+
+/// Value of an environment variable, if set and not blank
+fn env_value(name: &str) -> Option<String> {
+    std::env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
 impl Config {
+    /// config.toml given with `--config`, else the one in the working directory, or next
+    /// to the executable (`config.<profile>.toml` with `--profile`)
+    pub fn path() -> std::path::PathBuf {
+        if let Some(path) = OVERRIDES.get().and_then(|o| o.config_path.clone()) {
+            return path;
+        }
+        let file_name = profile_file("config.toml");
+        if std::path::Path::new(&file_name).exists() {
+            std::path::PathBuf::from(file_name)
+        } else {
+            exe_dir().join(file_name)
+        }
+    }
+
     /// Loads the config and also returns the path where it was found
     pub fn load() -> Result<(Self, std::path::PathBuf)> {
-        let path = if std::path::Path::new("config.toml").exists() {
-            std::path::PathBuf::from("config.toml")
-        } else {
-            exe_dir().join("config.toml")
-        };
+        let path = Self::path();
         let content = std::fs::read_to_string(&path)
             .with_context(|| format!("config.toml not found (searched in {:?})", path))?;
-        let config: Config =
-            toml::from_str(&content).context("Error parsing config.toml")?;
+        Ok((Self::parse(&content)?, path))
+    }
 
-        if config.binance.api_key == "YOUR_API_KEY_HERE" {
-            anyhow::bail!("Configure your API keys in config.toml before running the bot");
+    /// Credentials from the environment and `--testnet` take precedence over the file
+    fn apply_overrides(&mut self) {
+        if let Some(key) = env_value(API_KEY_ENV) {
+            self.binance.api_key = key;
         }
-        if config.dca.quote_amount <= 0.0 {
-            anyhow::bail!("dca.quote_amount must be greater than 0");
+        if let Some(secret) = env_value(API_SECRET_ENV) {
+            self.binance.api_secret = secret;
         }
-        if config.dca.interval_minutes == 0 {
-            anyhow::bail!("dca.interval_minutes must be greater than 0");
+        if OVERRIDES.get().is_some_and(|o| o.testnet) {
+            self.binance.testnet = true;
         }
+    }
+
+    /// Parses and validates the content of config.toml
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut config: Config =
+            toml::from_str(content).context("Error parsing config.toml")?;
+        config.apply_overrides();
+
+        let problems = config.validate();
+        match problems.as_slice() {
+            [] => Ok(config),
+            [problem] => anyhow::bail!("{}", problem),
+            _ => anyhow::bail!("{} problems in config.toml:\n  - {}", problems.len(), problems.join("\n  - ")),
+        }
+    }
+
+    /// Checks every setting and the consistency between them. Returns all the problems
+    /// found (empty = valid) so they can be fixed in one go
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let key = &self.binance.api_key;
+        if key.is_empty() || key == "YOUR_API_KEY_HERE" || self.binance.api_secret.is_empty() {
+            problems.push(format!(
+                "Configure your API keys in config.toml (or set {} and {}) before running the bot",
+                API_KEY_ENV,
+                API_SECRET_ENV
+            ));
+        }
+        if self.alerts.volume_spike_factor < 0.0 {
+            problems.push("alerts.volume_spike_factor must be 0 or greater".to_string());
+        }
+        if self.alerts.volume_window_minutes == 0 {
+            problems.push("alerts.volume_window_minutes must be greater than 0".to_string());
+        }
+        if self.retention.alert_history == 0 {
+            problems.push("retention.alert_history must be at least 1".to_string());
+        }
+        if self.retention.maintenance_hours == 0 {
+            problems.push("retention.maintenance_hours must be at least 1".to_string());
+        }
+        if !(1..=MAX_SLOTS_LIMIT).contains(&self.app.max_slots) {
+            problems.push(format!("app.max_slots must be between 1 and {}", MAX_SLOTS_LIMIT));
+        }
+        if self.app.quote_assets.is_empty() {
+            problems.push("app.quote_assets must list at least one asset".to_string());
+        }
+        if self.app.quote_assets.iter().any(|q| q.trim().is_empty()) {
+            problems.push("app.quote_assets must not contain empty names".to_string());
+        }
+        if self.risk.max_daily_spend_total < 0.0 {
+            problems.push("risk.max_daily_spend_total must be 0 or greater (0 = no global cap)".to_string());
+        }
+        if self.ui.refresh_ms < 16 {
+            problems.push("ui.refresh_ms must be at least 16".to_string());
+        }
+        for (asset, limit) in &self.risk.max_exposure {
+            if let Err(e) = limit.validate() {
+                problems.push(format!("risk.max_exposure.{}: {}", asset, e));
+            }
+        }
+        if self.ui.display_currency.trim().is_empty() {
+            problems.push("ui.display_currency must not be empty".to_string());
+        }
+        if let Some((asset, _)) = self.ui.decimals.iter().find(|(_, d)| **d > MAX_DECIMALS) {
+            problems.push(format!("ui.decimals.{} must be between 0 and {}", asset, MAX_DECIMALS));
+        }
+        if self.ui.unfocused_refresh_ms < self.ui.refresh_ms {
+            problems.push("ui.unfocused_refresh_ms must not be lower than ui.refresh_ms".to_string());
+        }
+        if let Some((name, _)) = self.fees.rates().find(|(_, v)| !(0.0..10.0).contains(v)) {
+            problems.push(format!("{} must be between 0 and 10 (% per side)", name));
+        }
+        if self.websocket.reconnect_delay_secs <= 0.0 {
+            problems.push("websocket.reconnect_delay_secs must be greater than 0".to_string());
+        }
+        if self.websocket.max_reconnect_delay_secs < self.websocket.reconnect_delay_secs {
+            problems.push("websocket.max_reconnect_delay_secs must not be lower than reconnect_delay_secs".to_string());
+        }
+        if !(0.0..100.0).contains(&self.websocket.jitter_pct) {
+            problems.push("websocket.jitter_pct must be between 0 and 100".to_string());
+        }
+        if !KLINE_INTERVALS.contains(&self.alerts.candle_interval.as_str()) {
+            problems.push(format!(
+                "alerts.candle_interval \"{}\" is not a Binance interval ({})",
+                self.alerts.candle_interval,
+                KLINE_INTERVALS.join(", ")
+            ));
+        }
+
+        let dca_problems = self.dca.problems(self.risk.max_daily_spend);
+        problems.extend(dca_problems.iter().map(|p| format!("dca.{}", p)));
+        if self.slots.len() > self.app.max_slots {
+            problems.push(format!(
+                "slots lists {} strategies, more than app.max_slots ({})",
+                self.slots.len(),
+                self.app.max_slots
+            ));
+        }
+        for (i, slot) in self.slots.iter().enumerate() {
+            if slot.symbol.trim().is_empty() {
+                problems.push(format!("slots[{}].symbol must not be empty", i));
+                continue;
+            }
+            match slot.apply(&self.dca) {
+                // What the slot inherits from [dca] was already reported above
+                Ok((config, _)) => problems.extend(
+                    config
+                        .problems(self.risk.max_daily_spend)
+                        .into_iter()
+                        .filter(|p| !dca_problems.contains(p))
+                        .map(|p| format!("slots[{}].{}", i, p)),
+                ),
+                Err(e) => problems.push(format!("slots[{}] ({}): {:#}", i, slot.symbol, e)),
+            }
+        }
+
+        problems
+    }
 
-        Ok((config, path))
+    /// Checks that need the exchange: the symbols of `[dca]` and `[[slots]]` must be
+    /// among the `available` pairs (those quoted in `app.quote_assets`)
+    pub fn check_symbols(&self, available: &[String]) -> Vec<String> {
+        let sections = self.slots.iter().enumerate().map(|(i, slot)| (format!("slots[{}]", i), &slot.symbol));
+        std::iter::once(("dca".to_string(), &self.dca.symbol))
+            .chain(sections)
+            .filter_map(|(section, symbol)| {
+                let symbol = symbol.trim().to_uppercase();
+                (!available.contains(&symbol)).then(|| {
+                    format!(
+                        "{}.symbol {} is not a trading pair on Binance{} quoted in {}",
+                        section,
+                        symbol,
+                        if self.binance.testnet { " testnet" } else { "" },
+                        self.app.quote_assets.join("/")
+                    )
+                })
+            })
+            .collect()
     }
 
-    /// Saves symbol and amount in config.toml preserving comments
-    pub fn save_dca(path: &std::path::Path, symbol: &str, amount: f64) -> Result<()> {
+    /// Writes settings (`section.key`, None removes the key) into config.toml preserving
+    /// comments, layout and the inline comment of each replaced value. The edited file
+    /// must still load, otherwise nothing is written. Returns the previous values, in the
+    /// same form, to undo the change.
+    pub fn save_settings(path: &std::path::Path, settings: &[Setting]) -> Result<Vec<Setting>> {
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("Could not read {:?}", path))?;
         let mut doc = content
             .parse::<toml_edit::DocumentMut>()
             .context("Error parsing config.toml to save")?;
 
-        doc["dca"]["symbol"] = toml_edit::value(symbol);
-        doc["dca"]["quote_amount"] = toml_edit::value(amount);
+        let mut previous = Vec::new();
+        for (key, value) in settings {
+            let (section, name) = key.split_once('.').with_context(|| format!("'{}' is not section.key", key))?;
+            let table = doc
+                .entry(section)
+                .or_insert(toml_edit::table())
+                .as_table_like_mut()
+                .with_context(|| format!("[{}] is not a table", section))?;
+            let old = table.get(name).and_then(|item| item.as_value()).cloned();
+            match value {
+                Some(value) => {
+                    let mut value = value.clone();
+                    if let Some(old) = &old {
+                        *value.decor_mut() = old.decor().clone();
+                    }
+                    table.insert(name, toml_edit::Item::Value(value));
+                }
+                None => {
+                    table.remove(name);
+                }
+            }
+            previous.push((key.clone(), old));
+        }
 
-        std::fs::write(path, doc.to_string())
+        let edited = doc.to_string();
+        Self::parse(&edited).context("config.toml not saved")?;
+        std::fs::write(path, edited)
             .with_context(|| format!("Could not write {:?}", path))?;
-        Ok(())
+        previous.reverse();
+        Ok(previous)
+    }
+
+    /// Current values of `keys` as written in config.toml (None when missing)
+    pub fn read_settings(path: &std::path::Path, keys: &[&str]) -> Result<Vec<Option<toml_edit::Value>>> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read {:?}", path))?;
+        let doc = content
+            .parse::<toml_edit::DocumentMut>()
+            .context("Error parsing config.toml")?;
+        Ok(keys
+            .iter()
+            .map(|key| {
+                let (section, name) = key.split_once('.')?;
+                let value = doc.get(section)?.as_table_like()?.get(name)?.as_value()?;
+                let mut value = value.clone();
+                value.decor_mut().clear();
+                Some(value)
+            })
+            .collect())
+    }
+}
+
+/// One config.toml setting to write: `section.key` and its value (None = remove the key,
+/// back to the default)
+pub type Setting = (String, Option<toml_edit::Value>);
+
+/// Settings applied to the running bot when config.toml changes, as `section.key` or a
+/// whole section. Everything else is read only at startup and needs a restart
+const LIVE_SETTINGS: &[&str] = &[
+    "app.max_slots",
+    "dca",
+    "risk.max_daily_spend",
+    "risk.max_daily_spend_total",
+    "risk.amount_confirm_pct",
+    "risk.max_price_age_secs",
+    "risk.max_exposure",
+    "risk.equity_floor_usdt",
+    "risk.equity_floor_action",
+    "alerts.rolling_window",
+    "alerts.cooldown_minutes",
+    "groups",
+    "ui.theme",
+    "ui.display_currency",
+    "ui.timezone",
+    "ui.decimals",
+    "ui.language",
+    "notifications",
+    "fees",
+    "retention.alert_history",
+];
+
+/// Whether a changed setting (from `ConfigWatch::poll`) takes effect without a restart
+pub fn is_live_setting(key: &str) -> bool {
+    LIVE_SETTINGS.iter().any(|live| {
+        key == *live || key.strip_prefix(live).is_some_and(|rest| rest.starts_with('.'))
+    })
+}
+
+/// Polls config.toml for edits made while the bot runs
+pub struct ConfigWatch {
+    path: std::path::PathBuf,
+    modified: Option<std::time::SystemTime>,
+    /// Last version that parsed, to tell which settings an edit changed
+    table: toml::Table,
+}
+
+impl ConfigWatch {
+    /// Starts from the file as it is now
+    pub fn new(path: &std::path::Path) -> Self {
+        let table = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| content.parse().ok())
+            .unwrap_or_default();
+        Self { path: path.to_path_buf(), modified: modified_at(path), table }
+    }
+
+    /// When the file changed since the last poll: the new config and the settings that
+    /// differ, as `section.key` (empty when only comments changed). An edit that does not
+    /// parse or validate is reported once as an error and the running config stays.
+    pub fn poll(&mut self) -> Option<Result<(Config, Vec<String>)>> {
+        let modified = modified_at(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        let result = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Could not read {:?}", self.path))
+            .and_then(|content| {
+                let config = Config::parse(&content)?;
+                let table: toml::Table = content.parse().context("Error parsing config.toml")?;
+                Ok((config, table))
+            });
+        Some(result.map(|(config, table)| {
+            let changed = changed_settings(&self.table, &table);
+            self.table = table;
+            (config, changed)
+        }))
+    }
+}
+
+fn modified_at(path: &std::path::Path) -> Option<std::time::SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Keys that differ between two versions of config.toml: `section.key` inside tables,
+/// the bare name for top-level values and arrays of tables (`groups`)
+fn changed_settings(old: &toml::Table, new: &toml::Table) -> Vec<String> {
+    let empty = toml::Table::new();
+    let mut changed = Vec::new();
+    let sections: std::collections::BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for section in sections {
+        match (old.get(section), new.get(section)) {
+            (a, b) if a == b => {}
+            (Some(toml::Value::Table(_)) | None, Some(toml::Value::Table(_)) | None) => {
+                let a = old.get(section).and_then(|v| v.as_table()).unwrap_or(&empty);
+                let b = new.get(section).and_then(|v| v.as_table()).unwrap_or(&empty);
+                let keys: std::collections::BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+                changed.extend(keys.into_iter().filter(|k| a.get(*k) != b.get(*k)).map(|k| format!("{}.{}", section, k)));
+            }
+            _ => changed.push(section.clone()),
+        }
     }
+    changed
 }
@@ -0,0 +1,172 @@
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+
+use crate::api::client::BinanceClient;
+use crate::bundle;
+use crate::config::Config;
+use crate::state_file;
+use crate::strategy::dca::STATE_SCHEMA_VERSION;
+use crate::strategy::events::EventLog;
+
+/// Log lines included in the bundle (the most recent ones)
+const LOG_TAIL_LINES: usize = 500;
+/// Pings used for the latency stats
+const LATENCY_SAMPLES: usize = 5;
+
+/// Files read by the diagnostics generator
+pub struct DiagnosticFiles<'a> {
+    pub config: &'a Path,
+    pub state: &'a Path,
+    pub events: &'a Path,
+    pub log: &'a Path,
+    /// WebSocket capture (`websocket.record_minutes`), added when it exists
+    pub capture: &'a Path,
+    /// Session counters (`stats.json`), added when it exists
+    pub stats: &'a Path,
+}
+
+/// Writes a zip meant to be attached to GitHub issues: a plain-text report
+/// (versions, OS/terminal, state schema, API latency), the config without API keys and
+/// the tail of the log with amounts and balances masked, plus the WebSocket capture if
+/// frames are being recorded and the session counters.
+pub async fn generate(files: &DiagnosticFiles<'_>, out: &Path) -> Result<()> {
+    let raw_config = std::fs::read_to_string(files.config).unwrap_or_default();
+    let config: Option<Config> = toml::from_str(&raw_config).ok();
+    let secrets: Vec<String> = config
+        .iter()
+        .flat_map(|c| [c.binance.api_key.clone(), c.binance.api_secret.clone()])
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let mut report = String::new();
+    writeln!(report, "Trading View diagnostics")?;
+    writeln!(report, "generated:      {}", chrono::Utc::now().to_rfc3339())?;
+    writeln!(report, "version:        {}", env!("CARGO_PKG_VERSION"))?;
+    writeln!(report, "os / arch:      {} / {}", std::env::consts::OS, std::env::consts::ARCH)?;
+    for var in ["TERM", "COLORTERM", "TERM_PROGRAM", "WT_SESSION"] {
+        if let Ok(v) = std::env::var(var) {
+            writeln!(report, "{:<15} {}", format!("{}:", var), v)?;
+        }
+    }
+    match crossterm::terminal::size() {
+        Ok((w, h)) => writeln!(report, "terminal size:  {}x{}", w, h)?,
+        Err(e) => writeln!(report, "terminal size:  unknown ({})", e)?,
+    }
+
+    writeln!(report)?;
+    writeln!(report, "state schema:   v{}", STATE_SCHEMA_VERSION)?;
+    match state_file::load(files.state) {
+        Ok(_) if !files.state.exists() => writeln!(report, "state file:     none")?,
+        Ok(snaps) => writeln!(report, "state file:     OK, {} slot(s)", snaps.len())?,
+        Err(e) => writeln!(report, "state file:     unreadable ({})", e)?,
+    }
+    writeln!(report, "event log:      {} event(s)", EventLog::load(files.events).len())?;
+
+    writeln!(report)?;
+    match &config {
+        Some(c) => {
+            writeln!(report, "network:        {}", if c.binance.testnet { "testnet" } else { "mainnet" })?;
+            write_latency(&mut report, c).await?;
+        }
+        None => writeln!(report, "config:         could not be parsed, API checks skipped")?,
+    }
+
+    let log = std::fs::read_to_string(files.log).unwrap_or_default();
+    let lines: Vec<&str> = log.lines().collect();
+    let tail = lines[lines.len().saturating_sub(LOG_TAIL_LINES)..]
+        .iter()
+        .map(|l| redact_line(l, &secrets))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let file = std::fs::File::create(out).with_context(|| format!("Could not create {:?}", out))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+    zip.start_file("report.txt", options)?;
+    zip.write_all(report.as_bytes())?;
+    if !raw_config.is_empty() {
+        zip.start_file("config.toml", options)?;
+        zip.write_all(bundle::redact_config(&raw_config)?.as_bytes())?;
+    }
+    zip.start_file("tradingbot.log", options)?;
+    zip.write_all(tail.as_bytes())?;
+    // Public market data only: the capture needs no redaction
+    if let Ok(capture) = std::fs::read(files.capture) {
+        zip.start_file("ws_capture.jsonl", options)?;
+        zip.write_all(&capture)?;
+    }
+    // Counts and timestamps only, nothing to redact
+    if let Ok(stats) = std::fs::read(files.stats) {
+        zip.start_file("stats.json", options)?;
+        zip.write_all(&stats)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Pings Binance a few times and reports min/avg/max round-trip and the clock offset
+async fn write_latency(report: &mut String, config: &Config) -> Result<()> {
+    let client = match BinanceClient::new(config.binance.clone()) {
+        Ok(c) => c,
+        Err(e) => {
+            writeln!(report, "api latency:    client error ({})", e)?;
+            return Ok(());
+        }
+    };
+
+    let mut samples: Vec<Duration> = Vec::new();
+    let mut errors = 0;
+    for _ in 0..LATENCY_SAMPLES {
+        let start = Instant::now();
+        match client.ping().await {
+            Ok(()) => samples.push(start.elapsed()),
+            Err(_) => errors += 1,
+        }
+    }
+    if samples.is_empty() {
+        writeln!(report, "api latency:    unreachable ({} errors)", errors)?;
+    } else {
+        let ms = |d: &Duration| d.as_secs_f64() * 1000.0;
+        let min = samples.iter().map(ms).fold(f64::MAX, f64::min);
+        let max = samples.iter().map(ms).fold(0.0, f64::max);
+        let avg = samples.iter().map(ms).sum::<f64>() / samples.len() as f64;
+        writeln!(
+            report,
+            "api latency:    min {:.0}ms / avg {:.0}ms / max {:.0}ms ({} ok, {} errors)",
+            min, avg, max, samples.len(), errors
+        )?;
+    }
+    match client.sync_time().await {
+        Ok(()) => writeln!(report, "clock offset:   {}ms", client.time_offset_ms())?,
+        Err(e) => writeln!(report, "clock offset:   unknown ({})", e)?,
+    }
+    Ok(())
+}
+
+/// Masks API secrets, `$` amounts and every number on balance lines
+fn redact_line(line: &str, secrets: &[String]) -> String {
+    let mut line = line.to_string();
+    for secret in secrets {
+        line = line.replace(secret.as_str(), "***");
+    }
+    let mask_all = line.to_lowercase().contains("balance");
+    let mut out = String::with_capacity(line.len());
+    let mut masking = false;
+    for c in line.chars() {
+        let numeric = c.is_ascii_digit() || (masking && (c == '.' || c == ','));
+        if numeric && (masking || mask_all || out.ends_with('$')) {
+            if !masking {
+                out.push_str("***");
+                masking = true;
+            }
+            continue;
+        }
+        masking = false;
+        out.push(c);
+    }
+    out
+}
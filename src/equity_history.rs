@@ -0,0 +1,92 @@
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Minimum time between two samples: the balance refresh runs every 30 seconds, the history
+/// only needs the curve
+const SAMPLE_MINUTES: i64 = 5;
+
+/// Account equity at one moment, valued in USDT
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquitySample {
+    pub at: DateTime<Utc>,
+    pub equity: f64,
+}
+
+/// Append-only equity curve of the account (one JSON sample per line). Every method does
+/// blocking file I/O behind one lock, so call it from `spawn_blocking`.
+pub struct EquityHistory {
+    path: PathBuf,
+    /// Time of the newest sample on disk; its lock also keeps a rewrite from racing an append
+    last: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl EquityHistory {
+    pub fn open(path: &Path) -> Self {
+        let last = Self::load(path).last().map(|s| s.at);
+        Self { path: path.to_path_buf(), last: Mutex::new(last) }
+    }
+
+    /// Every sample in the file at `path`, oldest first (unreadable lines are skipped)
+    pub fn load(path: &Path) -> Vec<EquitySample> {
+        match std::fs::File::open(path) {
+            Ok(file) => std::io::BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| serde_json::from_str(&line).ok())
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Appends a sample unless the previous one is less than `SAMPLE_MINUTES` old. Returns
+    /// whether it was written.
+    pub fn record(&self, at: DateTime<Utc>, equity: f64) -> Result<bool> {
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        if last.is_some_and(|t| at - t < Duration::minutes(SAMPLE_MINUTES)) {
+            return Ok(false);
+        }
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let mut line = serde_json::to_string(&EquitySample { at, equity })?;
+        line.push('\n');
+        file.write_all(line.as_bytes())?;
+        *last = Some(at);
+        Ok(true)
+    }
+
+    /// Keeps only the last sample of each UTC day among the ones older than `before` and
+    /// rewrites the file. Returns how many were dropped.
+    pub fn downsample(&self, before: DateTime<Utc>) -> Result<usize> {
+        let _last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        let samples = Self::load(&self.path);
+        let total = samples.len();
+        let mut kept: Vec<EquitySample> = Vec::with_capacity(total);
+        for sample in samples {
+            if let Some(prev) = kept.last_mut() {
+                // Samples are in time order: a later one of the same old day replaces it
+                if prev.at < before && sample.at < before && prev.at.date_naive() == sample.at.date_naive() {
+                    *prev = sample;
+                    continue;
+                }
+            }
+            kept.push(sample);
+        }
+        if kept.len() == total {
+            return Ok(0);
+        }
+        let mut out = String::new();
+        for sample in &kept {
+            out.push_str(&serde_json::to_string(sample)?);
+            out.push('\n');
+        }
+        // Through a temporary file: a crash mid-write must not lose the history
+        let tmp = self.path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, out)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(total - kept.len())
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::config::Direction;
+use crate::strategy::cycles::CycleRecord;
+use crate::strategy::events::{SlotEvent, SlotEventKind};
+
+/// One fill of the trade ledger, as exported for spreadsheets and tax tools
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeRow {
+    /// Sequence of the event that recorded it
+    pub seq: u64,
+    pub slot_id: usize,
+    pub symbol: String,
+    pub timestamp: DateTime<Utc>,
+    /// BUY or SELL
+    pub side: &'static str,
+    /// ENTRY, PARTIAL EXIT or the exit that closed the cycle (TAKE PROFIT, STOP LOSS, ...)
+    pub kind: String,
+    /// Average fill price
+    pub price: f64,
+    /// Base quantity
+    pub quantity: f64,
+    /// Quote spent (BUY) or received (SELL)
+    pub quote: f64,
+    /// Realized P&L in the slot's quote (exits only)
+    pub pnl: Option<f64>,
+    pub fee: Option<f64>,
+    pub fee_asset: Option<String>,
+    /// Binance order ID (entries only; exits are not linked to their order in the log)
+    pub order_id: Option<u64>,
+}
+
+/// Fills recorded in `events`, oldest first. Merged duplicates of an entry appear once, with
+/// the merged figures. `directions` covers slots whose `Created` event is no longer in the
+/// log.
+pub fn trades(
+    events: &[SlotEvent],
+    directions: impl IntoIterator<Item = (usize, Direction)>,
+) -> Vec<TradeRow> {
+    let mut directions: HashMap<usize, Direction> = directions.into_iter().collect();
+    let mut rows: Vec<TradeRow> = Vec::new();
+    for event in events {
+        let id = event.slot_id;
+        let direction = directions.get(&id).cloned().unwrap_or_default();
+        let (entry_side, exit_side) = match direction {
+            Direction::Long => ("BUY", "SELL"),
+            Direction::Short => ("SELL", "BUY"),
+        };
+        let row = |timestamp, side, kind: &str, quantity: f64, quote: f64| TradeRow {
+            seq: event.seq,
+            slot_id: id,
+            symbol: event.symbol.clone(),
+            timestamp,
+            side,
+            kind: kind.to_string(),
+            price: if quantity > 0.0 { quote / quantity } else { 0.0 },
+            quantity,
+            quote,
+            pnl: None,
+            fee: None,
+            fee_asset: None,
+            order_id: None,
+        };
+        match &event.kind {
+            // Followed as the log goes, so a flip does not relabel the fills before it
+            SlotEventKind::Created { config } => {
+                directions.insert(id, config.direction.clone());
+            }
+            SlotEventKind::ConfigChanged { field, value } if field == "direction" => {
+                if let Ok(direction) = serde_json::from_value(value.clone()) {
+                    directions.insert(id, direction);
+                }
+            }
+            SlotEventKind::EntryRecorded { trade, .. } => {
+                let mut entry = row(trade.timestamp, entry_side, "ENTRY", trade.quantity, trade.cost);
+                entry.price = trade.buy_price;
+                entry.fee = trade.commission.as_ref().map(|c| c.amount);
+                entry.fee_asset = trade.commission.as_ref().map(|c| c.asset.clone());
+                // Imported and watched positions have no Binance order
+                entry.order_id = Some(trade.order_id).filter(|id| *id != 0);
+                rows.push(entry);
+            }
+            SlotEventKind::TradesMerged { order_id, kept } => {
+                let merged = |r: &TradeRow| r.slot_id == id && r.order_id == Some(*order_id);
+                let Some(first) = rows.iter().position(merged) else { continue };
+                let seq = rows[first].seq;
+                rows.retain(|r| !merged(r));
+                let mut entry = row(kept.timestamp, entry_side, "ENTRY", kept.quantity, kept.cost);
+                entry.seq = seq;
+                entry.price = kept.buy_price;
+                entry.fee = kept.commission.as_ref().map(|c| c.amount);
+                entry.fee_asset = kept.commission.as_ref().map(|c| c.asset.clone());
+                entry.order_id = Some(*order_id);
+                rows.insert(first.min(rows.len()), entry);
+            }
+            SlotEventKind::PartialExit { quantity, received, pnl, commission, .. } => {
+                let mut exit = row(event.timestamp, exit_side, "PARTIAL EXIT", *quantity, *received);
+                exit.pnl = Some(*pnl);
+                exit.fee = commission.as_ref().map(|c| c.amount);
+                exit.fee_asset = commission.as_ref().map(|c| c.asset.clone());
+                rows.push(exit);
+            }
+            SlotEventKind::ExitExecuted { kind, quantity, received, pnl, commission } => {
+                let mut exit = row(event.timestamp, exit_side, kind, *quantity, *received);
+                exit.pnl = Some(*pnl);
+                exit.fee = commission.as_ref().map(|c| c.amount);
+                exit.fee_asset = commission.as_ref().map(|c| c.asset.clone());
+                rows.push(exit);
+            }
+            _ => {}
+        }
+    }
+    rows
+}
+
+/// Writes `<name>-<timestamp>.csv` and `.json` for the trades and, when there are any,
+/// `cycles-<timestamp>.csv` and `.json` for the cycle journal (timestamp in UTC). Returns the
+/// files written.
+pub fn export(dir: &Path, name: &str, trades: &[TradeRow], cycles: &[CycleRecord]) -> Result<Vec<PathBuf>> {
+    // UTC, like the diagnostics bundle name and the timestamps inside the files
+    let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+    let mut written = Vec::new();
+
+    let stem = dir.join(format!("{}-{}", name, stamp));
+    written.push(write(&stem, "csv", trades_csv(trades))?);
+    written.push(write(&stem, "json", serde_json::to_string_pretty(trades)?)?);
+
+    if !cycles.is_empty() {
+        let stem = dir.join(format!("cycles-{}", stamp));
+        written.push(write(&stem, "csv", cycles_csv(cycles))?);
+        written.push(write(&stem, "json", serde_json::to_string_pretty(cycles)?)?);
+    }
+    Ok(written)
+}
+
+fn write(stem: &Path, extension: &str, content: String) -> Result<PathBuf> {
+    let path = stem.with_extension(extension);
+    std::fs::write(&path, content).with_context(|| format!("Could not write {:?}", path))?;
+    Ok(path)
+}
+
+fn trades_csv(trades: &[TradeRow]) -> String {
+    let mut out = String::from("seq,slot_id,symbol,timestamp,side,kind,price,quantity,quote,pnl,fee,fee_asset,order_id\n");
+    for t in trades {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            t.seq,
+            t.slot_id,
+            field(&t.symbol),
+            t.timestamp.to_rfc3339(),
+            t.side,
+            field(&t.kind),
+            t.price,
+            t.quantity,
+            t.quote,
+            optional(t.pnl),
+            optional(t.fee),
+            field(t.fee_asset.as_deref().unwrap_or("")),
+            optional(t.order_id),
+        );
+    }
+    out
+}
+
+fn cycles_csv(cycles: &[CycleRecord]) -> String {
+    let mut out = String::from("seq,slot_id,symbol,direction,exit,opened_at,closed_at,invested,received,pnl,fees\n");
+    for c in cycles {
+        // Several fee assets fit in one column as "0.1 BNB; 0.05 USDT"
+        let fees: Vec<String> = c.fees.iter().map(|(asset, amount)| format!("{} {}", amount, asset)).collect();
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            c.seq,
+            c.slot_id,
+            field(&c.symbol),
+            match c.direction {
+                Direction::Long => "long",
+                Direction::Short => "short",
+            },
+            field(&c.exit),
+            c.opened_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            c.closed_at.to_rfc3339(),
+            c.invested,
+            c.received,
+            c.pnl,
+            field(&fees.join("; ")),
+        );
+    }
+    out
+}
+
+/// Empty for None, so spreadsheets read a blank cell instead of text
+fn optional<T: std::fmt::Display>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Quotes a text cell when it holds a separator, a quote or a line break
+fn field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,280 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::client::BinanceClient;
+use crate::models::order::{Order, OrderSide, OrderStatus};
+use crate::models::symbol::SymbolFilters;
+use crate::orders::POLL_SECS;
+
+/// Each rung of a geometric ladder is this many times the previous one
+const GEOMETRIC_RATIO: f64 = 1.5;
+
+/// How the total amount is spread across the rungs, from the one nearest the market to
+/// the farthest (lowest buy / highest sell)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LadderSizing {
+    /// Sizes grow by the same step: 1, 2, 3...
+    #[default]
+    Linear,
+    /// Sizes grow by `GEOMETRIC_RATIO` each rung
+    Geometric,
+}
+
+impl LadderSizing {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LadderSizing::Linear => "linear",
+            LadderSizing::Geometric => "geometric",
+        }
+    }
+
+    fn weight(&self, rung: usize) -> f64 {
+        match self {
+            LadderSizing::Linear => (rung + 1) as f64,
+            LadderSizing::Geometric => GEOMETRIC_RATIO.powi(rung as i32),
+        }
+    }
+}
+
+impl std::str::FromStr for LadderSizing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(LadderSizing::Linear),
+            "geometric" => Ok(LadderSizing::Geometric),
+            _ => Err(format!("Unknown ladder sizing '{}' (linear or geometric)", s)),
+        }
+    }
+}
+
+/// One limit order of a ladder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LadderRung {
+    pub price: f64,
+    pub quantity: f64,
+    /// None until placed (or when placing it failed)
+    pub order_id: Option<u64>,
+    pub filled_qty: f64,
+    pub filled_quote: f64,
+    /// Off the book: filled, cancelled, expired or never placed
+    pub closed: bool,
+}
+
+impl LadderRung {
+    pub fn resting(&self) -> bool {
+        self.order_id.is_some() && !self.closed
+    }
+
+    fn update(&mut self, order: &Order) {
+        self.filled_qty = order.executed_qty.parse().unwrap_or(self.filled_qty);
+        self.filled_quote = order.cummulative_quote_qty.parse().unwrap_or(self.filled_quote);
+        self.closed = is_final(&order.status);
+    }
+}
+
+/// Limit orders spread between two prices, placed at once and tracked until they fill.
+/// The filled part can be handed to a DCA slot, which then manages the exit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ladder {
+    pub id: u64,
+    pub symbol: String,
+    pub side: OrderSide,
+    /// Quote amount spread across the rungs
+    pub total: f64,
+    pub sizing: LadderSizing,
+    /// Nearest rung to the market first
+    pub rungs: Vec<LadderRung>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Ladder {
+    /// Spreads `total` quote over `count` rungs evenly spaced between `low` and `high`.
+    /// Buys start at `high` and go down, sells start at `low` and go up; the farther
+    /// rungs get the bigger share. Fails when a rung is below the symbol's minimums.
+    #[allow(clippy::too_many_arguments)]
+    pub fn plan(
+        id: u64,
+        side: OrderSide,
+        total: f64,
+        low: f64,
+        high: f64,
+        count: usize,
+        sizing: LadderSizing,
+        filters: &SymbolFilters,
+    ) -> Result<Self> {
+        anyhow::ensure!(count >= 2, "a ladder needs at least 2 orders");
+        anyhow::ensure!(low > 0.0 && high > low, "the price range must be low < high");
+        let (near, far) = match side {
+            OrderSide::Buy => (high, low),
+            OrderSide::Sell => (low, high),
+        };
+        let weights: f64 = (0..count).map(|i| sizing.weight(i)).sum();
+        let rungs = (0..count)
+            .map(|i| {
+                let price = filters.round_price(near + (far - near) * i as f64 / (count - 1) as f64);
+                let quote = total * sizing.weight(i) / weights;
+                let quantity = filters.compliant_qty(quote / price, Some(price))?;
+                Ok(LadderRung { price, quantity, order_id: None, filled_qty: 0.0, filled_quote: 0.0, closed: false })
+            })
+            .collect::<Result<Vec<_>>>()
+            .context("Ladder order too small, use a bigger amount or fewer orders")?;
+        Ok(Self { id, symbol: filters.symbol.clone(), side, total, sizing, rungs, created_at: Utc::now() })
+    }
+
+    pub fn filled_qty(&self) -> f64 {
+        self.rungs.iter().map(|r| r.filled_qty).sum()
+    }
+
+    pub fn filled_quote(&self) -> f64 {
+        self.rungs.iter().map(|r| r.filled_quote).sum()
+    }
+
+    /// Average fill price (0 while nothing filled)
+    pub fn average_price(&self) -> f64 {
+        let qty = self.filled_qty();
+        if qty > 0.0 { self.filled_quote() / qty } else { 0.0 }
+    }
+
+    /// Rungs fully filled
+    pub fn filled_rungs(&self) -> usize {
+        self.rungs.iter().filter(|r| r.closed && r.filled_qty >= r.quantity).count()
+    }
+
+    pub fn resting_rungs(&self) -> usize {
+        self.rungs.iter().filter(|r| r.resting()).count()
+    }
+
+    /// Price range, nearest rung first
+    pub fn range(&self) -> (f64, f64) {
+        let first = self.rungs.first().map(|r| r.price).unwrap_or(0.0);
+        let last = self.rungs.last().map(|r| r.price).unwrap_or(0.0);
+        (first, last)
+    }
+}
+
+/// Ladders placed from the palette, persisted as JSON so their orders are still tracked
+/// after a restart
+#[derive(Debug, Default)]
+pub struct LadderBook {
+    ladders: Vec<Ladder>,
+    path: Option<PathBuf>,
+    last_poll: Option<DateTime<Utc>>,
+}
+
+impl LadderBook {
+    /// Loads the book saved at `path` (empty when the file does not exist yet)
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let ladders = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .with_context(|| format!("Invalid ladders file {:?}", path))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("Could not read {:?}", path)),
+        };
+        Ok(Self { ladders, path: Some(path), last_poll: None })
+    }
+
+    pub fn ladders(&self) -> &[Ladder] {
+        &self.ladders
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Ladder> {
+        self.ladders.iter().find(|l| l.id == id)
+    }
+
+    pub fn next_id(&self) -> u64 {
+        self.ladders.iter().map(|l| l.id).max().unwrap_or(0) + 1
+    }
+
+    /// Adds a ladder or replaces the one with the same ID
+    pub fn upsert(&mut self, ladder: Ladder) {
+        match self.ladders.iter_mut().find(|l| l.id == ladder.id) {
+            Some(existing) => *existing = ladder,
+            None => self.ladders.push(ladder),
+        }
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<Ladder> {
+        let index = self.ladders.iter().position(|l| l.id == id)?;
+        Some(self.ladders.remove(index))
+    }
+
+    /// Ladders with resting orders to check now; empty until `POLL_SECS` have passed
+    pub fn due(&mut self, now: DateTime<Utc>) -> Vec<Ladder> {
+        let active: Vec<Ladder> = self.ladders.iter().filter(|l| l.resting_rungs() > 0).cloned().collect();
+        if active.is_empty() || self.last_poll.is_some_and(|t| (now - t).num_seconds() < POLL_SECS) {
+            return Vec::new();
+        }
+        self.last_poll = Some(now);
+        active
+    }
+
+    /// File and contents to save, written by the state writer away from the app state lock
+    /// (None for a book without a file)
+    pub fn contents(&self) -> Result<Option<(PathBuf, String)>> {
+        let Some(path) = &self.path else { return Ok(None) };
+        Ok(Some((path.clone(), serde_json::to_string_pretty(&self.ladders)?)))
+    }
+}
+
+/// Places every rung not placed yet. Returns the errors of the rungs that failed, which
+/// are left closed and unfilled.
+pub async fn place(client: &BinanceClient, ladder: &mut Ladder) -> Vec<String> {
+    let mut errors = Vec::new();
+    for (i, rung) in ladder.rungs.iter_mut().enumerate().filter(|(_, r)| r.order_id.is_none() && !r.closed) {
+        let result = match ladder.side {
+            OrderSide::Buy => client.limit_buy(&ladder.symbol, rung.quantity, rung.price, None).await,
+            OrderSide::Sell => client.limit_sell(&ladder.symbol, rung.quantity, rung.price, None).await,
+        };
+        match result {
+            Ok(order) => {
+                rung.order_id = Some(order.order_id);
+                rung.update(&order);
+            }
+            Err(e) => {
+                rung.closed = true;
+                errors.push(format!("order {} @ ${:.4}: {:#}", i + 1, rung.price, e));
+            }
+        }
+    }
+    errors
+}
+
+/// Refreshes the fills of the resting rungs: one GET /api/v3/openOrders for the symbol,
+/// plus GET /api/v3/order for the rungs no longer on the book
+pub async fn sync(client: &BinanceClient, ladder: &mut Ladder) -> Result<()> {
+    let open = client.open_orders(&ladder.symbol).await?;
+    for rung in ladder.rungs.iter_mut().filter(|r| r.resting()) {
+        let Some(order_id) = rung.order_id else { continue };
+        match open.iter().find(|o| o.order_id == order_id) {
+            Some(order) => rung.update(order),
+            None => rung.update(&client.get_order(&ladder.symbol, order_id).await?),
+        }
+    }
+    Ok(())
+}
+
+/// Cancels the resting rungs and records what they filled before the cancel
+pub async fn cancel(client: &BinanceClient, ladder: &mut Ladder) -> Result<()> {
+    for rung in ladder.rungs.iter_mut().filter(|r| r.resting()) {
+        let Some(order_id) = rung.order_id else { continue };
+        client
+            .cancel_order(&ladder.symbol, order_id)
+            .await
+            .with_context(|| format!("Cancel ladder order {} [{}] failed", order_id, ladder.symbol))?;
+        rung.update(&client.get_order(&ladder.symbol, order_id).await?);
+        rung.closed = true;
+    }
+    Ok(())
+}
+
+fn is_final(status: &OrderStatus) -> bool {
+    matches!(
+        status,
+        OrderStatus::Filled | OrderStatus::Canceled | OrderStatus::Expired | OrderStatus::Rejected
+    )
+}
@@ -1,34 +1,149 @@
 mod api;
 mod app;
+mod bundle;
+mod cli;
 mod config;
+mod diagnostics;
+mod equity_history;
+mod journal;
+mod ladder;
+mod market_data;
 mod models;
+mod orders;
+mod position_import;
+mod presets;
+mod profiling;
+mod selftest;
+mod session_stats;
+mod state_file;
 mod strategy;
+mod triggers;
 mod ui;
+mod valuation;
 
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
+use clap::Parser;
 use tokio::sync::{mpsc, watch, Mutex};
 
 use api::client::BinanceClient;
 use api::websocket;
-use app::{AlertLevel, AppCommand, AppState, DEFAULT_SYMBOLS, SaleResult, StrategySlot, UiMode, MAX_SLOTS};
-use config::{AlertsConfig, Config, Direction, DcaConfig};
-use models::ticker::MiniTickerEvent;
+use app::{AlertLevel, AppCommand, AppState, ConfigChange, DEFAULT_SYMBOLS, EDIT_SLOT_FIELDS, NEW_STRAT_FIELDS, PresetInput, RestoreIssue, SaleResult, SETTINGS_FIELDS, StrategySlot, StrategyTemplate, UiMode};
+use config::{AlertsConfig, Config, Direction, DcaConfig, EntryOrderType, EquityFloorAction, NotifyEvent, QuoteAmount, StrategyKind, TakeProfit};
+use models::order::{AccountTrade, Commission, EntryTrigger, ExitReason, OrderSide, OrderStatus, PendingExit};
+use models::symbol::BelowMinimum;
+use models::ticker::{AggTradeEvent, KlineEvent, PartialDepth};
+use orders::{EntryIntent, ManagedOrder, OrderManager, OrderUpdate};
+use strategy::cycles::CycleHistory;
 use strategy::dca::{DcaState, DcaStrategy, StrategySnapshot};
+use strategy::events::{EventLog, SlotEvent, SlotEventKind};
+use strategy::fees::CommissionRates;
+use strategy::performance::SlotPerformance;
+use strategy::{groups, sizing};
+use ladder::{Ladder, LadderBook};
+use presets::{Preset, PresetBook};
+use session_stats::SessionStats;
+use triggers::TriggerBook;
+use ui::palette;
 use ui::tui::Tui;
 
+/// Trades de @aggTrade que se aplican al volumen con un solo lock
+const TRADE_BATCH: usize = 256;
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    // --config / --testnet se aplican cada vez que se lee config.toml (también al recargar)
+    let cli = cli::Cli::parse();
+    config::set_overrides(config::Overrides {
+        config_path: cli.config.clone(),
+        testnet: cli.testnet,
+        profile: cli.profile.clone(),
+    });
+
+    // Ruta del log, del archivo de estado persistente y del event log (uno por --profile)
+    let log_path = config::data_path("tradingbot.log");
+    let state_path = config::data_path("strategy_state.json");
+    let events_path = config::data_path("slot_events.jsonl");
+    let cycles_path = config::data_path("cycle_history.jsonl");
+    let equity_path = config::data_path("equity_history.jsonl");
+    let triggers_path = config::data_path("conditional_orders.json");
+    let ladders_path = config::data_path("ladders.json");
+    let capture_path = config::data_path("ws_capture.jsonl");
+    let stats_path = config::data_path("stats.json");
+
+    // Comandos de línea (sin abrir el TUI). Van antes de crear el log para no pisar
+    // el de la última sesión.
+    if let Some(cmd) = cli.command() {
+        let config_path = Config::path();
+        let files = bundle::BotFiles {
+            config: &config_path,
+            state: &state_path,
+            events: &events_path,
+            cycles: &cycles_path,
+            equity: &equity_path,
+        };
+        let result = match cmd {
+            cli::Command::Export(out) => bundle::export(&files, &out)
+                .map(|_| format!("Bundle exported to {} (API keys removed)", out.display())),
+            cli::Command::Import(file) => bundle::import(&file, &files)
+                .map(|_| format!("Bundle {} imported (previous files kept as *.bak)", file.display())),
+            cli::Command::ExportTrades(dir) => {
+                let dir = dir.unwrap_or_else(config::exe_dir);
+                let events = EventLog::load(&events_path);
+                // Dirección de los slots cuyo Created ya se podó del log
+                let directions: Vec<_> = state_file::load(&state_path)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|snap| Some((snap.slot_id?, snap.direction)))
+                    .collect();
+                let (cycles, _) = CycleHistory::open(&cycles_path, &events, directions.clone());
+                let trades = journal::trades(&events, directions);
+                journal::export(&dir, "trades", &trades, cycles.records()).map(|files| {
+                    let names: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+                    format!("{} trades and {} cycles exported: {}", trades.len(), cycles.records().len(), names.join(", "))
+                })
+            }
+            cli::Command::Diagnostics(out) => {
+                let out = out.unwrap_or_else(|| {
+                    format!("diagnostics-{}.zip", chrono::Utc::now().format("%Y%m%d-%H%M%S")).into()
+                });
+                let files = diagnostics::DiagnosticFiles {
+                    config: &config_path,
+                    state: &state_path,
+                    events: &events_path,
+                    log: &log_path,
+                    capture: &capture_path,
+                    stats: &stats_path,
+                };
+                diagnostics::generate(&files, &out)
+                    .await
+                    .map(|_| format!("Diagnostics written to {} (keys and balances redacted)", out.display()))
+            }
+            // Herramienta de desarrollo: pasa una captura del WebSocket por el parser
+            cli::Command::ReplayWs(file) => {
+                let file = file.unwrap_or_else(|| capture_path.clone());
+                api::recording::replay(&file)
+            }
+            // Verificación completa antes de confiarle dinero al bot
+            cli::Command::SelfTest => selftest::run(&state_path, &events_path).await,
+        };
+        match result {
+            Ok(msg) => println!("✓ {}", msg),
+            Err(e) => {
+                eprintln!("❌ {:#}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
     // Redirigir logs a archivo junto al ejecutable, para no interferir con el TUI
-    let log_path = config::exe_dir().join("tradingbot.log");
+    // Con la feature `profiling` también se registran los tiempos de los spans
     let log_file = std::fs::File::create(&log_path)?;
-    tracing_subscriber::fmt()
-        .with_writer(log_file)
-        .with_ansi(false)
-        .init();
+    let _profiling = profiling::init(log_file, &config::exe_dir())?;
 
     tracing::info!("Starting Trading View...");
 
@@ -37,16 +152,45 @@ async fn main() -> Result<()> {
         Ok(c) => c,
         Err(e) => {
             eprintln!("\n❌ Configuration error:\n   {}\n", e);
-            eprintln!("📝 Edit config.toml with your Binance API keys");
+            eprintln!(
+                "📝 Edit config.toml with your Binance API keys (or set {} / {})",
+                config::API_KEY_ENV,
+                config::API_SECRET_ENV
+            );
             std::process::exit(1);
         }
     };
 
-    // Ruta del archivo de estado persistente
-    let state_path = config::exe_dir().join("strategy_state.json");
+    app::set_display_format(&config.ui);
+    ui::i18n::set_language(config.ui.language);
+
+    if cli.testnet {
+        tracing::info!("--testnet: using the Binance testnet");
+    }
+    if let Some(profile) = &cli.profile {
+        tracing::info!("Profile '{}': {:?}", profile, config_path);
+    }
+
+    // Contadores de la sesión sobre los acumulados de las anteriores (stats.json)
+    let stats = SessionStats::open(&stats_path);
+    if let Some(previous) = &stats.previous {
+        tracing::info!(
+            "Session #{}: previous one ran {}s until {} ({} order(s) placed, {} failed, {} API error(s))",
+            stats.sessions(),
+            previous.counters.uptime_secs,
+            previous.updated_at.to_rfc3339(),
+            previous.counters.orders_placed,
+            previous.counters.orders_failed,
+            previous.counters.api_errors
+        );
+    }
 
     // Crear cliente REST de Binance
-    let client = Arc::new(BinanceClient::new(config.binance.clone())?);
+    let client = Arc::new(
+        BinanceClient::new(config.binance.clone())?
+            .with_audit_log(config::data_path("orders_audit.jsonl"))
+            .with_counters(Arc::clone(&stats.api)),
+    );
 
     // Test de conectividad
     client.ping().await.map_err(|e| {
@@ -59,10 +203,18 @@ async fn main() -> Result<()> {
         anyhow::anyhow!("Could not synchronize time with Binance: {}", e)
     })?;
 
-    // Obtener lista de pares USDT disponibles en Binance (mainnet o testnet)
-    let available_symbols: Vec<String> = match client.get_usdt_symbols().await {
+    // Obtener lista de pares disponibles en Binance (mainnet o testnet) para las quotes
+    // de `[app] quote_assets`
+    let quote_assets: Vec<String> = config.app.quote_assets.iter().map(|q| q.trim().to_uppercase()).collect();
+    let available_symbols: Vec<String> = match client.get_symbols(&quote_assets).await {
         Ok(syms) if !syms.is_empty() => {
-            tracing::info!("{} USDT pairs obtained from Binance", syms.len());
+            tracing::info!("{} pairs quoted in {} obtained from Binance", syms.len(), quote_assets.join("/"));
+            // Con la lista real se verifica que el par de [dca] exista en el exchange
+            let problems = config.check_symbols(&syms);
+            if !problems.is_empty() {
+                eprintln!("\n❌ Configuration error:\n   {}\n", problems.join("\n   "));
+                std::process::exit(1);
+            }
             syms
         }
         Ok(_) | Err(_) => {
@@ -71,61 +223,103 @@ async fn main() -> Result<()> {
         }
     };
 
-    // Cargar snapshots anteriores
-    let snapshots = load_snapshots(&state_path);
-
-    // Crear los slots iniciales
-    let mut slots: Vec<StrategySlot> = Vec::new();
-    let mut next_id = 0usize;
-    let mut restore_info: Vec<(String, Direction, usize, bool)> = Vec::new();
-
-    if !snapshots.is_empty() {
-        // Restaurar desde snapshots previos
-        for snap in &snapshots {
-            if slots.len() >= MAX_SLOTS {
-                break;
-            }
-            let (base, quote) = parse_symbol(&snap.symbol);
-            let mut strat_config = config.dca.clone();
-            strat_config.symbol = snap.symbol.clone();
-            strat_config.direction = snap.direction.clone();
-            let mut strat = DcaStrategy::new(strat_config);
-            let trade_count = snap.trades.len();
-            strat.restore_from_snapshot(snap.clone());
-
-            restore_info.push((snap.symbol.clone(), snap.direction.clone(), trade_count, strat.state.is_active()));
-
-            slots.push(StrategySlot {
-                id: next_id,
-                strategy: strat,
-                symbol: snap.symbol.clone(),
-                base_asset: base,
-                quote_asset: quote,
-                base_balance: 0.0,
-                quote_balance: 0.0,
-            });
+    // Cargar snapshots anteriores (checkpoint) + eventos posteriores del log
+    let events = EventLog::load(&events_path);
+    // Archivo dañado o con checksum que no coincide: se sigue desde el backup válido más
+    // reciente y los eventos posteriores del log. Sin backup decide el usuario en el modal
+    let mut state_fallback = None;
+    let (snapshots, recovery) = match state_file::load(&state_path) {
+        Ok(snapshots) => (snapshots, None),
+        Err(error) => {
+            tracing::error!("State file {:?} unreadable: {}", state_path, error);
+            let recovery = state_recovery(&state_path, error);
+            let restored = recovery
+                .backup
+                .as_ref()
+                .and_then(|b| Some((b.path.clone(), state_file::load(&b.path).ok()?)));
+            match restored {
+                Some((backup, snapshots)) => {
+                    state_fallback = Some(format!(
+                        "State file unreadable ({}): restored from {:?} plus the event log{}",
+                        recovery.error,
+                        backup,
+                        recovery.preserved.as_ref().map(|p| format!(", damaged file kept as {:?}", p)).unwrap_or_default()
+                    ));
+                    (snapshots, None)
+                }
+                None => (Vec::new(), Some(recovery)),
+            }
+        }
+    };
+
+    // Crear los slots iniciales. Con el archivo de estado dañado y sin backup válido no se
+    // restaura nada hasta que el usuario elija en el modal de recuperación
+    let session = match recovery {
+        None => restore_session(&snapshots, &events, &config.dca),
+        Some(_) => RestoredSession::default(),
+    };
+    let restore_info = session.info;
+    let restore_issues = session.issues;
+    let mut orders = OrderManager::default();
+    for order in session.orders {
+        orders.track(order);
+    }
+    let mut slots = session.slots;
+    // IDs nuevos por encima de los del log: una recuperación posterior no los confunde
+    let mut next_id = slots
+        .iter()
+        .map(|s| s.id + 1)
+        .chain(events.iter().map(|e| e.slot_id + 1))
+        .max()
+        .unwrap_or(0);
+    if slots.is_empty() {
+        // Crear los slots iniciales desde config: uno por [[slots]], o el de [dca]
+        for strat in initial_strategies(&config.dca, &config.slots) {
+            slots.push(StrategySlot::new(next_id, strat.config.symbol.clone(), strat));
             next_id += 1;
         }
-    } else {
-        // Crear slot inicial desde config
-        let (base, quote) = parse_symbol(&config.dca.symbol);
-        let strat = DcaStrategy::new(config.dca.clone());
-        slots.push(StrategySlot {
-            id: next_id,
-            strategy: strat,
-            symbol: config.dca.symbol.clone(),
-            base_asset: base,
-            quote_asset: quote,
-            base_balance: 0.0,
-            quote_balance: 0.0,
-        });
-        next_id += 1;
+    }
+
+    // Historial de ciclos: completa los que el log tiene y el archivo no (versiones previas
+    // o un corte entre el log y el historial) y arma los ciclos en curso
+    let (cycles, cycles_added) = CycleHistory::open(
+        &cycles_path,
+        &events,
+        slots.iter().map(|s| (s.id, s.strategy.config.direction.clone())),
+    );
+
+    let event_log = EventLog::open(&events_path);
+
+    // Órdenes condicionales pendientes de la sesión anterior
+    let triggers = TriggerBook::load(triggers_path).unwrap_or_else(|e| {
+        tracing::warn!("Conditional orders not loaded: {:#}", e);
+        TriggerBook::default()
+    });
+
+    // Escaleras de órdenes LIMIT: sus órdenes siguen en el libro entre sesiones
+    let ladders = LadderBook::load(ladders_path).unwrap_or_else(|e| {
+        tracing::warn!("Ladders not loaded: {:#}", e);
+        LadderBook::default()
+    });
+
+    // Presets de estrategia, junto a config.toml. Un archivo inválido no se pisa: la
+    // pantalla de presets queda vacía y sin guardar hasta corregirlo
+    let (presets, presets_error) = match PresetBook::load(Config::path().with_file_name("presets.toml")) {
+        Ok(book) => (book, None),
+        Err(e) => (PresetBook::default(), Some(format!("Presets not loaded: {:#}", e))),
+    };
+
+    // Comisiones de `[fees]` desde el arranque (las de la cuenta llegan con el primer balance)
+    for slot in slots.iter_mut() {
+        slot.strategy.set_fee_rates(CommissionRates::default().for_symbol(&config.fees, &slot.symbol));
     }
 
     // Símbolos activos para WebSocket
-    let initial_symbols: Vec<String> = slots.iter().map(|s| s.symbol.clone()).collect();
+    let initial_symbols = watched_symbols(&slots, &triggers);
 
-    let ui_mode = if restore_info.iter().any(|(_, _, c, active)| *c > 0 || *active) {
+    let ui_mode = if let Some(recovery) = recovery {
+        UiMode::Recovery(recovery)
+    } else if restore_info.iter().any(|(_, _, c, active)| *c > 0 || *active) {
         UiMode::RestoreSession(restore_info)
     } else {
         UiMode::Normal
@@ -133,25 +327,133 @@ async fn main() -> Result<()> {
 
     let state = Arc::new(Mutex::new(AppState {
         slots,
+        max_slots: config.app.max_slots,
         selected_slot: 0,
+        trade_selected: 0,
         prices: HashMap::new(),
+        live_symbols: std::collections::HashSet::new(),
+        stream_status: websocket::StreamStatus::default(),
+        stream_stats: websocket::ReconnectStats::default(),
+        ws_policy: config.websocket.clone(),
+        max_price_age_secs: config.risk.max_price_age_secs,
+        stale_symbols: std::collections::HashSet::new(),
         alert_levels: HashMap::new(),
+        missing_klines: HashMap::new(),
+        sr_window: config.alerts.rolling_window.max(1),
+        alert_cooldown_minutes: config.alerts.cooldown_minutes,
         symbols: available_symbols,
         log: std::collections::VecDeque::new(),
+        log_view: app::LogView::default(),
+        help_scroll: 0,
+        diagnostics_scroll: 0,
+        theme: ui::theme::Theme::named(config.ui.theme),
         should_quit: false,
         ui_mode,
+        view_tab: app::ViewTab::default(),
         new_strat_symbol_idx: 0,
         new_strat_direction: Direction::Long,
+        new_strat_kind: config.dca.kind,
         new_strat_auto_restart: config.dca.auto_restart,
         new_strat_auto_flip: config.dca.auto_flip,
         new_strat_has_bnb: config.dca.has_bnb_balance,
+        new_strat_group: None,
+        new_strat_template: None,
+        new_strat_preset: None,
+        new_strat_bufs: Vec::new(),
+        new_strat_field: 0,
+        new_strat_error: None,
+        groups: config.groups.clone(),
+        config_slots: config.slots.clone(),
         cfg_amount_buf: String::new(),
         cfg_has_bnb: config.dca.has_bnb_balance,
+        cfg_amount_warning: None,
+        amount_confirm_pct: config.risk.amount_confirm_pct,
+        new_strat_preview: None,
+        palette_input: String::new(),
+        palette_selected: 0,
+        edit_slot_bufs: Vec::new(),
+        edit_slot_initial: Vec::new(),
+        edit_slot_field: 0,
+        edit_slot_error: None,
+        settings_bufs: Vec::new(),
+        settings_initial: Vec::new(),
+        settings_field: 0,
+        settings_error: None,
         next_slot_id: next_id,
+        state_writer: Arc::new(state_file::StateWriter::spawn(event_log.file(), cycles_path)),
+        event_log,
+        cycles,
+        equity_history: Arc::new(equity_history::EquityHistory::open(&equity_path)),
+        stats,
+        commission: CommissionRates::default(),
+        fees: config.fees.clone(),
+        config_history: std::collections::VecDeque::new(),
+        revision: 0,
+        dump_screen_requested: false,
+        alerts: std::collections::VecDeque::new(),
+        alert_history_len: config.retention.alert_history,
+        triggers,
+        trigger_selected: 0,
+        ladders,
+        presets,
+        preset_view: app::PresetView::default(),
+        notifications: config.notifications.clone(),
+        orders,
+        restore_issues,
+        candles: market_data::CandleStore::default(),
+        volume: market_data::VolumeTracker::new(
+            config.alerts.volume_spike_factor,
+            config.alerts.volume_window_minutes,
+            config.alerts.cooldown_minutes,
+        ),
+        price_history: market_data::PriceHistory::default(),
+        valuation: None,
+        display_currency: config.ui.display_currency.trim().to_uppercase(),
+        quote_rates: HashMap::new(),
+        daily_summary: None,
+        holdings: HashMap::new(),
+        max_daily_spend: config.risk.max_daily_spend,
+        max_daily_spend_total: config.risk.max_daily_spend_total,
+        max_exposure: config.risk.max_exposure.iter().map(|(a, l)| (a.to_uppercase(), *l)).collect(),
+        equity_floor_usdt: config.risk.equity_floor_usdt,
+        equity_floor_action: config.risk.equity_floor_action,
+        equity_floor_tripped: false,
     }));
 
+    // Más slots guardados que `[app] max_slots`: se gestionan todos, pero no se crean nuevos
+    {
+        let mut s = state.lock().await;
+        if let Some(msg) = presets_error {
+            s.log_error(&msg);
+        }
+        if let Some(msg) = state_fallback {
+            s.log_error(&msg);
+        }
+        if cycles_added > 0 {
+            s.log(&format!("Cycle history: {} completed cycle(s) recovered from the event log", cycles_added));
+        }
+        if s.slots.len() > s.max_slots {
+            let msg = format!(
+                "{} saved slots exceed app.max_slots = {}: all are still managed, new ones are blocked",
+                s.slots.len(), s.max_slots
+            );
+            s.log(&msg);
+        }
+    }
+
     // Canal de precios (WebSocket → motor)
-    let (price_tx, price_rx) = mpsc::channel::<MiniTickerEvent>(200);
+    // Último ticker por símbolo: las ráfagas se pisan en vez de encolarse
+    let tickers = Arc::new(market_data::TickerBoard::default());
+
+    // Canal del tope del libro (WebSocket → bid/ask de MarketData)
+    let (book_tx, mut book_rx) = mpsc::channel::<PartialDepth>(200);
+
+    // Canal de trades agregados (WebSocket → volumen por minuto), solo con la alerta activa
+    let (trade_tx, mut trade_rx) = mpsc::channel::<AggTradeEvent>(1000);
+    let trade_tx = (config.alerts.volume_spike_factor > 0.0).then_some(trade_tx);
+
+    // Canal de velas (WebSocket → buffers de market_data)
+    let (kline_tx, mut kline_rx) = mpsc::channel::<KlineEvent>(200);
 
     // Canal de comandos (UI → motor)
     let (cmd_tx, cmd_rx) = mpsc::channel::<AppCommand>(16);
@@ -159,12 +461,125 @@ async fn main() -> Result<()> {
     // Canal watch para la lista de símbolos activos
     let (symbol_tx, symbol_rx) = watch::channel::<Vec<String>>(initial_symbols);
 
+    // Canal watch del estado de conexión y contadores del WebSocket
+    let (stream_health_tx, mut stream_health_rx) = watch::channel(websocket::StreamHealth::default());
+
     // ----------------------------------------------------------------
     // Tarea 1: WebSocket de precios (se reconecta automáticamente)
     // ----------------------------------------------------------------
-    tokio::spawn(async move {
-        websocket::run_price_stream(symbol_rx, price_tx).await;
-    });
+    {
+        let mut extra = vec![config.alerts.candle_interval.as_str()];
+        extra.extend(market_data::ANALYSIS_INTERVALS);
+        let intervals = market_data::stream_intervals(&extra);
+        let ws_tickers = Arc::clone(&tickers);
+        let policy = config.websocket.clone();
+        let recorder = api::recording::FrameRecorder::new(capture_path, config.websocket.record_minutes);
+        if recorder.is_some() {
+            tracing::info!(
+                "Recording WebSocket frames of the last {} minute(s)",
+                config.websocket.record_minutes
+            );
+        }
+        tokio::spawn(async move {
+            websocket::run_price_stream(
+                symbol_rx, policy, stream_health_tx, intervals, ws_tickers, book_tx, kline_tx, trade_tx, recorder,
+            )
+            .await;
+        });
+    }
+
+    // ----------------------------------------------------------------
+    // Tarea 1a: estado de conexión del WebSocket (header) y contadores (panel I)
+    // ----------------------------------------------------------------
+    {
+        let state_ref = Arc::clone(&state);
+        tokio::spawn(async move {
+            while stream_health_rx.changed().await.is_ok() {
+                let health = stream_health_rx.borrow_and_update().clone();
+                let mut s = state_ref.lock().await;
+                s.stream_status = health.status;
+                // Cortes e intentos fallidos: las rotaciones de 24 h y los cambios de símbolos
+                // también abren sesiones, pero no son reconexiones
+                s.stats.reconnects = health.stats.drops + health.stats.failed_attempts;
+                s.stream_stats = health.stats;
+                s.touch();
+            }
+        });
+    }
+
+    // ----------------------------------------------------------------
+    // Tarea 1b: mejor bid/ask y spread de cada símbolo
+    // ----------------------------------------------------------------
+    {
+        let state_ref = Arc::clone(&state);
+        tokio::spawn(async move {
+            while let Some(depth) = book_rx.recv().await {
+                let (bid, ask) = (depth.best_bid(), depth.best_ask());
+                let mut s = state_ref.lock().await;
+                let entry = s.prices.entry(depth.symbol).or_default();
+                let changed = entry.bid != bid || entry.ask != ask;
+                entry.bid = bid;
+                entry.ask = ask;
+                entry.book_at = Some(chrono::Utc::now());
+                entry.message_at = entry.book_at;
+                if changed {
+                    s.touch();
+                }
+            }
+        });
+    }
+
+    // ----------------------------------------------------------------
+    // Tarea 1c: buffers de velas en memoria (alertas e indicadores)
+    // ----------------------------------------------------------------
+    {
+        let state_ref = Arc::clone(&state);
+        tokio::spawn(async move {
+            while let Some(event) = kline_rx.recv().await {
+                let mut s = state_ref.lock().await;
+                s.candles.apply(&event);
+                // La vela también cuenta como señal de vida del precio (ver MarketData::message_at)
+                if let Some(entry) = s.prices.get_mut(&event.symbol) {
+                    entry.message_at = Some(chrono::Utc::now());
+                }
+            }
+        });
+    }
+
+    // ----------------------------------------------------------------
+    // Tarea 1d: volumen por minuto y alertas de picos de volumen
+    // ----------------------------------------------------------------
+    {
+        let state_ref = Arc::clone(&state);
+        tokio::spawn(async move {
+            // Un lock por tanda de trades listos, no uno por trade
+            let mut batch = Vec::with_capacity(TRADE_BATCH);
+            while trade_rx.recv_many(&mut batch, TRADE_BATCH).await > 0 {
+                let mut s = state_ref.lock().await;
+                for trade in batch.drain(..) {
+                    let Some(spike) = s.volume.apply(&trade) else { continue };
+                    let (kind, side) = if spike.volume.buy >= spike.volume.sell {
+                        ("VOL BUY", "buyers")
+                    } else {
+                        ("VOL SELL", "sellers")
+                    };
+                    let price = s.prices.get(&trade.symbol).map(|m| m.price).unwrap_or(0.0);
+                    let msg = format!(
+                        "[{}] Volume spike! ${:.0} in 1m vs ${:.0} avg ({:.1}x), {:.0}% {}",
+                        trade.symbol,
+                        spike.volume.total(),
+                        spike.average,
+                        spike.ratio,
+                        spike.volume.buy_ratio().max(1.0 - spike.volume.buy_ratio()) * 100.0,
+                        side
+                    );
+                    s.push_alert(&trade.symbol, kind, spike.ratio, price, &msg);
+                    let slot_id = s.slots.iter().find(|sl| sl.symbol == trade.symbol).map(|sl| sl.id);
+                    notify(&s, slot_id, NotifyEvent::Alert);
+                }
+            }
+        });
+    }
 
     // ----------------------------------------------------------------
     // Tarea 2: Motor de alertas S/R (rolling window, cada 5 min)
@@ -176,23 +591,62 @@ async fn main() -> Result<()> {
         tokio::spawn(run_alert_engine(state_ref, client_ref, alerts_config));
     }
 
+    // ----------------------------------------------------------------
+    // Tarea 2a: stats.json cada minuto (contadores de la sesión y acumulados)
+    // ----------------------------------------------------------------
+    tokio::spawn(run_stats_saver(Arc::clone(&state)));
+
+    // ----------------------------------------------------------------
+    // Tarea 2b: Risk parity (ATR diario, se recalcula cada día y al cambiar los slots)
+    // ----------------------------------------------------------------
+    if config.risk.risk_parity {
+        let state_ref = Arc::clone(&state);
+        let client_ref = Arc::clone(&client);
+        let symbols_rx = symbol_tx.subscribe();
+        tokio::spawn(run_sizing_engine(state_ref, client_ref, symbols_rx, config.risk.atr_period));
+    }
+
+    // ----------------------------------------------------------------
+    // Tarea 2c: Mantenimiento de historiales (event log, auditoría de órdenes)
+    // ----------------------------------------------------------------
+    if config.retention.events_days > 0 || config.retention.audit_days > 0 {
+        let state_ref = Arc::clone(&state);
+        let client_ref = Arc::clone(&client);
+        tokio::spawn(run_maintenance(state_ref, client_ref, state_path.clone(), config.retention.clone()));
+    }
+
+    // ----------------------------------------------------------------
+    // Filtros de símbolo (minNotional) de los slots restaurados
+    // ----------------------------------------------------------------
+    {
+        let slots: Vec<(usize, String)> =
+            state.lock().await.slots.iter().map(|s| (s.id, s.symbol.clone())).collect();
+        for (id, symbol) in slots {
+            tokio::spawn(fetch_symbol_filters(Arc::clone(&state), Arc::clone(&client), id, symbol));
+        }
+    }
+
+    // ----------------------------------------------------------------
+    // Fills ocurridos con el bot apagado (salidas manuales, OCO...)
+    // ----------------------------------------------------------------
+    reconcile_positions(&state, &client, &events, &state_path).await;
+    check_holdings(&state, &client).await;
+
     // ----------------------------------------------------------------
     // Tarea 3: Motor de estrategia multi-slot
     // ----------------------------------------------------------------
     {
         let state_ref = Arc::clone(&state);
         let client_ref = Arc::clone(&client);
-        let max_daily = config.risk.max_daily_spend;
         let dca_config = config.dca.clone();
 
         tokio::spawn(run_strategy_engine(
             state_ref,
             client_ref,
-            price_rx,
+            tickers,
             cmd_rx,
             config_path,
             state_path,
-            max_daily,
             dca_config,
             symbol_tx,
         ));
@@ -201,42 +655,64 @@ async fn main() -> Result<()> {
     // ----------------------------------------------------------------
     // Tarea principal: TUI (bloquea el hilo principal)
     // ----------------------------------------------------------------
-    let mut tui = Tui::new(Arc::clone(&state), cmd_tx)?;
+    let mut tui = Tui::new(Arc::clone(&state), cmd_tx, &config.ui)?;
     tui.run().await?;
 
+    // El último guardado puede seguir en el debounce: se espera a que llegue al disco
+    let writer = Arc::clone(&state.lock().await.state_writer);
+    writer.flush().await;
+    save_stats(&state).await;
+
     tracing::info!("Bot stopped.");
     Ok(())
 }
 
 /// Motor principal multi-slot de la estrategia DCA
+#[allow(clippy::too_many_arguments)]
 async fn run_strategy_engine(
     state: Arc<Mutex<AppState>>,
     client: Arc<BinanceClient>,
-    mut price_rx: mpsc::Receiver<MiniTickerEvent>,
+    tickers: Arc<market_data::TickerBoard>,
     mut cmd_rx: mpsc::Receiver<AppCommand>,
     config_path: std::path::PathBuf,
     state_path: std::path::PathBuf,
-    max_daily: f64,
-    base_config: DcaConfig,
+    mut base_config: DcaConfig,
     symbol_tx: watch::Sender<Vec<String>>,
 ) {
+    let mut config_watch = config::ConfigWatch::new(&config_path);
+    let mut config_tick = tokio::time::interval(Duration::from_secs(CONFIG_POLL_SECS));
     let mut strategy_tick = tokio::time::interval(Duration::from_secs(1));
     let mut balance_tick = tokio::time::interval(Duration::from_secs(30));
+    let mut rest_price_tick = tokio::time::interval(Duration::from_secs(15));
+    let mut symbol_status_tick = tokio::time::interval(Duration::from_secs(SYMBOL_STATUS_SECS));
 
     // Primera actualización de balance
     refresh_balance(&state, &client).await;
 
     loop {
         tokio::select! {
-            // Evento de precio del WebSocket
-            Some(event) = price_rx.recv() => {
-                let mut s = state.lock().await;
-                let sym = event.symbol.clone();
-                let entry = s.prices.entry(sym).or_default();
-                entry.price = event.close_f64();
-                entry.change_24h_pct = event.change_pct();
-                entry.high_24h = event.high_price.parse().unwrap_or(entry.high_24h);
-                entry.low_24h = event.low_price.parse().unwrap_or(entry.low_24h);
+            // Últimos precios del WebSocket (uno por símbolo, el más reciente)
+            updates = tickers.changed() => {
+                let mut s = profiling::lock(&state, "tickers").await;
+                let mut changed = false;
+                for (event, received_at) in updates {
+                    let sym = event.symbol.clone();
+                    if !s.live_symbols.contains(&sym) {
+                        s.live_symbols.insert(sym.clone());
+                    }
+                    let entry = s.prices.entry(sym).or_default();
+                    changed |= entry.price != event.close_f64();
+                    entry.price = event.close_f64();
+                    entry.change_24h_pct = event.change_pct();
+                    entry.high_24h = event.high_price.parse().unwrap_or(entry.high_24h);
+                    entry.low_24h = event.low_price.parse().unwrap_or(entry.low_24h);
+                    entry.price_at = Some(received_at);
+                    entry.message_at = Some(received_at);
+                    s.price_history.record(&event.symbol, event.close_f64(), received_at);
+                }
+                if changed {
+                    s.touch();
+                }
             }
 
             // Comandos del UI
@@ -250,22 +726,64 @@ async fn run_strategy_engine(
                     &base_config,
                     &symbol_tx,
                 ).await;
-                if state.lock().await.should_quit {
+                let mut s = state.lock().await;
+                s.touch();
+                if s.should_quit {
                     break;
                 }
             }
 
             // Tick de estrategia (cada 1 segundo): evalúa todos los slots
             _ = strategy_tick.tick() => {
+                poll_entry_orders(&state, &client, &state_path).await;
+                // Con el archivo de estado dañado no se opera: las condicionales y las
+                // escaleras esperan a que el usuario decida en el modal de recuperación
+                if !matches!(state.lock().await.ui_mode, UiMode::Recovery(_)) {
+                    check_triggers(&state, &client).await;
+                    poll_ladders(&state, &client).await;
+                }
                 let ids: Vec<usize> = state.lock().await.slots.iter().map(|s| s.id).collect();
                 for id in ids {
-                    evaluate_slot(&state, &client, id, max_daily, &state_path).await;
+                    evaluate_slot(&state, &client, id, &state_path).await;
                 }
             }
 
             // Actualización periódica de balances (cada 30s)
             _ = balance_tick.tick() => {
-                refresh_balance(&state, &client).await;
+                if let Some(account) = refresh_balance(&state, &client).await {
+                    check_equity_floor(&state, &client, &account, &state_path).await;
+                }
+            }
+
+            // Fallback REST (cada 15s) para símbolos sin precio del WebSocket
+            _ = rest_price_tick.tick() => {
+                refresh_missing_prices(&state, &client).await;
+            }
+
+            // Estado de los símbolos en exchangeInfo (halts / deslistados)
+            _ = symbol_status_tick.tick() => {
+                check_symbol_status(&state, &client, &state_path).await;
+            }
+
+            // config.toml editado con el bot andando: se aplican los ajustes seguros
+            _ = config_tick.tick() => {
+                let Some(reload) = config_watch.poll() else { continue };
+                let mut s = state.lock().await;
+                match reload {
+                    Ok((config, changed)) => {
+                        if changed.iter().any(|k| config::is_live_setting(k)) {
+                            base_config = config.dca.clone();
+                            apply_config(&mut s, &config);
+                        }
+                        log_config_reload(&mut s, &changed);
+                    }
+                    // Un problema por línea: la validación los informa todos juntos
+                    Err(e) => {
+                        for line in format!("config.toml not reloaded, keeping the running config: {:#}", e).lines() {
+                            s.log_error(line);
+                        }
+                    }
+                }
             }
         }
     }
@@ -289,25 +807,47 @@ async fn handle_command(
         AppCommand::RestoreSessionContinue => {
             let mut s = state.lock().await;
             s.log("Previous sessions restored. Active strategies have been RESUMED.");
+            s.restore_issues.clear();
             s.ui_mode = UiMode::Normal;
         }
+        AppCommand::RestoreSessionReconcile => {
+            {
+                let mut s = state.lock().await;
+                let issues = std::mem::take(&mut s.restore_issues);
+                for issue in issues {
+                    let RestoreIssue::ExceedsHoldings { asset, slot_ids, recorded, held } = issue else { continue };
+                    let keep = if recorded > 0.0 { held / recorded } else { 0.0 };
+                    for id in &slot_ids {
+                        if let Some(slot) = s.slot_by_id_mut(*id) {
+                            slot.strategy.adjust_position(keep);
+                        }
+                    }
+                    s.log(&format!(
+                        "Reconciled {} positions with the wallet: {:.8} → {:.8} {}",
+                        asset, recorded, held, asset
+                    ));
+                }
+                s.log("Previous sessions restored. Active strategies have been RESUMED.");
+                s.ui_mode = UiMode::Normal;
+            }
+            save_all_snapshots(state, state_path).await;
+        }
         AppCommand::RestoreSessionDiscard => {
+            let pending = state.lock().await.orders.untrack_all();
+            cancel_entry_orders(state, client, pending).await;
             {
                 let mut s = state.lock().await;
                 s.slots.clear();
+                s.restore_issues.clear();
                 s.selected_slot = 0;
-                let (base, quote) = parse_symbol(&base_config.symbol);
-                let strat = DcaStrategy::new(base_config.clone());
-                let id = s.alloc_slot_id();
-                s.slots.push(StrategySlot {
-                    id,
-                    strategy: strat,
-                    symbol: base_config.symbol.clone(),
-                    base_asset: base,
-                    quote_asset: quote,
-                    base_balance: 0.0,
-                    quote_balance: 0.0,
-                });
+                let sections = s.config_slots.clone();
+                for mut strat in initial_strategies(base_config, &sections) {
+                    let symbol = strat.config.symbol.clone();
+                    strat.set_fee_rates(s.fee_rates(&symbol));
+                    let id = s.alloc_slot_id();
+                    s.slots.push(StrategySlot::new(id, symbol.clone(), strat));
+                    tokio::spawn(fetch_symbol_filters(state.clone(), client.clone(), id, symbol));
+                }
                 s.log("Previous session discarded. Starting from scratch.");
                 s.ui_mode = UiMode::Normal;
             }
@@ -316,11 +856,85 @@ async fn handle_command(
             refresh_balance(state, client).await;
         }
 
+        // --- Recuperación de un archivo de estado dañado ---
+        AppCommand::RecoveryLoadBackup | AppCommand::RecoveryRebuild => {
+            let from_backup = matches!(cmd, AppCommand::RecoveryLoadBackup);
+            let (backup, events) = {
+                let s = state.lock().await;
+                let UiMode::Recovery(recovery) = &s.ui_mode else { return };
+                (recovery.backup.clone(), s.event_log.events())
+            };
+            // Backup: sus snapshots + los eventos posteriores. Reconstruir: solo el event log
+            let snapshots = match (from_backup, backup) {
+                (false, _) => Vec::new(),
+                (true, None) => return,
+                (true, Some(backup)) => match state_file::load(&backup.path) {
+                    Ok(snapshots) => snapshots,
+                    Err(e) => {
+                        state.lock().await.log_error(&format!("Backup {:?} unreadable: {}", backup.path, e));
+                        return;
+                    }
+                },
+            };
+            let session = restore_session(&snapshots, &events, base_config);
+            if session.slots.is_empty() {
+                state.lock().await.log_error(if from_backup {
+                    "The backup holds no slots: rebuild from history or start clean."
+                } else {
+                    "The event log holds no slots to rebuild: load a backup or start clean."
+                });
+                return;
+            }
+            {
+                let mut s = state.lock().await;
+                let s = &mut *s;
+                s.slots = session.slots;
+                for slot in s.slots.iter_mut() {
+                    let rates = s.commission.for_symbol(&s.fees, &slot.symbol);
+                    slot.strategy.set_fee_rates(rates);
+                    tokio::spawn(fetch_symbol_filters(state.clone(), client.clone(), slot.id, slot.symbol.clone()));
+                }
+                for order in session.orders {
+                    s.orders.track(order);
+                }
+                s.selected_slot = 0;
+                s.trade_selected = 0;
+                s.restore_issues = session.issues;
+                s.log(&format!(
+                    "{} slot(s) recovered from {}",
+                    s.slots.len(),
+                    if from_backup { "the latest backup and the event log" } else { "the event log" }
+                ));
+                // Mismo paso que en un arranque normal: continuar, ajustar o descartar
+                s.ui_mode = UiMode::RestoreSession(session.info);
+            }
+            update_symbol_watch(state, symbol_tx).await;
+            // Fills de Binance posteriores a lo registrado y posiciones mayores que la wallet
+            reconcile_positions(state, client, &events, state_path).await;
+            check_holdings(state, client).await;
+            save_all_snapshots(state, state_path).await;
+            refresh_balance(state, client).await;
+        }
+        AppCommand::RecoveryStartClean => {
+            {
+                let mut s = state.lock().await;
+                let UiMode::Recovery(recovery) = &s.ui_mode else { return };
+                let msg = match &recovery.preserved {
+                    Some(path) => format!("Damaged state discarded (copy kept in {:?}). Starting from scratch.", path),
+                    None => "Damaged state discarded. Starting from scratch.".to_string(),
+                };
+                s.log(&msg);
+                s.ui_mode = UiMode::Normal;
+            }
+            save_all_snapshots(state, state_path).await;
+        }
+
         // --- Navegación de slots ---
         AppCommand::SlotSelectUp => {
             let mut s = state.lock().await;
             if s.selected_slot > 0 {
                 s.selected_slot -= 1;
+                s.trade_selected = 0;
             }
         }
         AppCommand::SlotSelectDown => {
@@ -328,23 +942,74 @@ async fn handle_command(
             let len = s.slots.len();
             if s.selected_slot + 1 < len {
                 s.selected_slot += 1;
+                s.trade_selected = 0;
+            }
+        }
+        AppCommand::MoveSlotUp | AppCommand::MoveSlotDown => {
+            let mut s = state.lock().await;
+            let from = s.selected_slot;
+            let to = match cmd {
+                AppCommand::MoveSlotUp => from.checked_sub(1),
+                _ => Some(from + 1).filter(|i| *i < s.slots.len()),
+            };
+            let Some(to) = to else { return };
+            // El orden de la lista es el del archivo de estado
+            s.slots.swap(from, to);
+            s.selected_slot = to;
+            drop(s);
+            save_all_snapshots(state, state_path).await;
+        }
+        AppCommand::SelectSlot(index) => {
+            let mut s = state.lock().await;
+            if index < s.slots.len() && index != s.selected_slot {
+                s.selected_slot = index;
+                s.trade_selected = 0;
+            }
+        }
+        AppCommand::TradeSelectUp => {
+            let mut s = state.lock().await;
+            s.trade_selected = s.trade_selected.saturating_sub(1);
+        }
+        AppCommand::TradeSelectDown => {
+            let mut s = state.lock().await;
+            let len = s.selected().map(|sl| sl.strategy.trades.len()).unwrap_or(0);
+            if s.trade_selected + 1 < len {
+                s.trade_selected += 1;
+            }
+        }
+        AppCommand::OpenTradeDetail => {
+            let mut s = state.lock().await;
+            let len = s.selected().map(|sl| sl.strategy.trades.len()).unwrap_or(0);
+            // La posición pudo cerrarse o achicarse desde la última selección
+            if len > 0 {
+                s.trade_selected = s.trade_selected.min(len - 1);
+                s.ui_mode = UiMode::TradeDetail;
             }
         }
 
         AppCommand::ToggleStartStopSelected => {
             let mut s = state.lock().await;
             let mut log_msg = None;
+            let blocked = match s.selected() {
+                Some(slot) if !slot.strategy.state.is_active() => {
+                    let id = slot.id;
+                    fee_guard_blocks(&mut s, id)
+                }
+                _ => false,
+            };
             if let Some(slot) = s.selected_mut() {
                 if slot.strategy.state.is_active() {
                     slot.strategy.stop();
                     log_msg = Some(format!("Strategy for {} STOPPED.", slot.symbol));
-                } else {
+                } else if let Some(status) = &slot.halted {
+                    log_msg = Some(format!("{} is not trading (status {}): cannot start", slot.symbol, status));
+                } else if !blocked {
                     slot.strategy.start();
                     log_msg = Some(format!("Strategy for {} STARTED.", slot.symbol));
                 }
             }
             if let Some(msg) = log_msg {
-                s.log(&msg);
+                s.log_selected(&msg);
                 drop(s);
                 save_all_snapshots(state, state_path).await;
             }
@@ -353,18 +1018,57 @@ async fn handle_command(
         AppCommand::ToggleAutoFlip => {
             let mut s = state.lock().await;
             let mut log_msg = None;
+            let mut change = ConfigChange::new(String::new());
+            if s.selected().is_some_and(|sl| sl.strategy.watching()) {
+                s.log_error("Watch-only slots never enter: auto-flip is not available");
+                return;
+            }
             if let Some(slot) = s.selected_mut() {
-                slot.strategy.config.auto_flip = !slot.strategy.config.auto_flip;
+                let auto_flip = !slot.strategy.config.auto_flip;
+                change.set(slot, "auto_flip", auto_flip);
                 let status = if slot.strategy.config.auto_flip { "ENABLED" } else { "DISABLED" };
                 log_msg = Some(format!("Auto-Flip {} for {}", status, slot.symbol));
             }
             if let Some(msg) = log_msg {
-                s.log(&msg);
+                change.description = msg.clone();
+                s.push_config_change(change);
+                s.log_selected(&msg);
                 drop(s);
                 save_all_snapshots(state, state_path).await;
             }
         }
 
+        AppCommand::ToggleReduceOnly => {
+            let mut s = state.lock().await;
+            let mut log_msg = None;
+            let mut enabled_on = None;
+            let mut change = ConfigChange::new(String::new());
+            if s.selected().is_some_and(|sl| sl.strategy.watching()) {
+                s.log_error("Watch-only slots never enter: reduce-only is not available");
+                return;
+            }
+            if let Some(slot) = s.selected_mut() {
+                let reduce_only = !slot.strategy.config.reduce_only;
+                change.set(slot, "reduce_only", reduce_only);
+                log_msg = Some(if reduce_only {
+                    format!("Reduce-only ENABLED for {}: no new entries, the open position is still managed", slot.symbol)
+                } else {
+                    format!("Reduce-only DISABLED for {}: entries resume", slot.symbol)
+                });
+                enabled_on = reduce_only.then_some(slot.id);
+            }
+            if let Some(msg) = log_msg {
+                change.description = msg.clone();
+                s.push_config_change(change);
+                s.log_selected(&msg);
+                // La entrada LIMIT que espera en el libro también es una entrada nueva: se cancela
+                let pending = enabled_on.and_then(|id| s.orders.untrack(id));
+                drop(s);
+                cancel_entry_orders(state, client, pending.into_iter().collect()).await;
+                save_all_snapshots(state, state_path).await;
+            }
+        }
+
         // --- Borrado de slot (D) ---
         AppCommand::OpenConfirmDelete => {
             let mut s = state.lock().await;
@@ -383,6 +1087,8 @@ async fn handle_command(
             };
 
             if let Some(id) = id {
+                let pending = state.lock().await.orders.untrack(id);
+                cancel_entry_orders(state, client, pending.into_iter().collect()).await;
                 let mut s = state.lock().await;
                 s.remove_slot(id);
                 s.log("Slot removed.");
@@ -394,710 +1100,4047 @@ async fn handle_command(
             }
         }
 
-        // --- Modal nueva estrategia (S) ---
-        AppCommand::OpenNewStrategy => {
+        // --- Deshacer último cambio de config ---
+        AppCommand::UndoConfigChange => {
+            let change = {
+                let mut s = state.lock().await;
+                match s.config_history.pop_back() {
+                    Some(c) => {
+                        for (slot_id, field, old) in &c.previous {
+                            if let Some(slot) = s.slot_by_id_mut(*slot_id) {
+                                slot.strategy.set_config_field(field, old.clone());
+                            }
+                        }
+                        s.log(&format!("Undone: {}", c.description));
+                        c
+                    }
+                    None => {
+                        s.log("Nothing to undo.");
+                        return;
+                    }
+                }
+            };
+            if !change.saved_settings.is_empty() {
+                if let Err(e) = Config::save_settings(config_path, &change.saved_settings) {
+                    state.lock().await.log_error(&format!("Could not restore config.toml: {:#}", e));
+                }
+            }
+            save_all_snapshots(state, state_path).await;
+        }
+
+        // --- Paleta de comandos (:) ---
+        AppCommand::OpenPalette => {
             let mut s = state.lock().await;
-            // Pre-seleccionar el primer símbolo no usado
-            let used: Vec<String> = s.slots.iter().map(|sl| sl.symbol.clone()).collect();
-            let idx = s.symbols
-                .iter()
-                .position(|sym| !used.contains(sym))
-                .unwrap_or(0);
-            s.new_strat_symbol_idx = idx;
-            s.new_strat_direction = Direction::Long;
-            s.new_strat_auto_restart = base_config.auto_restart;
-            s.new_strat_auto_flip = base_config.auto_flip;
-            s.ui_mode = UiMode::NewStrategy;
+            s.palette_input.clear();
+            s.palette_selected = 0;
+            s.ui_mode = UiMode::Palette;
         }
-        AppCommand::NewStratSymbolUp => {
+        AppCommand::PaletteInput(c) => {
             let mut s = state.lock().await;
-            let len = s.symbols.len();
-            if len > 0 {
-                s.new_strat_symbol_idx =
-                    if s.new_strat_symbol_idx == 0 { len - 1 } else { s.new_strat_symbol_idx - 1 };
-            }
+            s.palette_input.push(c);
+            s.palette_selected = 0;
         }
-        AppCommand::NewStratSymbolDown => {
+        AppCommand::PaletteBackspace => {
             let mut s = state.lock().await;
-            let len = s.symbols.len();
-            if len > 0 {
-                s.new_strat_symbol_idx = (s.new_strat_symbol_idx + 1) % len;
-            }
+            s.palette_input.pop();
+            s.palette_selected = 0;
         }
-        AppCommand::NewStratToggleDirection => {
+        AppCommand::PaletteUp => {
             let mut s = state.lock().await;
-            s.new_strat_direction = match s.new_strat_direction {
-                Direction::Long  => Direction::Short,
-                Direction::Short => Direction::Long,
-            };
-        }
-        AppCommand::NewStratToggleAutoRestart => {
-            let mut s = state.lock().await;
-            s.new_strat_auto_restart = !s.new_strat_auto_restart;
-        }
-        AppCommand::NewStratToggleAutoFlip => {
-            let mut s = state.lock().await;
-            s.new_strat_auto_flip = !s.new_strat_auto_flip;
+            s.palette_selected = s.palette_selected.saturating_sub(1);
         }
-        AppCommand::NewStratToggleBnb => {
+        AppCommand::PaletteDown => {
             let mut s = state.lock().await;
-            s.new_strat_has_bnb = !s.new_strat_has_bnb;
+            let len = palette::matches(&s.palette_input).len();
+            if s.palette_selected + 1 < len {
+                s.palette_selected += 1;
+            }
         }
-        AppCommand::NewStratCancel => {
+        AppCommand::PaletteCancel => {
             state.lock().await.ui_mode = UiMode::Normal;
         }
-        AppCommand::NewStratConfirm => {
-            let (symbol, direction, auto_restart, auto_flip, has_bnb, can_add) = {
-                let s = state.lock().await;
-                let idx = s.new_strat_symbol_idx.min(s.symbols.len().saturating_sub(1));
-                let sym = s.symbols.get(idx).cloned().unwrap_or_else(|| "BTCUSDT".to_string());
-                let dir = s.new_strat_direction.clone();
-                let ar = s.new_strat_auto_restart;
-                let af = s.new_strat_auto_flip;
-                let bnb = s.new_strat_has_bnb;
-                let can = s.slots.len() < MAX_SLOTS;
-                (sym, dir, ar, af, bnb, can)
-            };
-
-            if !can_add {
-                state.lock().await.log_error("Maximum strategies reached (4).");
-                return;
-            }
-
-            let (base, quote) = parse_symbol(&symbol);
-            let mut cfg = base_config.clone();
-            cfg.symbol = symbol.clone();
-            cfg.direction = direction.clone();
-            cfg.auto_restart = auto_restart;
-            cfg.auto_flip = auto_flip;
-            cfg.has_bnb_balance = has_bnb;
-            let mut strat = DcaStrategy::new(cfg);
-            strat.start();
-
-            {
+        AppCommand::PaletteConfirm => {
+            let resolved = {
                 let mut s = state.lock().await;
-                let id = s.alloc_slot_id();
-                let dir_label = match direction {
-                    Direction::Long  => "LONG",
-                    Direction::Short => "SHORT",
-                };
-                s.log(&format!("New strategy: {} {} started", symbol, dir_label));
-                s.slots.push(StrategySlot {
-                    id,
-                    strategy: strat,
-                    symbol: symbol.clone(),
-                    base_asset: base,
-                    quote_asset: quote,
-                    base_balance: 0.0,
-                    quote_balance: 0.0,
-                });
-                s.selected_slot = s.slots.len() - 1;
-                s.ui_mode = UiMode::Normal;
-            }
-
-            update_symbol_watch(state, symbol_tx).await;
-            save_all_snapshots(state, state_path).await;
-            refresh_balance(state, client).await;
-        }
-
-        // --- Post-venta ---
-        AppCommand::PostSaleRestart(slot_id) => {
-            let mut s = state.lock().await;
-            if let Some(slot) = s.slot_by_id_mut(slot_id) {
-                slot.strategy.start();
-            }
-            s.ui_mode = UiMode::Normal;
-            s.log("DCA cycle restarted.");
-            drop(s);
-            save_all_snapshots(state, state_path).await;
-        }
-        AppCommand::PostSaleDismiss(slot_id) => {
-            let mut s = state.lock().await;
-            if let UiMode::PostSale(id, _) = &s.ui_mode {
-                if *id == slot_id {
-                    s.ui_mode = UiMode::Normal;
+                let input = s.palette_input.trim().to_string();
+                match palette::parse(&input) {
+                    Ok(cmd) => Some(cmd),
+                    Err(err) => {
+                        // Sin match exacto: usar la sugerencia resaltada
+                        let suggestion = palette::matches(&input).get(s.palette_selected).map(|i| i.usage);
+                        match suggestion {
+                            Some(usage) if usage.contains('<') => {
+                                // Con parámetro: autocompletar y esperar el valor
+                                let prefix = usage.split('<').next().unwrap_or("").to_string();
+                                s.palette_input = prefix;
+                                s.palette_selected = 0;
+                                return;
+                            }
+                            Some(usage) if !input.is_empty() || s.palette_selected > 0 => {
+                                palette::parse(usage).ok()
+                            }
+                            _ => {
+                                s.log_error(&err);
+                                None
+                            }
+                        }
+                    }
                 }
-            }
-        }
-
-        // --- Panel de configuración (solo monto) ---
-        AppCommand::OpenConfig => {
-            let mut s = state.lock().await;
-            let (amt, bnb) = s
-                .selected()
-                .map(|sl| (sl.strategy.config.quote_amount, sl.strategy.config.has_bnb_balance))
-                .unwrap_or((base_config.quote_amount, base_config.has_bnb_balance));
-            s.cfg_amount_buf = format!("{}", amt);
-            s.cfg_has_bnb = bnb;
-            s.ui_mode = UiMode::Config;
-        }
-        AppCommand::CloseConfig => {
+            };
             state.lock().await.ui_mode = UiMode::Normal;
-        }
-        AppCommand::CfgInputChar(c) => {
-            let mut s = state.lock().await;
-            if c.is_ascii_digit() || (c == '.' && !s.cfg_amount_buf.contains('.')) {
-                s.cfg_amount_buf.push(c);
+            if let Some(cmd) = resolved {
+                Box::pin(handle_command(
+                    cmd,
+                    state,
+                    client,
+                    config_path,
+                    state_path,
+                    base_config,
+                    symbol_tx,
+                ))
+                .await;
             }
         }
-        AppCommand::CfgBackspace => {
-            state.lock().await.cfg_amount_buf.pop();
-        }
-        // --- Cierre manual de posición ---
-        AppCommand::OpenConfirmClose => {
-            let mut s = state.lock().await;
-            let has_position = s
-                .selected()
-                .map(|sl| sl.strategy.total_quantity() > 0.0)
-                .unwrap_or(false);
-            if has_position {
-                s.ui_mode = UiMode::ConfirmClose;
-            } else {
-                s.log("No open position to close.");
-            }
+
+        AppCommand::ClosePositionPct(pct) if pct >= 100.0 => {
+            Box::pin(handle_command(
+                AppCommand::ConfirmCloseNow,
+                state,
+                client,
+                config_path,
+                state_path,
+                base_config,
+                symbol_tx,
+            ))
+            .await;
         }
-        AppCommand::ConfirmCloseNow => {
-            let (slot_id, symbol, qty, direction, price, pnl, pnl_pct) = {
+        AppCommand::ClosePositionPct(pct) => {
+            let (slot_id, symbol, total, qty, direction, price, position_pnl) = {
                 let s = state.lock().await;
-                let slot = match s.selected() {
-                    Some(sl) => sl,
-                    None => {
-                        drop(s);
-                        state.lock().await.ui_mode = UiMode::Normal;
-                        return;
-                    }
-                };
                 let price = s.selected_price();
-                (
-                    slot.id,
-                    slot.symbol.clone(),
-                    slot.strategy.total_quantity(),
-                    slot.strategy.config.direction.clone(),
-                    price,
-                    slot.strategy.pnl(price),
-                    slot.strategy.pnl_pct(price),
-                )
+                match s.selected() {
+                    Some(sl) => {
+                        let total = sl.strategy.total_quantity();
+                        // Al paso de LOT_SIZE: Binance rechaza (o recorta) una cantidad fuera del paso
+                        let wanted = total * pct / 100.0;
+                        let qty = sl.filters.as_ref().map_or(wanted, |f| f.round_qty(wanted));
+                        (
+                            sl.id,
+                            sl.symbol.clone(),
+                            total,
+                            qty,
+                            sl.strategy.config.direction.clone(),
+                            price,
+                            sl.strategy.pnl(price),
+                        )
+                    }
+                    None => return,
+                }
             };
-
-            state.lock().await.ui_mode = UiMode::Normal;
-
-            if qty <= 0.0 {
+            if total <= 0.0 {
                 state.lock().await.log("No open position to close.");
                 return;
             }
+            if qty <= 0.0 {
+                state.lock().await.log_for(&symbol, &format!(
+                    "Partial close {:.0}% [{}]: below the LOT_SIZE step, nothing to sell",
+                    pct, symbol
+                ));
+                return;
+            }
 
             let log_msg = match direction {
-                Direction::Long  => format!("⚠ MANUAL CLOSE [{}]: Selling {:.6} @ ${:.2}", symbol, qty, price),
-                Direction::Short => format!("⚠ MANUAL CLOSE [{}]: Rebuying {:.6} @ ${:.2}", symbol, qty, price),
+                Direction::Long  => format!("⚠ PARTIAL CLOSE {:.0}% [{}]: Selling {:.6} @ ${:.2}", pct, symbol, qty, price),
+                Direction::Short => format!("⚠ PARTIAL CLOSE {:.0}% [{}]: Rebuying {:.6} @ ${:.2}", pct, symbol, qty, price),
             };
-            state.lock().await.log(&log_msg);
+            state.lock().await.log_for(&symbol, &log_msg);
+            warn_testnet_divergence(state, client, &symbol, price, direction == Direction::Short).await;
 
-            let order_result = match direction {
-                Direction::Long  => client.market_sell_qty(&symbol, qty).await,
-                Direction::Short => client.market_buy_qty(&symbol, qty).await,
-            };
+            let side = if direction == Direction::Long { OrderSide::Sell } else { OrderSide::Buy };
+            let order_result = orders::market_exit(client, &symbol, side, qty, None).await;
+            record_api_result(state, slot_id, order_result.is_ok()).await;
 
             match order_result {
                 Ok(order) => {
                     let received: f64 = order.cummulative_quote_qty.parse().unwrap_or(0.0);
+                    // Lo ejecutado de verdad sobre la posición, no el porcentaje pedido
+                    let executed = order.executed_qty.parse::<f64>().ok().filter(|q| *q > 0.0).unwrap_or(qty);
+                    let fraction = (executed / total).min(1.0);
+                    let pnl = position_pnl * fraction;
                     {
                         let mut s = state.lock().await;
                         if let Some(slot) = s.slot_by_id_mut(slot_id) {
-                            slot.strategy.stop();
-                            slot.strategy.clear_trades();
+                            slot.strategy.record_partial_exit(fraction, received, pnl, order.commission());
                         }
-                        s.log(&format!(
-                            "✓ MANUAL CLOSE [{}] executed. Received: ${:.2}",
-                            symbol, received
+                        s.log_for(&symbol, &format!(
+                            "✓ PARTIAL CLOSE [{}] executed. Received: ${:.2}  P&L: ${:.2}",
+                            symbol, received, pnl
                         ));
-                        s.ui_mode = UiMode::PostSale(
-                            slot_id,
-                            SaleResult {
-                                kind: "MANUAL CLOSE".to_string(),
-                                received,
-                                pnl,
-                                pnl_pct,
-                            },
-                        );
+                        notify(&s, Some(slot_id), NotifyEvent::Exit);
                     }
                     save_all_snapshots(state, state_path).await;
+                    refresh_balance(state, client).await;
                 }
                 Err(e) => {
                     state
                         .lock()
                         .await
-                        .log_error(&format!("Manual close [{}] failed: {}", symbol, e));
+                        .log_error_for(&symbol, &format!("Partial close [{}] failed: {}", symbol, e));
                 }
             }
         }
 
-        AppCommand::CfgConfirm => {
-            let (amount, buf) = {
+        AppCommand::SetSelectedParam { field, value } => {
+            let mut s = state.lock().await;
+            let mut log_msg = None;
+            let mut change = ConfigChange::new(String::new());
+            if let Some(slot) = s.selected_mut() {
+                change.set(slot, &field, value.clone());
+                log_msg = Some(format!("{}: {} = {}", slot.symbol, field, value));
+            }
+            if let Some(msg) = log_msg {
+                change.description = msg.clone();
+                s.push_config_change(change);
+                s.log_selected(&msg);
+                drop(s);
+                save_all_snapshots(state, state_path).await;
+            }
+        }
+
+        AppCommand::SetSlotNotify { event, enabled } => {
+            let mut s = state.lock().await;
+            let mut log_msg = None;
+            let mut change = ConfigChange::new(String::new());
+            if let Some(slot) = s.selected_mut() {
+                let mut overrides = slot.strategy.config.notify.clone();
+                match enabled {
+                    Some(on) => overrides.insert(event, on),
+                    None => overrides.remove(&event),
+                };
+                let value = serde_json::to_value(&overrides).unwrap_or_default();
+                change.set(slot, "notify", value);
+                let label = match enabled {
+                    Some(true) => "on",
+                    Some(false) => "off",
+                    None => if event.enabled_by_default() { "on (default)" } else { "off (default)" },
+                };
+                log_msg = Some(format!("{}: notify {} = {}", slot.symbol, event.name(), label));
+            }
+            if let Some(msg) = log_msg {
+                change.description = msg.clone();
+                s.push_config_change(change);
+                s.log_selected(&msg);
+                drop(s);
+                save_all_snapshots(state, state_path).await;
+            }
+        }
+
+        AppCommand::DumpScreen => {
+            state.lock().await.dump_screen_requested = true;
+        }
+        AppCommand::ExportTrades => {
+            // Primero los eventos pendientes al log, para que el export incluya el último fill
+            save_all_snapshots(state, state_path).await;
+            let writer = Arc::clone(&state.lock().await.state_writer);
+            writer.flush().await;
+            // Bajo el lock solo se junta lo del slot; la lectura del log y los archivos van
+            // fuera del motor
+            let (id, symbol, direction, file, cycles) = {
                 let s = state.lock().await;
-                (s.cfg_amount_buf.parse::<f64>().ok(), s.cfg_amount_buf.clone())
+                let Some(sl) = s.selected() else { return };
+                let cycles: Vec<_> = s.cycles.for_slot(sl.id, &sl.symbol).cloned().collect();
+                (sl.id, sl.symbol.clone(), sl.strategy.config.direction.clone(), s.event_log.file(), cycles)
             };
-            match amount {
-                Some(v) if v >= 1.0 => {
-                    {
-                        let mut s = state.lock().await;
-                        let bnb = s.cfg_has_bnb;
-                        // Aplicar a todos los slots
-                        for slot in s.slots.iter_mut() {
-                            slot.strategy.config.quote_amount = v;
-                            slot.strategy.config.has_bnb_balance = bnb;
-                        }
-                        s.ui_mode = UiMode::Normal;
-                        s.log(&format!("Config updated: ${:.2} USDT, BNB Fees: {} (all slots)", v, if bnb { "YES" } else { "NO" }));
-                    }
-                    if let Err(e) = Config::save_dca(config_path, &base_config.symbol, v) {
-                        state.lock().await.log_error(&format!(
-                            "Could not save config: {}",
-                            e
-                        ));
-                    }
-                }
-                _ => {
-                    state.lock().await.log_error(&format!(
-                        "Invalid amount: '{}' (minimum $1)",
-                        buf
-                    ));
+            let pair = symbol.clone();
+            let written = tokio::task::spawn_blocking(move || {
+                let events: Vec<_> = file.events().into_iter().filter(|e| e.slot_id == id).collect();
+                let trades: Vec<_> = journal::trades(&events, [(id, direction)])
+                    .into_iter()
+                    .filter(|t| t.symbol == pair)
+                    .collect();
+                let name = format!("trades-{}", pair);
+                journal::export(&config::exe_dir(), &name, &trades, &cycles).map(|files| (trades.len(), files))
+            })
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|r| r);
+            let mut s = state.lock().await;
+            match written {
+                Ok((count, files)) => {
+                    let names: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+                    s.log_for(&symbol, &format!("[{}] {} trades exported: {}", symbol, count, names.join(" / ")));
                 }
+                Err(e) => s.log_error_for(&symbol, &format!("[{}] Could not export trades: {:#}", symbol, e)),
             }
         }
 
-        AppCommand::CfgToggleBnb => {
+        // --- Historial de alertas (A) ---
+        AppCommand::OpenAlerts => {
+            state.lock().await.ui_mode = UiMode::Alerts;
+        }
+        AppCommand::OpenDiagnostics => {
             let mut s = state.lock().await;
-            s.cfg_has_bnb = !s.cfg_has_bnb;
+            s.diagnostics_scroll = 0;
+            s.ui_mode = UiMode::Diagnostics;
         }
-    }
-}
-
-/// Evalúa las condiciones de un slot y ejecuta órdenes si corresponde
-async fn evaluate_slot(
-    state: &Arc<Mutex<AppState>>,
-    client: &Arc<BinanceClient>,
-    slot_id: usize,
-    max_daily: f64,
-    state_path: &std::path::Path,
-) {
-    let (price, direction, should_entry, should_tp, should_sl, should_trailing_tp,
-         qty, amount, pnl, pnl_pct, auto_restart, auto_flip, cooldown_minutes, symbol, price_peak, price_trough) =
-    {
-        let mut s = state.lock().await;
-        let now = chrono::Utc::now();
-
-        // Tick del timer
-        if let Some(slot) = s.slot_by_id_mut(slot_id) {
-            slot.strategy.tick(now);
+        // --- Vista de log (L) ---
+        AppCommand::OpenLogView => {
+            let mut s = state.lock().await;
+            s.log_view.scroll = 0;
+            s.ui_mode = UiMode::LogView;
         }
-
-        // Obtener símbolo
-        let sym = match s.slot_by_id(slot_id) {
-            Some(sl) => sl.symbol.clone(),
-            None => return,
-        };
-
-        // Obtener precio actual
-        let price = s.prices.get(&sym).map(|m| m.price).unwrap_or(0.0);
-        if price == 0.0 {
-            return;
+        AppCommand::LogScroll(delta) => {
+            let mut s = state.lock().await;
+            let symbol = s.selected().map(|sl| sl.symbol.clone());
+            let matching = s.log.iter().filter(|e| s.log_view.matches(e, symbol.as_deref())).count();
+            let scroll = s.log_view.scroll.saturating_add_signed(delta);
+            s.log_view.scroll = scroll.min(matching.saturating_sub(1));
         }
-
-        // Actualizar extremo (peak para LONG, trough para SHORT)
-        if let Some(slot) = s.slot_by_id_mut(slot_id) {
-            slot.strategy.update_price_peak(price);
+        // --- Ayuda (?) ---
+        AppCommand::OpenHelp => {
+            let mut s = state.lock().await;
+            s.help_scroll = 0;
+            s.ui_mode = UiMode::Help;
         }
-
-        // Leer decisiones y datos del slot
-        let slot = match s.slot_by_id(slot_id) {
-            Some(sl) => sl,
-            None => return,
-        };
-
-        let direction      = slot.strategy.config.direction.clone();
-        let should_entry   = slot.strategy.should_buy(price, now, max_daily);
-        let should_tp      = slot.strategy.should_take_profit(price);
-        let should_sl      = slot.strategy.should_stop_loss(price);
-        let should_trailing_tp = slot.strategy.should_trailing_tp(price);
-        let qty            = slot.strategy.total_quantity();
-        let amount         = slot.strategy.config.quote_amount;
-        let pnl            = slot.strategy.pnl(price);
-        let pnl_pct        = slot.strategy.pnl_pct(price);
-        let auto_restart        = slot.strategy.config.auto_restart;
-        let auto_flip           = slot.strategy.config.auto_flip;
-        let cooldown_minutes    = slot.strategy.config.restart_cooldown_minutes;
-        let symbol         = slot.symbol.clone();
-        let price_peak     = slot.strategy.price_peak;
-        let price_trough   = slot.strategy.price_trough;
-
-        (price, direction, should_entry, should_tp, should_sl, should_trailing_tp,
-         qty, amount, pnl, pnl_pct, auto_restart, auto_flip, cooldown_minutes, symbol, price_peak, price_trough)
-    };
-
-    // =====================================================================
-    // Stop Loss (prioridad máxima)
-    // =====================================================================
-    if should_sl && qty > 0.0 {
-        let log_msg = match direction {
-            Direction::Long  => format!("⚠ STOP LOSS [{}]! Selling {:.6} @ ${:.2}", symbol, qty, price),
-            Direction::Short => format!("⚠ STOP LOSS [{}]! Re-buying {:.6} @ ${:.2}", symbol, qty, price),
-        };
-        state.lock().await.log(&log_msg);
-
-        let order_result = match direction {
-            Direction::Long  => client.market_sell_qty(&symbol, qty).await,
-            Direction::Short => client.market_buy_qty(&symbol, qty).await,
-        };
-
-        match order_result {
-            Ok(order) => {
-                let received: f64 = order.cummulative_quote_qty.parse().unwrap_or(0.0);
-                {
-                    let mut s = state.lock().await;
-                    if let Some(slot) = s.slot_by_id_mut(slot_id) {
-                        slot.strategy.state = DcaState::StopLossReached;
-                        slot.strategy.stop();
-                        slot.strategy.clear_trades();
-                    }
-                    s.log(&format!("✓ STOP LOSS [{}] executed. Received: ${:.2}", symbol, received));
-                    s.ui_mode = UiMode::PostSale(slot_id, SaleResult {
-                        kind: "STOP LOSS".to_string(),
-                        received,
-                        pnl,
-                        pnl_pct,
-                    });
-                }
-                save_all_snapshots(state, state_path).await;
-            }
-            Err(e) => {
-                state.lock().await.log_error(&format!("Stop loss [{}] failed: {}", symbol, e));
+        AppCommand::DiagnosticsScroll(delta) => {
+            let mut s = state.lock().await;
+            let scroll = s.diagnostics_scroll.saturating_add_signed(delta);
+            s.diagnostics_scroll = scroll.min(Tui::diagnostics_max_scroll(&s));
+        }
+        AppCommand::HelpScroll(delta) => {
+            let mut s = state.lock().await;
+            let scroll = s.help_scroll.saturating_add_signed(delta);
+            s.help_scroll = scroll.min(Tui::help_max_scroll());
+        }
+        AppCommand::LogCycleLevel => {
+            state.lock().await.log_view.cycle_level();
+        }
+        AppCommand::LogToggleSlot => {
+            let mut s = state.lock().await;
+            s.log_view.selected_slot_only = !s.log_view.selected_slot_only;
+            s.log_view.scroll = 0;
+        }
+        AppCommand::LogSearchStart => {
+            let mut s = state.lock().await;
+            s.log_view.searching = true;
+            s.log_view.search.clear();
+            s.log_view.scroll = 0;
+        }
+        AppCommand::LogSearchInput(c) => {
+            let mut s = state.lock().await;
+            s.log_view.search.push(c);
+            s.log_view.scroll = 0;
+        }
+        AppCommand::LogSearchBackspace => {
+            let mut s = state.lock().await;
+            s.log_view.search.pop();
+            s.log_view.scroll = 0;
+        }
+        AppCommand::LogSearchDone => {
+            state.lock().await.log_view.searching = false;
+        }
+        AppCommand::NextTab => {
+            let mut s = state.lock().await;
+            s.view_tab = s.view_tab.next();
+        }
+        AppCommand::SelectTab(index) => {
+            if let Some(tab) = app::ViewTab::ALL.get(index) {
+                state.lock().await.view_tab = *tab;
             }
         }
+        AppCommand::CycleAnalysisInterval => {
+            let target = {
+                let mut s = state.lock().await;
+                let window = s.sr_window.max(market_data::CHART_CANDLES);
+                let Some(slot) = s.selected_mut() else { return };
+                let interval = market_data::next_analysis_interval(&slot.analysis_interval);
+                slot.analysis_interval = interval.to_string();
+                let symbol = slot.symbol.clone();
+                s.log_for(&symbol, &format!("[{}] Chart and S/R interval: {}", symbol, interval));
+                let missing = s.candles.closed(&symbol, interval, window).is_none();
+                missing.then_some((symbol, interval, window))
+            };
+            save_all_snapshots(state, state_path).await;
+            // Intervalo sin velas todavía: relleno por REST en vez de esperar al stream
+            if let Some((symbol, interval, window)) = target {
+                let (state, client) = (state.clone(), client.clone());
+                tokio::spawn(async move {
+                    backfill_klines(&state, &client, &symbol, interval, window).await;
+                    state.lock().await.touch();
+                });
+            }
+        }
+        AppCommand::AckAlerts => {
+            let mut s = state.lock().await;
+            for alert in s.alerts.iter_mut() {
+                alert.acknowledged = true;
+            }
+        }
+        AppCommand::ClearAlerts => {
+            let mut s = state.lock().await;
+            s.alerts.clear();
+            s.ui_mode = UiMode::Normal;
+        }
+
+        // --- Presets de estrategia (M) ---
+        AppCommand::OpenPresets => {
+            let mut s = state.lock().await;
+            s.preset_view = app::PresetView::default();
+            s.ui_mode = UiMode::Presets;
+        }
+        AppCommand::PresetSelect(delta) => {
+            let mut s = state.lock().await;
+            let last = s.presets.len().saturating_sub(1) as isize;
+            let view = &mut s.preset_view;
+            view.selected = (view.selected as isize + delta).clamp(0, last) as usize;
+            view.field = 0;
+            view.error = None;
+        }
+        AppCommand::PresetField(delta) => {
+            let mut s = state.lock().await;
+            let count = s.presets.get(s.preset_view.selected).map_or(0, |p| p.fields.len());
+            let view = &mut s.preset_view;
+            view.field = (view.field as isize + delta).clamp(0, count.saturating_sub(1) as isize) as usize;
+            view.error = None;
+        }
+        AppCommand::PresetStartInput(input) => {
+            let mut s = state.lock().await;
+            let preset = s.presets.get(s.preset_view.selected);
+            let buf = match input {
+                PresetInput::SaveAs if s.selected().is_none() => return,
+                PresetInput::SaveAs => String::new(),
+                PresetInput::Rename => match preset {
+                    Some(p) => p.name.clone(),
+                    None => return,
+                },
+                PresetInput::Value => match preset.and_then(|p| p.fields.values().nth(s.preset_view.field)) {
+                    Some(value) => value.to_string(),
+                    None => return,
+                },
+            };
+            s.preset_view.input = Some(input);
+            s.preset_view.buf = buf;
+            s.preset_view.error = None;
+        }
+        AppCommand::PresetInputChar(c) => {
+            let mut s = state.lock().await;
+            s.preset_view.buf.push(c);
+            s.preset_view.error = None;
+        }
+        AppCommand::PresetInputBackspace => {
+            let mut s = state.lock().await;
+            s.preset_view.buf.pop();
+            s.preset_view.error = None;
+        }
+        AppCommand::PresetInputCancel => {
+            let mut s = state.lock().await;
+            s.preset_view.input = None;
+            s.preset_view.error = None;
+        }
+        AppCommand::PresetInputDone => {
+            let mut s = state.lock().await;
+            let Some(input) = s.preset_view.input else { return };
+            let text = s.preset_view.buf.trim().to_string();
+            let done = match input {
+                PresetInput::SaveAs => save_preset(&mut s, &text),
+                PresetInput::Rename => rename_preset(&mut s, &text),
+                PresetInput::Value => set_preset_value(&mut s, base_config, &text),
+            };
+            match done {
+                Ok(msg) => {
+                    s.preset_view.input = None;
+                    s.log(&msg);
+                    save_presets(&mut s);
+                }
+                Err(e) => s.preset_view.error = Some(e),
+            }
+        }
+        AppCommand::PresetRemoveField => {
+            let mut s = state.lock().await;
+            let (index, field) = (s.preset_view.selected, s.preset_view.field);
+            let Some(preset) = s.presets.get_mut(index) else { return };
+            let Some(key) = preset.fields.keys().nth(field).cloned() else { return };
+            preset.fields.remove(&key);
+            let msg = format!("Preset '{}': {} removed, it takes the [dca] value", preset.name, key);
+            let count = preset.fields.len();
+            s.preset_view.field = field.min(count.saturating_sub(1));
+            s.log(&msg);
+            save_presets(&mut s);
+        }
+        AppCommand::PresetDelete => {
+            let mut s = state.lock().await;
+            let index = s.preset_view.selected;
+            let Some(preset) = s.presets.remove(index) else { return };
+            s.preset_view.selected = index.min(s.presets.len().saturating_sub(1));
+            s.preset_view.field = 0;
+            s.log(&format!("Preset '{}' deleted", preset.name));
+            save_presets(&mut s);
+        }
+        AppCommand::SavePreset(name) => {
+            let mut s = state.lock().await;
+            match save_preset(&mut s, &name) {
+                Ok(msg) => {
+                    s.log(&msg);
+                    save_presets(&mut s);
+                }
+                Err(e) => s.log_error(&format!("Preset not saved: {}", e)),
+            }
+        }
+
+        // --- Órdenes condicionales (O) ---
+        AppCommand::OpenTriggers => {
+            let mut s = state.lock().await;
+            s.trigger_selected = 0;
+            s.ui_mode = UiMode::Triggers;
+        }
+        AppCommand::TriggerSelectUp => {
+            let mut s = state.lock().await;
+            s.trigger_selected = s.trigger_selected.saturating_sub(1);
+        }
+        AppCommand::TriggerSelectDown => {
+            let mut s = state.lock().await;
+            if s.trigger_selected + 1 < s.triggers.orders().len() {
+                s.trigger_selected += 1;
+            }
+        }
+        AppCommand::CancelTrigger => {
+            {
+                let mut s = state.lock().await;
+                let Some(id) = s.triggers.orders().get(s.trigger_selected).map(|o| o.id) else { return };
+                if let Some(order) = s.triggers.remove(id) {
+                    s.log(&format!("Conditional #{} cancelled: {}", order.id, order.describe()));
+                }
+                save_triggers(&mut s);
+                s.trigger_selected = s.trigger_selected.min(s.triggers.orders().len().saturating_sub(1));
+            }
+            update_symbol_watch(state, symbol_tx).await;
+        }
+        // --- Escaleras de órdenes LIMIT ---
+        AppCommand::PlaceLadder { side, symbol, total, low, high, count, sizing } => {
+            let id = {
+                let s = state.lock().await;
+                if !s.symbols.contains(&symbol) {
+                    drop(s);
+                    state.lock().await.log_error(&format!("Unknown pair: {}", symbol));
+                    return;
+                }
+                s.ladders.next_id()
+            };
+            let planned = match client.symbol_filters(&symbol).await {
+                Ok(filters) => Ladder::plan(id, side, total, low, high, count, sizing, &filters),
+                Err(e) => Err(e.context(format!("Could not load {} filters", symbol))),
+            };
+            let mut ladder = match planned {
+                Ok(ladder) => ladder,
+                Err(e) => {
+                    state.lock().await.log_error(&format!("Ladder not placed: {:#}", e));
+                    return;
+                }
+            };
+            // Todos los escalones de compra cuentan contra el límite de exposición del asset,
+            // valuados al precio actual (o al del primer escalón si el par no tiene precio)
+            if ladder.side == OrderSide::Buy {
+                let mut s = state.lock().await;
+                let asset = app::parse_symbol(&symbol).0;
+                let price = s
+                    .prices
+                    .get(&symbol)
+                    .map(|m| m.price)
+                    .filter(|p| *p > 0.0)
+                    .unwrap_or(ladder.rungs[0].price);
+                let qty: f64 = ladder.rungs.iter().map(|r| r.quantity).sum();
+                if let Some(Err(reason)) = check_exposure(&s, &asset, price, qty * price) {
+                    s.log_error(&format!("Ladder not placed: {}", reason));
+                    return;
+                }
+            }
+            let errors = ladder::place(client, &mut ladder).await;
+            {
+                let mut s = state.lock().await;
+                let (first, last) = ladder.range();
+                s.log_for(&ladder.symbol, &format!(
+                    "Ladder #{} [{}]: {} ${:.2} in {}/{} orders ${:.4} → ${:.4} ({})",
+                    ladder.id,
+                    ladder.symbol,
+                    if ladder.side == OrderSide::Buy { "BUY" } else { "SELL" },
+                    ladder.total,
+                    ladder.resting_rungs(),
+                    ladder.rungs.len(),
+                    first,
+                    last,
+                    ladder.sizing.label()
+                ));
+                for err in &errors {
+                    s.log_error(&format!("Ladder #{} {}", ladder.id, err));
+                }
+                if ladder.resting_rungs() > 0 || ladder.filled_qty() > 0.0 {
+                    s.ladders.upsert(ladder);
+                    save_ladders(&mut s);
+                }
+            }
+            refresh_balance(state, client).await;
+        }
+        AppCommand::CancelLadder(id) => {
+            let Some(mut ladder) = state.lock().await.ladders.get(id).cloned() else {
+                state.lock().await.log_error(&format!("No ladder #{}", id));
+                return;
+            };
+            let result = ladder::cancel(client, &mut ladder).await;
+            {
+                let mut s = state.lock().await;
+                match result {
+                    Ok(()) if ladder.filled_qty() > 0.0 => s.log(&format!(
+                        "Ladder #{} cancelled, {:.6} filled @ avg ${:.4} (ladder adopt {} to manage it)",
+                        id, ladder.filled_qty(), ladder.average_price(), id
+                    )),
+                    Ok(()) => s.log(&format!("Ladder #{} cancelled, nothing filled", id)),
+                    Err(e) => s.log_error(&format!("Ladder #{}: {:#}", id, e)),
+                }
+                if ladder.resting_rungs() == 0 && ladder.filled_qty() <= 0.0 {
+                    s.ladders.remove(id);
+                } else {
+                    s.ladders.upsert(ladder);
+                }
+                save_ladders(&mut s);
+            }
+            refresh_balance(state, client).await;
+        }
+        AppCommand::AdoptLadder(id) => {
+            let Some(mut ladder) = state.lock().await.ladders.get(id).cloned() else {
+                state.lock().await.log_error(&format!("No ladder #{}", id));
+                return;
+            };
+            let mut cfg = base_config.clone();
+            cfg.symbol = ladder.symbol.clone();
+            cfg.kind = StrategyKind::Trading;
+            cfg.direction = match ladder.side {
+                OrderSide::Buy => Direction::Long,
+                OrderSide::Sell => Direction::Short,
+            };
+            {
+                let mut s = state.lock().await;
+                if !s.can_add_slot() {
+                    let msg = format!("Maximum strategies reached ({}).", s.max_slots);
+                    s.log_error(&msg);
+                    return;
+                }
+                // Cada escalón con algo ejecutado es una entrada del slot: no puede pasar de
+                // las que permite [dca] (se cuentan también los que siguen en el libro)
+                let entries = ladder.rungs.iter().filter(|r| r.filled_qty > 0.0 || r.resting()).count();
+                let max = DcaStrategy::new(cfg.clone()).max_entries();
+                if entries > max {
+                    s.log_error(&format!(
+                        "Ladder #{} not adopted: {} orders, a slot takes at most {} entries ([dca] max_orders / safety_orders)",
+                        id, entries, max
+                    ));
+                    return;
+                }
+            }
+            // Lo que siga en el libro se cancela: el slot gestiona solo lo ejecutado
+            if let Err(e) = ladder::cancel(client, &mut ladder).await {
+                let mut s = state.lock().await;
+                s.log_error(&format!("Ladder #{} not adopted: {:#}", id, e));
+                s.ladders.upsert(ladder);
+                save_ladders(&mut s);
+                return;
+            }
+            if ladder.filled_qty() <= 0.0 {
+                let mut s = state.lock().await;
+                s.log_error(&format!("Ladder #{} has nothing filled to adopt", id));
+                s.ladders.remove(id);
+                save_ladders(&mut s);
+                return;
+            }
+
+            // Las comisiones salen de los fills de cada orden (myTrades); sin ellos quedan
+            // como no informadas
+            let fills = match client.my_trades(&ladder.symbol, RECONCILE_TRADES).await {
+                Ok(fills) => fills,
+                Err(e) => {
+                    state.lock().await.log_error(&format!(
+                        "Ladder #{}: fills not loaded, commissions left unknown: {}",
+                        id, e
+                    ));
+                    Vec::new()
+                }
+            };
+            let mut strat = DcaStrategy::new(cfg);
+            strat.record_created();
+            for rung in ladder.rungs.iter().filter(|r| r.filled_qty > 0.0) {
+                let avg = rung.filled_quote / rung.filled_qty;
+                let order_id = rung.order_id.unwrap_or_default();
+                let rung_fills = fills
+                    .iter()
+                    .filter(|f| rung.order_id == Some(f.order_id))
+                    .map(|f| f.trade_fill())
+                    .collect();
+                strat.record_buy(order_id, rung.price, avg, rung.filled_qty, rung.filled_quote, rung_fills, None);
+            }
+
+            {
+                let mut s = state.lock().await;
+                let slot_id = push_position_slot(&mut s, &ladder.symbol, strat);
+                s.log_for(&ladder.symbol, &format!(
+                    "Ladder #{} handed to a new {} slot: {:.6} @ avg ${:.4}",
+                    id,
+                    ladder.symbol,
+                    ladder.filled_qty(),
+                    ladder.average_price()
+                ));
+                s.ladders.remove(id);
+                save_ladders(&mut s);
+                tokio::spawn(fetch_symbol_filters(state.clone(), client.clone(), slot_id, ladder.symbol.clone()));
+            }
+
+            update_symbol_watch(state, symbol_tx).await;
+            save_all_snapshots(state, state_path).await;
+            refresh_balance(state, client).await;
+        }
+
+        AppCommand::WatchPosition { symbol, position } => {
+            {
+                let mut s = state.lock().await;
+                if let Some(reason) = position_slot_blocked(&s, &symbol) {
+                    s.log_error_for(&symbol, &format!("[{}] Not watched: {}", symbol, reason));
+                    return;
+                }
+            }
+            let (base_asset, _) = app::parse_symbol(&symbol);
+            let free = match client.get_account().await {
+                Ok(account) => account.get_free(&base_asset),
+                Err(e) => {
+                    state.lock().await.log_error_for(&symbol, &format!("[{}] Not watched: balance unavailable: {}", symbol, e));
+                    return;
+                }
+            };
+            let (qty, avg, source) = match position {
+                Some((qty, avg)) => (qty, avg, "entered cost".to_string()),
+                None => {
+                    let trades = match client.my_trades(&symbol, RECONCILE_TRADES).await {
+                        Ok(trades) => trades,
+                        Err(e) => {
+                            state.lock().await.log_error_for(&symbol, &format!("[{}] Not watched: trade history unavailable: {}", symbol, e));
+                            return;
+                        }
+                    };
+                    let Some((traded, avg)) = AccountTrade::holding_cost(&trades) else {
+                        state.lock().await.log_error_for(&symbol, &format!(
+                            "[{}] Not watched: no holding left in the trade history; use watch {} <qty> <avg price>",
+                            symbol, symbol
+                        ));
+                        return;
+                    };
+                    // Lo depositado no tiene costo en el historial: se vigila solo lo comprado
+                    (traded.min(free), avg, format!("cost from {} trade(s)", trades.len()))
+                }
+            };
+            if qty <= 0.0 || qty > free * 1.001 {
+                state.lock().await.log_error_for(&symbol, &format!(
+                    "[{}] Not watched: {:.6} {} to watch but {:.6} free",
+                    symbol, qty, base_asset, free
+                ));
+                return;
+            }
+
+            let mut cfg = base_config.clone();
+            cfg.symbol = symbol.clone();
+            cfg.kind = StrategyKind::Watch;
+            cfg.direction = Direction::Long;
+            // Una vez cerrada la posición no hay nada más que vigilar
+            cfg.auto_restart = false;
+            cfg.auto_flip = false;
+            let mut strat = DcaStrategy::new(cfg);
+            strat.record_created();
+            strat.record_entry(models::order::DcaTrade::new(0, avg, avg, qty, qty * avg, Vec::new(), None));
+
+            {
+                let mut s = state.lock().await;
+                let slot_id = push_position_slot(&mut s, &symbol, strat);
+                s.log_for(&symbol, &format!(
+                    "[{}] Watching {:.6} {} @ avg ${:.4} ({}): exits only, the bot never buys",
+                    symbol, qty, base_asset, avg, source
+                ));
+                tokio::spawn(fetch_symbol_filters(state.clone(), client.clone(), slot_id, symbol.clone()));
+            }
+
+            update_symbol_watch(state, symbol_tx).await;
+            save_all_snapshots(state, state_path).await;
+            refresh_balance(state, client).await;
+        }
+
+        AppCommand::ImportPosition(import) => {
+            let symbol = import.symbol.clone();
+            {
+                let mut s = state.lock().await;
+                if let Some(reason) = position_slot_blocked(&s, &symbol) {
+                    s.log_error_for(&symbol, &format!("[{}] Not imported: {}", symbol, reason));
+                    return;
+                }
+            }
+            let (base_asset, _) = app::parse_symbol(&symbol);
+            let free = match client.get_account().await {
+                Ok(account) => account.get_free(&base_asset),
+                Err(e) => {
+                    state.lock().await.log_error_for(&symbol, &format!("[{}] Not imported: balance unavailable: {}", symbol, e));
+                    return;
+                }
+            };
+            // El slot vendería monedas que la cuenta no tiene
+            let qty = import.quantity();
+            if qty > free * 1.001 {
+                state.lock().await.log_error_for(&symbol, &format!(
+                    "[{}] Not imported: {:.6} {} in the entries but {:.6} free",
+                    symbol, qty, base_asset, free
+                ));
+                return;
+            }
+
+            let mut cfg = base_config.clone();
+            cfg.symbol = symbol.clone();
+            cfg.kind = import.kind;
+            cfg.direction = Direction::Long;
+            if import.kind == StrategyKind::Watch {
+                cfg.auto_restart = false;
+                cfg.auto_flip = false;
+            }
+            let mut strat = DcaStrategy::new(cfg);
+            strat.record_created();
+            for trade in import.trades() {
+                strat.record_entry(trade);
+            }
+
+            {
+                let mut s = state.lock().await;
+                let slot_id = push_position_slot(&mut s, &symbol, strat);
+                let mode = match import.kind {
+                    StrategyKind::Watch => "exits only, the bot never buys",
+                    _ => "safety orders and exit from [dca]",
+                };
+                s.log_for(&symbol, &format!(
+                    "[{}] Imported {} entries: {:.6} {} @ avg ${:.4} ({})",
+                    symbol,
+                    import.entries.len(),
+                    qty,
+                    base_asset,
+                    import.cost() / qty,
+                    mode
+                ));
+                tokio::spawn(fetch_symbol_filters(state.clone(), client.clone(), slot_id, symbol.clone()));
+            }
+
+            update_symbol_watch(state, symbol_tx).await;
+            save_all_snapshots(state, state_path).await;
+            refresh_balance(state, client).await;
+        }
+
+        AppCommand::AddTrigger { side, symbol, amount, price } => {
+            let known = {
+                let s = state.lock().await;
+                if !s.symbols.contains(&symbol) {
+                    drop(s);
+                    state.lock().await.log_error(&format!("Unknown pair: {}", symbol));
+                    return;
+                }
+                s.prices.get(&symbol).map(|m| m.price).filter(|p| *p > 0.0)
+            };
+            // El precio actual decide si se espera una caída o una suba hasta el trigger
+            let current = match known {
+                Some(p) => p,
+                None => match client.get_price(&symbol).await {
+                    Ok(p) => p,
+                    Err(e) => {
+                        state.lock().await.log_error_for(&symbol, &format!("Could not get {} price: {}", symbol, e));
+                        return;
+                    }
+                },
+            };
+            {
+                let mut s = state.lock().await;
+                let order = s.triggers.add(symbol, side, amount, price, current);
+                s.log(&format!("Conditional #{} added: {} (now ${:.4})", order.id, order.describe(), current));
+                save_triggers(&mut s);
+            }
+            update_symbol_watch(state, symbol_tx).await;
+        }
+
+        // --- Editor de parámetros del slot (E) ---
+        AppCommand::OpenEditSlot => {
+            let mut s = state.lock().await;
+            let values = match s.selected() {
+                Some(slot) => edit_slot_values(&slot.strategy.config),
+                None => return,
+            };
+            s.edit_slot_initial = values.clone();
+            s.edit_slot_bufs = values;
+            s.edit_slot_field = 0;
+            s.edit_slot_error = None;
+            s.ui_mode = UiMode::EditSlot;
+        }
+        AppCommand::EditSlotUp => {
+            let mut s = state.lock().await;
+            let n = EDIT_SLOT_FIELDS.len();
+            s.edit_slot_field = (s.edit_slot_field + n - 1) % n;
+        }
+        AppCommand::EditSlotDown => {
+            let mut s = state.lock().await;
+            s.edit_slot_field = (s.edit_slot_field + 1) % EDIT_SLOT_FIELDS.len();
+        }
+        AppCommand::EditSlotInput(c) => {
+            let mut s = state.lock().await;
+            let idx = s.edit_slot_field;
+            if let Some(buf) = s.edit_slot_bufs.get_mut(idx) {
+                if c.is_ascii_digit() || (c == '.' && !buf.contains('.')) {
+                    buf.push(c);
+                }
+            }
+            s.edit_slot_error = None;
+        }
+        AppCommand::EditSlotBackspace => {
+            let mut s = state.lock().await;
+            let idx = s.edit_slot_field;
+            if let Some(buf) = s.edit_slot_bufs.get_mut(idx) {
+                buf.pop();
+            }
+            s.edit_slot_error = None;
+        }
+        AppCommand::EditSlotConfirm => {
+            let mut s = state.lock().await;
+            // Validar solo los campos modificados (mismas reglas que `set` en la paleta)
+            let mut updates: Vec<(&str, &str, serde_json::Value)> = Vec::new();
+            for (i, (_, param)) in EDIT_SLOT_FIELDS.iter().enumerate() {
+                let buf = s.edit_slot_bufs.get(i).map(|b| b.trim()).unwrap_or("");
+                if s.edit_slot_initial.get(i).map(|v| v.as_str()) == Some(buf) {
+                    continue;
+                }
+                match palette::parse_param(param, buf) {
+                    Ok((field, value)) => updates.push((param, field, value)),
+                    Err(e) => {
+                        s.edit_slot_field = i;
+                        s.edit_slot_error = Some(e);
+                        return;
+                    }
+                }
+            }
+            s.ui_mode = UiMode::Normal;
+            if updates.is_empty() {
+                return;
+            }
+            // Solo el slot: quedan en sus config_overrides (snapshot). [dca] de config.toml es
+            // el default de todos los slots y se edita desde el panel de ajustes
+            let mut change = ConfigChange::new(String::new());
+            let Some(slot) = s.selected_mut() else { return };
+            let summary: Vec<String> = updates.iter().map(|(param, _, v)| format!("{} {}", param, v)).collect();
+            change.description = format!("{}: {}", slot.symbol, summary.join(", "));
+            for (_, field, value) in updates {
+                change.set(slot, field, value);
+            }
+            let msg = change.description.clone();
+            s.push_config_change(change);
+            s.log_selected(&msg);
+            drop(s);
+            save_all_snapshots(state, state_path).await;
+        }
+
+        // --- Ajustes globales de config.toml (G) ---
+        AppCommand::OpenSettings => {
+            let keys: Vec<&str> = SETTINGS_FIELDS.iter().map(|(_, key)| *key).collect();
+            let values = match Config::read_settings(config_path, &keys) {
+                Ok(values) => values,
+                Err(e) => {
+                    state.lock().await.log_error(&format!("Could not read config: {:#}", e));
+                    return;
+                }
+            };
+            // Los strings se muestran sin comillas; una clave ausente queda vacía (default)
+            let bufs: Vec<String> = values
+                .iter()
+                .map(|v| match v {
+                    Some(v) => v.as_str().map(String::from).unwrap_or_else(|| v.to_string()),
+                    None => String::new(),
+                })
+                .collect();
+            let mut s = state.lock().await;
+            s.settings_initial = bufs.clone();
+            s.settings_bufs = bufs;
+            s.settings_field = 0;
+            s.settings_error = None;
+            s.ui_mode = UiMode::Settings;
+        }
+        AppCommand::SettingsUp => {
+            let mut s = state.lock().await;
+            let n = SETTINGS_FIELDS.len();
+            s.settings_field = (s.settings_field + n - 1) % n;
+        }
+        AppCommand::SettingsDown => {
+            let mut s = state.lock().await;
+            s.settings_field = (s.settings_field + 1) % SETTINGS_FIELDS.len();
+        }
+        AppCommand::SettingsInput(c) => {
+            let mut s = state.lock().await;
+            let idx = s.settings_field;
+            if let Some(buf) = s.settings_bufs.get_mut(idx) {
+                buf.push(c);
+            }
+            s.settings_error = None;
+        }
+        AppCommand::SettingsBackspace => {
+            let mut s = state.lock().await;
+            let idx = s.settings_field;
+            if let Some(buf) = s.settings_bufs.get_mut(idx) {
+                buf.pop();
+            }
+            s.settings_error = None;
+        }
+        AppCommand::SettingsConfirm => {
+            let mut s = state.lock().await;
+            let mut settings: Vec<config::Setting> = Vec::new();
+            let mut summary: Vec<String> = Vec::new();
+            for (i, (_, key)) in SETTINGS_FIELDS.iter().enumerate() {
+                let buf = s.settings_bufs.get(i).map(|b| b.trim()).unwrap_or("");
+                if s.settings_initial.get(i).map(|v| v.as_str()) == Some(buf) {
+                    continue;
+                }
+                match setting_value(buf) {
+                    Ok(value) => {
+                        summary.push(format!("{} {}", key, if buf.is_empty() { "default" } else { buf }));
+                        settings.push((key.to_string(), value));
+                    }
+                    Err(e) => {
+                        s.settings_field = i;
+                        s.settings_error = Some(e);
+                        return;
+                    }
+                }
+            }
+            if settings.is_empty() {
+                s.ui_mode = UiMode::Normal;
+                return;
+            }
+            // config.toml se valida entero antes de escribir; la recarga en caliente aplica el cambio
+            match Config::save_settings(config_path, &settings) {
+                Ok(previous) => {
+                    let mut change = ConfigChange::new(format!("settings: {}", summary.join(", ")));
+                    change.saved_settings = previous;
+                    let msg = format!("Saved to config.toml: {}", summary.join(", "));
+                    s.push_config_change(change);
+                    s.log(&msg);
+                    s.ui_mode = UiMode::Normal;
+                }
+                Err(e) => s.settings_error = Some(format!("{:#}", e).lines().collect::<Vec<_>>().join(" ")),
+            }
+        }
+
+        // --- Modal nueva estrategia (S) ---
+        AppCommand::OpenNewStrategy => {
+            let mut s = state.lock().await;
+            s.new_strat_symbol_idx = first_unused_symbol(&s);
+            s.new_strat_preset = None;
+            fill_new_strategy(&mut s, base_config, None);
+            s.new_strat_direction = Direction::Long;
+            s.new_strat_group = None;
+            s.ui_mode = UiMode::NewStrategy;
+        }
+        AppCommand::DuplicateSlot => {
+            let mut s = state.lock().await;
+            if !s.can_add_slot() {
+                let msg = format!("Maximum strategies reached ({}).", s.max_slots);
+                s.log_error(&msg);
+                return;
+            }
+            let Some(template) = s.selected().map(|sl| StrategyTemplate {
+                label: format!("Duplicate {}", sl.symbol),
+                config: sl.strategy.config.clone(),
+                overrides: sl.strategy.config_overrides.clone().into_iter().collect(),
+            }) else {
+                return;
+            };
+            s.new_strat_symbol_idx = first_unused_symbol(&s);
+            s.new_strat_preset = None;
+            fill_new_strategy(&mut s, base_config, Some(template));
+            s.ui_mode = UiMode::NewStrategy;
+        }
+        AppCommand::NewStratCyclePreset => {
+            let mut s = state.lock().await;
+            // Sin preset → preset 1 → ... → preset N → sin preset (`[dca]`)
+            let next = match s.new_strat_preset {
+                None if !s.presets.is_empty() => Some(0),
+                Some(i) if i + 1 < s.presets.len() => Some(i + 1),
+                _ => None,
+            };
+            s.new_strat_preset = next;
+            let applied = next.and_then(|i| s.presets.get(i)).map(|preset| {
+                preset.apply(base_config).map(|(config, overrides)| StrategyTemplate {
+                    label: format!("Preset {}", preset.name),
+                    config,
+                    overrides,
+                })
+            });
+            match applied {
+                Some(Ok(template)) => fill_new_strategy(&mut s, base_config, Some(template)),
+                Some(Err(e)) => {
+                    fill_new_strategy(&mut s, base_config, None);
+                    s.new_strat_error = Some(format!("{:#}", e));
+                }
+                None => fill_new_strategy(&mut s, base_config, None),
+            }
+        }
+        AppCommand::NewStratNextField => {
+            let mut s = state.lock().await;
+            s.new_strat_field = (s.new_strat_field + 1) % NEW_STRAT_FIELDS.len();
+        }
+        AppCommand::NewStratInput(c) => {
+            let mut s = state.lock().await;
+            let idx = s.new_strat_field;
+            // '%' solo en el monto (porcentaje del balance libre)
+            let percent_ok = NEW_STRAT_FIELDS[idx].1 == "amount";
+            if let Some(buf) = s.new_strat_bufs.get_mut(idx) {
+                let accepted = c.is_ascii_digit()
+                    || (c == '.' && !buf.contains('.'))
+                    || (c == '%' && percent_ok && !buf.is_empty() && !buf.contains('%'));
+                if accepted && !buf.contains('%') {
+                    buf.push(c);
+                }
+            }
+            s.new_strat_error = None;
+        }
+        AppCommand::NewStratBackspace => {
+            let mut s = state.lock().await;
+            let idx = s.new_strat_field;
+            if let Some(buf) = s.new_strat_bufs.get_mut(idx) {
+                buf.pop();
+            }
+            s.new_strat_error = None;
+        }
+        AppCommand::NewStratSymbolUp => {
+            let mut s = state.lock().await;
+            let len = s.symbols.len();
+            if len > 0 {
+                s.new_strat_symbol_idx =
+                    if s.new_strat_symbol_idx == 0 { len - 1 } else { s.new_strat_symbol_idx - 1 };
+            }
+        }
+        AppCommand::NewStratSymbolDown => {
+            let mut s = state.lock().await;
+            let len = s.symbols.len();
+            if len > 0 {
+                s.new_strat_symbol_idx = (s.new_strat_symbol_idx + 1) % len;
+            }
+        }
+        AppCommand::NewStratToggleDirection => {
+            let mut s = state.lock().await;
+            s.new_strat_direction = match s.new_strat_direction {
+                Direction::Long  => Direction::Short,
+                Direction::Short => Direction::Long,
+            };
+        }
+        AppCommand::NewStratToggleKind => {
+            let mut s = state.lock().await;
+            s.new_strat_kind = match s.new_strat_kind {
+                StrategyKind::Trading => StrategyKind::Accumulate,
+                StrategyKind::Accumulate => StrategyKind::Distribute,
+                StrategyKind::Distribute | StrategyKind::Watch => StrategyKind::Trading,
+            };
+        }
+        AppCommand::NewStratToggleAutoRestart => {
+            let mut s = state.lock().await;
+            s.new_strat_auto_restart = !s.new_strat_auto_restart;
+        }
+        AppCommand::NewStratToggleAutoFlip => {
+            let mut s = state.lock().await;
+            s.new_strat_auto_flip = !s.new_strat_auto_flip;
+        }
+        AppCommand::NewStratToggleBnb => {
+            let mut s = state.lock().await;
+            s.new_strat_has_bnb = !s.new_strat_has_bnb;
+        }
+        AppCommand::NewStratCycleGroup => {
+            let mut s = state.lock().await;
+            // Sin grupo → grupo 1 → ... → grupo N → sin grupo
+            let next = match &s.new_strat_group {
+                None => s.groups.first(),
+                Some(cur) => s
+                    .groups
+                    .iter()
+                    .position(|g| &g.name == cur)
+                    .and_then(|i| s.groups.get(i + 1)),
+            }
+            .map(|g| g.name.clone());
+            s.new_strat_group = next;
+        }
+        AppCommand::NewStratCancel => {
+            state.lock().await.ui_mode = UiMode::Normal;
+        }
+        AppCommand::NewStratReview => {
+            let mut s = state.lock().await;
+            match new_strategy_config(&s, base_config) {
+                Ok((cfg, _)) => {
+                    s.new_strat_preview = Some(cfg);
+                    s.ui_mode = UiMode::NewStrategySummary;
+                }
+                Err((field, e)) => {
+                    s.new_strat_field = field;
+                    s.new_strat_error = Some(e);
+                }
+            }
+        }
+        AppCommand::NewStratBack => {
+            let mut s = state.lock().await;
+            s.new_strat_preview = None;
+            s.ui_mode = UiMode::NewStrategy;
+        }
+        AppCommand::NewStratConfirm => {
+            let (built, inherited, can_add) = {
+                let mut s = state.lock().await;
+                s.new_strat_preview = None;
+                // Duplicado o preset: sus overrides, salvo los campos que elige el modal
+                let inherited: Vec<(String, serde_json::Value)> = s
+                    .new_strat_template
+                    .iter()
+                    .flat_map(|t| t.overrides.iter())
+                    .filter(|(field, _)| !NEW_STRAT_OWN_FIELDS.contains(&field.as_str()))
+                    .cloned()
+                    .collect();
+                (new_strategy_config(&s, base_config), inherited, s.can_add_slot())
+            };
+            // Validado en el modal; solo falla si el estado cambió en el medio
+            let (cfg, fields) = match built {
+                Ok(built) => built,
+                Err((field, e)) => {
+                    let mut s = state.lock().await;
+                    s.new_strat_field = field;
+                    s.new_strat_error = Some(e);
+                    s.ui_mode = UiMode::NewStrategy;
+                    return;
+                }
+            };
+
+            if !can_add {
+                let mut s = state.lock().await;
+                let msg = format!("Maximum strategies reached ({}).", s.max_slots);
+                s.log_error(&msg);
+                return;
+            }
+
+            let symbol = cfg.symbol.clone();
+            let direction = cfg.direction.clone();
+            let mut strat = DcaStrategy::new(cfg);
+            strat.record_created();
+            // Como overrides: sobreviven al snapshot, que parte de `[dca]`
+            for (field, value) in inherited {
+                strat.set_config_field(&field, value);
+            }
+            for (field, value) in fields {
+                strat.set_config_field(field, value);
+            }
+
+            {
+                let mut s = state.lock().await;
+                strat.set_fee_rates(s.fee_rates(&symbol));
+                let id = s.alloc_slot_id();
+                let dir_label = match direction {
+                    Direction::Long  => "LONG",
+                    Direction::Short => "SHORT",
+                };
+                let mut slot = StrategySlot::new(id, symbol.clone(), strat);
+                slot.warming_up = true;
+                s.slots.push(slot);
+                if fee_guard_blocks(&mut s, id) {
+                    s.log_for(&symbol, &format!("New strategy: {} {} created (stopped)", symbol, dir_label));
+                } else {
+                    if let Some(slot) = s.slot_by_id_mut(id) {
+                        slot.strategy.start();
+                    }
+                    s.log_for(&symbol, &format!("New strategy: {} {} started, warming up", symbol, dir_label));
+                }
+                s.selected_slot = s.slots.len() - 1;
+                s.ui_mode = UiMode::Normal;
+                tokio::spawn(fetch_symbol_filters(state.clone(), client.clone(), id, symbol.clone()));
+            }
+
+            update_symbol_watch(state, symbol_tx).await;
+            save_all_snapshots(state, state_path).await;
+            refresh_balance(state, client).await;
+        }
+
+        // --- Post-venta ---
+        AppCommand::PostSaleRestart(slot_id) => {
+            let mut s = state.lock().await;
+            if fee_guard_blocks(&mut s, slot_id) {
+                s.ui_mode = UiMode::Normal;
+                return;
+            }
+            if let Some(slot) = s.slot_by_id_mut(slot_id) {
+                slot.strategy.start();
+            }
+            s.ui_mode = UiMode::Normal;
+            s.log("DCA cycle restarted.");
+            drop(s);
+            save_all_snapshots(state, state_path).await;
+        }
+        AppCommand::PostSaleDismiss(slot_id) => {
+            let mut s = state.lock().await;
+            if let UiMode::PostSale(id, _) = &s.ui_mode {
+                if *id == slot_id {
+                    s.ui_mode = UiMode::Normal;
+                }
+            }
+        }
+
+        // --- Panel de configuración (solo monto) ---
+        AppCommand::OpenConfig => {
+            let mut s = state.lock().await;
+            let (amt, bnb) = s
+                .selected()
+                .map(|sl| (sl.strategy.config.quote_amount, sl.strategy.config.has_bnb_balance))
+                .unwrap_or((base_config.quote_amount, base_config.has_bnb_balance));
+            s.cfg_amount_buf = amt.to_string();
+            s.cfg_has_bnb = bnb;
+            s.cfg_amount_warning = None;
+            s.ui_mode = UiMode::Config;
+        }
+        AppCommand::CloseConfig => {
+            let mut s = state.lock().await;
+            s.cfg_amount_warning = None;
+            s.edit_slot_error = None;
+            s.ui_mode = UiMode::Normal;
+        }
+        AppCommand::CfgInputChar(c) => {
+            let mut s = state.lock().await;
+            let buf = &s.cfg_amount_buf;
+            let accept = if buf.ends_with('%') {
+                false
+            } else if c == '%' {
+                !buf.is_empty()
+            } else {
+                c.is_ascii_digit() || (c == '.' && !buf.contains('.'))
+            };
+            if accept {
+                s.cfg_amount_buf.push(c);
+                s.cfg_amount_warning = None;
+            }
+        }
+        AppCommand::CfgBackspace => {
+            let mut s = state.lock().await;
+            s.cfg_amount_buf.pop();
+            s.cfg_amount_warning = None;
+        }
+        // --- Cierre manual de posición ---
+        AppCommand::OpenConfirmClose => {
+            let mut s = state.lock().await;
+            let has_position = s
+                .selected()
+                .map(|sl| sl.strategy.total_quantity() > 0.0)
+                .unwrap_or(false);
+            if has_position {
+                s.ui_mode = UiMode::ConfirmClose;
+            } else {
+                s.log("No open position to close.");
+            }
+        }
+        AppCommand::ConfirmCloseNow => {
+            let slot_id = {
+                let mut s = state.lock().await;
+                s.ui_mode = UiMode::Normal;
+                match s.selected() {
+                    Some(sl) if sl.strategy.total_quantity() > 0.0 => sl.id,
+                    Some(_) => {
+                        s.log("No open position to close.");
+                        return;
+                    }
+                    None => return,
+                }
+            };
+            if let Some(sale) = close_position(state, client, slot_id, "MANUAL CLOSE", state_path).await {
+                state.lock().await.ui_mode = UiMode::PostSale(slot_id, sale);
+            }
+        }
+
+        AppCommand::CfgConfirm => {
+            let (amount, buf) = {
+                let s = state.lock().await;
+                (s.cfg_amount_buf.parse::<QuoteAmount>().ok(), s.cfg_amount_buf.clone())
+            };
+            let valid = amount.filter(|a| {
+                a.validate().is_ok() && !matches!(a, QuoteAmount::Fixed(v) if *v < 1.0)
+            });
+            match valid {
+                Some(v) => {
+                    {
+                        let mut s = state.lock().await;
+                        let s = &mut *s;
+                        // Preflight: Binance rechaza órdenes bajo el minNotional (-1013)
+                        if let Some(err) = s.min_notional_violation(v) {
+                            s.log_error(&format!("Amount rejected: {}", err));
+                            return;
+                        }
+                        // Fat-finger: un monto desproporcionado requiere un segundo Enter
+                        if s.cfg_amount_warning.take().is_none() {
+                            if let Some(warning) = s.amount_sanity_warning(v) {
+                                s.log(&format!("⚠ Check the amount: {}. Press Enter again to confirm.", warning));
+                                s.cfg_amount_warning = Some(warning);
+                                return;
+                            }
+                        }
+                        let bnb = s.cfg_has_bnb;
+                        let amount_label = match v {
+                            QuoteAmount::Fixed(a) => {
+                                app::fmt_quote_named(a, s.selected().map_or("USDT", |sl| sl.quote_asset.as_str()))
+                            }
+                            QuoteAmount::Percent(_) => format!("{} of balance", v),
+                        };
+                        let mut change = ConfigChange::new(format!("amount {} / BNB on all slots", amount_label));
+                        // Aplicar a todos los slots
+                        for slot in s.slots.iter_mut() {
+                            change.set(slot, "quote_amount", v);
+                            change.set(slot, "has_bnb_balance", bnb);
+                        }
+                        let settings = [
+                            ("dca.quote_amount".to_string(), Some(v.to_toml())),
+                            ("dca.has_bnb_balance".to_string(), Some(bnb.into())),
+                        ];
+                        save_settings(s, config_path, &mut change, &settings);
+                        s.push_config_change(change);
+                        s.ui_mode = UiMode::Normal;
+                        s.log(&format!("Config updated: {}, BNB Fees: {} (all slots)", amount_label, if bnb { "YES" } else { "NO" }));
+                    }
+                }
+                _ => {
+                    state.lock().await.log_error(&format!(
+                        "Invalid amount: '{}' (minimum $1, or 0-100%)",
+                        buf
+                    ));
+                }
+            }
+        }
+
+        AppCommand::CfgToggleBnb => {
+            let mut s = state.lock().await;
+            s.cfg_has_bnb = !s.cfg_has_bnb;
+        }
+    }
+}
+
+/// Evalúa las condiciones de un slot y ejecuta órdenes si corresponde
+#[cfg_attr(feature = "profiling", tracing::instrument(skip_all, fields(slot = slot_id)))]
+async fn evaluate_slot(
+    state: &Arc<Mutex<AppState>>,
+    client: &Arc<BinanceClient>,
+    slot_id: usize,
+    state_path: &std::path::Path,
+) {
+    let (price, direction, entry_trigger, should_tp, should_sl, sl_reason, should_trailing_tp, tp_fraction,
+         tp_qty, qty, amount, pnl, pnl_pct, auto_restart, auto_flip, cooldown_minutes, symbol, price_peak, price_trough,
+         retrying) =
+    {
+        let mut s = profiling::lock(state, "evaluate_slot").await;
+        let now = chrono::Utc::now();
+
+        // Tick del timer
+        if let Some(slot) = s.slot_by_id_mut(slot_id) {
+            slot.strategy.tick(now);
+        }
+
+        // Obtener símbolo
+        let sym = match s.slot_by_id(slot_id) {
+            Some(sl) => sl.symbol.clone(),
+            None => return,
+        };
+
+        // Warm-up: sin entradas hasta recibir un tick en vivo y los filtros del símbolo. Las
+        // salidas de una posición (watch, import, escalera adoptada) corren igual
+        let live = s.live_symbols.contains(&sym);
+        let (warming, warmed_up) = match s.slot_by_id_mut(slot_id) {
+            Some(slot) if slot.warming_up => {
+                if live && slot.filters.is_some() {
+                    slot.warming_up = false;
+                }
+                (slot.warming_up, !slot.warming_up)
+            }
+            _ => (false, false),
+        };
+        if warmed_up {
+            s.log_for(&sym, &format!("✓ {} warm-up complete: live price and filters received", sym));
+        }
+
+        // Símbolo en halt o deslistado: las órdenes serían rechazadas
+        if s.slot_by_id(slot_id).is_some_and(|sl| sl.halted.is_some()) {
+            return;
+        }
+
+        // Obtener precio actual
+        let price = s.prices.get(&sym).map(|m| m.price).unwrap_or(0.0);
+        if price == 0.0 {
+            return;
+        }
+
+        // Precio viejo (WebSocket caído o colgado): ni entradas ni salidas hasta que se actualice
+        if s.prices.get(&sym).is_some_and(|m| m.is_stale(s.max_price_age_secs, now)) {
+            if s.stale_symbols.insert(sym.clone()) {
+                let msg = format!(
+                    "[{}] Price older than {}s: trading paused until the feed recovers",
+                    sym, s.max_price_age_secs
+                );
+                s.log_error_for(&sym, &msg);
+            }
+            return;
+        }
+        if s.stale_symbols.remove(&sym) {
+            s.log_for(&sym, &format!("✓ [{}] Price feed recovered, trading resumed", sym));
+        }
+
+        // Actualizar extremo (peak para LONG, trough para SHORT) y armar el stop en break-even
+        let mut breakeven_msg = None;
+        if let Some(slot) = s.slot_by_id_mut(slot_id) {
+            slot.strategy.update_price_peak(price);
+            if slot.strategy.update_breakeven(price) {
+                breakeven_msg = Some(format!(
+                    "[{}] Break-even stop armed @ ${:.4} (P&L reached +{:.2}%)",
+                    sym, slot.strategy.breakeven_stop_price(), slot.strategy.config.breakeven_trigger_pct
+                ));
+            }
+            // EXIT PENDING sin posición (cerrada a mano o como polvo): nada que reintentar
+            if slot.exit_pending.is_some() && slot.strategy.total_quantity() <= 0.0 {
+                slot.exit_pending = None;
+            }
+        }
+        if let Some(msg) = breakeven_msg {
+            s.log_for(&sym, &msg);
+        }
+
+        // Distribución: el inventario es el balance libre al arrancar (si no se configuró)
+        // y termina cuando lo que queda no alcanza el mínimo del exchange
+        let mut distribution_msg = None;
+        if let Some(slot) = s.slot_by_id_mut(slot_id).filter(|sl| sl.strategy.distributing()) {
+            if slot.strategy.config.distribute_qty <= 0.0 && slot.base_balance > 0.0 {
+                let qty = slot.base_balance;
+                slot.strategy.set_config_field("distribute_qty", qty);
+                distribution_msg = Some(format!(
+                    "[{}] Distribution inventory set to the free balance: {:.6} {}",
+                    sym, qty, slot.base_asset
+                ));
+            } else if slot.strategy.config.distribute_qty > 0.0 && slot.strategy.state.is_active() {
+                let min_notional = slot.filters.as_ref().map(|f| f.min_notional).unwrap_or(0.0);
+                let remaining = slot.strategy.remaining_inventory();
+                if remaining * price < min_notional.max(f64::EPSILON) {
+                    slot.strategy.stop();
+                    distribution_msg = Some(format!(
+                        "[{}] Distribution complete: {:.6} {} sold @ avg ${:.4}",
+                        sym, slot.strategy.total_quantity(), slot.base_asset, slot.strategy.average_cost()
+                    ));
+                }
+            }
+        }
+        if let Some(msg) = distribution_msg {
+            s.log_for(&sym, &msg);
+        }
+
+        // Leer decisiones y datos del slot
+        let slot = match s.slot_by_id(slot_id) {
+            Some(sl) => sl,
+            None => return,
+        };
+        // Límite propio del slot (o el de [risk]) y lo que deja el tope global
+        let max_daily = s.daily_limit(slot);
+
+        let direction      = slot.strategy.config.direction.clone();
+        let amount         = slot.entry_amount(price);
+        // EXIT PENDING: la salida ya se decidió, se reintenta aunque el precio haya vuelto
+        let pending        = slot.exit_pending.as_ref().map(|p| p.reason);
+        // Ola de volumen en contra: se espera a que pase
+        let adverse_spike  = slot.strategy.config.pause_on_volume_spike
+            && s.volume.spike(&sym).is_some_and(|spike| match direction {
+                Direction::Long => spike.volume.sell > spike.volume.buy,
+                Direction::Short => spike.volume.buy > spike.volume.sell,
+            });
+        // LIMIT_MAKER rechazada hace poco: se espera su backoff antes de volver a colocarla
+        let maker_waiting  = slot.maker_retry.is_some_and(|(_, at)| now < at);
+        let entry_trigger  = if amount > 0.0 && pending.is_none() && !warming && !adverse_spike && !maker_waiting
+            && !s.orders.has_order(slot_id)
+        {
+            slot.strategy.entry_trigger(price, now, max_daily, amount)
+        } else {
+            None
+        };
+        let should_tp      = slot.strategy.should_take_profit(price);
+        // El stop loss configurado tiene prioridad sobre el de break-even
+        let sl_reason = match pending {
+            Some(reason @ (ExitReason::StopLoss | ExitReason::BreakEven)) => Some(reason),
+            Some(_) => None,
+            None if slot.strategy.should_stop_loss(price) => Some(ExitReason::StopLoss),
+            None if slot.strategy.should_breakeven_stop(price) => Some(ExitReason::BreakEven),
+            None => None,
+        };
+        let should_sl      = sl_reason.is_some();
+        let sl_reason      = sl_reason.unwrap_or(ExitReason::StopLoss);
+        let should_trailing_tp = slot.strategy.should_trailing_tp(price);
+        let (should_tp, should_sl, should_trailing_tp) = match pending {
+            Some(ExitReason::StopLoss | ExitReason::BreakEven) => (false, true, false),
+            Some(ExitReason::TakeProfit | ExitReason::TakeProfitLevel) => (true, false, false),
+            Some(ExitReason::TrailingTp) => (false, false, true),
+            None => (should_tp, should_sl, should_trailing_tp),
+        };
+        let mut tp_fraction = slot.strategy.take_profit_close_fraction();
+        if pending == Some(ExitReason::TakeProfit) {
+            tp_fraction = 1.0;
+        }
+        // Nivel parcial cuya parte (o el resto) queda bajo el mínimo del exchange: cerrar todo
+        if let Some(filters) = slot.filters.as_ref().filter(|_| tp_fraction < 1.0) {
+            let total = slot.strategy.total_quantity();
+            let part = total * tp_fraction;
+            if filters.compliant_qty(part, Some(price)).is_err()
+                || filters.compliant_qty(total - part, Some(price)).is_err()
+            {
+                tp_fraction = 1.0;
+            }
+        }
+        let qty            = slot.strategy.total_quantity();
+        // Parte del nivel al paso de LOT_SIZE, lo que Binance ejecuta de verdad
+        let tp_qty         = slot.filters.as_ref().map_or(qty * tp_fraction, |f| f.round_qty(qty * tp_fraction));
+        let pnl            = slot.strategy.pnl(price);
+        let pnl_pct        = slot.strategy.pnl_pct(price);
+        // Reduce-only: al cerrar la posición el slot se detiene en vez de reiniciar
+        let auto_restart        = slot.strategy.config.auto_restart && !slot.strategy.config.reduce_only;
+        let auto_flip           = slot.strategy.config.auto_flip;
+        let cooldown_minutes    = slot.strategy.config.restart_cooldown_minutes;
+        let symbol         = slot.symbol.clone();
+        let price_peak     = slot.strategy.price_peak;
+        let price_trough   = slot.strategy.price_trough;
+
+        (price, direction, entry_trigger, should_tp, should_sl, sl_reason, should_trailing_tp, tp_fraction,
+         tp_qty, qty, amount, pnl, pnl_pct, auto_restart, auto_flip, cooldown_minutes, symbol, price_peak, price_trough,
+         pending.is_some())
+    };
+
+    // Presupuesto y límites compartidos del grupo; efectivo de la billetera virtual
+    let should_entry = entry_trigger.is_some()
+        && group_allows_entry(state, slot_id, amount).await
+        && wallet_allows_entry(state, slot_id, amount).await
+        && exposure_allows_entry(state, slot_id, amount, price).await;
+
+    // Testnet: el precio mostrado viene de mainnet, verificar el book antes de operar
+    let exiting = (should_sl || should_tp || should_trailing_tp) && qty > 0.0;
+    if (exiting || should_entry) && !retrying {
+        let is_buy = (direction == Direction::Long) != exiting;
+        warn_testnet_divergence(state, client, &symbol, price, is_buy).await;
+    }
+
+    // =====================================================================
+    // Stop Loss / stop en break-even (prioridad máxima)
+    // =====================================================================
+    if should_sl && qty > 0.0 {
+        let kind = sl_reason.label();
+        let log_msg = match direction {
+            Direction::Long  => format!("⚠ {} [{}]! Selling {:.6} @ ${:.2}", kind, symbol, qty, price),
+            Direction::Short => format!("⚠ {} [{}]! Re-buying {:.6} @ ${:.2}", kind, symbol, qty, price),
+        };
+        // Reintento de EXIT PENDING: el aviso ya se dio cuando se disparó
+        if !retrying {
+            let mut s = state.lock().await;
+            s.log_for(&symbol, &log_msg);
+            notify(&s, Some(slot_id), NotifyEvent::StopLoss);
+        }
+
+        let order_result = send_exit(state, client, slot_id, &symbol, &direction, qty).await;
+        record_api_result(state, slot_id, order_result.is_ok()).await;
+
+        match order_result {
+            Ok(order) => {
+                let received: f64 = order.cummulative_quote_qty.parse().unwrap_or(0.0);
+                {
+                    let mut s = state.lock().await;
+                    if let Some(slot) = s.slot_by_id_mut(slot_id) {
+                        slot.strategy.set_state(DcaState::StopLossReached);
+                        slot.strategy.record_exit(kind, received, pnl, order.commission());
+                        resolve_pending_exit(slot);
+                    }
+                    s.log_for(&symbol, &format!("✓ {} [{}] executed. Received: ${:.2}", kind, symbol, received));
+                    s.ui_mode = UiMode::PostSale(slot_id, SaleResult {
+                        kind: kind.to_string(),
+                        received,
+                        pnl,
+                        pnl_pct,
+                    });
+                }
+                save_all_snapshots(state, state_path).await;
+            }
+            Err(e) if close_dust_position(state, slot_id, &e, state_path).await => {}
+            Err(e) => exit_failed(state, slot_id, sl_reason, &symbol, e, state_path).await,
+        }
+        return;
+    }
+
+    // =====================================================================
+    // Take Profit escalonado: nivel intermedio, cierra solo una parte
+    // =====================================================================
+    if should_tp && qty > 0.0 && tp_fraction < 1.0 {
+        let log_msg = match direction {
+            Direction::Long  => format!("✓ TAKE PROFIT LEVEL [{}]! {:.0}% of position  Selling {:.6} @ ${:.2}", symbol, tp_fraction * 100.0, tp_qty, price),
+            Direction::Short => format!("✓ TAKE PROFIT LEVEL [{}]! {:.0}% of position  Re-buying {:.6} @ ${:.2}", symbol, tp_fraction * 100.0, tp_qty, price),
+        };
+        if !retrying {
+            state.lock().await.log_for(&symbol, &log_msg);
+        }
+
+        let order_result = send_exit(state, client, slot_id, &symbol, &direction, tp_qty).await;
+        record_api_result(state, slot_id, order_result.is_ok()).await;
+
+        match order_result {
+            Ok(order) => {
+                let received: f64 = order.cummulative_quote_qty.parse().unwrap_or(0.0);
+                // Lo ejecutado de verdad sobre la posición, no la fracción del nivel
+                let executed = order.executed_qty.parse::<f64>().ok().filter(|q| *q > 0.0).unwrap_or(tp_qty);
+                let fraction = (executed / qty).min(1.0);
+                let part_pnl = pnl * fraction;
+                {
+                    let mut s = state.lock().await;
+                    if let Some(slot) = s.slot_by_id_mut(slot_id) {
+                        slot.strategy.record_take_profit_step(fraction, received, part_pnl, order.commission());
+                        resolve_pending_exit(slot);
+                    }
+                    s.log_for(&symbol, &format!(
+                        "✓ TAKE PROFIT LEVEL [{}] executed. Received: ${:.2}  P&L: +${:.2}",
+                        symbol, received, part_pnl
+                    ));
+                    notify(&s, Some(slot_id), NotifyEvent::Exit);
+                }
+                save_all_snapshots(state, state_path).await;
+                refresh_balance(state, client).await;
+                return;
+            }
+            // La parte del nivel quedó bajo el mínimo del exchange (filtros todavía sin
+            // cargar, o el precio cambió): se cierra toda la posición en este mismo tick
+            Err(e) if e.downcast_ref::<BelowMinimum>().is_some() => {
+                state.lock().await.log_for(&symbol, &format!(
+                    "TAKE PROFIT LEVEL [{}]: {}; closing the whole position", symbol, e
+                ));
+            }
+            Err(e) => {
+                exit_failed(state, slot_id, ExitReason::TakeProfitLevel, &symbol, e, state_path).await;
+                return;
+            }
+        }
+    }
+
+    // =====================================================================
+    // Take Profit
+    // =====================================================================
+    if should_tp && qty > 0.0 {
+        let log_msg = match direction {
+            Direction::Long  => format!("✓ TAKE PROFIT [{}]! P&L: +${:.2}  Selling {:.6} @ ${:.2}", symbol, pnl, qty, price),
+            Direction::Short => format!("✓ TAKE PROFIT [{}]! P&L: +${:.2}  Re-buying {:.6} @ ${:.2}", symbol, pnl, qty, price),
+        };
+        if !retrying {
+            state.lock().await.log_for(&symbol, &log_msg);
+        }
+
+        let order_result = send_exit(state, client, slot_id, &symbol, &direction, qty).await;
+        record_api_result(state, slot_id, order_result.is_ok()).await;
+
+        match order_result {
+            Ok(order) => {
+                let received: f64 = order.cummulative_quote_qty.parse().unwrap_or(0.0);
+                {
+                    let mut s = state.lock().await;
+                    let mut flipped_to = None;
+                    let auto_restart = auto_restart && !fee_guard_blocks(&mut s, slot_id);
+                    if let Some(slot) = s.slot_by_id_mut(slot_id) {
+                        slot.strategy.set_state(DcaState::TakeProfitReached);
+                        slot.strategy.record_exit("TAKE PROFIT", received, pnl, order.commission());
+                        resolve_pending_exit(slot);
+                        if auto_restart {
+                            if auto_flip {
+                                let flipped = slot.strategy.config.direction.flip();
+                                slot.strategy.set_config_field("direction", &flipped);
+                                flipped_to = Some(flipped);
+                            }
+                            slot.strategy.start_after_tp(cooldown_minutes);
+                        } else {
+                            slot.strategy.stop();
+                        }
+                    }
+
+                    if let Some(dir) = flipped_to {
+                        let dir_label = match dir {
+                            Direction::Long => "LONG",
+                            Direction::Short => "SHORT",
+                        };
+                        s.log(&format!("Auto-flip enabled. Switched to {} mode.", dir_label));
+                    }
+                    s.log_for(&symbol, &format!("✓ TAKE PROFIT [{}] executed. Received: ${:.2}", symbol, received));
+                    notify(&s, Some(slot_id), NotifyEvent::Exit);
+                    if auto_restart {
+                        s.log("Auto-restart enabled. DCA cycle restarted.");
+                    } else {
+                        s.ui_mode = UiMode::PostSale(slot_id, SaleResult {
+                            kind: "TAKE PROFIT".to_string(),
+                            received,
+                            pnl,
+                            pnl_pct,
+                        });
+                    }
+                }
+                save_all_snapshots(state, state_path).await;
+            }
+            Err(e) if close_dust_position(state, slot_id, &e, state_path).await => {}
+            Err(e) => exit_failed(state, slot_id, ExitReason::TakeProfit, &symbol, e, state_path).await,
+        }
+        return;
+    }
+
+    // =====================================================================
+    // Trailing Take Profit
+    // =====================================================================
+    if should_trailing_tp && qty > 0.0 {
+        let log_msg = match direction {
+            Direction::Long => {
+                let drop = ((price_peak - price) / price_peak) * 100.0;
+                format!(
+                    "↓ TRAILING TP [{}]! Max: ${:.4}  Drop: {:.2}%  P&L: +${:.2}",
+                    symbol, price_peak, drop, pnl
+                )
+            }
+            Direction::Short => {
+                let rise = ((price - price_trough) / price_trough) * 100.0;
+                format!(
+                    "↑ TRAILING TP [{}]! Min: ${:.4}  Rise: {:.2}%  P&L: +${:.2}",
+                    symbol, price_trough, rise, pnl
+                )
+            }
+        };
+        if !retrying {
+            state.lock().await.log_for(&symbol, &log_msg);
+        }
+
+        let order_result = send_exit(state, client, slot_id, &symbol, &direction, qty).await;
+        record_api_result(state, slot_id, order_result.is_ok()).await;
+
+        match order_result {
+            Ok(order) => {
+                let received: f64 = order.cummulative_quote_qty.parse().unwrap_or(0.0);
+                {
+                    let mut s = state.lock().await;
+                    let mut flipped_to = None;
+                    let auto_restart = auto_restart && !fee_guard_blocks(&mut s, slot_id);
+                    if let Some(slot) = s.slot_by_id_mut(slot_id) {
+                        slot.strategy.set_state(DcaState::TakeProfitReached);
+                        slot.strategy.record_exit("TRAILING TP", received, pnl, order.commission());
+                        resolve_pending_exit(slot);
+                        if auto_restart {
+                            if auto_flip {
+                                let flipped = slot.strategy.config.direction.flip();
+                                slot.strategy.set_config_field("direction", &flipped);
+                                flipped_to = Some(flipped);
+                            }
+                            slot.strategy.start_after_tp(cooldown_minutes);
+                        } else {
+                            slot.strategy.stop();
+                        }
+                    }
+
+                    if let Some(dir) = flipped_to {
+                        let dir_label = match dir {
+                            Direction::Long => "LONG",
+                            Direction::Short => "SHORT",
+                        };
+                        s.log(&format!("Auto-flip enabled. Switched to {} mode.", dir_label));
+                    }
+                    s.log_for(&symbol, &format!("✓ TRAILING TP [{}] executed. Received: ${:.2}", symbol, received));
+                    notify(&s, Some(slot_id), NotifyEvent::Exit);
+                    if auto_restart {
+                        s.log("Auto-restart enabled. DCA cycle restarted.");
+                    } else {
+                        s.ui_mode = UiMode::PostSale(slot_id, SaleResult {
+                            kind: "TRAILING TP".to_string(),
+                            received,
+                            pnl,
+                            pnl_pct,
+                        });
+                    }
+                }
+                save_all_snapshots(state, state_path).await;
+            }
+            Err(e) if close_dust_position(state, slot_id, &e, state_path).await => {}
+            Err(e) => exit_failed(state, slot_id, ExitReason::TrailingTp, &symbol, e, state_path).await,
+        }
+        return;
+    }
+
+    // =====================================================================
+    // Entrada DCA (orders::market_entry, o una LIMIT del gestor de órdenes)
+    //   LONG:  compra USDT → base asset
+    //   SHORT: vende base asset → recibe USDT
+    // =====================================================================
+    if should_entry {
+        // En testnet las órdenes se llenan contra el libro de testnet, no contra el de
+        // mainnet que llega por el stream: el spread sale de su bookTicker
+        let testnet_spread = if client.is_testnet() {
+            Some(client.cached_book_ticker(&symbol).await.ok().and_then(|b| b.spread_pct()))
+        } else {
+            None
+        };
+        let intent = {
+            let mut s = state.lock().await;
+            let spread = testnet_spread.unwrap_or_else(|| s.prices.get(&symbol).and_then(|m| m.spread_pct()));
+            match s.slot_by_id(slot_id) {
+                Some(sl) if sl.strategy.config.entry_order_type != EntryOrderType::Market => {
+                    Some(entry_intent(sl, amount, entry_trigger))
+                }
+                // Spread amplio: una orden market pagaría el cruce del libro, se usa
+                // LIMIT_MAKER al mejor bid (LONG) / ask (SHORT)
+                Some(sl) => match spread {
+                    Some(spread) if sl.strategy.config.max_market_spread_pct > 0.0
+                        && spread > sl.strategy.config.max_market_spread_pct =>
+                    {
+                        let max = sl.strategy.config.max_market_spread_pct;
+                        let mut intent = entry_intent(sl, amount, entry_trigger);
+                        intent.post_only = true;
+                        intent.offset_pct = 0.0;
+                        s.log_for(&symbol, &format!(
+                            "Spread [{}] {:.3}% > {:.3}%: entry as LIMIT_MAKER instead of market",
+                            symbol, spread, max
+                        ));
+                        Some(intent)
+                    }
+                    _ => None,
+                },
+                None => return,
+            }
+        };
+        if let Some(intent) = intent {
+            submit_entry(state, client, slot_id, intent, price, 0, state_path).await;
+            return;
+        }
+        match direction {
+            Direction::Long => {
+                let order_num = {
+                    state.lock().await
+                        .slot_by_id(slot_id)
+                        .map(|sl| sl.strategy.trades.len() + 1)
+                        .unwrap_or(1)
+                };
+                tracing::info!(
+                    "Executing DCA LONG buy [{}] #{} of ${:.2}",
+                    symbol, order_num, amount
+                );
+
+                let order_result = orders::market_entry(client, &symbol, OrderSide::Buy, amount, price).await;
+                record_api_result(state, slot_id, order_result.is_ok()).await;
+                match order_result {
+                    Ok(order) => {
+                        let exec_qty: f64 = order.executed_qty.parse().unwrap_or(0.0);
+                        let cost: f64 = order.cummulative_quote_qty.parse().unwrap_or(amount);
+                        let actual_price = if exec_qty > 0.0 { cost / exec_qty } else { price };
+                        {
+                            let mut s = state.lock().await;
+                            if let Some(slot) = s.slot_by_id_mut(slot_id) {
+                                let num = slot.strategy.trades.len() + 1;
+                                let base = slot.base_asset.clone();
+                                slot.strategy.record_buy(
+                                    order.order_id, price, actual_price, exec_qty, cost, order.trade_fills(), entry_trigger,
+                                );
+                                s.log_for(&symbol, &format!(
+                                    "BUY #{} [{}]: {:.6} {} @ ${:.4} (${:.2})",
+                                    num, symbol, exec_qty, base, actual_price, cost
+                                ));
+                            }
+                        }
+                        save_all_snapshots(state, state_path).await;
+                    }
+                    Err(e) => {
+                        let mut s = state.lock().await;
+                        let mut err_msg = format!("Buy [{}] failed: {}", symbol, e);
+                        
+                        if err_msg.contains("-2010") {
+                            if let Some(slot) = s.slot_by_id(slot_id) {
+                                let needed = amount - slot.quote_balance;
+                                if needed > 0.0 {
+                                    err_msg = format!("Buy [{}] failed: Insufficient balance. You need ${:.2} more {}.", symbol, needed, slot.quote_asset);
+                                }
+                            }
+                        }
+                        
+                        s.log_error_for(&symbol, &err_msg);
+                        if let Some(slot) = s.slot_by_id_mut(slot_id) {
+                            slot.strategy.stop();
+                            slot.strategy.set_state(DcaState::Idle);
+                        }
+                        s.log_for(&symbol, &format!("Strategy for {} STOPPED due to error.", symbol));
+                        notify(&s, Some(slot_id), NotifyEvent::Error);
+                    }
+                }
+            }
+
+            Direction::Short => {
+                let qty_to_sell = if price > 0.0 { amount / price } else { return };
+                let order_num = {
+                    state.lock().await
+                        .slot_by_id(slot_id)
+                        .map(|sl| sl.strategy.trades.len() + 1)
+                        .unwrap_or(1)
+                };
+                tracing::info!(
+                    "Executing DCA SHORT sell [{}] #{}: {:.6}",
+                    symbol, order_num, qty_to_sell
+                );
+
+                let order_result = orders::market_entry(client, &symbol, OrderSide::Sell, amount, price).await;
+                record_api_result(state, slot_id, order_result.is_ok()).await;
+                match order_result {
+                    Ok(order) => {
+                        let exec_qty: f64 = order.executed_qty.parse().unwrap_or(0.0);
+                        let received: f64 = order.cummulative_quote_qty.parse().unwrap_or(amount);
+                        let actual_price = if exec_qty > 0.0 { received / exec_qty } else { price };
+                        {
+                            let mut s = state.lock().await;
+                            if let Some(slot) = s.slot_by_id_mut(slot_id) {
+                                let num = slot.strategy.trades.len() + 1;
+                                let base = slot.base_asset.clone();
+                                slot.strategy.record_buy(
+                                    order.order_id, price, actual_price, exec_qty, received, order.trade_fills(), entry_trigger,
+                                );
+                                let label = if slot.strategy.distributing() { "SELL" } else { "SHORT" };
+                                s.log_for(&symbol, &format!(
+                                    "{} #{} [{}]: sold {:.6} {} @ ${:.4} (${:.2})",
+                                    label, num, symbol, exec_qty, base, actual_price, received
+                                ));
+                            }
+                        }
+                        save_all_snapshots(state, state_path).await;
+                    }
+                    Err(e) => {
+                        let mut s = state.lock().await;
+                        let mut err_msg = format!("Short entry [{}] failed: {}", symbol, e);
+                        
+                        if err_msg.contains("-2010") {
+                            if let Some(slot) = s.slot_by_id(slot_id) {
+                                let needed = qty_to_sell - slot.base_balance;
+                                if needed > 0.0 {
+                                    err_msg = format!("Short entry [{}] failed: Insufficient balance. You need {:.6} more {}.", symbol, needed, slot.base_asset);
+                                }
+                            }
+                        }
+                        
+                        s.log_error_for(&symbol, &err_msg);
+                        if let Some(slot) = s.slot_by_id_mut(slot_id) {
+                            slot.strategy.stop();
+                            slot.strategy.set_state(DcaState::Idle);
+                        }
+                        s.log_for(&symbol, &format!("Strategy for {} STOPPED due to error.", symbol));
+                        notify(&s, Some(slot_id), NotifyEvent::Error);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Intención de entrada LIMIT / LIMIT_MAKER de un slot por `amount` USDT, a
+/// `limit_offset_pct`% del precio (debajo para LONG, encima para SHORT)
+fn entry_intent(slot: &StrategySlot, amount: f64, trigger: Option<EntryTrigger>) -> EntryIntent {
+    let cfg = &slot.strategy.config;
+    EntryIntent {
+        symbol: slot.symbol.clone(),
+        side: match cfg.direction {
+            Direction::Long => OrderSide::Buy,
+            Direction::Short => OrderSide::Sell,
+        },
+        amount,
+        post_only: cfg.entry_order_type == EntryOrderType::Maker,
+        offset_pct: cfg.limit_offset_pct,
+        iceberg_visible_pct: cfg.iceberg_visible_pct,
+        timeout_secs: cfg.limit_timeout_secs,
+        entries_before: slot.strategy.trades.len(),
+        trigger,
+    }
+}
+
+/// Envía la intención de entrada al gestor de órdenes, que coloca la orden y la sigue.
+/// `replaces` indica cuántas veces ya venció sin llenarse (acerca el precio al mercado)
+async fn submit_entry(
+    state: &Arc<Mutex<AppState>>,
+    client: &Arc<BinanceClient>,
+    slot_id: usize,
+    intent: EntryIntent,
+    price: f64,
+    replaces: u32,
+    state_path: &std::path::Path,
+) {
+    let filters = {
+        let s = state.lock().await;
+        let Some(slot) = s.slot_by_id(slot_id) else { return };
+        slot.filters.clone().unwrap_or_default()
+    };
+    let result = orders::place(client, &intent, &filters, price, replaces).await;
+    record_api_result(state, slot_id, result.is_ok()).await;
+    let symbol = &intent.symbol;
+    match result {
+        Ok(Some(order)) => {
+            let mut s = state.lock().await;
+            let label = if order.side == OrderSide::Buy { "BUY" } else { "SELL" };
+            let visible = intent
+                .iceberg_qty(&filters, order.quantity)
+                .map(|v| format!(", iceberg {:.6} visible", v))
+                .unwrap_or_default();
+            s.log_for(symbol, &format!(
+                "{} {} #{} [{}] placed: {:.6} @ ${:.4} (${:.2}{})",
+                if order.post_only { "LIMIT_MAKER" } else { "LIMIT" },
+                label, intent.entries_before + 1, symbol, order.quantity, order.price,
+                order.quantity * order.price, visible
+            ));
+            if let Some(slot) = s.slot_by_id_mut(slot_id) {
+                slot.maker_retry = None;
+            }
+            s.orders.track(ManagedOrder { slot_id, intent, order });
+            drop(s);
+            save_all_snapshots(state, state_path).await;
+        }
+        Ok(None) => {}
+        // El libro se movió en cada intento: se reintenta con backoff exponencial, no en
+        // cada tick mientras el mercado siga corriendo
+        Err(e) if intent.post_only && BinanceClient::would_take(&e) => {
+            let mut s = state.lock().await;
+            let Some(slot) = s.slot_by_id_mut(slot_id) else { return };
+            let rejections = slot.maker_retry.map_or(0, |(n, _)| n) + 1;
+            let delay = orders::maker_retry_delay(rejections);
+            slot.maker_retry = Some((rejections, chrono::Utc::now() + delay));
+            s.log_for(symbol, &format!(
+                "LIMIT_MAKER [{}] kept crossing the book ({} in a row), retrying in {}s",
+                symbol,
+                rejections,
+                delay.num_seconds()
+            ));
+        }
+        Err(e) => {
+            let mut s = state.lock().await;
+            s.log_error_for(symbol, &format!("Limit entry [{}] failed: {}", symbol, e));
+            if let Some(slot) = s.slot_by_id_mut(slot_id) {
+                slot.strategy.stop();
+                slot.strategy.set_state(DcaState::Idle);
+            }
+            s.log_for(symbol, &format!("Strategy for {} STOPPED due to error.", symbol));
+            notify(&s, Some(slot_id), NotifyEvent::Error);
+        }
+    }
+}
+
+/// Tick del gestor de órdenes: registra los fills de las entradas LIMIT pendientes y cancela
+/// las que vencieron (re-colocándolas más cerca del mercado), las de ciclos ya cerrados y
+/// las de slots detenidos
+async fn poll_entry_orders(
+    state: &Arc<Mutex<AppState>>,
+    client: &Arc<BinanceClient>,
+    state_path: &std::path::Path,
+) {
+    let now = chrono::Utc::now();
+    let (due, abandon) = {
+        let mut s = state.lock().await;
+        let due = s.orders.due(now);
+        let abandon: Vec<usize> = due
+            .iter()
+            .filter(|m| {
+                s.slot_by_id(m.slot_id).is_none_or(|sl| {
+                    !sl.strategy.state.is_active() || sl.strategy.trades.len() < m.order.entries_before
+                })
+            })
+            .map(|m| m.slot_id)
+            .collect();
+        (due, abandon)
+    };
+    if due.is_empty() {
+        return;
+    }
+
+    let mut changed = false;
+    let mut chase = Vec::new();
+    for (slot_id, update) in orders::sync(client, &due, &abandon, now).await {
+        let Some(managed) = due.iter().find(|m| m.slot_id == slot_id) else { continue };
+        let (order, symbol) = (&managed.order, &managed.intent.symbol);
+        let (status, timed_out) = match update {
+            Ok(OrderUpdate::Resting) => continue,
+            Ok(OrderUpdate::Closed { status, timed_out }) => (status, timed_out),
+            Err(e) => {
+                state.lock().await.log_error(&format!("{:#}", e));
+                continue;
+            }
+        };
+
+        changed = true;
+        let exec_qty: f64 = status.executed_qty.parse().unwrap_or(0.0);
+        let quote: f64 = status.cummulative_quote_qty.parse().unwrap_or(0.0);
+        let mut s = state.lock().await;
+        s.orders.untrack(slot_id);
+        let Some(slot) = s.slot_by_id_mut(slot_id) else { continue };
+        let cycle_closed = slot.strategy.trades.len() < order.entries_before;
+        let active = slot.strategy.state.is_active();
+        let base = slot.base_asset.clone();
+        // Lo ya llenado cuenta como entrada. Si el ciclo cerró mientras la orden esperaba,
+        // el fill abre la posición actual: el asset está en la wallet y el bot lo gestiona
+        if exec_qty > 0.0 {
+            let fill_price = quote / exec_qty;
+            // GET /order no trae los fills: la comisión queda sin reportar
+            slot.strategy.record_buy(order.order_id, order.price, fill_price, exec_qty, quote, Vec::new(), order.trigger);
+            if cycle_closed {
+                s.log_error_for(symbol, &format!(
+                    "LIMIT [{}] filled {:.6} {} @ ${:.4} after its cycle closed; recorded as an entry of the current position",
+                    symbol, exec_qty, base, fill_price
+                ));
+            } else {
+                s.log_for(symbol, &format!(
+                    "LIMIT #{} [{}] filled: {:.6} {} @ ${:.4} (${:.2})",
+                    order.entries_before + 1, symbol, exec_qty, base, fill_price, quote
+                ));
+            }
+        }
+        match status.status {
+            OrderStatus::Filled => {}
+            _ if exec_qty > 0.0 => {}
+            _ if timed_out && active && !cycle_closed => {
+                let replaces = order.replaces + 1;
+                s.log_for(symbol, &format!(
+                    "LIMIT entry [{}] unfilled after {}s, re-placing at {:.3}% from the market",
+                    symbol,
+                    managed.intent.timeout_secs,
+                    managed.intent.offset_pct(replaces)
+                ));
+                chase.push((managed.clone(), replaces));
+            }
+            _ => s.log_for(symbol, &format!("LIMIT entry [{}] cancelled", symbol)),
+        }
+    }
+    if changed {
+        save_all_snapshots(state, state_path).await;
+    }
+
+    for (managed, replaces) in chase {
+        let price = state.lock().await.prices.get(&managed.intent.symbol).map(|m| m.price).unwrap_or(0.0);
+        if price > 0.0 {
+            submit_entry(state, client, managed.slot_id, managed.intent, price, replaces, state_path).await;
+        }
+    }
+}
+
+/// Salida automática que falló. Si Binance no respondió queda EXIT PENDING: se reintenta en
+/// cada tick (aunque el precio ya no cumpla la condición) y se guarda en el snapshot para
+/// sobrevivir reinicios. Si la orden pudo haber llegado (timeout, 5xx) se guarda su client
+/// order ID para consultarla antes de reenviarla. Un rechazo de la API descarta la salida
+/// como antes
+async fn exit_failed(
+    state: &Arc<Mutex<AppState>>,
+    slot_id: usize,
+    reason: ExitReason,
+    symbol: &str,
+    err: anyhow::Error,
+    state_path: &std::path::Path,
+) {
+    if settle_partial_exit(state, slot_id, symbol, &err, state_path).await {
+        return;
+    }
+    let mut s = state.lock().await;
+    let unreachable = BinanceClient::is_unreachable(&err);
+    let unsettled = BinanceClient::uncertain_order(&err).map(str::to_string);
+    let Some(slot) = s.slot_by_id_mut(slot_id) else { return };
+    let was_pending = slot.exit_pending.is_some();
+    if !unreachable {
+        slot.exit_pending = None;
+        s.log_error_for(symbol, &format!("{} [{}] failed: {}", reason.label(), symbol, err));
+        drop(s);
+        if was_pending {
+            save_all_snapshots(state, state_path).await;
+        }
+        return;
+    }
+    if let Some(pending) = slot.exit_pending.as_mut() {
+        pending.attempts += 1;
+        pending.last_error = err.to_string();
+        pending.client_order_id = unsettled;
+        tracing::warn!("EXIT PENDING {} [{}] attempt {} failed: {}", reason.label(), symbol, pending.attempts, err);
+        return;
+    }
+    slot.exit_pending = Some(PendingExit::new(reason, err.to_string(), unsettled));
+    s.log_error_for(symbol, &format!(
+        "EXIT PENDING: {} [{}] could not reach Binance ({}), retrying every second",
+        reason.label(), symbol, err
+    ));
+    notify(&s, Some(slot_id), NotifyEvent::Error);
+    drop(s);
+    save_all_snapshots(state, state_path).await;
+}
+
+/// Salida sin respuesta que Binance ejecutó solo en parte (`orders::PartialExit`): registra
+/// lo ejecutado como cierre parcial y deja EXIT PENDING, sin client order ID, para el resto.
+/// False si `err` es otro error
+async fn settle_partial_exit(
+    state: &Arc<Mutex<AppState>>,
+    slot_id: usize,
+    symbol: &str,
+    err: &anyhow::Error,
+    state_path: &std::path::Path,
+) -> bool {
+    let Some(partial) = err.downcast_ref::<orders::PartialExit>() else { return false };
+    let executed = partial.executed_qty();
+    let received: f64 = partial.order.cummulative_quote_qty.parse().unwrap_or(0.0);
+    {
+        let mut s = state.lock().await;
+        let Some(slot) = s.slot_by_id_mut(slot_id) else { return true };
+        let total = slot.strategy.total_quantity();
+        if total <= 0.0 {
+            return true;
+        }
+        let fraction = (executed / total).min(1.0);
+        // P&L al precio promedio del fill, sobre la parte ejecutada
+        let pnl = slot.strategy.pnl(received / executed) * fraction;
+        slot.strategy.record_partial_exit(fraction, received, pnl, partial.order.commission());
+        if let Some(pending) = slot.exit_pending.as_mut() {
+            pending.attempts += 1;
+            pending.last_error = err.to_string();
+            pending.client_order_id = None;
+        }
+        s.log_error_for(symbol, &format!(
+            "EXIT PENDING [{}]: {}. Received: ${:.2}  P&L: ${:.2}; retrying the rest",
+            symbol, err, received, pnl
+        ));
+        notify(&s, Some(slot_id), NotifyEvent::Error);
+    }
+    save_all_snapshots(state, state_path).await;
+    true
+}
+
+/// Orden de mercado que cierra `qty` de la posición. Si un EXIT PENDING dejó una orden sin
+/// respuesta, `orders::market_exit` la consulta antes de enviar otra
+async fn send_exit(
+    state: &Arc<Mutex<AppState>>,
+    client: &BinanceClient,
+    slot_id: usize,
+    symbol: &str,
+    direction: &Direction,
+    qty: f64,
+) -> Result<models::order::Order> {
+    let unsettled = {
+        let s = state.lock().await;
+        s.slot_by_id(slot_id)
+            .and_then(|sl| sl.exit_pending.as_ref())
+            .and_then(|p| p.client_order_id.clone())
+    };
+    let side = match direction {
+        Direction::Long => OrderSide::Sell,
+        Direction::Short => OrderSide::Buy,
+    };
+    orders::market_exit(client, symbol, side, qty, unsettled.as_deref()).await
+}
+
+/// La salida pendiente (si había) llegó a Binance
+fn resolve_pending_exit(slot: &mut StrategySlot) {
+    if let Some(pending) = slot.exit_pending.take() {
+        tracing::info!(
+            "EXIT PENDING {} [{}] executed after {} failed attempts",
+            pending.reason.label(), slot.symbol, pending.attempts
+        );
+    }
+}
+
+/// Posición por debajo del mínimo del exchange (LOT_SIZE / NOTIONAL): ninguna orden puede
+/// cerrarla, así que se cierra localmente y el remanente queda como polvo en la wallet.
+/// Devuelve false si el error es de otro tipo.
+async fn close_dust_position(
+    state: &Arc<Mutex<AppState>>,
+    slot_id: usize,
+    err: &anyhow::Error,
+    state_path: &std::path::Path,
+) -> bool {
+    let Some(dust) = err.downcast_ref::<BelowMinimum>() else { return false };
+    {
+        let mut s = state.lock().await;
+        let price = s.prices.get(&dust.symbol).map(|m| m.price).filter(|p| *p > 0.0);
+        let mut pnl = 0.0;
+        if let Some(slot) = s.slot_by_id_mut(slot_id) {
+            // Nada se vende: el resultado es el polvo a precio actual contra el costo que queda
+            // (sin comisión de salida). Sin precio no se inventa una pérdida total
+            if let Some(price) = price {
+                let value = slot.strategy.total_quantity() * price;
+                let invested = slot.strategy.total_invested();
+                pnl = match slot.strategy.config.direction {
+                    Direction::Long => value - invested,
+                    Direction::Short => invested - value,
+                };
+            }
+            slot.strategy.record_exit("DUST", 0.0, pnl, None);
+        }
+        s.log_error_for(&dust.symbol, &format!(
+            "{}; position closed locally, {:.8} left in the wallet as dust (P&L ${:.2})",
+            dust, dust.quantity, pnl
+        ));
+    }
+    save_all_snapshots(state, state_path).await;
+    true
+}
+
+/// Cancela en Binance órdenes LIMIT de entrada que el bot deja de seguir. Lo que se llenó
+/// antes de la cancelación se registra como entrada del slot (los llamadores guardan después)
+async fn cancel_entry_orders(
+    state: &Arc<Mutex<AppState>>,
+    client: &Arc<BinanceClient>,
+    pending: Vec<ManagedOrder>,
+) {
+    for managed in pending {
+        let symbol = &managed.intent.symbol;
+        let status = match orders::cancel_untracked(client, &managed).await {
+            Ok(status) => status,
+            Err(e) => {
+                state.lock().await.log_error_for(symbol, &format!("{:#}", e));
+                continue;
+            }
+        };
+        let exec_qty: f64 = status.executed_qty.parse().unwrap_or(0.0);
+        let quote: f64 = status.cummulative_quote_qty.parse().unwrap_or(0.0);
+        let mut s = state.lock().await;
+        if exec_qty <= 0.0 {
+            s.log_for(symbol, &format!("LIMIT entry [{}] cancelled", symbol));
+            continue;
+        }
+        let order = &managed.order;
+        match s.slot_by_id_mut(managed.slot_id) {
+            Some(slot) => {
+                // GET /order no trae los fills: la comisión queda sin reportar
+                slot.strategy.record_buy(order.order_id, order.price, quote / exec_qty, exec_qty, quote, Vec::new(), order.trigger);
+                s.log_for(symbol, &format!(
+                    "LIMIT entry [{}] cancelled after filling {:.6} @ ${:.4}: recorded as an entry",
+                    symbol, exec_qty, quote / exec_qty
+                ));
+            }
+            None => s.log_error_for(symbol, &format!(
+                "LIMIT entry [{}] filled {:.6} before it was cancelled; its slot is gone, not tracked",
+                symbol, exec_qty
+            )),
+        }
+    }
+}
+
+/// Exceso tolerado (en %) de lo registrado sobre la wallet: comisiones cobradas en el asset base
+const RESTORE_DUST_PCT: f64 = 1.0;
+
+/// Arranque: compara la cantidad registrada por los slots LONG con el balance real de cada
+/// asset base. Si lo registrado supera la wallet por más que polvo se marca en el modal de
+/// restauración, que ofrece ajustar las posiciones
+async fn check_holdings(state: &Arc<Mutex<AppState>>, client: &Arc<BinanceClient>) {
+    let mut recorded: std::collections::BTreeMap<String, (Vec<usize>, f64)> = std::collections::BTreeMap::new();
+    for slot in state.lock().await.slots.iter() {
+        let qty = slot.strategy.total_quantity();
+        if slot.strategy.config.direction == Direction::Long && qty > 0.0 {
+            let entry = recorded.entry(slot.base_asset.clone()).or_default();
+            entry.0.push(slot.id);
+            entry.1 += qty;
+        }
+    }
+    if recorded.is_empty() {
         return;
     }
+    let account = match client.get_account().await {
+        Ok(account) => account,
+        Err(e) => {
+            tracing::warn!("Could not check holdings on restore: {}", e);
+            return;
+        }
+    };
+
+    let mut s = state.lock().await;
+    for (asset, (slot_ids, qty)) in recorded {
+        let held = account
+            .balances
+            .iter()
+            .find(|b| b.asset == asset)
+            .map(|b| b.free_f64() + b.locked_f64())
+            .unwrap_or(0.0);
+        if qty > held * (1.0 + RESTORE_DUST_PCT / 100.0) {
+            tracing::warn!("Restored {} positions ({}) exceed holdings ({})", asset, qty, held);
+            s.restore_issues.push(RestoreIssue::ExceedsHoldings { asset, slot_ids, recorded: qty, held });
+        }
+    }
+}
+
+/// Fills consultados por símbolo al reconciliar (GET /api/v3/myTrades)
+const RECONCILE_TRADES: u32 = 1000;
+
+/// Arranque: compara las posiciones restauradas con los fills reales de Binance. Fills que
+/// reducen la posición después del último evento del slot (venta manual, OCO, una salida
+/// cuyo resultado no llegó a registrarse) cierran el ciclo o la parte correspondiente en vez
+/// de confiar a ciegas en strategy_state.json
+async fn reconcile_positions(
+    state: &Arc<Mutex<AppState>>,
+    client: &Arc<BinanceClient>,
+    events: &[SlotEvent],
+    state_path: &std::path::Path,
+) {
+    let positions: Vec<(usize, String, chrono::DateTime<chrono::Utc>, std::collections::HashSet<u64>)> = {
+        let s = state.lock().await;
+        s.slots
+            .iter()
+            .filter(|sl| !sl.strategy.trades.is_empty())
+            .map(|sl| {
+                // Desde el último evento del slot (o la última compra registrada)
+                let since = events
+                    .iter()
+                    .filter(|e| e.slot_id == sl.id)
+                    .map(|e| e.timestamp)
+                    .chain(sl.strategy.trades.iter().map(|t| t.timestamp))
+                    .max()
+                    .unwrap_or_default();
+                let mut known: std::collections::HashSet<u64> =
+                    sl.strategy.trades.iter().map(|t| t.order_id).collect();
+                // La entrada LIMIT pendiente la registra el gestor de órdenes
+                known.extend(s.orders.get(sl.id).map(|m| m.order.order_id));
+                (sl.id, sl.symbol.clone(), since, known)
+            })
+            .collect()
+    };
+
+    let mut changed = false;
+    for (slot_id, symbol, since, known) in &positions {
+        if positions.iter().filter(|(_, sym, _, _)| sym == symbol).count() > 1 {
+            state.lock().await.log_error_for(symbol, &format!(
+                "Reconcile [{}]: several slots hold a position, fills cannot be attributed; check balances manually",
+                symbol
+            ));
+            continue;
+        }
+        let fills = match client.my_trades(symbol, RECONCILE_TRADES).await {
+            Ok(fills) => fills,
+            Err(e) => {
+                state.lock().await.log_error_for(symbol, &format!("Reconcile [{}] failed: {}", symbol, e));
+                continue;
+            }
+        };
+        let fills: Vec<_> = fills
+            .into_iter()
+            .filter(|f| f.time_utc() > *since && !known.contains(&f.order_id))
+            .collect();
+        if fills.is_empty() {
+            continue;
+        }
+
+        let mut s = state.lock().await;
+        let Some(slot) = s.slot_by_id_mut(*slot_id) else { continue };
+        // LONG se cierra vendiendo, SHORT recomprando
+        let closes_as_buyer = slot.strategy.config.direction == Direction::Short;
+        let (closing, foreign): (Vec<_>, Vec<_>) = fills.iter().partition(|f| f.is_buyer == closes_as_buyer);
+        let qty: f64 = closing.iter().map(|f| f.qty.parse::<f64>().unwrap_or(0.0)).sum();
+        let quote: f64 = closing.iter().map(|f| f.quote_qty.parse::<f64>().unwrap_or(0.0)).sum();
+        let commission = closing.first().map(|first| Commission {
+            asset: first.commission_asset.clone(),
+            amount: closing
+                .iter()
+                .filter(|f| f.commission_asset == first.commission_asset)
+                .map(|f| f.commission.parse::<f64>().unwrap_or(0.0))
+                .sum(),
+        });
+        let mut msgs = Vec::new();
+        if !foreign.is_empty() {
+            msgs.push(format!(
+                "⚠ Reconcile [{}]: {} fill(s) adding to the position while the bot was down; not tracked by the bot",
+                symbol,
+                foreign.len()
+            ));
+        }
+        let position = slot.strategy.total_quantity();
+        if qty > 0.0 && position > 0.0 {
+            let fraction = (qty / position).min(1.0);
+            let invested = slot.strategy.total_invested() * fraction;
+            let pnl = match slot.strategy.config.direction {
+                Direction::Long => quote - invested,
+                Direction::Short => invested - quote,
+            };
+            // Restos por redondeo del lote cuentan como cierre completo
+            if fraction >= 0.999 {
+                slot.strategy.record_exit("RECONCILED", quote, pnl, commission);
+                slot.strategy.stop();
+                msgs.push(format!(
+                    "⚠ Reconcile [{}]: position closed outside the bot ({:.6} @ ${:.4}, P&L ${:.2}); cycle closed and strategy STOPPED",
+                    symbol, qty, quote / qty, pnl
+                ));
+            } else {
+                slot.strategy.record_partial_exit(fraction, quote, pnl, commission);
+                msgs.push(format!(
+                    "⚠ Reconcile [{}]: {:.0}% of the position closed outside the bot ({:.6} @ ${:.4}, P&L ${:.2})",
+                    symbol, fraction * 100.0, qty, quote / qty, pnl
+                ));
+            }
+            changed = true;
+        }
+        for msg in msgs {
+            s.log_error_for(symbol, &msg);
+        }
+    }
+    if changed {
+        save_all_snapshots(state, state_path).await;
+    }
+}
+
+/// Testnet: avisa si el book de testnet difiere del precio mostrado (que viene de mainnet),
+/// ya que los fills en testnet pueden ser muy distintos
+async fn warn_testnet_divergence(
+    state: &Arc<Mutex<AppState>>,
+    client: &Arc<BinanceClient>,
+    symbol: &str,
+    price: f64,
+    is_buy: bool,
+) {
+    match client.testnet_divergence(symbol, price, is_buy).await {
+        Ok(Some((book_price, diff_pct))) => {
+            state.lock().await.log_error_for(symbol, &format!(
+                "Testnet price for {} is ${:.4} ({:+.2}% vs displayed ${:.4}); fills may differ",
+                symbol, book_price, diff_pct, price
+            ));
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Testnet book check ({}) failed: {}", symbol, e),
+    }
+}
+
+/// Campos de la config que elige el modal de nueva estrategia: de un slot duplicado o un
+/// preset no se copian como overrides (reduce-only tampoco, el slot nuevo arranca operando)
+const NEW_STRAT_OWN_FIELDS: [&str; 8] =
+    ["symbol", "direction", "kind", "auto_restart", "auto_flip", "has_bnb_balance", "group", "reduce_only"];
+
+/// Primer símbolo de la lista sin slot, para pre-seleccionarlo en el modal
+fn first_unused_symbol(s: &AppState) -> usize {
+    s.symbols.iter().position(|sym| !s.slots.iter().any(|sl| &sl.symbol == sym)).unwrap_or(0)
+}
+
+/// Carga en el modal de nueva estrategia los valores de `template` (None = `[dca]`)
+fn fill_new_strategy(s: &mut AppState, base_config: &DcaConfig, template: Option<StrategyTemplate>) {
+    let cfg = template.as_ref().map(|t| &t.config).unwrap_or(base_config);
+    s.new_strat_direction = cfg.direction.clone();
+    s.new_strat_kind = cfg.kind;
+    s.new_strat_auto_restart = cfg.auto_restart;
+    s.new_strat_auto_flip = cfg.auto_flip;
+    s.new_strat_has_bnb = cfg.has_bnb_balance;
+    s.new_strat_group = cfg.group.clone();
+    s.new_strat_bufs = NEW_STRAT_FIELDS.iter().map(|(_, param)| param_value(cfg, param)).collect();
+    s.new_strat_field = 0;
+    s.new_strat_error = None;
+    s.new_strat_template = template;
+}
+
+/// Campos editados en el modal como (campo de la config, valor), guardados como overrides
+type EditedFields = Vec<(&'static str, serde_json::Value)>;
+
+/// Config del modal de nueva estrategia y los campos editados que difieren de la base
+/// (`[dca]`, el slot duplicado o el preset; validados como `set` en la paleta).
+/// Err: (índice en NEW_STRAT_FIELDS, mensaje)
+fn new_strategy_config(s: &AppState, base_config: &DcaConfig) -> Result<(DcaConfig, EditedFields), (usize, String)> {
+    let template = s.new_strat_template.as_ref().map(|t| &t.config).unwrap_or(base_config);
+    let mut fields = Vec::new();
+    for (i, (_, param)) in NEW_STRAT_FIELDS.iter().enumerate() {
+        let buf = s.new_strat_bufs.get(i).map(|b| b.trim()).unwrap_or("");
+        if buf.is_empty() || buf == param_value(template, param) {
+            continue;
+        }
+        fields.push(palette::parse_param(param, buf).map_err(|e| (i, e))?);
+    }
+
+    let idx = s.new_strat_symbol_idx.min(s.symbols.len().saturating_sub(1));
+    let mut cfg = template.clone();
+    cfg.reduce_only = false;
+    cfg.symbol = s.symbols.get(idx).cloned().unwrap_or_else(|| "BTCUSDT".to_string());
+    cfg.direction = s.new_strat_direction.clone();
+    cfg.kind = s.new_strat_kind;
+    // La acumulación solo compra y la distribución solo vende; ninguna cierra el ciclo
+    match cfg.kind {
+        StrategyKind::Accumulate => cfg.direction = Direction::Long,
+        StrategyKind::Distribute => cfg.direction = Direction::Short,
+        StrategyKind::Trading | StrategyKind::Watch => {}
+    }
+    cfg.auto_restart = s.new_strat_auto_restart;
+    cfg.auto_flip = s.new_strat_auto_flip;
+    if cfg.kind != StrategyKind::Trading {
+        cfg.auto_flip = false;
+    }
+    cfg.has_bnb_balance = s.new_strat_has_bnb;
+    cfg.group = s.new_strat_group.clone();
+    if !fields.is_empty() {
+        let mut json = serde_json::to_value(&cfg).map_err(|e| (0, e.to_string()))?;
+        if let Some(obj) = json.as_object_mut() {
+            for (field, value) in &fields {
+                obj.insert(field.to_string(), value.clone());
+            }
+        }
+        cfg = serde_json::from_value(json).map_err(|e| (0, e.to_string()))?;
+    }
+    Ok((cfg, fields))
+}
+
+/// Por qué no se puede crear un slot para una posición existente (watch / import)
+fn position_slot_blocked(s: &AppState, symbol: &str) -> Option<String> {
+    if !s.symbols.iter().any(|known| known == symbol) {
+        return Some("unknown pair".to_string());
+    }
+    if !s.can_add_slot() {
+        return Some(format!("maximum strategies reached ({})", s.max_slots));
+    }
+    // Dos slots vendiendo las mismas monedas se pisarían las salidas
+    let held = s.slots.iter().any(|sl| {
+        sl.symbol == symbol && sl.strategy.config.direction == Direction::Long && !sl.strategy.trades.is_empty()
+    });
+    held.then(|| "another slot already holds a position in it".to_string())
+}
+
+/// Agrega un slot que nace con una posición (watch / import / ladder adoptada), lo arranca
+/// si el guard de comisiones lo permite y lo selecciona. Devuelve su ID.
+fn push_position_slot(s: &mut AppState, symbol: &str, mut strat: DcaStrategy) -> usize {
+    strat.set_fee_rates(s.fee_rates(symbol));
+    let slot_id = s.alloc_slot_id();
+    let mut slot = StrategySlot::new(slot_id, symbol.to_string(), strat);
+    // El warm-up solo frena entradas (órdenes de seguridad): SL/TP de la posición corren ya
+    slot.warming_up = true;
+    s.slots.push(slot);
+    if !fee_guard_blocks(s, slot_id) {
+        if let Some(slot) = s.slot_by_id_mut(slot_id) {
+            slot.strategy.start();
+        }
+    }
+    s.selected_slot = s.slots.len() - 1;
+    s.ui_mode = UiMode::Normal;
+    slot_id
+}
+
+/// Guard de comisiones: avisa si las fees del ciclo se comen el TP esperado.
+/// Devuelve true si la config pide bloquear el (re)inicio en ese caso.
+fn fee_guard_blocks(s: &mut AppState, slot_id: usize) -> bool {
+    let (symbol, ratio, tp, block) = match s.slot_by_id(slot_id) {
+        Some(slot) => match slot.strategy.fee_guard_exceeded() {
+            Some(ratio) => (
+                slot.symbol.clone(),
+                ratio,
+                slot.strategy.effective_take_profit_pct(),
+                slot.strategy.config.block_restart_on_fees,
+            ),
+            None => return false,
+        },
+        None => return false,
+    };
+    let msg = format!(
+        "Fees on {} eat {:.0}% of the expected TP ({:.2}%). Raise take_profit_pct",
+        symbol, ratio * 100.0, tp
+    );
+    if block {
+        s.log_error_for(&symbol, &format!("{} (restart blocked)", msg));
+    } else {
+        s.log_error_for(&symbol, &msg);
+    }
+    block
+}
+
+/// Verifica los límites del grupo del slot antes de una entrada.
+/// Loguea solo cuando el slot pasa de permitido a bloqueado (y viceversa).
+async fn group_allows_entry(state: &Arc<Mutex<AppState>>, slot_id: usize, amount: f64) -> bool {
+    let mut s = state.lock().await;
+    let (symbol, result) = {
+        let slot = match s.slot_by_id(slot_id) {
+            Some(sl) => sl,
+            None => return false,
+        };
+        let group = match s.group_of(slot) {
+            Some(g) => g,
+            None => return true,
+        };
+        let members = s
+            .slots
+            .iter()
+            .filter(|sl| sl.strategy.config.group.as_deref() == Some(group.name.as_str()))
+            .map(|sl| &sl.strategy);
+        (slot.symbol.clone(), groups::check_entry(group, members, amount))
+    };
+
+    let was_blocked = s.slot_by_id(slot_id).map(|sl| sl.group_blocked).unwrap_or(false);
+    if let Some(slot) = s.slot_by_id_mut(slot_id) {
+        slot.group_blocked = result.is_err();
+    }
+    match result {
+        Ok(()) => {
+            if was_blocked {
+                s.log_for(&symbol, &format!("[{}] Group budget available again, entries resumed.", symbol));
+            }
+            true
+        }
+        Err(reason) => {
+            if !was_blocked {
+                s.log_error_for(&symbol, &format!("[{}] Entry skipped: {}", symbol, reason));
+            }
+            false
+        }
+    }
+}
+
+/// Billetera virtual: la entrada no puede gastar más que el efectivo del slot, aunque la
+/// cuenta de Binance tenga saldo (es de las demás estrategias)
+async fn wallet_allows_entry(state: &Arc<Mutex<AppState>>, slot_id: usize, amount: f64) -> bool {
+    let mut s = state.lock().await;
+    let Some(slot) = s.slot_by_id_mut(slot_id) else { return false };
+    let Some(cash) = slot.wallet_cash() else { return true };
+    let allowed = amount <= cash + 1e-9;
+    let was_blocked = std::mem::replace(&mut slot.wallet_blocked, !allowed);
+    let symbol = slot.symbol.clone();
+    if allowed && was_blocked {
+        s.log_for(&symbol, &format!("[{}] Wallet cash available again, entries resumed.", symbol));
+    } else if !allowed && !was_blocked {
+        s.log_error_for(&symbol, &format!(
+            "[{}] Entry skipped: ${:.2} needed, ${:.2} left in the slot wallet",
+            symbol, amount, cash
+        ));
+    }
+    allowed
+}
+
+/// Exposición máxima por asset: una entrada LONG no puede llevar la posición combinada
+/// (slots, entradas pendientes y balance de la cuenta) por encima de `risk.max_exposure`
+async fn exposure_allows_entry(state: &Arc<Mutex<AppState>>, slot_id: usize, amount: f64, price: f64) -> bool {
+    let mut s = state.lock().await;
+    let Some(slot) = s.slot_by_id(slot_id) else { return false };
+    if slot.strategy.config.direction != Direction::Long {
+        return true;
+    }
+    let asset = slot.base_asset.clone();
+    let Some(result) = check_exposure(&s, &asset, price, amount) else { return true };
+
+    let Some(slot) = s.slot_by_id_mut(slot_id) else { return false };
+    let was_blocked = std::mem::replace(&mut slot.exposure_blocked, result.is_err());
+    let symbol = slot.symbol.clone();
+    match result {
+        Ok(()) => {
+            if was_blocked {
+                s.log_for(&symbol, &format!("[{}] Below the {} exposure cap again, entries resumed.", symbol, asset));
+            }
+            true
+        }
+        Err(reason) => {
+            if !was_blocked {
+                s.log_error_for(&symbol, &format!("[{}] Entry skipped: {}", symbol, reason));
+            }
+            false
+        }
+    }
+}
+
+/// Revisa una compra de `amount` quote de `asset` contra `risk.max_exposure` (None si el
+/// asset no tiene límite). Cuenta las posiciones LONG de los slots, el balance de la cuenta
+/// y lo que falta llenar de las compras LIMIT en el libro: entradas de slots y escalones de
+/// escaleras
+fn check_exposure(s: &AppState, asset: &str, price: f64, amount: f64) -> Option<Result<(), String>> {
+    let limit = s.max_exposure.get(asset).copied()?;
+    let same_asset = || s.slots.iter().filter(|sl| sl.base_asset == asset);
+    // El balance tarda hasta 30s en reflejar las compras: se toma el mayor de los dos
+    let positions: f64 = same_asset()
+        .filter(|sl| sl.strategy.config.direction == Direction::Long)
+        .map(|sl| sl.strategy.total_quantity())
+        .sum();
+    let held = s.holdings.get(asset).copied().unwrap_or(0.0).max(positions);
+    let entries: f64 = same_asset()
+        .filter_map(|sl| s.orders.get(sl.id))
+        .filter(|m| m.order.side == OrderSide::Buy)
+        .map(|m| m.order.quantity)
+        .sum();
+    let rungs: f64 = s
+        .ladders
+        .ladders()
+        .iter()
+        .filter(|l| l.side == OrderSide::Buy && app::parse_symbol(&l.symbol).0 == asset)
+        .flat_map(|l| l.rungs.iter().filter(|r| r.resting()))
+        .map(|r| (r.quantity - r.filled_qty).max(0.0))
+        .sum();
+    Some(strategy::exposure::check_entry(asset, limit, held, entries + rungs, price, amount))
+}
+
+/// Contabiliza una orden enviada por el slot (para el health score)
+async fn record_api_result(state: &Arc<Mutex<AppState>>, slot_id: usize, ok: bool) {
+    if let Some(slot) = state.lock().await.slot_by_id_mut(slot_id) {
+        slot.record_api_result(ok);
+    }
+}
+
+/// Actualiza el canal watch con la lista actual de símbolos
+async fn update_symbol_watch(
+    state: &Arc<Mutex<AppState>>,
+    symbol_tx: &watch::Sender<Vec<String>>,
+) {
+    let symbols: Vec<String> = {
+        let mut s = state.lock().await;
+        let symbols = watched_symbols(&s.slots, &s.triggers);
+        s.candles.retain_symbols(&symbols);
+        s.volume.retain_symbols(&symbols);
+        s.price_history.retain_symbols(&symbols);
+        s.missing_klines.retain(|sym, _| symbols.contains(sym));
+        s.stale_symbols.retain(|sym| symbols.contains(sym));
+        symbols
+    };
+    let _ = symbol_tx.send(symbols);
+}
+
+/// Símbolos que necesitan precio en vivo: los de los slots y los de las órdenes condicionales
+fn watched_symbols(slots: &[StrategySlot], triggers: &TriggerBook) -> Vec<String> {
+    let mut symbols: Vec<String> = slots.iter().map(|s| s.symbol.clone()).collect();
+    for symbol in triggers.symbols() {
+        if !symbols.iter().any(|s| s == symbol) {
+            symbols.push(symbol.to_string());
+        }
+    }
+    symbols
+}
+
+/// Encola el archivo de un libro (condicionales, escaleras, presets) en el escritor de
+/// estado: se escribe fuera del lock, y el flush al salir también lo espera
+fn queue_book_save(s: &mut AppState, what: &str, file: anyhow::Result<Option<(std::path::PathBuf, String)>>) {
+    match file {
+        Ok(Some((path, contents))) => s.state_writer.write_file(path, contents),
+        Ok(None) => {}
+        Err(e) => s.log_error(&format!("Could not save {}: {:#}", what, e)),
+    }
+}
+
+fn save_presets(s: &mut AppState) {
+    let file = s.presets.contents();
+    queue_book_save(s, "presets", file);
+}
+
+fn save_triggers(s: &mut AppState) {
+    let file = s.triggers.contents();
+    queue_book_save(s, "conditional orders", file);
+}
+
+/// Guarda la config completa del slot seleccionado como preset `name` (reemplaza al
+/// preset con ese nombre) y lo deja seleccionado en la pantalla de presets
+fn save_preset(s: &mut AppState, name: &str) -> Result<String, String> {
+    presets::validate_name(name)?;
+    let slot = s.selected().ok_or("no slot selected")?;
+    let preset = Preset::from_config(name.trim(), &slot.strategy.config).map_err(|e| format!("{:#}", e))?;
+    let msg = format!("Preset '{}' saved from the {} slot ({} fields)", preset.name, slot.symbol, preset.fields.len());
+    let replaced = s.presets.position(&preset.name).is_some();
+    s.preset_view.selected = s.presets.upsert(preset);
+    s.preset_view.field = 0;
+    Ok(if replaced { format!("{} (replaced)", msg) } else { msg })
+}
+
+fn rename_preset(s: &mut AppState, name: &str) -> Result<String, String> {
+    presets::validate_name(name)?;
+    let index = s.preset_view.selected;
+    if s.presets.position(name.trim()).is_some_and(|i| i != index) {
+        return Err(format!("there is already a preset called '{}'", name.trim()));
+    }
+    let preset = s.presets.get_mut(index).ok_or("no preset selected")?;
+    let old = std::mem::replace(&mut preset.name, name.trim().to_string());
+    Ok(format!("Preset '{}' renamed to '{}'", old, preset.name))
+}
 
-    // =====================================================================
-    // Take Profit
-    // =====================================================================
-    if should_tp && qty > 0.0 {
-        let log_msg = match direction {
-            Direction::Long  => format!("✓ TAKE PROFIT [{}]! P&L: +${:.2}  Selling {:.6} @ ${:.2}", symbol, pnl, qty, price),
-            Direction::Short => format!("✓ TAKE PROFIT [{}]! P&L: +${:.2}  Re-buying {:.6} @ ${:.2}", symbol, pnl, qty, price),
-        };
-        state.lock().await.log(&log_msg);
+/// Cambia el valor del campo seleccionado del preset; se rechaza si la config resultante
+/// no es válida
+fn set_preset_value(s: &mut AppState, base_config: &DcaConfig, text: &str) -> Result<String, String> {
+    let value = presets::parse_value(text)?;
+    let (index, field) = (s.preset_view.selected, s.preset_view.field);
+    let preset = s.presets.get(index).ok_or("no preset selected")?;
+    let key = preset.fields.keys().nth(field).cloned().ok_or("no field selected")?;
+    let mut edited = preset.clone();
+    edited.fields.insert(key.clone(), value);
+    edited.apply(base_config).map_err(|e| format!("{}: {:#}", key, e))?;
+    let msg = format!("Preset '{}': {} = {}", edited.name, key, edited.fields[&key]);
+    if let Some(preset) = s.presets.get_mut(index) {
+        *preset = edited;
+    }
+    Ok(msg)
+}
+
+fn save_ladders(s: &mut AppState) {
+    let file = s.ladders.contents();
+    queue_book_save(s, "ladders", file);
+}
+
+/// Actualiza las ejecuciones de las escaleras con órdenes en el libro (cada `orders::POLL_SECS`)
+async fn poll_ladders(state: &Arc<Mutex<AppState>>, client: &Arc<BinanceClient>) {
+    let due = state.lock().await.ladders.due(chrono::Utc::now());
+    for mut ladder in due {
+        let before = ladder.filled_qty();
+        if let Err(e) = ladder::sync(client, &mut ladder).await {
+            tracing::warn!("Ladder #{} [{}] check failed: {}", ladder.id, ladder.symbol, e);
+            continue;
+        }
+        let mut s = state.lock().await;
+        if ladder.filled_qty() > before {
+            s.log_for(&ladder.symbol, &format!(
+                "Ladder #{} [{}]: {}/{} orders filled, {:.6} @ avg ${:.4}",
+                ladder.id,
+                ladder.symbol,
+                ladder.filled_rungs(),
+                ladder.rungs.len(),
+                ladder.filled_qty(),
+                ladder.average_price()
+            ));
+        }
+        // Cancelada o adoptada mientras tanto: no se resucita
+        if s.ladders.get(ladder.id).is_some() {
+            s.ladders.upsert(ladder);
+            save_ladders(&mut s);
+        }
+    }
+}
+
+/// Órdenes condicionales: envía a mercado las que alcanzaron su precio. Se quitan del libro
+/// antes de enviarlas, así una falla no las dispara dos veces (quedan en el log).
+async fn check_triggers(state: &Arc<Mutex<AppState>>, client: &Arc<BinanceClient>) {
+    let fired = {
+        let mut s = state.lock().await;
+        if s.triggers.is_empty() {
+            return;
+        }
+        let now = chrono::Utc::now();
+        let s = &mut *s;
+        let (prices, max_age) = (&s.prices, s.max_price_age_secs);
+        // Solo con precio reciente: un precio viejo podría disparar la orden a destiempo
+        let fired = s.triggers.take_triggered(|symbol| {
+            prices
+                .get(symbol)
+                .filter(|m| m.price > 0.0 && m.price_at.is_some() && !m.is_stale(max_age, now))
+                .map(|m| m.price)
+        });
+        if fired.is_empty() {
+            return;
+        }
+        save_triggers(s);
+        s.trigger_selected = s.trigger_selected.min(s.triggers.orders().len().saturating_sub(1));
+        fired
+    };
 
-        let order_result = match direction {
-            Direction::Long  => client.market_sell_qty(&symbol, qty).await,
-            Direction::Short => client.market_buy_qty(&symbol, qty).await,
+    for order in fired {
+        // Las compras condicionales también respetan el límite de exposición del asset
+        if order.side == OrderSide::Buy {
+            let mut s = state.lock().await;
+            let asset = app::parse_symbol(&order.symbol).0;
+            let price = s.prices.get(&order.symbol).map(|m| m.price).unwrap_or(0.0);
+            if let Some(Err(reason)) = check_exposure(&s, &asset, price, order.amount) {
+                s.log_error(&format!("Conditional #{} skipped, removed: {} ({})", order.id, order.describe(), reason));
+                notify(&s, None, NotifyEvent::Error);
+                continue;
+            }
+        }
+        // BUY gasta `amount` en quote, SELL vende `amount` del asset
+        let result = match order.side {
+            OrderSide::Buy => orders::market_entry(client, &order.symbol, OrderSide::Buy, order.amount, 0.0).await,
+            OrderSide::Sell => orders::market_exit(client, &order.symbol, OrderSide::Sell, order.amount, None).await,
         };
+        let mut s = state.lock().await;
+        match result {
+            Ok(filled) => {
+                let qty: f64 = filled.executed_qty.parse().unwrap_or(0.0);
+                let quote: f64 = filled.cummulative_quote_qty.parse().unwrap_or(0.0);
+                let avg = if qty > 0.0 { quote / qty } else { 0.0 };
+                s.log(&format!(
+                    "⚡ Conditional #{} filled: {} → {:.6} @ ${:.4} (${:.2})",
+                    order.id, order.describe(), qty, avg, quote
+                ));
+                notify(&s, None, NotifyEvent::Alert);
+            }
+            Err(e) => {
+                s.log_error(&format!("Conditional #{} failed, removed: {} ({:#})", order.id, order.describe(), e));
+                notify(&s, None, NotifyEvent::Error);
+            }
+        }
+    }
+    refresh_balance(state, client).await;
+}
 
-        match order_result {
-            Ok(order) => {
-                let received: f64 = order.cummulative_quote_qty.parse().unwrap_or(0.0);
-                {
-                    let mut s = state.lock().await;
-                    let mut flipped_to = None;
-                    if let Some(slot) = s.slot_by_id_mut(slot_id) {
-                        slot.strategy.state = DcaState::TakeProfitReached;
-                        slot.strategy.clear_trades();
-                        if auto_restart {
-                            if auto_flip {
-                                slot.strategy.config.direction = slot.strategy.config.direction.flip();
-                                flipped_to = Some(slot.strategy.config.direction.clone());
-                            }
-                            slot.strategy.start_after_tp(cooldown_minutes);
-                        } else {
-                            slot.strategy.stop();
-                        }
-                    }
+/// Vuelca los eventos pendientes al log y pasa todos los slots (Vec<StrategySnapshot>) al
+/// escritor de estado, que los guarda en segundo plano
+async fn save_all_snapshots(state: &Arc<Mutex<AppState>>, path: &std::path::Path) {
+    let mut s = state.lock().await;
+    let s = &mut *s;
+    // Archivo de estado dañado: no se escribe nada hasta que el usuario decida
+    if matches!(s.ui_mode, UiMode::Recovery(_)) {
+        return;
+    }
+    s.touch();
+    let now = chrono::Utc::now();
+    for slot in s.slots.iter_mut() {
+        for event in slot.strategy.drain_events() {
+            slot.performance.observe(&event, now);
+            s.event_log.push(slot.id, &slot.symbol, event);
+        }
+    }
+    // El escritor agrega los eventos (y los ciclos que cierran) al log antes que el
+    // snapshot: si el proceso muere en medio, el próximo arranque los re-aplica sobre el
+    // snapshot anterior
+    let events = s.event_log.drain();
+    let cycles = events.iter().filter_map(|event| s.cycles.observe(event)).collect();
+    let logs = state_file::LogAppend { events, cycles };
+    let last_seq = s.event_log.last_seq();
+    let snapshots: Vec<StrategySnapshot> = s
+        .slots
+        .iter()
+        .map(|sl| {
+            let mut snap = sl.strategy.to_snapshot(&sl.symbol);
+            snap.slot_id = Some(sl.id);
+            snap.last_event_seq = last_seq;
+            snap.open_order = s.orders.get(sl.id).map(|m| m.order.clone());
+            snap.exit_pending = sl.exit_pending.clone();
+            snap.analysis_interval = Some(sl.analysis_interval.clone());
+            snap.performance = Some(sl.performance.clone());
+            snap
+        })
+        .collect();
+    s.state_writer.submit(path, snapshots, logs);
+}
 
-                    if let Some(dir) = flipped_to {
-                        let dir_label = match dir {
-                            Direction::Long => "LONG",
-                            Direction::Short => "SHORT",
-                        };
-                        s.log(&format!("Auto-flip enabled. Switched to {} mode.", dir_label));
-                    }
-                    s.log(&format!("✓ TAKE PROFIT [{}] executed. Received: ${:.2}", symbol, received));
-                    if auto_restart {
-                        s.log("Auto-restart enabled. DCA cycle restarted.");
-                    } else {
-                        s.ui_mode = UiMode::PostSale(slot_id, SaleResult {
-                            kind: "TAKE PROFIT".to_string(),
-                            received,
-                            pnl,
-                            pnl_pct,
-                        });
+/// Obtiene los filtros del símbolo (exchangeInfo): warm-up de slots nuevos y minNotional.
+/// Reintenta cada 10s mientras el slot exista.
+async fn fetch_symbol_filters(
+    state: Arc<Mutex<AppState>>,
+    client: Arc<BinanceClient>,
+    slot_id: usize,
+    symbol: String,
+) {
+    loop {
+        match client.symbol_filters(&symbol).await {
+            Ok(filters) => {
+                let mut s = state.lock().await;
+                if filters.status != "TRADING" {
+                    s.log_error_for(&symbol, &format!("{} is not trading (status {})", symbol, filters.status));
+                }
+                if let Some(slot) = s.slot_by_id_mut(slot_id) {
+                    // Base y quote según exchangeInfo: el sufijo del símbolo no alcanza para
+                    // quotes fuera de la lista conocida
+                    if !filters.base_asset.is_empty() && !filters.quote_asset.is_empty() {
+                        slot.base_asset = filters.base_asset.clone();
+                        slot.quote_asset = filters.quote_asset.clone();
                     }
+                    slot.filters = Some(filters);
                 }
-                save_all_snapshots(state, state_path).await;
+                return;
             }
             Err(e) => {
-                state.lock().await.log_error(&format!("Take profit [{}] failed: {}", symbol, e));
+                let mut s = state.lock().await;
+                if s.slot_by_id(slot_id).is_none() {
+                    return;
+                }
+                s.log_error_for(&symbol, &format!("Could not load {} filters, retrying: {}", symbol, e));
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(10)).await;
+    }
+}
+
+/// Cada cuántos segundos se revisa el estado de los símbolos en exchangeInfo
+const SYMBOL_STATUS_SECS: u64 = 300;
+
+/// Escribe `settings` en config.toml y guarda en `change` los valores anteriores, para que
+/// deshacer también restaure el archivo
+fn save_settings(s: &mut AppState, config_path: &std::path::Path, change: &mut ConfigChange, settings: &[config::Setting]) {
+    match Config::save_settings(config_path, settings) {
+        Ok(previous) => change.saved_settings = previous,
+        Err(e) => s.log_error(&format!("Could not save config: {:#}", e)),
+    }
+}
+
+/// Valor escrito en el modal de settings: TOML (`150`, `true`) o, si no lo es, un string
+/// (`light`). Vacío = quitar la clave y volver al valor por defecto
+fn setting_value(text: &str) -> Result<Option<toml_edit::Value>, String> {
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let value = presets::parse_value(text).unwrap_or_else(|_| toml::Value::String(text.to_string()));
+    value
+        .to_string()
+        .parse::<toml_edit::Value>()
+        .map(Some)
+        .map_err(|_| format!("'{}' is not a valid value", text))
+}
+
+/// Cada cuántos segundos se mira si config.toml cambió
+const CONFIG_POLL_SECS: u64 = 2;
+
+/// Aplica al estado los ajustes de config.toml que no requieren reiniciar
+/// (`config::is_live_setting`). `[dca]` lo toma el motor
+fn apply_config(s: &mut AppState, config: &Config) {
+    s.max_slots = config.app.max_slots;
+    s.amount_confirm_pct = config.risk.amount_confirm_pct;
+    s.max_daily_spend = config.risk.max_daily_spend;
+    s.max_daily_spend_total = config.risk.max_daily_spend_total;
+    s.max_price_age_secs = config.risk.max_price_age_secs;
+    s.max_exposure = config.risk.max_exposure.iter().map(|(a, l)| (a.to_uppercase(), *l)).collect();
+    s.equity_floor_usdt = config.risk.equity_floor_usdt;
+    s.equity_floor_action = config.risk.equity_floor_action;
+    s.sr_window = config.alerts.rolling_window.max(1);
+    s.alert_cooldown_minutes = config.alerts.cooldown_minutes;
+    s.volume.set_cooldown_minutes(config.alerts.cooldown_minutes);
+    s.groups = config.groups.clone();
+    s.theme = ui::theme::Theme::named(config.ui.theme);
+    app::set_display_format(&config.ui);
+    ui::i18n::set_language(config.ui.language);
+    s.display_currency = config.ui.display_currency.trim().to_uppercase();
+    s.notifications = config.notifications.clone();
+    s.alert_history_len = config.retention.alert_history;
+    s.fees = config.fees.clone();
+    for i in 0..s.slots.len() {
+        let rates = s.fee_rates(&s.slots[i].symbol);
+        s.slots[i].strategy.set_fee_rates(rates);
+    }
+    // Un slot en un grupo que ya no existe queda sin límites de grupo
+    let unknown: Vec<String> = s
+        .slots
+        .iter()
+        .filter_map(|sl| sl.strategy.config.group.clone())
+        .filter(|g| !s.groups.iter().any(|group| &group.name == g))
+        .collect();
+    if !unknown.is_empty() {
+        let msg = format!("Groups no longer in config.toml: {} (their slots run without group limits)", unknown.join(", "));
+        s.log(&msg);
+    }
+}
+
+/// Anuncia qué ajustes se recargaron y cuáles solo toman efecto al reiniciar
+fn log_config_reload(s: &mut AppState, changed: &[String]) {
+    let (live, restart): (Vec<&String>, Vec<&String>) = changed.iter().partition(|k| config::is_live_setting(k));
+    let join = |keys: &[&String]| keys.iter().map(|k| k.as_str()).collect::<Vec<_>>().join(", ");
+    if !live.is_empty() {
+        // [dca] es la plantilla de las estrategias nuevas: los slots existentes no cambian
+        let note = if live.iter().any(|k| k.starts_with("dca.")) { " ([dca] applies to new strategies)" } else { "" };
+        s.log(&format!("⟳ config.toml reloaded: {}{}", join(&live), note));
+    }
+    if !restart.is_empty() {
+        s.log_alert(&format!("⚠ config.toml: {} changed, restart the bot to apply", join(&restart)));
+    }
+}
+
+/// Revisa el estado de los símbolos de los slots. Si uno sale de TRADING (halt, break o
+/// deslistado) el slot se pausa y se avisa; cuando vuelve a operar se ofrece cerrar la
+/// posición a mercado con el panel de confirmación
+async fn check_symbol_status(
+    state: &Arc<Mutex<AppState>>,
+    client: &Arc<BinanceClient>,
+    state_path: &std::path::Path,
+) {
+    let mut symbols: Vec<String> = state.lock().await.slots.iter().map(|s| s.symbol.clone()).collect();
+    symbols.sort();
+    symbols.dedup();
+
+    let mut statuses = std::collections::HashMap::new();
+    for symbol in symbols {
+        match client.symbol_status(&symbol).await {
+            Ok(status) => {
+                statuses.insert(symbol, status.unwrap_or_else(|| "DELISTED".to_string()));
+            }
+            Err(e) => tracing::warn!("Could not check {} status: {}", symbol, e),
+        }
+    }
+
+    let mut s = state.lock().await;
+    let mut halted = Vec::new();
+    let mut resumed = Vec::new();
+    for (i, slot) in s.slots.iter_mut().enumerate() {
+        let Some(status) = statuses.get(&slot.symbol) else { continue };
+        let trading = status == "TRADING";
+        match &slot.halted {
+            None if !trading => {
+                let was_active = slot.strategy.state.is_active();
+                if was_active {
+                    slot.strategy.stop();
+                }
+                slot.halted = Some(status.clone());
+                halted.push((slot.id, slot.symbol.clone(), status.clone(), was_active));
             }
+            Some(_) if trading => {
+                slot.halted = None;
+                resumed.push((i, slot.id, slot.symbol.clone(), slot.strategy.total_quantity() > 0.0));
+            }
+            Some(old) if old != status => slot.halted = Some(status.clone()),
+            _ => {}
         }
+    }
+    if halted.is_empty() && resumed.is_empty() {
         return;
     }
 
-    // =====================================================================
-    // Trailing Take Profit
-    // =====================================================================
-    if should_trailing_tp && qty > 0.0 {
-        let log_msg = match direction {
-            Direction::Long => {
-                let drop = ((price_peak - price) / price_peak) * 100.0;
-                format!(
-                    "↓ TRAILING TP [{}]! Max: ${:.4}  Drop: {:.2}%  P&L: +${:.2}",
-                    symbol, price_peak, drop, pnl
-                )
+    // La entrada LIMIT que espera en el libro se cancela ya: se llenaría al reabrir el
+    // mercado con el slot pausado
+    let mut pending = Vec::new();
+    for (id, symbol, status, was_active) in &halted {
+        s.log_error_for(symbol, &format!(
+            "⛔ {} left TRADING (status {}){}",
+            symbol, status, if *was_active { ": strategy PAUSED" } else { "" }
+        ));
+        notify(&s, Some(*id), NotifyEvent::Error);
+        pending.extend(s.orders.untrack(*id));
+    }
+    for (i, id, symbol, has_position) in resumed {
+        s.log_for(&symbol, &format!("✓ {} is TRADING again. Press S to restart the strategy.", symbol));
+        notify(&s, Some(id), NotifyEvent::Error);
+        if has_position {
+            // Salida guiada: se abre la confirmación de cierre (sin pisar otro panel abierto)
+            if s.ui_mode == UiMode::Normal {
+                s.selected_slot = i;
+                s.ui_mode = UiMode::ConfirmClose;
             }
-            Direction::Short => {
-                let rise = ((price - price_trough) / price_trough) * 100.0;
-                format!(
-                    "↑ TRAILING TP [{}]! Min: ${:.4}  Rise: {:.2}%  P&L: +${:.2}",
-                    symbol, price_trough, rise, pnl
-                )
+            s.log_for(&symbol, &format!("{}: close the position at market now? [Enter] close / [Esc] keep", symbol));
+        }
+    }
+    drop(s);
+    cancel_entry_orders(state, client, pending).await;
+    save_all_snapshots(state, state_path).await;
+}
+
+/// Actualiza los balances de todos los slots con una sola llamada a la API
+async fn refresh_balance(
+    state: &Arc<Mutex<AppState>>,
+    client: &Arc<BinanceClient>,
+) -> Option<models::account::AccountInfo> {
+    match client.get_account().await {
+        Ok(account) => {
+            let rates = CommissionRates::from_account(&account);
+            let mut s = state.lock().await;
+            if s.commission != rates {
+                s.commission = rates;
+                let overridden = s.fees.maker_pct.is_some() || s.fees.taker_pct.is_some() || !s.fees.symbols.is_empty();
+                s.log(&format!(
+                    "Commission rates: maker {:.3}%  taker {:.3}%{}",
+                    rates.maker_pct,
+                    rates.taker_pct,
+                    if overridden { " ([fees] in config.toml takes precedence)" } else { "" }
+                ));
+            }
+            s.holdings = account
+                .non_zero_balances()
+                .into_iter()
+                .map(|b| (b.asset.clone(), b.free_f64() + b.locked_f64()))
+                .collect();
+            let fees = s.fees.clone();
+            for slot in s.slots.iter_mut() {
+                slot.base_balance = account.get_free(&slot.base_asset);
+                slot.quote_balance = account.get_free(&slot.quote_asset);
+                slot.strategy.set_fee_rates(rates.for_symbol(&fees, &slot.symbol));
+            }
+            tracing::debug!("Balances updated for {} slot(s)", s.slots.len());
+            Some(account)
+        }
+        Err(e) => {
+            tracing::warn!("Could not update balance: {}", e);
+            None
+        }
+    }
+}
+
+/// Valúa todos los balances (libres + bloqueados) en la moneda de visualización y guarda
+/// el resultado para el header, el portfolio y el resumen diario. Los precios del
+/// WebSocket tienen prioridad sobre el snapshot REST; los pares que faltan se cruzan por
+/// BTC/USDT. La equity en USDT se agrega a equity_history.jsonl (una muestra cada 5 minutos)
+/// y se devuelve para el stop de portafolio; None si no se pudieron obtener los precios
+async fn account_equity(
+    state: &Arc<Mutex<AppState>>,
+    client: &Arc<BinanceClient>,
+    account: &models::account::AccountInfo,
+) -> Option<f64> {
+    let (info, mut prices) = match tokio::try_join!(client.exchange_info(), client.all_prices()) {
+        Ok(result) => result,
+        Err(e) => {
+            tracing::warn!("Equity: could not fetch prices: {}", e);
+            return None;
+        }
+    };
+    let routes = valuation::PairRoutes::from_exchange_info(&info);
+    let balances: Vec<(String, f64)> = account
+        .non_zero_balances()
+        .into_iter()
+        .map(|b| (b.asset.clone(), b.free_f64() + b.locked_f64()))
+        .collect();
+
+    let mut s = state.lock().await;
+    prices.extend(s.prices.iter().filter(|(_, m)| m.price > 0.0).map(|(k, m)| (k.clone(), m.price)));
+    let valued = valuation::Valuation::compute(&routes, &balances, &s.display_currency, &prices);
+    let equity = if valued.currency == "USDT" {
+        valued.total
+    } else {
+        valuation::Valuation::compute(&routes, &balances, "USDT", &prices).total
+    };
+    // Cotización en USDT de cada quote en uso (límite de gasto diario)
+    let quotes: std::collections::BTreeSet<String> = s.slots.iter().map(|sl| sl.quote_asset.clone()).collect();
+    for quote in quotes {
+        if let Some((rate, _)) = routes.rate(&quote, "USDT", &prices) {
+            s.quote_rates.insert(quote, rate);
+        }
+    }
+    daily_summary(&mut s, &valued);
+    s.valuation = Some(valued);
+    let history = Arc::clone(&s.equity_history);
+    drop(s);
+    let at = chrono::Utc::now();
+    match tokio::task::spawn_blocking(move || history.record(at, equity)).await {
+        Ok(Err(e)) => tracing::warn!("Could not append to equity history: {:#}", e),
+        Err(e) => tracing::warn!("Could not append to equity history: {}", e),
+        Ok(Ok(_)) => {}
+    }
+    Some(equity)
+}
+
+/// Al cambiar el día UTC anuncia la equity y su variación desde el resumen anterior, más
+/// el P&L realizado del día que terminó. La primera valuación de la sesión solo fija la base
+fn daily_summary(s: &mut AppState, valued: &valuation::Valuation) {
+    let today = valued.at.date_naive();
+    let previous = s.daily_summary.replace((today, valued.total));
+    let Some((day, total)) = previous else { return };
+    if day == today {
+        s.daily_summary = previous;
+        return;
+    }
+    let yesterday = today - chrono::Duration::days(1);
+    // P&L realizado sumado por quote: no se mezclan USDT con BTC
+    let mut realized: std::collections::BTreeMap<&str, f64> = std::collections::BTreeMap::new();
+    for sl in &s.slots {
+        *realized.entry(sl.quote_asset.as_str()).or_default() +=
+            strategy::performance::daily_pnl(&sl.performance.realized, 1, yesterday)[0];
+    }
+    let realized = if realized.is_empty() {
+        "+0.00 USDT".to_string()
+    } else {
+        realized
+            .iter()
+            .map(|(quote, pnl)| {
+                let decimals = if app::is_usd_quote(quote) { 2 } else { 8 };
+                format!("{:+.*} {}", decimals, pnl, quote)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let change = valued.total - total;
+    let change_pct = if total > 0.0 { change / total * 100.0 } else { 0.0 };
+    let unpriced: Vec<&str> = valued.unpriced().collect();
+    s.log_alert(&format!(
+        "📅 Daily summary: equity {:.2} {} ({:+.2} / {:+.2}% since {}), realized P&L {}{}",
+        valued.total,
+        valued.currency,
+        change,
+        change_pct,
+        day,
+        realized,
+        if unpriced.is_empty() { String::new() } else { format!(" (unpriced: {})", unpriced.join(", ")) }
+    ));
+    notify(s, None, NotifyEvent::DailySummary);
+}
+
+/// Stop de portafolio: si la equity total cae bajo `equity_floor_usdt` se cierran todas las
+/// posiciones (o se pausan todas las estrategias) y se avisa. Mientras siga bajo el piso se
+/// vuelve a aplicar en cada chequeo; el aviso se repite solo tras recuperarse
+async fn check_equity_floor(
+    state: &Arc<Mutex<AppState>>,
+    client: &Arc<BinanceClient>,
+    account: &models::account::AccountInfo,
+    state_path: &std::path::Path,
+) {
+    let Some(equity) = account_equity(state, client, account).await else { return };
+    let (floor, action, to_close, pending) = {
+        let mut s = state.lock().await;
+        let floor = s.equity_floor_usdt;
+        if floor <= 0.0 || equity >= floor {
+            s.equity_floor_tripped = false;
+            return;
+        }
+        let action = s.equity_floor_action;
+        if !s.equity_floor_tripped {
+            s.equity_floor_tripped = true;
+            let what = match action {
+                EquityFloorAction::CloseAll => "closing all positions",
+                EquityFloorAction::PauseAll => "pausing all strategies",
+            };
+            s.log_error(&format!(
+                "⛔ EQUITY FLOOR: account equity ${:.2} < ${:.2}, {}",
+                equity, floor, what
+            ));
+            notify(&s, None, NotifyEvent::EquityFloor);
+        }
+        let mut stopped = false;
+        for slot in s.slots.iter_mut() {
+            if slot.strategy.state.is_active() {
+                slot.strategy.stop();
+                stopped = true;
+            }
+        }
+        // En Distribute la "posición" es inventario ya vendido: cerrarla sería recomprarlo.
+        // El resto (Watch y Accumulate son LONG) se cierra vendiendo
+        let to_close: Vec<usize> = match action {
+            EquityFloorAction::CloseAll => s
+                .slots
+                .iter()
+                .filter(|sl| !sl.strategy.distributing() && sl.strategy.total_quantity() > 0.0)
+                .map(|sl| sl.id)
+                .collect(),
+            EquityFloorAction::PauseAll => Vec::new(),
+        };
+        let pending = s.orders.untrack_all();
+        if !stopped && to_close.is_empty() && pending.is_empty() {
+            return;
+        }
+        (floor, action, to_close, pending)
+    };
+    tracing::warn!("Equity floor {:.2} ({:?}) applied at equity {:.2}", floor, action, equity);
+
+    cancel_entry_orders(state, client, pending).await;
+    for slot_id in to_close {
+        close_position(state, client, slot_id, "EQUITY FLOOR", state_path).await;
+    }
+    save_all_snapshots(state, state_path).await;
+}
+
+/// Cierra a mercado la posición completa de un slot, lo detiene y registra la salida como
+/// `kind` (cierre manual, piso de equity). Devuelve el resultado para el overlay post-venta;
+/// None si no había posición o la orden falló
+async fn close_position(
+    state: &Arc<Mutex<AppState>>,
+    client: &Arc<BinanceClient>,
+    slot_id: usize,
+    kind: &str,
+    state_path: &std::path::Path,
+) -> Option<SaleResult> {
+    let (symbol, qty, direction, price, pnl, pnl_pct) = {
+        let s = state.lock().await;
+        let slot = s.slot_by_id(slot_id)?;
+        let price = s.prices.get(&slot.symbol).map(|m| m.price).unwrap_or(0.0);
+        (
+            slot.symbol.clone(),
+            slot.strategy.total_quantity(),
+            slot.strategy.config.direction.clone(),
+            price,
+            slot.strategy.pnl(price),
+            slot.strategy.pnl_pct(price),
+        )
+    };
+    if qty <= 0.0 {
+        return None;
+    }
+
+    let log_msg = match direction {
+        Direction::Long  => format!("⚠ {} [{}]: Selling {:.6} @ ${:.2}", kind, symbol, qty, price),
+        Direction::Short => format!("⚠ {} [{}]: Rebuying {:.6} @ ${:.2}", kind, symbol, qty, price),
+    };
+    state.lock().await.log_for(&symbol, &log_msg);
+    warn_testnet_divergence(state, client, &symbol, price, direction == Direction::Short).await;
+
+    let order_result = send_exit(state, client, slot_id, &symbol, &direction, qty).await;
+    record_api_result(state, slot_id, order_result.is_ok()).await;
+
+    match order_result {
+        Ok(order) => {
+            let received: f64 = order.cummulative_quote_qty.parse().unwrap_or(0.0);
+            {
+                let mut s = state.lock().await;
+                if let Some(slot) = s.slot_by_id_mut(slot_id) {
+                    slot.strategy.stop();
+                    slot.strategy.record_exit(kind, received, pnl, order.commission());
+                    slot.exit_pending = None;
+                }
+                s.log_for(&symbol, &format!("✓ {} [{}] executed. Received: ${:.2}", kind, symbol, received));
+                notify(&s, Some(slot_id), NotifyEvent::Exit);
+            }
+            save_all_snapshots(state, state_path).await;
+            Some(SaleResult { kind: kind.to_string(), received, pnl, pnl_pct })
+        }
+        Err(e) => {
+            if !settle_partial_exit(state, slot_id, &symbol, &e, state_path).await
+                && !close_dust_position(state, slot_id, &e, state_path).await
+            {
+                state.lock().await.log_error_for(&symbol, &format!("{} [{}] failed: {}", kind, symbol, e));
+            }
+            None
+        }
+    }
+}
+
+/// Completa vía REST los precios que el WebSocket todavía no entregó,
+/// con una sola llamada batch para todos los símbolos faltantes
+async fn refresh_missing_prices(state: &Arc<Mutex<AppState>>, client: &Arc<BinanceClient>) {
+    let missing: Vec<String> = {
+        let s = state.lock().await;
+        let mut syms: Vec<String> = watched_symbols(&s.slots, &s.triggers)
+            .into_iter()
+            .filter(|sym| s.prices.get(sym).map(|m| m.price).unwrap_or(0.0) == 0.0)
+            .collect();
+        syms.sort();
+        syms.dedup();
+        syms
+    };
+    if missing.is_empty() {
+        return;
+    }
+
+    match client.get_tickers_24h(&missing).await {
+        Ok(tickers) => {
+            let mut s = state.lock().await;
+            for t in tickers {
+                let entry = s.prices.entry(t.symbol.clone()).or_default();
+                if entry.price > 0.0 {
+                    continue; // el WebSocket llegó mientras tanto
+                }
+                entry.price = t.last_f64();
+                entry.change_24h_pct = t.price_change_percent.parse().unwrap_or(0.0);
+                entry.high_24h = t.high_price.parse().unwrap_or(0.0);
+                entry.low_24h = t.low_price.parse().unwrap_or(0.0);
+                entry.price_at = Some(chrono::Utc::now());
+            }
+            tracing::debug!("REST price fallback for {} symbol(s)", missing.len());
+        }
+        Err(e) => {
+            tracing::warn!("REST price fallback failed: {}", e);
+        }
+    }
+}
+
+/// Datos del modal de recuperación: copia el archivo dañado aparte (los guardados
+/// posteriores lo pisarían) y busca el backup rotado más reciente que se pueda leer
+fn state_recovery(path: &std::path::Path, error: String) -> app::StateRecovery {
+    let mut corrupt = path.as_os_str().to_owned();
+    corrupt.push(format!(".corrupt-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S")));
+    let corrupt = std::path::PathBuf::from(corrupt);
+    let preserved = match std::fs::copy(path, &corrupt) {
+        Ok(_) => Some(corrupt),
+        Err(e) => {
+            tracing::warn!("Could not copy the damaged state file: {}", e);
+            None
+        }
+    };
+    let backup = state_file::newest_valid_backup(path).and_then(|(backup, snapshots)| {
+        let saved_at = std::fs::metadata(&backup).and_then(|m| m.modified()).ok()?;
+        Some(app::StateBackup { path: backup, saved_at: saved_at.into(), slots: snapshots.len() })
+    });
+    app::StateRecovery { error, preserved, backup }
+}
+
+/// Slots reconstruidos desde snapshots + event log, con lo que muestra el modal de restauración
+#[derive(Default)]
+struct RestoredSession {
+    slots: Vec<StrategySlot>,
+    /// Entradas LIMIT que quedaron pendientes (vuelven al gestor de órdenes)
+    orders: Vec<ManagedOrder>,
+    /// (símbolo, dirección, trades, activo) por slot
+    info: Vec<(String, Direction, usize, bool)>,
+    issues: Vec<RestoreIssue>,
+}
+
+/// Estrategias de una sesión nueva: una por `[[slots]]` de config.toml, o la de `[dca]` si
+/// no hay. Los campos de cada `[[slots]]` quedan como overrides: el snapshot parte de `[dca]`
+fn initial_strategies(base: &DcaConfig, sections: &[config::SlotSection]) -> Vec<DcaStrategy> {
+    if sections.is_empty() {
+        let mut strat = DcaStrategy::new(base.clone());
+        strat.record_created();
+        return vec![strat];
+    }
+    sections
+        .iter()
+        .filter_map(|section| match section.apply(base) {
+            Ok((config, overrides)) => {
+                let mut strat = DcaStrategy::new(config);
+                strat.record_created();
+                for (field, value) in overrides {
+                    strat.set_config_field(&field, value);
+                }
+                Some(strat)
             }
-        };
-        state.lock().await.log(&log_msg);
+            // config.toml ya se validó al cargarlo; solo puede fallar con un [dca] recargado
+            Err(e) => {
+                tracing::warn!("[[slots]] {} not created: {:#}", section.symbol, e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn restore_session(snapshots: &[StrategySnapshot], events: &[SlotEvent], base_config: &DcaConfig) -> RestoredSession {
+    let mut session = RestoredSession::default();
+    // Todos los slots guardados, aunque superen `[app] max_slots`: el límite solo frena
+    // los nuevos (descartarlos dejaría posiciones abiertas sin gestionar)
+    for (id, symbol, mut strat) in restore_slots(snapshots, events, base_config) {
+        // La misma orden registrada dos veces (p. ej. caída entre el log y el snapshot)
+        let order_ids = strat.merge_duplicate_trades();
+        if !order_ids.is_empty() {
+            tracing::warn!("Merged duplicate trades for {} (orders {:?})", symbol, order_ids);
+            session.issues.push(RestoreIssue::DuplicateTrades { symbol: symbol.clone(), order_ids });
+        }
+        session.info.push((
+            symbol.clone(),
+            strat.config.direction.clone(),
+            strat.trades.len(),
+            strat.state.is_active(),
+        ));
 
-        let order_result = match direction {
-            Direction::Long  => client.market_sell_qty(&symbol, qty).await,
-            Direction::Short => client.market_buy_qty(&symbol, qty).await,
+        let mut slot = StrategySlot::new(id, symbol, strat);
+        let snap = snapshots.iter().find(|snap| snap.slot_id == Some(id));
+        // Estadísticas guardadas hasta el checkpoint (los eventos previos pueden estar
+        // podados) más los eventos posteriores del log
+        slot.performance = match snap.and_then(|snap| snap.performance.clone().map(|p| (p, snap.last_event_seq))) {
+            Some((performance, covered)) => performance.replay(events, id, covered),
+            None => SlotPerformance::from_events(events, id),
         };
+        // Salida que no llegó a Binance antes del cierre: se sigue reintentando
+        slot.exit_pending = snap.and_then(|snap| snap.exit_pending.clone());
+        if let Some(interval) = snap.and_then(|snap| snap.analysis_interval.clone()) {
+            slot.analysis_interval = interval;
+        }
+        // Orden LIMIT de entrada que quedó pendiente: vuelve al gestor de órdenes
+        if let Some(order) = snap.and_then(|snap| snap.open_order.clone()) {
+            let mut intent = entry_intent(&slot, order.quantity * order.price, order.trigger);
+            intent.entries_before = order.entries_before;
+            if slot.strategy.config.entry_order_type == EntryOrderType::Market {
+                // Entrada market convertida a LIMIT_MAKER por spread amplio
+                intent.post_only = true;
+                intent.offset_pct = 0.0;
+            }
+            session.orders.push(ManagedOrder { slot_id: id, intent, order });
+        }
+        session.slots.push(slot);
+    }
+    session
+}
 
-        match order_result {
-            Ok(order) => {
-                let received: f64 = order.cummulative_quote_qty.parse().unwrap_or(0.0);
-                {
-                    let mut s = state.lock().await;
-                    let mut flipped_to = None;
-                    if let Some(slot) = s.slot_by_id_mut(slot_id) {
-                        slot.strategy.state = DcaState::TakeProfitReached;
-                        slot.strategy.clear_trades();
-                        if auto_restart {
-                            if auto_flip {
-                                slot.strategy.config.direction = slot.strategy.config.direction.flip();
-                                flipped_to = Some(slot.strategy.config.direction.clone());
-                            }
-                            slot.strategy.start_after_tp(cooldown_minutes);
-                        } else {
-                            slot.strategy.stop();
-                        }
-                    }
+/// Reconstruye los slots: parte de los snapshots (checkpoint) y aplica los eventos
+/// posteriores del log. Sin snapshots, el estado se deriva por completo de los eventos.
+fn restore_slots(
+    snapshots: &[StrategySnapshot],
+    events: &[SlotEvent],
+    base_config: &DcaConfig,
+) -> Vec<(usize, String, DcaStrategy)> {
+    // (id, símbolo, estrategia, último seq ya incluido)
+    let mut slots: Vec<(usize, String, DcaStrategy, u64)> = Vec::new();
+    for (i, snap) in snapshots.iter().enumerate() {
+        let mut strat_config = base_config.clone();
+        strat_config.symbol = snap.symbol.clone();
+        strat_config.direction = snap.direction.clone();
+        let mut strat = DcaStrategy::new(strat_config);
+        strat.restore_from_snapshot(snap.clone());
+        let id = snap.slot_id.unwrap_or(i);
+        slots.push((id, snap.symbol.clone(), strat, snap.last_event_seq));
+    }
+    let checkpoint = snapshots.iter().map(|s| s.last_event_seq).max().unwrap_or(0);
 
-                    if let Some(dir) = flipped_to {
-                        let dir_label = match dir {
-                            Direction::Long => "LONG",
-                            Direction::Short => "SHORT",
-                        };
-                        s.log(&format!("Auto-flip enabled. Switched to {} mode.", dir_label));
-                    }
-                    s.log(&format!("✓ TRAILING TP [{}] executed. Received: ${:.2}", symbol, received));
-                    if auto_restart {
-                        s.log("Auto-restart enabled. DCA cycle restarted.");
-                    } else {
-                        s.ui_mode = UiMode::PostSale(slot_id, SaleResult {
-                            kind: "TRAILING TP".to_string(),
-                            received,
-                            pnl,
-                            pnl_pct,
-                        });
-                    }
+    for event in events {
+        match slots.iter().position(|(id, ..)| *id == event.slot_id) {
+            Some(pos) => {
+                if event.seq <= slots[pos].3 {
+                    continue;
                 }
-                save_all_snapshots(state, state_path).await;
+                if let SlotEventKind::Removed = event.kind {
+                    slots.remove(pos);
+                    continue;
+                }
+                slots[pos].2.apply(&event.kind);
+                slots[pos].3 = event.seq;
             }
-            Err(e) => {
-                state.lock().await.log_error(&format!("Trailing TP [{}] failed: {}", symbol, e));
+            None => {
+                // Slot creado después del último snapshot
+                if let SlotEventKind::Created { config } = &event.kind {
+                    if event.seq > checkpoint {
+                        let strat = DcaStrategy::new((**config).clone());
+                        slots.push((event.slot_id, event.symbol.clone(), strat, event.seq));
+                    }
+                }
             }
         }
+    }
+
+    let replayed = events.iter().filter(|e| e.seq > checkpoint).count();
+    if replayed > 0 {
+        tracing::info!("Replayed {} event(s) from the event log", replayed);
+    }
+
+    slots.into_iter().map(|(id, sym, strat, _)| (id, sym, strat)).collect()
+}
+
+/// Valores actuales del slot en el orden de EDIT_SLOT_FIELDS (texto para el editor)
+fn edit_slot_values(cfg: &DcaConfig) -> Vec<String> {
+    EDIT_SLOT_FIELDS.iter().map(|(_, param)| param_value(cfg, param)).collect()
+}
+
+/// Valor de un parámetro de `set` como texto editable
+fn param_value(cfg: &DcaConfig, param: &str) -> String {
+    match param {
+        // Una escalera de TP se muestra por su primer nivel; editarlo la reemplaza
+        "tp" => match &cfg.take_profit_pct {
+            TakeProfit::Single(v) => v.to_string(),
+            TakeProfit::Ladder(_) => cfg.take_profit_pct.first_pct().to_string(),
+        },
+        "sl" => cfg.stop_loss_pct.to_string(),
+        "trail" => cfg.trailing_tp_pct.to_string(),
+        "breakeven" => cfg.breakeven_trigger_pct.to_string(),
+        "beoffset" => cfg.breakeven_offset_pct.to_string(),
+        "interval" => cfg.interval_minutes.to_string(),
+        "maxorders" => cfg.max_orders.to_string(),
+        "amount" => cfg.quote_amount.to_string(),
+        "daily" => cfg.max_daily_spend.to_string(),
+        _ => cfg.price_drop_trigger.to_string(),
+    }
+}
+
+/// Beep del sistema si el slot (o, sin slot, el default) tiene el evento activo (`[dca.notify]` / `:notify`), salvo en
+/// horario silencioso para eventos fuera de `always_notify` (el evento queda solo en el log)
+fn notify(s: &AppState, slot_id: Option<usize>, event: NotifyEvent) {
+    let enabled = slot_id
+        .and_then(|id| s.slot_by_id(id))
+        .map_or(event.enabled_by_default(), |slot| event.enabled_for(&slot.strategy.config.notify));
+    if !enabled {
         return;
     }
+    if s.notifications.allows(event, chrono::Utc::now()) {
+        play_alert_sound();
+    } else {
+        tracing::debug!("Quiet hours: {:?} notification muted", event);
+    }
+}
 
-    // =====================================================================
-    // Entrada DCA
-    //   LONG:  compra USDT → base asset      (market_buy_quote)
-    //   SHORT: vende base asset → recibe USDT (market_sell_qty)
-    // =====================================================================
-    if should_entry {
-        match direction {
-            Direction::Long => {
-                let order_num = {
-                    state.lock().await
-                        .slot_by_id(slot_id)
-                        .map(|sl| sl.strategy.trades.len() + 1)
-                        .unwrap_or(1)
-                };
-                tracing::info!(
-                    "Executing DCA LONG buy [{}] #{} of ${:.2}",
-                    symbol, order_num, amount
-                );
+/// Beep del sistema para alertas y eventos críticos
+fn play_alert_sound() {
+    // BEL character: la mayoría de terminales/consolas emiten un beep
+    eprint!("\x07");
+}
 
-                match client.market_buy_quote(&symbol, amount).await {
-                    Ok(order) => {
-                        let exec_qty: f64 = order.executed_qty.parse().unwrap_or(0.0);
-                        let cost: f64 = order.cummulative_quote_qty.parse().unwrap_or(amount);
-                        let actual_price = if exec_qty > 0.0 { cost / exec_qty } else { price };
-                        {
-                            let mut s = state.lock().await;
-                            if let Some(slot) = s.slot_by_id_mut(slot_id) {
-                                let num = slot.strategy.trades.len() + 1;
-                                let base = slot.base_asset.clone();
-                                slot.strategy.record_buy(order.order_id, actual_price, exec_qty, cost);
-                                s.log(&format!(
-                                    "BUY #{} [{}]: {:.6} {} @ ${:.4} (${:.2})",
-                                    num, symbol, exec_qty, base, actual_price, cost
-                                ));
-                            }
-                        }
-                        save_all_snapshots(state, state_path).await;
-                    }
-                    Err(e) => {
-                        let mut s = state.lock().await;
-                        let mut err_msg = format!("Buy [{}] failed: {}", symbol, e);
-                        
-                        if err_msg.contains("-2010") {
-                            if let Some(slot) = s.slot_by_id(slot_id) {
-                                let needed = amount - slot.quote_balance;
-                                if needed > 0.0 {
-                                    err_msg = format!("Buy [{}] failed: Insufficient balance. You need ${:.2} more {}.", symbol, needed, slot.quote_asset);
-                                }
-                            }
-                        }
-                        
-                        s.log_error(&err_msg);
-                        if let Some(slot) = s.slot_by_id_mut(slot_id) {
-                            slot.strategy.stop();
-                            slot.strategy.state = DcaState::Idle;
-                        }
-                        s.log(&format!("Strategy for {} STOPPED due to error.", symbol));
-                    }
-                }
+/// Poda periódica de los historiales según `[retention]`: eventos del event log ya cubiertos
+/// por el snapshot guardado y registros de la auditoría de órdenes más viejos que el límite
+async fn run_maintenance(
+    state: Arc<Mutex<AppState>>,
+    client: Arc<BinanceClient>,
+    state_path: std::path::PathBuf,
+    retention: config::RetentionConfig,
+) {
+    let mut tick = tokio::time::interval(Duration::from_secs(retention.maintenance_hours * 3600));
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        tick.tick().await;
+        let now = chrono::Utc::now();
+
+        // La E/S va en spawn_blocking, sin tomar el estado: el motor sigue agregando al
+        // log mientras tanto (EventFile serializa la escritura y la poda)
+        if retention.events_days > 0 {
+            let before = now - chrono::Duration::days(retention.events_days as i64);
+            let file = state.lock().await.event_log.file();
+            let path = state_path.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                // Solo lo que ya está en el snapshot en disco, con las estadísticas del slot
+                // guardadas: un arranque nunca re-aplica lo podado ni lo necesita para el
+                // P&L de vida (con el archivo ilegible no se poda nada)
+                let covered = state_file::load(&path)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|s| if s.performance.is_some() { s.last_event_seq } else { 0 })
+                    .min()
+                    .unwrap_or(0);
+                file.prune(before, covered)
+            })
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|r| r);
+            let mut s = state.lock().await;
+            match result {
+                Ok(0) => {}
+                Ok(n) => s.log(&format!("Maintenance: {} event(s) older than {} days pruned", n, retention.events_days)),
+                Err(e) => s.log_error(&format!("Maintenance: could not prune the event log: {:#}", e)),
             }
+        }
 
-            Direction::Short => {
-                let qty_to_sell = if price > 0.0 { amount / price } else { return };
-                let order_num = {
-                    state.lock().await
-                        .slot_by_id(slot_id)
-                        .map(|sl| sl.strategy.trades.len() + 1)
-                        .unwrap_or(1)
-                };
-                tracing::info!(
-                    "Executing DCA SHORT sell [{}] #{}: {:.6}",
-                    symbol, order_num, qty_to_sell
-                );
+        if retention.audit_days > 0 {
+            let before = now - chrono::Duration::days(retention.audit_days as i64);
+            let client = Arc::clone(&client);
+            let result = tokio::task::spawn_blocking(move || client.prune_audit_log(before))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|r| r.map_err(anyhow::Error::from));
+            let mut s = state.lock().await;
+            match result {
+                Ok(0) => {}
+                Ok(n) => s.log(&format!("Maintenance: {} order audit record(s) older than {} days pruned", n, retention.audit_days)),
+                Err(e) => s.log_error(&format!("Maintenance: could not prune the order audit: {:#}", e)),
+            }
+        }
 
-                match client.market_sell_qty(&symbol, qty_to_sell).await {
-                    Ok(order) => {
-                        let exec_qty: f64 = order.executed_qty.parse().unwrap_or(0.0);
-                        let received: f64 = order.cummulative_quote_qty.parse().unwrap_or(amount);
-                        let actual_price = if exec_qty > 0.0 { received / exec_qty } else { price };
-                        {
-                            let mut s = state.lock().await;
-                            if let Some(slot) = s.slot_by_id_mut(slot_id) {
-                                let num = slot.strategy.trades.len() + 1;
-                                let base = slot.base_asset.clone();
-                                slot.strategy.record_buy(order.order_id, actual_price, exec_qty, received);
-                                s.log(&format!(
-                                    "SHORT #{} [{}]: sold {:.6} {} @ ${:.4} (${:.2})",
-                                    num, symbol, exec_qty, base, actual_price, received
-                                ));
-                            }
-                        }
-                        save_all_snapshots(state, state_path).await;
-                    }
-                    Err(e) => {
-                        let mut s = state.lock().await;
-                        let mut err_msg = format!("Short entry [{}] failed: {}", symbol, e);
-                        
-                        if err_msg.contains("-2010") {
-                            if let Some(slot) = s.slot_by_id(slot_id) {
-                                let needed = qty_to_sell - slot.base_balance;
-                                if needed > 0.0 {
-                                    err_msg = format!("Short entry [{}] failed: Insufficient balance. You need {:.6} more {}.", symbol, needed, slot.base_asset);
-                                }
-                            }
-                        }
-                        
-                        s.log_error(&err_msg);
-                        if let Some(slot) = s.slot_by_id_mut(slot_id) {
-                            slot.strategy.stop();
-                            slot.strategy.state = DcaState::Idle;
-                        }
-                        s.log(&format!("Strategy for {} STOPPED due to error.", symbol));
-                    }
-                }
+        if retention.equity_days > 0 {
+            let before = now - chrono::Duration::days(retention.equity_days as i64);
+            let history = Arc::clone(&state.lock().await.equity_history);
+            let result = tokio::task::spawn_blocking(move || history.downsample(before))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|r| r);
+            let mut s = state.lock().await;
+            match result {
+                Ok(0) => {}
+                Ok(n) => s.log(&format!(
+                    "Maintenance: {} equity sample(s) older than {} days thinned to one per day",
+                    n, retention.equity_days
+                )),
+                Err(e) => s.log_error(&format!("Maintenance: could not thin the equity history: {:#}", e)),
             }
         }
     }
 }
 
-/// Actualiza el canal watch con la lista actual de símbolos
-async fn update_symbol_watch(
-    state: &Arc<Mutex<AppState>>,
-    symbol_tx: &watch::Sender<Vec<String>>,
-) {
-    let symbols: Vec<String> = state.lock().await.slots.iter().map(|s| s.symbol.clone()).collect();
-    let _ = symbol_tx.send(symbols);
+/// Cada cuánto se guarda stats.json: un corte pierde a lo sumo ese intervalo de contadores
+const STATS_SAVE_SECS: u64 = 60;
+
+async fn run_stats_saver(state: Arc<Mutex<AppState>>) {
+    let mut tick = tokio::time::interval(Duration::from_secs(STATS_SAVE_SECS));
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        tick.tick().await;
+        save_stats(&state).await;
+    }
 }
 
-/// Guarda todos los slots como Vec<StrategySnapshot>
-async fn save_all_snapshots(state: &Arc<Mutex<AppState>>, path: &std::path::Path) {
-    let snapshots: Vec<StrategySnapshot> = {
+async fn save_stats(state: &Arc<Mutex<AppState>>) {
+    let (path, file) = {
         let s = state.lock().await;
-        s.slots.iter().map(|sl| sl.strategy.to_snapshot(&sl.symbol)).collect()
+        (s.stats.path.clone(), s.stats.file())
     };
-    if let Err(e) = save_snapshots(&snapshots, path) {
-        tracing::warn!("Could not save state: {}", e);
+    if let Err(e) = file.save(&path).await {
+        tracing::warn!("Could not save session stats: {:#}", e);
     }
 }
 
-/// Actualiza los balances de todos los slots con una sola llamada a la API
-async fn refresh_balance(state: &Arc<Mutex<AppState>>, client: &Arc<BinanceClient>) {
-    match client.get_account().await {
-        Ok(account) => {
-            let mut s = state.lock().await;
-            for slot in s.slots.iter_mut() {
-                slot.base_balance = account.get_free(&slot.base_asset);
-                slot.quote_balance = account.get_free(&slot.quote_asset);
+/// Risk parity: multiplicador de tamaño por slot, inverso a la volatilidad (ATR% diario)
+async fn run_sizing_engine(
+    state: Arc<Mutex<AppState>>,
+    client: Arc<BinanceClient>,
+    mut symbols_rx: watch::Receiver<Vec<String>>,
+    atr_period: usize,
+) {
+    let mut tick = tokio::time::interval(Duration::from_secs(24 * 3600));
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            _ = tick.tick() => {}
+            changed = symbols_rx.changed() => {
+                if changed.is_err() {
+                    return;
+                }
             }
-            tracing::debug!("Balances updated for {} slot(s)", s.slots.len());
         }
-        Err(e) => {
-            tracing::warn!("Could not update balance: {}", e);
+
+        let slots: Vec<(usize, String)> = state
+            .lock()
+            .await
+            .slots
+            .iter()
+            .map(|s| (s.id, s.symbol.clone()))
+            .collect();
+
+        // ATR% por slot con velas diarias cerradas
+        // (+1 para el cierre previo de la primera vela, +1 por la vela actual incompleta)
+        let mut vols: Vec<(usize, f64)> = Vec::new();
+        for (id, symbol) in &slots {
+            match client.get_klines(symbol, "1d", (atr_period + 2) as u32).await {
+                Ok(klines) if !klines.is_empty() => {
+                    let completed = &klines[..klines.len() - 1];
+                    if let Some(v) = sizing::atr_pct(completed, atr_period) {
+                        vols.push((*id, v));
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Risk parity: get_klines({}) error: {}", symbol, e),
+            }
+        }
+
+        let multipliers = sizing::risk_parity_multipliers(&vols);
+        if multipliers.is_empty() {
+            continue;
+        }
+        let mut s = state.lock().await;
+        let mut summary = Vec::new();
+        for (id, mult) in multipliers {
+            if let Some(slot) = s.slot_by_id_mut(id) {
+                slot.strategy.size_multiplier = mult;
+                summary.push(format!("{} x{:.2}", slot.base_asset, mult));
+            }
         }
+        s.log(&format!("Risk parity sizing: {}", summary.join(", ")));
     }
 }
 
-/// Carga snapshots desde disco (array JSON o single object para compatibilidad)
-fn load_snapshots(path: &std::path::Path) -> Vec<StrategySnapshot> {
-    let content = match std::fs::read_to_string(path) {
-        Ok(c) => c,
-        Err(_) => return vec![],
-    };
-    // Intentar array primero (nuevo formato)
-    if let Ok(snaps) = serde_json::from_str::<Vec<StrategySnapshot>>(&content) {
-        return snaps;
+/// Primer reintento de un símbolo sin velas, en segundos (se duplica en cada fallo)
+const MISSING_KLINES_RETRY_SECS: u64 = 300;
+/// Espera máxima entre reintentos de un símbolo sin velas
+const MISSING_KLINES_MAX_RETRY_SECS: u64 = 6 * 3600;
+
+/// Rellena el buffer de velas de un símbolo desde REST y devuelve las últimas `window`
+/// cerradas (None si Binance no devolvió suficientes). Un símbolo sin velas queda marcado
+/// y no se vuelve a pedir hasta que venza su backoff.
+async fn backfill_klines(
+    state: &Arc<Mutex<AppState>>,
+    client: &Arc<BinanceClient>,
+    symbol: &str,
+    interval: &str,
+    window: usize,
+) -> Option<Vec<models::ticker::Kline>> {
+    let now = std::time::Instant::now();
+    if state.lock().await.missing_klines.get(symbol).is_some_and(|m| m.retry_at > now) {
+        return None;
     }
-    // Fallback: single object (formato anterior de una sola estrategia)
-    if let Ok(snap) = serde_json::from_str::<StrategySnapshot>(&content) {
-        return vec![snap];
+    // +1 para excluir la vela actual (incompleta)
+    let klines = match client.get_klines(symbol, interval, (window + 1) as u32).await {
+        Ok(k) if k.len() > 1 => k,
+        Ok(k) if k.is_empty() => {
+            mark_missing_klines(state, symbol, false).await;
+            return None;
+        }
+        Ok(_) => return None,
+        Err(e) if e.to_string().contains("-1121") => {
+            mark_missing_klines(state, symbol, true).await;
+            return None;
+        }
+        Err(e) => {
+            tracing::warn!("get_klines({}) error: {}", symbol, e);
+            return None;
+        }
+    };
+    let mut s = state.lock().await;
+    if s.missing_klines.remove(symbol).is_some() {
+        s.log_for(symbol, &format!("[{}] Klines available, alerts and chart resumed", symbol));
     }
-    vec![]
+    s.candles.seed(symbol, interval, &klines[..klines.len() - 1]);
+    s.candles.closed(symbol, interval, window.min(klines.len() - 1))
 }
 
-/// Guarda Vec<StrategySnapshot> como JSON
-fn save_snapshots(snapshots: &[StrategySnapshot], path: &std::path::Path) -> anyhow::Result<()> {
-    let json = serde_json::to_string_pretty(snapshots)?;
-    std::fs::write(path, json)?;
-    Ok(())
+/// Marca un símbolo sin velas y programa el próximo intento. Solo el primer fallo va al
+/// log, con el símbolo disponible más parecido como sugerencia; el badge queda en el slot.
+async fn mark_missing_klines(state: &Arc<Mutex<AppState>>, symbol: &str, unknown: bool) {
+    let mut s = state.lock().await;
+    let failures = s.missing_klines.get(symbol).map_or(0, |m| m.failures) + 1;
+    let delay = (MISSING_KLINES_RETRY_SECS << (failures - 1).min(8)).min(MISSING_KLINES_MAX_RETRY_SECS);
+    let retry_at = std::time::Instant::now() + Duration::from_secs(delay);
+    let suggestion = market_data::closest_symbol(symbol, &s.symbols);
+    let missing = app::MissingKlines { unknown, suggestion, failures, retry_at };
+    if failures == 1 {
+        let hint = missing.suggestion.as_ref().map(|c| format!(" Did you mean {}?", c)).unwrap_or_default();
+        s.log_error_for(symbol, &format!(
+            "[{}] {}: skipped by alerts and chart, retrying with backoff.{}",
+            symbol, missing.reason(), hint
+        ));
+    } else {
+        tracing::debug!("[{}] {} (attempt {}), next retry in {}s", symbol, missing.reason(), failures, delay);
+    }
+    s.missing_klines.insert(symbol.to_string(), missing);
+    s.touch();
 }
 
-/// Beep del sistema para alertas de soporte/resistencia
-fn play_alert_sound() {
-    // BEL character: la mayoría de terminales/consolas emiten un beep
-    eprint!("\x07");
+/// Rellena por REST las velas del intervalo de análisis de cada slot que todavía no
+/// alcanzan para el gráfico y los niveles S/R (slots nuevos, restaurados o huecos del stream)
+async fn backfill_charts(state: &Arc<Mutex<AppState>>, client: &Arc<BinanceClient>, sr_window: usize) {
+    let window = sr_window.max(market_data::CHART_CANDLES);
+    let missing: Vec<(String, String)> = {
+        let s = state.lock().await;
+        let mut pairs: Vec<(String, String)> = s
+            .slots
+            .iter()
+            .map(|sl| (sl.symbol.clone(), sl.analysis_interval.clone()))
+            .filter(|(symbol, interval)| s.candles.closed(symbol, interval, window).is_none())
+            .collect();
+        pairs.sort();
+        pairs.dedup();
+        pairs
+    };
+    if missing.is_empty() {
+        return;
+    }
+    for (symbol, interval) in missing {
+        backfill_klines(state, client, &symbol, &interval, window).await;
+    }
+    state.lock().await.touch();
 }
 
 /// Motor de alertas S/R: cada 5 minutos descarga klines, calcula soporte/resistencia
@@ -1107,36 +5150,41 @@ async fn run_alert_engine(
     client: Arc<BinanceClient>,
     cfg: AlertsConfig,
 ) {
+    // El gráfico de cada slot no espera a la primera pasada
+    backfill_charts(&state, &client, cfg.rolling_window.max(1)).await;
+
     // Primera ejecución después de 30s (dar tiempo al WebSocket para recibir precios)
     tokio::time::sleep(Duration::from_secs(30)).await;
 
     let mut tick = tokio::time::interval(Duration::from_secs(300)); // cada 5 minutos
     tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
-    let limit = (cfg.rolling_window + 1) as u32; // +1 para excluir la vela actual (incompleta)
-    let cooldown = Duration::from_secs(cfg.cooldown_minutes * 60);
-
     loop {
         tick.tick().await;
+        // Ventana y cooldown desde el estado: se pueden recargar de config.toml en caliente
+        let (window, cooldown) = {
+            let s = state.lock().await;
+            (s.sr_window, Duration::from_secs(s.alert_cooldown_minutes * 60))
+        };
+        backfill_charts(&state, &client, window).await;
 
-        // Obtener todos los símbolos activos
-        let symbols: Vec<String> = state.lock().await.slots.iter()
+        // Obtener todos los símbolos activos, una vez cada uno aunque varios slots lo operen
+        let mut symbols: Vec<String> = state.lock().await.slots.iter()
             .map(|s| s.symbol.clone())
             .collect();
+        symbols.sort();
+        symbols.dedup();
 
         for symbol in symbols {
-            // Descargar velas (endpoint público, sin firma)
-            let klines = match client.get_klines(&symbol, &cfg.candle_interval, limit).await {
-                Ok(k) if k.len() > 1 => k,
-                Ok(_) => continue,
-                Err(e) => {
-                    tracing::warn!("get_klines({}) error: {}", symbol, e);
-                    continue;
-                }
+            // Velas cerradas del stream @kline; REST solo para el relleno inicial (o tras un hueco)
+            let cached = state.lock().await.candles.closed(&symbol, &cfg.candle_interval, window);
+            let completed = match cached {
+                Some(k) => k,
+                None => match backfill_klines(&state, &client, &symbol, &cfg.candle_interval, window).await {
+                    Some(k) => k,
+                    None => continue,
+                },
             };
-
-            // Usar solo velas cerradas (excluir la última, que puede estar incompleta)
-            let completed = &klines[..klines.len() - 1];
             let resistance = completed.iter().map(|k| k.high).fold(f64::NEG_INFINITY, f64::max);
             let support    = completed.iter().map(|k| k.low ).fold(f64::INFINITY,     f64::min);
 
@@ -1164,8 +5212,8 @@ async fn run_alert_engine(
             let support_broken    = current_price < support    && prev_price >= support;
             let resistance_broken = current_price > resistance && prev_price <= resistance;
 
-            let sup_ok = last_sup.map_or(true, |t| now.duration_since(t) >= cooldown);
-            let res_ok = last_res.map_or(true, |t| now.duration_since(t) >= cooldown);
+            let sup_ok = last_sup.is_none_or(|t| now.duration_since(t) >= cooldown);
+            let res_ok = last_res.is_none_or(|t| now.duration_since(t) >= cooldown);
 
             if support_broken && sup_ok {
                 let msg = format!(
@@ -1174,7 +5222,7 @@ async fn run_alert_engine(
                 );
                 {
                     let mut s = state.lock().await;
-                    s.log_alert(&msg);
+                    s.push_alert(&symbol, "SUPPORT", support, current_price, &msg);
                     let level = s.alert_levels.entry(symbol.clone()).or_insert(AlertLevel {
                         resistance,
                         support,
@@ -1183,8 +5231,10 @@ async fn run_alert_engine(
                         last_resistance_alert: None,
                     });
                     level.last_support_alert = Some(now);
+                    // Las alertas son por símbolo: decide el primer slot que lo opera
+                    let slot_id = s.slots.iter().find(|sl| sl.symbol == symbol).map(|sl| sl.id);
+                    notify(&s, slot_id, NotifyEvent::Alert);
                 }
-                play_alert_sound();
             }
 
             if resistance_broken && res_ok {
@@ -1194,7 +5244,7 @@ async fn run_alert_engine(
                 );
                 {
                     let mut s = state.lock().await;
-                    s.log_alert(&msg);
+                    s.push_alert(&symbol, "RESISTANCE", resistance, current_price, &msg);
                     let level = s.alert_levels.entry(symbol.clone()).or_insert(AlertLevel {
                         resistance,
                         support,
@@ -1203,8 +5253,10 @@ async fn run_alert_engine(
                         last_resistance_alert: None,
                     });
                     level.last_resistance_alert = Some(now);
+                    // Las alertas son por símbolo: decide el primer slot que lo opera
+                    let slot_id = s.slots.iter().find(|sl| sl.symbol == symbol).map(|sl| sl.id);
+                    notify(&s, slot_id, NotifyEvent::Alert);
                 }
-                play_alert_sound();
             }
 
             // Actualizar niveles y precio previo para la próxima iteración
@@ -1224,17 +5276,3 @@ async fn run_alert_engine(
         }
     }
 }
-
-/// Extrae base y quote asset de un símbolo de Binance
-/// Ej: "BTCUSDT" → ("BTC", "USDT")
-fn parse_symbol(symbol: &str) -> (String, String) {
-    const QUOTE_ASSETS: &[&str] = &["USDT", "BUSD", "USDC", "TUSD", "BTC", "ETH", "BNB", "DAI"];
-    for qa in QUOTE_ASSETS {
-        if symbol.ends_with(qa) && symbol.len() > qa.len() {
-            let base = &symbol[..symbol.len() - qa.len()];
-            return (base.to_string(), qa.to_string());
-        }
-    }
-    let mid = symbol.len() / 2;
-    (symbol[..mid].to_string(), symbol[mid..].to_string())
-}
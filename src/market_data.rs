@@ -0,0 +1,395 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::Notify;
+
+use crate::models::ticker::{AggTradeEvent, Kline, KlineEvent, MiniTickerEvent};
+
+/// Closed candles kept per symbol and interval
+const CAPACITY: usize = 500;
+
+/// Intervals streamed for every active symbol (more can be added, e.g. the alert interval)
+pub const BASE_INTERVALS: [&str; 2] = ["1m", "1h"];
+
+/// Intervals the chart and S/R levels of a slot can be switched to, in cycling order
+pub const ANALYSIS_INTERVALS: [&str; 5] = ["5m", "15m", "1h", "4h", "1d"];
+/// Default analysis interval of a slot
+pub const DEFAULT_ANALYSIS_INTERVAL: &str = "1h";
+/// Closed candles drawn in the chart panel
+pub const CHART_CANDLES: usize = 40;
+
+/// Analysis interval after `current` (wraps around; unknown values restart the cycle)
+pub fn next_analysis_interval(current: &str) -> &'static str {
+    match ANALYSIS_INTERVALS.iter().position(|i| *i == current) {
+        Some(i) => ANALYSIS_INTERVALS[(i + 1) % ANALYSIS_INTERVALS.len()],
+        None => ANALYSIS_INTERVALS[0],
+    }
+}
+
+#[derive(Debug, Default)]
+struct CandleBuffer {
+    /// Closed candles, oldest first, without gaps
+    closed: VecDeque<Kline>,
+}
+
+/// In-memory candle buffers fed by the @kline WebSocket streams, so indicators read
+/// candles locally instead of polling GET /api/v3/klines. A buffer is backfilled once from
+/// REST (`seed`) and is dropped when the stream misses a candle, so readers re-seed it.
+#[derive(Debug, Default)]
+pub struct CandleStore {
+    buffers: HashMap<(String, String), CandleBuffer>,
+}
+
+impl CandleStore {
+    /// Applies a streamed candle update
+    pub fn apply(&mut self, event: &KlineEvent) {
+        // Only closed candles are buffered; the one still forming is skipped
+        if !event.kline.closed {
+            return;
+        }
+        let key = (event.symbol.clone(), event.kline.interval.clone());
+        let step = interval_ms(&event.kline.interval);
+        let buffer = self.buffers.entry(key).or_default();
+        let kline = event.to_kline();
+        match buffer.closed.back() {
+            Some(last) if last.open_time >= kline.open_time => return,
+            // Candles missed while the stream was down: the buffer is no longer contiguous
+            Some(last) if step.is_some_and(|step| kline.open_time - last.open_time > step) => {
+                buffer.closed.clear();
+            }
+            _ => {}
+        }
+        buffer.closed.push_back(kline);
+        if buffer.closed.len() > CAPACITY {
+            buffer.closed.pop_front();
+        }
+    }
+
+    /// Backfills closed candles (e.g. from REST), merged by open time with the streamed ones
+    pub fn seed(&mut self, symbol: &str, interval: &str, klines: &[Kline]) {
+        let buffer = self.buffers.entry((symbol.to_string(), interval.to_string())).or_default();
+        let mut merged: BTreeMap<i64, Kline> = klines.iter().map(|k| (k.open_time, k.clone())).collect();
+        merged.extend(buffer.closed.drain(..).map(|k| (k.open_time, k)));
+        let skip = merged.len().saturating_sub(CAPACITY);
+        buffer.closed = merged.into_values().skip(skip).collect();
+    }
+
+    /// Last `n` closed candles, oldest first. None until that many are buffered.
+    pub fn closed(&self, symbol: &str, interval: &str, n: usize) -> Option<Vec<Kline>> {
+        let buffer = self.buffers.get(&(symbol.to_string(), interval.to_string()))?;
+        if n == 0 || buffer.closed.len() < n {
+            return None;
+        }
+        Some(buffer.closed.iter().skip(buffer.closed.len() - n).cloned().collect())
+    }
+
+    /// Up to the last `n` closed candles, oldest first (fewer while the buffer fills)
+    pub fn recent(&self, symbol: &str, interval: &str, n: usize) -> Vec<Kline> {
+        self.buffers
+            .get(&(symbol.to_string(), interval.to_string()))
+            .map(|b| b.closed.iter().skip(b.closed.len().saturating_sub(n)).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Support (lowest low) and resistance (highest high) of the last `n` closed candles.
+    /// None until that many are buffered.
+    pub fn levels(&self, symbol: &str, interval: &str, n: usize) -> Option<(f64, f64)> {
+        let candles = self.closed(symbol, interval, n)?;
+        let support = candles.iter().map(|k| k.low).fold(f64::INFINITY, f64::min);
+        let resistance = candles.iter().map(|k| k.high).fold(f64::NEG_INFINITY, f64::max);
+        Some((support, resistance))
+    }
+
+    /// Drops the buffers of symbols no longer traded
+    pub fn retain_symbols(&mut self, symbols: &[String]) {
+        self.buffers.retain(|(symbol, _), _| symbols.contains(symbol));
+    }
+}
+
+/// Latest mini ticker per symbol, written by the WebSocket and drained by the engine.
+/// A burst of tickers for one symbol overwrites the pending one instead of queueing, so the
+/// engine always reads the freshest price and no symbol is dropped for lack of room.
+#[derive(Debug, Default)]
+pub struct TickerBoard {
+    /// Ticker not yet read per symbol, with the time it was received
+    pending: Mutex<HashMap<String, (MiniTickerEvent, DateTime<Utc>)>>,
+    notify: Notify,
+}
+
+impl TickerBoard {
+    /// Stores the ticker of its symbol, replacing any still unread, and wakes the reader
+    pub fn publish(&self, event: MiniTickerEvent) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.insert(event.symbol.clone(), (event, Utc::now()));
+        drop(pending);
+        self.notify.notify_one();
+    }
+
+    /// Waits until at least one symbol has a new ticker and takes every pending one
+    pub async fn changed(&self) -> Vec<(MiniTickerEvent, DateTime<Utc>)> {
+        loop {
+            let updates: Vec<_> = {
+                let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+                pending.drain().map(|(_, update)| update).collect()
+            };
+            if !updates.is_empty() {
+                return updates;
+            }
+            self.notify.notified().await;
+        }
+    }
+}
+
+/// Live prices kept per symbol for the price sparkline: one hour at `PRICE_SAMPLE_SECS`
+const PRICE_HISTORY: usize = 720;
+/// Seconds between two kept prices; updates inside the gap replace the latest one
+const PRICE_SAMPLE_SECS: i64 = 5;
+
+/// Ring buffer of live prices per symbol since the bot started, sampled from the ticker
+/// stream so it holds the last `PRICE_HISTORY × PRICE_SAMPLE_SECS` seconds
+#[derive(Debug, Default)]
+pub struct PriceHistory {
+    samples: HashMap<String, VecDeque<(DateTime<Utc>, f64)>>,
+}
+
+impl PriceHistory {
+    pub fn record(&mut self, symbol: &str, price: f64, at: DateTime<Utc>) {
+        if price <= 0.0 {
+            return;
+        }
+        let samples = self.samples.entry(symbol.to_string()).or_default();
+        match samples.back_mut() {
+            Some((last_at, last)) if (at - *last_at).num_seconds() < PRICE_SAMPLE_SECS => *last = price,
+            _ => {
+                samples.push_back((at, price));
+                if samples.len() > PRICE_HISTORY {
+                    samples.pop_front();
+                }
+            }
+        }
+    }
+
+    /// The whole buffer of `symbol` in at most `count` prices, oldest first, with the time
+    /// of the oldest sample. A longer buffer is split into `count` equal stretches and each
+    /// one is represented by its last price.
+    pub fn recent(&self, symbol: &str, count: usize) -> Option<(DateTime<Utc>, Vec<f64>)> {
+        let samples = self.samples.get(symbol)?;
+        let since = samples.front()?.0;
+        let len = samples.len();
+        if len <= count {
+            return Some((since, samples.iter().map(|(_, p)| *p).collect()));
+        }
+        let prices = (1..=count).map(|i| samples[i * len / count - 1].1).collect();
+        Some((since, prices))
+    }
+
+    /// Drops the prices of symbols no longer watched
+    pub fn retain_symbols(&mut self, symbols: &[String]) {
+        self.samples.retain(|symbol, _| symbols.contains(symbol));
+    }
+}
+
+/// Taker volume of one minute in quote asset, split by aggressor side
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MinuteVolume {
+    pub buy: f64,
+    pub sell: f64,
+}
+
+impl MinuteVolume {
+    pub fn total(&self) -> f64 {
+        self.buy + self.sell
+    }
+
+    /// Share of the volume bought by takers, 0..1
+    pub fn buy_ratio(&self) -> f64 {
+        if self.total() > 0.0 { self.buy / self.total() } else { 0.5 }
+    }
+}
+
+/// Grace after the end of a minute before it counts as over without a newer trade (the
+/// trade times come from Binance's clock, the check uses the local one)
+const MINUTE_GRACE_MS: i64 = 5_000;
+
+/// Minute whose volume exceeded `factor` × the rolling average
+#[derive(Debug, Clone)]
+pub struct VolumeSpike {
+    pub volume: MinuteVolume,
+    pub average: f64,
+    pub ratio: f64,
+}
+
+#[derive(Debug, Default)]
+struct VolumeWindow {
+    /// Start of the minute being accumulated, in ms
+    minute: i64,
+    current: MinuteVolume,
+    /// Totals of the previous minutes, oldest first (minutes without trades count as 0)
+    history: VecDeque<f64>,
+    /// Minute of the last spike reported
+    last_spike: Option<i64>,
+}
+
+/// Rolling 1-minute buy/sell volume per symbol, fed by the @aggTrade stream
+#[derive(Debug)]
+pub struct VolumeTracker {
+    windows: HashMap<String, VolumeWindow>,
+    /// Spike threshold over the rolling average (0 = detection off)
+    factor: f64,
+    /// Minutes in the rolling average
+    window: usize,
+    /// Minimum minutes between two spikes reported for the same symbol
+    cooldown_minutes: i64,
+}
+
+impl VolumeTracker {
+    pub fn new(factor: f64, window: usize, cooldown_minutes: u64) -> Self {
+        Self {
+            windows: HashMap::new(),
+            factor,
+            window: window.max(1),
+            cooldown_minutes: cooldown_minutes as i64,
+        }
+    }
+
+    /// Changes the minimum minutes between two spikes of the same symbol
+    pub fn set_cooldown_minutes(&mut self, minutes: u64) {
+        self.cooldown_minutes = minutes as i64;
+    }
+
+    /// Adds a trade. Returns the spike the first time the current minute crosses the
+    /// threshold (once the rolling window is full and outside the cooldown).
+    pub fn apply(&mut self, trade: &AggTradeEvent) -> Option<VolumeSpike> {
+        let minute = trade.trade_time - trade.trade_time.rem_euclid(60_000);
+        let w = self.windows.entry(trade.symbol.clone()).or_default();
+        if minute > w.minute {
+            if w.minute > 0 {
+                let skipped = ((minute - w.minute) / 60_000 - 1).clamp(0, self.window as i64);
+                w.history.push_back(w.current.total());
+                w.history.extend(std::iter::repeat_n(0.0, skipped as usize));
+                while w.history.len() > self.window {
+                    w.history.pop_front();
+                }
+            }
+            w.minute = minute;
+            w.current = MinuteVolume::default();
+        } else if minute < w.minute {
+            // Late trade from a minute already rolled over
+            return None;
+        }
+        if trade.buyer_is_maker {
+            w.current.sell += trade.quote_qty();
+        } else {
+            w.current.buy += trade.quote_qty();
+        }
+
+        if self.factor <= 0.0 || w.history.len() < self.window {
+            return None;
+        }
+        let cooled = w
+            .last_spike
+            .is_none_or(|t| (minute - t) / 60_000 >= self.cooldown_minutes.max(1));
+        let spike = Self::spike_of(w, self.factor)?;
+        if !cooled {
+            return None;
+        }
+        w.last_spike = Some(minute);
+        Some(spike)
+    }
+
+    /// Spike in progress in the current minute of a symbol (None below the threshold, or
+    /// once that minute is over and no trade has rolled it yet)
+    pub fn spike(&self, symbol: &str) -> Option<VolumeSpike> {
+        let w = self.windows.get(symbol)?;
+        if self.factor <= 0.0 || w.history.len() < self.window || !Self::live(w) {
+            return None;
+        }
+        Self::spike_of(w, self.factor)
+    }
+
+    /// Current minute volume and the rolling average of a symbol (no volume once the last
+    /// traded minute is over)
+    pub fn current(&self, symbol: &str) -> Option<(MinuteVolume, Option<f64>)> {
+        let w = self.windows.get(symbol)?;
+        let average = (!w.history.is_empty())
+            .then(|| w.history.iter().sum::<f64>() / w.history.len() as f64);
+        let volume = if Self::live(w) { w.current } else { MinuteVolume::default() };
+        Some((volume, average))
+    }
+
+    /// Whether the minute being accumulated is still the current one. The window only rolls
+    /// over with a trade, so a symbol that stops trading would keep its last minute forever
+    fn live(w: &VolumeWindow) -> bool {
+        Utc::now().timestamp_millis() < w.minute + 60_000 + MINUTE_GRACE_MS
+    }
+
+    /// Drops the windows of symbols no longer traded
+    pub fn retain_symbols(&mut self, symbols: &[String]) {
+        self.windows.retain(|symbol, _| symbols.contains(symbol));
+    }
+
+    fn spike_of(w: &VolumeWindow, factor: f64) -> Option<VolumeSpike> {
+        let average = w.history.iter().sum::<f64>() / w.history.len() as f64;
+        if average <= 0.0 || w.current.total() < average * factor {
+            return None;
+        }
+        Some(VolumeSpike { volume: w.current, average, ratio: w.current.total() / average })
+    }
+}
+
+/// Closest symbol to `symbol` among `candidates` by edit distance, e.g. "BTCUSTD" →
+/// "BTCUSDT". None when nothing is within a third of its length (or it is listed as is).
+pub fn closest_symbol(symbol: &str, candidates: &[String]) -> Option<String> {
+    let max_distance = (symbol.len() / 3).max(1);
+    candidates
+        .iter()
+        .filter(|c| c.as_str() != symbol)
+        .map(|c| (edit_distance(symbol, c), c))
+        .filter(|(d, _)| *d <= max_distance)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c.clone())
+}
+
+/// Levenshtein distance (insertions, deletions and substitutions)
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1).min(row[j] + 1).min(diagonal + usize::from(ca != *cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Intervals streamed for active symbols: the base ones plus `extra`, without duplicates
+pub fn stream_intervals(extra: &[&str]) -> Vec<String> {
+    let mut intervals: Vec<String> = BASE_INTERVALS.iter().map(|i| i.to_string()).collect();
+    for interval in extra {
+        if !intervals.iter().any(|i| i == interval) {
+            intervals.push(interval.to_string());
+        }
+    }
+    intervals
+}
+
+/// Length of a Binance kline interval ("1m", "4h", "1d", "1w") in milliseconds.
+/// Monthly candles have no fixed length (None).
+fn interval_ms(interval: &str) -> Option<i64> {
+    let (num, unit) = interval.split_at(interval.len().checked_sub(1)?);
+    let num: i64 = num.parse().ok()?;
+    let unit_ms = match unit {
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        "w" => 7 * 86_400_000,
+        _ => return None,
+    };
+    Some(num * unit_ms)
+}
@@ -22,10 +22,7 @@ impl Balance {
 pub struct AccountInfo {
     pub maker_commission: i32,
     pub taker_commission: i32,
-    pub buyer_commission: i32,
-    pub seller_commission: i32,
     pub can_trade: bool,
-    pub can_deposit: bool,
     pub can_withdraw: bool,
     pub balances: Vec<Balance>,
 }
@@ -1,3 +1,4 @@
 pub mod account;
 pub mod order;
+pub mod symbol;
 pub mod ticker;
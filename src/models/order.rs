@@ -8,18 +8,6 @@ pub enum OrderSide {
     Sell,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum OrderType {
-    Market,
-    Limit,
-    StopLoss,
-    StopLossLimit,
-    TakeProfit,
-    TakeProfitLimit,
-    LimitMaker,
-}
-
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderStatus {
@@ -36,38 +24,271 @@ pub enum OrderStatus {
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Order {
-    pub symbol: String,
     pub order_id: u64,
     pub client_order_id: String,
-    pub transact_time: u64,
-    pub price: String,
     pub orig_qty: String,
     pub executed_qty: String,
     pub cummulative_quote_qty: String,
     pub status: OrderStatus,
+    /// Individual fills; only present when the order is created (FULL response)
+    #[serde(default)]
+    pub fills: Vec<Fill>,
+}
+
+impl Order {
+    /// Commission charged across all fills (None when Binance reported no fills).
+    /// Fills are assumed to share the commission asset of the first one.
+    pub fn commission(&self) -> Option<Commission> {
+        Commission::from_fills(&self.trade_fills())
+    }
+
+    /// Fills as numbers, for the trade record
+    pub fn trade_fills(&self) -> Vec<TradeFill> {
+        self.fills
+            .iter()
+            .map(|f| TradeFill {
+                price: f.price.parse().unwrap_or(0.0),
+                qty: f.qty.parse().unwrap_or(0.0),
+                commission: f.commission.parse().unwrap_or(0.0),
+                commission_asset: f.commission_asset.clone(),
+            })
+            .collect()
+    }
+}
+
+/// One fill of an order
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Fill {
+    pub price: String,
+    pub qty: String,
+    pub commission: String,
+    pub commission_asset: String,
+}
+
+/// One fill of a recorded entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeFill {
+    pub price: f64,
+    pub qty: f64,
+    pub commission: f64,
+    pub commission_asset: String,
+}
+
+/// What made the strategy enter
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryTrigger {
+    /// `interval_minutes` passed since the previous entry
+    Interval { minutes: i64 },
+    /// Price moved against the position by `moved_pct` (threshold `trigger_pct`)
+    PriceMove { moved_pct: f64, trigger_pct: f64 },
+    /// Safety order `number` of the ladder, `deviation_pct` away from the base order
+    SafetyOrder { number: usize, deviation_pct: f64 },
+    /// Accumulation/distribution schedule came due
+    Schedule,
+    /// Distribution sell level reached
+    SellLevel { price: f64 },
+}
+
+impl EntryTrigger {
+    pub fn describe(&self) -> String {
+        match self {
+            EntryTrigger::Interval { minutes } => format!("interval: {} min since the previous entry", minutes),
+            EntryTrigger::PriceMove { moved_pct, trigger_pct } => {
+                format!("price move: {:.2}% against the last entry (trigger {:.2}%)", moved_pct, trigger_pct)
+            }
+            EntryTrigger::SafetyOrder { number, deviation_pct } => {
+                format!("safety order #{}: {:.2}% from the base order", number, deviation_pct)
+            }
+            EntryTrigger::Schedule => "buy schedule due".to_string(),
+            EntryTrigger::SellLevel { price } => format!("sell level ${:.4} reached", price),
+        }
+    }
+}
+
+/// One of the account's own fills (GET /api/v3/myTrades)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountTrade {
+    pub id: u64,
+    pub order_id: u64,
+    pub price: String,
+    pub qty: String,
+    pub quote_qty: String,
+    pub commission: String,
+    pub commission_asset: String,
+    /// Fill time in milliseconds
+    pub time: i64,
+    pub is_buyer: bool,
+}
+
+impl AccountTrade {
+    pub fn time_utc(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(self.time).unwrap_or_default()
+    }
+
+    /// The fill as numbers, for the trade record
+    pub fn trade_fill(&self) -> TradeFill {
+        TradeFill {
+            price: self.price.parse().unwrap_or(0.0),
+            qty: self.qty.parse().unwrap_or(0.0),
+            commission: self.commission.parse().unwrap_or(0.0),
+            commission_asset: self.commission_asset.clone(),
+        }
+    }
+
+    /// Quantity and average cost left after `trades` (any order), averaging buys and
+    /// taking sells out at the running average. None when nothing is left.
+    pub fn holding_cost(trades: &[AccountTrade]) -> Option<(f64, f64)> {
+        let mut sorted: Vec<&AccountTrade> = trades.iter().collect();
+        sorted.sort_by_key(|t| (t.time, t.id));
+        let (qty, cost) = sorted.into_iter().fold((0.0f64, 0.0f64), |(qty, cost), t| {
+            let fill_qty: f64 = t.qty.parse().unwrap_or(0.0);
+            if t.is_buyer {
+                (qty + fill_qty, cost + t.quote_qty.parse::<f64>().unwrap_or(0.0))
+            } else if qty > 0.0 {
+                let left = (qty - fill_qty).max(0.0);
+                (left, cost * left / qty)
+            } else {
+                (qty, cost)
+            }
+        });
+        (qty > 0.0).then(|| (qty, cost / qty))
+    }
+}
+
+/// Commission actually paid on an order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Commission {
+    pub asset: String,
+    pub amount: f64,
+}
+
+impl Commission {
+    /// Commission of the fills charged in the first fill's asset (None = no fills)
+    pub fn from_fills(fills: &[TradeFill]) -> Option<Self> {
+        let first = fills.first()?;
+        let amount = fills
+            .iter()
+            .filter(|f| f.commission_asset == first.commission_asset)
+            .map(|f| f.commission)
+            .sum();
+        Some(Self { asset: first.commission_asset.clone(), amount })
+    }
+}
+
+/// Entry LIMIT order waiting to be filled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenOrder {
+    pub order_id: u64,
     pub side: OrderSide,
-    #[serde(rename = "type")]
-    pub order_type: OrderType,
+    pub price: f64,
+    pub quantity: f64,
+    pub placed_at: DateTime<Utc>,
+    /// LIMIT_MAKER (post-only) instead of a plain GTC LIMIT
+    #[serde(default)]
+    pub post_only: bool,
+    /// Entries in the position when it was placed (fewer now = the cycle was closed)
+    pub entries_before: usize,
+    /// Times it was cancelled for timing out and re-placed closer to the market
+    #[serde(default)]
+    pub replaces: u32,
+    /// Why the entry was requested
+    #[serde(default)]
+    pub trigger: Option<EntryTrigger>,
+}
+
+/// Automatic exit whose market order could not reach Binance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitReason {
+    StopLoss,
+    TakeProfit,
+    TakeProfitLevel,
+    TrailingTp,
+    BreakEven,
+}
+
+impl ExitReason {
+    pub fn label(self) -> &'static str {
+        match self {
+            ExitReason::StopLoss => "STOP LOSS",
+            ExitReason::TakeProfit => "TAKE PROFIT",
+            ExitReason::TakeProfitLevel => "TAKE PROFIT LEVEL",
+            ExitReason::TrailingTp => "TRAILING TP",
+            ExitReason::BreakEven => "BREAK-EVEN STOP",
+        }
+    }
+}
+
+/// Exit that fired while REST was unreachable: retried until it goes through, even if the
+/// price no longer meets the exit condition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingExit {
+    pub reason: ExitReason,
+    pub since: DateTime<Utc>,
+    pub attempts: u32,
+    pub last_error: String,
+    /// Client order ID of the last attempt when it may have reached Binance; looked up
+    /// before the exit is sent again
+    #[serde(default)]
+    pub client_order_id: Option<String>,
+}
+
+impl PendingExit {
+    pub fn new(reason: ExitReason, error: String, client_order_id: Option<String>) -> Self {
+        Self { reason, since: Utc::now(), attempts: 1, last_error: error, client_order_id }
+    }
 }
 
 /// Internal record of a DCA operation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DcaTrade {
     pub order_id: u64,
-    pub buy_price: f64,
+    pub buy_price: f64, // average fill price
     pub quantity: f64,  // base quantity (e.g.: BTC)
     pub cost: f64,      // total cost in quote (e.g.: USDT)
     pub timestamp: DateTime<Utc>,
+    /// Price seen when the entry was decided (0 = unknown, older trades)
+    #[serde(default)]
+    pub expected_price: f64,
+    /// Commission charged on the fills (None = not reported)
+    #[serde(default)]
+    pub commission: Option<Commission>,
+    /// Individual fills (empty when Binance did not report them, e.g. LIMIT entries)
+    #[serde(default)]
+    pub fills: Vec<TradeFill>,
+    /// Why the entry was made (None = unknown: adopted or recorded by an older version)
+    #[serde(default)]
+    pub trigger: Option<EntryTrigger>,
 }
 
 impl DcaTrade {
-    pub fn new(order_id: u64, buy_price: f64, quantity: f64, cost: f64) -> Self {
+    pub fn new(
+        order_id: u64,
+        expected_price: f64,
+        buy_price: f64,
+        quantity: f64,
+        cost: f64,
+        fills: Vec<TradeFill>,
+        trigger: Option<EntryTrigger>,
+    ) -> Self {
         Self {
             order_id,
             buy_price,
             quantity,
             cost,
             timestamp: Utc::now(),
+            expected_price,
+            commission: Commission::from_fills(&fills),
+            fills,
+            trigger,
         }
     }
+
+    /// Fill price vs the expected price in % (positive = filled above it)
+    pub fn slippage_pct(&self) -> Option<f64> {
+        (self.expected_price > 0.0).then(|| (self.buy_price - self.expected_price) / self.expected_price * 100.0)
+    }
 }
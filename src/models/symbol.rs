@@ -0,0 +1,122 @@
+use serde_json::Value;
+
+/// Trading filters of a symbol, taken from its entry in GET /api/v3/exchangeInfo
+#[derive(Debug, Clone, Default)]
+pub struct SymbolFilters {
+    pub symbol: String,
+    pub status: String,
+    /// Assets of the pair as Binance lists them (`BTC` / `FDUSD`)
+    pub base_asset: String,
+    pub quote_asset: String,
+    /// LOT_SIZE: quantity increment
+    pub step_size: f64,
+    /// LOT_SIZE: minimum quantity
+    pub min_qty: f64,
+    /// PRICE_FILTER: price increment
+    pub tick_size: f64,
+    /// NOTIONAL / MIN_NOTIONAL: minimum order value in quote
+    pub min_notional: f64,
+    /// ICEBERG_PARTS: maximum number of visible slices of an iceberg order (0 = unknown)
+    pub max_iceberg_parts: u64,
+}
+
+impl SymbolFilters {
+    /// Parses one item of the exchangeInfo `symbols` array
+    pub fn from_exchange_info(entry: &Value) -> Self {
+        let num = |v: &Value| v.as_str().and_then(|s| s.parse().ok()).unwrap_or(0.0);
+        let mut filters = Self {
+            symbol: entry["symbol"].as_str().unwrap_or_default().to_string(),
+            status: entry["status"].as_str().unwrap_or_default().to_string(),
+            base_asset: entry["baseAsset"].as_str().unwrap_or_default().to_string(),
+            quote_asset: entry["quoteAsset"].as_str().unwrap_or_default().to_string(),
+            ..Self::default()
+        };
+        for f in entry["filters"].as_array().into_iter().flatten() {
+            match f["filterType"].as_str() {
+                Some("LOT_SIZE") => {
+                    filters.step_size = num(&f["stepSize"]);
+                    filters.min_qty = num(&f["minQty"]);
+                }
+                Some("PRICE_FILTER") => filters.tick_size = num(&f["tickSize"]),
+                Some("NOTIONAL") | Some("MIN_NOTIONAL") => filters.min_notional = num(&f["minNotional"]),
+                Some("ICEBERG_PARTS") => filters.max_iceberg_parts = f["limit"].as_u64().unwrap_or(0),
+                _ => {}
+            }
+        }
+        filters
+    }
+
+    /// Rounds a price down to the symbol's tick size
+    pub fn round_price(&self, price: f64) -> f64 {
+        round_down(price, self.tick_size)
+    }
+
+    /// Rounds a quantity down to the symbol's step size
+    pub fn round_qty(&self, quantity: f64) -> f64 {
+        round_down(quantity, self.step_size)
+    }
+
+    /// Rounds `quantity` to the step size and checks it against minQty and, when the
+    /// price is known, minNotional (orders failing these are rejected with -1013)
+    pub fn compliant_qty(&self, quantity: f64, price: Option<f64>) -> Result<f64, BelowMinimum> {
+        let qty = self.round_qty(quantity);
+        let below = |reason: String| BelowMinimum { symbol: self.symbol.clone(), quantity, reason };
+        if qty <= 0.0 || qty < self.min_qty {
+            return Err(below(format!("quantity {:.8} below LOT_SIZE minimum {}", quantity, self.min_qty)));
+        }
+        if let Some(price) = price.filter(|p| *p > 0.0) {
+            if qty * price < self.min_notional {
+                return Err(below(format!(
+                    "order value ${:.2} below minimum notional ${:.2}",
+                    qty * price,
+                    self.min_notional
+                )));
+            }
+        }
+        Ok(qty)
+    }
+
+    /// Visible quantity of an iceberg order showing `visible_pct`% of `quantity`, raised so
+    /// the order doesn't exceed ICEBERG_PARTS slices. None when the iceberg would not hide
+    /// anything (or the slice falls below LOT_SIZE).
+    pub fn iceberg_qty(&self, quantity: f64, visible_pct: f64) -> Option<f64> {
+        if visible_pct <= 0.0 || visible_pct >= 100.0 {
+            return None;
+        }
+        let mut visible = quantity * visible_pct / 100.0;
+        if self.max_iceberg_parts > 0 {
+            visible = visible.max(quantity / self.max_iceberg_parts as f64);
+        }
+        // Round up to the step size so the slice count stays within the limit
+        let visible = if self.step_size > 0.0 {
+            (visible / self.step_size - 1e-9).ceil() * self.step_size
+        } else {
+            visible
+        };
+        (visible >= self.min_qty && visible < quantity).then_some(visible)
+    }
+}
+
+/// Order rejected locally because it is below the symbol's LOT_SIZE / NOTIONAL minimum
+#[derive(Debug, Clone)]
+pub struct BelowMinimum {
+    pub symbol: String,
+    pub quantity: f64,
+    pub reason: String,
+}
+
+impl std::fmt::Display for BelowMinimum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.symbol, self.reason)
+    }
+}
+
+impl std::error::Error for BelowMinimum {}
+
+fn round_down(value: f64, step: f64) -> f64 {
+    if step <= 0.0 {
+        return value;
+    }
+    // El epsilon evita que 0.3 / 0.1 = 2.9999... baje un paso entero
+    (value / step + 1e-9).floor() * step
+}
@@ -7,13 +7,53 @@ pub struct TickerPrice {
     pub price: String,
 }
 
+/// Response item from GET /api/v3/ticker/24hr (batched with `symbols=[...]`)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Ticker24h {
+    pub symbol: String,
+    pub last_price: String,
+    pub price_change_percent: String,
+    pub high_price: String,
+    pub low_price: String,
+}
+
+impl Ticker24h {
+    pub fn last_f64(&self) -> f64 {
+        self.last_price.parse().unwrap_or(0.0)
+    }
+}
+
+/// Response from GET /api/v3/ticker/bookTicker
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BookTicker {
+    pub bid_price: String,
+    pub ask_price: String,
+}
+
+impl BookTicker {
+    pub fn bid_f64(&self) -> f64 {
+        self.bid_price.parse().unwrap_or(0.0)
+    }
+
+    pub fn ask_f64(&self) -> f64 {
+        self.ask_price.parse().unwrap_or(0.0)
+    }
+
+    /// Spread as % of the mid price (None for an empty or crossed book)
+    pub fn spread_pct(&self) -> Option<f64> {
+        let (bid, ask) = (self.bid_f64(), self.ask_f64());
+        if bid <= 0.0 || ask < bid {
+            return None;
+        }
+        Some((ask - bid) / ((bid + ask) / 2.0) * 100.0)
+    }
+}
+
 /// Event from WebSocket stream @miniTicker
 #[derive(Debug, Deserialize, Clone)]
 pub struct MiniTickerEvent {
-    #[serde(rename = "e")]
-    pub event_type: String,
-    #[serde(rename = "E")]
-    pub event_time: u64,
     #[serde(rename = "s")]
     pub symbol: String,
     /// Closing price (last price)
@@ -28,28 +68,109 @@ pub struct MiniTickerEvent {
     /// Precio mínimo (24h)
     #[serde(rename = "l")]
     pub low_price: String,
-    /// Base volume (24h)
-    #[serde(rename = "v")]
-    pub base_volume: String,
-    /// Quote volume (24h)
+}
+
+/// Event from WebSocket stream @depth5@1000ms: the top 5 levels of the book as
+/// [price, qty] pairs, best first. The payload has no symbol; it comes from the stream name.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PartialDepth {
+    #[serde(skip)]
+    pub symbol: String,
+    pub bids: Vec<[String; 2]>,
+    pub asks: Vec<[String; 2]>,
+}
+
+impl PartialDepth {
+    pub fn best_bid(&self) -> f64 {
+        self.bids.first().and_then(|l| l[0].parse().ok()).unwrap_or(0.0)
+    }
+
+    pub fn best_ask(&self) -> f64 {
+        self.asks.first().and_then(|l| l[0].parse().ok()).unwrap_or(0.0)
+    }
+}
+
+/// Event from WebSocket stream @aggTrade (trades aggregated by taker order and price)
+#[derive(Debug, Deserialize, Clone)]
+pub struct AggTradeEvent {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "p")]
+    pub price: String,
     #[serde(rename = "q")]
-    pub quote_volume: String,
+    pub quantity: String,
+    /// Trade time in milliseconds
+    #[serde(rename = "T")]
+    pub trade_time: i64,
+    /// True when the buyer was the maker, i.e. the taker sold
+    #[serde(rename = "m")]
+    pub buyer_is_maker: bool,
+}
+
+impl AggTradeEvent {
+    /// Traded value in quote asset (price × quantity)
+    pub fn quote_qty(&self) -> f64 {
+        let price: f64 = self.price.parse().unwrap_or(0.0);
+        let qty: f64 = self.quantity.parse().unwrap_or(0.0);
+        price * qty
+    }
 }
 
 /// Binance combined stream wrapper (multi-symbol)
-/// Formato: {"stream":"btcusdt@miniTicker","data":{...MiniTickerEvent...}}
+/// Formato: {"stream":"btcusdt@miniTicker","data":{...}}; `data` depends on the stream
 #[derive(Debug, Deserialize, Clone)]
 pub struct CombinedStreamWrapper {
     pub stream: String,
-    pub data: MiniTickerEvent,
+    pub data: serde_json::Value,
+}
+
+/// Event from WebSocket stream @kline_<interval>
+#[derive(Debug, Deserialize, Clone)]
+pub struct KlineEvent {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "k")]
+    pub kline: StreamKline,
+}
+
+/// Candle inside a @kline event (updated every ~2s until `closed`)
+#[derive(Debug, Deserialize, Clone)]
+pub struct StreamKline {
+    #[serde(rename = "t")]
+    pub open_time: i64,
+    #[serde(rename = "i")]
+    pub interval: String,
+    #[serde(rename = "h")]
+    pub high: String,
+    #[serde(rename = "l")]
+    pub low: String,
+    #[serde(rename = "c")]
+    pub close: String,
+    /// True on the final update of the candle
+    #[serde(rename = "x")]
+    pub closed: bool,
+}
+
+impl KlineEvent {
+    pub fn to_kline(&self) -> Kline {
+        Kline {
+            open_time: self.kline.open_time,
+            high: self.kline.high.parse().unwrap_or(0.0),
+            low: self.kline.low.parse().unwrap_or(0.0),
+            close: self.kline.close.parse().unwrap_or(0.0),
+        }
+    }
 }
 
-/// An OHLC candle (result of GET /api/v3/klines)
-/// Only high and low are extracted, which are needed for S/R
+/// An OHLC candle (GET /api/v3/klines or the @kline stream)
+/// Only high, low (S/R) and close (ATR) are extracted
 #[derive(Debug, Clone)]
 pub struct Kline {
+    /// Candle open time in milliseconds
+    pub open_time: i64,
     pub high: f64,
     pub low: f64,
+    pub close: f64,
 }
 
 impl MiniTickerEvent {
@@ -0,0 +1,376 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+
+use crate::api::client::{BinanceClient, UncertainOrder};
+use crate::models::order::{EntryTrigger, OpenOrder, Order, OrderSide, OrderStatus};
+use crate::models::symbol::SymbolFilters;
+
+/// Seconds between GET /api/v3/openOrders polls
+pub const POLL_SECS: i64 = 5;
+/// Attempts to re-place a LIMIT_MAKER rejected for crossing the book
+const MAKER_PLACE_ATTEMPTS: u32 = 3;
+/// Each replace after a timeout keeps this fraction of the previous offset
+const CHASE_FACTOR: f64 = 0.5;
+/// Replaces after which the order is placed at the reference price itself
+const MAX_CHASES: u32 = 4;
+/// Wait before placing again after a LIMIT_MAKER kept crossing the book; doubles with each
+/// rejection in a row up to `MAKER_RETRY_MAX_SECS`
+const MAKER_RETRY_SECS: i64 = 5;
+const MAKER_RETRY_MAX_SECS: i64 = 300;
+
+/// Wait before the next placement after `rejections` LIMIT_MAKER rejections in a row
+pub fn maker_retry_delay(rejections: u32) -> chrono::Duration {
+    let doublings = rejections.saturating_sub(1).min(16);
+    chrono::Duration::seconds((MAKER_RETRY_SECS << doublings).min(MAKER_RETRY_MAX_SECS))
+}
+
+/// Entry order requested by the strategy engine
+#[derive(Debug, Clone)]
+pub struct EntryIntent {
+    pub symbol: String,
+    pub side: OrderSide,
+    /// Quote amount to spend (buy) or to raise (sell)
+    pub amount: f64,
+    /// LIMIT_MAKER measured from the own side of the book instead of a GTC LIMIT
+    pub post_only: bool,
+    /// Distance from the reference price on the first placement
+    pub offset_pct: f64,
+    /// Visible share of a sell order (0 = plain order)
+    pub iceberg_visible_pct: f64,
+    /// Seconds an order may rest unfilled before it is cancelled and re-placed
+    pub timeout_secs: u64,
+    /// Entries in the position when requested (fewer later = the cycle was closed)
+    pub entries_before: usize,
+    /// Condition that made the strategy enter, recorded with the fill
+    pub trigger: Option<EntryTrigger>,
+}
+
+impl EntryIntent {
+    /// Offset for the placement after `replaces` timeouts: halved each time, and at the
+    /// reference price once `MAX_CHASES` is reached
+    pub fn offset_pct(&self, replaces: u32) -> f64 {
+        if replaces >= MAX_CHASES {
+            0.0
+        } else {
+            self.offset_pct * CHASE_FACTOR.powi(replaces as i32)
+        }
+    }
+
+    /// Visible quantity for an iceberg order (sell entries only)
+    pub fn iceberg_qty(&self, filters: &SymbolFilters, quantity: f64) -> Option<f64> {
+        match self.side {
+            OrderSide::Sell => filters.iceberg_qty(quantity, self.iceberg_visible_pct),
+            OrderSide::Buy => None,
+        }
+    }
+
+    fn limit_price(&self, filters: &SymbolFilters, reference: f64, replaces: u32) -> f64 {
+        let offset = self.offset_pct(replaces) / 100.0;
+        match self.side {
+            OrderSide::Buy => filters.round_price(reference * (1.0 - offset)),
+            OrderSide::Sell => filters.round_price(reference * (1.0 + offset)),
+        }
+    }
+}
+
+/// Entry order owned by the manager
+#[derive(Debug, Clone)]
+pub struct ManagedOrder {
+    pub slot_id: usize,
+    pub intent: EntryIntent,
+    pub order: OpenOrder,
+}
+
+impl ManagedOrder {
+    pub fn expired(&self, now: DateTime<Utc>) -> bool {
+        (now - self.order.placed_at).num_seconds() >= self.intent.timeout_secs as i64
+    }
+}
+
+/// Result of checking one managed order against Binance
+#[derive(Debug)]
+pub enum OrderUpdate {
+    /// Still on the book within its deadline
+    Resting,
+    /// Off the book (filled, cancelled or expired). `timed_out` is set when the manager
+    /// cancelled it for exceeding its deadline.
+    Closed { status: Order, timed_out: bool },
+}
+
+/// Open entry orders, at most one per slot
+#[derive(Debug, Default)]
+pub struct OrderManager {
+    orders: BTreeMap<usize, ManagedOrder>,
+    last_poll: Option<DateTime<Utc>>,
+}
+
+impl OrderManager {
+    pub fn get(&self, slot_id: usize) -> Option<&ManagedOrder> {
+        self.orders.get(&slot_id)
+    }
+
+    pub fn has_order(&self, slot_id: usize) -> bool {
+        self.orders.contains_key(&slot_id)
+    }
+
+    /// Starts tracking an order, replacing any previous one of the same slot
+    pub fn track(&mut self, managed: ManagedOrder) {
+        self.orders.insert(managed.slot_id, managed);
+    }
+
+    pub fn untrack(&mut self, slot_id: usize) -> Option<ManagedOrder> {
+        self.orders.remove(&slot_id)
+    }
+
+    pub fn untrack_all(&mut self) -> Vec<ManagedOrder> {
+        std::mem::take(&mut self.orders).into_values().collect()
+    }
+
+    /// Orders to check now; empty until `POLL_SECS` have passed since the last poll
+    pub fn due(&mut self, now: DateTime<Utc>) -> Vec<ManagedOrder> {
+        if self.orders.is_empty()
+            || self.last_poll.is_some_and(|t| (now - t).num_seconds() < POLL_SECS)
+        {
+            return Vec::new();
+        }
+        self.last_poll = Some(now);
+        self.orders.values().cloned().collect()
+    }
+}
+
+/// Places the order for `intent` after `replaces` timeouts. A LIMIT is measured from
+/// `last_price`; a LIMIT_MAKER from the best bid/ask, re-priced with the current book when
+/// Binance rejects it for crossing. Returns None when the quantity rounds to zero.
+pub async fn place(
+    client: &BinanceClient,
+    intent: &EntryIntent,
+    filters: &SymbolFilters,
+    last_price: f64,
+    replaces: u32,
+) -> Result<Option<OpenOrder>> {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let reference = if intent.post_only {
+            match client.get_book_ticker(&intent.symbol).await {
+                Ok(book) => match intent.side {
+                    OrderSide::Buy => book.bid_f64(),
+                    OrderSide::Sell => book.ask_f64(),
+                },
+                Err(e) => {
+                    tracing::warn!("Book ticker [{}] failed, using last price: {}", intent.symbol, e);
+                    last_price
+                }
+            }
+        } else {
+            last_price
+        };
+        let price = intent.limit_price(filters, reference, replaces);
+        let qty = if price > 0.0 { filters.round_qty(intent.amount / price) } else { 0.0 };
+        if qty <= 0.0 {
+            return Ok(None);
+        }
+        let iceberg = intent.iceberg_qty(filters, qty);
+
+        let result = match (&intent.side, intent.post_only) {
+            (OrderSide::Buy, false) => client.limit_buy(&intent.symbol, qty, price, iceberg).await,
+            (OrderSide::Sell, false) => client.limit_sell(&intent.symbol, qty, price, iceberg).await,
+            (OrderSide::Buy, true) => client.limit_maker_buy(&intent.symbol, qty, price, iceberg).await,
+            (OrderSide::Sell, true) => client.limit_maker_sell(&intent.symbol, qty, price, iceberg).await,
+        };
+        match result {
+            Err(e) if intent.post_only && BinanceClient::would_take(&e) && attempt < MAKER_PLACE_ATTEMPTS => {
+                tracing::info!("LIMIT_MAKER [{}] @ {} would cross the book, repositioning", intent.symbol, price);
+            }
+            Err(e) => return Err(e),
+            Ok(order) => {
+                return Ok(Some(OpenOrder {
+                    order_id: order.order_id,
+                    side: intent.side.clone(),
+                    price,
+                    quantity: qty,
+                    placed_at: Utc::now(),
+                    post_only: intent.post_only,
+                    entries_before: intent.entries_before,
+                    replaces,
+                    trigger: intent.trigger,
+                }))
+            }
+        }
+    }
+}
+
+/// Market entry for `amount` quote: a buy spending it, or a sell of the quantity it is
+/// worth at `price` (SHORT entries)
+pub async fn market_entry(
+    client: &BinanceClient,
+    symbol: &str,
+    side: OrderSide,
+    amount: f64,
+    price: f64,
+) -> Result<Order> {
+    match side {
+        OrderSide::Buy => client.market_buy_quote(symbol, amount).await,
+        OrderSide::Sell => {
+            anyhow::ensure!(price > 0.0, "no price to size the sell of ${:.2}", amount);
+            client.market_sell_qty(symbol, amount / price).await
+        }
+    }
+}
+
+/// Exit that got no answer and was found later expired or cancelled with only part of its
+/// quantity executed: what filled is in `order`, the rest of the position is still open
+#[derive(Debug)]
+pub struct PartialExit {
+    pub order: Order,
+}
+
+impl PartialExit {
+    /// Base quantity the exit executed
+    pub fn executed_qty(&self) -> f64 {
+        self.order.executed_qty.parse().unwrap_or(0.0)
+    }
+}
+
+impl std::fmt::Display for PartialExit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "exit {} filled {} of {} before it {:?}",
+            self.order.client_order_id, self.order.executed_qty, self.order.orig_qty, self.order.status
+        )
+    }
+}
+
+impl std::error::Error for PartialExit {}
+
+/// Market order closing `quantity` of a position. `unsettled` is the client order ID of an
+/// earlier attempt that got no answer: it is looked up first and, when it filled, returned
+/// instead of sending another order; when it executed only in part the result is a
+/// `PartialExit` error. A new order is only sent once Binance confirms it never had that
+/// one.
+pub async fn market_exit(
+    client: &BinanceClient,
+    symbol: &str,
+    side: OrderSide,
+    quantity: f64,
+    unsettled: Option<&str>,
+) -> Result<Order> {
+    if let Some(client_order_id) = unsettled {
+        let found = client
+            .get_order_by_client_id(symbol, client_order_id)
+            .await
+            // Still unknown: keep the ID so the next attempt looks it up again
+            .map_err(|e| UncertainOrder { client_order_id: client_order_id.to_string(), source: e })?;
+        match found {
+            Some(order) if order.status == OrderStatus::Filled => return Ok(order),
+            Some(order) if is_final(&order.status) && order.executed_qty.parse::<f64>().unwrap_or(0.0) > 0.0 => {
+                let partial = PartialExit { order };
+                tracing::warn!("Exit [{}]: {}", symbol, partial);
+                return Err(partial.into());
+            }
+            Some(order) if !is_final(&order.status) => {
+                return Err(UncertainOrder {
+                    client_order_id: client_order_id.to_string(),
+                    source: anyhow!("exit order still {:?}", order.status),
+                }
+                .into());
+            }
+            _ => tracing::info!("Exit {} [{}] never executed, sending it again", client_order_id, symbol),
+        }
+    }
+    match side {
+        OrderSide::Sell => client.market_sell_qty(symbol, quantity).await,
+        OrderSide::Buy => client.market_buy_qty(symbol, quantity).await,
+    }
+}
+
+/// Checks managed orders with one GET /api/v3/openOrders per symbol. Orders past their
+/// deadline, or whose slot is in `abandon`, are cancelled; orders no longer on the book get
+/// their final status from GET /api/v3/order. Orders whose status could not be read are
+/// left out and checked again on the next poll.
+pub async fn sync(
+    client: &BinanceClient,
+    orders: &[ManagedOrder],
+    abandon: &[usize],
+    now: DateTime<Utc>,
+) -> Vec<(usize, Result<OrderUpdate>)> {
+    let mut symbols: Vec<&str> = orders.iter().map(|m| m.intent.symbol.as_str()).collect();
+    symbols.sort_unstable();
+    symbols.dedup();
+
+    let mut updates = Vec::new();
+    for symbol in symbols {
+        let open = match client.open_orders(symbol).await {
+            Ok(open) => open,
+            Err(e) => {
+                tracing::warn!("Open orders [{}] check failed: {}", symbol, e);
+                continue;
+            }
+        };
+        for managed in orders.iter().filter(|m| m.intent.symbol == symbol) {
+            let order_id = managed.order.order_id;
+            let timed_out = managed.expired(now);
+            if open.iter().any(|o| o.order_id == order_id) {
+                if timed_out || abandon.contains(&managed.slot_id) {
+                    let update = cancel(client, managed)
+                        .await
+                        .map(|status| OrderUpdate::Closed { status, timed_out });
+                    updates.push((managed.slot_id, update));
+                } else {
+                    updates.push((managed.slot_id, Ok(OrderUpdate::Resting)));
+                }
+                continue;
+            }
+            match client.get_order(symbol, order_id).await {
+                Ok(status) if is_final(&status.status) => {
+                    updates.push((managed.slot_id, Ok(OrderUpdate::Closed { status, timed_out: false })));
+                }
+                // Just placed and not listed yet
+                Ok(_) => updates.push((managed.slot_id, Ok(OrderUpdate::Resting))),
+                Err(e) => tracing::warn!("Limit order {} [{}] status check failed: {}", order_id, symbol, e),
+            }
+        }
+    }
+    updates
+}
+
+/// Cancels a managed order and returns its final status (with whatever was filled)
+async fn cancel(client: &BinanceClient, managed: &ManagedOrder) -> Result<Order> {
+    let symbol = &managed.intent.symbol;
+    let order_id = managed.order.order_id;
+    client
+        .cancel_order(symbol, order_id)
+        .await
+        .with_context(|| format!("Cancel limit order {} [{}] failed", order_id, symbol))?;
+    client
+        .get_order(symbol, order_id)
+        .await
+        .with_context(|| format!("Limit order {} [{}] status check failed", order_id, symbol))
+}
+
+/// Cancels an order the bot stops managing (slot deleted, reduce-only, session discarded)
+/// and returns its final status, so whatever filled before the cancel can be recorded. An
+/// order that already left the book (filled meanwhile) makes the cancel fail; its status is
+/// returned all the same.
+pub async fn cancel_untracked(client: &BinanceClient, managed: &ManagedOrder) -> Result<Order> {
+    let symbol = &managed.intent.symbol;
+    let order_id = managed.order.order_id;
+    let cancelled = client.cancel_order(symbol, order_id).await;
+    match (cancelled, client.get_order(symbol, order_id).await) {
+        (_, Ok(status)) if is_final(&status.status) => Ok(status),
+        (Err(e), _) => Err(e.context(format!("Cancel limit order {} [{}] failed", order_id, symbol))),
+        (Ok(_), status) => {
+            status.with_context(|| format!("Limit order {} [{}] status check failed", order_id, symbol))
+        }
+    }
+}
+
+fn is_final(status: &OrderStatus) -> bool {
+    matches!(
+        status,
+        OrderStatus::Filled | OrderStatus::Canceled | OrderStatus::Expired | OrderStatus::Rejected
+    )
+}
@@ -0,0 +1,111 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::config::StrategyKind;
+use crate::models::order::{Commission, DcaTrade};
+
+/// A LONG position bought outside the bot, handed to a new slot that manages its exit.
+///
+/// ```json
+/// {
+///   "symbol": "BTCUSDT",
+///   "kind": "trading",
+///   "entries": [
+///     { "price": 61000, "quantity": 0.03 },
+///     { "price": 58500, "quantity": 0.02, "cost": 1171.2, "timestamp": "2026-09-30T14:00:00Z" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PositionImport {
+    pub symbol: String,
+    /// `trading` keeps placing safety orders from [dca]; `watch` only manages the exit
+    #[serde(default)]
+    pub kind: StrategyKind,
+    pub entries: Vec<ImportedEntry>,
+}
+
+/// One manual buy of the imported position
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImportedEntry {
+    /// Average fill price
+    pub price: f64,
+    /// Base quantity bought
+    pub quantity: f64,
+    /// Quote spent, fees included when paid in the quote (default: price × quantity)
+    #[serde(default)]
+    pub cost: Option<f64>,
+    /// When it was bought (default: the moment of the import)
+    #[serde(default)]
+    pub timestamp: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub commission: Option<Commission>,
+}
+
+impl PositionImport {
+    /// Reads and checks a `PositionImport` JSON file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).with_context(|| format!("Could not read {:?}", path))?;
+        let mut import: Self = serde_json::from_str(&content).with_context(|| format!("Invalid {:?}", path))?;
+        import.symbol = import.symbol.to_uppercase();
+        import.validate()?;
+        Ok(import)
+    }
+
+    /// Rejects what the slot could not manage: no entries, non-positive figures or a kind
+    /// other than trading / watch
+    pub fn validate(&self) -> Result<()> {
+        if !matches!(self.kind, StrategyKind::Trading | StrategyKind::Watch) {
+            bail!("kind must be trading or watch");
+        }
+        if self.entries.is_empty() {
+            bail!("at least one entry is needed");
+        }
+        for (n, entry) in self.entries.iter().enumerate() {
+            if entry.price <= 0.0 || entry.quantity <= 0.0 || entry.cost.is_some_and(|c| c <= 0.0) {
+                bail!("entry {}: price, quantity and cost must be greater than 0", n + 1);
+            }
+        }
+        Ok(())
+    }
+
+    /// Base quantity of every entry
+    pub fn quantity(&self) -> f64 {
+        self.entries.iter().map(|e| e.quantity).sum()
+    }
+
+    /// Quote spent on every entry
+    pub fn cost(&self) -> f64 {
+        self.entries.iter().map(ImportedEntry::cost).sum()
+    }
+
+    /// Entries as slot trades, oldest first. They carry no order ID, so they are never
+    /// merged or matched against Binance orders.
+    pub fn trades(&self) -> Vec<DcaTrade> {
+        let mut trades: Vec<DcaTrade> = self
+            .entries
+            .iter()
+            .map(|e| {
+                let mut trade = DcaTrade::new(0, e.price, e.price, e.quantity, e.cost(), Vec::new(), None);
+                if let Some(timestamp) = e.timestamp {
+                    trade.timestamp = timestamp;
+                }
+                trade.commission = e.commission.clone();
+                trade
+            })
+            .collect();
+        trades.sort_by_key(|t| t.timestamp);
+        trades
+    }
+}
+
+impl ImportedEntry {
+    pub fn cost(&self) -> f64 {
+        self.cost.unwrap_or(self.price * self.quantity)
+    }
+}
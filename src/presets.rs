@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::DcaConfig;
+
+/// Longest preset name accepted
+pub const MAX_NAME_LEN: usize = 32;
+
+/// Config fields never stored in a preset: they belong to the slot, not to the strategy
+const SLOT_FIELDS: [&str; 2] = ["symbol", "reduce_only"];
+
+/// A named set of `[dca]` fields. Fields left out of a hand-written preset keep the value
+/// of `[dca]` in config.toml.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    #[serde(flatten)]
+    pub fields: BTreeMap<String, toml::Value>,
+}
+
+impl Preset {
+    /// Preset with every field of `config` except the slot-only ones
+    pub fn from_config(name: &str, config: &DcaConfig) -> Result<Self> {
+        let json = serde_json::to_value(config)?;
+        let fields = json
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(field, _)| !SLOT_FIELDS.contains(&field.as_str()))
+            .filter_map(|(field, value)| Some((field, without_nulls(value.clone())?)))
+            .map(|(field, value)| {
+                let value = toml::Value::try_from(value).with_context(|| format!("field {} has no TOML form", field))?;
+                Ok((field.clone(), value))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { name: name.to_string(), fields })
+    }
+
+    /// `base` with the preset fields applied, plus those fields as JSON values (they are
+    /// recorded as config overrides of the slot created from it)
+    pub fn apply(&self, base: &DcaConfig) -> Result<(DcaConfig, Vec<(String, serde_json::Value)>)> {
+        base.with_fields(self.fields.iter().filter(|(field, _)| !SLOT_FIELDS.contains(&field.as_str())))
+            .with_context(|| format!("preset '{}'", self.name))
+    }
+}
+
+/// Drops nulls (unset options) recursively: TOML has no null, a missing key means unset
+fn without_nulls(value: serde_json::Value) -> Option<serde_json::Value> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::Array(items) => Some(items.into_iter().filter_map(without_nulls).collect()),
+        serde_json::Value::Object(map) => {
+            Some(map.into_iter().filter_map(|(k, v)| Some((k, without_nulls(v)?))).collect())
+        }
+        other => Some(other),
+    }
+}
+
+/// Layout of presets.toml: one `[[preset]]` table per preset
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PresetFile {
+    #[serde(default)]
+    preset: Vec<Preset>,
+}
+
+/// Presets saved in presets.toml next to config.toml, in the order they were added
+#[derive(Debug, Default)]
+pub struct PresetBook {
+    presets: Vec<Preset>,
+    path: Option<PathBuf>,
+}
+
+impl PresetBook {
+    /// Loads the presets saved at `path` (none when the file does not exist yet)
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let presets = match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str::<PresetFile>(&content)
+                .with_context(|| format!("Invalid presets file {:?}", path))?
+                .preset,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("Could not read {:?}", path)),
+        };
+        Ok(Self { presets, path: Some(path) })
+    }
+
+    pub fn presets(&self) -> &[Preset] {
+        &self.presets
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Preset> {
+        self.presets.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Preset> {
+        self.presets.get_mut(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.presets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.presets.is_empty()
+    }
+
+    /// Index of the preset called `name` (case-insensitive)
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.presets.iter().position(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Adds a preset or replaces the one with the same name; returns its index
+    pub fn upsert(&mut self, preset: Preset) -> usize {
+        match self.position(&preset.name) {
+            Some(index) => {
+                self.presets[index] = preset;
+                index
+            }
+            None => {
+                self.presets.push(preset);
+                self.presets.len() - 1
+            }
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Preset> {
+        (index < self.presets.len()).then(|| self.presets.remove(index))
+    }
+
+    /// File and contents to save, written by the state writer away from the app state lock
+    /// (None for a book without a file)
+    pub fn contents(&self) -> Result<Option<(PathBuf, String)>> {
+        let Some(path) = &self.path else { return Ok(None) };
+        Ok(Some((path.clone(), toml::to_string_pretty(&PresetFile { preset: self.presets.clone() })?)))
+    }
+}
+
+/// Checks a name typed for a preset: not empty, not too long
+pub fn validate_name(name: &str) -> Result<(), String> {
+    match name.trim() {
+        "" => Err("the name cannot be empty".to_string()),
+        n if n.chars().count() > MAX_NAME_LEN => Err(format!("the name is longer than {} characters", MAX_NAME_LEN)),
+        _ => Ok(()),
+    }
+}
+
+/// Parses a value typed in the presets screen as TOML (`1.5`, `true`, `"08:00-22:00"`,
+/// `[[1.0, 0.5], [2.0, 1.0]]`)
+pub fn parse_value(text: &str) -> Result<toml::Value, String> {
+    let table: toml::Table = format!("v = {}", text.trim()).parse().map_err(|_| format!("'{}' is not a TOML value", text.trim()))?;
+    table.get("v").cloned().ok_or_else(|| "empty value".to_string())
+}
@@ -0,0 +1,52 @@
+//! Timing spans for the `profiling` feature.
+//!
+//! Built with `--features profiling`, lock waits, slot evaluation, rendering and REST
+//! calls open `tracing` spans: their busy/idle times are logged when they close and a
+//! folded stack file (`tradingbot.folded`, for inferno or flamegraph.pl) is written next
+//! to the executable. Without the feature the spans are compiled out.
+
+use std::fs::File;
+use std::path::Path;
+
+use anyhow::Result;
+use tokio::sync::{Mutex, MutexGuard};
+
+/// Keeps the flame graph writer alive; its buffer is flushed when dropped on exit
+pub struct Guard {
+    #[cfg(feature = "profiling")]
+    _flame: tracing_flame::FlushGuard<std::io::BufWriter<File>>,
+}
+
+/// Sends the log to `log_file`. With `profiling` it also logs span timings and records
+/// the folded stacks in `dir`.
+#[cfg(feature = "profiling")]
+pub fn init(log_file: File, dir: &Path) -> Result<Guard> {
+    use tracing_subscriber::fmt::format::FmtSpan;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    let fmt = tracing_subscriber::fmt::layer()
+        .with_writer(log_file)
+        .with_ansi(false)
+        .with_span_events(FmtSpan::CLOSE);
+    let (flame, guard) = tracing_flame::FlameLayer::with_file(dir.join("tradingbot.folded"))?;
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::filter::LevelFilter::INFO)
+        .with(fmt)
+        .with(flame)
+        .init();
+    Ok(Guard { _flame: guard })
+}
+
+#[cfg(not(feature = "profiling"))]
+pub fn init(log_file: File, _dir: &Path) -> Result<Guard> {
+    tracing_subscriber::fmt().with_writer(log_file).with_ansi(false).init();
+    Ok(Guard {})
+}
+
+/// Locks `mutex`; with `profiling` the wait shows up as a `lock` span tagged with `site`
+#[cfg_attr(feature = "profiling", tracing::instrument(name = "lock", skip(mutex)))]
+#[cfg_attr(not(feature = "profiling"), allow(unused_variables))]
+pub async fn lock<'a, T>(mutex: &'a Mutex<T>, site: &'static str) -> MutexGuard<'a, T> {
+    mutex.lock().await
+}
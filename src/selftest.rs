@@ -0,0 +1,283 @@
+use std::collections::BTreeSet;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::api::client::{self, BinanceClient};
+use crate::api::websocket::MAINNET_WS;
+use crate::config::Config;
+use crate::state_file;
+use crate::strategy::dca::{DcaStrategy, StrategySnapshot};
+use crate::strategy::events::EventLog;
+use crate::ui::tui::{COMPACT_WIDTH, MIN_SIZE, WIDE_HEIGHT};
+
+/// Example of the Binance API docs (SIGNED endpoints): secret, query string and the
+/// signature it must produce
+const SIGNING_SECRET: &str = "NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j";
+const SIGNING_PAYLOAD: &str =
+    "symbol=LTCBTC&side=BUY&type=LIMIT&timeInForce=GTC&quantity=1&price=0.1&recvWindow=5000&timestamp=1499827319559";
+const SIGNING_EXPECTED: &str = "c8db56825ae71d6d79447849e617115f4a920fa2acdcab2b053c4b2838bd6b71";
+/// WebSocket handshake timeout per endpoint
+const WS_TIMEOUT: Duration = Duration::from_secs(10);
+/// Clock offset worth a warning: it is corrected on every request, but a drifting clock
+/// ends in -1021 errors
+const MAX_CLOCK_OFFSET_MS: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Pass,
+    Warn,
+    Fail,
+    /// Not run because a previous check failed
+    Skip,
+}
+
+impl Outcome {
+    fn label(&self) -> &'static str {
+        match self {
+            Outcome::Pass => "PASS",
+            Outcome::Warn => "WARN",
+            Outcome::Fail => "FAIL",
+            Outcome::Skip => "SKIP",
+        }
+    }
+}
+
+/// Outcomes printed as they come, so slow network checks show progress
+#[derive(Default)]
+struct Report {
+    outcomes: Vec<Outcome>,
+}
+
+impl Report {
+    fn add(&mut self, name: &str, outcome: Outcome, detail: impl std::fmt::Display) {
+        println!("  {}  {:<10} {}", outcome.label(), name, detail);
+        self.outcomes.push(outcome);
+    }
+
+    fn count(&self, outcome: Outcome) -> usize {
+        self.outcomes.iter().filter(|o| **o == outcome).count()
+    }
+}
+
+/// Checks everything the bot needs before it trades: config, request signing, Binance
+/// endpoints and API key, filters of the configured symbols, state file round-trip and the
+/// terminal. Prints one line per check; fails when any check failed.
+pub async fn run(state_path: &Path, events_path: &Path) -> Result<String> {
+    println!("Trading View self-test");
+    let mut report = Report::default();
+
+    let config = match Config::load() {
+        Ok((config, path)) => {
+            report.add(
+                "config",
+                Outcome::Pass,
+                format!(
+                    "{:?} ({}, {})",
+                    path,
+                    if config.binance.testnet { "testnet" } else { "mainnet" },
+                    config.dca.symbol
+                ),
+            );
+            Some(config)
+        }
+        Err(e) => {
+            report.add("config", Outcome::Fail, format!("{:#}", e));
+            None
+        }
+    };
+
+    if client::signature(SIGNING_SECRET, SIGNING_PAYLOAD) == SIGNING_EXPECTED {
+        report.add("signing", Outcome::Pass, "HMAC-SHA256 matches the Binance reference vector");
+    } else {
+        report.add("signing", Outcome::Fail, "HMAC-SHA256 does not match the Binance reference vector");
+    }
+
+    let snapshots = check_state(&mut report, state_path, events_path, config.as_ref());
+
+    match &config {
+        Some(config) => check_binance(&mut report, config, &snapshots).await,
+        None => {
+            for name in ["rest", "clock", "api key", "filters"] {
+                report.add(name, Outcome::Skip, "config not loaded");
+            }
+        }
+    }
+    check_websocket(&mut report).await;
+    check_terminal(&mut report);
+
+    let (passed, warned, failed) = (report.count(Outcome::Pass), report.count(Outcome::Warn), report.count(Outcome::Fail));
+    if failed > 0 {
+        anyhow::bail!("Self-test failed: {} failed, {} warning(s), {} passed", failed, warned, passed);
+    }
+    Ok(format!("Self-test passed: {} passed, {} warning(s)", passed, warned))
+}
+
+/// Reads the state file, checks that serialize → parse → serialize gives the same JSON
+/// and returns its snapshots (a fresh one from the config when there is no file yet)
+fn check_state(
+    report: &mut Report,
+    state_path: &Path,
+    events_path: &Path,
+    config: Option<&Config>,
+) -> Vec<StrategySnapshot> {
+    let (snapshots, source) = match state_path.try_exists() {
+        // Same loader as at startup, checksum included
+        Ok(true) => match state_file::load(state_path) {
+            Ok(snaps) => {
+                let source = format!("{} slot(s)", snaps.len());
+                (snaps, source)
+            }
+            Err(e) => {
+                report.add("state", Outcome::Fail, format!("{:?} unreadable: {}", state_path, e));
+                return Vec::new();
+            }
+        },
+        Ok(false) => match config {
+            Some(config) => (
+                vec![DcaStrategy::new(config.dca.clone()).to_snapshot(&config.dca.symbol)],
+                "no state file yet, fresh snapshot".to_string(),
+            ),
+            None => {
+                report.add("state", Outcome::Skip, "no state file and no config");
+                return Vec::new();
+            }
+        },
+        Err(e) => {
+            report.add("state", Outcome::Fail, format!("Could not read {:?}: {}", state_path, e));
+            return Vec::new();
+        }
+    };
+
+    let round_trip = serde_json::to_string(&snapshots).and_then(|first| {
+        let again: Vec<StrategySnapshot> = serde_json::from_str(&first)?;
+        Ok(first == serde_json::to_string(&again)?)
+    });
+    let events = EventLog::load(events_path).len();
+    match round_trip {
+        Ok(true) => report.add("state", Outcome::Pass, format!("{}, {} event(s), round-trip identical", source, events)),
+        Ok(false) => report.add("state", Outcome::Fail, format!("{}: round-trip changed the snapshot", source)),
+        Err(e) => report.add("state", Outcome::Fail, format!("{}: round-trip failed: {}", source, e)),
+    }
+    snapshots
+}
+
+/// REST reachability, clock offset, API key permissions and the filters of every
+/// configured symbol (the config's plus the ones in the state file)
+async fn check_binance(report: &mut Report, config: &Config, snapshots: &[StrategySnapshot]) {
+    let network = if config.binance.testnet { "testnet" } else { "mainnet" };
+    let client = match BinanceClient::new(config.binance.clone()) {
+        Ok(client) => client,
+        Err(e) => {
+            report.add("rest", Outcome::Fail, format!("client error: {:#}", e));
+            return;
+        }
+    };
+
+    let start = Instant::now();
+    if let Err(e) = client.ping().await {
+        report.add("rest", Outcome::Fail, format!("{} unreachable: {:#}", network, e));
+        for name in ["clock", "api key", "filters"] {
+            report.add(name, Outcome::Skip, "REST API unreachable");
+        }
+        return;
+    }
+    report.add("rest", Outcome::Pass, format!("{} ping {:.0}ms", network, start.elapsed().as_secs_f64() * 1000.0));
+
+    match client.sync_time().await {
+        Ok(()) if client.time_offset_ms().abs() > MAX_CLOCK_OFFSET_MS => report.add(
+            "clock",
+            Outcome::Warn,
+            format!("local clock {}ms off Binance (corrected, but sync the system clock)", client.time_offset_ms()),
+        ),
+        Ok(()) => report.add("clock", Outcome::Pass, format!("offset {}ms", client.time_offset_ms())),
+        Err(e) => report.add("clock", Outcome::Fail, format!("{:#}", e)),
+    }
+
+    match client.get_account().await {
+        Ok(account) if !account.can_trade => report.add("api key", Outcome::Fail, "accepted, but trading is disabled for it"),
+        Ok(account) if account.can_withdraw => report.add(
+            "api key",
+            Outcome::Warn,
+            "accepted, but it can withdraw: restrict it to spot trading",
+        ),
+        Ok(_) => report.add("api key", Outcome::Pass, "accepted, spot trading enabled"),
+        Err(e) => report.add("api key", Outcome::Fail, format!("{:#}", e)),
+    }
+
+    let symbols: BTreeSet<&str> = std::iter::once(config.dca.symbol.as_str())
+        .chain(snapshots.iter().map(|s| s.symbol.as_str()))
+        .collect();
+    for symbol in symbols {
+        match client.symbol_filters(symbol).await {
+            Ok(f) if f.status != "TRADING" => {
+                report.add("filters", Outcome::Warn, format!("{}: status {}, not trading", symbol, f.status))
+            }
+            Ok(f) => report.add(
+                "filters",
+                Outcome::Pass,
+                format!("{}: step {} · tick {} · min notional {}", symbol, f.step_size, f.tick_size, f.min_notional),
+            ),
+            Err(e) => report.add("filters", Outcome::Fail, format!("{}: {:#}", symbol, e)),
+        }
+    }
+}
+
+/// WebSocket handshake with the price stream endpoints, in the order the bot tries them
+async fn check_websocket(report: &mut Report) {
+    let mut errors = Vec::new();
+    for base in MAINNET_WS {
+        let start = Instant::now();
+        match tokio::time::timeout(WS_TIMEOUT, tokio_tungstenite::connect_async(format!("{}/ws", base))).await {
+            Ok(Ok((mut ws, _))) => {
+                let _ = ws.close(None).await;
+                let detail = format!("{} handshake {:.0}ms", base, start.elapsed().as_secs_f64() * 1000.0);
+                if errors.is_empty() {
+                    report.add("websocket", Outcome::Pass, detail);
+                } else {
+                    report.add("websocket", Outcome::Warn, format!("{} (failed before: {})", detail, errors.join("; ")));
+                }
+                return;
+            }
+            Ok(Err(e)) => errors.push(format!("{}: {}", base, e)),
+            Err(_) => errors.push(format!("{}: no answer in {}s", base, WS_TIMEOUT.as_secs())),
+        }
+    }
+    report.add("websocket", Outcome::Fail, errors.join("; "));
+}
+
+/// Interactive terminal with raw mode and enough room for the dashboard
+fn check_terminal(report: &mut Report) {
+    if !std::io::stdout().is_terminal() {
+        report.add("terminal", Outcome::Fail, "stdout is not a terminal");
+        return;
+    }
+    if let Err(e) = crossterm::terminal::enable_raw_mode().and_then(|_| crossterm::terminal::disable_raw_mode()) {
+        report.add("terminal", Outcome::Fail, format!("raw mode not supported: {}", e));
+        return;
+    }
+    let colors = match (std::env::var("COLORTERM"), std::env::var("TERM")) {
+        (Ok(c), _) if c == "truecolor" || c == "24bit" => "truecolor",
+        (_, Ok(t)) if t.contains("256color") => "256 colors",
+        _ => "basic colors",
+    };
+    match crossterm::terminal::size() {
+        Ok((w, h)) if w < MIN_SIZE.0 || h < MIN_SIZE.1 => report.add(
+            "terminal",
+            Outcome::Warn,
+            format!("{}x{}, {}: smaller than {}x{}, the dashboard will not fit", w, h, colors, MIN_SIZE.0, MIN_SIZE.1),
+        ),
+        Ok((w, h)) if w < COMPACT_WIDTH || h < WIDE_HEIGHT => report.add(
+            "terminal",
+            Outcome::Pass,
+            format!(
+                "{}x{}, raw mode, {}: compact layout below {}x{}",
+                w, h, colors, COMPACT_WIDTH, WIDE_HEIGHT
+            ),
+        ),
+        Ok((w, h)) => report.add("terminal", Outcome::Pass, format!("{}x{}, raw mode, {}", w, h, colors)),
+        Err(e) => report.add("terminal", Outcome::Fail, format!("size unknown: {}", e)),
+    }
+}
@@ -0,0 +1,159 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+/// Order and API counters bumped by the REST client as requests complete
+#[derive(Debug, Default)]
+pub struct ApiCounters {
+    /// Orders Binance accepted
+    pub orders_placed: AtomicU64,
+    /// Orders rejected or lost on the way (timeouts included)
+    pub orders_failed: AtomicU64,
+    /// Requests answered with an error status
+    pub api_errors: AtomicU64,
+}
+
+/// Counters of one session, or of every session added up
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Counters {
+    pub orders_placed: u64,
+    pub orders_failed: u64,
+    pub api_errors: u64,
+    /// Price feed drops plus failed connection attempts (24h rotations not counted)
+    pub reconnects: u64,
+    /// S/R and volume alerts
+    pub alerts_fired: u64,
+    pub uptime_secs: u64,
+}
+
+impl Counters {
+    fn plus(&self, other: &Counters) -> Counters {
+        Counters {
+            orders_placed: self.orders_placed + other.orders_placed,
+            orders_failed: self.orders_failed + other.orders_failed,
+            api_errors: self.api_errors + other.api_errors,
+            reconnects: self.reconnects + other.reconnects,
+            alerts_fired: self.alerts_fired + other.alerts_fired,
+            uptime_secs: self.uptime_secs + other.uptime_secs,
+        }
+    }
+}
+
+/// One run of the bot as last saved; `updated_at` is close to when it stopped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub counters: Counters,
+}
+
+/// Layout of `stats.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsFile {
+    /// Start of the first session counted
+    pub since: DateTime<Utc>,
+    pub sessions: u64,
+    /// Every session, the latest included
+    pub totals: Counters,
+    /// The latest session
+    pub session: SessionRecord,
+}
+
+impl StatsFile {
+    /// Writes through a temporary file and a rename, like the state file
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("json.tmp");
+        let mut out = tokio::fs::File::create(&tmp).await?;
+        out.write_all(serde_json::to_string_pretty(self)?.as_bytes()).await?;
+        out.sync_all().await?;
+        drop(out);
+        tokio::fs::rename(&tmp, path).await?;
+        Ok(())
+    }
+}
+
+/// Counters of the running session on top of the ones saved by earlier sessions, so a
+/// long-running bot can be audited after the fact
+pub struct SessionStats {
+    pub path: PathBuf,
+    /// Shared with the REST client
+    pub api: Arc<ApiCounters>,
+    pub reconnects: u64,
+    pub alerts_fired: u64,
+    pub started_at: DateTime<Utc>,
+    since: DateTime<Utc>,
+    /// Sessions and totals before this one
+    earlier_sessions: u64,
+    earlier: Counters,
+    /// The session before this one, as it was last saved
+    pub previous: Option<SessionRecord>,
+}
+
+impl SessionStats {
+    /// Starts a session on top of the stats saved at `path`. A missing or unreadable file
+    /// starts the count over.
+    pub fn open(path: &Path) -> Self {
+        let saved: Option<StatsFile> = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok());
+        let started_at = Utc::now();
+        Self {
+            path: path.to_path_buf(),
+            api: Arc::new(ApiCounters::default()),
+            reconnects: 0,
+            alerts_fired: 0,
+            started_at,
+            since: saved.as_ref().map(|f| f.since).unwrap_or(started_at),
+            earlier_sessions: saved.as_ref().map(|f| f.sessions).unwrap_or(0),
+            earlier: saved.as_ref().map(|f| f.totals.clone()).unwrap_or_default(),
+            previous: saved.map(|f| f.session),
+        }
+    }
+
+    /// Counters of this session so far
+    pub fn session(&self) -> Counters {
+        Counters {
+            orders_placed: self.api.orders_placed.load(Ordering::Relaxed),
+            orders_failed: self.api.orders_failed.load(Ordering::Relaxed),
+            api_errors: self.api.api_errors.load(Ordering::Relaxed),
+            reconnects: self.reconnects,
+            alerts_fired: self.alerts_fired,
+            uptime_secs: (Utc::now() - self.started_at).num_seconds().max(0) as u64,
+        }
+    }
+
+    /// Counters of every session, this one included
+    pub fn totals(&self) -> Counters {
+        self.earlier.plus(&self.session())
+    }
+
+    /// Sessions counted, this one included
+    pub fn sessions(&self) -> u64 {
+        self.earlier_sessions + 1
+    }
+
+    /// Start of the first session counted
+    pub fn since(&self) -> DateTime<Utc> {
+        self.since
+    }
+
+    /// What `stats.json` holds right now
+    pub fn file(&self) -> StatsFile {
+        StatsFile {
+            since: self.since,
+            sessions: self.sessions(),
+            totals: self.totals(),
+            session: SessionRecord {
+                started_at: self.started_at,
+                updated_at: Utc::now(),
+                counters: self.session(),
+            },
+        }
+    }
+}
@@ -0,0 +1,533 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::watch;
+
+use crate::strategy::cycles::{CycleHistory, CycleRecord};
+use crate::strategy::dca::{StrategySnapshot, STATE_SCHEMA_VERSION};
+use crate::strategy::events::{EventFile, SlotEvent};
+
+/// Rotated backups of the state file that are kept
+pub const BACKUPS: usize = 3;
+/// Minimum age of the newest backup before rotating again (1 hour)
+const BACKUP_SECS: u64 = 3600;
+/// Wait after a save request so the ones right behind it are written together
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// On-disk layout: the slots, the schema version they follow and the SHA-256 of both, so a
+/// torn or edited file is detected on load instead of being restored half-written
+#[derive(Serialize, Deserialize)]
+struct StateFile {
+    /// Missing only in the first checksummed files, which follow version 1
+    version: Option<u32>,
+    sha256: String,
+    slots: Value,
+}
+
+/// Hash of the slots as serialized. On load it is computed again over the slots parsed back
+/// from the file, so it relies on serde_json's `float_roundtrip` to read every f64 back bit
+/// for bit (without it about one float in seven comes back one ULP off).
+fn checksum(version: Option<u32>, slots: &Value) -> Result<String> {
+    let bytes = match version {
+        Some(version) => serde_json::to_vec(&(version, slots))?,
+        None => serde_json::to_vec(slots)?,
+    };
+    Ok(hex::encode(Sha256::digest(bytes)))
+}
+
+/// One step of the upgrade path: turns the slots of version `n` into version `n + 1`
+type Migration = fn(Value) -> Result<Value, String>;
+
+/// `MIGRATIONS[n]` upgrades version `n`. A layout change bumps `STATE_SCHEMA_VERSION` and
+/// appends its step here, so every old file goes through the same steps in the same order.
+const MIGRATIONS: [Migration; STATE_SCHEMA_VERSION as usize] =
+    [single_to_list, explicit_fields, performance_checkpoint, strategy_kind, cycle_flags];
+
+/// v0 → v1: the first format held one strategy as a single object
+fn single_to_list(slots: Value) -> Result<Value, String> {
+    if slots.is_object() {
+        Ok(Value::Array(vec![slots]))
+    } else {
+        Err("expected a single slot object".to_string())
+    }
+}
+
+/// Adds the `defaults` missing from `fields`
+fn fill_defaults(fields: &mut Map<String, Value>, defaults: &[(&str, Value)]) {
+    for (name, value) in defaults {
+        fields.entry(*name).or_insert_with(|| value.clone());
+    }
+}
+
+/// v1 → v2: every field explicit, in the slot and in its trades and open order. Until v1
+/// the fields added over time were filled in by serde defaults on load; these are their
+/// values, frozen
+fn explicit_fields(slots: Value) -> Result<Value, String> {
+    let defaults = [
+        ("direction", json!("long")),
+        ("price_peak", json!(0.0)),
+        ("price_trough", json!(f64::MAX)),
+        ("breakeven_armed", json!(false)),
+        ("has_bnb_balance", json!(false)),
+        ("state", json!("IDLE")),
+        ("cooldown_until", Value::Null),
+        ("consecutive_losses", json!(0)),
+        ("tp_steps_done", json!(0)),
+        ("group", Value::Null),
+        ("slot_id", Value::Null),
+        ("last_event_seq", json!(0)),
+        ("config_overrides", json!({})),
+        ("open_order", Value::Null),
+        ("exit_pending", Value::Null),
+        ("analysis_interval", Value::Null),
+    ];
+    let trade_defaults = [
+        ("expected_price", json!(0.0)),
+        ("commission", Value::Null),
+        ("fills", json!([])),
+        ("trigger", Value::Null),
+    ];
+    let order_defaults = [
+        ("post_only", json!(false)),
+        ("replaces", json!(0)),
+        ("trigger", Value::Null),
+    ];
+    let Value::Array(mut list) = slots else {
+        return Err("expected a list of slots".to_string());
+    };
+    for slot in list.iter_mut() {
+        let Some(fields) = slot.as_object_mut() else {
+            return Err("expected every slot to be an object".to_string());
+        };
+        fill_defaults(fields, &defaults);
+        if let Some(Value::Array(trades)) = fields.get_mut("trades") {
+            for trade in trades.iter_mut().filter_map(Value::as_object_mut) {
+                fill_defaults(trade, &trade_defaults);
+            }
+        }
+        if let Some(Value::Object(order)) = fields.get_mut("open_order") {
+            fill_defaults(order, &order_defaults);
+        }
+    }
+    Ok(Value::Array(list))
+}
+
+/// v2 → v3: `performance` holds the slot's lifetime stats. Older files have none, so they
+/// are rebuilt from the event log on restore, as before. A pending exit gains the client
+/// order ID of its last attempt, unknown in older files
+fn performance_checkpoint(slots: Value) -> Result<Value, String> {
+    let Value::Array(mut list) = slots else {
+        return Err("expected a list of slots".to_string());
+    };
+    for slot in list.iter_mut() {
+        let Some(fields) = slot.as_object_mut() else {
+            return Err("expected every slot to be an object".to_string());
+        };
+        fields.entry("performance").or_insert(Value::Null);
+        if let Some(Value::Object(exit)) = fields.get_mut("exit_pending") {
+            exit.entry("client_order_id").or_insert(Value::Null);
+        }
+    }
+    Ok(Value::Array(list))
+}
+
+/// v3 → v4: `kind` is saved with the slot. Until v3 every slot came back with `[dca]`'s
+/// kind; the slots of those files are taken as trading slots, the default
+fn strategy_kind(slots: Value) -> Result<Value, String> {
+    let Value::Array(mut list) = slots else {
+        return Err("expected a list of slots".to_string());
+    };
+    for slot in list.iter_mut() {
+        let Some(fields) = slot.as_object_mut() else {
+            return Err("expected every slot to be an object".to_string());
+        };
+        fields.entry("kind").or_insert(json!("trading"));
+    }
+    Ok(Value::Array(list))
+}
+
+/// v4 → v5: `auto_restart` and `auto_flip` are saved with the slot. Older files did not
+/// have them; null keeps `[dca]`'s values on restore, as before
+fn cycle_flags(slots: Value) -> Result<Value, String> {
+    let Value::Array(mut list) = slots else {
+        return Err("expected a list of slots".to_string());
+    };
+    for slot in list.iter_mut() {
+        let Some(fields) = slot.as_object_mut() else {
+            return Err("expected every slot to be an object".to_string());
+        };
+        fields.entry("auto_restart").or_insert(Value::Null);
+        fields.entry("auto_flip").or_insert(Value::Null);
+    }
+    Ok(Value::Array(list))
+}
+
+/// Runs the steps from `version` up to the current one
+fn migrate(mut slots: Value, version: u32) -> Result<Value, String> {
+    if version > STATE_SCHEMA_VERSION {
+        return Err(format!(
+            "written by a newer version (schema v{}, this build reads up to v{})",
+            version, STATE_SCHEMA_VERSION
+        ));
+    }
+    for step in &MIGRATIONS[version as usize..] {
+        slots = step(slots).map_err(|e| format!("upgrade from schema v{}: {}", version, e))?;
+    }
+    Ok(slots)
+}
+
+/// Reads the snapshots saved at `path`, upgraded to the current schema. A missing file is
+/// an empty state; an unreadable one or a checksum mismatch is an error. Files written
+/// before checksums (a plain array, or the single object of the first format) are still
+/// accepted.
+pub fn load(path: &Path) -> Result<Vec<StrategySnapshot>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => parse(&content),
+        // First run: no file yet
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Snapshots in the content of a state file, upgraded to the current schema
+fn parse(content: &str) -> Result<Vec<StrategySnapshot>, String> {
+    let value: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let (version, slots) = if value.get("sha256").is_some() {
+        let file: StateFile = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        if checksum(file.version, &file.slots).map_err(|e| e.to_string())? != file.sha256 {
+            return Err("checksum mismatch: the file changed after it was written".to_string());
+        }
+        (file.version.unwrap_or(1), file.slots)
+    } else if value.is_object() {
+        (0, value)
+    } else {
+        (1, value)
+    };
+    serde_json::from_value(migrate(slots, version)?).map_err(|e| e.to_string())
+}
+
+/// Rotated backup `n` of the state file (`strategy_state.json.1` is the newest)
+pub fn backup_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    name.into()
+}
+
+/// Newest rotated backup that loads and holds at least one slot
+pub fn newest_valid_backup(path: &Path) -> Option<(PathBuf, Vec<StrategySnapshot>)> {
+    (1..=BACKUPS).find_map(|n| {
+        let backup = backup_path(path, n);
+        let snapshots = load(&backup).ok().filter(|s| !s.is_empty())?;
+        Some((backup, snapshots))
+    })
+}
+
+/// state → .1 → .2 → ... → .BACKUPS. Only a file that loads is copied, so a damaged state
+/// never pushes out the good backups.
+async fn rotate_backups(path: &Path) {
+    let newest = backup_path(path, 1);
+    let fresh = tokio::fs::metadata(&newest)
+        .await
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.elapsed().ok())
+        .is_some_and(|age| age.as_secs() < BACKUP_SECS);
+    let loads = match tokio::fs::read_to_string(path).await {
+        Ok(content) => parse(&content).is_ok_and(|s| !s.is_empty()),
+        Err(_) => false,
+    };
+    if fresh || !loads {
+        return;
+    }
+    for n in (1..BACKUPS).rev() {
+        let _ = tokio::fs::rename(backup_path(path, n), backup_path(path, n + 1)).await;
+    }
+    if let Err(e) = tokio::fs::copy(path, &newest).await {
+        tracing::warn!("Could not back up the state file: {}", e);
+    }
+}
+
+/// Writes the snapshots through a synced temporary file and a rename, so `path` always
+/// holds either the previous state or the new one. Rotates the backups first when the
+/// newest is older than an hour.
+async fn save(snapshots: &[StrategySnapshot], path: &Path) -> Result<()> {
+    rotate_backups(path).await;
+    let slots = serde_json::to_value(snapshots)?;
+    let file = StateFile {
+        version: Some(STATE_SCHEMA_VERSION),
+        sha256: checksum(Some(STATE_SCHEMA_VERSION), &slots)?,
+        slots,
+    };
+    let tmp = path.with_extension("json.tmp");
+    let mut out = tokio::fs::File::create(&tmp).await?;
+    out.write_all(serde_json::to_string_pretty(&file)?.as_bytes()).await?;
+    out.sync_all().await?;
+    drop(out);
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+/// Latest state handed to the writer: its generation and what to write where
+type Job = (u64, Option<Arc<(PathBuf, Vec<StrategySnapshot>)>>);
+
+/// Log lines drained with a snapshot: the slots' events and the cycles they completed.
+/// Unlike snapshots they never collapse, and they reach disk before the snapshot that
+/// follows them, so a restart can replay them over the previous one.
+#[derive(Default)]
+pub struct LogAppend {
+    pub events: Vec<SlotEvent>,
+    pub cycles: Vec<CycleRecord>,
+}
+
+impl LogAppend {
+    fn is_empty(&self) -> bool {
+        self.events.is_empty() && self.cycles.is_empty()
+    }
+}
+
+/// Saves the state from a background task, off the engine. Saves requested while one is
+/// waiting or being written collapse into the newest, so a burst of commands costs a single
+/// write.
+pub struct StateWriter {
+    jobs: watch::Sender<Job>,
+    /// Log lines not appended yet, oldest first
+    appends: Arc<Mutex<Vec<LogAppend>>>,
+    /// Small files (conditional orders, ladders, presets) not written yet, one per path
+    files: Arc<Mutex<Vec<(PathBuf, String)>>>,
+    /// Generation of the last job written (or given up on)
+    done: watch::Receiver<u64>,
+}
+
+/// Appends the queued log lines in order: each batch's events, then its cycles (a cycle is
+/// only recorded once its exit is in the log). The batches from the first failure on are
+/// put back at the front of the queue for the next round.
+fn append_logs(appends: &Mutex<Vec<LogAppend>>, events: &EventFile, cycles_path: &Path) {
+    let batches = std::mem::take(&mut *appends.lock().unwrap_or_else(|e| e.into_inner()));
+    let mut batches = batches.into_iter();
+    while let Some(batch) = batches.next() {
+        let written = events
+            .append(&batch.events)
+            .map_err(|e| format!("Could not append to event log: {}", e))
+            .and_then(|()| {
+                CycleHistory::append(cycles_path, &batch.cycles)
+                    .map_err(|e| format!("Could not append to cycle history: {}", e))
+            });
+        if let Err(e) = written {
+            tracing::warn!("{}", e);
+            let mut queue = appends.lock().unwrap_or_else(|e| e.into_inner());
+            let failed: Vec<LogAppend> = std::iter::once(batch).chain(batches).collect();
+            queue.splice(0..0, failed);
+            return;
+        }
+    }
+}
+
+/// Writes the queued files. A file that fails goes back to the queue unless a newer version
+/// of it was queued meanwhile.
+fn write_files(files: &Mutex<Vec<(PathBuf, String)>>) {
+    let queued = std::mem::take(&mut *files.lock().unwrap_or_else(|e| e.into_inner()));
+    for (path, contents) in queued {
+        if let Err(e) = std::fs::write(&path, &contents) {
+            tracing::warn!("Could not write {:?}: {}", path, e);
+            let mut queue = files.lock().unwrap_or_else(|e| e.into_inner());
+            if !queue.iter().any(|(p, _)| *p == path) {
+                queue.push((path, contents));
+            }
+        }
+    }
+}
+
+impl StateWriter {
+    /// Starts the writer task, which also appends to the event log in `events` and to the
+    /// cycle history at `cycles_path`. Call from within the runtime
+    pub fn spawn(events: Arc<EventFile>, cycles_path: PathBuf) -> Self {
+        let (jobs, mut pending) = watch::channel::<Job>((0, None));
+        let (done_tx, done) = watch::channel(0);
+        let appends: Arc<Mutex<Vec<LogAppend>>> = Arc::default();
+        let files: Arc<Mutex<Vec<(PathBuf, String)>>> = Arc::default();
+        let (queue, file_queue) = (Arc::clone(&appends), Arc::clone(&files));
+        tokio::spawn(async move {
+            // A `write_file` wakes the task without a new snapshot: the last one is not rewritten
+            let mut saved: Option<Arc<(PathBuf, Vec<StrategySnapshot>)>> = None;
+            while pending.changed().await.is_ok() {
+                tokio::time::sleep(DEBOUNCE).await;
+                let (generation, job) = pending.borrow_and_update().clone();
+                // Everything queued with this job or before it goes to the log first
+                let (queue, files, events, cycles_path) =
+                    (Arc::clone(&queue), Arc::clone(&file_queue), Arc::clone(&events), cycles_path.clone());
+                let blocking = tokio::task::spawn_blocking(move || {
+                    append_logs(&queue, &events, &cycles_path);
+                    write_files(&files);
+                });
+                if let Err(e) = blocking.await {
+                    tracing::warn!("Log append task failed: {}", e);
+                }
+                if let Some(job) = job.filter(|job| !saved.as_ref().is_some_and(|s| Arc::ptr_eq(s, job))) {
+                    let (path, snapshots) = &*job;
+                    if let Err(e) = save(snapshots, path).await {
+                        tracing::warn!("Could not save state: {}", e);
+                    }
+                    saved = Some(job);
+                }
+                let _ = done_tx.send(generation);
+            }
+        });
+        Self { jobs, appends, files, done }
+    }
+
+    /// Queues `contents` to be written to `path`, replacing a version of it still waiting
+    pub fn write_file(&self, path: PathBuf, contents: String) {
+        {
+            let mut files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+            match files.iter_mut().find(|(p, _)| *p == path) {
+                Some(queued) => queued.1 = contents,
+                None => files.push((path, contents)),
+            }
+        }
+        self.jobs.send_modify(|(generation, _)| *generation += 1);
+    }
+
+    /// Queues `snapshots` to be written to `path`, replacing any save still waiting, after
+    /// `logs` and every batch queued before it
+    pub fn submit(&self, path: &Path, snapshots: Vec<StrategySnapshot>, logs: LogAppend) {
+        if !logs.is_empty() {
+            self.appends.lock().unwrap_or_else(|e| e.into_inner()).push(logs);
+        }
+        self.jobs.send_modify(|(generation, job)| {
+            *generation += 1;
+            *job = Some(Arc::new((path.to_path_buf(), snapshots)));
+        });
+    }
+
+    /// Waits until everything submitted so far is on disk
+    pub async fn flush(&self) {
+        let target = self.jobs.borrow().0;
+        let mut done = self.done.clone();
+        let _ = done.wait_for(|written| *written >= target).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, Direction, StrategyKind};
+    use crate::strategy::dca::DcaStrategy;
+
+    /// Snapshots full of prices and quantities that need all 17 digits to come back
+    fn snapshots() -> Vec<StrategySnapshot> {
+        let config: Config = toml::from_str(include_str!("../config.toml")).expect("config.toml parses");
+        let mut seed: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut next = || {
+            // Knuth's MMIX LCG: enough to get varied mantissas
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        (0..20)
+            .map(|n| {
+                let mut strat = DcaStrategy::new(config.dca.clone());
+                for order in 0..10 {
+                    let price = 1000.0 + next() * 60000.0;
+                    let qty = next() * 0.5;
+                    strat.record_buy(order + 1, price, price, qty, price * qty, Vec::new(), None);
+                }
+                strat.drain_events();
+                let mut snap = strat.to_snapshot("BTCUSDT");
+                snap.slot_id = Some(n);
+                snap.daily_spent = next() * 1e4;
+                snap.price_peak = 1000.0 + next() * 60000.0;
+                snap
+            })
+            .collect()
+    }
+
+    #[test]
+    fn explicit_fields_fills_trades_and_open_order() {
+        let v1 = json!([{
+            "symbol": "BTCUSDT",
+            "trades": [{ "order_id": 1, "buy_price": 100.0, "quantity": 1.0, "cost": 100.0 }],
+            "open_order": { "order_id": 2, "side": "BUY", "price": 90.0, "quantity": 1.0, "entries_before": 1 },
+        }]);
+        let v2 = explicit_fields(v1).unwrap();
+        assert_eq!(v2[0]["trades"][0]["expected_price"], json!(0.0));
+        assert_eq!(v2[0]["trades"][0]["fills"], json!([]));
+        assert_eq!(v2[0]["open_order"]["post_only"], json!(false));
+        assert_eq!(v2[0]["open_order"]["replaces"], json!(0));
+        assert_eq!(v2[0]["direction"], json!("long"));
+    }
+
+    /// A slot of `kind` and `direction` saved, parsed back and restored over `[dca]`, as on
+    /// a restart
+    fn restored(kind: StrategyKind, direction: Direction) -> DcaStrategy {
+        let config: Config = toml::from_str(include_str!("../config.toml")).expect("config.toml parses");
+        let mut cfg = config.dca.clone();
+        cfg.kind = kind;
+        cfg.direction = direction;
+        let snap = DcaStrategy::new(cfg).to_snapshot("BTCUSDT");
+        let json = serde_json::to_string(&snap).unwrap();
+        let mut restored = DcaStrategy::new(config.dca.clone());
+        restored.restore_from_snapshot(serde_json::from_str(&json).unwrap());
+        restored
+    }
+
+    #[test]
+    fn restore_keeps_the_strategy_kind() {
+        assert_eq!(restored(StrategyKind::Accumulate, Direction::Long).config.kind, StrategyKind::Accumulate);
+        assert_eq!(restored(StrategyKind::Trading, Direction::Long).config.kind, StrategyKind::Trading);
+    }
+
+    #[test]
+    fn restore_keeps_a_distribution_slot() {
+        let strat = restored(StrategyKind::Distribute, Direction::Short);
+        assert_eq!(strat.config.kind, StrategyKind::Distribute);
+        assert_eq!(strat.config.direction, Direction::Short);
+        assert!(strat.distributing());
+        assert!(!strat.is_trading());
+    }
+
+    #[test]
+    fn restore_keeps_a_watch_slot_from_restarting() {
+        let config: Config = toml::from_str(include_str!("../config.toml")).expect("config.toml parses");
+        let mut cfg = config.dca.clone();
+        cfg.kind = StrategyKind::Watch;
+        cfg.auto_restart = false;
+        cfg.auto_flip = false;
+        let json = serde_json::to_string(&DcaStrategy::new(cfg).to_snapshot("BTCUSDT")).unwrap();
+        let mut base = config.dca.clone();
+        base.auto_restart = true;
+        base.auto_flip = true;
+        let mut restored = DcaStrategy::new(base);
+        restored.restore_from_snapshot(serde_json::from_str(&json).unwrap());
+        assert_eq!(restored.config.kind, StrategyKind::Watch);
+        assert!(!restored.config.auto_restart);
+        assert!(!restored.config.auto_flip);
+    }
+
+    #[test]
+    fn strategy_kind_defaults_older_slots_to_trading() {
+        let v3 = json!([{ "symbol": "BTCUSDT" }, { "symbol": "ETHUSDT", "kind": "accumulate" }]);
+        let v4 = strategy_kind(v3).unwrap();
+        assert_eq!(v4[0]["kind"], json!("trading"));
+        assert_eq!(v4[1]["kind"], json!("accumulate"));
+    }
+
+    #[tokio::test]
+    async fn save_then_load_keeps_the_checksum() {
+        let dir = std::env::temp_dir().join(format!("tv-state-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("strategy_state.json");
+        let saved = snapshots();
+        save(&saved, &path).await.unwrap();
+        let loaded = load(&path).expect("the file just written loads");
+        assert_eq!(
+            serde_json::to_value(&saved).unwrap(),
+            serde_json::to_value(&loaded).unwrap()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
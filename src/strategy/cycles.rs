@@ -0,0 +1,225 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Direction;
+use crate::models::order::Commission;
+use crate::strategy::events::{SlotEvent, SlotEventKind};
+
+/// A completed cycle (first entry to full exit), as stored in the cycle history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CycleRecord {
+    /// Sequence of the exit event that closed the cycle
+    pub seq: u64,
+    pub slot_id: usize,
+    pub symbol: String,
+    pub direction: Direction,
+    /// What closed it: TAKE PROFIT, TRAILING TP, STOP LOSS, MANUAL CLOSE, ...
+    pub exit: String,
+    /// First entry (None when it was pruned from the event log before being recorded)
+    pub opened_at: Option<DateTime<Utc>>,
+    pub closed_at: DateTime<Utc>,
+    /// Quote spent on the entries (LONG) or received for them (SHORT)
+    pub invested: f64,
+    /// Quote of every exit of the cycle, partial ones included
+    pub received: f64,
+    /// Realized P&L of every exit of the cycle, in the slot's quote
+    pub pnl: f64,
+    /// Commissions of the entries and exits, by asset
+    pub fees: BTreeMap<String, f64>,
+}
+
+/// Cycle in progress of one slot
+#[derive(Debug, Clone)]
+struct OpenCycle {
+    opened_at: DateTime<Utc>,
+    /// Cost of each entry by order ID, so merged duplicates are counted once
+    entries: Vec<(u64, f64)>,
+    received: f64,
+    pnl: f64,
+    fees: BTreeMap<String, f64>,
+}
+
+impl OpenCycle {
+    fn add_fee(&mut self, commission: Option<&Commission>) {
+        if let Some(c) = commission.filter(|c| c.amount > 0.0) {
+            *self.fees.entry(c.asset.clone()).or_insert(0.0) += c.amount;
+        }
+    }
+}
+
+/// Append-only history of completed cycles (one JSON record per line). Unlike the event
+/// log it is never pruned, and it keeps the cycles of deleted slots.
+pub struct CycleHistory {
+    records: Vec<CycleRecord>,
+    open: HashMap<usize, OpenCycle>,
+    directions: HashMap<usize, Direction>,
+}
+
+impl CycleHistory {
+    /// Loads the history at `path` and replays `events` to rebuild the cycles in progress.
+    /// Cycles closed in the log but missing from the file (recorded by an older version, or
+    /// lost in a crash) are appended. `directions` covers slots whose `Created` event is no
+    /// longer in the log. Returns how many cycles were added.
+    pub fn open(
+        path: &Path,
+        events: &[SlotEvent],
+        directions: impl IntoIterator<Item = (usize, Direction)>,
+    ) -> (Self, usize) {
+        let records: Vec<CycleRecord> = match std::fs::File::open(path) {
+            Ok(file) => std::io::BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| serde_json::from_str(&line).ok())
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        let mut history = Self {
+            records,
+            open: HashMap::new(),
+            directions: directions.into_iter().collect(),
+        };
+        let added: Vec<CycleRecord> = events.iter().filter_map(|event| history.observe(event)).collect();
+        if let Err(e) = Self::append(path, &added) {
+            tracing::warn!("Could not append to cycle history: {}", e);
+        }
+        (history, added.len())
+    }
+
+    /// Every completed cycle, oldest first
+    pub fn records(&self) -> &[CycleRecord] {
+        &self.records
+    }
+
+    /// Cycles closed by a slot (slot IDs can be reused after a delete, so the symbol must
+    /// match too)
+    pub fn for_slot<'a>(&'a self, slot_id: usize, symbol: &'a str) -> impl Iterator<Item = &'a CycleRecord> {
+        self.records.iter().filter(move |r| r.slot_id == slot_id && r.symbol == symbol)
+    }
+
+    /// Follows one event of the log. An exit that completes a cycle adds it to the history,
+    /// unless it is already there, and returns it so the caller appends it to the file with
+    /// `append` (no I/O here: this runs under the app state lock)
+    pub fn observe(&mut self, event: &SlotEvent) -> Option<CycleRecord> {
+        let id = event.slot_id;
+        match &event.kind {
+            SlotEventKind::Created { config } => {
+                self.directions.insert(id, config.direction.clone());
+            }
+            SlotEventKind::ConfigChanged { field, value } if field == "direction" => {
+                if let Ok(direction) = serde_json::from_value(value.clone()) {
+                    self.directions.insert(id, direction);
+                }
+            }
+            SlotEventKind::EntryRecorded { trade, .. } => {
+                let cycle = self.open.entry(id).or_insert_with(|| OpenCycle {
+                    opened_at: trade.timestamp,
+                    entries: Vec::new(),
+                    received: 0.0,
+                    pnl: 0.0,
+                    fees: BTreeMap::new(),
+                });
+                cycle.entries.push((trade.order_id, trade.cost));
+                cycle.add_fee(trade.commission.as_ref());
+            }
+            SlotEventKind::TradesMerged { order_id, kept } => {
+                if let Some(cycle) = self.open.get_mut(&id) {
+                    cycle.entries.retain(|(order, _)| order != order_id);
+                    cycle.entries.push((*order_id, kept.cost));
+                }
+            }
+            // The wallet no longer holds the position: the cycle ends without an exit
+            SlotEventKind::PositionAdjusted { keep } if *keep <= 0.0 => {
+                self.open.remove(&id);
+            }
+            SlotEventKind::PositionAdjusted { keep } => {
+                if let Some(cycle) = self.open.get_mut(&id) {
+                    for (_, cost) in cycle.entries.iter_mut() {
+                        *cost *= keep;
+                    }
+                }
+            }
+            SlotEventKind::PartialExit { received, pnl, commission, .. } => {
+                if let Some(cycle) = self.open.get_mut(&id) {
+                    cycle.received += received;
+                    cycle.pnl += pnl;
+                    cycle.add_fee(commission.as_ref());
+                }
+            }
+            SlotEventKind::ExitExecuted { kind, received, pnl, commission, .. } => {
+                // A flip changes the direction after the exit, so this is still the cycle's
+                let direction = self.directions.get(&id).cloned().unwrap_or_default();
+                let record = match self.open.remove(&id) {
+                    Some(mut cycle) => {
+                        cycle.add_fee(commission.as_ref());
+                        CycleRecord {
+                            seq: event.seq,
+                            slot_id: id,
+                            symbol: event.symbol.clone(),
+                            direction,
+                            exit: kind.clone(),
+                            opened_at: Some(cycle.opened_at),
+                            closed_at: event.timestamp,
+                            invested: cycle.entries.iter().map(|(_, cost)| cost).sum(),
+                            received: cycle.received + received,
+                            pnl: cycle.pnl + pnl,
+                            fees: cycle.fees,
+                        }
+                    }
+                    None => {
+                        // Entries already pruned: the cost basis still follows from the exit
+                        let invested = match direction {
+                            Direction::Long => received - pnl,
+                            Direction::Short => received + pnl,
+                        };
+                        let mut fees = BTreeMap::new();
+                        if let Some(c) = commission.as_ref().filter(|c| c.amount > 0.0) {
+                            fees.insert(c.asset.clone(), c.amount);
+                        }
+                        CycleRecord {
+                            seq: event.seq,
+                            slot_id: id,
+                            symbol: event.symbol.clone(),
+                            direction,
+                            exit: kind.clone(),
+                            opened_at: None,
+                            closed_at: event.timestamp,
+                            invested,
+                            received: *received,
+                            pnl: *pnl,
+                            fees,
+                        }
+                    }
+                };
+                if !self.records.iter().any(|r| r.seq == record.seq) {
+                    self.records.push(record.clone());
+                    return Some(record);
+                }
+            }
+            SlotEventKind::Removed => {
+                self.open.remove(&id);
+                self.directions.remove(&id);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Appends `records` to the history file at `path` in one write
+    pub fn append(path: &Path, records: &[CycleRecord]) -> anyhow::Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        let mut out = String::new();
+        for record in records {
+            out.push_str(&serde_json::to_string(record)?);
+            out.push('\n');
+        }
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(out.as_bytes())?;
+        Ok(())
+    }
+}
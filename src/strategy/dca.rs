@@ -1,8 +1,13 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Datelike, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::config::{DcaConfig, Direction};
-use crate::models::order::DcaTrade;
+use crate::config::{DcaConfig, Direction, EntryOrderType, SafetyOrder, StrategyKind, TakeProfitStep};
+use crate::models::order::{Commission, DcaTrade, EntryTrigger, OpenOrder, PendingExit, TradeFill};
+use crate::strategy::events::SlotEventKind;
+use crate::strategy::fees;
+use crate::strategy::performance::SlotPerformance;
 
 /// DCA strategy state
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -46,7 +51,7 @@ pub struct DcaStrategy {
     pub trades: Vec<DcaTrade>,
     pub last_buy_time: Option<DateTime<Utc>>,
     pub last_buy_price: Option<f64>,
-    /// Total spent on the current day (LONG: USDT bought; SHORT: base asset USDT sold)
+    /// Total spent on the current day in the quote asset (LONG: bought; SHORT: received for the base sold)
     pub daily_spent: f64,
     /// Day of the month of the last reset
     last_reset_day: u32,
@@ -56,8 +61,24 @@ pub struct DcaStrategy {
     pub price_peak: f64,
     /// SHORT: minimum price seen while position is open (for inverse trailing TP)
     pub price_trough: f64,
+    /// Break-even stop armed for the current cycle (P&L reached `breakeven_trigger_pct`)
+    pub breakeven_armed: bool,
     /// Timestamp when the post-TP cooldown expires (None = no cooldown active)
     pub cooldown_until: Option<DateTime<Utc>>,
+    /// Number of cycles in a row closed at a loss
+    pub consecutive_losses: u32,
+    /// Take profit levels already filled in the current cycle
+    pub tp_steps_done: usize,
+    /// Account taker commission per side in % (refreshed from the account info)
+    pub taker_fee_pct: f64,
+    /// Account maker commission per side in %, paid by post-only entries
+    pub maker_fee_pct: f64,
+    /// Entry size multiplier set by the risk-parity allocator (1.0 = unscaled)
+    pub size_multiplier: f64,
+    /// Config fields changed at runtime (last value per field), kept in the snapshot
+    pub config_overrides: BTreeMap<String, serde_json::Value>,
+    /// Events produced since the last save, drained into the event log
+    pending_events: Vec<SlotEventKind>,
 }
 
 impl DcaStrategy {
@@ -73,7 +94,15 @@ impl DcaStrategy {
             next_buy_in_secs: 0,
             price_peak: 0.0,
             price_trough: f64::MAX,
+            breakeven_armed: false,
             cooldown_until: None,
+            consecutive_losses: 0,
+            tp_steps_done: 0,
+            taker_fee_pct: fees::TAKER_FEE_PCT,
+            maker_fee_pct: fees::TAKER_FEE_PCT,
+            size_multiplier: 1.0,
+            config_overrides: BTreeMap::new(),
+            pending_events: Vec::new(),
         }
     }
 
@@ -102,9 +131,9 @@ impl DcaStrategy {
         self.trades.iter().map(|t| t.quantity).sum()
     }
 
-    /// Absolute P&L in USDT at current price, including estimated fees (0.2% total)
-    /// LONG:  (current_value * 0.999) - invested
-    /// SHORT: invested - (current_value * 1.001)
+    /// Absolute P&L in USDT at current price, net of the commission of the closing order
+    /// LONG:  (current_value * (1 - exit_fee)) - invested
+    /// SHORT: invested - (current_value * (1 + exit_fee))
     pub fn pnl(&self, current_price: f64) -> f64 {
         let total_qty = self.total_quantity();
         if total_qty == 0.0 { return 0.0; }
@@ -112,10 +141,11 @@ impl DcaStrategy {
         let current_value = total_qty * current_price;
         let invested = self.total_invested();
         
-        // Estimamos un 0.1% de comisión para la orden de cierre
+        // El cierre es una orden a mercado: comisión taker (con descuento BNB si aplica)
+        let exit_fee = fees::side_fee_pct(self.taker_fee_pct, self.config.has_bnb_balance) / 100.0;
         match self.config.direction {
-            Direction::Long  => (current_value * 0.999) - invested,
-            Direction::Short => invested - (current_value * 1.001),
+            Direction::Long  => (current_value * (1.0 - exit_fee)) - invested,
+            Direction::Short => invested - (current_value * (1.0 + exit_fee)),
         }
     }
 
@@ -128,6 +158,224 @@ impl DcaStrategy {
         (self.pnl(current_price) / invested) * 100.0
     }
 
+    /// Amount in quote for the next entry, scaled by the risk-parity multiplier and by
+    /// `volume_scale` once per order already filled in this cycle.
+    /// Percent amounts are taken from the balance that funds it: free quote in LONG,
+    /// free base valued at `price` in SHORT.
+    pub fn entry_amount(&self, quote_balance: f64, base_balance: f64, price: f64) -> f64 {
+        let funding = match self.config.direction {
+            Direction::Long => quote_balance,
+            Direction::Short => base_balance * price,
+        };
+        let scale = if !self.is_trading() {
+            1.0
+        } else if self.config.safety_orders.is_empty() {
+            self.config.volume_scale.powi(self.trades.len() as i32)
+        } else {
+            self.next_safety_order().map(|(_, so)| so.size_multiplier).unwrap_or(1.0)
+        };
+        let amount = self.config.quote_amount.resolve(funding) * self.size_multiplier * scale;
+        if self.distributing() {
+            // Nunca más que el inventario restante; el último nivel de la escalera vende todo
+            let remaining = self.remaining_inventory() * price;
+            let last_level = !self.config.sell_levels.is_empty()
+                && self.trades.len() + 1 >= self.config.sell_levels.len();
+            return if last_level { remaining } else { amount.min(remaining) };
+        }
+        amount
+    }
+
+    /// Quote needed to fill every order of a full cycle, starting from `first_amount`
+    /// and applying `volume_scale` (or the ladder multipliers) to each safety order
+    pub fn cycle_budget(&self, first_amount: f64) -> f64 {
+        self.config.cycle_budget(first_amount)
+    }
+
+    /// DCA cycles closed by TP/SL (not an accumulation or distribution slot). Watch-only
+    /// slots count too: they run the same exits, they just never enter
+    pub fn is_trading(&self) -> bool {
+        matches!(self.config.kind, StrategyKind::Trading | StrategyKind::Watch)
+    }
+
+    /// Watch-only slot: exits of an imported position, no entries
+    pub fn watching(&self) -> bool {
+        self.config.kind == StrategyKind::Watch
+    }
+
+    /// Accumulation slot: recurring buys with no exits
+    pub fn accumulating(&self) -> bool {
+        self.config.kind == StrategyKind::Accumulate
+    }
+
+    /// Distribution slot: gradual sale of `distribute_qty`, recorded as SHORT entries
+    pub fn distributing(&self) -> bool {
+        self.config.kind == StrategyKind::Distribute
+    }
+
+    /// Entries fired by `buy_schedule` instead of `interval_minutes` / price
+    pub fn on_schedule(&self) -> bool {
+        self.accumulating() || (self.distributing() && self.config.sell_levels.is_empty())
+    }
+
+    /// Distribute: base quantity still to sell
+    pub fn remaining_inventory(&self) -> f64 {
+        (self.config.distribute_qty - self.total_quantity()).max(0.0)
+    }
+
+    /// Distribute: price of the next ladder sale (None when selling on schedule or done)
+    pub fn next_sell_level(&self) -> Option<f64> {
+        self.config.sell_levels.get(self.trades.len()).copied()
+    }
+
+    /// Seconds between scheduled entries: the accumulation schedule or `interval_minutes`
+    pub fn entry_interval_secs(&self) -> i64 {
+        if self.on_schedule() {
+            self.config.buy_schedule.period().num_seconds()
+        } else {
+            (self.config.interval_minutes * 60) as i64
+        }
+    }
+
+    /// Maximum entries per cycle: base order + ladder steps, or `max_orders` without a ladder.
+    /// Unlimited when accumulating; one per sell level when distributing on a ladder.
+    pub fn max_entries(&self) -> usize {
+        if self.distributing() && !self.config.sell_levels.is_empty() {
+            self.config.sell_levels.len()
+        } else if !self.is_trading() {
+            usize::MAX
+        } else if self.config.safety_orders.is_empty() {
+            self.config.max_orders as usize
+        } else {
+            1 + self.config.safety_orders.len()
+        }
+    }
+
+    /// Next ladder step (index, step) once the base order is filled
+    pub fn next_safety_order(&self) -> Option<(usize, &SafetyOrder)> {
+        let index = self.trades.len().checked_sub(1)?;
+        self.config.safety_orders.get(index).map(|so| (index, so))
+    }
+
+    /// Price at which the next ladder step fires, measured from the base order price
+    pub fn safety_order_trigger_price(&self) -> Option<f64> {
+        let (_, so) = self.next_safety_order()?;
+        let base_price = self.trades.first()?.buy_price;
+        Some(match self.config.direction {
+            Direction::Long => base_price * (1.0 - so.deviation_pct / 100.0),
+            Direction::Short => base_price * (1.0 + so.deviation_pct / 100.0),
+        })
+    }
+
+    /// Sets the commission rates used in P&L, break-even and minimum-TP math
+    pub fn set_fee_rates(&mut self, rates: fees::CommissionRates) {
+        self.maker_fee_pct = rates.maker_pct;
+        self.taker_fee_pct = rates.taker_pct;
+    }
+
+    /// Commission rate per side in % paid by entries: maker for post-only entries, taker otherwise
+    pub fn entry_fee_rate_pct(&self) -> f64 {
+        match self.config.entry_order_type {
+            EntryOrderType::Maker => self.maker_fee_pct,
+            EntryOrderType::Market | EntryOrderType::Limit => self.taker_fee_pct,
+        }
+    }
+
+    /// Commission of a full cycle in %: entry at `entry_fee_rate_pct`, market exit at taker
+    pub fn round_trip_fee_pct(&self) -> f64 {
+        fees::round_trip_fee_pct(self.entry_fee_rate_pct(), self.taker_fee_pct, self.config.has_bnb_balance)
+    }
+
+    /// Estimated commissions of the current cycle in quote: every entry fill plus the exit
+    pub fn cycle_fees(&self) -> f64 {
+        let entry_side = self.entry_fee_pct() / 100.0;
+        let exit_side = fees::side_fee_pct(self.taker_fee_pct, self.config.has_bnb_balance) / 100.0;
+        let entry_fees: f64 = self.trades.iter().map(|t| t.cost * entry_side).sum();
+        entry_fees + self.total_invested() * exit_side
+    }
+
+    /// Entry commission per side in %, with the BNB discount if it applies
+    pub fn entry_fee_pct(&self) -> f64 {
+        fees::side_fee_pct(self.entry_fee_rate_pct(), self.config.has_bnb_balance)
+    }
+
+    /// Lowest take profit that still closes net-positive. It is compared with `pnl_pct`,
+    /// which already nets the exit commission, so only the entry side is added to the
+    /// configured minimum net profit
+    pub fn min_take_profit_pct(&self) -> f64 {
+        self.entry_fee_pct() + self.config.min_net_profit_pct
+    }
+
+    /// Next take profit level of the cycle, raised to the fee floor (None = TP off)
+    pub fn next_take_profit_step(&self) -> Option<TakeProfitStep> {
+        if !self.is_trading() {
+            return None;
+        }
+        let step = *self.config.take_profit_pct.steps().get(self.tp_steps_done)?;
+        Some(TakeProfitStep { pct: step.pct.max(self.min_take_profit_pct()), ..step })
+    }
+
+    /// Next take profit level as configured, before the fee floor (0 = off)
+    pub fn configured_take_profit_pct(&self) -> f64 {
+        if !self.is_trading() {
+            return 0.0;
+        }
+        self.config.take_profit_pct.steps().get(self.tp_steps_done).map(|s| s.pct).unwrap_or(0.0)
+    }
+
+    /// Take profit actually used for exits: the next level, raised to the fee floor (0 = off)
+    pub fn effective_take_profit_pct(&self) -> f64 {
+        self.next_take_profit_step().map(|s| s.pct).unwrap_or(0.0)
+    }
+
+    /// Fraction of the current position sold by the next take profit level (1.0 = full close).
+    /// Level fractions refer to the position at the start of the ladder.
+    pub fn take_profit_close_fraction(&self) -> f64 {
+        let steps = self.config.take_profit_pct.steps();
+        let step = match steps.get(self.tp_steps_done) {
+            Some(s) if self.tp_steps_done + 1 < steps.len() => s,
+            _ => return 1.0,
+        };
+        let closed: f64 = steps[..self.tp_steps_done].iter().map(|s| s.fraction).sum();
+        let remaining = 1.0 - closed;
+        if remaining <= step.fraction + 1e-9 {
+            1.0
+        } else {
+            step.fraction / remaining
+        }
+    }
+
+    /// Cycle fees as a fraction of the expected take profit (None if TP is off).
+    /// Without an open position the configured round-trip cost is used.
+    pub fn fee_to_tp_ratio(&self) -> Option<f64> {
+        self.fee_ratio(self.effective_take_profit_pct())
+    }
+
+    /// Like `fee_to_tp_ratio`, against the configured take profit before the fee floor
+    pub fn fee_to_configured_tp_ratio(&self) -> Option<f64> {
+        self.fee_ratio(self.configured_take_profit_pct())
+    }
+
+    fn fee_ratio(&self, tp: f64) -> Option<f64> {
+        if tp <= 0.0 {
+            return None;
+        }
+        let invested = self.total_invested();
+        if invested > 0.0 {
+            Some(self.cycle_fees() / (invested * tp / 100.0))
+        } else {
+            Some(self.round_trip_fee_pct() / tp)
+        }
+    }
+
+    /// Returns the fee/TP ratio when it exceeds `max_fee_tp_ratio` (guard disabled with 0)
+    pub fn fee_guard_exceeded(&self) -> Option<f64> {
+        let max = self.config.max_fee_tp_ratio;
+        if max <= 0.0 {
+            return None;
+        }
+        self.fee_to_tp_ratio().filter(|r| *r > max)
+    }
+
     // -----------------------------------------------------------
     // Lógica de decisión
     // -----------------------------------------------------------
@@ -142,7 +390,13 @@ impl DcaStrategy {
         }
 
         // Calcular tiempo hasta próxima entrada
-        if let Some(last_time) = self.last_buy_time {
+        if self.on_schedule() {
+            let schedule = self.config.buy_schedule;
+            self.next_buy_in_secs = match self.last_buy_time {
+                Some(last) if last >= schedule.last_due(now) => (schedule.next_due(now) - now).num_seconds(),
+                _ => 0,
+            };
+        } else if let Some(last_time) = self.last_buy_time {
             let interval_secs = (self.config.interval_minutes * 60) as i64;
             let elapsed = now.signed_duration_since(last_time).num_seconds();
             self.next_buy_in_secs = (interval_secs - elapsed).max(0);
@@ -151,38 +405,94 @@ impl DcaStrategy {
         }
     }
 
-    /// Decides if a DCA entry should be executed now
+    /// True if `now` is inside the configured trading schedule (`active_hours` / `active_days`)
+    pub fn in_schedule(&self, now: DateTime<Utc>) -> bool {
+        let day_ok = self.config.active_days.is_empty() || self.config.active_days.contains(&now.weekday());
+        let hour_ok = self.config.active_hours.is_none_or(|w| w.contains(now.time()));
+        day_ok && hour_ok
+    }
+
+    /// Running but outside the schedule: no new entries until the window opens
+    pub fn paused_by_schedule(&self, now: DateTime<Utc>) -> bool {
+        self.state.is_active() && !self.in_schedule(now)
+    }
+
+    /// Running in reduce-only mode: no new entries until it is turned off
+    pub fn winding_down(&self) -> bool {
+        self.state.is_active() && self.config.reduce_only
+    }
+
+    /// Decides if a DCA entry should be executed now, and why (None = no entry)
     /// LONG: buy; SHORT: sell base asset
-    pub fn should_buy(&self, current_price: f64, now: DateTime<Utc>, max_daily: f64) -> bool {
-        if !self.state.is_active() {
-            return false;
+    pub fn entry_trigger(&self, current_price: f64, now: DateTime<Utc>, max_daily: f64, amount: f64) -> Option<EntryTrigger> {
+        if !self.state.is_active() || self.watching() {
+            return None;
+        }
+
+        // Horario de operación: fuera de la ventana solo se gestionan salidas
+        if !self.in_schedule(now) {
+            return None;
+        }
+
+        // Reduce-only: solo se gestiona la salida de la posición abierta
+        if self.config.reduce_only {
+            return None;
         }
 
         // Cooldown post-TP
         if let Some(until) = self.cooldown_until {
             if now < until {
-                return false;
+                return None;
+            }
+        }
+
+        // Distribución: hasta vender todo el inventario; en escalera, al alcanzar cada nivel
+        if self.distributing() {
+            if self.remaining_inventory() <= 0.0 || self.daily_spent + amount > max_daily {
+                return None;
+            }
+            if !self.config.sell_levels.is_empty() {
+                return self
+                    .next_sell_level()
+                    .filter(|level| current_price >= *level)
+                    .map(|price| EntryTrigger::SellLevel { price });
             }
         }
 
+        // Acumulación / distribución: una operación por cada horario vencido desde la
+        // última (las perdidas con el bot apagado no se repiten)
+        if self.on_schedule() {
+            let due = self.config.buy_schedule.last_due(now);
+            let fire = self.daily_spent + amount <= max_daily && self.last_buy_time.is_some_and(|t| t < due);
+            return fire.then_some(EntryTrigger::Schedule);
+        }
+
         // Límite de órdenes
-        if self.trades.len() >= self.config.max_orders as usize {
-            return false;
+        if self.trades.len() >= self.max_entries() {
+            return None;
         }
 
         // Límite diario
-        if self.daily_spent + self.config.quote_amount > max_daily {
-            return false;
+        if self.daily_spent + amount > max_daily {
+            return None;
+        }
+
+        // Escalera de safety orders: solo por desviación de precio desde la orden base
+        if !self.config.safety_orders.is_empty() && !self.trades.is_empty() {
+            let (index, so) = self.next_safety_order()?;
+            let trigger = self.safety_order_trigger_price()?;
+            let reached = match self.config.direction {
+                Direction::Long => current_price <= trigger,
+                Direction::Short => current_price >= trigger,
+            };
+            return reached.then_some(EntryTrigger::SafetyOrder { number: index + 1, deviation_pct: so.deviation_pct });
         }
 
         // Trigger por tiempo
-        let last_time = match self.last_buy_time {
-            Some(t) => t,
-            None => return false,
-        };
+        let last_time = self.last_buy_time?;
         let elapsed = now.signed_duration_since(last_time).num_minutes();
         if elapsed >= self.config.interval_minutes as i64 {
-            return true;
+            return Some(EntryTrigger::Interval { minutes: elapsed });
         }
 
         // Trigger por movimiento de precio
@@ -196,13 +506,16 @@ impl DcaStrategy {
                         Direction::Short => ((current_price - last_price) / last_price) * 100.0,
                     };
                     if move_pct >= self.config.price_drop_trigger {
-                        return true;
+                        return Some(EntryTrigger::PriceMove {
+                            moved_pct: move_pct,
+                            trigger_pct: self.config.price_drop_trigger,
+                        });
                     }
                 }
             }
         }
 
-        false
+        None
     }
 
     // -----------------------------------------------------------
@@ -230,7 +543,7 @@ impl DcaStrategy {
     /// LONG: Trailing Take Profit: closes if price fell X% from the maximum AND is still in profit
     /// SHORT: Trailing Take Profit: closes if price rose X% from the minimum AND is still in profit
     pub fn should_trailing_tp(&self, current_price: f64) -> bool {
-        if self.trades.is_empty() || self.config.trailing_tp_pct <= 0.0 {
+        if self.trades.is_empty() || self.config.trailing_tp_pct <= 0.0 || !self.is_trading() {
             return false;
         }
         let avg = self.average_cost();
@@ -284,17 +597,43 @@ impl DcaStrategy {
     /// LONG: profit when price rises above average cost
     /// SHORT: profit when price falls below average sell price
     pub fn should_take_profit(&self, current_price: f64) -> bool {
-        if self.trades.is_empty() || self.config.take_profit_pct <= 0.0 {
+        let tp = self.effective_take_profit_pct();
+        if self.trades.is_empty() || tp <= 0.0 {
             return false;
         }
-        self.pnl_pct(current_price) >= self.config.take_profit_pct
+        self.pnl_pct(current_price) >= tp
+    }
+
+    /// Rough time until the take profit price is reached if price keeps moving at the
+    /// average rate seen since the first entry of the cycle (None if it drifts away)
+    pub fn eta_to_take_profit(&self, current_price: f64, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        let first = self.trades.first()?;
+        let tp = self.effective_take_profit_pct();
+        let elapsed = now.signed_duration_since(first.timestamp).num_seconds();
+        if tp <= 0.0 || elapsed <= 0 || first.buy_price <= 0.0 {
+            return None;
+        }
+        let avg = self.average_cost();
+        // Distancia restante y deriva por segundo, ambas a favor de la posición
+        let (remaining, drift) = match self.config.direction {
+            Direction::Long => (avg * (1.0 + tp / 100.0) - current_price, current_price - first.buy_price),
+            Direction::Short => (current_price - avg * (1.0 - tp / 100.0), first.buy_price - current_price),
+        };
+        let drift_per_sec = drift / elapsed as f64;
+        if remaining <= 0.0 {
+            return Some(chrono::Duration::zero());
+        }
+        if drift_per_sec <= 0.0 {
+            return None;
+        }
+        Some(chrono::Duration::seconds((remaining / drift_per_sec) as i64))
     }
 
     /// Decides if stop loss should be activated (close position)
     /// LONG: loss when price falls below average cost
     /// SHORT: loss when price rises above average sell price
     pub fn should_stop_loss(&self, current_price: f64) -> bool {
-        if self.trades.is_empty() || self.config.stop_loss_pct <= 0.0 {
+        if self.trades.is_empty() || self.config.stop_loss_pct <= 0.0 || !self.is_trading() {
             return false;
         }
         let avg = self.average_cost();
@@ -308,63 +647,306 @@ impl DcaStrategy {
         loss_pct >= self.config.stop_loss_pct
     }
 
+    // -----------------------------------------------------------
+    // Break-even stop
+    // -----------------------------------------------------------
+
+    /// Price at which closing the position returns exactly what was invested,
+    /// entry and exit commissions included (0 = no position)
+    pub fn breakeven_price(&self) -> f64 {
+        let qty = self.total_quantity();
+        if qty <= 0.0 {
+            return 0.0;
+        }
+        let entry_side = self.entry_fee_pct() / 100.0;
+        let exit_side = fees::side_fee_pct(self.taker_fee_pct, self.config.has_bnb_balance) / 100.0;
+        let invested = self.total_invested();
+        match self.config.direction {
+            Direction::Long => invested * (1.0 + entry_side) / (qty * (1.0 - exit_side)),
+            Direction::Short => invested * (1.0 - entry_side) / (qty * (1.0 + exit_side)),
+        }
+    }
+
+    /// Level of the break-even stop: the break-even price moved `breakeven_offset_pct`
+    /// in the position's favor (0 = no position)
+    pub fn breakeven_stop_price(&self) -> f64 {
+        let offset = self.config.breakeven_offset_pct.max(0.0) / 100.0;
+        let price = self.breakeven_price();
+        match self.config.direction {
+            Direction::Long => price * (1.0 + offset),
+            Direction::Short => price * (1.0 - offset),
+        }
+    }
+
+    /// Arms the break-even stop once P&L reaches `breakeven_trigger_pct`.
+    /// Returns true only when it gets armed by this call.
+    pub fn update_breakeven(&mut self, current_price: f64) -> bool {
+        if self.breakeven_armed
+            || self.trades.is_empty()
+            || self.config.breakeven_trigger_pct <= 0.0
+            || !self.is_trading()
+        {
+            return false;
+        }
+        self.breakeven_armed = self.pnl_pct(current_price) >= self.config.breakeven_trigger_pct;
+        self.breakeven_armed
+    }
+
+    /// Armed break-even stop hit: price came back to the break-even stop level
+    pub fn should_breakeven_stop(&self, current_price: f64) -> bool {
+        if !self.breakeven_armed || self.trades.is_empty() || self.config.breakeven_trigger_pct <= 0.0 {
+            return false;
+        }
+        let level = self.breakeven_stop_price();
+        match self.config.direction {
+            Direction::Long => current_price <= level,
+            Direction::Short => current_price >= level,
+        }
+    }
+
     // -----------------------------------------------------------
     // Mutaciones de estado
     // -----------------------------------------------------------
 
+    /// Records the slot creation with its full config (first event of every slot)
+    pub fn record_created(&mut self) {
+        let config = Box::new(self.config.clone());
+        self.emit(SlotEventKind::Created { config });
+    }
+
     pub fn start(&mut self) {
-        // Reset the interval timer whenever we start or restart the strategy
-        if self.state != DcaState::Running {
-            self.last_buy_time = Some(Utc::now());
-        }
-        self.cooldown_until = None;
-        self.state = DcaState::Running;
+        self.emit(SlotEventKind::Started { at: Utc::now(), cooldown_until: None });
     }
 
     /// Restarts after a TP/Trailing TP, applying a cooldown before the first re-entry
     pub fn start_after_tp(&mut self, cooldown_minutes: u64) {
         let now = Utc::now();
-        self.last_buy_time = Some(now);
-        self.cooldown_until = if cooldown_minutes > 0 {
+        let cooldown_until = if cooldown_minutes > 0 {
             Some(now + chrono::Duration::minutes(cooldown_minutes as i64))
         } else {
             None
         };
-        self.state = DcaState::Running;
+        self.emit(SlotEventKind::Started { at: now, cooldown_until });
     }
 
     pub fn stop(&mut self) {
         if self.state == DcaState::Running {
-            self.state = DcaState::Idle;
+            self.emit(SlotEventKind::Stopped);
         }
     }
 
-    /// Records a successful entry (buy in LONG, sell in SHORT)
-    pub fn record_buy(&mut self, order_id: u64, price: f64, quantity: f64, cost: f64) {
-        let now = Utc::now();
-        self.trades.push(DcaTrade::new(order_id, price, quantity, cost));
-        self.last_buy_time = Some(now);
-        self.last_buy_price = Some(price);
-        self.daily_spent += cost;
-        self.next_buy_in_secs = (self.config.interval_minutes * 60) as i64;
+    /// Explicit state transition (TP/SL reached, error, ...)
+    pub fn set_state(&mut self, state: DcaState) {
+        if self.state != state {
+            self.emit(SlotEventKind::StateChanged { state });
+        }
+    }
+
+    /// Records a successful entry (buy in LONG, sell in SHORT).
+    /// `expected_price` is the price when the entry was decided, `price` the average fill,
+    /// `fills` the breakdown reported by Binance (empty when unknown).
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_buy(
+        &mut self,
+        order_id: u64,
+        expected_price: f64,
+        price: f64,
+        quantity: f64,
+        cost: f64,
+        fills: Vec<TradeFill>,
+        trigger: Option<EntryTrigger>,
+    ) {
+        let trade = DcaTrade::new(order_id, expected_price, price, quantity, cost, fills, trigger);
+        self.emit(SlotEventKind::EntryRecorded { trade, external: false });
+    }
+
+    /// Records an entry made outside the bot (an imported or watched position), with its
+    /// own date. It does not count against `max_daily_spend`
+    pub fn record_entry(&mut self, trade: DcaTrade) {
+        self.emit(SlotEventKind::EntryRecorded { trade, external: true });
+    }
+
+    /// Records a closed position and clears its trades (TP / SL / manual)
+    pub fn record_exit(&mut self, kind: &str, received: f64, pnl: f64, commission: Option<Commission>) {
+        let quantity = self.total_quantity();
+        self.emit(SlotEventKind::ExitExecuted {
+            kind: kind.to_string(),
+            quantity,
+            received,
+            pnl,
+            commission,
+        });
+    }
+
+    /// Records a partial close (`fraction` in 0..1) keeping the rest of the position
+    pub fn record_partial_exit(&mut self, fraction: f64, received: f64, pnl: f64, commission: Option<Commission>) {
+        let quantity = self.total_quantity() * fraction;
+        self.emit(SlotEventKind::PartialExit { fraction, quantity, received, pnl, take_profit: false, commission });
+    }
+
+    /// Records a filled take profit level that closed only part of the position
+    pub fn record_take_profit_step(&mut self, fraction: f64, received: f64, pnl: f64, commission: Option<Commission>) {
+        let quantity = self.total_quantity() * fraction;
+        self.emit(SlotEventKind::PartialExit { fraction, quantity, received, pnl, take_profit: true, commission });
+    }
+
+    /// Collapses trades recorded more than once for the same order, keeping the largest
+    /// record of each. Returns the merged order IDs.
+    pub fn merge_duplicate_trades(&mut self) -> Vec<u64> {
+        let mut merged: Vec<u64> = Vec::new();
+        for trade in &self.trades {
+            let id = trade.order_id;
+            if id != 0 && !merged.contains(&id) && self.trades.iter().filter(|t| t.order_id == id).count() > 1 {
+                merged.push(id);
+            }
+        }
+        for &order_id in &merged {
+            let kept = self
+                .trades
+                .iter()
+                .filter(|t| t.order_id == order_id)
+                .max_by(|a, b| a.quantity.total_cmp(&b.quantity))
+                .cloned();
+            if let Some(kept) = kept {
+                self.emit(SlotEventKind::TradesMerged { order_id, kept });
+            }
+        }
+        merged
+    }
+
+    /// Scales the open position by `keep` (0..1) so it matches what the wallet holds
+    pub fn adjust_position(&mut self, keep: f64) {
+        self.emit(SlotEventKind::PositionAdjusted { keep: keep.clamp(0.0, 1.0) });
+    }
 
-        if self.trades.len() >= self.config.max_orders as usize {
-            self.state = DcaState::MaxOrdersReached;
+    /// Current value of one config field (as stored in ConfigChanged events)
+    pub fn config_value(&self, field: &str) -> Option<serde_json::Value> {
+        serde_json::to_value(&self.config).ok()?.get(field).cloned()
+    }
+
+    /// Changes one config field at runtime, keeping it in the event log
+    pub fn set_config_field<T: Serialize>(&mut self, field: &str, value: T) {
+        match serde_json::to_value(value) {
+            Ok(value) => self.emit(SlotEventKind::ConfigChanged { field: field.to_string(), value }),
+            Err(e) => tracing::warn!("Config field '{}' not serializable: {}", field, e),
         }
     }
 
     /// Clears trades after closing position (TP / SL)
     pub fn clear_trades(&mut self) {
         self.trades.clear();
+        self.tp_steps_done = 0;
         self.last_buy_time = None;
         self.last_buy_price = None;
         self.price_peak = 0.0;
         self.price_trough = f64::MAX;
+        self.breakeven_armed = false;
+    }
+
+    // -----------------------------------------------------------
+    // Event sourcing
+    // -----------------------------------------------------------
+
+    fn emit(&mut self, event: SlotEventKind) {
+        self.apply(&event);
+        self.pending_events.push(event);
+    }
+
+    /// Applies an event to the in-memory state. Every state mutation goes through here,
+    /// so replaying the event log reproduces the same state.
+    pub fn apply(&mut self, event: &SlotEventKind) {
+        match event {
+            SlotEventKind::Created { config } => {
+                self.config = (**config).clone();
+            }
+            SlotEventKind::Started { at, cooldown_until } => {
+                // Reset the interval timer whenever we start or restart the strategy
+                if self.state != DcaState::Running || cooldown_until.is_some() {
+                    self.last_buy_time = Some(*at);
+                }
+                self.cooldown_until = *cooldown_until;
+                self.state = DcaState::Running;
+            }
+            SlotEventKind::Stopped => {
+                if self.state == DcaState::Running {
+                    self.state = DcaState::Idle;
+                }
+            }
+            SlotEventKind::EntryRecorded { trade, external } => {
+                self.trades.push(trade.clone());
+                self.last_buy_time = Some(trade.timestamp);
+                self.last_buy_price = Some(trade.buy_price);
+                if !external {
+                    self.daily_spent += trade.cost;
+                }
+                self.next_buy_in_secs = self.entry_interval_secs();
+
+                if self.trades.len() >= self.max_entries() {
+                    self.state = DcaState::MaxOrdersReached;
+                }
+            }
+            SlotEventKind::ExitExecuted { pnl, .. } => {
+                if *pnl < 0.0 {
+                    self.consecutive_losses += 1;
+                } else {
+                    self.consecutive_losses = 0;
+                }
+                self.clear_trades();
+            }
+            SlotEventKind::PartialExit { fraction, take_profit, .. } => {
+                let keep = (1.0 - fraction).clamp(0.0, 1.0);
+                for trade in self.trades.iter_mut() {
+                    trade.quantity *= keep;
+                    trade.cost *= keep;
+                }
+                if *take_profit {
+                    self.tp_steps_done += 1;
+                }
+            }
+            SlotEventKind::TradesMerged { order_id, kept } => {
+                if let Some(pos) = self.trades.iter().position(|t| t.order_id == *order_id) {
+                    self.trades.retain(|t| t.order_id != *order_id);
+                    self.trades.insert(pos.min(self.trades.len()), kept.clone());
+                }
+            }
+            SlotEventKind::PositionAdjusted { keep } => {
+                if *keep <= 0.0 {
+                    self.clear_trades();
+                } else {
+                    for trade in self.trades.iter_mut() {
+                        trade.quantity *= keep;
+                        trade.cost *= keep;
+                    }
+                }
+            }
+            SlotEventKind::StateChanged { state } => {
+                self.state = state.clone();
+            }
+            SlotEventKind::ConfigChanged { field, value } => {
+                let updated = serde_json::to_value(&self.config).ok().and_then(|mut cfg| {
+                    cfg.as_object_mut()?.insert(field.clone(), value.clone());
+                    serde_json::from_value::<DcaConfig>(cfg).ok()
+                });
+                match updated {
+                    Some(cfg) => {
+                        self.config = cfg;
+                        self.config_overrides.insert(field.clone(), value.clone());
+                    }
+                    None => tracing::warn!("Ignoring invalid config event: {} = {}", field, value),
+                }
+            }
+            SlotEventKind::Removed => {}
+        }
+    }
+
+    /// Takes the events produced since the last call
+    pub fn drain_events(&mut self) -> Vec<SlotEventKind> {
+        std::mem::take(&mut self.pending_events)
     }
 
     /// Formats time until next entry as "MM:SS" (shows cooldown if active)
     pub fn next_buy_countdown(&self) -> String {
-        if !self.state.is_active() {
+        if !self.state.is_active() || self.config.reduce_only {
             return "--:--".to_string();
         }
         // During post-TP cooldown show remaining cooldown time
@@ -378,6 +960,9 @@ impl DcaStrategy {
             return "00:00".to_string();
         }
         let secs = self.next_buy_in_secs;
+        if secs >= 3600 {
+            return format!("{}h{:02}m", secs / 3600, secs % 3600 / 60);
+        }
         format!("{:02}:{:02}", secs / 60, secs % 60)
     }
 
@@ -385,6 +970,9 @@ impl DcaStrategy {
         StrategySnapshot {
             symbol: symbol.to_string(),
             direction: self.config.direction.clone(),
+            kind: self.config.kind,
+            auto_restart: Some(self.config.auto_restart),
+            auto_flip: Some(self.config.auto_flip),
             trades: self.trades.clone(),
             last_buy_time: self.last_buy_time,
             last_buy_price: self.last_buy_price,
@@ -392,15 +980,33 @@ impl DcaStrategy {
             last_reset_day: self.last_reset_day,
             price_peak: self.price_peak,
             price_trough: self.price_trough,
+            breakeven_armed: self.breakeven_armed,
             has_bnb_balance: self.config.has_bnb_balance,
             state: self.state.clone(),
             cooldown_until: self.cooldown_until,
+            consecutive_losses: self.consecutive_losses,
+            tp_steps_done: self.tp_steps_done,
+            group: self.config.group.clone(),
+            slot_id: None,
+            last_event_seq: 0,
+            config_overrides: self.config_overrides.clone(),
+            open_order: None,
+            exit_pending: None,
+            analysis_interval: None,
+            performance: None,
         }
     }
 
     /// Restores state from a snapshot
     pub fn restore_from_snapshot(&mut self, snapshot: StrategySnapshot) {
         self.config.direction = snapshot.direction;
+        self.config.kind = snapshot.kind;
+        if let Some(auto_restart) = snapshot.auto_restart {
+            self.config.auto_restart = auto_restart;
+        }
+        if let Some(auto_flip) = snapshot.auto_flip {
+            self.config.auto_flip = auto_flip;
+        }
         self.config.has_bnb_balance = snapshot.has_bnb_balance;
         self.trades = snapshot.trades;
         self.last_buy_time = snapshot.last_buy_time;
@@ -409,8 +1015,15 @@ impl DcaStrategy {
         self.last_reset_day = snapshot.last_reset_day;
         self.price_peak = snapshot.price_peak;
         self.price_trough = snapshot.price_trough;
+        self.breakeven_armed = snapshot.breakeven_armed;
         self.state = snapshot.state;
         self.cooldown_until = snapshot.cooldown_until;
+        self.consecutive_losses = snapshot.consecutive_losses;
+        self.tp_steps_done = snapshot.tp_steps_done;
+        self.config.group = snapshot.group;
+        for (field, value) in snapshot.config_overrides {
+            self.apply(&SlotEventKind::ConfigChanged { field, value });
+        }
     }
 }
 
@@ -418,52 +1031,99 @@ impl DcaStrategy {
 // Persistencia del estado de la estrategia
 // ---------------------------------------------------------------------------
 
+/// Version of the snapshot layout below, stored in the state file. Any change to it (a new
+/// field included), or to the structs nested in it (trades, open order, pending exit), bumps
+/// this and adds the upgrade step to `state_file::MIGRATIONS`.
+pub const STATE_SCHEMA_VERSION: u32 = 5;
+
 /// Serializable snapshot of DCA state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StrategySnapshot {
     pub symbol: String,
-    /// Dirección de la estrategia (long/short). Default = long para compatibilidad.
-    #[serde(default)]
+    /// Dirección de la estrategia (long/short)
     pub direction: Direction,
+    /// What the slot does with its entries (trading, accumulate, distribute, watch)
+    pub kind: StrategyKind,
+    /// Restart after each closed cycle; None in files from before v5 (`[dca]`'s value)
+    pub auto_restart: Option<bool>,
+    /// Flip direction after each closed cycle; None in files from before v5 (`[dca]`'s value)
+    pub auto_flip: Option<bool>,
     pub trades: Vec<DcaTrade>,
     pub last_buy_time: Option<DateTime<Utc>>,
     pub last_buy_price: Option<f64>,
     pub daily_spent: f64,
     pub last_reset_day: u32,
-    #[serde(default)]
     pub price_peak: f64,
-    #[serde(default = "default_trough")]
     pub price_trough: f64,
+    /// Break-even stop armed in the open position
+    pub breakeven_armed: bool,
     /// If true, use BNB for fees (lower fee calculations possible)
-    #[serde(default)]
     pub has_bnb_balance: bool,
     /// Current state of the strategy
-    #[serde(default = "default_state")]
     pub state: DcaState,
     /// Post-TP cooldown expiry timestamp (None = no cooldown)
-    #[serde(default)]
     pub cooldown_until: Option<DateTime<Utc>>,
+    /// Cycles in a row closed at a loss (health score)
+    pub consecutive_losses: u32,
+    /// Take profit levels already filled in the open position
+    pub tp_steps_done: usize,
+    /// Slot group (shared budget)
+    pub group: Option<String>,
+    /// Slot ID, so events in the log can be matched back to this snapshot
+    pub slot_id: Option<usize>,
+    /// Last event-log sequence already contained in this snapshot
+    pub last_event_seq: u64,
+    /// Config fields changed at runtime (editor, palette), re-applied over config.toml on restore
+    pub config_overrides: BTreeMap<String, serde_json::Value>,
+    /// Entry limit order still open on Binance when the snapshot was taken
+    pub open_order: Option<OpenOrder>,
+    /// Exit still waiting for Binance to be reachable again
+    pub exit_pending: Option<PendingExit>,
+    /// Candle interval of the slot's chart and S/R levels
+    pub analysis_interval: Option<String>,
+    /// Lifetime stats up to `last_event_seq`, so pruning the event log does not reset them
+    pub performance: Option<SlotPerformance>,
 }
 
-fn default_state() -> DcaState {
-    DcaState::Idle
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, TakeProfit};
 
-fn default_trough() -> f64 {
-    f64::MAX
-}
+    #[test]
+    fn exit_at_the_fee_floor_nets_the_minimum_profit() {
+        let config: Config = toml::from_str(include_str!("../../config.toml")).expect("config.toml parses");
+        for direction in [Direction::Long, Direction::Short] {
+            for has_bnb_balance in [false, true] {
+                let mut dca = config.dca.clone();
+                dca.direction = direction.clone();
+                dca.has_bnb_balance = has_bnb_balance;
+                // Below the floor, so it gets raised to it
+                dca.take_profit_pct = TakeProfit::Single(0.01);
+                let mut strat = DcaStrategy::new(dca);
+                strat.record_buy(1, 100.0, 100.0, 2.0, 200.0, Vec::new(), None);
+                let floor = strat.effective_take_profit_pct();
+                assert_eq!(floor, strat.min_take_profit_pct());
 
-impl StrategySnapshot {
-    /// Guarda el snapshot en disco como JSON
-    pub fn save(&self, path: &std::path::Path) -> anyhow::Result<()> {
-        let json = serde_json::to_string_pretty(self)?;
-        std::fs::write(path, json)?;
-        Ok(())
-    }
+                // Price at which pnl_pct (already net of the exit fee) lands exactly on the floor
+                let (invested, qty) = (strat.total_invested(), strat.total_quantity());
+                let exit_side = fees::side_fee_pct(strat.taker_fee_pct, has_bnb_balance) / 100.0;
+                let price = match direction {
+                    Direction::Long => invested * (1.0 + floor / 100.0) / (qty * (1.0 - exit_side)),
+                    Direction::Short => invested * (1.0 - floor / 100.0) / (qty * (1.0 + exit_side)),
+                };
+                assert!((strat.pnl_pct(price) - floor).abs() < 1e-9);
 
-    /// Carga el snapshot desde disco; devuelve None si no existe o está corrupto
-    pub fn load(path: &std::path::Path) -> Option<Self> {
-        let content = std::fs::read_to_string(path).ok()?;
-        serde_json::from_str(&content).ok()
+                let net = strat.pnl(price) - invested * strat.entry_fee_pct() / 100.0;
+                let net_pct = net / invested * 100.0;
+                assert!(
+                    (net_pct - strat.config.min_net_profit_pct).abs() < 1e-9,
+                    "{:?} bnb={}: nets {}%",
+                    direction,
+                    has_bnb_balance,
+                    net_pct
+                );
+            }
+        }
     }
 }
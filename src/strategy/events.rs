@@ -0,0 +1,208 @@
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::DcaConfig;
+use crate::models::order::{Commission, DcaTrade};
+use crate::strategy::dca::DcaState;
+
+/// A single fact about a slot. Current slot state is derived by applying these in order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SlotEventKind {
+    /// Slot created with its full strategy configuration
+    Created { config: Box<DcaConfig> },
+    /// Strategy (re)started; `cooldown_until` is set after a TP restart
+    Started {
+        at: DateTime<Utc>,
+        cooldown_until: Option<DateTime<Utc>>,
+    },
+    /// Strategy stopped by the user or by an error
+    Stopped,
+    /// Entry filled (buy in LONG, sell in SHORT)
+    EntryRecorded {
+        trade: DcaTrade,
+        /// Bought outside the bot (imported or watched position): not part of today's spend
+        #[serde(default)]
+        external: bool,
+    },
+    /// Position closed (TP, trailing TP, SL or manual close)
+    ExitExecuted {
+        kind: String,
+        quantity: f64,
+        received: f64,
+        pnl: f64,
+        /// Commission charged on the exit order (None = not reported)
+        #[serde(default)]
+        commission: Option<Commission>,
+    },
+    /// Part of the position closed; every trade is scaled down by `fraction`
+    PartialExit {
+        fraction: f64,
+        quantity: f64,
+        received: f64,
+        pnl: f64,
+        /// True when a take profit level closed it (advances the TP ladder)
+        #[serde(default)]
+        take_profit: bool,
+        #[serde(default)]
+        commission: Option<Commission>,
+    },
+    /// Several trades recorded for the same order collapsed into `kept`
+    TradesMerged { order_id: u64, kept: DcaTrade },
+    /// Position resized to match the wallet on restore; every trade is scaled by `keep`
+    PositionAdjusted { keep: f64 },
+    /// Explicit state transition (TAKE PROFIT, STOP LOSS, ...)
+    StateChanged { state: DcaState },
+    /// A single config field changed at runtime
+    ConfigChanged {
+        field: String,
+        value: serde_json::Value,
+    },
+    /// Slot deleted by the user
+    Removed,
+}
+
+/// Event as stored in the append-only log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotEvent {
+    /// Global, strictly increasing sequence number
+    pub seq: u64,
+    pub slot_id: usize,
+    pub symbol: String,
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: SlotEventKind,
+}
+
+/// Append-only event log (one JSON event per line)
+pub struct EventLog {
+    file: Arc<EventFile>,
+    next_seq: u64,
+    buffer: Vec<SlotEvent>,
+}
+
+/// The log file. Appends and rewrites go through one lock, so the maintenance task can prune
+/// it from `spawn_blocking` while the engine keeps appending.
+pub struct EventFile {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl EventLog {
+    /// Opens the log and resumes the sequence after the last stored event
+    pub fn open(path: &Path) -> Self {
+        let next_seq = Self::load(path).last().map(|e| e.seq + 1).unwrap_or(1);
+        Self {
+            file: Arc::new(EventFile { path: path.to_path_buf(), lock: Mutex::new(()) }),
+            next_seq,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Reads every event from disk; malformed lines (e.g. a torn last write) are skipped
+    pub fn load(path: &Path) -> Vec<SlotEvent> {
+        let file = match std::fs::File::open(path) {
+            Ok(f) => f,
+            Err(_) => return vec![],
+        };
+        std::io::BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str::<SlotEvent>(&line).ok())
+            .collect()
+    }
+
+    /// Every event on disk (the queued ones and the ones the state writer has not appended
+    /// yet are missing)
+    pub fn events(&self) -> Vec<SlotEvent> {
+        self.file.events()
+    }
+
+    /// Shared handle to the file, for I/O away from the app state
+    pub fn file(&self) -> Arc<EventFile> {
+        Arc::clone(&self.file)
+    }
+
+
+    /// Sequence number of the last event handed to the log (0 = empty)
+    pub fn last_seq(&self) -> u64 {
+        self.next_seq - 1
+    }
+
+    /// Queues an event; it reaches disk once `drain` hands it to the state writer
+    pub fn push(&mut self, slot_id: usize, symbol: &str, kind: SlotEventKind) {
+        self.buffer.push(SlotEvent {
+            seq: self.next_seq,
+            slot_id,
+            symbol: symbol.to_string(),
+            timestamp: Utc::now(),
+            kind,
+        });
+        self.next_seq += 1;
+    }
+
+    /// Takes the queued events, to be appended with `EventFile::append` away from the app
+    /// state lock
+    pub fn drain(&mut self) -> Vec<SlotEvent> {
+        std::mem::take(&mut self.buffer)
+    }
+}
+
+impl EventFile {
+    fn guard(&self) -> std::sync::MutexGuard<'_, ()> {
+        self.lock.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Every event in the file, read without racing an append or a prune
+    pub fn events(&self) -> Vec<SlotEvent> {
+        let _guard = self.guard();
+        EventLog::load(&self.path)
+    }
+
+    /// Drops the events older than `before` whose sequence is at most `covered` (already in
+    /// a saved snapshot, so no restart replays them) and rewrites the file. The newest event
+    /// is always kept so the sequence resumes after it. Returns how many were dropped.
+    pub fn prune(&self, before: DateTime<Utc>, covered: u64) -> anyhow::Result<usize> {
+        let _guard = self.guard();
+        let events = EventLog::load(&self.path);
+        let newest = events.last().map(|e| e.seq);
+        let total = events.len();
+        let kept: Vec<SlotEvent> = events
+            .into_iter()
+            .filter(|e| e.timestamp >= before || e.seq > covered || Some(e.seq) == newest)
+            .collect();
+        if kept.len() == total {
+            return Ok(0);
+        }
+        let mut out = String::new();
+        for event in &kept {
+            out.push_str(&serde_json::to_string(event)?);
+            out.push('\n');
+        }
+        // Through a temporary file: a crash mid-write must not lose the log
+        let tmp = self.path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, out)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(total - kept.len())
+    }
+
+    /// Appends `events` in one write
+    pub fn append(&self, events: &[SlotEvent]) -> anyhow::Result<()> {
+        let mut out = String::new();
+        for event in events {
+            out.push_str(&serde_json::to_string(event)?);
+            out.push('\n');
+        }
+        let _guard = self.guard();
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(out.as_bytes())?;
+        Ok(())
+    }
+}
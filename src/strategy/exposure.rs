@@ -0,0 +1,27 @@
+use crate::config::ExposureLimit;
+
+/// Checks whether a LONG entry spending `amount` quote at `price` keeps `asset` within
+/// `limit`. `held` is the position already counted against the cap (the larger of the
+/// account holding and the slots' positions, as the balance lags the fills) and `pending`
+/// the base quantity of resting entry orders. Returns the reason when the entry must be
+/// skipped.
+pub fn check_entry(
+    asset: &str,
+    limit: ExposureLimit,
+    held: f64,
+    pending: f64,
+    price: f64,
+    amount: f64,
+) -> Result<(), String> {
+    if price <= 0.0 {
+        return Err(format!("{} exposure cap: no price to value the entry", asset));
+    }
+    let current = held + pending;
+    if limit.allows(current + amount / price, price) {
+        return Ok(());
+    }
+    Err(match limit {
+        ExposureLimit::Base(max) => format!("{} exposure cap: {:.6} of {} {} held", asset, current, max, asset),
+        ExposureLimit::Quote(max) => format!("{} exposure cap: ${:.2} of ${:.2} held", asset, current * price, max),
+    })
+}
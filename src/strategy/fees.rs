@@ -0,0 +1,63 @@
+use crate::config::FeesConfig;
+use crate::models::account::AccountInfo;
+
+/// Binance Spot standard taker commission per side, in %
+pub const TAKER_FEE_PCT: f64 = 0.1;
+/// Discount applied when commissions are paid with BNB
+pub const BNB_DISCOUNT: f64 = 0.25;
+
+/// Account commission rates per side, in %
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommissionRates {
+    pub maker_pct: f64,
+    pub taker_pct: f64,
+}
+
+impl Default for CommissionRates {
+    fn default() -> Self {
+        Self {
+            maker_pct: TAKER_FEE_PCT,
+            taker_pct: TAKER_FEE_PCT,
+        }
+    }
+}
+
+impl CommissionRates {
+    /// Binance reports commissions in basis points (10 = 0.1%)
+    pub fn from_account(account: &AccountInfo) -> Self {
+        Self {
+            maker_pct: account.maker_commission as f64 / 100.0,
+            taker_pct: account.taker_commission as f64 / 100.0,
+        }
+    }
+
+    /// Rates that apply to `symbol`: its `[fees.symbols]` entry first, then the
+    /// `[fees]` rates, then these (account) rates
+    pub fn for_symbol(self, fees: &FeesConfig, symbol: &str) -> Self {
+        let symbol_rates = fees.symbols.get(symbol);
+        Self {
+            maker_pct: symbol_rates
+                .and_then(|r| r.maker_pct)
+                .or(fees.maker_pct)
+                .unwrap_or(self.maker_pct),
+            taker_pct: symbol_rates
+                .and_then(|r| r.taker_pct)
+                .or(fees.taker_pct)
+                .unwrap_or(self.taker_pct),
+        }
+    }
+}
+
+/// Commission per side in %, considering the BNB discount
+pub fn side_fee_pct(taker_pct: f64, has_bnb: bool) -> f64 {
+    if has_bnb {
+        taker_pct * (1.0 - BNB_DISCOUNT)
+    } else {
+        taker_pct
+    }
+}
+
+/// Commission of a full cycle (entry + exit) in %, each side at its own rate
+pub fn round_trip_fee_pct(entry_pct: f64, exit_pct: f64, has_bnb: bool) -> f64 {
+    side_fee_pct(entry_pct, has_bnb) + side_fee_pct(exit_pct, has_bnb)
+}
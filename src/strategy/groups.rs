@@ -0,0 +1,29 @@
+use crate::config::GroupConfig;
+use crate::strategy::dca::DcaStrategy;
+
+/// Checks whether a new entry of `amount` fits the group's shared limits.
+/// `members` are the strategies of every slot in the group (including the one entering).
+/// Returns the reason when the entry must be skipped.
+pub fn check_entry<'a>(
+    group: &GroupConfig,
+    members: impl Iterator<Item = &'a DcaStrategy>,
+    amount: f64,
+) -> Result<(), String> {
+    let (deployed, spent_today) = members.fold((0.0, 0.0), |(d, s), m| {
+        (d + m.total_invested(), s + m.daily_spent)
+    });
+
+    if group.budget > 0.0 && deployed + amount > group.budget {
+        return Err(format!(
+            "group '{}' budget used: ${:.2} of ${:.2}",
+            group.name, deployed, group.budget
+        ));
+    }
+    if group.max_daily_spend > 0.0 && spent_today + amount > group.max_daily_spend {
+        return Err(format!(
+            "group '{}' daily limit: ${:.2} of ${:.2}",
+            group.name, spent_today, group.max_daily_spend
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+
+use crate::strategy::dca::DcaStrategy;
+
+/// Overall health of a slot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HealthLevel {
+    Good,
+    Warning,
+    Critical,
+}
+
+impl HealthLevel {
+    pub fn label(&self) -> &str {
+        match self {
+            HealthLevel::Good => "GOOD",
+            HealthLevel::Warning => "WARN",
+            HealthLevel::Critical => "BAD",
+        }
+    }
+}
+
+/// Score (0-100) plus human-readable suggestions, worst problem first
+#[derive(Debug, Clone)]
+pub struct HealthReport {
+    pub score: u8,
+    pub level: HealthLevel,
+    pub suggestions: Vec<String>,
+}
+
+/// Computes the health of a slot from its recent behaviour.
+/// `api_calls`/`api_errors` are the order requests sent and failed during this session.
+pub fn evaluate(
+    strategy: &DcaStrategy,
+    api_calls: u32,
+    api_errors: u32,
+    now: DateTime<Utc>,
+) -> HealthReport {
+    // (penalty, suggestion)
+    let mut issues: Vec<(u32, String)> = Vec::new();
+
+    // Fee-to-profit: round-trip commissions eat the take profit. Judged on the configured TP,
+    // since exits already use it raised to the fee floor
+    if let Some(ratio) = strategy.fee_to_configured_tp_ratio() {
+        let tp = strategy.configured_take_profit_pct();
+        let raised = strategy.effective_take_profit_pct();
+        if raised > tp {
+            issues.push((50, format!("TP {:.2}% doesn't cover fees, raised to {:.2}%", tp, raised)));
+        } else if ratio >= 0.5 {
+            issues.push((20, format!("TP too tight for fees ({:.0}% of TP)", ratio * 100.0)));
+        }
+    }
+
+    // Consecutive losing cycles
+    match strategy.consecutive_losses {
+        0 => {}
+        1 => issues.push((10, "Last cycle closed at a loss".to_string())),
+        n => issues.push((
+            (15 * n).min(45),
+            format!("{} losing cycles in a row, review SL/TP", n),
+        )),
+    }
+
+    // Time since last fill vs configured interval (ladder steps wait for price, not time)
+    if strategy.state.is_active() && strategy.cooldown_until.is_none_or(|t| t <= now) {
+        if let Some(last) = strategy.trades.last().map(|t| t.timestamp).or(strategy.last_buy_time) {
+            let interval_secs = strategy.entry_interval_secs().max(60);
+            let idle = now.signed_duration_since(last).num_seconds();
+            let waiting_on_time = strategy.next_safety_order().is_none()
+                && !strategy.paused_by_schedule(now)
+                && !strategy.config.reduce_only;
+            if strategy.trades.len() < strategy.max_entries() && waiting_on_time && idle > interval_secs * 3 {
+                issues.push((20, "No fills for 3+ intervals (balance/daily limit?)".to_string()));
+            }
+        }
+    }
+
+    // API error rate
+    if api_calls >= 3 {
+        let rate = api_errors as f64 / api_calls as f64;
+        if rate >= 0.5 {
+            issues.push((30, format!("{:.0}% of orders failing", rate * 100.0)));
+        } else if rate >= 0.2 {
+            issues.push((15, format!("{:.0}% of orders failing", rate * 100.0)));
+        }
+    }
+
+    issues.sort_by_key(|(p, _)| std::cmp::Reverse(*p));
+    let penalty: u32 = issues.iter().map(|(p, _)| *p).sum();
+    let score = 100u32.saturating_sub(penalty) as u8;
+    let level = match score {
+        75..=100 => HealthLevel::Good,
+        40..=74 => HealthLevel::Warning,
+        _ => HealthLevel::Critical,
+    };
+
+    HealthReport {
+        score,
+        level,
+        suggestions: issues.into_iter().map(|(_, s)| s).collect(),
+    }
+}
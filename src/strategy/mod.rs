@@ -1 +1,9 @@
+pub mod cycles;
 pub mod dca;
+pub mod events;
+pub mod exposure;
+pub mod fees;
+pub mod health;
+pub mod performance;
+pub mod sizing;
+pub mod groups;
@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::order::Commission;
+use crate::strategy::events::{SlotEvent, SlotEventKind};
+
+/// Realized P&L of a closed (or partially closed) position
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RealizedPnl {
+    pub at: DateTime<Utc>,
+    pub pnl: f64,
+}
+
+/// Per-slot history derived from the event log: realized P&L, cycle durations and
+/// execution costs (slippage and commissions). It is checkpointed in the state file, so it
+/// outlives the events the maintenance task prunes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SlotPerformance {
+    pub realized: Vec<RealizedPnl>,
+    /// Duration of every completed cycle (first entry to full exit), oldest first
+    #[serde(with = "seconds")]
+    pub cycle_durations: Vec<Duration>,
+    /// Entry fill vs expected price in % (positive = filled above it), one per entry
+    pub slippage_pct: Vec<f64>,
+    /// Commissions actually paid on entries and exits, by asset
+    pub fees_paid: BTreeMap<String, f64>,
+    /// First entry of the cycle in progress
+    cycle_started: Option<DateTime<Utc>>,
+}
+
+impl SlotPerformance {
+    /// Rebuilds the history of one slot from the event log
+    pub fn from_events(events: &[SlotEvent], slot_id: usize) -> Self {
+        Self::default().replay(events, slot_id, 0)
+    }
+
+    /// Continues a checkpoint that covers the log up to sequence `covered` with the later
+    /// events of the slot
+    pub fn replay(mut self, events: &[SlotEvent], slot_id: usize, covered: u64) -> Self {
+        for e in events.iter().filter(|e| e.slot_id == slot_id && e.seq > covered) {
+            self.observe(&e.kind, e.timestamp);
+        }
+        self
+    }
+
+    /// Updates the history with one event that happened at `at`
+    pub fn observe(&mut self, kind: &SlotEventKind, at: DateTime<Utc>) {
+        match kind {
+            SlotEventKind::EntryRecorded { trade, .. } => {
+                self.cycle_started.get_or_insert(trade.timestamp);
+                self.slippage_pct.extend(trade.slippage_pct());
+                self.add_fee(trade.commission.as_ref());
+            }
+            SlotEventKind::ExitExecuted { pnl, commission, .. } => {
+                self.realized.push(RealizedPnl { at, pnl: *pnl });
+                if let Some(start) = self.cycle_started.take() {
+                    self.cycle_durations.push(at - start);
+                }
+                self.add_fee(commission.as_ref());
+            }
+            SlotEventKind::PartialExit { pnl, commission, .. } => {
+                self.realized.push(RealizedPnl { at, pnl: *pnl });
+                self.add_fee(commission.as_ref());
+            }
+            _ => {}
+        }
+    }
+
+    fn add_fee(&mut self, commission: Option<&Commission>) {
+        if let Some(c) = commission.filter(|c| c.amount > 0.0) {
+            *self.fees_paid.entry(c.asset.clone()).or_insert(0.0) += c.amount;
+        }
+    }
+
+    /// Sum of the P&L of every full and partial exit
+    pub fn realized_total(&self) -> f64 {
+        self.realized.iter().map(|r| r.pnl).sum()
+    }
+
+    /// Average entry slippage in % (None until an entry with a known expected price)
+    pub fn avg_slippage_pct(&self) -> Option<f64> {
+        if self.slippage_pct.is_empty() {
+            return None;
+        }
+        Some(self.slippage_pct.iter().sum::<f64>() / self.slippage_pct.len() as f64)
+    }
+
+    /// Median duration of the completed cycles
+    pub fn median_cycle(&self) -> Option<Duration> {
+        let mut sorted = self.cycle_durations.clone();
+        sorted.sort();
+        sorted.get(sorted.len() / 2).copied()
+    }
+}
+
+/// Realized P&L per UTC day for the last `days` days ending at `today`, oldest first
+pub fn daily_pnl(history: &[RealizedPnl], days: usize, today: NaiveDate) -> Vec<f64> {
+    let mut buckets = vec![0.0; days];
+    for r in history {
+        let age = (today - r.at.date_naive()).num_days();
+        if age >= 0 && (age as usize) < days {
+            buckets[days - 1 - age as usize] += r.pnl;
+        }
+    }
+    buckets
+}
+
+/// Cycle durations as whole seconds (chrono's `Duration` has no serde form)
+mod seconds {
+    use chrono::Duration;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(durations: &[Duration], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(durations.iter().map(|d| d.num_seconds()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Duration>, D::Error> {
+        Ok(Vec::<i64>::deserialize(deserializer)?.into_iter().map(Duration::seconds).collect())
+    }
+}
@@ -0,0 +1,47 @@
+use crate::models::ticker::Kline;
+
+/// Multiplier bounds so a very calm or very wild symbol can't dominate the allocation
+const MIN_MULTIPLIER: f64 = 0.25;
+const MAX_MULTIPLIER: f64 = 4.0;
+
+/// Average True Range over the last `period` closed candles, as % of the last close.
+/// Needs `period + 1` candles (each true range uses the previous close).
+pub fn atr_pct(klines: &[Kline], period: usize) -> Option<f64> {
+    if period == 0 || klines.len() < period + 1 {
+        return None;
+    }
+    let window = &klines[klines.len() - period - 1..];
+    let atr = window
+        .windows(2)
+        .map(|w| {
+            let (prev, k) = (&w[0], &w[1]);
+            (k.high - k.low)
+                .max((k.high - prev.close).abs())
+                .max((k.low - prev.close).abs())
+        })
+        .sum::<f64>()
+        / period as f64;
+    let last_close = window.last()?.close;
+    if last_close <= 0.0 || atr <= 0.0 {
+        return None;
+    }
+    Some(atr / last_close * 100.0)
+}
+
+/// Risk-parity multipliers: each slot is weighted by 1/volatility, normalised so the
+/// average multiplier is 1 (total allocation stays roughly the same as unscaled sizing).
+/// Input and output are `(slot_id, value)` pairs.
+pub fn risk_parity_multipliers(vols: &[(usize, f64)]) -> Vec<(usize, f64)> {
+    let inv: Vec<(usize, f64)> = vols
+        .iter()
+        .filter(|(_, v)| *v > 0.0)
+        .map(|(id, v)| (*id, 1.0 / v))
+        .collect();
+    if inv.is_empty() {
+        return vec![];
+    }
+    let mean = inv.iter().map(|(_, w)| w).sum::<f64>() / inv.len() as f64;
+    inv.into_iter()
+        .map(|(id, w)| (id, (w / mean).clamp(MIN_MULTIPLIER, MAX_MULTIPLIER)))
+        .collect()
+}
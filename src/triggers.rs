@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::order::OrderSide;
+
+/// Price move that fires a conditional order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerCondition {
+    /// Price falls to the trigger or below
+    AtOrBelow,
+    /// Price rises to the trigger or above
+    AtOrAbove,
+}
+
+impl TriggerCondition {
+    /// Condition that waits for the price to move from `price` to `trigger`
+    pub fn toward(trigger: f64, price: f64) -> Self {
+        if trigger <= price { TriggerCondition::AtOrBelow } else { TriggerCondition::AtOrAbove }
+    }
+
+    pub fn met(&self, trigger: f64, price: f64) -> bool {
+        match self {
+            TriggerCondition::AtOrBelow => price <= trigger,
+            TriggerCondition::AtOrAbove => price >= trigger,
+        }
+    }
+
+    pub fn sign(&self) -> &'static str {
+        match self {
+            TriggerCondition::AtOrBelow => "≤",
+            TriggerCondition::AtOrAbove => "≥",
+        }
+    }
+}
+
+/// One-shot market order fired when a symbol's price reaches `trigger`, outside any strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalOrder {
+    pub id: u64,
+    pub symbol: String,
+    pub side: OrderSide,
+    /// Buy: quote amount to spend. Sell: base quantity to sell
+    pub amount: f64,
+    pub trigger: f64,
+    pub condition: TriggerCondition,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ConditionalOrder {
+    /// Amount as typed: "$50.00" for buys, "0.010000" for sells
+    pub fn amount_label(&self) -> String {
+        match self.side {
+            OrderSide::Buy => format!("${:.2}", self.amount),
+            OrderSide::Sell => format!("{:.6}", self.amount),
+        }
+    }
+
+    /// One-line description for the log, e.g. "BUY $50.00 BTCUSDT when ≤ $60000.0000"
+    pub fn describe(&self) -> String {
+        let side = match self.side {
+            OrderSide::Buy => "BUY",
+            OrderSide::Sell => "SELL",
+        };
+        format!(
+            "{} {} {} when {} ${:.4}",
+            side,
+            self.amount_label(),
+            self.symbol,
+            self.condition.sign(),
+            self.trigger
+        )
+    }
+}
+
+/// Conditional orders waiting for their price, persisted as JSON so they survive restarts
+#[derive(Debug, Default)]
+pub struct TriggerBook {
+    orders: Vec<ConditionalOrder>,
+    path: Option<PathBuf>,
+}
+
+impl TriggerBook {
+    /// Loads the book saved at `path` (empty when the file does not exist yet)
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let orders = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .with_context(|| format!("Invalid conditional orders file {:?}", path))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("Could not read {:?}", path)),
+        };
+        Ok(Self { orders, path: Some(path) })
+    }
+
+    pub fn orders(&self) -> &[ConditionalOrder] {
+        &self.orders
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Symbols that need a live price
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.orders.iter().map(|o| o.symbol.as_str())
+    }
+
+    /// Adds an order that fires when the price moves from `price` to `trigger`
+    pub fn add(&mut self, symbol: String, side: OrderSide, amount: f64, trigger: f64, price: f64) -> ConditionalOrder {
+        let id = self.orders.iter().map(|o| o.id).max().unwrap_or(0) + 1;
+        let order = ConditionalOrder {
+            id,
+            symbol,
+            side,
+            amount,
+            trigger,
+            condition: TriggerCondition::toward(trigger, price),
+            created_at: Utc::now(),
+        };
+        self.orders.push(order.clone());
+        order
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<ConditionalOrder> {
+        let index = self.orders.iter().position(|o| o.id == id)?;
+        Some(self.orders.remove(index))
+    }
+
+    /// Removes and returns the orders whose condition is met. `price` gives the current
+    /// price of a symbol, or None when it is unknown or too old to act on.
+    pub fn take_triggered(&mut self, price: impl Fn(&str) -> Option<f64>) -> Vec<ConditionalOrder> {
+        let (fired, waiting) = std::mem::take(&mut self.orders)
+            .into_iter()
+            .partition(|o| price(&o.symbol).is_some_and(|p| o.condition.met(o.trigger, p)));
+        self.orders = waiting;
+        fired
+    }
+
+    /// File and contents to save, written by the state writer away from the app state lock
+    /// (None for a book without a file)
+    pub fn contents(&self) -> Result<Option<(PathBuf, String)>> {
+        let Some(path) = &self.path else { return Ok(None) };
+        Ok(Some((path.clone(), serde_json::to_string_pretty(&self.orders)?)))
+    }
+}
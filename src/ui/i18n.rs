@@ -0,0 +1,638 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{OnceLock, RwLock};
+
+use crate::config::Language;
+
+/// Idioma de `[ui] language`. Global como el formato de `app::fmt_*`:
+/// lo leen el render, el footer y la ayuda; se actualiza al recargar config.toml
+static LANGUAGE: RwLock<Language> = RwLock::new(Language::En);
+
+/// Aplica `[ui] language` a todos los textos del dashboard
+pub fn set_language(language: Language) {
+    if let Ok(mut current) = LANGUAGE.write() {
+        *current = language;
+    }
+}
+
+/// `text` (en inglés, la clave del catálogo) en el idioma elegido. Sin traducción se
+/// muestra tal cual
+pub fn tr(text: &str) -> &str {
+    match LANGUAGE.read().map(|l| *l).unwrap_or_default() {
+        Language::En => text,
+        Language::Es => {
+            static INDEX: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+            INDEX.get_or_init(|| ES.iter().copied().collect()).get(text).copied().unwrap_or(text)
+        }
+    }
+}
+
+/// Como `tr` con los `{}` de la plantilla reemplazados por `args` en orden (los formatos con
+/// decimales se aplican antes, al armar los argumentos)
+pub fn trf(template: &'static str, args: &[&dyn Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut parts = tr(template).split("{}");
+    if let Some(first) = parts.next() {
+        out.push_str(first);
+    }
+    for part in parts {
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+/// Catálogo inglés → español. Las etiquetas alineadas en columnas conservan el ancho
+const ES: &[(&str, &str)] = &[
+    // Estados, direcciones y etiquetas compartidas
+    ("ACTIVE", "ACTIVO"),
+    ("STOPPED", "DETENIDO"),
+    ("TAKE PROFIT", "TAKE PROFIT"),
+    ("STOP LOSS", "STOP LOSS"),
+    ("MAX ORDERS", "MÁX. ÓRDENES"),
+    ("ERROR", "ERROR"),
+    ("TAKE PROFIT LEVEL", "NIVEL DE TAKE PROFIT"),
+    ("TRAILING TP", "TRAILING TP"),
+    ("BREAK-EVEN STOP", "STOP EN BREAK-EVEN"),
+    ("MANUAL CLOSE", "CIERRE MANUAL"),
+    ("GOOD", "BIEN"),
+    ("WARN", "ATENCIÓN"),
+    ("BAD", "MAL"),
+    ("connecting", "conectando"),
+    ("connected", "conectado"),
+    ("reconnecting", "reconectando"),
+    ("stale", "sin precios"),
+    ("down", "caído"),
+    ("info", "info"),
+    ("alert", "alerta"),
+    ("error", "error"),
+    ("all", "todo"),
+    ("DCA Trading", "Trading DCA"),
+    ("Accumulate", "Acumular"),
+    ("Distribute", "Distribuir"),
+    ("Watch-only", "Solo vigilar"),
+    ("Detail", "Detalle"),
+    ("Portfolio", "Portfolio"),
+    ("symbol not found", "símbolo inexistente"),
+    ("no klines yet", "todavía sin velas"),
+    ("SUPPORT", "SOPORTE"),
+    ("RESISTANCE", "RESISTENCIA"),
+    ("VOL BUY", "VOL COMPRA"),
+    ("VOL SELL", "VOL VENTA"),
+    ("BUY", "COMPRA"),
+    ("SELL", "VENTA"),
+    ("On", "Sí"),
+    ("Off", "No"),
+    ("Yes", "Sí"),
+    ("No", "No"),
+    ("off", "no"),
+    ("none", "ninguno"),
+    ("default", "por defecto"),
+    ("(default)", "(por defecto)"),
+    ("Manual", "Manual"),
+    ("buy", "compra"),
+    ("buys", "compras"),
+    ("Cancel", "Cancelar"),
+    ("Back", "Volver"),
+    ("Pause", "Pausar"),
+    ("Start", "Iniciar"),
+    // Terminal chica y header
+    ("Terminal too small", "Terminal demasiado chica"),
+    ("{}x{}, needs at least {}x{}", "{}x{}, se necesita al menos {}x{}"),
+    ("Resize the window · the bot keeps running", "Agrandá la ventana · el bot sigue operando"),
+    ("No active strategies — Press [S] to start", "Sin estrategias activas — [S] para empezar"),
+    ("⚠ STALE PRICE {}", "⚠ PRECIO VIEJO {}"),
+    // Lista de slots
+    ("  [S] New", "  [S] Nuevo"),
+    (" Slots {}/{}{}{} ", " Slots {}/{}{}{} "),
+    // Panel de precio
+    (" Price ", " Precio "),
+    ("── MARKETS ──────────────────", "── MERCADO ──────────────────"),
+    (" Vol 1m: ", " Vol 1m: "),
+    ("  buy {}%", "  compra {}%"),
+    ("  {}x avg", "  {}x prom."),
+    ("── BALANCE ──────────────────", "── BALANCE ──────────────────"),
+    (" Equity: ", " Equity: "),
+    ("  floor {} USDT", "  piso {} USDT"),
+    ("── CHART {} ", "── GRÁFICO {} "),
+    (" Did you mean ", " ¿Quisiste decir "),
+    (" Support:    ", " Soporte:    "),
+    (" Resistance: ", " Resistencia:"),
+    (" loading candles...", " cargando velas..."),
+    (" Live: collecting prices...", " En vivo: juntando precios..."),
+    // Panel DCA
+    (" DCA Strategy ", " Estrategia DCA "),
+    (" Trail TP:   ", " Trail TP:   "),
+    ("peak ${}  closes <${} ({}%↓)", "máximo ${}  cierra <${} ({}%↓)"),
+    ("trough ${}  closes >${} ({}%↑)", "mínimo ${}  cierra >${} ({}%↑)"),
+    (" Next buy:    ", " Próx.compra: "),
+    (" Next sell:   ", " Próx. venta: "),
+    (" Avg cost:    ", " Costo prom.: "),
+    (" Invested:   ", " Invertido:  "),
+    (" Quantity:   ", " Cantidad:   "),
+    (" Buy amount:  ", " Monto compra:"),
+    (" Sell price:  ", " Precio venta:"),
+    (" Received:   ", " Recibido:   "),
+    (" Sold:       ", " Vendido:    "),
+    (" Sell amount: ", " Monto venta: "),
+    ("── STATE ───────────────────", "── ESTADO ──────────────────"),
+    (" Mode:       ", " Modo:       "),
+    ("Accumulate {} UTC", "Acumular {} UTC"),
+    ("Distribute ladder {}/{}", "Distribuir escalera {}/{}"),
+    ("Distribute {} UTC", "Distribuir {} UTC"),
+    ("Watch-only · exits, no entries", "Solo vigilar · salidas, sin entradas"),
+    ("Auto-Restart ✓ ", "Reinicio auto ✓ "),
+    ("Manual ", "Manual "),
+    (" Status:     ", " Estado:     "),
+    ("WARMING UP", "INICIANDO"),
+    ("PAUSED (schedule)", "PAUSADO (horario)"),
+    ("REDUCE-ONLY", "SOLO REDUCIR"),
+    ("── POSITION ────────────────", "── POSICIÓN ────────────────"),
+    (" Orders:     ", " Órdenes:    "),
+    ("{} buys", "{} compras"),
+    ("{} sells, {} {} left", "{} ventas, quedan {} {}"),
+    ("imported holding", "tenencia importada"),
+    ("  ⚠ EXIT PENDING: {} ({} attempts, {}s)", "  ⚠ SALIDA PENDIENTE: {} ({} intentos, {}s)"),
+    (", chase {}", ", persecución {}"),
+    ("  Next SO {}/{} @ ${} ({}{}%, x{})", "  Próx. SO {}/{} @ ${} ({}{}%, x{})"),
+    (" {} ({})  Today: {} / {}", " {} ({})  Hoy: {} / {}"),
+    (" {}  Today: {} / {}", " {}  Hoy: {} / {}"),
+    ("  Next: ", "  Próx.: "),
+    ("⚠ MIN", "⚠ MÍN"),
+    ("✓ OK", "✓ OK"),
+    ("⚠ LOW", "⚠ FALTA"),
+    (" Wallet:     ", " Billetera:  "),
+    ("{} cash", "{} libre"),
+    (" / {} capital", " / {} de capital"),
+    ("shared account balance", "balance compartido de la cuenta"),
+    (" Liq. Safety: ", " Liq. segura: "),
+    ("Need {} more {}", "Faltan {} {}"),
+    ("Need {} more", "Faltan {}"),
+    ("⚠ INSUFFICIENT ({})", "⚠ INSUFICIENTE ({})"),
+    ("✓ READY TO CLOSE", "✓ LISTO PARA CERRAR"),
+    ("── PERFORMANCE ─────────────", "── RENDIMIENTO ─────────────"),
+    (" P&L:        ", " P&L:        "),
+    ("  B/E stop ${}", "  stop B/E ${}"),
+    ("  B/E at +{}%", "  B/E en +{}%"),
+    (" 7d:        ", " 7d:        "),
+    (" 30d:       ", " 30d:       "),
+    (" Lifetime:   ", " Histórico:  "),
+    ("  {} cycles, {} won", "  {} ciclos, {} ganados"),
+    (" Cycle:      ", " Ciclo:      "),
+    ("  median ", "  mediana "),
+    ("  ETA TP ", "  ETA TP "),
+    ("drifting away", "alejándose"),
+    (" Slippage:   ", " Slippage:   "),
+    ("  Fees paid ", "  Comisiones "),
+    // Historial de operaciones y portfolio
+    (" Trade History ", " Operaciones "),
+    (" Trade History ({}/{}) ", " Operaciones ({}/{}) "),
+    (" Trade History ({}) ", " Operaciones ({}) "),
+    ("Buy Price", "Precio compra"),
+    ("Sell Price", "Precio venta"),
+    ("Quantity", "Cantidad"),
+    ("Current P&L", "P&L actual"),
+    ("Date/Time", "Fecha/Hora"),
+    ("Symbol", "Símbolo"),
+    ("Side", "Lado"),
+    ("Status", "Estado"),
+    ("Invested", "Invertido"),
+    ("Unrealized P&L", "P&L no realizado"),
+    ("Realized today", "Realizado hoy"),
+    (" Portfolio ({} slots) ", " Portfolio ({} slots) "),
+    (" Invested: ", " Invertido: "),
+    ("  Unrealized: ", "  No realizado: "),
+    ("  Realized today: ", "  Realizado hoy: "),
+    ("  All cycles: ", "  Todos los ciclos: "),
+    ("  Equity: ", "  Equity: "),
+    (" Holdings ", " Tenencias "),
+    (" Holdings in {} ({}) · {} ", " Tenencias en {} ({}) · {} "),
+    (" Waiting for the first balance valuation...", " Esperando la primera valuación de balances..."),
+    ("Asset", "Activo"),
+    ("Value", "Valor"),
+    ("Share", "Peso"),
+    ("Route", "Ruta"),
+    ("no priced pair", "sin par con precio"),
+    // Log
+    (" Log ", " Log "),
+    (" Log · {} · {}/{} lines{} ", " Log · {} · {}/{} líneas{} "),
+    ("no slot", "sin slot"),
+    (" No log lines match the filters", " Ninguna línea del log coincide con los filtros"),
+    // Sesiones anteriores y archivo de estado dañado
+    (" ↩ Previous sessions found ", " ↩ Sesiones anteriores encontradas "),
+    ("  Saved sessions:", "  Sesiones guardadas:"),
+    ("  ⚠ Inconsistencies found:", "  ⚠ Inconsistencias encontradas:"),
+    ("    {}: {} duplicate trade(s) merged", "    {}: {} operación(es) duplicada(s) fusionada(s)"),
+    ("    {}: recorded {}, wallet holds {}", "    {}: registrado {}, la billetera tiene {}"),
+    ("  Do you want to continue where you left off?", "  ¿Querés continuar donde lo dejaste?"),
+    ("Continue previous session", "Continuar la sesión anterior"),
+    ("Continue, adjusting positions to the wallet", "Continuar, ajustando las posiciones a la billetera"),
+    ("Discard and start from scratch", "Descartar y empezar de cero"),
+    (" ⚠ State file damaged ", " ⚠ Archivo de estado dañado "),
+    ("  strategy_state.json could not be read:", "  No se pudo leer strategy_state.json:"),
+    ("  Damaged file kept as {}", "  Archivo dañado guardado como {}"),
+    ("  ⚠ The damaged file could not be copied aside", "  ⚠ No se pudo apartar una copia del archivo dañado"),
+    ("  Nothing is restored or saved until you choose:", "  No se restaura ni se guarda nada hasta que elijas:"),
+    ("Load the latest backup", "Cargar el último backup"),
+    ("      {} · saved {} · {} slot(s), plus later events", "      {} · guardado {} · {} slot(s), más los eventos posteriores"),
+    ("Load the latest backup (no readable backup)", "Cargar el último backup (no hay uno legible)"),
+    ("Rebuild from history", "Reconstruir desde el historial"),
+    ("      slots replayed from the event log, checked against Binance fills", "      slots rearmados con el log de eventos y cotejados con los fills de Binance"),
+    ("Start clean", "Empezar de cero"),
+    ("      one new slot from config.toml; open positions are no longer tracked", "      un slot nuevo desde config.toml; las posiciones abiertas dejan de seguirse"),
+    // Nueva estrategia
+    (" ▶ New DCA Strategy ", " ▶ Nueva estrategia DCA "),
+    (" Symbol (↑↓):", " Símbolo (↑↓):"),
+    ("{}{} ← in use", "{}{} ← en uso"),
+    (" Type (T):         ", " Tipo (T):         "),
+    (" Trading ", " Trading "),
+    (" Accumulate ", " Acumular "),
+    (" Distribute ", " Distribuir "),
+    (" Direction (Tab):  ", " Dirección (Tab):  "),
+    (" ▲ LONG ", " ▲ LONG "),
+    (" ▼ SHORT ", " ▼ SHORT "),
+    (" Preset (P):       ", " Preset (P):       "),
+    (" None (duplicate) ", " Ninguno (duplicado) "),
+    (" None · M to manage ", " Ninguno · M para gestionar "),
+    (" None ([dca]) ", " Ninguno ([dca]) "),
+    (" None ", " Ninguno "),
+    (" Restart (←→):     ", " Reinicio (←→):    "),
+    (" Manual ", " Manual "),
+    (" Auto ", " Auto "),
+    (" Dir Flip (F):     ", " Invertir dir. (F):"),
+    (" Off ", " No "),
+    (" ▲↔▼ Invert Dir ↺ ", " ▲↔▼ Invertir ↺ "),
+    ("   ↳ Flips Long↔Short direction after each TP", "   ↳ Alterna Long↔Short después de cada TP"),
+    (" Pay Fees w/ BNB(B):", " Comisión c/BNB (B):"),
+    (" No ", " No "),
+    (" Yes (25% Disc) ", " Sí (25% desc.) "),
+    (" Group (G):        ", " Grupo (G):        "),
+    ("Review Strategy  ", "Revisar estrategia  "),
+    // Panel de configuración
+    (" ⚙ Global Config ", " ⚙ Configuración global "),
+    (" {} Amount: ", " Monto {}: "),
+    (" (Current: {})", " (Actual: {})"),
+    (" (Current: {} ≈ {})", " (Actual: {} ≈ {})"),
+    (" Min order:   ", " Orden mín.:  "),
+    (" Min order:   loading...", " Orden mín.:  cargando..."),
+    (" Pay Fees w/ BNB (B): ", " Comisión c/BNB (B): "),
+    (" Min TP (fees): ", " TP mín. (comisiones): "),
+    ("  (TP {}% raised)", "  (TP {}% subido)"),
+    (" (these settings apply to ALL active slots)", " (se aplica a TODOS los slots activos)"),
+    ("Confirm Anyway ", "Confirmar igual "),
+    ("Save All    ", "Guardar todo "),
+    // Cierre manual, edición del slot, ajustes y borrado
+    (" ⚡ Market Close Position ", " ⚡ Cerrar posición a mercado "),
+    ("Market SELL", "VENTA a mercado"),
+    ("Market BUY (rebuy)", "COMPRA a mercado (recompra)"),
+    ("  Pair:      ", "  Par:       "),
+    ("  Action:   ", "  Acción:   "),
+    ("  Quantity: ", "  Cantidad: "),
+    ("  Curr. P&L: ", "  P&L act.:  "),
+    ("  This action does not wait for take profit.", "  Esta acción no espera al take profit."),
+    ("Execute now    ", "Ejecutar ahora "),
+    (" ✎ Edit {} ", " ✎ Editar {} "),
+    ("Notify", "Avisos"),
+    (" TP ladder: changing TP replaces it", " Escalera de TP: cambiar el TP la reemplaza"),
+    (" (this slot only, kept across restarts)", " (solo este slot, se mantiene al reiniciar)"),
+    (" ⚙ Settings · config.toml ", " ⚙ Ajustes · config.toml "),
+    (" (saved to config.toml, applied by the live reload)", " (se guarda en config.toml y lo aplica la recarga en vivo)"),
+    (" 🗑 Delete Slot ", " 🗑 Borrar slot "),
+    ("  Delete the slot of ", "  ¿Confirmás borrar el slot de "),
+    ("  ⚠ OPEN POSITION DETECTED", "  ⚠ POSICIÓN ABIERTA DETECTADA"),
+    ("  If you delete it, the bot stops managing it.", "  Si lo borrás, el bot deja de gestionarla."),
+    ("  The local history of this cycle will be lost.", "  Se pierde el historial local de este ciclo."),
+    ("Delete now     ", "Borrar ahora   "),
+    // Después de una venta
+    ("Received:  ", "Recibido:  "),
+    ("Profit:    ", "Ganancia:  "),
+    ("What do you want to do?", "¿Qué querés hacer?"),
+    ("Restart DCA cycle immediately", "Reiniciar el ciclo DCA ahora"),
+    ("  [Esc / any key] ", "  [Esc / cualquier tecla] "),
+    ("Stay stopped", "Quedar detenido"),
+    // Resumen de la nueva estrategia
+    (" ▶ Review New Strategy ", " ▶ Revisar nueva estrategia "),
+    ("{} of balance ≈ {}", "{} del balance ≈ {}"),
+    ("{} of balance", "{} del balance"),
+    ("never sold (accumulation)", "nunca se vende (acumulación)"),
+    ("never bought back (distribution)", "nunca se recompra (distribución)"),
+    ("{}% (raised from {}% for fees)", "{}% (subido de {}% por comisiones)"),
+    (" Pair:         ", " Par:          "),
+    (" Amount/order: ", " Monto/orden:  "),
+    (" Capital:      ", " Capital:      "),
+    ("{} (virtual wallet)", "{} (billetera virtual)"),
+    (" Orders:       ", " Órdenes:      "),
+    ("{} UTC, no limit", "{} UTC, sin límite"),
+    ("sell at {}", "vender en {}"),
+    ("{} UTC until sold out", "{} UTC hasta vender todo"),
+    ("{} max  scale x{}  every {} min", "{} máx.  escala x{}  cada {} min"),
+    ("base + {} safety orders (ladder)", "base + {} safety orders (escalera)"),
+    (" Inventory:    ", " Inventario:   "),
+    (" Extra entry:  ", " Entrada extra:"),
+    ("free {} balance at start", "balance libre de {} al empezar"),
+    ("ladder up to {}% against the base order", "escalera hasta {}% contra la orden base"),
+    ("on {}% move against the position", "con un movimiento de {}% contra la posición"),
+    (" Take profit:  ", " Take profit:  "),
+    (" Stop loss:    ", " Stop loss:    "),
+    ("   Trailing TP: ", "   Trailing TP: "),
+    (" Restart:      ", " Reinicio:     "),
+    ("Auto (cooldown {} min)", "Auto (espera {} min)"),
+    ("   Flip: ", "   Invertir: "),
+    (" BNB fees:     ", " Comisión BNB: "),
+    ("   Group: ", "   Grupo: "),
+    ("── REQUIRED BALANCE ───────────────────", "── BALANCE NECESARIO ──────────────────"),
+    ("≈ {} in {}", "≈ {} en {}"),
+    (" Max exposure: ", " Expos. máx.:  "),
+    (" Per buy:      ", " Por compra:   "),
+    (" Per sale:     ", " Por venta:    "),
+    ("  {} orders", "  {} órdenes"),
+    (" Cap:          ", " Tope:         "),
+    ("within the {} exposure cap ✓", "dentro del tope de exposición de {} ✓"),
+    ("⚠ {}: entries stop early", "⚠ {}: las entradas se cortan antes"),
+    (" Free:         ", " Libre:        "),
+    ("unknown until first balance refresh", "desconocido hasta la primera lectura de balances"),
+    ("  ⚠ not enough for all orders", "  ⚠ no alcanza para todas las órdenes"),
+    (" Depends on the free balance at each entry", " Depende del balance libre en cada entrada"),
+    ("Create and Start  ", "Crear e iniciar  "),
+    // Detalle de una operación
+    (" Trade #{} of {} · {} ", " Operación #{} de {} · {} "),
+    ("not reported", "no informada"),
+    ("unknown (adopted or recorded by an older version)", "desconocido (adoptada o registrada por una versión anterior)"),
+    (" Order ID:   ", " ID orden:   "),
+    (" Time:       ", " Hora:       "),
+    (" Trigger:    ", " Disparo:    "),
+    (" Expected:   ", " Esperado:   "),
+    (" Avg fill:   ", " Fill prom.: "),
+    (" Total:      ", " Total:      "),
+    (" Fees:       ", " Comisiones: "),
+    ("── FILLS ({}) ──────────────────", "── FILLS ({}) ──────────────────"),
+    (" Not reported (LIMIT and adopted entries only know the average)", " No informados (las entradas LIMIT y adoptadas solo conocen el promedio)"),
+    ("   fee ", "   comisión "),
+    (" … {} more", " … {} más"),
+    // Ayuda
+    (" ? Keys ({}-{} of {}) ", " ? Teclas ({}-{} de {}) "),
+    // Diagnóstico del feed de precios
+    (" ⚙ Price feed diagnostics ", " ⚙ Diagnóstico del feed de precios "),
+    (" ⚙ Price feed diagnostics ({}-{} of {}) ", " ⚙ Diagnóstico del feed de precios ({}-{} de {}) "),
+    (" Status:          ", " Estado:          "),
+    (" Uptime:          ", " Conectado hace:  "),
+    ("── RECONNECTS ──────────────────", "── RECONEXIONES ────────────────"),
+    (" Sessions:        ", " Sesiones:        "),
+    ("   Drops: ", "   Caídas: "),
+    ("   Failed: ", "   Fallidas: "),
+    (" 24h rotations:   ", " Rotaciones 24h:  "),
+    ("   Symbol changes: ", "   Cambios de símbolos: "),
+    (" Dropped trades:  ", " Trades perdidos: "),
+    (" Failing now:     ", " Fallando ahora:  "),
+    ("{} in a row", "{} seguidas"),
+    ("   Last wait: ", "   Última espera: "),
+    (" Last error:      ", " Último error:    "),
+    ("── POLICY [websocket] ──────────", "── POLÍTICA [websocket] ────────"),
+    (" Backoff:         ", " Backoff:         "),
+    ("{}s → {}s (x2 per failure), ±{}%", "{}s → {}s (x2 por falla), ±{}%"),
+    (" Max retries:     ", " Reintentos máx.: "),
+    ("unlimited", "sin límite"),
+    (" Stale prices:    ", " Precios viejos:  "),
+    ("check off", "chequeo apagado"),
+    ("none (max age {}s)", "ninguno (antigüedad máx. {}s)"),
+    ("{} (max age {}s)", "{} (antigüedad máx. {}s)"),
+    ("── SESSION (stats.json) ────────", "── SESIÓN (stats.json) ─────────"),
+    ("This session", "Esta sesión"),
+    ("{} sessions since {}", "{} sesiones desde {}"),
+    (" Running:         ", " En marcha:       "),
+    (" Orders:          ", " Órdenes:         "),
+    ("{} ok · {} failed", "{} ok · {} fallidas"),
+    (" API errors:      ", " Errores de API:  "),
+    (" Reconnects:      ", " Reconexiones:    "),
+    (" Alerts:          ", " Alertas:         "),
+    (" Previous:        ", " Anterior:        "),
+    ("ran {} until {}", "duró {} hasta {}"),
+    // Presets
+    (" ☰ Strategy presets ({}) · presets.toml ", " ☰ Presets de estrategia ({}) · presets.toml "),
+    ("  No presets yet", "  Todavía no hay presets"),
+    ("  N saves the selected slot's config as a preset (or :preset save <name>)", "  N guarda la config del slot seleccionado como preset (o :preset save <nombre>)"),
+    ("Name", "Nombre"),
+    ("{} fields · the rest come from [dca]", "{} campos · el resto sale de [dca]"),
+    ("Save {} config as: {}▌", "Guardar la config de {} como: {}▌"),
+    ("Rename to: {}▌", "Renombrar a: {}▌"),
+    ("Value as TOML: 1.5, true, \"08:00-22:00\", [[1.0, 0.5]]", "Valor en TOML: 1.5, true, \"08:00-22:00\", [[1.0, 0.5]]"),
+    ("[Enter] Save  [Esc] Cancel", "[Enter] Guardar  [Esc] Cancelar"),
+    ("[↑↓] Preset  [Tab] Field  [Enter] Edit  [X] Remove field  [N] New  [R] Rename  [D] Delete  [Esc] Close", "[↑↓] Preset  [Tab] Campo  [Enter] Editar  [X] Quitar campo  [N] Nuevo  [R] Renombrar  [D] Borrar  [Esc] Cerrar"),
+    // Órdenes condicionales, alertas y paleta
+    (" ⚡ Conditional orders ({}) · Ladders ({}) ", " ⚡ Órdenes condicionales ({}) · Escaleras ({}) "),
+    ("── LADDERS (:ladder cancel|adopt <id>) ──", "── ESCALERAS (:ladder cancel|adopt <id>) ──"),
+    ("{} ${} ${}→${}  {}/{} filled  {} @ ${}  {} open", "{} ${} ${}→${}  {}/{} llenas  {} @ ${}  {} abiertas"),
+    ("  No conditional orders", "  No hay órdenes condicionales"),
+    ("  Add one from the palette: buy 50 BTCUSDT at 60000 / sell 0.01 BTCUSDT at 70000", "  Se agregan desde la paleta: buy 50 BTCUSDT at 60000 / sell 0.01 BTCUSDT at 70000"),
+    ("Amount", "Monto"),
+    ("Trigger", "Disparo"),
+    ("Price", "Precio"),
+    ("Created", "Creada"),
+    (" 🔔 Alerts ({} new / {}) ", " 🔔 Alertas ({} nuevas / {}) "),
+    ("  No alerts yet", "  Todavía no hay alertas"),
+    ("{}x avg", "{}x prom."),
+    ("Time", "Hora"),
+    ("Level", "Nivel"),
+    ("At", "En"),
+    (" : Command ", " : Comando "),
+    (" No matching command", " Ningún comando coincide"),
+    // Campos de edición y ajustes
+    ("Take profit %", "Take profit %"),
+    ("Stop loss %", "Stop loss %"),
+    ("Trailing TP %", "Trailing TP %"),
+    ("Break-even at %", "Break-even en %"),
+    ("B/E offset %", "Margen B/E %"),
+    ("Interval (min)", "Intervalo (min)"),
+    ("Max orders", "Órdenes máx."),
+    ("Drop trigger %", "Caída disparo %"),
+    ("Daily limit", "Límite diario"),
+    ("Max daily spend", "Gasto diario máx."),
+    ("Daily cap, total", "Tope diario total"),
+    ("Amount confirm %", "Confirmar monto %"),
+    ("Equity floor", "Piso de equity"),
+    ("Max price age s", "Edad precio máx s"),
+    ("S/R window", "Ventana S/R"),
+    ("Alert cooldown m", "Espera alertas m"),
+    ("Volume spike x", "Pico de volumen x"),
+    ("Max slots", "Slots máx."),
+    ("Theme", "Tema"),
+    ("Display currency", "Moneda mostrada"),
+    ("Timezone", "Zona horaria"),
+    ("Language", "Idioma"),
+    // Teclas: nombres con palabras
+    ("any key", "cualquier tecla"),
+    ("Esc / any key", "Esc / cualquier tecla"),
+    ("Enter / Esc", "Enter / Esc"),
+    ("type", "escribir"),
+    ("Click", "Clic"),
+    ("Wheel", "Rueda"),
+    ("Home / End", "Inicio / Fin"),
+    // Teclas: acciones
+    ("Start / pause the selected slot", "Iniciar / pausar el slot seleccionado"),
+    ("Next tab", "Pestaña siguiente"),
+    ("New", "Nuevo"),
+    ("Sell now", "Vender ya"),
+    ("Flip", "Invertir"),
+    ("Delete", "Borrar"),
+    ("Config", "Config"),
+    ("Edit", "Editar"),
+    ("Alerts", "Alertas"),
+    ("Triggers", "Órdenes"),
+    ("Diag", "Diag"),
+    ("Log", "Log"),
+    ("Interval", "Intervalo"),
+    ("Slots", "Slots"),
+    ("Trades", "Operaciones"),
+    ("Commands", "Comandos"),
+    ("Help", "Ayuda"),
+    ("Exit", "Salir"),
+    ("Go to tab", "Ir a la pestaña"),
+    ("Next / previous slot", "Slot siguiente / anterior"),
+    ("Move the selected slot up / down", "Subir / bajar el slot seleccionado"),
+    ("Duplicate the selected slot onto another symbol", "Duplicar el slot seleccionado en otro símbolo"),
+    ("Strategy presets (presets.toml)", "Presets de estrategia (presets.toml)"),
+    ("Risk, alert and UI settings (config.toml)", "Ajustes de riesgo, alertas e interfaz (config.toml)"),
+    ("Newer / older trade history entry", "Operación más nueva / más vieja"),
+    ("Detail of the selected entry", "Detalle de la entrada seleccionada"),
+    ("Select a slot / run a footer hint", "Seleccionar un slot / usar un atajo del footer"),
+    ("Scroll the trade history", "Desplazar el historial de operaciones"),
+    ("Reduce-only: no new entries, keep managing the exit", "Solo reducir: sin entradas nuevas, sigue gestionando la salida"),
+    ("Undo the last config change", "Deshacer el último cambio de config"),
+    ("Save the dashboard as text / ANSI", "Guardar el dashboard como texto / ANSI"),
+    ("Export the slot's trades and cycles (CSV / JSON)", "Exportar los trades y ciclos del slot (CSV / JSON)"),
+    ("Continue", "Continuar"),
+    ("New session", "Sesión nueva"),
+    ("Reconcile", "Conciliar"),
+    ("Load backup", "Cargar backup"),
+    ("Restart", "Reinicio"),
+    ("Field", "Campo"),
+    ("Review", "Revisar"),
+    ("Next / previous symbol", "Símbolo siguiente / anterior"),
+    ("Manual / auto restart", "Reinicio manual / automático"),
+    ("DCA trading / accumulation", "Trading DCA / acumulación"),
+    ("Auto-flip", "Inversión automática"),
+    ("Use BNB for fees", "Pagar comisiones con BNB"),
+    ("Cycle strategy group", "Cambiar el grupo de estrategia"),
+    ("Cycle saved presets (none = [dca])", "Cambiar el preset guardado (ninguno = [dca])"),
+    ("Type the amount / TP / SL / interval", "Escribir monto / TP / SL / intervalo"),
+    ("Create and start", "Crear e iniciar"),
+    ("Enter amount", "Ingresar monto"),
+    ("Confirm", "Confirmar"),
+    ("Delete last digit", "Borrar el último dígito"),
+    ("Acknowledge all", "Marcar todas vistas"),
+    ("Clear", "Limpiar"),
+    ("Close", "Cerrar"),
+    ("Select", "Elegir"),
+    ("Cancel order", "Cancelar orden"),
+    ("buy/sell ... at <price> · ladder ...", "buy/sell ... at <precio> · ladder ..."),
+    ("Next / previous order", "Orden siguiente / anterior"),
+    ("Preset", "Preset"),
+    ("Edit value", "Editar valor"),
+    ("Save slot as", "Guardar slot como"),
+    ("Rename", "Renombrar"),
+    ("Next / previous preset", "Preset siguiente / anterior"),
+    ("Previous / next field", "Campo anterior / siguiente"),
+    ("Remove the field (it takes the [dca] value)", "Quitar el campo (toma el valor de [dca])"),
+    ("Delete the preset", "Borrar el preset"),
+    ("Name / TOML value", "Nombre / valor TOML"),
+    ("Save", "Guardar"),
+    ("Delete last character", "Borrar el último carácter"),
+    ("Search", "Buscar"),
+    ("Done", "Listo"),
+    ("Scroll", "Desplazar"),
+    ("Oldest / newest", "Más vieja / más nueva"),
+    ("Severity", "Severidad"),
+    ("Selected slot", "Slot seleccionado"),
+    ("Scroll one line", "Desplazar una línea"),
+    ("Newer / older entry", "Entrada más nueva / más vieja"),
+    ("Older / newer entry", "Entrada más vieja / más nueva"),
+    ("Apply", "Aplicar"),
+    ("Applies to this slot only (saved with its state, [dca] is untouched)", "Solo afecta a este slot (se guarda con su estado, [dca] no cambia)"),
+    ("Delete last character (empty = default value)", "Borrar el último carácter (vacío = valor por defecto)"),
+    ("Restart cycle", "Reiniciar ciclo"),
+    ("Close market position", "Cerrar la posición a mercado"),
+    ("Confirm delete slot", "Confirmar el borrado del slot"),
+    ("Run", "Ejecutar"),
+    // Secciones de la ayuda
+    ("Dashboard", "Dashboard"),
+    ("Previous sessions", "Sesiones anteriores"),
+    ("Damaged state file", "Archivo de estado dañado"),
+    ("New strategy", "Nueva estrategia"),
+    ("New strategy · summary", "Nueva estrategia · resumen"),
+    ("Config (C)", "Config (C)"),
+    ("Edit slot (E)", "Editar slot (E)"),
+    ("Settings (G)", "Ajustes (G)"),
+    ("Alerts (A)", "Alertas (A)"),
+    ("Conditional orders (O)", "Órdenes condicionales (O)"),
+    ("Strategy presets (M)", "Presets de estrategia (M)"),
+    ("Strategy presets · input", "Presets de estrategia · texto"),
+    ("Diagnostics (I)", "Diagnóstico (I)"),
+    ("Log (L)", "Log (L)"),
+    ("Log · search (/)", "Log · búsqueda (/)"),
+    ("Trade detail (Enter)", "Detalle de operación (Enter)"),
+    ("Command palette (:)", "Paleta de comandos (:)"),
+    ("After a sale", "Después de una venta"),
+    ("Close position (V)", "Cerrar posición (V)"),
+    ("Delete slot (D)", "Borrar slot (D)"),
+    ("Help (?)", "Ayuda (?)"),
+    // Paleta de comandos
+    ("Open the new strategy modal", "Abrir el modal de nueva estrategia"),
+    ("Start/stop the selected slot", "Iniciar/detener el slot seleccionado"),
+    ("Close the selected position (asks to confirm)", "Cerrar la posición seleccionada (pide confirmación)"),
+    ("Close part of the position at market", "Cerrar parte de la posición a mercado"),
+    ("Delete the selected slot", "Borrar el slot seleccionado"),
+    ("Toggle auto-flip on the selected slot", "Activar/desactivar la inversión automática del slot"),
+    ("Toggle reduce-only (no new entries) on the selected slot", "Activar/desactivar solo reducir (sin entradas nuevas) en el slot"),
+    ("Open the config panel (amount, BNB)", "Abrir el panel de config (monto, BNB)"),
+    ("Edit TP/SL/trailing/interval of the selected slot", "Editar TP/SL/trailing/intervalo del slot seleccionado"),
+    ("Select the next slot", "Elegir el slot siguiente"),
+    ("Select the previous slot", "Elegir el slot anterior"),
+    ("Move the selected slot up the list", "Subir el slot seleccionado en la lista"),
+    ("Move the selected slot down the list", "Bajar el slot seleccionado en la lista"),
+    ("Copy the selected slot's config onto another symbol", "Copiar la config del slot seleccionado a otro símbolo"),
+    ("Edit risk limits, alerts and UI settings in config.toml", "Editar límites de riesgo, alertas y ajustes de interfaz en config.toml"),
+    ("Edit the strategy presets saved in presets.toml", "Editar los presets de estrategia guardados en presets.toml"),
+    ("Save the selected slot's config as a named preset", "Guardar la config del slot seleccionado como preset con nombre"),
+    ("Detail of the selected Trade History entry", "Detalle de la operación seleccionada"),
+    ("Dashboard of the selected slot", "Dashboard del slot seleccionado"),
+    ("Summary of all slots and the account equity", "Resumen de todos los slots y el equity de la cuenta"),
+    ("Next chart / S/R interval of the selected slot", "Siguiente intervalo de gráfico / S/R del slot seleccionado"),
+    ("Take profit % of the selected slot", "Take profit % del slot seleccionado"),
+    ("Stop loss % of the selected slot", "Stop loss % del slot seleccionado"),
+    ("Trailing TP % of the selected slot", "Trailing TP % del slot seleccionado"),
+    ("Profit % that arms a break-even stop (0 = off)", "Ganancia % que arma un stop en break-even (0 = no)"),
+    ("Break-even stop distance past break-even, in % (0 = exact)", "Distancia % del stop más allá del break-even (0 = exacto)"),
+    ("Price-drop entry trigger %", "Caída de precio % que dispara la entrada"),
+    ("Entry amount of the selected slot", "Monto de entrada del slot seleccionado"),
+    ("Minutes between entries", "Minutos entre entradas"),
+    ("Maximum DCA orders", "Órdenes DCA máximas"),
+    ("Size multiplier for each safety order", "Multiplicador de tamaño de cada safety order"),
+    ("Cooldown after TP in minutes", "Espera después del TP en minutos"),
+    ("Visible % of limit SHORT entries (0 = off)", "% visible de las entradas SHORT limit (0 = no)"),
+    ("Virtual wallet capital of the selected slot (0 = off)", "Capital de la billetera virtual del slot seleccionado (0 = no)"),
+    ("Daily spend limit of the selected slot (0 = [risk] max_daily_spend)", "Límite de gasto diario del slot seleccionado (0 = [risk] max_daily_spend)"),
+    ("Max spread for market entries, above it LIMIT_MAKER (0 = off)", "Spread máximo para entradas a mercado, por encima LIMIT_MAKER (0 = no)"),
+    ("Beep for alert/stop_loss/error/exit on this slot", "Pitido por alert/stop_loss/error/exit en este slot"),
+    ("Revert the last config change", "Revertir el último cambio de config"),
+    ("Show the S/R alert history", "Mostrar el historial de alertas S/R"),
+    ("Market buy once when the price reaches a level", "Compra a mercado una vez cuando el precio llega a un nivel"),
+    ("Market sell once when the price reaches a level", "Venta a mercado una vez cuando el precio llega a un nivel"),
+    ("List and cancel the conditional orders", "Listar y cancelar las órdenes condicionales"),
+    ("Price stream diagnostics and session stats", "Diagnóstico del feed de precios y estadísticas de la sesión"),
+    ("Full log with filters and search", "Log completo con filtros y búsqueda"),
+    ("Every key of every mode", "Todas las teclas de cada modo"),
+    ("Place limit buys spread between two prices", "Colocar compras limit repartidas entre dos precios"),
+    ("Place limit sells spread between two prices", "Colocar ventas limit repartidas entre dos precios"),
+    ("Cancel the resting orders of a ladder", "Cancelar las órdenes pendientes de una escalera"),
+    ("Hand a ladder's fills to a new DCA slot for the exit", "Pasar los fills de una escalera a un slot DCA nuevo para la salida"),
+    ("Exit-only slot for a holding, cost from the trade history", "Slot solo de salida para una tenencia, costo del historial de operaciones"),
+    ("Exit-only slot for a holding bought at that cost", "Slot solo de salida para una tenencia comprada a ese costo"),
+    ("DCA slot seeded with buys made outside the bot", "Slot DCA con compras hechas fuera del bot"),
+    ("Slot seeded with the entries of a PositionImport file", "Slot con las entradas de un archivo PositionImport"),
+    ("Save the dashboard as text and ANSI files", "Guardar el dashboard como archivos de texto y ANSI"),
+    ("Export the selected slot's trades and the cycle history to CSV and JSON", "Exportar los trades del slot seleccionado y el historial de ciclos a CSV y JSON"),
+    ("Exit the bot", "Salir del bot"),
+];
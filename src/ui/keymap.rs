@@ -0,0 +1,363 @@
+use crossterm::event::KeyCode;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Span;
+
+use crate::app::{AppState, UiMode};
+use crate::ui::i18n::tr;
+use crate::ui::theme::Theme;
+
+/// Texto de una acción: fijo, o que depende del estado (solo en el footer)
+#[derive(Debug, Clone, Copy)]
+pub enum Action {
+    Text(&'static str),
+    /// "Pause" o "Start" según el slot seleccionado
+    StartPause,
+    /// Nombre de la pestaña siguiente
+    NextTab,
+}
+
+/// Una tecla (o grupo de teclas) de un modo del UI
+#[derive(Debug, Clone, Copy)]
+pub struct Binding {
+    pub keys: &'static str,
+    pub action: Action,
+    /// Color de la tecla, tomado del tema
+    pub color: fn(&Theme) -> Color,
+    pub bold: bool,
+    /// Se muestra en el footer; el resto solo aparece en la ayuda (?)
+    pub footer: bool,
+}
+
+impl Binding {
+    pub fn style(&self, theme: &Theme) -> Style {
+        let style = Style::default().fg((self.color)(theme));
+        if self.bold { style.add_modifier(Modifier::BOLD) } else { style }
+    }
+
+    pub fn label(&self, state: &AppState) -> String {
+        match self.action {
+            Action::Text(text) => tr(text).to_string(),
+            Action::StartPause if state.selected_slot_is_active() => tr("Pause").to_string(),
+            Action::StartPause => tr("Start").to_string(),
+            Action::NextTab => tr(state.view_tab.next().label()).to_string(),
+        }
+    }
+
+    /// Tecla que se simula al hacer click en la pista del footer: la primera de `keys`
+    /// (None si no es una tecla concreta, como "↑↓" o "0-9 .")
+    pub fn press(&self) -> Option<KeyCode> {
+        let first = self.keys.split(" / ").next()?.split_whitespace().next()?;
+        let mut chars = first.chars();
+        match (first, chars.next(), chars.next()) {
+            ("Enter", ..) => Some(KeyCode::Enter),
+            // "any key" cierra: Esc vale en todos los modos que lo usan
+            ("Esc" | "any", ..) => Some(KeyCode::Esc),
+            ("Tab", ..) => Some(KeyCode::Tab),
+            ("Home", ..) => Some(KeyCode::Home),
+            ("End", ..) => Some(KeyCode::End),
+            ("Delete", ..) => Some(KeyCode::Delete),
+            ("Backspace", ..) => Some(KeyCode::Backspace),
+            (_, Some(c), None) if !matches!(c, '↑' | '↓' | '←' | '→') => Some(KeyCode::Char(c.to_ascii_lowercase())),
+            _ => None,
+        }
+    }
+
+    /// Texto para la ayuda, sin depender del estado
+    pub fn help_label(&self) -> &'static str {
+        match self.action {
+            Action::Text(text) => text,
+            Action::StartPause => "Start / pause the selected slot",
+            Action::NextTab => "Next tab",
+        }
+    }
+}
+
+const fn key(keys: &'static str, action: &'static str, color: fn(&Theme) -> Color, bold: bool) -> Binding {
+    Binding { keys, action: Action::Text(action), color, bold, footer: true }
+}
+
+/// Tecla que solo se lista en la ayuda
+const fn extra(keys: &'static str, action: &'static str) -> Binding {
+    Binding { keys, action: Action::Text(action), color: |t| t.accent, bold: false, footer: false }
+}
+
+pub const NORMAL: &[Binding] = &[
+    key("S", "New", |t| t.positive, true),
+    Binding { keys: "X", action: Action::StartPause, color: |t| t.highlight, bold: true, footer: true },
+    key("V", "Sell now", |t| t.negative, true),
+    key("F", "Flip", |t| t.special, true),
+    key("D", "Delete", |t| t.negative, true),
+    key("C", "Config", |t| t.accent, true),
+    key("E", "Edit", |t| t.accent, true),
+    key("A", "Alerts", |t| t.highlight, true),
+    key("O", "Triggers", |t| t.highlight, true),
+    key("I", "Diag", |t| t.accent, true),
+    key("L", "Log", |t| t.accent, true),
+    key("T", "Interval", |t| t.accent, true),
+    Binding { keys: "Tab", action: Action::NextTab, color: |t| t.accent, bold: true, footer: true },
+    key("↑↓", "Slots", |t| t.accent, false),
+    key("[ ] Enter", "Trades", |t| t.accent, false),
+    key(":", "Commands", |t| t.accent, true),
+    key("?", "Help", |t| t.accent, true),
+    key("Q", "Exit", |t| t.negative, true),
+    extra("1-4", "Go to tab"),
+    extra("j / k", "Next / previous slot"),
+    extra("Shift+↑↓ / K J", "Move the selected slot up / down"),
+    extra("Y", "Duplicate the selected slot onto another symbol"),
+    extra("M", "Strategy presets (presets.toml)"),
+    extra("G", "Risk, alert and UI settings (config.toml)"),
+    extra("[ / ]", "Newer / older trade history entry"),
+    extra("Enter", "Detail of the selected entry"),
+    extra("Click", "Select a slot / run a footer hint"),
+    extra("Wheel", "Scroll the trade history"),
+    extra("R", "Reduce-only: no new entries, keep managing the exit"),
+    extra("U", "Undo the last config change"),
+    extra("P", "Save the dashboard as text / ANSI"),
+    extra("W", "Export the slot's trades and cycles (CSV / JSON)"),
+    extra("Esc / Ctrl+C", "Exit"),
+];
+
+pub const RESTORE_SESSION: &[Binding] = &[
+    key("C / Enter", "Continue", |t| t.positive, true),
+    key("N / Esc", "New session", |t| t.negative, true),
+];
+
+/// Restauración con posiciones mayores que la wallet: R las ajusta
+pub const RESTORE_SESSION_RECONCILE: &[Binding] = &[
+    key("C / Enter", "Continue", |t| t.positive, true),
+    key("N / Esc", "New session", |t| t.negative, true),
+    key("R", "Reconcile", |t| t.highlight, true),
+];
+
+pub const RECOVERY: &[Binding] = &[
+    key("B", "Load backup", |t| t.positive, true),
+    key("R", "Rebuild from history", |t| t.highlight, true),
+    key("N", "Start clean", |t| t.negative, true),
+];
+
+pub const NEW_STRATEGY: &[Binding] = &[
+    key("↑↓", "Symbol", |t| t.accent, true),
+    key("Tab", "LONG/SHORT", |t| t.accent, true),
+    key("←→", "Restart", |t| t.accent, true),
+    key("N", "Field", |t| t.accent, true),
+    key("Enter", "Review", |t| t.positive, true),
+    key("Esc", "Cancel", |t| t.negative, true),
+    extra("j / k", "Next / previous symbol"),
+    extra("h / l", "Manual / auto restart"),
+    extra("T", "DCA trading / accumulation"),
+    extra("F", "Auto-flip"),
+    extra("B", "Use BNB for fees"),
+    extra("G", "Cycle strategy group"),
+    extra("P", "Cycle saved presets (none = [dca])"),
+    extra("0-9 . %", "Type the amount / TP / SL / interval"),
+    extra("Q", "Cancel"),
+];
+
+pub const NEW_STRATEGY_SUMMARY: &[Binding] = &[
+    key("Enter / Y", "Create and start", |t| t.positive, true),
+    key("Esc", "Back", |t| t.negative, true),
+    extra("Backspace", "Back"),
+];
+
+pub const CONFIG: &[Binding] = &[
+    key("0-9 .", "Enter amount", |t| t.accent, false),
+    key("Enter", "Confirm", |t| t.positive, true),
+    key("Esc", "Cancel", |t| t.negative, true),
+    extra("B", "Use BNB for fees"),
+    extra("Backspace", "Delete last digit"),
+];
+
+pub const ALERTS: &[Binding] = &[
+    key("Enter / A", "Acknowledge all", |t| t.positive, true),
+    key("C", "Clear", |t| t.negative, true),
+    key("Esc", "Close", |t| t.highlight, true),
+];
+
+pub const TRIGGERS: &[Binding] = &[
+    key("↑↓", "Select", |t| t.accent, false),
+    key("D", "Cancel order", |t| t.negative, true),
+    key(":", "buy/sell ... at <price> · ladder ...", |t| t.accent, true),
+    key("Esc", "Close", |t| t.highlight, true),
+    extra("j / k", "Next / previous order"),
+    extra("Delete", "Cancel order"),
+];
+
+pub const PRESETS: &[Binding] = &[
+    key("↑↓", "Preset", |t| t.accent, false),
+    key("Tab", "Field", |t| t.accent, true),
+    key("Enter", "Edit value", |t| t.positive, true),
+    key("N", "Save slot as", |t| t.positive, true),
+    key("R", "Rename", |t| t.accent, true),
+    key("D", "Delete", |t| t.negative, true),
+    key("Esc", "Close", |t| t.highlight, true),
+    extra("j / k", "Next / previous preset"),
+    extra("Shift+Tab / ← →", "Previous / next field"),
+    extra("X", "Remove the field (it takes the [dca] value)"),
+    extra("Delete", "Delete the preset"),
+];
+
+pub const PRESET_INPUT: &[Binding] = &[
+    key("type", "Name / TOML value", |t| t.accent, false),
+    key("Enter", "Save", |t| t.positive, true),
+    key("Esc", "Cancel", |t| t.negative, true),
+    extra("Backspace", "Delete last character"),
+];
+
+pub const LOG_SEARCH: &[Binding] = &[
+    key("type", "Search", |t| t.accent, false),
+    key("Enter / Esc", "Done", |t| t.positive, true),
+    extra("Backspace", "Delete last character"),
+];
+
+pub const LOG_VIEW: &[Binding] = &[
+    key("↑↓ PgUp PgDn", "Scroll", |t| t.accent, false),
+    key("Home / End", "Oldest / newest", |t| t.accent, false),
+    key("F", "Severity", |t| t.highlight, true),
+    key("S", "Selected slot", |t| t.highlight, true),
+    key("/", "Search", |t| t.accent, true),
+    key("Esc / L", "Close", |t| t.highlight, true),
+    extra("j / k", "Scroll one line"),
+    extra("g / G", "Oldest / newest"),
+    extra("Q", "Close"),
+];
+
+pub const DIAGNOSTICS: &[Binding] = &[
+    key("↑↓ PgUp PgDn", "Scroll", |t| t.accent, false),
+    key("any key", "Close", |t| t.highlight, true),
+    extra("j / k", "Scroll one line"),
+];
+
+pub const TRADE_DETAIL: &[Binding] = &[
+    key("[ ] / ↑↓", "Newer / older entry", |t| t.accent, false),
+    key("any key", "Close", |t| t.highlight, true),
+    extra("j / k", "Older / newer entry"),
+];
+
+pub const EDIT_SLOT: &[Binding] = &[
+    key("↑↓ / Tab", "Field", |t| t.accent, true),
+    key("0-9 .", "Value", |t| t.accent, false),
+    key("Enter", "Apply", |t| t.positive, true),
+    key("Esc", "Cancel", |t| t.negative, true),
+    extra("Backspace", "Delete last character"),
+    extra("Enter", "Applies to this slot only (saved with its state, [dca] is untouched)"),
+];
+
+pub const SETTINGS: &[Binding] = &[
+    key("↑↓ / Tab", "Field", |t| t.accent, true),
+    key("Enter", "Save", |t| t.positive, true),
+    key("Esc", "Cancel", |t| t.negative, true),
+    extra("Backspace", "Delete last character (empty = default value)"),
+];
+
+pub const POST_SALE: &[Binding] = &[
+    key("S", "Restart cycle", |t| t.positive, true),
+    key("Esc / any key", "Stay stopped", |t| t.highlight, true),
+];
+
+pub const CONFIRM_CLOSE: &[Binding] = &[
+    key("Enter / Y", "Close market position", |t| t.negative, true),
+    key("Esc / N", "Cancel", |t| t.highlight, true),
+];
+
+pub const CONFIRM_DELETE: &[Binding] = &[
+    key("Enter / Y", "Confirm delete slot", |t| t.negative, true),
+    key("Esc / N", "Cancel", |t| t.highlight, true),
+];
+
+pub const PALETTE: &[Binding] = &[
+    key("↑↓ / Tab", "Select", |t| t.accent, true),
+    key("Enter", "Run", |t| t.positive, true),
+    key("Esc", "Cancel", |t| t.negative, true),
+    extra("Backspace", "Delete last character"),
+];
+
+pub const HELP: &[Binding] = &[
+    key("↑↓ PgUp PgDn", "Scroll", |t| t.accent, false),
+    key("any key", "Close", |t| t.highlight, true),
+    extra("j / k", "Scroll one line"),
+];
+
+/// Todas las secciones, en el orden en que las lista la ayuda
+pub const SECTIONS: &[(&str, &[Binding])] = &[
+    ("Dashboard", NORMAL),
+    ("Previous sessions", RESTORE_SESSION_RECONCILE),
+    ("Damaged state file", RECOVERY),
+    ("New strategy", NEW_STRATEGY),
+    ("New strategy · summary", NEW_STRATEGY_SUMMARY),
+    ("Config (C)", CONFIG),
+    ("Edit slot (E)", EDIT_SLOT),
+    ("Settings (G)", SETTINGS),
+    ("Alerts (A)", ALERTS),
+    ("Conditional orders (O)", TRIGGERS),
+    ("Strategy presets (M)", PRESETS),
+    ("Strategy presets · input", PRESET_INPUT),
+    ("Diagnostics (I)", DIAGNOSTICS),
+    ("Log (L)", LOG_VIEW),
+    ("Log · search (/)", LOG_SEARCH),
+    ("Trade detail (Enter)", TRADE_DETAIL),
+    ("Command palette (:)", PALETTE),
+    ("After a sale", POST_SALE),
+    ("Close position (V)", CONFIRM_CLOSE),
+    ("Delete slot (D)", CONFIRM_DELETE),
+    ("Help (?)", HELP),
+];
+
+/// Teclas del modo actual del UI
+pub fn for_state(state: &AppState) -> &'static [Binding] {
+    match &state.ui_mode {
+        UiMode::Normal => NORMAL,
+        UiMode::RestoreSession(_) if state.can_reconcile_restore() => RESTORE_SESSION_RECONCILE,
+        UiMode::RestoreSession(_) => RESTORE_SESSION,
+        UiMode::Recovery(_) => RECOVERY,
+        UiMode::NewStrategy => NEW_STRATEGY,
+        UiMode::NewStrategySummary => NEW_STRATEGY_SUMMARY,
+        UiMode::Config => CONFIG,
+        UiMode::EditSlot => EDIT_SLOT,
+        UiMode::Settings => SETTINGS,
+        UiMode::Alerts => ALERTS,
+        UiMode::Triggers => TRIGGERS,
+        UiMode::Presets if state.preset_view.input.is_some() => PRESET_INPUT,
+        UiMode::Presets => PRESETS,
+        UiMode::Diagnostics => DIAGNOSTICS,
+        UiMode::LogView if state.log_view.searching => LOG_SEARCH,
+        UiMode::LogView => LOG_VIEW,
+        UiMode::TradeDetail => TRADE_DETAIL,
+        UiMode::Palette => PALETTE,
+        UiMode::PostSale(..) => POST_SALE,
+        UiMode::ConfirmClose => CONFIRM_CLOSE,
+        UiMode::ConfirmDelete => CONFIRM_DELETE,
+        UiMode::Help => HELP,
+    }
+}
+
+/// Spans del footer: "[tecla] acción" de las teclas marcadas para el footer
+pub fn footer_spans(state: &AppState) -> Vec<Span<'static>> {
+    let bindings: Vec<&Binding> = for_state(state).iter().filter(|b| b.footer).collect();
+    let mut spans = vec![Span::raw(" ")];
+    for (i, binding) in bindings.iter().enumerate() {
+        let sep = if i + 1 < bindings.len() { "  " } else { "" };
+        spans.push(Span::styled(format!("[{}]", tr(binding.keys)), binding.style(&state.theme)));
+        spans.push(Span::raw(format!(" {}{}", binding.label(state), sep)));
+    }
+    spans
+}
+
+/// Pista del footer bajo la columna `column` (relativa al inicio del texto del footer)
+pub fn footer_binding_at(state: &AppState, column: u16) -> Option<&'static Binding> {
+    let spans = footer_spans(state);
+    let mut x = spans.first()?.width();
+    let bindings = for_state(state).iter().filter(|b| b.footer);
+    for (binding, pair) in bindings.zip(spans[1..].chunks(2)) {
+        let width: usize = pair.iter().map(|s| s.width()).sum();
+        if (x..x + width).contains(&(column as usize)) {
+            return Some(binding);
+        }
+        x += width;
+    }
+    None
+}
+
+/// Líneas de la ayuda: título, teclas y una línea en blanco por sección
+pub fn help_len() -> usize {
+    SECTIONS.iter().map(|(_, bindings)| bindings.len() + 2).sum()
+}
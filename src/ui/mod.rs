@@ -1 +1,6 @@
+pub mod i18n;
+pub mod keymap;
+pub mod palette;
+pub mod screenshot;
+pub mod theme;
 pub mod tui;
@@ -0,0 +1,350 @@
+use std::path::Path;
+
+use crate::app::AppCommand;
+use crate::config::{QuoteAmount, StrategyKind};
+use crate::models::order::OrderSide;
+use crate::position_import::{ImportedEntry, PositionImport};
+use crate::ui::i18n::tr;
+
+/// Entrada del catálogo de la paleta de comandos (`:`)
+pub struct PaletteItem {
+    /// Lo que se escribe; `<...>` marca un parámetro
+    pub usage: &'static str,
+    pub description: &'static str,
+    /// Tecla del dashboard que hace lo mismo (se muestra al lado, para aprenderla)
+    pub key: Option<&'static str>,
+}
+
+const fn cmd(usage: &'static str, description: &'static str) -> PaletteItem {
+    PaletteItem { usage, description, key: None }
+}
+
+const fn hotkey(key: &'static str, usage: &'static str, description: &'static str) -> PaletteItem {
+    PaletteItem { usage, description, key: Some(key) }
+}
+
+/// Órdenes por escalera (cada una es un request firmado)
+const MAX_LADDER_ORDERS: usize = 20;
+
+/// Todos los comandos disponibles desde la paleta
+pub const ITEMS: &[PaletteItem] = &[
+    hotkey("S", "new", "Open the new strategy modal"),
+    hotkey("X", "toggle", "Start/stop the selected slot"),
+    hotkey("V", "close", "Close the selected position (asks to confirm)"),
+    cmd("close <pct>%", "Close part of the position at market"),
+    hotkey("D", "delete", "Delete the selected slot"),
+    hotkey("F", "flip", "Toggle auto-flip on the selected slot"),
+    hotkey("R", "reduce", "Toggle reduce-only (no new entries) on the selected slot"),
+    hotkey("C", "config", "Open the config panel (amount, BNB)"),
+    hotkey("E", "edit", "Edit TP/SL/trailing/interval of the selected slot"),
+    hotkey("↓", "next", "Select the next slot"),
+    hotkey("↑", "prev", "Select the previous slot"),
+    hotkey("K", "move up", "Move the selected slot up the list"),
+    hotkey("J", "move down", "Move the selected slot down the list"),
+    hotkey("Y", "duplicate", "Copy the selected slot's config onto another symbol"),
+    hotkey("G", "settings", "Edit risk limits, alerts and UI settings in config.toml"),
+    hotkey("M", "presets", "Edit the strategy presets saved in presets.toml"),
+    cmd("preset save <name>", "Save the selected slot's config as a named preset"),
+    hotkey("Enter", "trade", "Detail of the selected Trade History entry"),
+    hotkey("1", "detail", "Dashboard of the selected slot"),
+    hotkey("2", "portfolio", "Summary of all slots and the account equity"),
+    hotkey("T", "chart", "Next chart / S/R interval of the selected slot"),
+    cmd("set tp <pct>", "Take profit % of the selected slot"),
+    cmd("set sl <pct>", "Stop loss % of the selected slot"),
+    cmd("set trail <pct>", "Trailing TP % of the selected slot"),
+    cmd("set breakeven <pct>", "Profit % that arms a break-even stop (0 = off)"),
+    cmd("set beoffset <pct>", "Break-even stop distance past break-even, in % (0 = exact)"),
+    cmd("set drop <pct>", "Price-drop entry trigger %"),
+    cmd("set amount <usdt|pct%>", "Entry amount of the selected slot"),
+    cmd("set interval <min>", "Minutes between entries"),
+    cmd("set maxorders <n>", "Maximum DCA orders"),
+    cmd("set scale <x>", "Size multiplier for each safety order"),
+    cmd("set cooldown <min>", "Cooldown after TP in minutes"),
+    cmd("set iceberg <pct>", "Visible % of limit SHORT entries (0 = off)"),
+    cmd("set capital <usdt>", "Virtual wallet capital of the selected slot (0 = off)"),
+    cmd("set daily <amount>", "Daily spend limit of the selected slot (0 = [risk] max_daily_spend)"),
+    cmd("set spread <pct>", "Max spread for market entries, above it LIMIT_MAKER (0 = off)"),
+    cmd("notify <event> on|off|default", "Beep for alert/stop_loss/error/exit on this slot"),
+    hotkey("U", "undo", "Revert the last config change"),
+    hotkey("A", "alerts", "Show the S/R alert history"),
+    cmd("buy <usdt> <pair> at <price>", "Market buy once when the price reaches a level"),
+    cmd("sell <qty> <pair> at <price>", "Market sell once when the price reaches a level"),
+    hotkey("O", "triggers", "List and cancel the conditional orders"),
+    hotkey("I", "diag", "Price stream diagnostics and session stats"),
+    hotkey("L", "log", "Full log with filters and search"),
+    hotkey("?", "help", "Every key of every mode"),
+    cmd(
+        "ladder buy <pair> <usdt> <low> <high> <count> [linear|geometric]",
+        "Place limit buys spread between two prices",
+    ),
+    cmd(
+        "ladder sell <pair> <usdt> <low> <high> <count> [linear|geometric]",
+        "Place limit sells spread between two prices",
+    ),
+    cmd("ladder cancel <id>", "Cancel the resting orders of a ladder"),
+    cmd("ladder adopt <id>", "Hand a ladder's fills to a new DCA slot for the exit"),
+    cmd("watch <pair>", "Exit-only slot for a holding, cost from the trade history"),
+    cmd("watch <pair> <qty> <avg price>", "Exit-only slot for a holding bought at that cost"),
+    cmd("import <pair> <qty>@<price> ...", "DCA slot seeded with buys made outside the bot"),
+    cmd("import <file.json>", "Slot seeded with the entries of a PositionImport file"),
+    hotkey("P", "snapshot", "Save the dashboard as text and ANSI files"),
+    hotkey("W", "export", "Export the selected slot's trades and the cycle history to CSV and JSON"),
+    hotkey("Q", "quit", "Exit the bot"),
+];
+
+/// Puntaje de coincidencia difusa: todos los caracteres del query deben aparecer
+/// en orden; se premian los caracteres consecutivos y el match al inicio.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0usize;
+    let mut prev_match: Option<usize> = None;
+    for qc in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = text[pos..].iter().position(|c| *c == qc)? + pos;
+        score += match prev_match {
+            Some(p) if p + 1 == found => 5,
+            _ if found == 0 => 3,
+            _ => 1,
+        };
+        prev_match = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
+
+/// Comandos del catálogo que coinciden con lo escrito, mejor primero
+pub fn matches(input: &str) -> Vec<&'static PaletteItem> {
+    // Solo se usa la parte "nombre" del input (sin los valores numéricos)
+    let query: String = input
+        .split_whitespace()
+        .filter(|w| !w.starts_with(|c: char| c.is_ascii_digit() || c == '.'))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut scored: Vec<(i32, usize, &PaletteItem)> = ITEMS
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| {
+            let usage_score = fuzzy_score(&query, item.usage).map(|s| s * 2);
+            let desc_score = fuzzy_score(&query, tr(item.description));
+            usage_score.max(desc_score).map(|s| (s, i, item))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored.into_iter().map(|(_, _, item)| item).collect()
+}
+
+/// Convierte el texto de la paleta en un comando
+pub fn parse(input: &str) -> Result<AppCommand, String> {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let cmd = match words.as_slice() {
+        ["new"] => AppCommand::OpenNewStrategy,
+        ["toggle"] => AppCommand::ToggleStartStopSelected,
+        ["close"] => AppCommand::OpenConfirmClose,
+        ["close", pct] => {
+            let pct = parse_pct(pct)?;
+            if pct <= 0.0 || pct > 100.0 {
+                return Err("close percentage must be between 0 and 100".to_string());
+            }
+            AppCommand::ClosePositionPct(pct)
+        }
+        ["delete"] => AppCommand::OpenConfirmDelete,
+        ["flip"] => AppCommand::ToggleAutoFlip,
+        ["reduce"] => AppCommand::ToggleReduceOnly,
+        ["config"] => AppCommand::OpenConfig,
+        ["edit"] => AppCommand::OpenEditSlot,
+        ["next"] => AppCommand::SlotSelectDown,
+        ["prev"] => AppCommand::SlotSelectUp,
+        ["move", "up"] => AppCommand::MoveSlotUp,
+        ["move", "down"] => AppCommand::MoveSlotDown,
+        ["duplicate"] => AppCommand::DuplicateSlot,
+        ["settings"] => AppCommand::OpenSettings,
+        ["presets"] => AppCommand::OpenPresets,
+        ["preset", "save", name @ ..] if !name.is_empty() => AppCommand::SavePreset(name.join(" ")),
+        ["trade"] => AppCommand::OpenTradeDetail,
+        ["detail"] => AppCommand::SelectTab(0),
+        ["portfolio"] => AppCommand::SelectTab(1),
+        ["chart"] => AppCommand::CycleAnalysisInterval,
+        ["diag"] => AppCommand::OpenDiagnostics,
+        ["log"] => AppCommand::OpenLogView,
+        ["help"] => AppCommand::OpenHelp,
+        ["undo"] => AppCommand::UndoConfigChange,
+        ["snapshot"] => AppCommand::DumpScreen,
+        ["export"] => AppCommand::ExportTrades,
+        ["alerts"] => AppCommand::OpenAlerts,
+        ["triggers"] => AppCommand::OpenTriggers,
+        [side @ ("buy" | "sell"), amount, symbol, "at", price] => parse_trigger(side, amount, symbol, price)?,
+        ["ladder", "cancel", id] => AppCommand::CancelLadder(parse_id(id)?),
+        ["ladder", "adopt", id] => AppCommand::AdoptLadder(parse_id(id)?),
+        ["ladder", side @ ("buy" | "sell"), symbol, total, low, high, count, rest @ ..] if rest.len() <= 1 => {
+            parse_ladder(side, symbol, [total, low, high], count, rest.first().copied())?
+        }
+        ["watch", symbol] => AppCommand::WatchPosition { symbol: symbol.to_uppercase(), position: None },
+        ["watch", symbol, qty, price] => parse_watch(symbol, qty, price)?,
+        ["import", rest @ ..] if !rest.is_empty() => parse_import(rest)?,
+        ["quit"] => AppCommand::Quit,
+        ["set", param, value] => parse_set(param, value)?,
+        ["notify", event, value] => {
+            let event = event.parse().map_err(|e| format!("{}", e))?;
+            let enabled = match *value {
+                "on" => Some(true),
+                "off" => Some(false),
+                "default" => None,
+                _ => return Err(format!("Expected on, off or default: '{}'", value)),
+            };
+            AppCommand::SetSlotNotify { event, enabled }
+        }
+        _ => return Err(format!("Unknown command: '{}'", input.trim())),
+    };
+    Ok(cmd)
+}
+
+fn parse_pct(value: &str) -> Result<f64, String> {
+    value
+        .trim_end_matches('%')
+        .parse::<f64>()
+        .map_err(|_| format!("Invalid number: '{}'", value))
+}
+
+/// Orden condicional: `buy <usdt> <par> at <precio>` o `sell <cantidad> <par> at <precio>`
+fn parse_trigger(side: &str, amount: &str, symbol: &str, price: &str) -> Result<AppCommand, String> {
+    let amount: f64 = amount.parse().map_err(|_| format!("Invalid amount: '{}'", amount))?;
+    let price: f64 = price.trim_start_matches('$').parse().map_err(|_| format!("Invalid price: '{}'", price))?;
+    if amount <= 0.0 || price <= 0.0 {
+        return Err("amount and price must be greater than 0".to_string());
+    }
+    let side = if side == "buy" { OrderSide::Buy } else { OrderSide::Sell };
+    Ok(AppCommand::AddTrigger { side, symbol: symbol.to_uppercase(), amount, price })
+}
+
+/// `ladder buy|sell <par> <usdt> <bajo> <alto> <cantidad> [linear|geometric]`
+fn parse_ladder(
+    side: &str,
+    symbol: &str,
+    [total, low, high]: [&str; 3],
+    count: &str,
+    sizing: Option<&str>,
+) -> Result<AppCommand, String> {
+    let num = |v: &str| v.trim_start_matches('$').parse::<f64>().map_err(|_| format!("Invalid number: '{}'", v));
+    let (total, low, high) = (num(total)?, num(low)?, num(high)?);
+    let count: usize = count.parse().map_err(|_| format!("Invalid order count: '{}'", count))?;
+    if total <= 0.0 || low <= 0.0 || high <= low {
+        return Err("amount must be greater than 0 and prices low < high".to_string());
+    }
+    if !(2..=MAX_LADDER_ORDERS).contains(&count) {
+        return Err(format!("a ladder has between 2 and {} orders", MAX_LADDER_ORDERS));
+    }
+    let sizing = sizing.map(str::parse).transpose()?.unwrap_or_default();
+    let side = if side == "buy" { OrderSide::Buy } else { OrderSide::Sell };
+    Ok(AppCommand::PlaceLadder { side, symbol: symbol.to_uppercase(), total, low, high, count, sizing })
+}
+
+/// `watch <par> <cantidad> <precio promedio>`
+fn parse_watch(symbol: &str, qty: &str, price: &str) -> Result<AppCommand, String> {
+    let qty: f64 = qty.parse().map_err(|_| format!("Invalid quantity: '{}'", qty))?;
+    let price: f64 = price.trim_start_matches('$').parse().map_err(|_| format!("Invalid price: '{}'", price))?;
+    if qty <= 0.0 || price <= 0.0 {
+        return Err("quantity and price must be greater than 0".to_string());
+    }
+    Ok(AppCommand::WatchPosition { symbol: symbol.to_uppercase(), position: Some((qty, price)) })
+}
+
+/// `import <par> <cantidad>@<precio> ...` (una entrada por compra) o `import <archivo.json>`
+fn parse_import(rest: &[&str]) -> Result<AppCommand, String> {
+    let import = match rest {
+        [symbol, entries @ ..] if !entries.is_empty() && entries.iter().all(|e| e.contains('@')) => {
+            let entries = entries
+                .iter()
+                .map(|entry| {
+                    let (qty, price) = entry.split_once('@').unwrap_or_default();
+                    let quantity = qty.parse().map_err(|_| format!("Invalid quantity: '{}'", qty))?;
+                    let price = price.trim_start_matches('$').parse().map_err(|_| format!("Invalid price: '{}'", price))?;
+                    Ok(ImportedEntry { price, quantity, cost: None, timestamp: None, commission: None })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            let import = PositionImport { symbol: symbol.to_uppercase(), kind: StrategyKind::Trading, entries };
+            import.validate().map_err(|e| e.to_string())?;
+            import
+        }
+        // Ruta del archivo (puede tener espacios)
+        _ => PositionImport::load(Path::new(&rest.join(" "))).map_err(|e| format!("{:#}", e))?,
+    };
+    Ok(AppCommand::ImportPosition(import))
+}
+
+fn parse_id(value: &str) -> Result<u64, String> {
+    value.trim_start_matches('#').parse().map_err(|_| format!("Invalid ID: '{}'", value))
+}
+
+fn parse_set(param: &str, value: &str) -> Result<AppCommand, String> {
+    let (field, json) = parse_param(param, value)?;
+    Ok(AppCommand::SetSelectedParam { field: field.to_string(), value: json })
+}
+
+/// Valida el valor de un parámetro de `set` y devuelve (campo de DcaConfig, valor JSON)
+pub fn parse_param(param: &str, value: &str) -> Result<(&'static str, serde_json::Value), String> {
+    let (field, json) = match param {
+        "tp" | "sl" | "trail" | "breakeven" | "beoffset" | "drop" => {
+            let v = parse_pct(value)?;
+            if v < 0.0 {
+                return Err("value must be 0 or greater".to_string());
+            }
+            let field = match param {
+                "tp" => "take_profit_pct",
+                "sl" => "stop_loss_pct",
+                "trail" => "trailing_tp_pct",
+                "breakeven" => "breakeven_trigger_pct",
+                "beoffset" => "breakeven_offset_pct",
+                _ => "price_drop_trigger",
+            };
+            (field, serde_json::json!(v))
+        }
+        "interval" | "maxorders" | "cooldown" => {
+            let v: u64 = value.parse().map_err(|_| format!("Invalid integer: '{}'", value))?;
+            if v == 0 && param != "cooldown" {
+                return Err(format!("{} must be greater than 0", param));
+            }
+            let field = match param {
+                "interval" => "interval_minutes",
+                "maxorders" => "max_orders",
+                _ => "restart_cooldown_minutes",
+            };
+            (field, serde_json::json!(v))
+        }
+        "iceberg" => {
+            let v = parse_pct(value)?;
+            if !(0.0..100.0).contains(&v) {
+                return Err("iceberg must be between 0 and 100 (0 = off)".to_string());
+            }
+            ("iceberg_visible_pct", serde_json::json!(v))
+        }
+        "capital" | "daily" => {
+            let v: f64 = value.parse().map_err(|_| format!("Invalid number: '{}'", value))?;
+            if v < 0.0 {
+                return Err(format!("{} must be 0 or greater (0 = off)", param));
+            }
+            let field = if param == "capital" { "capital_usdt" } else { "max_daily_spend" };
+            (field, serde_json::json!(v))
+        }
+        "spread" => {
+            let v = parse_pct(value)?;
+            if v < 0.0 {
+                return Err("spread must be 0 or greater (0 = off)".to_string());
+            }
+            ("max_market_spread_pct", serde_json::json!(v))
+        }
+        "scale" => {
+            let v: f64 = value.parse().map_err(|_| format!("Invalid number: '{}'", value))?;
+            if v <= 0.0 {
+                return Err("scale must be greater than 0".to_string());
+            }
+            ("volume_scale", serde_json::json!(v))
+        }
+        "amount" => {
+            let amount: QuoteAmount = value.parse().map_err(|e| format!("{}", e))?;
+            amount.validate().map_err(|e| format!("amount {}", e))?;
+            ("quote_amount", serde_json::to_value(amount).map_err(|e| e.to_string())?)
+        }
+        _ => return Err(format!("Unknown parameter: '{}'", param)),
+    };
+    Ok((field, json))
+}
@@ -0,0 +1,117 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier, Style};
+use unicode_width::UnicodeWidthStr;
+
+/// Guarda el dashboard dibujado como texto plano (.txt) y con colores ANSI (.ans)
+/// en `dir`. Devuelve las dos rutas.
+pub fn save(buf: &Buffer, dir: &Path) -> Result<(PathBuf, PathBuf)> {
+    let stem = format!("dashboard-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let txt = dir.join(format!("{}.txt", stem));
+    let ans = dir.join(format!("{}.ans", stem));
+    std::fs::write(&txt, to_plain(buf)).with_context(|| format!("Could not write {:?}", txt))?;
+    std::fs::write(&ans, to_ansi(buf)).with_context(|| format!("Could not write {:?}", ans))?;
+    Ok((txt, ans))
+}
+
+/// Una línea por fila del buffer, sin estilos ni espacios al final
+pub fn to_plain(buf: &Buffer) -> String {
+    let mut out = String::new();
+    for y in buf.area.top()..buf.area.bottom() {
+        let mut line = String::new();
+        for symbol in row_symbols(buf, y) {
+            line.push_str(symbol);
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Igual que `to_plain` pero con secuencias SGR para colores y atributos (se ve con `cat`)
+pub fn to_ansi(buf: &Buffer) -> String {
+    let mut out = String::new();
+    for y in buf.area.top()..buf.area.bottom() {
+        let mut current: Option<Style> = None;
+        let mut x = buf.area.left();
+        while x < buf.area.right() {
+            let cell = &buf[(x, y)];
+            let style = cell.style();
+            if current != Some(style) {
+                out.push_str(&sgr(style));
+                current = Some(style);
+            }
+            out.push_str(cell.symbol());
+            // Los símbolos anchos (emoji) ocupan también la celda siguiente
+            x += cell.symbol().width().max(1) as u16;
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// Símbolos de una fila, salteando las celdas tapadas por caracteres anchos
+fn row_symbols(buf: &Buffer, y: u16) -> Vec<&str> {
+    let mut symbols = Vec::new();
+    let mut x = buf.area.left();
+    while x < buf.area.right() {
+        let symbol = buf[(x, y)].symbol();
+        symbols.push(symbol);
+        x += symbol.width().max(1) as u16;
+    }
+    symbols
+}
+
+fn sgr(style: Style) -> String {
+    let mut codes = String::from("0");
+    let attrs = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+    for (modifier, code) in attrs {
+        if style.add_modifier.contains(modifier) {
+            let _ = write!(codes, ";{}", code);
+        }
+    }
+    if let Some(fg) = style.fg.and_then(|c| color_code(c, false)) {
+        let _ = write!(codes, ";{}", fg);
+    }
+    if let Some(bg) = style.bg.and_then(|c| color_code(c, true)) {
+        let _ = write!(codes, ";{}", bg);
+    }
+    format!("\x1b[{}m", codes)
+}
+
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = match color {
+        Color::Reset => return None,
+        Color::Black => 30,
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Blue => 34,
+        Color::Magenta => 35,
+        Color::Cyan => 36,
+        Color::Gray => 37,
+        Color::DarkGray => 90,
+        Color::LightRed => 91,
+        Color::LightGreen => 92,
+        Color::LightYellow => 93,
+        Color::LightBlue => 94,
+        Color::LightMagenta => 95,
+        Color::LightCyan => 96,
+        Color::White => 97,
+        Color::Rgb(r, g, b) => {
+            return Some(format!("{};2;{};{};{}", if background { 48 } else { 38 }, r, g, b))
+        }
+        Color::Indexed(i) => return Some(format!("{};5;{}", if background { 48 } else { 38 }, i)),
+    };
+    Some((if background { base + 10 } else { base }).to_string())
+}
@@ -0,0 +1,106 @@
+use ratatui::style::Color;
+
+use crate::config::ThemeName;
+
+/// Colores del dashboard por función. El UI nunca usa un `Color::` directo: lo pide acá,
+/// así cada tema ([ui] theme) cambia todo el dashboard.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    /// Texto normal y valores
+    pub text: Color,
+    /// Etiquetas, bordes y datos secundarios
+    pub muted: Color,
+    /// Texto apagado sobre fondo de selección
+    pub subtle: Color,
+    /// Texto sobre un fondo de color (badges, filas seleccionadas)
+    pub on_accent: Color,
+    /// Títulos, selección y avisos
+    pub highlight: Color,
+    /// Ganancias, LONG, estados OK
+    pub positive: Color,
+    /// Pérdidas, SHORT, acciones destructivas
+    pub negative: Color,
+    /// Errores y estados caídos
+    pub error: Color,
+    /// Bordes de paneles, teclas y datos de mercado
+    pub accent: Color,
+    /// Información neutra (cuenta, órdenes pendientes)
+    pub info: Color,
+    /// Auto-flip, acumulación y otros modos especiales
+    pub special: Color,
+}
+
+impl Theme {
+    pub fn named(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Default => Self::DEFAULT,
+            ThemeName::Light => Self::LIGHT,
+            ThemeName::Monochrome => Self::MONOCHROME,
+            ThemeName::Colorblind => Self::COLORBLIND,
+        }
+    }
+
+    pub const DEFAULT: Theme = Theme {
+        text: Color::White,
+        muted: Color::DarkGray,
+        subtle: Color::Gray,
+        on_accent: Color::Black,
+        highlight: Color::Yellow,
+        positive: Color::Green,
+        negative: Color::Red,
+        error: Color::LightRed,
+        accent: Color::Cyan,
+        info: Color::Blue,
+        special: Color::Magenta,
+    };
+
+    pub const LIGHT: Theme = Theme {
+        text: Color::Black,
+        muted: Color::DarkGray,
+        subtle: Color::DarkGray,
+        on_accent: Color::White,
+        highlight: Color::Rgb(160, 110, 0),
+        positive: Color::Rgb(0, 130, 40),
+        negative: Color::Rgb(190, 20, 20),
+        error: Color::Rgb(200, 50, 50),
+        accent: Color::Rgb(0, 110, 150),
+        info: Color::Blue,
+        special: Color::Magenta,
+    };
+
+    // Sin tonos: lo que distingue ganancias de pérdidas es el signo y el texto
+    pub const MONOCHROME: Theme = Theme {
+        text: Color::White,
+        muted: Color::DarkGray,
+        subtle: Color::Gray,
+        on_accent: Color::Black,
+        highlight: Color::White,
+        positive: Color::White,
+        negative: Color::White,
+        error: Color::White,
+        accent: Color::Gray,
+        info: Color::Gray,
+        special: Color::Gray,
+    };
+
+    // Paleta Okabe-Ito: distinguible con protanopia, deuteranopia y tritanopia
+    pub const COLORBLIND: Theme = Theme {
+        text: Color::White,
+        muted: Color::DarkGray,
+        subtle: Color::Gray,
+        on_accent: Color::Black,
+        highlight: Color::Rgb(240, 228, 66),
+        positive: Color::Rgb(0, 114, 178),
+        negative: Color::Rgb(230, 159, 0),
+        error: Color::Rgb(213, 94, 0),
+        accent: Color::Rgb(86, 180, 233),
+        info: Color::Rgb(0, 158, 115),
+        special: Color::Rgb(204, 121, 167),
+    };
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
@@ -1,17 +1,20 @@
 use std::io::{self, Stdout};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use crossterm::{
-    event::{Event, EventStream, KeyCode, KeyEventKind, KeyModifiers},
+    event::{
+        DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, Event, EventStream, KeyCode,
+        KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use futures_util::StreamExt;
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, BorderType, Borders, Cell, Clear, Paragraph, Row, Table, Wrap},
@@ -19,50 +22,149 @@ use ratatui::{
 };
 use tokio::sync::{mpsc, Mutex};
 
-use crate::app::{AppCommand, AppState, SaleResult, UiMode, MAX_SLOTS};
-use crate::config::Direction as TradeDirection;
+use crate::api::websocket::StreamStatus;
+use crate::app::{
+    fmt_pnl, fmt_price, fmt_quote, fmt_quote_named, fmt_time, is_usd_quote, AppCommand, AppState, LogEntry, LogLevel, PresetInput, RestoreIssue, SaleResult, StateRecovery, StrategySlot, UiMode, ViewTab, EDIT_SLOT_FIELDS, SETTINGS_FIELDS,
+    NEW_STRAT_FIELDS,
+};
+use crate::config::{Direction as TradeDirection, NotifyEvent, QuoteAmount, StrategyKind, TakeProfit, UiConfig};
+use crate::market_data;
+use crate::models::order::OrderSide;
+use crate::models::ticker::Kline;
+use crate::session_stats::Counters;
 use crate::strategy::dca::DcaState;
+use crate::strategy::fees;
+use crate::strategy::health::{self, HealthLevel};
+use crate::strategy::performance::{self, RealizedPnl};
+use crate::ui::theme::Theme;
+use crate::ui::i18n::{tr, trf};
+use crate::ui::{keymap, palette, screenshot};
+
+/// Líneas que se mueve la vista de log con PgUp/PgDn
+const LOG_PAGE: isize = 10;
+
+/// Redibujo forzado aunque el estado no cambie (reloj, cuenta regresiva, cooldowns)
+const IDLE_REDRAW: Duration = Duration::from_secs(1);
+
+/// Ancho por debajo del cual Price y DCA Strategy se apilan y se ocultan las secciones opcionales
+pub const COMPACT_WIDTH: u16 = 100;
+/// Alto que necesita el layout ancho (3 + 22 + 6 + 7 + 3); por debajo también se apila
+pub const WIDE_HEIGHT: u16 = 41;
+/// Alto del layout compacto con el panel de precio completo (3 + 11 + 15 + 6 + 5 + 3); por
+/// debajo el precio queda en una franja de una línea y el log en una
+const STACKED_HEIGHT: u16 = 43;
+/// Tamaño mínimo (ancho, alto): el layout compacto con la franja de precio (3 + 3 + 15 + 6 +
+/// 3 + 3). Por debajo solo se muestra el aviso de terminal chica
+pub const MIN_SIZE: (u16, u16) = (60, 33);
 
-const TICK_MS: u64 = 150; // ~6 FPS refresh rate
+/// Zonas del layout principal
+struct Areas {
+    header: Rect,
+    /// Cuerpo completo (pestaña Portfolio)
+    body: Rect,
+    slots: Rect,
+    price: Rect,
+    dca: Rect,
+    trades: Rect,
+    log: Rect,
+    footer: Rect,
+    /// Layout angosto o bajo: paneles apilados, sin gráficos; de performance solo el P&L
+    compact: bool,
+    /// Terminal baja: el panel de precio es una franja con el precio y la variación 24h
+    strip: bool,
+}
 
 pub struct Tui {
     terminal: Terminal<CrosstermBackend<Stdout>>,
     state: Arc<Mutex<AppState>>,
     cmd_tx: mpsc::Sender<AppCommand>,
+    /// Intervalo de chequeo de cambios ([ui] refresh_ms)
+    tick: Duration,
+    /// Intervalo de redibujo con la terminal sin foco ([ui] unfocused_refresh_ms)
+    unfocused_tick: Duration,
 }
 
 impl Tui {
     pub fn new(
         state: Arc<Mutex<AppState>>,
         cmd_tx: mpsc::Sender<AppCommand>,
+        ui: &UiConfig,
     ) -> Result<Self> {
         enable_raw_mode()?;
         let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen)?;
+        // Eventos de foco: sin foco se baja la frecuencia de redibujo (el motor sigue igual).
+        // Mouse: click en slots y pistas del footer, rueda en Trade History
+        execute!(stdout, EnterAlternateScreen, EnableFocusChange, EnableMouseCapture)?;
         let backend = CrosstermBackend::new(stdout);
         let terminal = Terminal::new(backend)?;
 
-        Ok(Self { terminal, state, cmd_tx })
+        Ok(Self {
+            terminal,
+            state,
+            cmd_tx,
+            tick: Duration::from_millis(ui.refresh_ms),
+            unfocused_tick: Duration::from_millis(ui.unfocused_refresh_ms),
+        })
     }
 
     pub async fn run(&mut self) -> Result<()> {
         let mut event_stream = EventStream::new();
-        let tick = Duration::from_millis(TICK_MS);
+        // Último estado dibujado: solo se redibuja si cambió la revisión, hubo un evento
+        // de terminal (tecla, resize) o pasó IDLE_REDRAW
+        let mut drawn_revision: Option<u64> = None;
+        let mut last_draw = Instant::now();
+        let mut force_redraw = true;
+        // Las terminales que no reportan foco nunca envían FocusLost: se asume con foco
+        let mut focused = true;
 
         loop {
             {
-                let state = self.state.lock().await;
-                self.terminal.draw(|f| Self::render(f, &state))?;
+                let mut state = crate::profiling::lock(&self.state, "render").await;
+                let due = if focused {
+                    drawn_revision != Some(state.revision) || last_draw.elapsed() >= IDLE_REDRAW
+                } else {
+                    last_draw.elapsed() >= self.unfocused_tick
+                };
+                if force_redraw || due || state.dump_screen_requested {
+                    let frame = self.terminal.draw(|f| Self::render(f, &state))?;
+                    if state.dump_screen_requested {
+                        state.dump_screen_requested = false;
+                        match screenshot::save(frame.buffer, &crate::config::exe_dir()) {
+                            Ok((txt, ans)) => state.log(&format!(
+                                "Dashboard saved: {} / {}",
+                                txt.display(),
+                                ans.display()
+                            )),
+                            Err(e) => state.log_error(&format!("Could not save dashboard: {}", e)),
+                        }
+                    }
+                    drawn_revision = Some(state.revision);
+                    last_draw = Instant::now();
+                    force_redraw = false;
+                }
             }
 
+            let tick = if focused { self.tick } else { self.unfocused_tick };
             tokio::select! {
                 _ = tokio::time::sleep(tick) => {}
                 maybe_event = event_stream.next() => {
+                    force_redraw = true;
                     match maybe_event {
-                        Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
-                            if self.handle_key(key.code, key.modifiers).await? {
-                                break;
-                            }
+                        Some(Ok(Event::FocusLost)) => {
+                            focused = false;
+                            force_redraw = false;
+                        }
+                        Some(Ok(Event::FocusGained)) => {
+                            focused = true;
+                        }
+                        Some(Ok(Event::Key(key)))
+                            if key.kind == KeyEventKind::Press
+                                && self.handle_key(key.code, key.modifiers).await? =>
+                        {
+                            break;
+                        }
+                        Some(Ok(Event::Mouse(mouse))) if self.handle_mouse(mouse).await? => {
+                            break;
                         }
                         Some(Err(e)) => {
                             tracing::error!("Event error: {}", e);
@@ -93,6 +195,25 @@ impl Tui {
                 KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
                     let _ = self.cmd_tx.send(AppCommand::RestoreSessionDiscard).await;
                 }
+                // Solo si hay algo que ajustar (la pista no se muestra si no)
+                KeyCode::Char('r') | KeyCode::Char('R') if self.state.lock().await.can_reconcile_restore() => {
+                    let _ = self.cmd_tx.send(AppCommand::RestoreSessionReconcile).await;
+                }
+                _ => {}
+            },
+
+            // ----------------------------------------------------------------
+            // Sin Esc: la decisión tiene que ser explícita
+            UiMode::Recovery(recovery) => match code {
+                KeyCode::Char('b') | KeyCode::Char('B') if recovery.backup.is_some() => {
+                    let _ = self.cmd_tx.send(AppCommand::RecoveryLoadBackup).await;
+                }
+                KeyCode::Char('r') | KeyCode::Char('R') => {
+                    let _ = self.cmd_tx.send(AppCommand::RecoveryRebuild).await;
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') => {
+                    let _ = self.cmd_tx.send(AppCommand::RecoveryStartClean).await;
+                }
                 _ => {}
             },
 
@@ -106,10 +227,21 @@ impl Tui {
                 }
             },
 
+            // ----------------------------------------------------------------
+            UiMode::NewStrategySummary => match code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    let _ = self.cmd_tx.send(AppCommand::NewStratConfirm).await;
+                }
+                KeyCode::Esc | KeyCode::Backspace => {
+                    let _ = self.cmd_tx.send(AppCommand::NewStratBack).await;
+                }
+                _ => {}
+            },
+
             // ----------------------------------------------------------------
             UiMode::NewStrategy => match code {
                 KeyCode::Enter => {
-                    let _ = self.cmd_tx.send(AppCommand::NewStratConfirm).await;
+                    let _ = self.cmd_tx.send(AppCommand::NewStratReview).await;
                 }
                 KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
                     let _ = self.cmd_tx.send(AppCommand::NewStratCancel).await;
@@ -123,6 +255,9 @@ impl Tui {
                 KeyCode::Tab => {
                     let _ = self.cmd_tx.send(AppCommand::NewStratToggleDirection).await;
                 }
+                KeyCode::Char('t') | KeyCode::Char('T') => {
+                    let _ = self.cmd_tx.send(AppCommand::NewStratToggleKind).await;
+                }
                 KeyCode::Left | KeyCode::Right | KeyCode::Char('h') | KeyCode::Char('l') => {
                     let _ = self.cmd_tx.send(AppCommand::NewStratToggleAutoRestart).await;
                 }
@@ -132,6 +267,22 @@ impl Tui {
                 KeyCode::Char('b') | KeyCode::Char('B') => {
                     let _ = self.cmd_tx.send(AppCommand::NewStratToggleBnb).await;
                 }
+                KeyCode::Char('g') | KeyCode::Char('G') => {
+                    let _ = self.cmd_tx.send(AppCommand::NewStratCycleGroup).await;
+                }
+                KeyCode::Char('p') | KeyCode::Char('P') => {
+                    let _ = self.cmd_tx.send(AppCommand::NewStratCyclePreset).await;
+                }
+                // Monto, TP, SL e intervalo: N cambia de campo, los dígitos escriben
+                KeyCode::Char('n') | KeyCode::Char('N') => {
+                    let _ = self.cmd_tx.send(AppCommand::NewStratNextField).await;
+                }
+                KeyCode::Char(c) if c.is_ascii_digit() || c == '.' || c == '%' => {
+                    let _ = self.cmd_tx.send(AppCommand::NewStratInput(c)).await;
+                }
+                KeyCode::Backspace => {
+                    let _ = self.cmd_tx.send(AppCommand::NewStratBackspace).await;
+                }
                 _ => {}
             },
 
@@ -155,6 +306,237 @@ impl Tui {
                 _ => {}
             },
 
+            // ----------------------------------------------------------------
+            UiMode::Alerts => match code {
+                KeyCode::Enter | KeyCode::Char('a') | KeyCode::Char('A') => {
+                    let _ = self.cmd_tx.send(AppCommand::AckAlerts).await;
+                }
+                KeyCode::Char('c') | KeyCode::Char('C') => {
+                    let _ = self.cmd_tx.send(AppCommand::ClearAlerts).await;
+                }
+                _ => {
+                    let _ = self.cmd_tx.send(AppCommand::CloseConfig).await;
+                }
+            },
+
+            // ----------------------------------------------------------------
+            UiMode::Diagnostics => match code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    let _ = self.cmd_tx.send(AppCommand::DiagnosticsScroll(-1)).await;
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let _ = self.cmd_tx.send(AppCommand::DiagnosticsScroll(1)).await;
+                }
+                KeyCode::PageUp => {
+                    let _ = self.cmd_tx.send(AppCommand::DiagnosticsScroll(-LOG_PAGE)).await;
+                }
+                KeyCode::PageDown => {
+                    let _ = self.cmd_tx.send(AppCommand::DiagnosticsScroll(LOG_PAGE)).await;
+                }
+                _ => {
+                    let _ = self.cmd_tx.send(AppCommand::CloseConfig).await;
+                }
+            },
+
+            // ----------------------------------------------------------------
+            // Detalle de una entrada: [ ] recorre las demás sin cerrar
+            UiMode::TradeDetail => match code {
+                KeyCode::Char('[') | KeyCode::Up | KeyCode::Char('k') => {
+                    let _ = self.cmd_tx.send(AppCommand::TradeSelectUp).await;
+                }
+                KeyCode::Char(']') | KeyCode::Down | KeyCode::Char('j') => {
+                    let _ = self.cmd_tx.send(AppCommand::TradeSelectDown).await;
+                }
+                _ => {
+                    let _ = self.cmd_tx.send(AppCommand::CloseConfig).await;
+                }
+            },
+
+            // ----------------------------------------------------------------
+            UiMode::Help => match code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    let _ = self.cmd_tx.send(AppCommand::HelpScroll(-1)).await;
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let _ = self.cmd_tx.send(AppCommand::HelpScroll(1)).await;
+                }
+                KeyCode::PageUp => {
+                    let _ = self.cmd_tx.send(AppCommand::HelpScroll(-LOG_PAGE)).await;
+                }
+                KeyCode::PageDown => {
+                    let _ = self.cmd_tx.send(AppCommand::HelpScroll(LOG_PAGE)).await;
+                }
+                _ => {
+                    let _ = self.cmd_tx.send(AppCommand::CloseConfig).await;
+                }
+            },
+
+            // ----------------------------------------------------------------
+            // Escribiendo la búsqueda: todas las teclas van al texto
+            UiMode::LogView if self.state.lock().await.log_view.searching => match code {
+                KeyCode::Enter | KeyCode::Esc => {
+                    let _ = self.cmd_tx.send(AppCommand::LogSearchDone).await;
+                }
+                KeyCode::Backspace => {
+                    let _ = self.cmd_tx.send(AppCommand::LogSearchBackspace).await;
+                }
+                KeyCode::Char(c) => {
+                    let _ = self.cmd_tx.send(AppCommand::LogSearchInput(c)).await;
+                }
+                _ => {}
+            },
+            UiMode::LogView => match code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    let _ = self.cmd_tx.send(AppCommand::LogScroll(1)).await;
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let _ = self.cmd_tx.send(AppCommand::LogScroll(-1)).await;
+                }
+                KeyCode::PageUp => {
+                    let _ = self.cmd_tx.send(AppCommand::LogScroll(LOG_PAGE)).await;
+                }
+                KeyCode::PageDown => {
+                    let _ = self.cmd_tx.send(AppCommand::LogScroll(-LOG_PAGE)).await;
+                }
+                KeyCode::Home | KeyCode::Char('g') => {
+                    let _ = self.cmd_tx.send(AppCommand::LogScroll(isize::MAX)).await;
+                }
+                KeyCode::End | KeyCode::Char('G') => {
+                    let _ = self.cmd_tx.send(AppCommand::LogScroll(isize::MIN)).await;
+                }
+                KeyCode::Char('f') | KeyCode::Char('F') => {
+                    let _ = self.cmd_tx.send(AppCommand::LogCycleLevel).await;
+                }
+                KeyCode::Char('s') | KeyCode::Char('S') => {
+                    let _ = self.cmd_tx.send(AppCommand::LogToggleSlot).await;
+                }
+                KeyCode::Char('/') => {
+                    let _ = self.cmd_tx.send(AppCommand::LogSearchStart).await;
+                }
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Char('l') | KeyCode::Char('L') => {
+                    let _ = self.cmd_tx.send(AppCommand::CloseConfig).await;
+                }
+                _ => {}
+            },
+
+            // ----------------------------------------------------------------
+            // Escribiendo un nombre o un valor: las teclas van al texto
+            UiMode::Presets if self.state.lock().await.preset_view.input.is_some() => match code {
+                KeyCode::Enter => {
+                    let _ = self.cmd_tx.send(AppCommand::PresetInputDone).await;
+                }
+                KeyCode::Esc => {
+                    let _ = self.cmd_tx.send(AppCommand::PresetInputCancel).await;
+                }
+                KeyCode::Backspace => {
+                    let _ = self.cmd_tx.send(AppCommand::PresetInputBackspace).await;
+                }
+                KeyCode::Char(c) => {
+                    let _ = self.cmd_tx.send(AppCommand::PresetInputChar(c)).await;
+                }
+                _ => {}
+            },
+
+            // ----------------------------------------------------------------
+            UiMode::Presets => match code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    let _ = self.cmd_tx.send(AppCommand::PresetSelect(-1)).await;
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let _ = self.cmd_tx.send(AppCommand::PresetSelect(1)).await;
+                }
+                KeyCode::Tab | KeyCode::Right => {
+                    let _ = self.cmd_tx.send(AppCommand::PresetField(1)).await;
+                }
+                KeyCode::BackTab | KeyCode::Left => {
+                    let _ = self.cmd_tx.send(AppCommand::PresetField(-1)).await;
+                }
+                KeyCode::Enter => {
+                    let _ = self.cmd_tx.send(AppCommand::PresetStartInput(PresetInput::Value)).await;
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') => {
+                    let _ = self.cmd_tx.send(AppCommand::PresetStartInput(PresetInput::SaveAs)).await;
+                }
+                KeyCode::Char('r') | KeyCode::Char('R') => {
+                    let _ = self.cmd_tx.send(AppCommand::PresetStartInput(PresetInput::Rename)).await;
+                }
+                KeyCode::Char('x') | KeyCode::Char('X') => {
+                    let _ = self.cmd_tx.send(AppCommand::PresetRemoveField).await;
+                }
+                KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Delete => {
+                    let _ = self.cmd_tx.send(AppCommand::PresetDelete).await;
+                }
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Char('m') | KeyCode::Char('M') => {
+                    let _ = self.cmd_tx.send(AppCommand::CloseConfig).await;
+                }
+                _ => {}
+            },
+
+            // ----------------------------------------------------------------
+            UiMode::Triggers => match code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    let _ = self.cmd_tx.send(AppCommand::TriggerSelectUp).await;
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let _ = self.cmd_tx.send(AppCommand::TriggerSelectDown).await;
+                }
+                KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Delete => {
+                    let _ = self.cmd_tx.send(AppCommand::CancelTrigger).await;
+                }
+                KeyCode::Char(':') => {
+                    let _ = self.cmd_tx.send(AppCommand::OpenPalette).await;
+                }
+                _ => {
+                    let _ = self.cmd_tx.send(AppCommand::CloseConfig).await;
+                }
+            },
+
+            // ----------------------------------------------------------------
+            UiMode::Settings => match code {
+                KeyCode::Esc => {
+                    let _ = self.cmd_tx.send(AppCommand::CloseConfig).await;
+                }
+                KeyCode::Enter => {
+                    let _ = self.cmd_tx.send(AppCommand::SettingsConfirm).await;
+                }
+                KeyCode::Up => {
+                    let _ = self.cmd_tx.send(AppCommand::SettingsUp).await;
+                }
+                KeyCode::Down | KeyCode::Tab => {
+                    let _ = self.cmd_tx.send(AppCommand::SettingsDown).await;
+                }
+                KeyCode::Backspace => {
+                    let _ = self.cmd_tx.send(AppCommand::SettingsBackspace).await;
+                }
+                KeyCode::Char(c) => {
+                    let _ = self.cmd_tx.send(AppCommand::SettingsInput(c)).await;
+                }
+                _ => {}
+            },
+
+            // ----------------------------------------------------------------
+            UiMode::EditSlot => match code {
+                KeyCode::Esc => {
+                    let _ = self.cmd_tx.send(AppCommand::CloseConfig).await;
+                }
+                KeyCode::Enter => {
+                    let _ = self.cmd_tx.send(AppCommand::EditSlotConfirm).await;
+                }
+                KeyCode::Up => {
+                    let _ = self.cmd_tx.send(AppCommand::EditSlotUp).await;
+                }
+                KeyCode::Down | KeyCode::Tab => {
+                    let _ = self.cmd_tx.send(AppCommand::EditSlotDown).await;
+                }
+                KeyCode::Backspace => {
+                    let _ = self.cmd_tx.send(AppCommand::EditSlotBackspace).await;
+                }
+                KeyCode::Char(c) => {
+                    let _ = self.cmd_tx.send(AppCommand::EditSlotInput(c)).await;
+                }
+                _ => {}
+            },
+
             // ----------------------------------------------------------------
             UiMode::ConfirmClose => match code {
                 KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
@@ -175,6 +557,29 @@ impl Tui {
                 }
             },
 
+            // ----------------------------------------------------------------
+            UiMode::Palette => match code {
+                KeyCode::Esc => {
+                    let _ = self.cmd_tx.send(AppCommand::PaletteCancel).await;
+                }
+                KeyCode::Enter => {
+                    let _ = self.cmd_tx.send(AppCommand::PaletteConfirm).await;
+                }
+                KeyCode::Up => {
+                    let _ = self.cmd_tx.send(AppCommand::PaletteUp).await;
+                }
+                KeyCode::Down | KeyCode::Tab => {
+                    let _ = self.cmd_tx.send(AppCommand::PaletteDown).await;
+                }
+                KeyCode::Backspace => {
+                    let _ = self.cmd_tx.send(AppCommand::PaletteBackspace).await;
+                }
+                KeyCode::Char(c) => {
+                    let _ = self.cmd_tx.send(AppCommand::PaletteInput(c)).await;
+                }
+                _ => {}
+            },
+
             // ----------------------------------------------------------------
             UiMode::Normal => match code {
                 KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => {
@@ -187,11 +592,18 @@ impl Tui {
                 }
                 // Nueva estrategia
                 KeyCode::Char('s') | KeyCode::Char('S') => {
-                    let slots_len = self.state.lock().await.slots.len();
-                    if slots_len < MAX_SLOTS {
+                    let can_add = self.state.lock().await.can_add_slot();
+                    if can_add {
                         let _ = self.cmd_tx.send(AppCommand::OpenNewStrategy).await;
                     }
                 }
+                // Duplicar la config del slot seleccionado en otro símbolo
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    let can_add = self.state.lock().await.can_add_slot();
+                    if can_add {
+                        let _ = self.cmd_tx.send(AppCommand::DuplicateSlot).await;
+                    }
+                }
                 // Iniciar/Detener slot seleccionado (X)
                 KeyCode::Char('x') | KeyCode::Char('X') => {
                     let _ = self.cmd_tx.send(AppCommand::ToggleStartStopSelected).await;
@@ -208,10 +620,86 @@ impl Tui {
                 KeyCode::Char('f') | KeyCode::Char('F') => {
                     let _ = self.cmd_tx.send(AppCommand::ToggleAutoFlip).await;
                 }
+                // Reduce-only: sin nuevas entradas, se sigue gestionando la salida
+                KeyCode::Char('r') | KeyCode::Char('R') => {
+                    let _ = self.cmd_tx.send(AppCommand::ToggleReduceOnly).await;
+                }
                 // Configuración (monto)
                 KeyCode::Char('c') | KeyCode::Char('C') => {
                     let _ = self.cmd_tx.send(AppCommand::OpenConfig).await;
                 }
+                // Editar TP/SL/trailing/intervalo del slot seleccionado
+                KeyCode::Char('e') | KeyCode::Char('E') => {
+                    let _ = self.cmd_tx.send(AppCommand::OpenEditSlot).await;
+                }
+                // Límites de riesgo, alertas y UI de config.toml
+                KeyCode::Char('g') | KeyCode::Char('G') => {
+                    let _ = self.cmd_tx.send(AppCommand::OpenSettings).await;
+                }
+                // Deshacer último cambio de config
+                KeyCode::Char('u') | KeyCode::Char('U') => {
+                    let _ = self.cmd_tx.send(AppCommand::UndoConfigChange).await;
+                }
+                // Historial de alertas S/R
+                KeyCode::Char('a') | KeyCode::Char('A') => {
+                    let _ = self.cmd_tx.send(AppCommand::OpenAlerts).await;
+                }
+                // Órdenes condicionales
+                KeyCode::Char('o') | KeyCode::Char('O') => {
+                    let _ = self.cmd_tx.send(AppCommand::OpenTriggers).await;
+                }
+                // Presets de estrategia (presets.toml)
+                KeyCode::Char('m') | KeyCode::Char('M') => {
+                    let _ = self.cmd_tx.send(AppCommand::OpenPresets).await;
+                }
+                // Intervalo del gráfico y niveles S/R del slot seleccionado
+                KeyCode::Char('t') | KeyCode::Char('T') => {
+                    let _ = self.cmd_tx.send(AppCommand::CycleAnalysisInterval).await;
+                }
+                // Diagnóstico de la conexión de precios
+                KeyCode::Char('i') | KeyCode::Char('I') => {
+                    let _ = self.cmd_tx.send(AppCommand::OpenDiagnostics).await;
+                }
+                // Log completo con filtros y búsqueda
+                KeyCode::Char('l') | KeyCode::Char('L') => {
+                    let _ = self.cmd_tx.send(AppCommand::OpenLogView).await;
+                }
+                // Guardar el dashboard como texto / ANSI
+                KeyCode::Char('p') | KeyCode::Char('P') => {
+                    let _ = self.cmd_tx.send(AppCommand::DumpScreen).await;
+                }
+                // Exportar trades del slot e historial de ciclos (CSV / JSON)
+                KeyCode::Char('w') | KeyCode::Char('W') => {
+                    let _ = self.cmd_tx.send(AppCommand::ExportTrades).await;
+                }
+                // Paleta de comandos
+                KeyCode::Char(':') => {
+                    let _ = self.cmd_tx.send(AppCommand::OpenPalette).await;
+                }
+                // Ayuda con todas las teclas
+                KeyCode::Char('?') => {
+                    let _ = self.cmd_tx.send(AppCommand::OpenHelp).await;
+                }
+                // Pestañas: detalle del slot / portfolio
+                KeyCode::Tab => {
+                    let _ = self.cmd_tx.send(AppCommand::NextTab).await;
+                }
+                KeyCode::Char(c @ '1'..='4') => {
+                    let _ = self.cmd_tx.send(AppCommand::SelectTab(c as usize - '1' as usize)).await;
+                }
+                // Reordenar slots (Shift+↑↓ o K/J)
+                KeyCode::Up if modifiers.contains(KeyModifiers::SHIFT) => {
+                    let _ = self.cmd_tx.send(AppCommand::MoveSlotUp).await;
+                }
+                KeyCode::Down if modifiers.contains(KeyModifiers::SHIFT) => {
+                    let _ = self.cmd_tx.send(AppCommand::MoveSlotDown).await;
+                }
+                KeyCode::Char('K') => {
+                    let _ = self.cmd_tx.send(AppCommand::MoveSlotUp).await;
+                }
+                KeyCode::Char('J') => {
+                    let _ = self.cmd_tx.send(AppCommand::MoveSlotDown).await;
+                }
                 // Navegar slots
                 KeyCode::Up | KeyCode::Char('k') => {
                     let _ = self.cmd_tx.send(AppCommand::SlotSelectUp).await;
@@ -219,15 +707,90 @@ impl Tui {
                 KeyCode::Down | KeyCode::Char('j') => {
                     let _ = self.cmd_tx.send(AppCommand::SlotSelectDown).await;
                 }
+                // Navegar Trade History y ver el detalle de una entrada
+                KeyCode::Char('[') => {
+                    let _ = self.cmd_tx.send(AppCommand::TradeSelectUp).await;
+                }
+                KeyCode::Char(']') => {
+                    let _ = self.cmd_tx.send(AppCommand::TradeSelectDown).await;
+                }
+                KeyCode::Enter => {
+                    let _ = self.cmd_tx.send(AppCommand::OpenTradeDetail).await;
+                }
                 _ => {}
             },
         }
         Ok(false)
     }
 
+    /// Click en un slot de la lista o en una pista del footer (como si se pulsara su tecla)
+    /// y rueda sobre Trade History. Devuelve true si el click pidió salir.
+    async fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<bool> {
+        let (width, height) = crossterm::terminal::size()?;
+        if width < MIN_SIZE.0 || height < MIN_SIZE.1 {
+            return Ok(false);
+        }
+        let areas = Self::areas(Rect::new(0, 0, width, height));
+        let pos = Position::new(mouse.column, mouse.row);
+        let (ui_mode, view_tab) = {
+            let s = self.state.lock().await;
+            (s.ui_mode.clone(), s.view_tab)
+        };
+        // Los overlays tapan el dashboard: solo el footer responde mientras están abiertos
+        let dashboard = ui_mode == UiMode::Normal && view_tab == ViewTab::Detail;
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if areas.footer.contains(pos) => {
+                let press = {
+                    let s = self.state.lock().await;
+                    keymap::footer_binding_at(&s, mouse.column.saturating_sub(areas.footer.x + 1))
+                        .and_then(|b| b.press())
+                };
+                if let Some(code) = press {
+                    return self.handle_key(code, KeyModifiers::NONE).await;
+                }
+            }
+            MouseEventKind::Down(MouseButton::Left) if dashboard && areas.slots.contains(pos) => {
+                let row = mouse.row.saturating_sub(areas.slots.y + 1) as usize;
+                let cmd = {
+                    let s = self.state.lock().await;
+                    // La lista puede estar desplazada para mostrar el slot seleccionado
+                    let row = row + Self::slot_list_offset(&s, areas.slots.height.saturating_sub(2) as usize);
+                    // Cada slot ocupa una o dos líneas; debajo, la pista "[S] New"
+                    let mut first = 0;
+                    let mut hit = None;
+                    for (i, slot) in s.slots.iter().enumerate() {
+                        let rows = if Self::slot_has_detail(slot) { 2 } else { 1 };
+                        if row < first + rows {
+                            hit = Some(AppCommand::SelectSlot(i));
+                            break;
+                        }
+                        first += rows;
+                    }
+                    match hit {
+                        Some(cmd) => Some(cmd),
+                        None if row == first && s.can_add_slot() => Some(AppCommand::OpenNewStrategy),
+                        None => None,
+                    }
+                };
+                if let Some(cmd) = cmd {
+                    let _ = self.cmd_tx.send(cmd).await;
+                }
+            }
+            MouseEventKind::ScrollUp if dashboard && areas.trades.contains(pos) => {
+                let _ = self.cmd_tx.send(AppCommand::TradeSelectUp).await;
+            }
+            MouseEventKind::ScrollDown if dashboard && areas.trades.contains(pos) => {
+                let _ = self.cmd_tx.send(AppCommand::TradeSelectDown).await;
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
     fn cleanup(&mut self) -> Result<()> {
         disable_raw_mode()?;
-        execute!(self.terminal.backend_mut(), LeaveAlternateScreen)?;
+        execute!(self.terminal.backend_mut(), DisableMouseCapture, DisableFocusChange, LeaveAlternateScreen)?;
         self.terminal.show_cursor()?;
         Ok(())
     }
@@ -236,16 +799,23 @@ impl Tui {
     // Rendering principal
     // -----------------------------------------------------------
 
-    fn render(f: &mut Frame, state: &AppState) {
-        let size = f.area();
+    /// Zonas del dashboard; también las usa el mouse para saber qué se clickeó
+    fn areas(size: Rect) -> Areas {
+        let compact = size.width < COMPACT_WIDTH || size.height < WIDE_HEIGHT;
+        let strip = compact && size.height < STACKED_HEIGHT;
 
-        // Layout vertical principal
+        // Layout vertical principal (en modo compacto el log cede filas a los paneles)
+        let log_rows = match (compact, strip) {
+            (_, true) => 3,
+            (true, false) => 5,
+            (false, _) => 7,
+        };
         let main_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),  // header
                 Constraint::Min(10),    // body (split horizontal)
-                Constraint::Length(7),  // log
+                Constraint::Length(log_rows),  // log
                 Constraint::Length(3),  // footer
             ])
             .split(size);
@@ -260,38 +830,121 @@ impl Tui {
             .split(main_chunks[1]);
 
         // Contenido principal: stats + trades
-        let content_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(10), // precio + DCA stats (10 = 8 contenido + 2 bordes + 1 S/R)
-                Constraint::Min(6),    // historial de operaciones
-            ])
-            .split(body_chunks[1]);
+        let (price, dca, trades) = if compact {
+            // Angosto: Price y DCA Strategy uno debajo del otro, solo las secciones básicas
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    // precio + balance (9 contenido + 2 bordes), o la franja de una línea
+                    Constraint::Length(if strip { 3 } else { 11 }),
+                    Constraint::Length(15), // estado + posición + P&L (13 contenido + 2 bordes)
+                    Constraint::Min(6),     // historial de operaciones
+                ])
+                .split(body_chunks[1]);
+            (chunks[0], chunks[1], chunks[2])
+        } else {
+            let content_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(22), // precio + DCA stats (22 = 20 contenido + 2 bordes)
+                    Constraint::Min(6),    // historial de operaciones
+                ])
+                .split(body_chunks[1]);
+            let cols = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(42), Constraint::Percentage(58)])
+                .split(content_chunks[0]);
+            (cols[0], cols[1], content_chunks[1])
+        };
 
-        Self::render_header(f, state, main_chunks[0]);
-        Self::render_slot_list(f, state, body_chunks[0]);
-        Self::render_stats(f, state, content_chunks[0]);
-        Self::render_trades(f, state, content_chunks[1]);
-        Self::render_log(f, state, main_chunks[2]);
-        Self::render_footer(f, state, main_chunks[3]);
+        Areas {
+            header: main_chunks[0],
+            body: main_chunks[1],
+            slots: body_chunks[0],
+            price,
+            dca,
+            trades,
+            log: main_chunks[2],
+            footer: main_chunks[3],
+            compact,
+            strip,
+        }
+    }
 
-        // Overlays (encima de todo)
-        match &state.ui_mode {
-            UiMode::RestoreSession(slots_info) => {
-                Self::render_restore_session_panel(f, slots_info);
-            }
-            UiMode::NewStrategy => {
+    /// Aviso en lugar del dashboard cuando la terminal es más chica que MIN_SIZE
+    fn render_too_small(f: &mut Frame, theme: &Theme) {
+        let size = f.area();
+        let lines = vec![
+            Line::from(Span::styled(
+                tr("Terminal too small"),
+                Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(Span::styled(
+                trf("{}x{}, needs at least {}x{}", &[&size.width, &size.height, &MIN_SIZE.0, &MIN_SIZE.1]),
+                Style::default().fg(theme.text),
+            )),
+            Line::from(Span::styled(
+                tr("Resize the window · the bot keeps running"),
+                Style::default().fg(theme.muted),
+            )),
+        ];
+        let top = size.height.saturating_sub(lines.len() as u16) / 2;
+        let area = Rect { y: size.y + top, height: size.height - top, ..size };
+        f.render_widget(Paragraph::new(lines).alignment(Alignment::Center).wrap(Wrap { trim: true }), area);
+    }
+
+    #[cfg_attr(feature = "profiling", tracing::instrument(skip_all))]
+    fn render(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
+        let size = f.area();
+        if size.width < MIN_SIZE.0 || size.height < MIN_SIZE.1 {
+            Self::render_too_small(f, theme);
+            return;
+        }
+
+        let areas = Self::areas(size);
+
+        Self::render_header(f, state, areas.header);
+        match state.view_tab {
+            ViewTab::Detail => {
+                Self::render_slot_list(f, state, areas.slots);
+                Self::render_stats(f, state, &areas);
+                Self::render_trades(f, state, areas.trades);
+            }
+            ViewTab::Portfolio => Self::render_portfolio(f, state, areas.body),
+        }
+        Self::render_log(f, state, areas.log);
+        Self::render_footer(f, state, areas.footer);
+
+        // Overlays (encima de todo)
+        match &state.ui_mode {
+            UiMode::RestoreSession(slots_info) => {
+                Self::render_restore_session_panel(f, theme, slots_info, &state.restore_issues, state.can_reconcile_restore());
+            }
+            UiMode::Recovery(recovery) => {
+                Self::render_recovery_panel(f, theme, recovery);
+            }
+            UiMode::NewStrategy => {
                 Self::render_new_strategy_panel(f, state);
             }
+            UiMode::NewStrategySummary => {
+                Self::render_new_strategy_summary(f, state);
+            }
             UiMode::Config => {
                 Self::render_config_panel(f, state);
             }
+            UiMode::EditSlot => {
+                Self::render_edit_slot_panel(f, state);
+            }
+            UiMode::Settings => {
+                Self::render_settings_panel(f, state);
+            }
             UiMode::PostSale(_, result) => {
                 let quote_asset = state
                     .selected()
                     .map(|s| s.quote_asset.as_str())
                     .unwrap_or("USDT");
-                Self::render_post_sale_panel(f, result, quote_asset);
+                Self::render_post_sale_panel(f, theme, result, quote_asset);
             }
             UiMode::ConfirmClose => {
                 Self::render_confirm_close_panel(f, state);
@@ -299,6 +952,30 @@ impl Tui {
             UiMode::ConfirmDelete => {
                 Self::render_confirm_delete_panel(f, state);
             }
+            UiMode::Palette => {
+                Self::render_palette(f, state);
+            }
+            UiMode::Alerts => {
+                Self::render_alerts_panel(f, state);
+            }
+            UiMode::Triggers => {
+                Self::render_triggers_panel(f, state);
+            }
+            UiMode::Presets => {
+                Self::render_presets_panel(f, state);
+            }
+            UiMode::Diagnostics => {
+                Self::render_diagnostics_panel(f, state);
+            }
+            UiMode::LogView => {
+                Self::render_log_view(f, state, Rect { height: areas.footer.y, ..size });
+            }
+            UiMode::TradeDetail => {
+                Self::render_trade_detail_panel(f, state);
+            }
+            UiMode::Help => {
+                Self::render_help_panel(f, state);
+            }
             UiMode::Normal => {}
         }
     }
@@ -308,31 +985,33 @@ impl Tui {
     // -----------------------------------------------------------
 
     fn render_header(f: &mut Frame, state: &AppState, area: Rect) {
-        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+        let theme = &state.theme;
+        let now = fmt_time(chrono::Utc::now(), "%Y-%m-%d %H:%M:%S");
 
-        let title_spans = if let Some(slot) = state.selected() {
+        let mut title_spans = if let Some(slot) = state.selected() {
             let symbol = format!("{} / {}", slot.base_asset, slot.quote_asset);
             let (status_color, status_label) = match &slot.strategy.state {
-                DcaState::Running           => (Color::Green, "● ACTIVE"),
-                DcaState::TakeProfitReached => (Color::Cyan, "✓ TAKE PROFIT"),
-                DcaState::StopLossReached   => (Color::Red, "✗ STOP LOSS"),
-                DcaState::MaxOrdersReached  => (Color::Yellow, "■ MAX ORDERS"),
-                DcaState::Error(_)          => (Color::Red, "✗ ERROR"),
-                DcaState::Idle              => (Color::DarkGray, "○ STOPPED"),
+                DcaState::Running           => (theme.positive, "●"),
+                DcaState::TakeProfitReached => (theme.accent, "✓"),
+                DcaState::StopLossReached   => (theme.negative, "✗"),
+                DcaState::MaxOrdersReached  => (theme.highlight, "■"),
+                DcaState::Error(_)          => (theme.negative, "✗"),
+                DcaState::Idle              => (theme.muted, "○"),
             };
+            let status_label = format!("{} {}", status_label, tr(slot.strategy.state.label()));
             let (dir_label, dir_color) = match slot.strategy.config.direction {
-                TradeDirection::Long  => ("▲ LONG",  Color::Green),
-                TradeDirection::Short => ("▼ SHORT", Color::Red),
+                TradeDirection::Long  => ("▲ LONG",  theme.positive),
+                TradeDirection::Short => ("▼ SHORT", theme.negative),
             };
             vec![
                 Span::styled(
                     " Trading View ",
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
                 ),
                 Span::raw("│ "),
                 Span::styled(
                     symbol,
-                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
                 ),
                 Span::raw(" "),
                 Span::styled(
@@ -345,31 +1024,68 @@ impl Tui {
                     Style::default().fg(status_color).add_modifier(Modifier::BOLD),
                 ),
                 Span::raw(" │ "),
-                Span::styled(now.to_string(), Style::default().fg(Color::DarkGray)),
+                Span::styled(now.to_string(), Style::default().fg(theme.muted)),
                 Span::raw(" "),
             ]
         } else {
             vec![
                 Span::styled(
                     " Trading View ",
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
                 ),
                 Span::raw("│ "),
                 Span::styled(
-                    "No active strategies — Press [S] to start",
-                    Style::default().fg(Color::DarkGray),
+                    tr("No active strategies — Press [S] to start"),
+                    Style::default().fg(theme.muted),
                 ),
                 Span::raw(" │ "),
-                Span::styled(now.to_string(), Style::default().fg(Color::DarkGray)),
+                Span::styled(now.to_string(), Style::default().fg(theme.muted)),
+                Span::raw(" "),
             ]
         };
 
+        // Perfil activo (--profile): para no confundir dos instancias abiertas
+        if let Some(profile) = crate::config::profile() {
+            title_spans.insert(
+                1,
+                Span::styled(format!("[{}] ", profile), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            );
+        }
+
+        // Feed de precios caído o con símbolos sin precio reciente
+        let feed = match state.feed_status() {
+            StreamStatus::Connected => None,
+            status @ StreamStatus::Connecting => Some((format!("◌ WS {}", tr(status.label())), theme.muted)),
+            status @ StreamStatus::Reconnecting => Some((format!("⚠ WS {}", tr(status.label())), theme.error)),
+            status @ StreamStatus::Down => Some((format!("✗ WS {} [I]", tr(status.label())), theme.error)),
+            StreamStatus::Stale => {
+                let mut symbols: Vec<&str> = state.stale_symbols.iter().map(|s| s.as_str()).collect();
+                symbols.sort_unstable();
+                Some((trf("⚠ STALE PRICE {}", &[&symbols.join(" ")]), theme.error))
+            }
+        };
+        if let Some((label, color)) = feed {
+            title_spans.push(Span::raw("│ "));
+            title_spans.push(Span::styled(label, Style::default().fg(color).add_modifier(Modifier::BOLD)));
+            title_spans.push(Span::raw(" "));
+        }
+
+        // Alertas sin reconocer (A abre el historial)
+        let unacked = state.unacked_alerts();
+        if unacked > 0 {
+            title_spans.push(Span::raw("│ "));
+            title_spans.push(Span::styled(
+                format!("🔔 {} [A]", unacked),
+                Style::default().fg(theme.on_accent).bg(theme.highlight).add_modifier(Modifier::BOLD),
+            ));
+        }
+
         let paragraph = Paragraph::new(Line::from(title_spans))
             .block(
                 Block::default()
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
-                    .border_style(Style::default().fg(Color::Yellow)),
+                    .border_style(Style::default().fg(theme.highlight)),
             )
             .alignment(Alignment::Left);
 
@@ -381,79 +1097,160 @@ impl Tui {
     // -----------------------------------------------------------
 
     fn render_slot_list(f: &mut Frame, state: &AppState, area: Rect) {
+        let theme = &state.theme;
         let mut lines: Vec<Line> = state
             .slots
             .iter()
             .enumerate()
-            .map(|(i, slot)| {
+            .flat_map(|(i, slot)| {
                 let is_selected = i == state.selected_slot;
                 let prefix = if is_selected { "►" } else { " " };
                 let base = &slot.base_asset[..slot.base_asset.len().min(5)];
                 let dir_arrow = match slot.strategy.config.direction {
+                    _ if slot.strategy.accumulating() => "Σ",
+                    _ if slot.strategy.distributing() => "↘",
+                    _ if slot.strategy.watching() => "◎",
                     TradeDirection::Long  => "▲",
                     TradeDirection::Short => "▼",
                 };
                 let (status_dot, status_color) = match &slot.strategy.state {
-                    DcaState::Running           => ("●", Color::Green),
-                    DcaState::TakeProfitReached => ("●", Color::Cyan),
-                    DcaState::StopLossReached   => ("●", Color::Magenta),
-                    DcaState::MaxOrdersReached  => ("●", Color::Yellow),
-                    DcaState::Error(_)          => ("●", Color::LightRed),
-                    DcaState::Idle              => ("●", Color::Red),
+                    DcaState::Running           => ("●", theme.positive),
+                    DcaState::TakeProfitReached => ("●", theme.accent),
+                    DcaState::StopLossReached   => ("●", theme.special),
+                    DcaState::MaxOrdersReached  => ("●", theme.highlight),
+                    DcaState::Error(_)          => ("●", theme.error),
+                    DcaState::Idle              => ("●", theme.negative),
+                };
+                // Salida pendiente por red, halt/deslistado del símbolo, o warm-up: esperando
+                // primer tick y filtros
+                let (status_dot, status_color) = if slot.exit_pending.is_some() {
+                    ("⚠", theme.error)
+                } else if slot.halted.is_some() {
+                    ("✖", theme.error)
+                } else if slot.warming_up {
+                    ("◌", theme.highlight)
+                } else if slot.strategy.paused_by_schedule(chrono::Utc::now()) {
+                    ("◐", theme.info)
+                } else if slot.strategy.winding_down() {
+                    ("◑", theme.highlight)
+                } else {
+                    (status_dot, status_color)
                 };
                 let dir_color = match slot.strategy.config.direction {
-                    TradeDirection::Long  => Color::Green,
-                    TradeDirection::Short => Color::Red,
+                    TradeDirection::Long  => theme.positive,
+                    TradeDirection::Short => theme.negative,
                 };
                 let sel_style = if is_selected {
-                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+                    Style::default().fg(theme.text).add_modifier(Modifier::BOLD)
                 } else {
-                    Style::default().fg(Color::Gray)
+                    Style::default().fg(theme.subtle)
                 };
 
                 let flip_icon = if slot.strategy.config.auto_flip { "↺" } else { " " };
 
-                Line::from(vec![
+                let mut slot_lines = vec![Line::from(vec![
                     Span::styled(format!("{} ", prefix), sel_style),
                     Span::styled(base.to_string(), sel_style),
                     Span::raw(" "),
                     Span::styled(dir_arrow.to_string(), Style::default().fg(dir_color)),
-                    Span::styled(flip_icon.to_string(), Style::default().fg(Color::Magenta)),
+                    Span::styled(flip_icon.to_string(), Style::default().fg(theme.special)),
                     Span::raw(" "),
                     Span::styled(status_dot.to_string(), Style::default().fg(status_color)),
-                ])
+                ])];
+                // Símbolo sin velas en Binance (ver el panel de precio)
+                if state.missing_klines.contains_key(&slot.symbol) {
+                    slot_lines[0].spans.push(Span::styled(" ?", Style::default().fg(theme.highlight)));
+                }
+
+                // Segunda línea: grupo y/o monto calculado por risk parity
+                let group = slot.strategy.config.group.as_deref();
+                let scaled = slot.strategy.size_multiplier != 1.0;
+                if Self::slot_has_detail(slot) {
+                    let mut detail = vec![Span::raw("  ")];
+                    if let Some(g) = group {
+                        let color = if slot.group_blocked { theme.error } else { theme.info };
+                        detail.push(Span::styled(
+                            format!("{} ", g.chars().take(6).collect::<String>()),
+                            Style::default().fg(color),
+                        ));
+                    }
+                    if scaled {
+                        let price = state.prices.get(&slot.symbol).map(|m| m.price).unwrap_or(0.0);
+                        let amount = slot.entry_amount(price);
+                        let label = if group.is_some() {
+                            format!("${:.1}", amount)
+                        } else {
+                            format!("${:.1} x{:.2}", amount, slot.strategy.size_multiplier)
+                        };
+                        detail.push(Span::styled(label, Style::default().fg(theme.muted)));
+                    }
+                    slot_lines.push(Line::from(detail));
+                }
+                slot_lines
             })
             .collect();
 
         // Pista para agregar nueva estrategia
-        if state.slots.len() < MAX_SLOTS {
+        if state.can_add_slot() {
             lines.push(Line::from(Span::styled(
-                "  [S] New",
-                Style::default().fg(Color::DarkGray),
+                tr("  [S] New"),
+                Style::default().fg(theme.muted),
             )));
         }
 
+        // Más slots que líneas: se desplaza hasta el seleccionado, con flechas en el título
+        let height = area.height.saturating_sub(2) as usize;
+        let offset = Self::slot_list_offset(state, height);
+        let title = trf(
+            " Slots {}/{}{}{} ",
+            &[
+                &state.slots.len(),
+                &state.max_slots,
+                &if offset > 0 { " ↑" } else { "" },
+                &if offset + height < lines.len() { " ↓" } else { "" },
+            ],
+        );
+
         f.render_widget(
-            Paragraph::new(lines).block(
+            Paragraph::new(lines).scroll((offset as u16, 0)).block(
                 Block::default()
-                    .title(" Slots ")
+                    .title(title)
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
-                    .border_style(Style::default().fg(Color::DarkGray)),
+                    .border_style(Style::default().fg(theme.muted)),
             ),
             area,
         );
     }
 
+    /// Primera línea visible de la lista de slots: lo justo para que el slot seleccionado
+    /// entre en `height` líneas
+    fn slot_list_offset(state: &AppState, height: usize) -> usize {
+        let mut first = 0usize;
+        for (i, slot) in state.slots.iter().enumerate() {
+            let rows = if Self::slot_has_detail(slot) { 2 } else { 1 };
+            if i == state.selected_slot {
+                return (first + rows).saturating_sub(height);
+            }
+            first += rows;
+        }
+        0
+    }
+
+    /// El slot ocupa una segunda línea en la lista (grupo o monto por risk parity)
+    fn slot_has_detail(slot: &StrategySlot) -> bool {
+        slot.strategy.config.group.is_some() || slot.strategy.size_multiplier != 1.0
+    }
+
     // -----------------------------------------------------------
     // Panel de estadísticas (precio + DCA stats)
     // -----------------------------------------------------------
 
-    fn render_stats(f: &mut Frame, state: &AppState, area: Rect) {
-        let cols = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(42), Constraint::Percentage(58)])
-            .split(area);
+    /// Panel de precio y panel DCA; en el layout compacto sin sparklines, volumen ni gráfico,
+    /// y de la performance solo la línea de P&L
+    fn render_stats(f: &mut Frame, state: &AppState, areas: &Areas) {
+        let theme = &state.theme;
+        let compact = areas.compact;
 
         let (base, quote, base_bal, quote_bal) = state
             .selected()
@@ -471,73 +1268,171 @@ impl Tui {
         {
             let market = state.selected_market();
 
-            let change_color = if market.change_24h_pct >= 0.0 { Color::Green } else { Color::Red };
+            let change_color = if market.change_24h_pct >= 0.0 { theme.positive } else { theme.negative };
             let change_sign  = if market.change_24h_pct >= 0.0 { "+" } else { "" };
 
+            let price_line = Line::from(vec![
+                Span::styled(
+                    format!(" {}", fmt_price(market.price, &quote, 2)),
+                    Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("  "),
+                Span::styled(
+                    format!("{}{:.2}% 24h", change_sign, market.change_24h_pct),
+                    Style::default().fg(change_color),
+                ),
+            ]);
             let mut price_text = vec![
                 Line::from(vec![
-                    Span::styled("── MARKETS ──────────────────", Style::default().fg(Color::DarkGray)),
+                    Span::styled(tr("── MARKETS ──────────────────"), Style::default().fg(theme.muted)),
                 ]),
+                price_line.clone(),
                 Line::from(vec![
+                    Span::styled(" H: ", Style::default().fg(theme.muted)),
+                    Span::styled(fmt_price(market.high_24h, &quote, 2), Style::default().fg(theme.positive)),
+                    Span::styled("  L: ", Style::default().fg(theme.muted)),
+                    Span::styled(fmt_price(market.low_24h, &quote, 2), Style::default().fg(theme.negative)),
+                ]),
+                match market.spread_pct() {
+                    Some(spread) => Line::from(vec![
+                        Span::styled(" B: ", Style::default().fg(theme.muted)),
+                        Span::styled(fmt_price(market.bid, &quote, 4), Style::default().fg(theme.positive)),
+                        Span::styled("  A: ", Style::default().fg(theme.muted)),
+                        Span::styled(fmt_price(market.ask, &quote, 4), Style::default().fg(theme.negative)),
+                        Span::styled(format!("  {:.3}%", spread), Style::default().fg(theme.highlight)),
+                    ]),
+                    None => Line::from(Span::styled(" B/A: --", Style::default().fg(theme.muted))),
+                },
+            ];
+
+            // Sparkline del precio en vivo (la última hora del ring buffer, o desde el arranque)
+            if let Some(slot) = state.selected().filter(|_| !compact) {
+                // Espacio para " +0.00% 00m" detrás de las barras
+                let width = (areas.price.width as usize).saturating_sub(16).max(8);
+                price_text.push(Self::live_price_line(theme, state.price_history.recent(&slot.symbol, width)));
+            }
+
+            // Volumen del minuto en curso (@aggTrade) contra el promedio móvil
+            let volume = state.selected().filter(|_| !compact).and_then(|sl| {
+                let (minute, average) = state.volume.current(&sl.symbol)?;
+                Some((minute, average, state.volume.spike(&sl.symbol).is_some()))
+            });
+            if let Some((minute, average, spiking)) = volume {
+                let mut spans = vec![
+                    Span::styled(tr(" Vol 1m: "), Style::default().fg(theme.muted)),
                     Span::styled(
-                        format!(" ${:.2}", market.price),
-                        Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                        format!("${:.0}", minute.total()),
+                        Style::default().fg(if spiking { theme.highlight } else { theme.text }),
                     ),
-                    Span::raw("  "),
                     Span::styled(
-                        format!("{}{:.2}% 24h", change_sign, market.change_24h_pct),
-                        Style::default().fg(change_color),
+                        trf("  buy {}%", &[&format!("{:.0}", minute.buy_ratio() * 100.0)]),
+                        Style::default().fg(if minute.buy >= minute.sell { theme.positive } else { theme.negative }),
                     ),
-                ]),
-                Line::from(vec![
-                    Span::styled(" H: ", Style::default().fg(Color::DarkGray)),
-                    Span::styled(format!("${:.2}", market.high_24h), Style::default().fg(Color::Green)),
-                    Span::styled("  L: ", Style::default().fg(Color::DarkGray)),
-                    Span::styled(format!("${:.2}", market.low_24h), Style::default().fg(Color::Red)),
-                ]),
+                ];
+                if let Some(avg) = average.filter(|a| *a > 0.0) {
+                    spans.push(Span::styled(
+                        trf("  {}x avg", &[&format!("{:.1}", minute.total() / avg)]),
+                        Style::default().fg(if spiking { theme.highlight } else { theme.muted }),
+                    ));
+                }
+                price_text.push(Line::from(spans));
+            }
+
+            price_text.extend([
                 Line::from(""),
                 Line::from(vec![
-                    Span::styled("── BALANCE ──────────────────", Style::default().fg(Color::DarkGray)),
+                    Span::styled(tr("── BALANCE ──────────────────"), Style::default().fg(theme.muted)),
                 ]),
                 Line::from(vec![
-                    Span::styled(format!(" {}: ", base), Style::default().fg(Color::Yellow)),
-                    Span::styled(format!("{:.6}", base_bal), Style::default().fg(Color::White)),
+                    Span::styled(format!(" {}: ", base), Style::default().fg(theme.highlight)),
+                    Span::styled(format!("{:.6}", base_bal), Style::default().fg(theme.text)),
                 ]),
                 Line::from(vec![
-                    Span::styled(format!(" {}: ", quote), Style::default().fg(Color::Yellow)),
-                    Span::styled(format!("{:.2}", quote_bal), Style::default().fg(Color::White)),
+                    Span::styled(format!(" {}: ", quote), Style::default().fg(theme.highlight)),
+                    Span::styled(
+                        format!("{:.*}", if is_usd_quote(&quote) { 2 } else { 8 }, quote_bal),
+                        Style::default().fg(theme.text),
+                    ),
                 ]),
-            ];
+            ]);
 
-            // Niveles de Soporte/Resistencia
-            if let Some(sym) = state.selected().map(|s| s.symbol.clone()) {
-                if let Some(level) = state.alert_levels.get(&sym) {
-                    if level.resistance > 0.0 {
-                        price_text.push(Line::from(""));
-                        price_text.push(Line::from(vec![
-                            Span::styled("── TECH LEVELS ──────────────", Style::default().fg(Color::DarkGray)),
-                        ]));
-                        price_text.push(Line::from(vec![
-                            Span::styled(" Support:    ", Style::default().fg(Color::DarkGray)),
-                            Span::styled(format!("${:.2}", level.support), Style::default().fg(Color::Green)),
-                        ]));
+            // Equity total de la cuenta (en la moneda de visualización) y el piso del stop de
+            // portafolio, que se controla en USDT: cada uno lleva su moneda
+            if let Some(valued) = &state.valuation {
+                let mut spans = vec![
+                    Span::styled(tr(" Equity: "), Style::default().fg(theme.highlight)),
+                    Span::styled(format_amount(valued.total, &valued.currency), Style::default().fg(theme.text)),
+                ];
+                if state.equity_floor_usdt > 0.0 {
+                    let floor_color = if state.equity_floor_tripped { theme.error } else { theme.muted };
+                    spans.push(Span::styled(
+                        trf("  floor {} USDT", &[&format!("{:.0}", state.equity_floor_usdt)]),
+                        Style::default().fg(floor_color),
+                    ));
+                }
+                price_text.push(Line::from(spans));
+            }
+
+            // Gráfico y niveles de Soporte/Resistencia del intervalo de análisis del slot (T)
+            if let Some(slot) = state.selected().filter(|_| !compact) {
+                let interval = slot.analysis_interval.as_str();
+                price_text.push(Line::from(""));
+                price_text.push(Line::from(vec![
+                    Span::styled(trf("── CHART {} ", &[&interval]), Style::default().fg(theme.muted)),
+                    Span::styled("[T]", Style::default().fg(theme.accent)),
+                    Span::styled(" ───────────────", Style::default().fg(theme.muted)),
+                ]));
+                let width = (areas.price.width as usize).saturating_sub(4).min(market_data::CHART_CANDLES);
+                if let Some(missing) = state.missing_klines.get(&slot.symbol) {
+                    price_text.push(Line::from(Span::styled(
+                        format!(" ⚠ {}", tr(missing.reason())),
+                        Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
+                    )));
+                    if let Some(suggestion) = &missing.suggestion {
                         price_text.push(Line::from(vec![
-                            Span::styled(" Resistance: ", Style::default().fg(Color::DarkGray)),
-                            Span::styled(format!("${:.2}", level.resistance), Style::default().fg(Color::Red)),
+                            Span::styled(tr(" Did you mean "), Style::default().fg(theme.muted)),
+                            Span::styled(suggestion.clone(), Style::default().fg(theme.accent)),
+                            Span::styled("?", Style::default().fg(theme.muted)),
                         ]));
                     }
+                } else {
+                    price_text.push(Self::candle_sparkline(theme, &state.candles.recent(&slot.symbol, interval, width)));
+                }
+                if let Some((support, resistance)) = state.candles.levels(&slot.symbol, interval, state.sr_window) {
+                    // El nivel a menos de 1% del precio se resalta
+                    let near = |level: f64| market.price > 0.0 && ((market.price - level) / level).abs() < 0.01;
+                    let level_style = |level: f64, color: Color| {
+                        if near(level) {
+                            Style::default().fg(theme.on_accent).bg(color).add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(color)
+                        }
+                    };
+                    price_text.push(Line::from(vec![
+                        Span::styled(tr(" Support:    "), Style::default().fg(theme.muted)),
+                        Span::styled(format!("${:.2}", support), level_style(support, theme.positive)),
+                    ]));
+                    price_text.push(Line::from(vec![
+                        Span::styled(tr(" Resistance: "), Style::default().fg(theme.muted)),
+                        Span::styled(format!("${:.2}", resistance), level_style(resistance, theme.negative)),
+                    ]));
                 }
             }
 
+            // Terminal baja: solo el precio, el resto no entraría
+            if areas.strip {
+                price_text = vec![price_line];
+            }
+
             f.render_widget(
                 Paragraph::new(price_text).block(
                     Block::default()
-                        .title(" Price ")
+                        .title(tr(" Price "))
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .border_style(Style::default().fg(Color::Cyan)),
+                        .border_style(Style::default().fg(theme.accent)),
                 ),
-                cols[0],
+                areas.price,
             );
         }
 
@@ -548,11 +1443,11 @@ impl Tui {
                 None => {
                     f.render_widget(
                         Block::default()
-                            .title(" DCA Strategy ")
+                            .title(tr(" DCA Strategy "))
                             .borders(Borders::ALL)
                             .border_type(BorderType::Rounded)
-                            .border_style(Style::default().fg(Color::Magenta)),
-                        cols[1],
+                            .border_style(Style::default().fg(theme.special)),
+                        areas.dca,
                     );
                     return;
                 }
@@ -565,17 +1460,26 @@ impl Tui {
             let pnl         = slot.strategy.pnl(price);
             let pnl_pct     = slot.strategy.pnl_pct(price);
             let orders_count = slot.strategy.trades.len();
-            let max_orders  = slot.strategy.config.max_orders;
+            let max_orders  = slot.strategy.max_entries();
             let countdown   = slot.strategy.next_buy_countdown();
             let daily_spent = slot.strategy.daily_spent;
-            let quote_amount = slot.strategy.config.quote_amount;
+            let daily_limit = state.daily_limit(slot);
+            let quote_amount = slot.entry_amount(price);
+            let amount_cfg  = slot.strategy.config.quote_amount;
             let trailing_trigger = slot.strategy.trailing_tp_trigger_price();
             let trailing_configured = slot.strategy.config.trailing_tp_pct > 0.0;
             let direction   = &slot.strategy.config.direction;
             let quote_asset = &slot.quote_asset;
             let base_asset  = &slot.base_asset;
 
-            let (pnl_color, pnl_sign) = if pnl >= 0.0 { (Color::Green, "+") } else { (Color::Red, "") };
+            let (pnl_color, pnl_sign) = if pnl >= 0.0 { (theme.positive, "+") } else { (theme.negative, "") };
+
+            let health = health::evaluate(&slot.strategy, slot.api_calls, slot.api_errors, chrono::Utc::now());
+            let health_color = match health.level {
+                HealthLevel::Good => theme.positive,
+                HealthLevel::Warning => theme.highlight,
+                HealthLevel::Critical => theme.negative,
+            };
 
             // Línea de trailing TP (dirección-aware)
             let trailing_line = match direction {
@@ -584,24 +1488,28 @@ impl Tui {
                     if trailing_configured && price_peak > 0.0 {
                         let drop_so_far = ((price_peak - price) / price_peak) * 100.0;
                         let trigger_color = if price <= trailing_trigger {
-                            Color::Yellow
+                            theme.highlight
                         } else {
-                            Color::Cyan
+                            theme.accent
                         };
                         Line::from(vec![
-                            Span::styled(" Trail TP:   ", Style::default().fg(Color::DarkGray)),
+                            Span::styled(tr(" Trail TP:   "), Style::default().fg(theme.muted)),
                             Span::styled(
-                                format!(
-                                    "peak ${:.4}  closes <${:.4} ({:.2}%↓)",
-                                    price_peak, trailing_trigger, drop_so_far
+                                trf(
+                                    "peak ${}  closes <${} ({}%↓)",
+                                    &[
+                                        &format!("{:.4}", price_peak),
+                                        &format!("{:.4}", trailing_trigger),
+                                        &format!("{:.2}", drop_so_far),
+                                    ],
                                 ),
                                 Style::default().fg(trigger_color),
                             ),
                         ])
                     } else {
                         Line::from(vec![
-                            Span::styled(" Next buy:    ", Style::default().fg(Color::DarkGray)),
-                            Span::styled(countdown, Style::default().fg(Color::Cyan)),
+                            Span::styled(tr(" Next buy:    "), Style::default().fg(theme.muted)),
+                            Span::styled(countdown, Style::default().fg(theme.accent)),
                         ])
                     }
                 }
@@ -611,100 +1519,244 @@ impl Tui {
                     if trailing_configured && valid {
                         let rise_so_far = ((price - price_trough) / price_trough) * 100.0;
                         let trigger_color = if price >= trailing_trigger {
-                            Color::Yellow
+                            theme.highlight
                         } else {
-                            Color::Cyan
+                            theme.accent
                         };
                         Line::from(vec![
-                            Span::styled(" Trail TP:   ", Style::default().fg(Color::DarkGray)),
+                            Span::styled(tr(" Trail TP:   "), Style::default().fg(theme.muted)),
                             Span::styled(
-                                format!(
-                                    "trough ${:.4}  closes >${:.4} ({:.2}%↑)",
-                                    price_trough, trailing_trigger, rise_so_far
+                                trf(
+                                    "trough ${}  closes >${} ({}%↑)",
+                                    &[
+                                        &format!("{:.4}", price_trough),
+                                        &format!("{:.4}", trailing_trigger),
+                                        &format!("{:.2}", rise_so_far),
+                                    ],
                                 ),
                                 Style::default().fg(trigger_color),
                             ),
                         ])
                     } else {
                         Line::from(vec![
-                            Span::styled(" Next sell:   ", Style::default().fg(Color::DarkGray)),
-                            Span::styled(countdown, Style::default().fg(Color::Cyan)),
+                            Span::styled(tr(" Next sell:   "), Style::default().fg(theme.muted)),
+                            Span::styled(countdown, Style::default().fg(theme.accent)),
                         ])
                     }
                 }
             };
 
             let (avg_label, invested_label, qty_label, entry_label) = match direction {
-                TradeDirection::Long  => (" Avg cost:    ", " Invested:   ", " Quantity:   ", " Buy amount:  "),
-                TradeDirection::Short => (" Sell price:  ", " Received:   ", " Sold:       ", " Sell amount: "),
+                TradeDirection::Long  => (tr(" Avg cost:    "), tr(" Invested:   "), tr(" Quantity:   "), tr(" Buy amount:  ")),
+                TradeDirection::Short => (tr(" Sell price:  "), tr(" Received:   "), tr(" Sold:       "), tr(" Sell amount: ")),
             };
 
-            let dca_text = vec![
+            let mut dca_text = vec![
                 Line::from(vec![
-                    Span::styled("── STATE ───────────────────", Style::default().fg(Color::DarkGray)),
+                    Span::styled(tr("── STATE ───────────────────"), Style::default().fg(theme.muted)),
                 ]),
+                if slot.strategy.accumulating() {
+                    Line::from(vec![
+                        Span::styled(tr(" Mode:       "), Style::default().fg(theme.muted)),
+                        Span::styled(
+                            trf("Accumulate {} UTC", &[&slot.strategy.config.buy_schedule]),
+                            Style::default().fg(theme.info),
+                        ),
+                    ])
+                } else if slot.strategy.distributing() {
+                    let levels = slot.strategy.config.sell_levels.len();
+                    Line::from(vec![
+                        Span::styled(tr(" Mode:       "), Style::default().fg(theme.muted)),
+                        Span::styled(
+                            if levels > 0 {
+                                trf("Distribute ladder {}/{}", &[&orders_count.min(levels), &levels])
+                            } else {
+                                trf("Distribute {} UTC", &[&slot.strategy.config.buy_schedule])
+                            },
+                            Style::default().fg(theme.info),
+                        ),
+                    ])
+                } else if slot.strategy.watching() {
+                    Line::from(vec![
+                        Span::styled(tr(" Mode:       "), Style::default().fg(theme.muted)),
+                        Span::styled(tr("Watch-only · exits, no entries"), Style::default().fg(theme.info)),
+                    ])
+                } else {
+                    Line::from(vec![
+                        Span::styled(tr(" Mode:       "), Style::default().fg(theme.muted)),
+                        Span::styled(
+                            tr(if slot.strategy.config.auto_restart { "Auto-Restart ✓ " } else { "Manual " }),
+                            Style::default().fg(if slot.strategy.config.auto_restart { theme.accent } else { theme.muted })
+                        ),
+                        Span::styled(
+                            if slot.strategy.config.auto_flip { "↺ L↔S" } else { "" },
+                            Style::default().fg(theme.special)
+                        ),
+                    ])
+                },
                 Line::from(vec![
-                    Span::styled(" Mode:       ", Style::default().fg(Color::DarkGray)),
-                    Span::styled(
-                        if slot.strategy.config.auto_restart { "Auto-Restart ✓ " } else { "Manual " },
-                        Style::default().fg(if slot.strategy.config.auto_restart { Color::Cyan } else { Color::DarkGray })
-                    ),
+                    Span::styled(tr(" Status:     "), Style::default().fg(theme.muted)),
+                    Span::styled("● ", Style::default().fg(match &slot.strategy.state {
+                        DcaState::Running => theme.positive,
+                        DcaState::Idle => theme.negative,
+                        DcaState::TakeProfitReached => theme.accent,
+                        DcaState::StopLossReached => theme.special,
+                        DcaState::MaxOrdersReached => theme.highlight,
+                        DcaState::Error(_) => theme.error,
+                    })),
+                    if slot.warming_up {
+                        Span::styled(
+                            tr("WARMING UP"),
+                            Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+                        )
+                    } else if slot.strategy.paused_by_schedule(chrono::Utc::now()) {
+                        Span::styled(
+                            tr("PAUSED (schedule)"),
+                            Style::default().fg(theme.info).add_modifier(Modifier::BOLD)
+                        )
+                    } else if slot.strategy.winding_down() {
+                        Span::styled(
+                            tr("REDUCE-ONLY"),
+                            Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+                        )
+                    } else {
+                        Span::styled(
+                            tr(slot.strategy.state.label()),
+                            Style::default().fg(theme.text).add_modifier(Modifier::BOLD)
+                        )
+                    },
                     Span::styled(
-                        if slot.strategy.config.auto_flip { "↺ L↔S" } else { "" },
-                        Style::default().fg(Color::Magenta)
+                        format!("  ♥ {} {}", health.score, tr(health.level.label())),
+                        Style::default().fg(health_color),
                     ),
                 ]),
+                // Sugerencia principal del health score (línea vacía si todo está bien)
                 Line::from(vec![
-                    Span::styled(" Status:     ", Style::default().fg(Color::DarkGray)),
-                    Span::styled("● ", Style::default().fg(match &slot.strategy.state {
-                        DcaState::Running => Color::Green,
-                        DcaState::Idle => Color::Red,
-                        DcaState::TakeProfitReached => Color::Cyan,
-                        DcaState::StopLossReached => Color::Magenta,
-                        DcaState::MaxOrdersReached => Color::Yellow,
-                        DcaState::Error(_) => Color::LightRed,
-                    })),
                     Span::styled(
-                        slot.strategy.state.label().to_string(),
-                        Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+                        health.suggestions.first().map(|s| format!(" ⚑ {}", s)).unwrap_or_default(),
+                        Style::default().fg(health_color),
                     ),
                 ]),
-                Line::from(""),
                 Line::from(vec![
-                    Span::styled("── POSITION ────────────────", Style::default().fg(Color::DarkGray)),
+                    Span::styled(tr("── POSITION ────────────────"), Style::default().fg(theme.muted)),
                 ]),
                 Line::from(vec![
-                    Span::styled(avg_label, Style::default().fg(Color::DarkGray)),
-                    Span::styled(format!("${:.4}", avg), Style::default().fg(Color::White)),
+                    Span::styled(avg_label, Style::default().fg(theme.muted)),
+                    Span::styled(fmt_price(avg, quote_asset, 4), Style::default().fg(theme.text)),
+                    // Acumulación / distribución: precio promedio de toda la vida contra el actual
+                    if !slot.strategy.is_trading() && avg > 0.0 {
+                        Span::styled(
+                            format!("  vs {} ({}{:.2}%)", fmt_price(price, quote_asset, 4), pnl_sign, (price - avg) / avg * 100.0),
+                            Style::default().fg(pnl_color),
+                        )
+                    } else {
+                        Span::raw("")
+                    },
                 ]),
                 Line::from(vec![
-                    Span::styled(invested_label, Style::default().fg(Color::DarkGray)),
+                    Span::styled(invested_label, Style::default().fg(theme.muted)),
                     Span::styled(
-                        format!("${:.2} {}", invested, quote_asset),
-                        Style::default().fg(Color::White),
+                        fmt_quote_named(invested, quote_asset),
+                        Style::default().fg(theme.text),
                     ),
                 ]),
                 Line::from(vec![
-                    Span::styled(qty_label, Style::default().fg(Color::DarkGray)),
+                    Span::styled(qty_label, Style::default().fg(theme.muted)),
                     Span::styled(
                         format!("{:.6} {}", qty, base_asset),
-                        Style::default().fg(Color::White),
+                        Style::default().fg(theme.text),
                     ),
                 ]),
                 Line::from(vec![
-                    Span::styled(" Orders:     ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(tr(" Orders:     "), Style::default().fg(theme.muted)),
                     Span::styled(
-                        format!("{} / {}", orders_count, max_orders),
-                        Style::default().fg(Color::White),
+                        if slot.strategy.accumulating() {
+                            trf("{} buys", &[&orders_count])
+                        } else if slot.strategy.distributing() {
+                            trf(
+                                "{} sells, {} {} left",
+                                &[&orders_count, &format!("{:.6}", slot.strategy.remaining_inventory()), base_asset],
+                            )
+                        } else if slot.strategy.watching() {
+                            tr("imported holding").to_string()
+                        } else {
+                            format!("{} / {}", orders_count, max_orders)
+                        },
+                        Style::default().fg(theme.text),
                     ),
+                    // Salida sin confirmar por caída de red; orden LIMIT de entrada pendiente;
+                    // si no hay, el próximo paso de la escalera de safety orders y su precio
+                    if let Some(pending) = &slot.exit_pending {
+                        Span::styled(
+                            trf(
+                                "  ⚠ EXIT PENDING: {} ({} attempts, {}s)",
+                                &[
+                                    &tr(pending.reason.label()),
+                                    &pending.attempts,
+                                    &(chrono::Utc::now() - pending.since).num_seconds().max(0),
+                                ],
+                            ),
+                            Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+                        )
+                    } else if let Some(order) = state.orders.get(slot.id).map(|m| &m.order) {
+                        Span::styled(
+                            format!(
+                                "  ⧗ {} {} {:.6} @ ${:.4} ({}s{})",
+                                if order.post_only { "MAKER" } else { "LIMIT" },
+                                tr(if order.side == OrderSide::Buy { "BUY" } else { "SELL" }),
+                                order.quantity,
+                                order.price,
+                                (chrono::Utc::now() - order.placed_at).num_seconds().max(0),
+                                if order.replaces > 0 { trf(", chase {}", &[&order.replaces]) } else { String::new() },
+                            ),
+                            Style::default().fg(theme.highlight),
+                        )
+                    } else {
+                        match (slot.strategy.next_safety_order(), slot.strategy.safety_order_trigger_price()) {
+                            (Some((i, so)), Some(trigger)) => Span::styled(
+                                trf(
+                                    "  Next SO {}/{} @ ${} ({}{}%, x{})",
+                                    &[
+                                        &(i + 1),
+                                        &slot.strategy.config.safety_orders.len(),
+                                        &format!("{:.4}", trigger),
+                                        &if *direction == TradeDirection::Long { "-" } else { "+" },
+                                        &format!("{:.2}", so.deviation_pct),
+                                        &format!("{:.2}", so.size_multiplier),
+                                    ],
+                                ),
+                                Style::default().fg(theme.accent),
+                            ),
+                            _ => Span::raw(""),
+                        }
+                    },
                 ]),
                 Line::from(vec![
-                    Span::styled(entry_label, Style::default().fg(Color::DarkGray)),
+                    Span::styled(entry_label, Style::default().fg(theme.muted)),
                     Span::styled(
-                        format!(" ${:.2}  Today: ${:.2}", quote_amount, daily_spent),
-                        Style::default().fg(Color::Yellow),
+                        if amount_cfg.is_percent() {
+                            trf(
+                                " {} ({})  Today: {} / {}",
+                                &[
+                                    &fmt_quote(quote_amount, quote_asset),
+                                    &amount_cfg,
+                                    &fmt_quote(daily_spent, quote_asset),
+                                    &fmt_quote(daily_limit, quote_asset),
+                                ],
+                            )
+                        } else {
+                            trf(
+                                " {}  Today: {} / {}",
+                                &[
+                                    &fmt_quote(quote_amount, quote_asset),
+                                    &fmt_quote(daily_spent, quote_asset),
+                                    &fmt_quote(daily_limit, quote_asset),
+                                ],
+                            )
+                        },
+                        Style::default().fg(theme.highlight),
                     ),
-                    Span::styled("  Next: ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(tr("  Next: "), Style::default().fg(theme.muted)),
                     {
                         let can_buy = match direction {
                             TradeDirection::Long => quote_bal >= quote_amount,
@@ -712,80 +1764,270 @@ impl Tui {
                                 let needed_qty = if price > 0.0 { quote_amount / price } else { 0.0 };
                                 base_bal >= needed_qty
                             }
-                        };
-                        if can_buy {
-                            Span::styled("✓ OK", Style::default().fg(Color::Green))
+                        } && slot.wallet_cash().is_none_or(|cash| cash >= quote_amount);
+                        let min_notional = slot.filters.as_ref().map(|f| f.min_notional).unwrap_or(0.0);
+                        if quote_amount < min_notional {
+                            Span::styled(tr("⚠ MIN"), Style::default().fg(theme.error).add_modifier(Modifier::BOLD))
+                        } else if can_buy {
+                            Span::styled(tr("✓ OK"), Style::default().fg(theme.positive))
                         } else {
-                            Span::styled("⚠ LOW", Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD))
+                            Span::styled(tr("⚠ LOW"), Style::default().fg(theme.error).add_modifier(Modifier::BOLD))
                         }
                     }
                 ]),
+                // Billetera virtual del slot (capital asignado)
+                match slot.wallet_cash() {
+                    Some(cash) => Line::from(vec![
+                        Span::styled(tr(" Wallet:     "), Style::default().fg(theme.muted)),
+                        Span::styled(
+                            trf("{} cash", &[&fmt_quote(cash, quote_asset)]),
+                            Style::default().fg(if cash >= quote_amount { theme.text } else { theme.error }),
+                        ),
+                        Span::styled(
+                            trf(" / {} capital", &[&fmt_quote(slot.strategy.config.capital_usdt, quote_asset)]),
+                            Style::default().fg(theme.muted),
+                        ),
+                    ]),
+                    None => Line::from(vec![
+                        Span::styled(tr(" Wallet:     "), Style::default().fg(theme.muted)),
+                        Span::styled(tr("shared account balance"), Style::default().fg(theme.muted)),
+                    ]),
+                },
                 Line::from(vec![
-                    Span::styled(" Liq. Safety: ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(tr(" Liq. Safety: "), Style::default().fg(theme.muted)),
                     {
                         let warning = match direction {
                             TradeDirection::Long => {
                                 if qty > 0.0 && base_bal < (qty * 0.9995) {
-                                    Some(format!("Need {:.6} more {}", qty - base_bal, base_asset))
+                                    Some(trf("Need {} more {}", &[&format!("{:.6}", qty - base_bal), base_asset]))
                                 } else { None }
                             }
                             TradeDirection::Short => {
                                 let cost = qty * price;
                                 if qty > 0.0 && quote_bal < cost {
-                                    Some(format!("Need ${:.2} more {}", cost - quote_bal, quote_asset))
+                                    Some(trf("Need {} more", &[&fmt_quote_named(cost - quote_bal, quote_asset)]))
                                 } else { None }
                             }
                         };
                         if let Some(msg) = warning {
-                            Span::styled(format!("⚠ INSUFFICIENT ({})", msg), Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD))
+                            Span::styled(trf("⚠ INSUFFICIENT ({})", &[&msg]), Style::default().fg(theme.error).add_modifier(Modifier::BOLD))
                         } else if qty > 0.0 {
-                            Span::styled("✓ READY TO CLOSE", Style::default().fg(Color::Green))
+                            Span::styled(tr("✓ READY TO CLOSE"), Style::default().fg(theme.positive))
                         } else {
-                            Span::styled("-", Style::default().fg(Color::DarkGray))
+                            Span::styled("-", Style::default().fg(theme.muted))
                         }
                     }
                 ]),
-                Line::from(""),
-                Line::from(vec![
-                    Span::styled("── PERFORMANCE ─────────────", Style::default().fg(Color::DarkGray)),
-                ]),
-                Line::from(vec![
-                    Span::styled(" P&L:        ", Style::default().fg(Color::DarkGray)),
-                    Span::styled(
-                        format!("{}{:.2} $ ({}{:.2}%)", pnl_sign, pnl, pnl_sign, pnl_pct),
-                        Style::default().fg(pnl_color).add_modifier(Modifier::BOLD),
-                    ),
-                ]),
-                trailing_line,
             ];
+            let pnl_line = Line::from(vec![
+                Span::styled(tr(" P&L:        "), Style::default().fg(theme.muted)),
+                Span::styled(
+                    format!("{} ({}{:.2}%)", fmt_pnl(pnl, quote_asset), pnl_sign, pnl_pct),
+                    Style::default().fg(pnl_color).add_modifier(Modifier::BOLD),
+                ),
+                // Stop en break-even: nivel si ya está armado, si no el % que lo arma
+                match slot.strategy.config.breakeven_trigger_pct {
+                    pct if pct <= 0.0 || !slot.strategy.is_trading() => Span::raw(""),
+                    _ if slot.strategy.breakeven_armed => Span::styled(
+                        trf("  B/E stop ${}", &[&format!("{:.4}", slot.strategy.breakeven_stop_price())]),
+                        Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
+                    ),
+                    pct => Span::styled(
+                        trf("  B/E at +{}%", &[&format!("{:.2}", pct)]),
+                        Style::default().fg(theme.muted),
+                    ),
+                },
+            ]);
+            // Performance completa solo con el layout ancho; el P&L de la posición siempre
+            if compact {
+                dca_text.push(pnl_line);
+            } else {
+                dca_text.extend([
+                    Line::from(""),
+                    Line::from(vec![
+                        Span::styled(tr("── PERFORMANCE ─────────────"), Style::default().fg(theme.muted)),
+                    ]),
+                    pnl_line,
+                    Self::realized_sparkline_line(theme, tr(" 7d:        "), &slot.performance.realized, 7),
+                    Self::realized_sparkline_line(theme, tr(" 30d:       "), &slot.performance.realized, 30),
+                    {
+                        // Ciclos completados del historial: no se pierden con la poda del log
+                        let (count, won, total) = state
+                            .cycles
+                            .for_slot(slot.id, &slot.symbol)
+                            .fold((0, 0, 0.0), |(n, w, t), c| (n + 1, w + usize::from(c.pnl > 0.0), t + c.pnl));
+                        Line::from(vec![
+                            Span::styled(tr(" Lifetime:   "), Style::default().fg(theme.muted)),
+                            if count == 0 {
+                                Span::styled("--", Style::default().fg(theme.muted))
+                            } else {
+                                Span::styled(
+                                    fmt_pnl(total, quote_asset),
+                                    Style::default().fg(if total >= 0.0 { theme.positive } else { theme.negative }),
+                                )
+                            },
+                            Span::styled(trf("  {} cycles, {} won", &[&count, &won]), Style::default().fg(theme.muted)),
+                        ])
+                    },
+                    {
+                        // Duración del ciclo actual vs mediana histórica, y ETA al TP según la deriva
+                        let now = chrono::Utc::now();
+                        let current = slot.strategy.trades.first().map(|t| now.signed_duration_since(t.timestamp));
+                        let median = slot.performance.median_cycle();
+                        let eta = slot.strategy.eta_to_take_profit(price, now);
+                        Line::from(vec![
+                            Span::styled(tr(" Cycle:      "), Style::default().fg(theme.muted)),
+                            Span::styled(
+                                current.map(format_duration).unwrap_or_else(|| "--".to_string()),
+                                Style::default().fg(theme.text),
+                            ),
+                            Span::styled(tr("  median "), Style::default().fg(theme.muted)),
+                            Span::styled(
+                                match median {
+                                    Some(m) => format!("{} ({})", format_duration(m), slot.performance.cycle_durations.len()),
+                                    None => "--".to_string(),
+                                },
+                                Style::default().fg(theme.text),
+                            ),
+                            Span::styled(tr("  ETA TP "), Style::default().fg(theme.muted)),
+                            Span::styled(
+                                match (current, eta) {
+                                    (None, _) => "--".to_string(),
+                                    (Some(_), Some(e)) => format!("~{}", format_duration(e)),
+                                    (Some(_), None) => tr("drifting away").to_string(),
+                                },
+                                Style::default().fg(theme.accent),
+                            ),
+                        ])
+                    },
+                    {
+                        // Costos reales: slippage promedio de las entradas y comisiones cobradas
+                        let slip_adverse = slot.performance.avg_slippage_pct().is_some_and(|s| match direction {
+                            TradeDirection::Long => s > 0.0,
+                            TradeDirection::Short => s < 0.0,
+                        });
+                        let fees = if slot.performance.fees_paid.is_empty() {
+                            "--".to_string()
+                        } else {
+                            slot.performance
+                                .fees_paid
+                                .iter()
+                                .map(|(asset, amount)| format!("{:.6} {}", amount, asset))
+                                .collect::<Vec<_>>()
+                                .join(" + ")
+                        };
+                        Line::from(vec![
+                            Span::styled(tr(" Slippage:   "), Style::default().fg(theme.muted)),
+                            Span::styled(
+                                match slot.performance.avg_slippage_pct() {
+                                    Some(s) => format!("{:+.3}% ({})", s, slot.performance.slippage_pct.len()),
+                                    None => "--".to_string(),
+                                },
+                                Style::default().fg(if slip_adverse { theme.error } else { theme.text }),
+                            ),
+                            Span::styled(tr("  Fees paid "), Style::default().fg(theme.muted)),
+                            Span::styled(fees, Style::default().fg(theme.text)),
+                        ])
+                    },
+                ]);
+            }
+            dca_text.push(trailing_line);
 
             f.render_widget(
                 Paragraph::new(dca_text).block(
                     Block::default()
-                        .title(" DCA Strategy ")
+                        .title(tr(" DCA Strategy "))
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .border_style(Style::default().fg(Color::Magenta)),
+                        .border_style(Style::default().fg(theme.special)),
                 ),
-                cols[1],
+                areas.dca,
             );
         }
     }
 
+    /// Cierres de las velas como sparkline, verde si el período sube y rojo si baja
+    fn candle_sparkline(theme: &Theme, candles: &[Kline]) -> Line<'static> {
+        if candles.is_empty() {
+            return Line::from(Span::styled(tr(" loading candles..."), Style::default().fg(theme.muted)));
+        }
+        let closes: Vec<f64> = candles.iter().map(|k| k.close).collect();
+        Line::from(vec![Span::raw(" "), Self::sparkline(theme, &closes)])
+    }
+
+    /// Precios en vivo desde el arranque como sparkline, con la variación y el tiempo cubierto
+    fn live_price_line(theme: &Theme, history: Option<(chrono::DateTime<chrono::Utc>, Vec<f64>)>) -> Line<'static> {
+        let Some((since, prices)) = history.filter(|(_, p)| p.len() >= 2) else {
+            return Line::from(Span::styled(tr(" Live: collecting prices..."), Style::default().fg(theme.muted)));
+        };
+        let (first, last) = (prices[0], prices[prices.len() - 1]);
+        let change = (last - first) / first * 100.0;
+        let minutes = (chrono::Utc::now() - since).num_minutes().max(1);
+        Line::from(vec![
+            Span::raw(" "),
+            Self::sparkline(theme, &prices),
+            Span::styled(
+                format!(" {}{:.2}%", if change >= 0.0 { "+" } else { "" }, change),
+                Style::default().fg(if change >= 0.0 { theme.positive } else { theme.negative }),
+            ),
+            Span::styled(format!(" {}m", minutes), Style::default().fg(theme.muted)),
+        ])
+    }
+
+    /// Barras ▁..█ escaladas entre el mínimo y el máximo, verde si la serie sube y rojo si baja
+    fn sparkline(theme: &Theme, values: &[f64]) -> Span<'static> {
+        const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let low = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let bars: String = values
+            .iter()
+            .map(|v| {
+                let level = if high > low { ((v - low) / (high - low) * 7.0).round() as usize } else { 3 };
+                BARS[level.min(7)]
+            })
+            .collect();
+        let rising = values.last() >= values.first();
+        Span::styled(bars, Style::default().fg(if rising { theme.positive } else { theme.negative }))
+    }
+
+    /// Sparkline del P&L realizado por día (últimos `days` días) con el total del período
+    fn realized_sparkline_line(theme: &Theme, label: &'static str, history: &[RealizedPnl], days: usize) -> Line<'static> {
+        const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let daily = performance::daily_pnl(history, days, chrono::Utc::now().date_naive());
+        let max = daily.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+        let total: f64 = daily.iter().sum();
+
+        let mut spans = vec![Span::styled(label, Style::default().fg(theme.muted))];
+        for v in &daily {
+            let span = if *v == 0.0 || max == 0.0 {
+                Span::styled("·", Style::default().fg(theme.muted))
+            } else {
+                let level = ((v.abs() / max) * (BARS.len() - 1) as f64).round() as usize;
+                let color = if *v > 0.0 { theme.positive } else { theme.negative };
+                Span::styled(BARS[level].to_string(), Style::default().fg(color))
+            };
+            spans.push(span);
+        }
+        let (sign, color) = if total >= 0.0 { ("+", theme.positive) } else { ("", theme.negative) };
+        spans.push(Span::styled(format!("  {}{:.2} $", sign, total), Style::default().fg(color)));
+        Line::from(spans)
+    }
+
     // -----------------------------------------------------------
     // Historial de operaciones
     // -----------------------------------------------------------
 
     fn render_trades(f: &mut Frame, state: &AppState, area: Rect) {
+        let theme = &state.theme;
         let slot = match state.selected() {
             Some(s) => s,
             None => {
                 f.render_widget(
                     Block::default()
-                        .title(" Trade History ")
+                        .title(tr(" Trade History "))
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .border_style(Style::default().fg(Color::Blue)),
+                        .border_style(Style::default().fg(theme.info)),
                     area,
                 );
                 return;
@@ -799,40 +2041,47 @@ impl Tui {
             TradeDirection::Long  => "Buy Price",
             TradeDirection::Short => "Sell Price",
         };
-        let header_arr = ["#", entry_col_header, "Quantity", "USDT", "Current P&L", "Date/Time"];
+        let header_arr = ["#", tr(entry_col_header), tr("Quantity"), slot.quote_asset.as_str(), tr("Current P&L"), tr("Date/Time")];
         let header_cells = header_arr.into_iter().map(|h| {
-            Cell::from(h).style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            Cell::from(h).style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD))
         });
         let header = Row::new(header_cells).height(1).bottom_margin(0);
 
+        // Ventana de filas (bordes y encabezado aparte) que sigue a la seleccionada
+        let total = slot.strategy.trades.len();
+        let visible = (area.height.saturating_sub(3) as usize).max(1);
+        let selected = state.trade_selected.min(total.saturating_sub(1));
+        let skip = (selected + 1).saturating_sub(visible);
+
         let rows: Vec<Row> = slot
             .strategy
             .trades
             .iter()
             .enumerate()
             .rev()
+            .skip(skip)
+            .take(visible)
             .map(|(i, t)| {
                 let trade_pnl = match direction {
                     TradeDirection::Long  => (price - t.buy_price) * t.quantity,
                     TradeDirection::Short => (t.buy_price - price) * t.quantity,
                 };
-                let (pnl_color, sign) =
-                    if trade_pnl >= 0.0 { (Color::Green, "+") } else { (Color::Red, "") };
+                let pnl_color = if trade_pnl >= 0.0 { theme.positive } else { theme.negative };
                 Row::new(vec![
                     Cell::from(format!("{}", i + 1)),
-                    Cell::from(format!("${:.4}", t.buy_price)),
+                    Cell::from(fmt_price(t.buy_price, &slot.quote_asset, 4)),
                     Cell::from(format!("{:.6}", t.quantity)),
-                    Cell::from(format!("${:.2}", t.cost)),
-                    Cell::from(format!("{}{:.2}$", sign, trade_pnl))
+                    Cell::from(fmt_quote(t.cost, &slot.quote_asset)),
+                    Cell::from(fmt_pnl(trade_pnl, &slot.quote_asset))
                         .style(Style::default().fg(pnl_color)),
-                    Cell::from(
-                        t.timestamp
-                            .with_timezone(&chrono::Local)
-                            .format("%m-%d %H:%M:%S")
-                            .to_string(),
-                    ),
+                    Cell::from(fmt_time(t.timestamp, "%m-%d %H:%M:%S")),
                 ])
                 .height(1)
+                .style(if total - 1 - i == selected {
+                    Style::default().bg(theme.muted).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                })
             })
             .collect();
 
@@ -840,8 +2089,8 @@ impl Tui {
             Constraint::Length(4),
             Constraint::Length(14),
             Constraint::Length(12),
-            Constraint::Length(13),
-            Constraint::Length(12),
+            Constraint::Length(15),
+            Constraint::Length(16),
             Constraint::Min(16),
         ];
 
@@ -849,15 +2098,188 @@ impl Tui {
             .header(header)
             .block(
                 Block::default()
-                    .title(format!(
-                        " Trade History ({}) ",
-                        slot.strategy.trades.len()
-                    ))
+                    .title(if total > visible {
+                        trf(" Trade History ({}/{}) ", &[&(total - selected), &total])
+                    } else {
+                        trf(" Trade History ({}) ", &[&total])
+                    })
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(theme.info)),
+            );
+
+        f.render_widget(table, area);
+    }
+
+    // -----------------------------------------------------------
+    // Portfolio (pestaña 2): todos los slots, los balances valuados y la equity total
+    // -----------------------------------------------------------
+
+    fn render_portfolio(f: &mut Frame, state: &AppState, area: Rect) {
+        let theme = &state.theme;
+        let holdings = state.valuation.as_ref().map_or(0, |v| v.holdings.len()) as u16;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(4), Constraint::Length(holdings.clamp(1, 10) + 3), Constraint::Length(3)])
+            .split(area);
+        let today = chrono::Utc::now().date_naive();
+        let pnl_style = |v: f64| Style::default().fg(if v >= 0.0 { theme.positive } else { theme.negative });
+
+        let header = Row::new(
+            ["#", "Symbol", "Side", "Status", "Invested", "Unrealized P&L", "Realized today"].into_iter().map(|h| {
+                Cell::from(tr(h)).style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD))
+            }),
+        );
+
+        let (mut invested_total, mut unrealized_total, mut realized_total) = (0.0, 0.0, 0.0);
+        let rows: Vec<Row> = state
+            .slots
+            .iter()
+            .enumerate()
+            .map(|(i, slot)| {
+                let price = state.prices.get(&slot.symbol).map(|m| m.price).unwrap_or(0.0);
+                let invested = slot.strategy.total_invested();
+                // Sin precio todavía no hay P&L no realizado que mostrar
+                let unrealized = if price > 0.0 { slot.strategy.pnl(price) } else { 0.0 };
+                let realized = performance::daily_pnl(&slot.performance.realized, 1, today)[0];
+                // Totales en USDT: cada slot a la cotización de su quote (sin cotización no suma)
+                if let Some(rate) = state.quote_rate(&slot.quote_asset) {
+                    invested_total += invested * rate;
+                    unrealized_total += unrealized * rate;
+                    realized_total += realized * rate;
+                }
+
+                let (side, side_color) = match slot.strategy.config.direction {
+                    TradeDirection::Long => ("LONG", theme.positive),
+                    TradeDirection::Short => ("SHORT", theme.negative),
+                };
+                let row_style = if i == state.selected_slot {
+                    Style::default().add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                Row::new(vec![
+                    Cell::from(format!("{}", slot.id)),
+                    Cell::from(slot.symbol.clone()),
+                    Cell::from(side).style(Style::default().fg(side_color)),
+                    Cell::from(tr(slot.strategy.state.label())),
+                    Cell::from(fmt_quote(invested, &slot.quote_asset)),
+                    Cell::from(if invested > 0.0 {
+                        format!("{} ({:+.2}%)", fmt_pnl(unrealized, &slot.quote_asset), slot.strategy.pnl_pct(price))
+                    } else {
+                        "--".to_string()
+                    })
+                    .style(pnl_style(unrealized)),
+                    Cell::from(fmt_pnl(realized, &slot.quote_asset)).style(pnl_style(realized)),
+                ])
+                .style(row_style)
+            })
+            .collect();
+
+        let widths = [
+            Constraint::Length(4),
+            Constraint::Length(12),
+            Constraint::Length(6),
+            Constraint::Length(12),
+            Constraint::Length(15),
+            Constraint::Length(26),
+            Constraint::Min(14),
+        ];
+        let table = Table::new(rows, widths).header(header).block(
+            Block::default()
+                .title(trf(" Portfolio ({} slots) ", &[&state.slots.len()]))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.special)),
+        );
+        f.render_widget(table, chunks[0]);
+        Self::render_holdings(f, state, chunks[1]);
+
+        let (cycles_count, cycles_total) = state.realized_cycles_usdt();
+        let equity = match &state.valuation {
+            Some(valued) => format_amount(valued.total, &valued.currency),
+            None => format!("-- {}", state.display_currency),
+        };
+        let totals = Line::from(vec![
+            Span::styled(tr(" Invested: "), Style::default().fg(theme.muted)),
+            Span::styled(format!("${:.2}", invested_total), Style::default().fg(theme.text)),
+            Span::styled(tr("  Unrealized: "), Style::default().fg(theme.muted)),
+            Span::styled(format!("{:+.2}$", unrealized_total), pnl_style(unrealized_total)),
+            Span::styled(tr("  Realized today: "), Style::default().fg(theme.muted)),
+            Span::styled(format!("{:+.2}$", realized_total), pnl_style(realized_total)),
+            Span::styled(tr("  All cycles: "), Style::default().fg(theme.muted)),
+            Span::styled(format!("{:+.2}$ ({})", cycles_total, cycles_count), pnl_style(cycles_total)),
+            Span::styled(tr("  Equity: "), Style::default().fg(theme.highlight)),
+            Span::styled(equity, Style::default().fg(theme.text).add_modifier(Modifier::BOLD)),
+        ]);
+        f.render_widget(
+            Paragraph::new(totals).block(
+                Block::default()
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
-                    .border_style(Style::default().fg(Color::Blue)),
+                    .border_style(Style::default().fg(theme.special)),
+            ),
+            chunks[2],
+        );
+    }
+
+    /// Balances de la cuenta valuados en la moneda de visualización, con el par (o la
+    /// ruta por BTC/USDT) usado para convertir cada uno
+    fn render_holdings(f: &mut Frame, state: &AppState, area: Rect) {
+        let theme = &state.theme;
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.special));
+        let Some(valued) = &state.valuation else {
+            f.render_widget(
+                Paragraph::new(Span::styled(
+                    tr(" Waiting for the first balance valuation..."),
+                    Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC),
+                ))
+                .block(block.title(tr(" Holdings "))),
+                area,
             );
+            return;
+        };
 
+        let header = Row::new(["Asset", "Quantity", "Value", "Share", "Route"].into_iter().map(|h| {
+            Cell::from(tr(h)).style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD))
+        }));
+        let rows: Vec<Row> = valued
+            .holdings
+            .iter()
+            .map(|h| {
+                let (value, share, route) = match h.value {
+                    Some(value) => (
+                        format_amount(value, &valued.currency),
+                        if valued.total > 0.0 { format!("{:.1}%", value / valued.total * 100.0) } else { "--".to_string() },
+                        if h.route.is_empty() { "—".to_string() } else { h.route.join(" → ") },
+                    ),
+                    None => ("--".to_string(), "--".to_string(), tr("no priced pair").to_string()),
+                };
+                let style = if h.value.is_some() { Style::default() } else { Style::default().fg(theme.muted) };
+                Row::new(vec![
+                    Cell::from(h.asset.clone()),
+                    Cell::from(format!("{:.8}", h.quantity)),
+                    Cell::from(value),
+                    Cell::from(share),
+                    Cell::from(route).style(Style::default().fg(theme.muted)),
+                ])
+                .style(style)
+            })
+            .collect();
+        let widths = [
+            Constraint::Length(8),
+            Constraint::Length(18),
+            Constraint::Length(20),
+            Constraint::Length(7),
+            Constraint::Min(16),
+        ];
+        let table = Table::new(rows, widths).header(header).block(block.title(trf(
+            " Holdings in {} ({}) · {} ",
+            &[&valued.currency, &valued.holdings.len(), &fmt_time(valued.at, "%H:%M:%S")],
+        )));
         f.render_widget(table, area);
     }
 
@@ -866,130 +2288,115 @@ impl Tui {
     // -----------------------------------------------------------
 
     fn render_log(f: &mut Frame, state: &AppState, area: Rect) {
+        let theme = &state.theme;
         let log_lines: Vec<Line> = state
             .log
             .iter()
             .rev()
             .take(5)
             .rev()
-            .map(|msg| {
-                let color = if msg.contains("⚠") || msg.contains("error") || msg.contains("Error") {
-                    Color::Red
-                } else if msg.contains("STOP LOSS") {
-                    Color::Red
-                } else if msg.contains("ALERT") {
-                    Color::Yellow
-                } else if msg.contains("TAKE PROFIT") || msg.contains("TRAILING TP") {
-                    Color::Green
-                } else if msg.contains("SHORT #") {
-                    Color::Cyan
-                } else if msg.contains("BUY #") {
-                    Color::Green
-                } else {
-                    Color::Gray
-                };
-                Line::from(Span::styled(format!(" {}", msg), Style::default().fg(color)))
-            })
+            .map(|entry| Line::from(Span::styled(format!(" {}", entry.text()), Style::default().fg(Self::log_color(theme, entry)))))
             .collect();
 
         f.render_widget(
             Paragraph::new(log_lines)
                 .block(
                     Block::default()
-                        .title(" Log ")
+                        .title(tr(" Log "))
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .border_style(Style::default().fg(Color::DarkGray)),
+                        .border_style(Style::default().fg(theme.muted)),
                 )
                 .wrap(Wrap { trim: false }),
             area,
         );
     }
 
+    /// Color de una línea del log: por severidad y, en las informativas, por el tipo de evento
+    fn log_color(theme: &Theme, entry: &LogEntry) -> Color {
+        let msg = &entry.message;
+        match entry.level {
+            LogLevel::Error => theme.negative,
+            LogLevel::Alert => theme.highlight,
+            LogLevel::Info if msg.contains("⚠") || msg.contains("error") || msg.contains("Error")
+                || msg.contains("STOP LOSS") => theme.negative,
+            LogLevel::Info if msg.contains("TAKE PROFIT") || msg.contains("TRAILING TP") => theme.positive,
+            LogLevel::Info if msg.contains("SHORT #") => theme.accent,
+            LogLevel::Info if msg.contains("BUY #") => theme.positive,
+            LogLevel::Info => theme.subtle,
+        }
+    }
+
     // -----------------------------------------------------------
-    // Footer de controles
+    // Vista de log (L): pantalla completa con scroll, filtros y búsqueda
     // -----------------------------------------------------------
 
-    fn render_footer(f: &mut Frame, state: &AppState, area: Rect) {
-        let controls = match &state.ui_mode {
-            UiMode::RestoreSession(_) => vec![
-                Span::raw(" "),
-                Span::styled("[C / Enter]", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                Span::raw(" Continue  "),
-                Span::styled("[N / Esc]", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                Span::raw(" New session"),
-            ],
-            UiMode::NewStrategy => vec![
-                Span::raw(" "),
-                Span::styled("[↑↓]", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-                Span::raw(" Symbol  "),
-                Span::styled("[Tab]", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-                Span::raw(" LONG/SHORT  "),
-                Span::styled("[←→]", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-                Span::raw(" Restart  "),
-                Span::styled("[Enter]", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                Span::raw(" Start  "),
-                Span::styled("[Esc]", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                Span::raw(" Cancel"),
-            ],
-            UiMode::Config => vec![
-                Span::raw(" "),
-                Span::styled("[0-9 .]", Style::default().fg(Color::Cyan)),
-                Span::raw(" Enter amount  "),
-                Span::styled("[Enter]", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                Span::raw(" Confirm  "),
-                Span::styled("[Esc]", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                Span::raw(" Cancel"),
-            ],
-            UiMode::PostSale(_slot_id, _) => vec![
-                Span::raw(" "),
-                Span::styled("[S]", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                Span::raw(" Restart cycle  "),
-                Span::styled("[Esc / any key]", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                Span::raw(" Stay stopped"),
-            ],
-            UiMode::ConfirmClose => vec![
-                Span::raw(" "),
-                Span::styled("[Enter / Y]", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                Span::raw(" Close market position  "),
-                Span::styled("[Esc / N]", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                Span::raw(" Cancel"),
-            ],
-            UiMode::ConfirmDelete => vec![
-                Span::raw(" "),
-                Span::styled("[Enter / Y]", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                Span::raw(" Confirm delete slot  "),
-                Span::styled("[Esc / N]", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                Span::raw(" Cancel"),
-            ],
-            UiMode::Normal => vec![
-                Span::raw(" "),
-                Span::styled("[S]", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                Span::raw(" New  "),
-                Span::styled("[X]", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                Span::raw(if state.selected_slot_is_active() { " Pause  " } else { " Start  " }),
-                Span::styled("[V]", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                Span::raw(" Sell now  "),
-                Span::styled("[F]", Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
-                Span::raw(" Flip  "),
-                Span::styled("[D]", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                Span::raw(" Delete  "),
-                Span::styled("[C]", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-                Span::raw(" Config  "),
-                Span::styled("[↑↓]", Style::default().fg(Color::Cyan)),
-                Span::raw(" Slots  "),
-                Span::styled("[Q]", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                Span::raw(" Exit"),
+    fn render_log_view(f: &mut Frame, state: &AppState, area: Rect) {
+        let theme = &state.theme;
+        let view = &state.log_view;
+        let symbol = state.selected().map(|s| s.symbol.as_str());
+        let entries: Vec<&LogEntry> = state.log.iter().filter(|e| view.matches(e, symbol)).collect();
+
+        // Filtros activos en el título
+        let mut filters = vec![tr(view.level.map_or("all", |l| l.label())).to_string()];
+        if view.selected_slot_only {
+            filters.push(symbol.unwrap_or(tr("no slot")).to_string());
+        }
+        if !view.search.is_empty() || view.searching {
+            filters.push(format!("\"{}\"{}", view.search, if view.searching { "▏" } else { "" }));
+        }
+        let title = trf(
+            " Log · {} · {}/{} lines{} ",
+            &[
+                &filters.join(" · "),
+                &entries.len(),
+                &state.log.len(),
+                &if view.scroll > 0 { format!(" · ↑{}", view.scroll) } else { String::new() },
             ],
+        );
+
+        // Ventana que termina `scroll` líneas antes de la más nueva
+        let height = area.height.saturating_sub(2) as usize;
+        let end = entries.len().saturating_sub(view.scroll);
+        let start = end.saturating_sub(height);
+        let lines: Vec<Line> = entries[start..end]
+            .iter()
+            .map(|entry| Line::from(Span::styled(format!(" {}", entry.text()), Style::default().fg(Self::log_color(theme, entry)))))
+            .collect();
+        let lines = if lines.is_empty() {
+            vec![Line::from(Span::styled(tr(" No log lines match the filters"), Style::default().fg(theme.muted)))]
+        } else {
+            lines
         };
 
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            ),
+            area,
+        );
+    }
+
+    // -----------------------------------------------------------
+    // Footer de controles
+    // -----------------------------------------------------------
+
+    fn render_footer(f: &mut Frame, state: &AppState, area: Rect) {
+        let theme = &state.theme;
+        let controls = keymap::footer_spans(state);
+
         f.render_widget(
             Paragraph::new(Line::from(controls))
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .border_style(Style::default().fg(Color::DarkGray)),
+                        .border_style(Style::default().fg(theme.muted)),
                 )
                 .alignment(Alignment::Left),
             area,
@@ -1002,12 +2409,17 @@ impl Tui {
 
     fn render_restore_session_panel(
         f: &mut Frame,
+        theme: &Theme,
         slots_info: &[(String, TradeDirection, usize, bool)],
+        issues: &[RestoreIssue],
+        can_reconcile: bool,
     ) {
         let size = f.area();
         let slot_count = slots_info.len().max(1);
-        let popup_h = (9 + slot_count as u16).min(size.height.saturating_sub(4));
-        let popup_w = 54u16.min(size.width.saturating_sub(4));
+        let issue_rows = if issues.is_empty() { 0 } else { issues.len() as u16 + 2 };
+        let popup_h = (9 + slot_count as u16 + issue_rows + can_reconcile as u16)
+            .min(size.height.saturating_sub(4));
+        let popup_w = 64u16.min(size.width.saturating_sub(4));
         let popup_x = (size.width.saturating_sub(popup_w)) / 2;
         let popup_y = (size.height.saturating_sub(popup_h)) / 2;
         let area = Rect { x: popup_x, y: popup_y, width: popup_w, height: popup_h };
@@ -1015,12 +2427,12 @@ impl Tui {
         f.render_widget(Clear, area);
         f.render_widget(
             Block::default()
-                .title(" ↩ Previous sessions found ")
+                .title(tr(" ↩ Previous sessions found "))
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
                 .border_style(
                     Style::default()
-                        .fg(Color::Yellow)
+                        .fg(theme.highlight)
                         .add_modifier(Modifier::BOLD),
                 ),
             area,
@@ -1036,83 +2448,227 @@ impl Tui {
         let mut lines = vec![
             Line::from(""),
             Line::from(Span::styled(
-                "  Saved sessions:",
-                Style::default().fg(Color::White),
+                tr("  Saved sessions:"),
+                Style::default().fg(theme.text),
             )),
             Line::from(""),
         ];
 
         for (sym, dir, count, active) in slots_info {
             let (dir_label, dir_color) = match dir {
-                TradeDirection::Long  => ("▲ LONG",  Color::Green),
-                TradeDirection::Short => ("▼ SHORT", Color::Red),
+                TradeDirection::Long  => ("▲ LONG",  theme.positive),
+                TradeDirection::Short => ("▼ SHORT", theme.negative),
             };
-            let trade_label = if *count == 1 { "buy" } else { "buys" };
-            let status = if *active { "  ACTIVE" } else { "" };
+            let trade_label = tr(if *count == 1 { "buy" } else { "buys" });
+            let status = if *active { format!("  {}", tr("ACTIVE")) } else { String::new() };
             lines.push(Line::from(vec![
-                Span::styled("  ● ", Style::default().fg(Color::Cyan)),
+                Span::styled("  ● ", Style::default().fg(theme.accent)),
                 Span::styled(
                     sym.clone(),
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
                 ),
                 Span::raw(" "),
                 Span::styled(dir_label, Style::default().fg(dir_color)),
                 Span::styled(
                     format!("  {} {}", count, trade_label),
-                    Style::default().fg(Color::White),
+                    Style::default().fg(theme.text),
                 ),
-                Span::styled(status, Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                Span::styled(status, Style::default().fg(theme.positive).add_modifier(Modifier::BOLD)),
             ]));
         }
 
+        // Inconsistencias: trades duplicados (ya fusionados) y posiciones mayores que la wallet
+        if !issues.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                tr("  ⚠ Inconsistencies found:"),
+                Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+            )));
+        }
+        for issue in issues {
+            let text = match issue {
+                RestoreIssue::DuplicateTrades { symbol, order_ids } => trf(
+                    "    {}: {} duplicate trade(s) merged",
+                    &[symbol, &order_ids.len()],
+                ),
+                RestoreIssue::ExceedsHoldings { asset, recorded, held, .. } => trf(
+                    "    {}: recorded {}, wallet holds {}",
+                    &[asset, &format!("{:.6}", recorded), &format!("{:.6}", held)],
+                ),
+            };
+            lines.push(Line::from(Span::styled(text, Style::default().fg(theme.highlight))));
+        }
+
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
-            "  Do you want to continue where you left off?",
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            tr("  Do you want to continue where you left off?"),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
         )));
         lines.push(Line::from(""));
         lines.push(Line::from(vec![
             Span::styled(
                 "  [C / Enter] ",
-                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                Style::default().fg(theme.positive).add_modifier(Modifier::BOLD),
             ),
-            Span::styled("Continue previous session", Style::default().fg(Color::White)),
+            Span::styled(tr("Continue previous session"), Style::default().fg(theme.text)),
         ]));
+        if can_reconcile {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    "  [R]         ",
+                    Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(tr("Continue, adjusting positions to the wallet"), Style::default().fg(theme.text)),
+            ]));
+        }
         lines.push(Line::from(vec![
             Span::styled(
                 "  [N / Esc]   ",
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                Style::default().fg(theme.negative).add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                "Discard and start from scratch",
-                Style::default().fg(Color::DarkGray),
+                tr("Discard and start from scratch"),
+                Style::default().fg(theme.muted),
             ),
         ]));
 
         f.render_widget(Paragraph::new(lines), inner);
     }
 
+    // -----------------------------------------------------------
+    // Modal: archivo de estado dañado
+    // -----------------------------------------------------------
+
+    fn render_recovery_panel(f: &mut Frame, theme: &Theme, recovery: &StateRecovery) {
+        let size = f.area();
+        let popup_w = 72u16.min(size.width.saturating_sub(4));
+        let popup_h = 19u16.min(size.height.saturating_sub(4));
+        let popup_x = (size.width.saturating_sub(popup_w)) / 2;
+        let popup_y = (size.height.saturating_sub(popup_h)) / 2;
+        let area = Rect { x: popup_x, y: popup_y, width: popup_w, height: popup_h };
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Block::default()
+                .title(tr(" ⚠ State file damaged "))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(
+                    Style::default()
+                        .fg(theme.error)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            area,
+        );
+
+        let inner = Rect {
+            x: area.x + 2,
+            y: area.y + 1,
+            width: area.width.saturating_sub(4),
+            height: area.height.saturating_sub(2),
+        };
+
+        let file_name = |path: &std::path::Path| {
+            path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+        };
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                tr("  strategy_state.json could not be read:"),
+                Style::default().fg(theme.text),
+            )),
+            Line::from(Span::styled(
+                format!("    {}", recovery.error),
+                Style::default().fg(theme.error),
+            )),
+            Line::from(""),
+        ];
+        lines.push(match &recovery.preserved {
+            Some(path) => Line::from(Span::styled(
+                trf("  Damaged file kept as {}", &[&file_name(path)]),
+                Style::default().fg(theme.muted),
+            )),
+            None => Line::from(Span::styled(
+                tr("  ⚠ The damaged file could not be copied aside"),
+                Style::default().fg(theme.highlight),
+            )),
+        });
+        lines.push(Line::from(Span::styled(
+            tr("  Nothing is restored or saved until you choose:"),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from(""));
+
+        match &recovery.backup {
+            Some(backup) => {
+                lines.push(Line::from(vec![
+                    Span::styled("  [B] ", Style::default().fg(theme.positive).add_modifier(Modifier::BOLD)),
+                    Span::styled(tr("Load the latest backup"), Style::default().fg(theme.text)),
+                ]));
+                lines.push(Line::from(Span::styled(
+                    trf(
+                        "      {} · saved {} · {} slot(s), plus later events",
+                        &[&file_name(&backup.path), &fmt_time(backup.saved_at, "%Y-%m-%d %H:%M"), &backup.slots],
+                    ),
+                    Style::default().fg(theme.muted),
+                )));
+            }
+            None => {
+                lines.push(Line::from(vec![
+                    Span::styled("  [B] ", Style::default().fg(theme.subtle)),
+                    Span::styled(tr("Load the latest backup (no readable backup)"), Style::default().fg(theme.subtle)),
+                ]));
+            }
+        }
+        lines.push(Line::from(vec![
+            Span::styled("  [R] ", Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+            Span::styled(tr("Rebuild from history"), Style::default().fg(theme.text)),
+        ]));
+        lines.push(Line::from(Span::styled(
+            tr("      slots replayed from the event log, checked against Binance fills"),
+            Style::default().fg(theme.muted),
+        )));
+        lines.push(Line::from(vec![
+            Span::styled("  [N] ", Style::default().fg(theme.negative).add_modifier(Modifier::BOLD)),
+            Span::styled(tr("Start clean"), Style::default().fg(theme.text)),
+        ]));
+        lines.push(Line::from(Span::styled(
+            tr("      one new slot from config.toml; open positions are no longer tracked"),
+            Style::default().fg(theme.muted),
+        )));
+
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+    }
+
     // -----------------------------------------------------------
     // Modal: nueva estrategia (S)
     // -----------------------------------------------------------
 
     fn render_new_strategy_panel(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
         let size = f.area();
-        let popup_w = 46u16.min(size.width.saturating_sub(4));
-        let popup_h = 17u16.min(size.height.saturating_sub(4));
+        let popup_w = 52u16.min(size.width.saturating_sub(4));
+        let popup_h = if state.groups.is_empty() { 25u16 } else { 26u16 };
+        let popup_h = popup_h.min(size.height.saturating_sub(4));
         let popup_x = (size.width.saturating_sub(popup_w)) / 2;
         let popup_y = (size.height.saturating_sub(popup_h)) / 2;
         let area = Rect { x: popup_x, y: popup_y, width: popup_w, height: popup_h };
 
+        // Duplicado o preset: el título indica de dónde viene la config
+        let title = match &state.new_strat_template {
+            Some(template) => format!(" ▶ {} ", template.label),
+            None => tr(" ▶ New DCA Strategy ").to_string(),
+        };
+
         f.render_widget(Clear, area);
         f.render_widget(
             Block::default()
-                .title(" ▶ New DCA Strategy ")
+                .title(title)
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
                 .border_style(
                     Style::default()
-                        .fg(Color::Green)
+                        .fg(theme.positive)
                         .add_modifier(Modifier::BOLD),
                 ),
             area,
@@ -1128,60 +2684,60 @@ impl Tui {
         let used_symbols: Vec<String> = state.slots.iter().map(|s| s.symbol.clone()).collect();
 
         let sel_style =
-            Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD);
-        let used_style = Style::default().fg(Color::DarkGray);
-        let normal_style = Style::default().fg(Color::White);
+            Style::default().fg(theme.on_accent).bg(theme.positive).add_modifier(Modifier::BOLD);
+        let used_style = Style::default().fg(theme.muted);
+        let normal_style = Style::default().fg(theme.text);
 
         let dir_long_style = if state.new_strat_direction == TradeDirection::Long {
-            Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD)
+            Style::default().fg(theme.on_accent).bg(theme.positive).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme.muted)
         };
         let dir_short_style = if state.new_strat_direction == TradeDirection::Short {
-            Style::default().fg(Color::Black).bg(Color::Red).add_modifier(Modifier::BOLD)
+            Style::default().fg(theme.on_accent).bg(theme.negative).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme.muted)
         };
         let manual_style = if !state.new_strat_auto_restart {
-            Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+            Style::default().fg(theme.on_accent).bg(theme.accent).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme.muted)
         };
         let auto_style = if state.new_strat_auto_restart {
-            Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+            Style::default().fg(theme.on_accent).bg(theme.accent).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme.muted)
         };
 
         let flip_off_style = if !state.new_strat_auto_flip {
-            Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)
+            Style::default().fg(theme.on_accent).bg(theme.highlight).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme.muted)
         };
         let flip_on_style = if state.new_strat_auto_flip {
-            Style::default().fg(Color::Black).bg(Color::Magenta).add_modifier(Modifier::BOLD)
+            Style::default().fg(theme.on_accent).bg(theme.special).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme.muted)
         };
         let bnb_off_style = if !state.new_strat_has_bnb {
-            Style::default().fg(Color::Black).bg(Color::DarkGray).add_modifier(Modifier::BOLD)
+            Style::default().fg(theme.on_accent).bg(theme.muted).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme.muted)
         };
         let bnb_on_style = if state.new_strat_has_bnb {
-            Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)
+            Style::default().fg(theme.on_accent).bg(theme.highlight).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme.muted)
         };
 
         // Lista de símbolos con scroll (visible = 5 a la vez)
         let visible = 5usize;
         let sel = state.new_strat_symbol_idx.min(state.symbols.len().saturating_sub(1));
-        let offset = if sel + 1 > visible { sel + 1 - visible } else { 0 };
+        let offset = (sel + 1).saturating_sub(visible);
 
         let mut lines: Vec<Line> = vec![Line::from(Span::styled(
-            " Symbol (↑↓):",
-            Style::default().fg(Color::DarkGray),
+            tr(" Symbol (↑↓):"),
+            Style::default().fg(theme.muted),
         ))];
 
         for (idx, sym) in state.symbols.iter().enumerate().skip(offset).take(visible) {
@@ -1189,7 +2745,7 @@ impl Tui {
             let is_used = used_symbols.contains(sym);
             let prefix = if is_sel { " ► " } else { "   " };
             let label = if is_used {
-                format!("{}{} ← in use", prefix, sym)
+                trf("{}{} ← in use", &[&prefix, sym])
             } else {
                 format!("{}{}", prefix, sym)
             };
@@ -1204,40 +2760,134 @@ impl Tui {
         }
 
         lines.push(Line::from(""));
+        let kind_style = |kind: StrategyKind| {
+            if state.new_strat_kind == kind {
+                Style::default().fg(theme.on_accent).bg(theme.info).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.muted)
+            }
+        };
+        lines.push(Line::from(vec![
+            Span::styled(tr(" Type (T):         "), Style::default().fg(theme.muted)),
+            Span::styled(tr(" Trading "), kind_style(StrategyKind::Trading)),
+            Span::raw("  "),
+            Span::styled(tr(" Accumulate "), kind_style(StrategyKind::Accumulate)),
+            Span::raw("  "),
+            Span::styled(tr(" Distribute "), kind_style(StrategyKind::Distribute)),
+        ]));
         lines.push(Line::from(vec![
-            Span::styled(" Direction (Tab):  ", Style::default().fg(Color::DarkGray)),
-            Span::styled(" ▲ LONG ", dir_long_style),
+            Span::styled(tr(" Direction (Tab):  "), Style::default().fg(theme.muted)),
+            Span::styled(tr(" ▲ LONG "), dir_long_style),
             Span::raw("  "),
-            Span::styled(" ▼ SHORT ", dir_short_style),
+            Span::styled(tr(" ▼ SHORT "), dir_short_style),
+        ]));
+        // Preset elegido; sin preset la config es la de [dca] (o la del slot duplicado)
+        let preset_label = match state.new_strat_preset.and_then(|i| state.presets.get(i)) {
+            Some(preset) => format!(" {} ", preset.name),
+            None if state.new_strat_template.is_some() => tr(" None (duplicate) ").to_string(),
+            None if state.presets.is_empty() => tr(" None · M to manage ").to_string(),
+            None => tr(" None ([dca]) ").to_string(),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(tr(" Preset (P):       "), Style::default().fg(theme.muted)),
+            Span::styled(
+                preset_label,
+                Style::default().fg(theme.on_accent).bg(theme.special).add_modifier(Modifier::BOLD),
+            ),
         ]));
         lines.push(Line::from(""));
+
+        // Monto, TP, SL e intervalo (N: siguiente campo); vacío = valor de [dca]
+        let quote = state
+            .symbols
+            .get(state.new_strat_symbol_idx)
+            .map(|sym| crate::app::parse_symbol(sym).1)
+            .unwrap_or_default();
+        for (i, (label, param)) in NEW_STRAT_FIELDS.iter().enumerate() {
+            let buf = state.new_strat_bufs.get(i).map(|b| b.as_str()).unwrap_or("");
+            let active = i == state.new_strat_field;
+            let value_style = if active {
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.text)
+            };
+            let unit = match *param {
+                "amount" if !buf.ends_with('%') => format!(" {}", quote),
+                _ => String::new(),
+            };
+            let label = if i == 0 { format!("{} (N):", tr(label)) } else { format!("{}:", tr(label)) };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!(" {}{:<17}", if active { "▶" } else { " " }, label),
+                    Style::default().fg(theme.muted),
+                ),
+                Span::styled(
+                    format!("{}{}", if buf.is_empty() { tr("default") } else { buf }, if active { "▌" } else { "" }),
+                    value_style,
+                ),
+                Span::styled(unit, Style::default().fg(theme.muted)),
+            ]));
+        }
+        if let Some(err) = &state.new_strat_error {
+            lines.push(Line::from(Span::styled(
+                format!(" ⚠ {}", err),
+                Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+            )));
+        } else {
+            lines.push(Line::from(""));
+        }
         lines.push(Line::from(vec![
-            Span::styled(" Restart (←→):     ", Style::default().fg(Color::DarkGray)),
-            Span::styled(" Manual ", manual_style),
+            Span::styled(tr(" Restart (←→):     "), Style::default().fg(theme.muted)),
+            Span::styled(tr(" Manual "), manual_style),
             Span::raw("  "),
-            Span::styled(" Auto ", auto_style),
+            Span::styled(tr(" Auto "), auto_style),
         ]));
         lines.push(Line::from(vec![
-            Span::styled(" Dir Flip (F):     ", Style::default().fg(Color::DarkGray)),
-            Span::styled(" Off ", flip_off_style),
+            Span::styled(tr(" Dir Flip (F):     "), Style::default().fg(theme.muted)),
+            Span::styled(tr(" Off "), flip_off_style),
             Span::raw("  "),
-            Span::styled(" ▲↔▼ Invert Dir ↺ ", flip_on_style),
+            Span::styled(tr(" ▲↔▼ Invert Dir ↺ "), flip_on_style),
         ]));
         lines.push(Line::from(vec![
-            Span::styled("   ↳ Flips Long↔Short direction after each TP", Style::default().fg(Color::DarkGray)),
+            Span::styled(tr("   ↳ Flips Long↔Short direction after each TP"), Style::default().fg(theme.muted)),
         ]));
         lines.push(Line::from(vec![
-            Span::styled(" Pay Fees w/ BNB(B):", Style::default().fg(Color::DarkGray)),
-            Span::styled(" No ", bnb_off_style),
+            Span::styled(tr(" Pay Fees w/ BNB(B):"), Style::default().fg(theme.muted)),
+            Span::styled(tr(" No "), bnb_off_style),
             Span::raw("      "),
-            Span::styled(" Yes (25% Disc) ", bnb_on_style),
+            Span::styled(tr(" Yes (25% Disc) "), bnb_on_style),
         ]));
+        if !state.groups.is_empty() {
+            let group_label = match &state.new_strat_group {
+                Some(name) => {
+                    let budget = state
+                        .groups
+                        .iter()
+                        .find(|g| &g.name == name)
+                        .map(|g| g.budget)
+                        .unwrap_or(0.0);
+                    if budget > 0.0 {
+                        format!(" {} (${:.0}) ", name, budget)
+                    } else {
+                        format!(" {} ", name)
+                    }
+                }
+                None => tr(" None ").to_string(),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(tr(" Group (G):        "), Style::default().fg(theme.muted)),
+                Span::styled(
+                    group_label,
+                    Style::default().fg(theme.on_accent).bg(theme.info).add_modifier(Modifier::BOLD),
+                ),
+            ]));
+        }
         lines.push(Line::from(""));
         lines.push(Line::from(vec![
-            Span::styled(" [Enter] ", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-            Span::styled("Create and Start Strategy", Style::default().fg(Color::White)),
-            Span::styled("[Esc] ", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-            Span::styled("Cancel", Style::default().fg(Color::DarkGray)),
+            Span::styled(" [Enter] ", Style::default().fg(theme.positive).add_modifier(Modifier::BOLD)),
+            Span::styled(tr("Review Strategy  "), Style::default().fg(theme.text)),
+            Span::styled("[Esc] ", Style::default().fg(theme.negative).add_modifier(Modifier::BOLD)),
+            Span::styled(tr("Cancel"), Style::default().fg(theme.muted)),
         ]));
 
         f.render_widget(Paragraph::new(lines), inner);
@@ -1248,9 +2898,10 @@ impl Tui {
     // -----------------------------------------------------------
 
     fn render_config_panel(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
         let size = f.area();
         let popup_w = 46u16.min(size.width.saturating_sub(4));
-        let popup_h = 13u16.min(size.height.saturating_sub(4));
+        let popup_h = 15u16.min(size.height.saturating_sub(4));
         let popup_x = (size.width.saturating_sub(popup_w)) / 2;
         let popup_y = (size.height.saturating_sub(popup_h)) / 2;
         let area = Rect { x: popup_x, y: popup_y, width: popup_w, height: popup_h };
@@ -1258,12 +2909,12 @@ impl Tui {
         f.render_widget(Clear, area);
         f.render_widget(
             Block::default()
-                .title(" ⚙ Global Config ")
+                .title(tr(" ⚙ Global Config "))
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
                 .border_style(
                     Style::default()
-                        .fg(Color::Cyan)
+                        .fg(theme.accent)
                         .add_modifier(Modifier::BOLD),
                 ),
             area,
@@ -1279,59 +2930,124 @@ impl Tui {
         let current = state
             .selected()
             .map(|s| s.strategy.config.quote_amount)
+            .unwrap_or(QuoteAmount::Fixed(0.0));
+        let current_abs = state
+            .selected()
+            .map(|s| s.entry_amount(state.selected_price()))
             .unwrap_or(0.0);
         let buf = &state.cfg_amount_buf;
         let has_bnb = state.cfg_has_bnb;
+        let quote = state.selected().map(|s| s.quote_asset.as_str()).unwrap_or("USDT");
+
+        // Mínimo de Binance (minNotional) del símbolo seleccionado y validación en vivo
+        let min_notional = state.selected().and_then(|s| s.filters.as_ref().map(|f| (s.symbol.clone(), f.min_notional)));
+        let min_violation = buf.parse::<QuoteAmount>().ok().and_then(|a| state.min_notional_violation(a));
+        let min_line = match (&min_violation, &min_notional) {
+            (Some(err), _) => Line::from(Span::styled(
+                format!(" ⚠ {}", err),
+                Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+            )),
+            (None, Some((symbol, min))) => Line::from(vec![
+                Span::styled(tr(" Min order:   "), Style::default().fg(theme.muted)),
+                Span::styled(format!("{} ({})", fmt_quote(*min, quote), symbol), Style::default().fg(theme.muted)),
+            ]),
+            (None, None) => Line::from(Span::styled(
+                tr(" Min order:   loading..."),
+                Style::default().fg(theme.muted),
+            )),
+        };
+
+        // Piso de TP según las comisiones de la cuenta y la opción BNB elegida
+        let (tp_cfg, tp_floor) = state
+            .selected()
+            .map(|s| {
+                let cfg = &s.strategy.config;
+                (
+                    cfg.take_profit_pct.first_pct(),
+                    fees::side_fee_pct(s.strategy.entry_fee_rate_pct(), has_bnb) + cfg.min_net_profit_pct,
+                )
+            })
+            .unwrap_or_default();
+        let floor_color = if tp_cfg > 0.0 && tp_cfg < tp_floor { theme.highlight } else { theme.muted };
 
         let bnb_on_style = if has_bnb {
-            Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)
+            Style::default().fg(theme.on_accent).bg(theme.highlight).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme.muted)
         };
         let bnb_off_style = if !has_bnb {
-            Style::default().fg(Color::Black).bg(Color::Gray).add_modifier(Modifier::BOLD)
+            Style::default().fg(theme.on_accent).bg(theme.subtle).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme.muted)
         };
 
         let lines = vec![
             Line::from(""),
             Line::from(vec![
-                Span::styled(" USDT Amount: ", Style::default().fg(Color::DarkGray)),
+                Span::styled(trf(" {} Amount: ", &[&quote]), Style::default().fg(theme.muted)),
                 Span::styled(
                     format!("{}▌", if buf.is_empty() { "_" } else { buf }),
-                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    match current {
+                        QuoteAmount::Fixed(v) => trf(" (Current: {})", &[&fmt_quote(v, quote)]),
+                        QuoteAmount::Percent(_) => trf(" (Current: {} ≈ {})", &[&current, &fmt_quote(current_abs, quote)]),
+                    },
+                    Style::default().fg(theme.muted),
                 ),
-                Span::styled(format!(" (Current: ${:.1})", current), Style::default().fg(Color::DarkGray)),
             ]),
-            Line::from(""),
+            min_line,
             Line::from(vec![
-                Span::styled(" Pay Fees w/ BNB (B): ", Style::default().fg(Color::DarkGray)),
-                Span::styled(" No ", bnb_off_style),
+                Span::styled(tr(" Pay Fees w/ BNB (B): "), Style::default().fg(theme.muted)),
+                Span::styled(tr(" No "), bnb_off_style),
                 Span::raw(" "),
-                Span::styled(" Yes (25% Disc) ", bnb_on_style),
+                Span::styled(tr(" Yes (25% Disc) "), bnb_on_style),
             ]),
             Line::from(""),
-            Line::from(Span::styled(
-                " (these settings apply to ALL active slots)",
-                Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
-            )),
+            Line::from(vec![
+                Span::styled(tr(" Min TP (fees): "), Style::default().fg(theme.muted)),
+                Span::styled(format!("{:.2}%", tp_floor), Style::default().fg(theme.accent)),
+                Span::styled(
+                    if tp_cfg > 0.0 && tp_cfg < tp_floor {
+                        trf("  (TP {}% raised)", &[&format!("{:.2}", tp_cfg)])
+                    } else {
+                        format!("  (TP {:.2}%)", tp_cfg)
+                    },
+                    Style::default().fg(floor_color),
+                ),
+            ]),
+            Line::from(""),
+            // Aviso de monto sospechoso (fat-finger) en lugar de la nota general
+            match &state.cfg_amount_warning {
+                Some(w) => Line::from(Span::styled(
+                    format!(" ⚠ {}", w),
+                    Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+                )),
+                None => Line::from(Span::styled(
+                    tr(" (these settings apply to ALL active slots)"),
+                    Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC),
+                )),
+            },
             Line::from(""),
             Line::from(vec![
                 Span::styled(
                     " [Enter] ",
-                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.positive).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    tr(if state.cfg_amount_warning.is_some() { "Confirm Anyway " } else { "Save All    " }),
+                    Style::default().fg(theme.text),
                 ),
-                Span::styled("Save All    ", Style::default().fg(Color::White)),
                 Span::styled(
                     " [Esc] ",
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.negative).add_modifier(Modifier::BOLD),
                 ),
-                Span::styled("Cancel", Style::default().fg(Color::DarkGray)),
+                Span::styled(tr("Cancel"), Style::default().fg(theme.muted)),
             ]),
         ];
 
-        f.render_widget(Paragraph::new(lines), inner);
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
     }
 
     // -----------------------------------------------------------
@@ -1339,6 +3055,7 @@ impl Tui {
     // -----------------------------------------------------------
 
     fn render_confirm_close_panel(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
         let size = f.area();
         let popup_w = 50u16.min(size.width.saturating_sub(4));
         let popup_h = 12u16.min(size.height.saturating_sub(4));
@@ -1349,10 +3066,10 @@ impl Tui {
         f.render_widget(Clear, area);
         f.render_widget(
             Block::default()
-                .title(" ⚡ Market Close Position ")
+                .title(tr(" ⚡ Market Close Position "))
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                .border_style(Style::default().fg(theme.negative).add_modifier(Modifier::BOLD)),
             area,
         );
 
@@ -1368,8 +3085,8 @@ impl Tui {
 
         let (symbol, qty, pnl, pnl_pct, dir_label, quote) = if let Some(sl) = slot {
             let dir = match sl.strategy.config.direction {
-                TradeDirection::Long  => "Market SELL",
-                TradeDirection::Short => "Market BUY (rebuy)",
+                TradeDirection::Long  => tr("Market SELL"),
+                TradeDirection::Short => tr("Market BUY (rebuy)"),
             };
             (
                 sl.symbol.clone(),
@@ -1383,27 +3100,27 @@ impl Tui {
             return;
         };
 
-        let (pnl_color, pnl_sign) = if pnl >= 0.0 { (Color::Green, "+") } else { (Color::Red, "") };
+        let (pnl_color, pnl_sign) = if pnl >= 0.0 { (theme.positive, "+") } else { (theme.negative, "") };
 
         let lines = vec![
             Line::from(""),
             Line::from(vec![
-                Span::styled("  Pair:      ", Style::default().fg(Color::DarkGray)),
+                Span::styled(tr("  Pair:      "), Style::default().fg(theme.muted)),
                 Span::styled(
                     symbol,
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
                 ),
             ]),
             Line::from(vec![
-                Span::styled("  Action:   ", Style::default().fg(Color::DarkGray)),
-                Span::styled(dir_label, Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::styled(tr("  Action:   "), Style::default().fg(theme.muted)),
+                Span::styled(dir_label, Style::default().fg(theme.negative).add_modifier(Modifier::BOLD)),
             ]),
             Line::from(vec![
-                Span::styled("  Quantity: ", Style::default().fg(Color::DarkGray)),
-                Span::styled(format!("{:.6}", qty), Style::default().fg(Color::White)),
+                Span::styled(tr("  Quantity: "), Style::default().fg(theme.muted)),
+                Span::styled(format!("{:.6}", qty), Style::default().fg(theme.text)),
             ]),
             Line::from(vec![
-                Span::styled("  Curr. P&L: ", Style::default().fg(Color::DarkGray)),
+                Span::styled(tr("  Curr. P&L: "), Style::default().fg(theme.muted)),
                 Span::styled(
                     format!("{}{:.2} {} ({}{:.2}%)", pnl_sign, pnl, quote, pnl_sign, pnl_pct),
                     Style::default().fg(pnl_color).add_modifier(Modifier::BOLD),
@@ -1411,28 +3128,173 @@ impl Tui {
             ]),
             Line::from(""),
             Line::from(Span::styled(
-                "  This action does not wait for take profit.",
-                Style::default().fg(Color::DarkGray),
+                tr("  This action does not wait for take profit."),
+                Style::default().fg(theme.muted),
             )),
             Line::from(""),
             Line::from(vec![
                 Span::styled(
                     "  [Enter / Y] ",
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.negative).add_modifier(Modifier::BOLD),
                 ),
-                Span::styled("Execute now    ", Style::default().fg(Color::White)),
+                Span::styled(tr("Execute now    "), Style::default().fg(theme.text)),
                 Span::styled(
                     "[Esc / N] ",
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
                 ),
-                Span::styled("Cancel", Style::default().fg(Color::DarkGray)),
+                Span::styled(tr("Cancel"), Style::default().fg(theme.muted)),
             ]),
         ];
 
         f.render_widget(Paragraph::new(lines), inner);
     }
 
+    fn render_edit_slot_panel(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
+        let size = f.area();
+        let popup_w = 50u16.min(size.width.saturating_sub(4));
+        let popup_h = 17u16.min(size.height.saturating_sub(4));
+        let popup_x = (size.width.saturating_sub(popup_w)) / 2;
+        let popup_y = (size.height.saturating_sub(popup_h)) / 2;
+        let area = Rect { x: popup_x, y: popup_y, width: popup_w, height: popup_h };
+
+        let symbol = state.selected().map(|s| s.symbol.clone()).unwrap_or_default();
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Block::default()
+                .title(trf(" ✎ Edit {} ", &[&symbol]))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            area,
+        );
+
+        let inner = Rect {
+            x: area.x + 2,
+            y: area.y + 1,
+            width: area.width.saturating_sub(4),
+            height: area.height.saturating_sub(2),
+        };
+
+        let mut lines = vec![Line::from("")];
+        for (i, (label, _)) in EDIT_SLOT_FIELDS.iter().enumerate() {
+            let buf = state.edit_slot_bufs.get(i).map(|b| b.as_str()).unwrap_or("");
+            let changed = state.edit_slot_initial.get(i).map(|v| v.as_str()) != Some(buf);
+            let (marker, value_style) = if i == state.edit_slot_field {
+                ("▶ ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+            } else if changed {
+                ("  ", Style::default().fg(theme.highlight))
+            } else {
+                ("  ", Style::default().fg(theme.text))
+            };
+            let cursor = if i == state.edit_slot_field { "▌" } else { "" };
+            lines.push(Line::from(vec![
+                Span::styled(format!("{}{:<16}", marker, tr(label)), Style::default().fg(theme.muted)),
+                Span::styled(format!("{}{}", if buf.is_empty() { "_" } else { buf }, cursor), value_style),
+            ]));
+        }
+
+        // Notificaciones del slot (se cambian con `:notify <evento> on|off`)
+        let overrides = state.selected().map(|s| s.strategy.config.notify.clone()).unwrap_or_default();
+        let mut notify_spans = vec![Span::styled(format!("  {:<16}", tr("Notify")), Style::default().fg(theme.muted))];
+        for event in NotifyEvent::ALL {
+            let style = if event.enabled_for(&overrides) {
+                Style::default().fg(theme.positive)
+            } else {
+                Style::default().fg(theme.muted).add_modifier(Modifier::CROSSED_OUT)
+            };
+            notify_spans.push(Span::styled(event.name(), style));
+            notify_spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(notify_spans));
+        lines.push(Line::from(""));
+
+        let ladder = state
+            .selected()
+            .is_some_and(|s| matches!(s.strategy.config.take_profit_pct, TakeProfit::Ladder(_)));
+        lines.push(match &state.edit_slot_error {
+            Some(err) => Line::from(Span::styled(
+                format!(" ⚠ {}", err),
+                Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+            )),
+            None if ladder => Line::from(Span::styled(
+                tr(" TP ladder: changing TP replaces it"),
+                Style::default().fg(theme.highlight),
+            )),
+            None => Line::from(""),
+        });
+        lines.push(Line::from(Span::styled(
+            tr(" (this slot only, kept across restarts)"),
+            Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC),
+        )));
+
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+    }
+
+    fn render_settings_panel(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
+        let size = f.area();
+        let popup_w = 56u16.min(size.width.saturating_sub(4));
+        let popup_h = 18u16.min(size.height.saturating_sub(4));
+        let popup_x = (size.width.saturating_sub(popup_w)) / 2;
+        let popup_y = (size.height.saturating_sub(popup_h)) / 2;
+        let area = Rect { x: popup_x, y: popup_y, width: popup_w, height: popup_h };
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Block::default()
+                .title(tr(" ⚙ Settings · config.toml "))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            area,
+        );
+
+        let inner = Rect {
+            x: area.x + 2,
+            y: area.y + 1,
+            width: area.width.saturating_sub(4),
+            height: area.height.saturating_sub(2),
+        };
+
+        let mut lines = vec![Line::from("")];
+        for (i, (label, _)) in SETTINGS_FIELDS.iter().enumerate() {
+            let buf = state.settings_bufs.get(i).map(|b| b.as_str()).unwrap_or("");
+            let changed = state.settings_initial.get(i).map(|v| v.as_str()) != Some(buf);
+            let (marker, value_style) = if i == state.settings_field {
+                ("▶ ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+            } else if changed {
+                ("  ", Style::default().fg(theme.highlight))
+            } else {
+                ("  ", Style::default().fg(theme.text))
+            };
+            let cursor = if i == state.settings_field { "▌" } else { "" };
+            // Vacío = la clave no está en config.toml y rige el valor por defecto
+            let value = if buf.is_empty() { tr("(default)") } else { buf };
+            lines.push(Line::from(vec![
+                Span::styled(format!("{}{:<18}", marker, tr(label)), Style::default().fg(theme.muted)),
+                Span::styled(format!("{}{}", value, cursor), value_style),
+            ]));
+        }
+        lines.push(Line::from(""));
+
+        lines.push(match &state.settings_error {
+            Some(err) => Line::from(Span::styled(
+                format!(" ⚠ {}", err),
+                Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+            )),
+            None => Line::from(""),
+        });
+        lines.push(Line::from(Span::styled(
+            tr(" (saved to config.toml, applied by the live reload)"),
+            Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC),
+        )));
+
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+    }
+
     fn render_confirm_delete_panel(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
         let size = f.area();
         let has_position = state.selected().map(|sl| sl.strategy.total_quantity() > 0.0).unwrap_or(false);
         
@@ -1446,10 +3308,10 @@ impl Tui {
         f.render_widget(Clear, area);
         f.render_widget(
             Block::default()
-                .title(" 🗑 Borrar Slot ")
+                .title(tr(" 🗑 Delete Slot "))
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                .border_style(Style::default().fg(theme.negative).add_modifier(Modifier::BOLD)),
             area,
         );
 
@@ -1465,10 +3327,10 @@ impl Tui {
         let mut lines = vec![
             Line::from(""),
             Line::from(vec![
-                Span::styled("  ¿Confirmas borrar el slot de ", Style::default().fg(Color::White)),
+                Span::styled(tr("  Delete the slot of "), Style::default().fg(theme.text)),
                 Span::styled(
                     symbol,
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
                 ),
                 Span::raw("?"),
             ]),
@@ -1477,16 +3339,16 @@ impl Tui {
         if has_position {
             lines.push(Line::from(""));
             lines.push(Line::from(vec![
-                Span::styled("  ⚠ POSICIÓN ABIERTA DETECTADA", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::styled(tr("  ⚠ OPEN POSITION DETECTED"), Style::default().fg(theme.negative).add_modifier(Modifier::BOLD)),
             ]));
             lines.push(Line::from(vec![
-                Span::styled("  Si borras, el bot dejará de gestionarla.", Style::default().fg(Color::Red)),
+                Span::styled(tr("  If you delete it, the bot stops managing it."), Style::default().fg(theme.negative)),
             ]));
         } else {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
-                "  Se perderá el historial local de este ciclo.",
-                Style::default().fg(Color::DarkGray),
+                tr("  The local history of this cycle will be lost."),
+                Style::default().fg(theme.muted),
             )));
         }
 
@@ -1494,14 +3356,14 @@ impl Tui {
         lines.push(Line::from(vec![
             Span::styled(
                 "  [Enter / Y] ",
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                Style::default().fg(theme.negative).add_modifier(Modifier::BOLD),
             ),
-            Span::styled("Borrar ahora   ", Style::default().fg(Color::White)),
+            Span::styled(tr("Delete now     "), Style::default().fg(theme.text)),
             Span::styled(
                 "[Esc / N] ",
-                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
             ),
-            Span::styled("Cancelar", Style::default().fg(Color::DarkGray)),
+            Span::styled(tr("Cancel"), Style::default().fg(theme.muted)),
         ]));
 
         f.render_widget(Paragraph::new(lines), inner);
@@ -1511,7 +3373,7 @@ impl Tui {
     // Overlay post-venta
     // -----------------------------------------------------------
 
-    fn render_post_sale_panel(f: &mut Frame, result: &SaleResult, quote_asset: &str) {
+    fn render_post_sale_panel(f: &mut Frame, theme: &Theme, result: &SaleResult, quote_asset: &str) {
         let size = f.area();
         let popup_w = 50u16.min(size.width.saturating_sub(4));
         let popup_h = 13u16.min(size.height.saturating_sub(4));
@@ -1522,14 +3384,14 @@ impl Tui {
         f.render_widget(Clear, area);
 
         let (border_color, _title_color) = if result.kind == "STOP LOSS" {
-            (Color::Red, Color::Red)
+            (theme.negative, theme.negative)
         } else {
-            (Color::Green, Color::Green)
+            (theme.positive, theme.positive)
         };
 
         f.render_widget(
             Block::default()
-                .title(format!(" {} ", result.kind))
+                .title(format!(" {} ", tr(&result.kind)))
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
                 .border_style(Style::default().fg(border_color).add_modifier(Modifier::BOLD)),
@@ -1544,60 +3406,1203 @@ impl Tui {
         };
 
         let (pnl_color, pnl_sign) = if result.pnl >= 0.0 {
-            (Color::Green, "+")
+            (theme.positive, "+")
         } else {
-            (Color::Red, "")
+            (theme.negative, "")
         };
 
         let lines = vec![
             Line::from(""),
             Line::from(vec![
-                Span::styled("Received:  ", Style::default().fg(Color::DarkGray)),
+                Span::styled(tr("Received:  "), Style::default().fg(theme.muted)),
                 Span::styled(
-                    format!("${:.2} {}", result.received, quote_asset),
-                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                    fmt_quote_named(result.received, quote_asset),
+                    Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
                 ),
             ]),
             Line::from(vec![
-                Span::styled("Profit:    ", Style::default().fg(Color::DarkGray)),
+                Span::styled(tr("Profit:    "), Style::default().fg(theme.muted)),
                 Span::styled(
-                    format!(
-                        "{}{:.2} {} ({}{:.2}%)",
-                        pnl_sign, result.pnl, quote_asset, pnl_sign, result.pnl_pct
-                    ),
+                    format!("{} ({}{:.2}%)", fmt_pnl(result.pnl, quote_asset), pnl_sign, result.pnl_pct),
                     Style::default().fg(pnl_color).add_modifier(Modifier::BOLD),
                 ),
             ]),
             Line::from(""),
             Line::from(Span::styled(
                 "─────────────────────────────────────",
-                Style::default().fg(Color::DarkGray),
+                Style::default().fg(theme.muted),
             )),
             Line::from(""),
             Line::from(Span::styled(
-                "What do you want to do?",
-                Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                tr("What do you want to do?"),
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
             )),
             Line::from(""),
             Line::from(vec![
                 Span::styled(
                     "  [S] ",
-                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.positive).add_modifier(Modifier::BOLD),
                 ),
                 Span::styled(
-                    "Restart DCA cycle immediately",
-                    Style::default().fg(Color::White),
+                    tr("Restart DCA cycle immediately"),
+                    Style::default().fg(theme.text),
                 ),
             ]),
             Line::from(vec![
                 Span::styled(
-                    "  [Esc / any key] ",
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    tr("  [Esc / any key] "),
+                    Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
                 ),
-                Span::raw("Stay stopped"),
+                Span::raw(tr("Stay stopped")),
             ]),
         ];
 
         f.render_widget(Paragraph::new(lines), inner);
     }
+
+    // -----------------------------------------------------------
+    // Resumen previo a crear una estrategia
+    // -----------------------------------------------------------
+
+    fn render_new_strategy_summary(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
+        let cfg = match &state.new_strat_preview {
+            Some(c) => c,
+            None => return,
+        };
+        let size = f.area();
+        let popup_w = 60u16.min(size.width.saturating_sub(4));
+        let popup_h = 23u16.min(size.height.saturating_sub(4));
+        let popup_x = (size.width.saturating_sub(popup_w)) / 2;
+        let popup_y = (size.height.saturating_sub(popup_h)) / 2;
+        let area = Rect { x: popup_x, y: popup_y, width: popup_w, height: popup_h };
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Block::default()
+                .title(tr(" ▶ Review New Strategy "))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.positive).add_modifier(Modifier::BOLD)),
+            area,
+        );
+
+        let inner = Rect {
+            x: area.x + 2,
+            y: area.y + 1,
+            width: area.width.saturating_sub(4),
+            height: area.height.saturating_sub(2),
+        };
+
+        // Estrategia temporal para reutilizar los cálculos (TP efectivo, montos)
+        let mut preview = crate::strategy::dca::DcaStrategy::new(cfg.clone());
+        preview.set_fee_rates(state.fee_rates(&cfg.symbol));
+
+        let (base_asset, quote_asset) = crate::app::parse_symbol(&cfg.symbol);
+        let price = state.prices.get(&cfg.symbol).map(|m| m.price).unwrap_or(0.0);
+        // Balance libre conocido (de otro slot con el mismo asset)
+        let free_quote = state.slots.iter().find(|s| s.quote_asset == quote_asset).map(|s| s.quote_balance);
+        let free_base = state.slots.iter().find(|s| s.base_asset == base_asset).map(|s| s.base_balance);
+
+        let label = Style::default().fg(theme.muted);
+        let value = Style::default().fg(theme.text);
+        let off = |v: f64| if v > 0.0 { format!("{:.2}%", v) } else { tr("off").to_string() };
+
+        let (dir_label, dir_color) = match cfg.direction {
+            TradeDirection::Long  => ("▲ LONG", theme.positive),
+            TradeDirection::Short => ("▼ SHORT", theme.negative),
+        };
+
+        // Con billetera virtual, el monto en % se calcula sobre el capital asignado
+        let (fund_quote, fund_base) = match cfg.capital_usdt {
+            c if c > 0.0 && price > 0.0 => (
+                free_quote.unwrap_or(c).min(c),
+                free_base.unwrap_or(c / price).min(c / price),
+            ),
+            _ => (free_quote.unwrap_or(0.0), free_base.unwrap_or(0.0)),
+        };
+        let first_amount = preview.entry_amount(fund_quote, fund_base, price);
+        let amount_label = match cfg.quote_amount {
+            QuoteAmount::Fixed(v) => fmt_quote_named(v, &quote_asset),
+            QuoteAmount::Percent(_) if first_amount > 0.0 => {
+                trf("{} of balance ≈ {}", &[&cfg.quote_amount, &fmt_quote(first_amount, &quote_asset)])
+            }
+            QuoteAmount::Percent(_) => trf("{} of balance", &[&cfg.quote_amount]),
+        };
+        let effective_tp = preview.effective_take_profit_pct();
+        let tp_first = cfg.take_profit_pct.first_pct();
+        let accumulate = cfg.kind == StrategyKind::Accumulate;
+        let distribute = cfg.kind == StrategyKind::Distribute;
+        let trading = cfg.kind == StrategyKind::Trading;
+        let tp_label = match &cfg.take_profit_pct {
+            _ if accumulate => tr("never sold (accumulation)").to_string(),
+            _ if distribute => tr("never bought back (distribution)").to_string(),
+            TakeProfit::Ladder(_) => cfg.take_profit_pct.to_string(),
+            TakeProfit::Single(_) if effective_tp > tp_first && tp_first > 0.0 => {
+                trf("{}% (raised from {}% for fees)", &[&format!("{:.2}", effective_tp), &format!("{:.2}", tp_first)])
+            }
+            TakeProfit::Single(p) => off(*p),
+        };
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(tr(" Pair:         "), label),
+                Span::styled(format!("{}  ", cfg.symbol), value.add_modifier(Modifier::BOLD)),
+                Span::styled(dir_label, Style::default().fg(dir_color).add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    if trading { String::new() } else { format!("  {}", tr(cfg.kind.label())) },
+                    Style::default().fg(theme.info).add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Amount/order: "), label),
+                Span::styled(amount_label, Style::default().fg(theme.highlight)),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Capital:      "), label),
+                Span::styled(
+                    if cfg.capital_usdt > 0.0 {
+                        trf("{} (virtual wallet)", &[&fmt_quote_named(cfg.capital_usdt, &quote_asset)])
+                    } else {
+                        tr("shared account balance").to_string()
+                    },
+                    value,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Orders:       "), label),
+                Span::styled(
+                    if accumulate {
+                        trf("{} UTC, no limit", &[&cfg.buy_schedule])
+                    } else if distribute && !cfg.sell_levels.is_empty() {
+                        let levels: Vec<String> = cfg.sell_levels.iter().map(|l| format!("${}", l)).collect();
+                        trf("sell at {}", &[&levels.join(" ")])
+                    } else if distribute {
+                        trf("{} UTC until sold out", &[&cfg.buy_schedule])
+                    } else if cfg.safety_orders.is_empty() {
+                        trf(
+                            "{} max  scale x{}  every {} min",
+                            &[&cfg.max_orders, &format!("{:.2}", cfg.volume_scale), &cfg.interval_minutes],
+                        )
+                    } else {
+                        trf("base + {} safety orders (ladder)", &[&cfg.safety_orders.len()])
+                    },
+                    value,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(if distribute { " Inventory:    " } else { " Extra entry:  " }), label),
+                Span::styled(
+                    if distribute && cfg.distribute_qty > 0.0 {
+                        format!("{:.6} {}", cfg.distribute_qty, base_asset)
+                    } else if distribute {
+                        trf("free {} balance at start", &[&base_asset])
+                    } else if accumulate {
+                        tr("off").to_string()
+                    } else if let Some(last) = cfg.safety_orders.last() {
+                        trf("ladder up to {}% against the base order", &[&format!("{:.2}", last.deviation_pct)])
+                    } else if cfg.price_drop_trigger > 0.0 {
+                        trf("on {}% move against the position", &[&format!("{:.2}", cfg.price_drop_trigger)])
+                    } else {
+                        tr("off").to_string()
+                    },
+                    value,
+                ),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(tr(" Take profit:  "), label),
+                Span::styled(tp_label, Style::default().fg(theme.positive)),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Stop loss:    "), label),
+                Span::styled(off(if trading { cfg.stop_loss_pct } else { 0.0 }), Style::default().fg(theme.negative)),
+                Span::styled(tr("   Trailing TP: "), label),
+                Span::styled(off(if trading { cfg.trailing_tp_pct } else { 0.0 }), Style::default().fg(theme.accent)),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Restart:      "), label),
+                Span::styled(
+                    if cfg.auto_restart {
+                        trf("Auto (cooldown {} min)", &[&cfg.restart_cooldown_minutes])
+                    } else {
+                        tr("Manual").to_string()
+                    },
+                    value,
+                ),
+                Span::styled(tr("   Flip: "), label),
+                Span::styled(tr(if cfg.auto_flip { "On" } else { "Off" }), value),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" BNB fees:     "), label),
+                Span::styled(tr(if cfg.has_bnb_balance { "Yes" } else { "No" }), value),
+                Span::styled(tr("   Group: "), label),
+                Span::styled(cfg.group.clone().unwrap_or_else(|| tr("none").to_string()), value),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(tr("── REQUIRED BALANCE ───────────────────"), label)),
+        ];
+
+        // Balance necesario para completar todas las órdenes del ciclo
+        if first_amount > 0.0 {
+            // Acumulación / distribución: sin ciclo, alcanza con cubrir la próxima operación
+            let budget = if trading { preview.cycle_budget(first_amount) } else { first_amount };
+            let (needed_label, free_label, enough) = match cfg.direction {
+                TradeDirection::Long => (
+                    fmt_quote_named(budget, &quote_asset),
+                    free_quote.map(|b| fmt_quote_named(b, &quote_asset)),
+                    free_quote.map(|b| b >= budget),
+                ),
+                TradeDirection::Short if price > 0.0 => (
+                    format!("{:.6} {} (≈ {})", budget / price, base_asset, fmt_quote(budget, &quote_asset)),
+                    free_base.map(|b| format!("{:.6} {}", b, base_asset)),
+                    free_base.map(|b| b >= budget / price),
+                ),
+                TradeDirection::Short => (trf("≈ {} in {}", &[&fmt_quote(budget, &quote_asset), &base_asset]), None, None),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    tr(match cfg.kind {
+                        StrategyKind::Trading | StrategyKind::Watch => " Max exposure: ",
+                        StrategyKind::Accumulate => " Per buy:      ",
+                        StrategyKind::Distribute => " Per sale:     ",
+                    }),
+                    label,
+                ),
+                Span::styled(needed_label, Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    match trading {
+                        true if cfg.safety_orders.is_empty() => trf("  {} orders", &[&cfg.max_orders]),
+                        true => trf("  {} orders", &[&(cfg.safety_orders.len() + 1)]),
+                        false => String::new(),
+                    },
+                    label,
+                ),
+            ]));
+            // Tope de exposición por asset ([risk] max_exposure): las entradas se saltean al llegar
+            let cap = state.max_exposure.get(&base_asset).copied();
+            if let Some(limit) = cap.filter(|_| cfg.direction == TradeDirection::Long && price > 0.0) {
+                let positions: f64 = state
+                    .slots
+                    .iter()
+                    .filter(|s| s.base_asset == base_asset && s.strategy.config.direction == TradeDirection::Long)
+                    .map(|s| s.strategy.total_quantity())
+                    .sum();
+                let held = state.holdings.get(&base_asset).copied().unwrap_or(0.0).max(positions);
+                let check = crate::strategy::exposure::check_entry(&base_asset, limit, held, 0.0, price, budget);
+                lines.push(Line::from(vec![
+                    Span::styled(tr(" Cap:          "), label),
+                    match check {
+                        Ok(()) => Span::styled(trf("within the {} exposure cap ✓", &[&base_asset]), value),
+                        Err(reason) => Span::styled(
+                            trf("⚠ {}: entries stop early", &[&reason]),
+                            Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+                        ),
+                    },
+                ]));
+            }
+            lines.push(Line::from(vec![
+                Span::styled(tr(" Free:         "), label),
+                Span::styled(free_label.unwrap_or_else(|| tr("unknown until first balance refresh").to_string()), value),
+                match enough {
+                    Some(true) => Span::styled("  ✓", Style::default().fg(theme.positive)),
+                    Some(false) => Span::styled(
+                        tr("  ⚠ not enough for all orders"),
+                        Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+                    ),
+                    None => Span::raw(""),
+                },
+            ]));
+        } else {
+            lines.push(Line::from(Span::styled(
+                tr(" Depends on the free balance at each entry"),
+                value,
+            )));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(" [Enter] ", Style::default().fg(theme.positive).add_modifier(Modifier::BOLD)),
+            Span::styled(tr("Create and Start  "), Style::default().fg(theme.text)),
+            Span::styled("[Esc] ", Style::default().fg(theme.negative).add_modifier(Modifier::BOLD)),
+            Span::styled(tr("Back"), Style::default().fg(theme.muted)),
+        ]));
+
+        f.render_widget(Paragraph::new(lines), inner);
+    }
+
+    // -----------------------------------------------------------
+    // Detalle de una entrada de Trade History (Enter)
+    // -----------------------------------------------------------
+
+    fn render_trade_detail_panel(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
+        let Some(slot) = state.selected() else { return };
+        let total = slot.strategy.trades.len();
+        // La posición pudo cerrarse con el panel abierto
+        let Some(index) = total.checked_sub(state.trade_selected.min(total.saturating_sub(1)) + 1) else { return };
+        let trade = &slot.strategy.trades[index];
+
+        // Como mucho 8 fills a la vista, el resto se resume en una línea
+        let fill_rows = 8;
+        let label = Style::default().fg(theme.muted);
+        let value = Style::default().fg(theme.text);
+        let side = match slot.strategy.config.direction {
+            TradeDirection::Long => Span::styled(tr("BUY"), Style::default().fg(theme.positive).add_modifier(Modifier::BOLD)),
+            TradeDirection::Short => Span::styled(tr("SELL"), Style::default().fg(theme.negative).add_modifier(Modifier::BOLD)),
+        };
+        // Slippage en contra: LONG pagó más caro, SHORT vendió más barato
+        let slippage = match trade.slippage_pct() {
+            Some(pct) => {
+                let adverse = match slot.strategy.config.direction {
+                    TradeDirection::Long => pct > 0.0,
+                    TradeDirection::Short => pct < 0.0,
+                };
+                Span::styled(
+                    format!("  ({:+.3}%)", pct),
+                    Style::default().fg(if adverse { theme.error } else { theme.positive }),
+                )
+            }
+            None => Span::raw(""),
+        };
+        let fee = trade
+            .commission
+            .as_ref()
+            .map(|c| format!("{:.8} {}", c.amount, c.asset))
+            .unwrap_or_else(|| tr("not reported").to_string());
+        let trigger = trade
+            .trigger
+            .map(|t| t.describe())
+            .unwrap_or_else(|| tr("unknown (adopted or recorded by an older version)").to_string());
+
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(tr(" Order ID:   "), label),
+                Span::styled(trade.order_id.to_string(), value),
+                Span::raw("   "),
+                side,
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Time:       "), label),
+                Span::styled(
+                    fmt_time(trade.timestamp, "%Y-%m-%d %H:%M:%S"),
+                    value,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Trigger:    "), label),
+                Span::styled(trigger, Style::default().fg(theme.highlight)),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(tr(" Expected:   "), label),
+                Span::styled(
+                    if trade.expected_price > 0.0 { format!("${:.4}", trade.expected_price) } else { "--".to_string() },
+                    value,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Avg fill:   "), label),
+                Span::styled(format!("${:.4}", trade.buy_price), value),
+                slippage,
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Quantity:   "), label),
+                Span::styled(format!("{:.8} {}", trade.quantity, slot.base_asset), value),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Total:      "), label),
+                Span::styled(format!("${:.2}", trade.cost), value),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Fees:       "), label),
+                Span::styled(fee, value),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(trf("── FILLS ({}) ──────────────────", &[&trade.fills.len()]), label)),
+        ];
+        if trade.fills.is_empty() {
+            lines.push(Line::from(Span::styled(
+                tr(" Not reported (LIMIT and adopted entries only know the average)"),
+                label.add_modifier(Modifier::ITALIC),
+            )));
+        }
+        for fill in trade.fills.iter().take(fill_rows) {
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {:.8}", fill.qty), value),
+                Span::styled(" @ ", label),
+                Span::styled(format!("${:.4}", fill.price), value),
+                Span::styled(tr("   fee "), label),
+                Span::styled(format!("{:.8} {}", fill.commission, fill.commission_asset), value),
+            ]));
+        }
+        if trade.fills.len() > fill_rows {
+            lines.push(Line::from(Span::styled(
+                trf(" … {} more", &[&(trade.fills.len() - fill_rows)]),
+                label,
+            )));
+        }
+
+        let size = f.area();
+        let popup_w = 68u16.min(size.width.saturating_sub(4));
+        let popup_h = (lines.len() as u16 + 2).min(size.height.saturating_sub(4));
+        let popup_x = (size.width.saturating_sub(popup_w)) / 2;
+        let popup_y = (size.height.saturating_sub(popup_h)) / 2;
+        let area = Rect { x: popup_x, y: popup_y, width: popup_w, height: popup_h };
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Block::default()
+                .title(trf(" Trade #{} of {} · {} ", &[&(index + 1), &total, &slot.symbol]))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.info).add_modifier(Modifier::BOLD)),
+            area,
+        );
+
+        let inner = Rect {
+            x: area.x + 2,
+            y: area.y + 1,
+            width: area.width.saturating_sub(4),
+            height: area.height.saturating_sub(2),
+        };
+        f.render_widget(Paragraph::new(lines), inner);
+    }
+
+    // -----------------------------------------------------------
+    // Modal: ayuda con las teclas de cada modo (?)
+    // -----------------------------------------------------------
+
+    fn help_area(size: Rect) -> Rect {
+        let popup_w = 72u16.min(size.width.saturating_sub(4));
+        let popup_h = size.height.saturating_sub(6).max(8).min(size.height);
+        let popup_x = (size.width.saturating_sub(popup_w)) / 2;
+        let popup_y = (size.height.saturating_sub(popup_h)) / 2;
+        Rect { x: popup_x, y: popup_y, width: popup_w, height: popup_h }
+    }
+
+    /// Última primera línea posible de la ayuda con la terminal actual: más allá la última
+    /// página ya está entera a la vista
+    pub fn help_max_scroll() -> usize {
+        let (width, height) = crossterm::terminal::size().unwrap_or((0, 0));
+        let visible = Self::help_area(Rect::new(0, 0, width, height)).height.saturating_sub(2);
+        keymap::help_len().saturating_sub(visible as usize)
+    }
+
+    fn render_help_panel(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
+        let area = Self::help_area(f.area());
+
+        // Se genera de la misma tabla que el footer
+        let mut lines = Vec::with_capacity(keymap::help_len());
+        for (title, bindings) in keymap::SECTIONS {
+            lines.push(Line::from(Span::styled(
+                format!(" {}", tr(title)),
+                Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
+            )));
+            for binding in bindings.iter() {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("   {:<16}", tr(binding.keys)), binding.style(theme)),
+                    Span::styled(tr(binding.help_label()), Style::default().fg(theme.text)),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
+
+        let visible = area.height.saturating_sub(2) as usize;
+        let scroll = state.help_scroll.min(lines.len().saturating_sub(visible));
+        let title = trf(" ? Keys ({}-{} of {}) ", &[&(scroll + 1), &(scroll + visible).min(lines.len()), &lines.len()]);
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines.into_iter().skip(scroll).take(visible).collect::<Vec<_>>()).block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            ),
+            area,
+        );
+    }
+
+    // -----------------------------------------------------------
+    // Diagnóstico del feed de precios (I)
+    // -----------------------------------------------------------
+
+    fn diagnostics_area(size: Rect) -> Rect {
+        let popup_w = 64u16.min(size.width.saturating_sub(4));
+        let popup_h = 28u16.min(size.height.saturating_sub(4));
+        let popup_x = (size.width.saturating_sub(popup_w)) / 2;
+        let popup_y = (size.height.saturating_sub(popup_h)) / 2;
+        Rect { x: popup_x, y: popup_y, width: popup_w, height: popup_h }
+    }
+
+    /// Última primera línea posible del panel de diagnóstico con la terminal actual: el
+    /// contenido no entra entero en terminales bajas
+    pub fn diagnostics_max_scroll(state: &AppState) -> usize {
+        let (width, height) = crossterm::terminal::size().unwrap_or((0, 0));
+        let visible = Self::diagnostics_area(Rect::new(0, 0, width, height)).height.saturating_sub(2);
+        Self::diagnostics_lines(state).len().saturating_sub(visible as usize)
+    }
+
+    fn render_diagnostics_panel(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
+        let area = Self::diagnostics_area(f.area());
+        let inner = Rect {
+            x: area.x + 2,
+            y: area.y + 1,
+            width: area.width.saturating_sub(4),
+            height: area.height.saturating_sub(2),
+        };
+
+        let lines = Self::diagnostics_lines(state);
+        let visible = inner.height as usize;
+        let scroll = state.diagnostics_scroll.min(lines.len().saturating_sub(visible));
+        let title = if lines.len() > visible {
+            trf(
+                " ⚙ Price feed diagnostics ({}-{} of {}) ",
+                &[&(scroll + 1), &(scroll + visible).min(lines.len()), &lines.len()],
+            )
+        } else {
+            tr(" ⚙ Price feed diagnostics ").to_string()
+        };
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            area,
+        );
+        // Sin wrap: cada Line es una fila, así el scroll cuenta filas de verdad
+        f.render_widget(Paragraph::new(lines.into_iter().skip(scroll).take(visible).collect::<Vec<_>>()), inner);
+    }
+
+    fn diagnostics_lines(state: &AppState) -> Vec<Line<'_>> {
+        let theme = &state.theme;
+        let label = Style::default().fg(theme.muted);
+        let value = Style::default().fg(theme.text);
+        let stats = &state.stream_stats;
+        let policy = &state.ws_policy;
+        let status = state.feed_status();
+        let status_color = match status {
+            StreamStatus::Connected => theme.positive,
+            StreamStatus::Connecting => theme.muted,
+            StreamStatus::Reconnecting | StreamStatus::Stale | StreamStatus::Down => theme.error,
+        };
+        let mut stale: Vec<&str> = state.stale_symbols.iter().map(|s| s.as_str()).collect();
+        stale.sort_unstable();
+
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(tr(" Status:          "), label),
+                Span::styled(format!("● {}", tr(status.label())), Style::default().fg(status_color).add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    stats.endpoint.map(|e| format!("  {}", e.trim_start_matches("wss://"))).unwrap_or_default(),
+                    label,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Uptime:          "), label),
+                Span::styled(
+                    stats
+                        .connected_since
+                        .map(|t| format_duration(chrono::Utc::now() - t))
+                        .unwrap_or_else(|| "--".to_string()),
+                    value,
+                ),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(tr("── RECONNECTS ──────────────────"), label)),
+            Line::from(vec![
+                Span::styled(tr(" Sessions:        "), label),
+                Span::styled(stats.sessions.to_string(), value),
+                Span::styled(tr("   Drops: "), label),
+                Span::styled(stats.drops.to_string(), value),
+                Span::styled(tr("   Failed: "), label),
+                Span::styled(stats.failed_attempts.to_string(), value),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" 24h rotations:   "), label),
+                Span::styled(stats.expirations.to_string(), value),
+                Span::styled(tr("   Symbol changes: "), label),
+                Span::styled(stats.symbol_changes.to_string(), value),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Dropped trades:  "), label),
+                Span::styled(
+                    stats.trades_dropped.to_string(),
+                    Style::default().fg(if stats.trades_dropped > 0 { theme.error } else { theme.text }),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Failing now:     "), label),
+                Span::styled(
+                    trf("{} in a row", &[&stats.consecutive_failures]),
+                    Style::default().fg(if stats.consecutive_failures > 0 { theme.error } else { theme.text }),
+                ),
+                Span::styled(tr("   Last wait: "), label),
+                Span::styled(
+                    stats.last_delay.map(|d| format!("{:.1}s", d.as_secs_f64())).unwrap_or_else(|| "--".to_string()),
+                    value,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Last error:      "), label),
+                Span::styled(
+                    stats.last_error.clone().unwrap_or_else(|| "--".to_string()),
+                    Style::default().fg(if stats.last_error.is_some() { theme.error } else { theme.text }),
+                ),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(tr("── POLICY [websocket] ──────────"), label)),
+            Line::from(vec![
+                Span::styled(tr(" Backoff:         "), label),
+                Span::styled(
+                    trf(
+                        "{}s → {}s (x2 per failure), ±{}%",
+                        &[
+                            &format!("{:.1}", policy.reconnect_delay_secs),
+                            &format!("{:.1}", policy.max_reconnect_delay_secs),
+                            &format!("{:.0}", policy.jitter_pct),
+                        ],
+                    ),
+                    value,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Max retries:     "), label),
+                Span::styled(
+                    if policy.max_retries == 0 { tr("unlimited").to_string() } else { policy.max_retries.to_string() },
+                    value,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled(tr(" Stale prices:    "), label),
+                Span::styled(
+                    match (state.max_price_age_secs, stale.is_empty()) {
+                        (0, _) => tr("check off").to_string(),
+                        (max, true) => trf("none (max age {}s)", &[&max]),
+                        (max, false) => trf("{} (max age {}s)", &[&stale.join(" "), &max]),
+                    },
+                    Style::default().fg(if stale.is_empty() { theme.text } else { theme.error }),
+                ),
+            ]),
+        ];
+
+        // Contadores de la sesión y de todas las guardadas en stats.json
+        let session = state.stats.session();
+        let totals = state.stats.totals();
+        let row = |name: &str, this: String, all: String| {
+            Line::from(vec![
+                Span::styled(tr(name).to_string(), label),
+                Span::styled(format!("{:<20}", this), value),
+                Span::styled(all, value),
+            ])
+        };
+        let uptime = |secs: u64| format_duration(chrono::Duration::seconds(secs as i64));
+        let orders = |c: &Counters| trf("{} ok · {} failed", &[&c.orders_placed, &c.orders_failed]);
+        lines.extend([
+            Line::from(""),
+            Line::from(Span::styled(tr("── SESSION (stats.json) ────────"), label)),
+            Line::from(Span::styled(
+                format!(
+                    "{:18}{:<20}{}",
+                    "",
+                    tr("This session"),
+                    trf("{} sessions since {}", &[&state.stats.sessions(), &fmt_time(state.stats.since(), "%Y-%m-%d")])
+                ),
+                label,
+            )),
+            row(" Running:         ", uptime(session.uptime_secs), uptime(totals.uptime_secs)),
+            row(" Orders:          ", orders(&session), orders(&totals)),
+            row(" API errors:      ", session.api_errors.to_string(), totals.api_errors.to_string()),
+            row(" Reconnects:      ", session.reconnects.to_string(), totals.reconnects.to_string()),
+            row(" Alerts:          ", session.alerts_fired.to_string(), totals.alerts_fired.to_string()),
+            Line::from(vec![
+                Span::styled(tr(" Previous:        "), label),
+                Span::styled(
+                    state
+                        .stats
+                        .previous
+                        .as_ref()
+                        .map(|p| {
+                            trf(
+                                "ran {} until {}",
+                                &[&uptime(p.counters.uptime_secs), &fmt_time(p.updated_at, "%Y-%m-%d %H:%M")],
+                            )
+                        })
+                        .unwrap_or_else(|| "--".to_string()),
+                    value,
+                ),
+            ]),
+        ]);
+        lines
+    }
+
+    // -----------------------------------------------------------
+    // Presets de estrategia (M): lista a la izquierda, campos a la derecha
+    // -----------------------------------------------------------
+
+    fn render_presets_panel(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
+        let size = f.area();
+        let popup_w = 80u16.min(size.width.saturating_sub(4));
+        let popup_h = 22u16.min(size.height.saturating_sub(4));
+        let popup_x = (size.width.saturating_sub(popup_w)) / 2;
+        let popup_y = (size.height.saturating_sub(popup_h)) / 2;
+        let area = Rect { x: popup_x, y: popup_y, width: popup_w, height: popup_h };
+        let view = &state.preset_view;
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Block::default()
+                .title(trf(" ☰ Strategy presets ({}) · presets.toml ", &[&state.presets.len()]))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.special).add_modifier(Modifier::BOLD)),
+            area,
+        );
+
+        let full = Rect {
+            x: area.x + 2,
+            y: area.y + 1,
+            width: area.width.saturating_sub(4),
+            height: area.height.saturating_sub(2),
+        };
+        // Dos líneas abajo: lo que se está escribiendo (o el error) y la ayuda
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(2)])
+            .split(full);
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(26), Constraint::Min(20)])
+            .split(rows[0]);
+
+        let sel_style = Style::default().fg(theme.on_accent).bg(theme.special).add_modifier(Modifier::BOLD);
+        let visible = cols[0].height.saturating_sub(1) as usize;
+
+        if state.presets.is_empty() {
+            f.render_widget(
+                Paragraph::new(vec![
+                    Line::from(""),
+                    Line::from(Span::styled(
+                        tr("  No presets yet"),
+                        Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC),
+                    )),
+                    Line::from(Span::styled(
+                        tr("  N saves the selected slot's config as a preset (or :preset save <name>)"),
+                        Style::default().fg(theme.muted),
+                    )),
+                ]),
+                rows[0],
+            );
+        } else {
+            // Nombres, con ventana que sigue al seleccionado
+            let skip = (view.selected + 1).saturating_sub(visible);
+            let mut names = vec![Line::from(Span::styled(tr("Name"), Style::default().fg(theme.muted).add_modifier(Modifier::BOLD)))];
+            names.extend(state.presets.presets().iter().enumerate().skip(skip).take(visible).map(|(i, p)| {
+                let selected = i == view.selected;
+                let style = if selected { sel_style } else { Style::default().fg(theme.text) };
+                Line::from(Span::styled(format!("{}{}", if selected { "► " } else { "  " }, p.name), style))
+            }));
+            f.render_widget(Paragraph::new(names), cols[0]);
+
+            // Campos del preset seleccionado; los que faltan toman el valor de [dca]
+            if let Some(preset) = state.presets.get(view.selected) {
+                let skip = (view.field + 1).saturating_sub(visible);
+                let mut fields = vec![Line::from(Span::styled(
+                    trf("{} fields · the rest come from [dca]", &[&preset.fields.len()]),
+                    Style::default().fg(theme.muted).add_modifier(Modifier::BOLD),
+                ))];
+                fields.extend(preset.fields.iter().enumerate().skip(skip).take(visible).map(|(i, (key, value))| {
+                    let selected = i == view.field;
+                    let editing = selected && view.input == Some(PresetInput::Value);
+                    let value = if editing { format!("{}▌", view.buf) } else { value.to_string() };
+                    let key_style = if selected {
+                        Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(theme.muted)
+                    };
+                    Line::from(vec![
+                        Span::styled(format!("{}{:<22}", if selected { "▶ " } else { "  " }, key), key_style),
+                        Span::styled(format!("= {}", value), Style::default().fg(theme.text)),
+                    ])
+                }));
+                f.render_widget(Paragraph::new(fields), cols[1]);
+            }
+        }
+
+        let prompt = match view.input {
+            Some(PresetInput::SaveAs) => {
+                let symbol = state.selected().map(|s| s.symbol.as_str()).unwrap_or("-");
+                Some(trf("Save {} config as: {}▌", &[&symbol, &view.buf]))
+            }
+            Some(PresetInput::Rename) => Some(trf("Rename to: {}▌", &[&view.buf])),
+            Some(PresetInput::Value) => Some(tr("Value as TOML: 1.5, true, \"08:00-22:00\", [[1.0, 0.5]]").to_string()),
+            None => None,
+        };
+        let status = match (&view.error, prompt) {
+            (Some(err), _) => Line::from(Span::styled(
+                format!("⚠ {}", err),
+                Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+            )),
+            (None, Some(prompt)) => Line::from(Span::styled(prompt, Style::default().fg(theme.accent))),
+            (None, None) => Line::from(""),
+        };
+        let hint = tr(if view.input.is_some() {
+            "[Enter] Save  [Esc] Cancel"
+        } else {
+            "[↑↓] Preset  [Tab] Field  [Enter] Edit  [X] Remove field  [N] New  [R] Rename  [D] Delete  [Esc] Close"
+        });
+        f.render_widget(
+            Paragraph::new(vec![status, Line::from(Span::styled(hint, Style::default().fg(theme.muted)))]),
+            rows[1],
+        );
+    }
+
+    // -----------------------------------------------------------
+    // Órdenes condicionales (O): pendientes de su precio de disparo
+    // -----------------------------------------------------------
+
+    fn render_triggers_panel(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
+        let size = f.area();
+        let visible = 12usize;
+        let popup_w = 76u16.min(size.width.saturating_sub(4));
+        let popup_h = (visible as u16 + 5).min(size.height.saturating_sub(4));
+        let popup_x = (size.width.saturating_sub(popup_w)) / 2;
+        let popup_y = (size.height.saturating_sub(popup_h)) / 2;
+        let area = Rect { x: popup_x, y: popup_y, width: popup_w, height: popup_h };
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Block::default()
+                .title(trf(
+                    " ⚡ Conditional orders ({}) · Ladders ({}) ",
+                    &[&state.triggers.orders().len(), &state.ladders.ladders().len()],
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+            area,
+        );
+
+        let full = Rect {
+            x: area.x + 2,
+            y: area.y + 1,
+            width: area.width.saturating_sub(4),
+            height: area.height.saturating_sub(2),
+        };
+
+        // Escaleras abajo, una línea cada una (el resto para las órdenes condicionales)
+        let ladders = state.ladders.ladders();
+        let ladder_rows = if ladders.is_empty() { 0 } else { ladders.len().min(4) as u16 + 2 };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(ladder_rows)])
+            .split(full);
+        let inner = chunks[0];
+        if !ladders.is_empty() {
+            let mut lines = vec![Line::from(Span::styled(
+                tr("── LADDERS (:ladder cancel|adopt <id>) ──"),
+                Style::default().fg(theme.muted),
+            ))];
+            lines.extend(ladders.iter().take(4).map(|l| {
+                let (side, color) = match l.side {
+                    OrderSide::Buy => (tr("BUY"), theme.positive),
+                    OrderSide::Sell => (tr("SELL"), theme.negative),
+                };
+                let (first, last) = l.range();
+                Line::from(vec![
+                    Span::styled(format!("#{:<3}", l.id), Style::default().fg(theme.text)),
+                    Span::styled(format!("{:<5}", side), Style::default().fg(color)),
+                    Span::styled(
+                        trf(
+                            "{} ${} ${}→${}  {}/{} filled  {} @ ${}  {} open",
+                            &[
+                                &l.symbol,
+                                &format!("{:.2}", l.total),
+                                &format!("{:.4}", first),
+                                &format!("{:.4}", last),
+                                &l.filled_rungs(),
+                                &l.rungs.len(),
+                                &format!("{:.6}", l.filled_qty()),
+                                &format!("{:.4}", l.average_price()),
+                                &l.resting_rungs(),
+                            ],
+                        ),
+                        Style::default().fg(theme.text),
+                    ),
+                ])
+            }));
+            f.render_widget(Paragraph::new(lines), chunks[1]);
+        }
+
+        if state.triggers.is_empty() {
+            f.render_widget(
+                Paragraph::new(vec![
+                    Line::from(""),
+                    Line::from(Span::styled(
+                        tr("  No conditional orders"),
+                        Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC),
+                    )),
+                    Line::from(Span::styled(
+                        tr("  Add one from the palette: buy 50 BTCUSDT at 60000 / sell 0.01 BTCUSDT at 70000"),
+                        Style::default().fg(theme.muted),
+                    )),
+                ]),
+                inner,
+            );
+            return;
+        }
+
+        // Ventana de `visible` filas que sigue a la seleccionada
+        let skip = (state.trigger_selected + 1).saturating_sub(visible);
+        let rows: Vec<Row> = state
+            .triggers
+            .orders()
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(visible)
+            .map(|(i, o)| {
+                let selected = i == state.trigger_selected;
+                let style = if selected {
+                    Style::default().fg(theme.on_accent).bg(theme.highlight).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                let (side, side_color) = match o.side {
+                    OrderSide::Buy => (tr("BUY"), theme.positive),
+                    OrderSide::Sell => (tr("SELL"), theme.negative),
+                };
+                let price = state.prices.get(&o.symbol).map(|m| m.price).filter(|p| *p > 0.0);
+                Row::new(vec![
+                    Cell::from(format!("#{}", o.id)),
+                    Cell::from(side).style(if selected { style } else { style.fg(side_color) }),
+                    Cell::from(o.symbol.clone()),
+                    Cell::from(o.amount_label()),
+                    Cell::from(format!("{} ${:.4}", o.condition.sign(), o.trigger)),
+                    Cell::from(price.map(|p| format!("${:.4}", p)).unwrap_or_else(|| "—".to_string())),
+                    Cell::from(fmt_time(o.created_at, "%m-%d %H:%M")),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let header = Row::new(["", "Side", "Symbol", "Amount", "Trigger", "Price", "Created"].map(tr))
+            .style(Style::default().fg(theme.muted).add_modifier(Modifier::BOLD));
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(4),
+                Constraint::Length(4),
+                Constraint::Length(10),
+                Constraint::Length(11),
+                Constraint::Length(15),
+                Constraint::Length(13),
+                Constraint::Length(11),
+            ],
+        )
+        .header(header)
+        .column_spacing(1);
+
+        f.render_widget(table, inner);
+    }
+
+    // -----------------------------------------------------------
+    // Historial de alertas (A)
+    // -----------------------------------------------------------
+
+    fn render_alerts_panel(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
+        let size = f.area();
+        let visible = 12usize;
+        let popup_w = 70u16.min(size.width.saturating_sub(4));
+        let popup_h = (visible as u16 + 5).min(size.height.saturating_sub(4));
+        let popup_x = (size.width.saturating_sub(popup_w)) / 2;
+        let popup_y = (size.height.saturating_sub(popup_h)) / 2;
+        let area = Rect { x: popup_x, y: popup_y, width: popup_w, height: popup_h };
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Block::default()
+                .title(trf(" 🔔 Alerts ({} new / {}) ", &[&state.unacked_alerts(), &state.alerts.len()]))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+            area,
+        );
+
+        let inner = Rect {
+            x: area.x + 2,
+            y: area.y + 1,
+            width: area.width.saturating_sub(4),
+            height: area.height.saturating_sub(2),
+        };
+
+        if state.alerts.is_empty() {
+            f.render_widget(
+                Paragraph::new(vec![
+                    Line::from(""),
+                    Line::from(Span::styled(
+                        tr("  No alerts yet"),
+                        Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC),
+                    )),
+                ]),
+                inner,
+            );
+            return;
+        }
+
+        // Las más recientes primero
+        let rows: Vec<Row> = state
+            .alerts
+            .iter()
+            .rev()
+            .take(visible)
+            .map(|a| {
+                let kind_color = match a.kind {
+                    "SUPPORT" | "VOL SELL" => theme.negative,
+                    _ => theme.positive,
+                };
+                let style = if a.acknowledged {
+                    Style::default().fg(theme.muted)
+                } else {
+                    Style::default().fg(theme.text).add_modifier(Modifier::BOLD)
+                };
+                Row::new(vec![
+                    Cell::from(if a.acknowledged { " " } else { "●" }).style(Style::default().fg(theme.highlight)),
+                    Cell::from(fmt_time(a.at, "%m-%d %H:%M:%S")).style(style),
+                    Cell::from(a.symbol.clone()).style(style),
+                    Cell::from(tr(a.kind)).style(if a.acknowledged { style } else { style.fg(kind_color) }),
+                    // Alertas de volumen: el nivel es el múltiplo sobre el promedio
+                    Cell::from(if a.kind.starts_with("VOL") {
+                        trf("{}x avg", &[&format!("{:.1}", a.level)])
+                    } else {
+                        format!("${:.4}", a.level)
+                    })
+                    .style(style),
+                    Cell::from(format!("${:.4}", a.price)).style(style),
+                ])
+            })
+            .collect();
+
+        let header = Row::new(["", "Time", "Symbol", "Level", "At", "Price"].map(tr))
+            .style(Style::default().fg(theme.muted).add_modifier(Modifier::BOLD));
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(1),
+                Constraint::Length(14),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(13),
+                Constraint::Length(13),
+            ],
+        )
+        .header(header)
+        .column_spacing(1);
+
+        f.render_widget(table, inner);
+    }
+
+    // -----------------------------------------------------------
+    // Paleta de comandos (:)
+    // -----------------------------------------------------------
+
+    fn render_palette(f: &mut Frame, state: &AppState) {
+        let theme = &state.theme;
+        let size = f.area();
+        let visible = 8usize;
+        let popup_w = 72u16.min(size.width.saturating_sub(4));
+        let popup_h = (visible as u16 + 4).min(size.height.saturating_sub(4));
+        let popup_x = (size.width.saturating_sub(popup_w)) / 2;
+        let area = Rect { x: popup_x, y: 3.min(size.height), width: popup_w, height: popup_h };
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Block::default()
+                .title(tr(" : Command "))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            area,
+        );
+
+        let inner = Rect {
+            x: area.x + 2,
+            y: area.y + 1,
+            width: area.width.saturating_sub(4),
+            height: area.height.saturating_sub(2),
+        };
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(": ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    format!("{}▌", state.palette_input),
+                    Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(""),
+        ];
+
+        let matches = palette::matches(&state.palette_input);
+        let offset = (state.palette_selected + 1).saturating_sub(visible);
+        for (idx, item) in matches.iter().enumerate().skip(offset).take(visible) {
+            let is_sel = idx == state.palette_selected;
+            let (usage_style, desc_style) = if is_sel {
+                (
+                    Style::default().fg(theme.on_accent).bg(theme.accent).add_modifier(Modifier::BOLD),
+                    Style::default().fg(theme.text),
+                )
+            } else {
+                (Style::default().fg(theme.accent), Style::default().fg(theme.muted))
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {:<24}", item.usage), usage_style),
+                Span::styled(format!(" {:<5}", item.key.unwrap_or("")), Style::default().fg(theme.highlight)),
+                Span::styled(format!(" {}", tr(item.description)), desc_style),
+            ]));
+        }
+        if matches.is_empty() {
+            lines.push(Line::from(Span::styled(
+                tr(" No matching command"),
+                Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC),
+            )));
+        }
+
+        f.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+/// Monto en cualquier moneda con decimales según su tamaño: "1234.56 EUR", "0.01234567 BTC"
+fn format_amount(value: f64, currency: &str) -> String {
+    let decimals = match value.abs() {
+        v if v >= 100.0 => 2,
+        v if v >= 1.0 => 4,
+        _ => 8,
+    };
+    format!("{:.*} {}", decimals, value, currency)
+}
+
+/// Duración compacta: "2d 4h", "4h 12m", "35m"
+fn format_duration(d: chrono::Duration) -> String {
+    let mins = d.num_minutes().max(0);
+    let (days, hours, mins) = (mins / 1440, (mins % 1440) / 60, mins % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else {
+        format!("{}m", mins)
+    }
 }
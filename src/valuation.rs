@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// Assets a conversion may pass through when a balance has no direct pair with the
+/// display currency, in the order they are tried
+const HUBS: [&str; 2] = ["USDT", "BTC"];
+
+/// One conversion step: the pair traded and whether its price is inverted
+/// (converting the quote asset into the base asset)
+#[derive(Debug, Clone)]
+struct Leg {
+    symbol: String,
+    inverse: bool,
+}
+
+/// Trading pairs by (base, quote), used to route conversions between assets
+#[derive(Debug, Default)]
+pub struct PairRoutes {
+    pairs: HashMap<(String, String), String>,
+}
+
+impl PairRoutes {
+    /// Pairs currently trading in an exchangeInfo response
+    pub fn from_exchange_info(info: &Value) -> Self {
+        let pairs = info["symbols"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|s| s["status"].as_str() == Some("TRADING"))
+            .filter_map(|s| {
+                let base = s["baseAsset"].as_str()?.to_string();
+                let quote = s["quoteAsset"].as_str()?.to_string();
+                Some(((base, quote), s["symbol"].as_str()?.to_string()))
+            })
+            .collect();
+        Self { pairs }
+    }
+
+    fn leg(&self, from: &str, to: &str) -> Option<Leg> {
+        if let Some(symbol) = self.pairs.get(&(from.to_string(), to.to_string())) {
+            return Some(Leg { symbol: symbol.clone(), inverse: false });
+        }
+        let symbol = self.pairs.get(&(to.to_string(), from.to_string()))?;
+        Some(Leg { symbol: symbol.clone(), inverse: true })
+    }
+
+    /// Possible routes from `asset` to `currency`, shortest first: the direct pair, then
+    /// through one hub, then through both
+    fn routes(&self, asset: &str, currency: &str) -> Vec<Vec<Leg>> {
+        let mut paths: Vec<Vec<&str>> = vec![vec![asset, currency]];
+        for hub in HUBS.into_iter().filter(|h| *h != asset && *h != currency) {
+            paths.push(vec![asset, hub, currency]);
+        }
+        for first in HUBS {
+            for second in HUBS.into_iter().filter(|h| *h != first) {
+                if ![first, second].iter().any(|h| *h == asset || *h == currency) {
+                    paths.push(vec![asset, first, second, currency]);
+                }
+            }
+        }
+        paths
+            .into_iter()
+            .filter_map(|path| path.windows(2).map(|w| self.leg(w[0], w[1])).collect::<Option<Vec<_>>>())
+            .collect()
+    }
+
+    /// Price of one `asset` in `currency` and the symbols it went through, using the first
+    /// route whose prices are all known (None when no route is priced)
+    pub fn rate(&self, asset: &str, currency: &str, prices: &HashMap<String, f64>) -> Option<(f64, Vec<String>)> {
+        if asset == currency {
+            return Some((1.0, Vec::new()));
+        }
+        self.routes(asset, currency).into_iter().find_map(|legs| {
+            let rate = legs.iter().try_fold(1.0, |acc, leg| {
+                let price = prices.get(&leg.symbol).copied().filter(|p| *p > 0.0)?;
+                Some(if leg.inverse { acc / price } else { acc * price })
+            })?;
+            Some((rate, legs.into_iter().map(|l| l.symbol).collect()))
+        })
+    }
+}
+
+/// One balance valued in the display currency
+#[derive(Debug, Clone)]
+pub struct Holding {
+    pub asset: String,
+    /// Free + locked
+    pub quantity: f64,
+    /// None when no route to the display currency has a price
+    pub value: Option<f64>,
+    /// Symbols used for the conversion (empty for the display currency itself)
+    pub route: Vec<String>,
+}
+
+/// Every non-zero balance of the account valued in one currency
+#[derive(Debug, Clone)]
+pub struct Valuation {
+    pub currency: String,
+    /// Sum of the priced holdings
+    pub total: f64,
+    /// Biggest value first, unpriced holdings last
+    pub holdings: Vec<Holding>,
+    pub at: DateTime<Utc>,
+}
+
+impl Valuation {
+    /// Values `balances` (asset, quantity) in `currency` with `prices` (last price by symbol)
+    pub fn compute(
+        routes: &PairRoutes,
+        balances: &[(String, f64)],
+        currency: &str,
+        prices: &HashMap<String, f64>,
+    ) -> Self {
+        let mut holdings: Vec<Holding> = balances
+            .iter()
+            .map(|(asset, quantity)| {
+                let rate = routes.rate(asset, currency, prices);
+                Holding {
+                    asset: asset.clone(),
+                    quantity: *quantity,
+                    value: rate.as_ref().map(|(r, _)| quantity * r),
+                    route: rate.map(|(_, route)| route).unwrap_or_default(),
+                }
+            })
+            .collect();
+        holdings.sort_by(|a, b| b.value.unwrap_or(-1.0).total_cmp(&a.value.unwrap_or(-1.0)));
+        Self {
+            currency: currency.to_string(),
+            total: holdings.iter().filter_map(|h| h.value).sum(),
+            holdings,
+            at: Utc::now(),
+        }
+    }
+
+    /// Assets left out of the total for lack of a priced route
+    pub fn unpriced(&self) -> impl Iterator<Item = &str> {
+        self.holdings.iter().filter(|h| h.value.is_none()).map(|h| h.asset.as_str())
+    }
+}